@@ -11,6 +11,7 @@ use serde_json::{json, to_string_pretty};
 use pact_cli::setup_loggers;
 use pact_models::PactSpecification;
 use pact_models::sync_pact::RequestResponsePact;
+use pact_models::v4::schema::v4_pact_schema;
 
 fn setup_app<'a, 'b>(program: &str, version: &'b str) -> App<'a, 'b> {
   App::new(program)
@@ -86,7 +87,13 @@ fn handle_matches(args: &ArgMatches) -> Result<(), i32> {
         }
       }
       PactSpecification::V3 => {}
-      PactSpecification::V4 => {}
+      PactSpecification::V4 => {
+        if let Some(attributes) = v4_pact_schema().as_object() {
+          for (k, v) in attributes {
+            map.insert(k.clone(), v.clone());
+          }
+        }
+      }
       _ => {
         eprintln!("ERROR: Mat a valid Pact specification version: '{}'",
                   args.value_of("spec").unwrap_or_default());
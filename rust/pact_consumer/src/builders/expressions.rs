@@ -0,0 +1,158 @@
+//! Support for matching rule definition expressions (i.e. `matching(type, 'Fred')`) embedded in
+//! the contents supplied to the builders.
+
+use serde_json::{json, Value};
+use tracing::warn;
+
+use pact_models::generators::{GeneratorCategory, Generators};
+use pact_models::matchingrules::{MatchingRuleCategory, RuleLogic};
+use pact_models::matchingrules::expressions::{is_matcher_def, MatchingRuleDefinition, parse_matcher_def, ValueType};
+use pact_models::path_exp::DocPath;
+
+/// Walks the JSON value, replacing any matching rule definition expressions with their example
+/// values. The matching rules and generators from the expressions are added for the path of the
+/// value.
+///
+/// Panics if a value looks like a matching rule definition, but it is not valid.
+pub(crate) fn process_json_expressions(
+  value: &Value,
+  path: DocPath,
+  rules: &mut MatchingRuleCategory,
+  generators: &mut Generators,
+  generator_category: &GeneratorCategory
+) -> Value {
+  match value {
+    Value::String(s) if is_matcher_def(s) => match parse_matcher_def(s) {
+      Ok(definition) => {
+        for rule in &definition.rules {
+          match rule.as_ref().left() {
+            Some(rule) => rules.add_rule(path.clone(), rule.clone(), RuleLogic::And),
+            None => warn!("Matching rule references are not supported in the Rust DSL, ignoring the reference in '{}'", s)
+          }
+        }
+        if let Some(generator) = &definition.generator {
+          generators.add_generator_with_subcategory(generator_category, path.clone(), generator.clone());
+        }
+        example_value(&definition)
+      }
+      Err(err) => panic!("'{}' is not a valid matching rule definition - {}", s, err)
+    },
+    Value::Object(map) => Value::Object(map.iter()
+      .map(|(key, value)| {
+        (key.clone(), process_json_expressions(value, path.join(key), rules, generators, generator_category))
+      })
+      .collect()),
+    Value::Array(items) => Value::Array(items.iter().enumerate()
+      .map(|(index, value)| {
+        let mut item_path = path.clone();
+        item_path.push_index(index);
+        process_json_expressions(value, item_path, rules, generators, generator_category)
+      })
+      .collect()),
+    _ => value.clone()
+  }
+}
+
+pub(crate) fn example_value(definition: &MatchingRuleDefinition) -> Value {
+  let value = definition.value.as_str();
+  match definition.value_type {
+    ValueType::Integer => value.parse::<i64>().map(|v| json!(v)).unwrap_or_else(|_| json!(value)),
+    ValueType::Decimal | ValueType::Number => value.parse::<i64>().map(|v| json!(v))
+      .or_else(|_| value.parse::<f64>().map(|v| json!(v)))
+      .unwrap_or_else(|_| json!(value)),
+    ValueType::Boolean => value.parse::<bool>().map(|v| json!(v)).unwrap_or_else(|_| json!(value)),
+    ValueType::Array | ValueType::Object => serde_json::from_str(value).unwrap_or_else(|_| json!(value)),
+    ValueType::String | ValueType::Unknown => json!(value)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+  use serde_json::json;
+
+  use pact_models::generators::{Generator, GeneratorCategory, Generators};
+  use pact_models::matchingrules::{MatchingRule, MatchingRuleCategory, RuleLogic};
+  use pact_models::path_exp::DocPath;
+
+  use super::process_json_expressions;
+
+  #[test]
+  fn replaces_matching_rule_definitions_with_example_values() {
+    let mut rules = MatchingRuleCategory::empty("body");
+    let mut generators = Generators::default();
+    let json = json!({
+      "name": "matching(type, 'Fred')",
+      "age": "matching(integer, 100)",
+      "active": "matching(boolean, true)",
+      "ids": ["matching(number, 1.5)", "plain"],
+      "other": "not a matcher"
+    });
+
+    let result = process_json_expressions(&json, DocPath::root(), &mut rules, &mut generators,
+      &GeneratorCategory::BODY);
+
+    expect!(result).to(be_equal_to(json!({
+      "name": "Fred",
+      "age": 100,
+      "active": true,
+      "ids": [1.5, "plain"],
+      "other": "not a matcher"
+    })));
+    let mut expected_rules = MatchingRuleCategory::empty("body");
+    expected_rules.add_rule(DocPath::new_unwrap("$.name"), MatchingRule::Type, RuleLogic::And);
+    expected_rules.add_rule(DocPath::new_unwrap("$.age"), MatchingRule::Integer, RuleLogic::And);
+    expected_rules.add_rule(DocPath::new_unwrap("$.active"), MatchingRule::Boolean, RuleLogic::And);
+    expected_rules.add_rule(DocPath::new_unwrap("$.ids[0]"), MatchingRule::Number, RuleLogic::And);
+    expect!(rules).to(be_equal_to(expected_rules));
+    expect!(generators.is_empty()).to(be_true());
+  }
+
+  #[test]
+  fn replaces_matching_rule_definitions_with_array_and_object_example_values() {
+    let mut rules = MatchingRuleCategory::empty("body");
+    let mut generators = Generators::default();
+    let json = json!({
+      "tags": "matching(type, ['a', 'b'])",
+      "owner": "matching(type, {'id': 1, 'roles': ['admin']})"
+    });
+
+    let result = process_json_expressions(&json, DocPath::root(), &mut rules, &mut generators,
+      &GeneratorCategory::BODY);
+
+    expect!(result).to(be_equal_to(json!({
+      "tags": ["a", "b"],
+      "owner": { "id": 1, "roles": ["admin"] }
+    })));
+    let mut expected_rules = MatchingRuleCategory::empty("body");
+    expected_rules.add_rule(DocPath::new_unwrap("$.tags"), MatchingRule::Type, RuleLogic::And);
+    expected_rules.add_rule(DocPath::new_unwrap("$.owner"), MatchingRule::Type, RuleLogic::And);
+    expect!(rules).to(be_equal_to(expected_rules));
+  }
+
+  #[test]
+  fn adds_generators_from_matching_rule_definitions() {
+    let mut rules = MatchingRuleCategory::empty("metadata");
+    let mut generators = Generators::default();
+    let json = json!({ "id": "matching(regex, '\\d+', '100')", "ts": "matching(datetime, 'yyyy-MM-dd', '2000-01-01')" });
+
+    let result = process_json_expressions(&json, DocPath::root(), &mut rules, &mut generators,
+      &GeneratorCategory::METADATA);
+
+    expect!(result).to(be_equal_to(json!({ "id": "100", "ts": "2000-01-01" })));
+    expect!(rules.rules.len()).to(be_equal_to(2));
+    let mut expected_generators = Generators::default();
+    expected_generators.add_generator_with_subcategory(&GeneratorCategory::METADATA, DocPath::new_unwrap("$.ts"),
+      Generator::DateTime(Some("yyyy-MM-dd".to_string()), None));
+    expect!(generators).to(be_equal_to(expected_generators));
+  }
+
+  #[test]
+  #[should_panic]
+  fn panics_with_an_invalid_matching_rule_definition() {
+    let mut rules = MatchingRuleCategory::empty("body");
+    let mut generators = Generators::default();
+    process_json_expressions(&json!({ "a": "matching(type," }), DocPath::root(), &mut rules, &mut generators,
+      &GeneratorCategory::BODY);
+  }
+}
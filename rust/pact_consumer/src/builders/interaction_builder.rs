@@ -34,7 +34,10 @@ pub struct InteractionBuilder {
     pub interaction_type: String,
 
     /// Any configuration provided by plugins that needs to be persisted to the Pact metadata
-    pub plugin_configuration: HashMap<String, Value>
+    pub plugin_configuration: HashMap<String, Value>,
+
+    /// Plugin configuration blocks to persist on the interaction
+    interaction_plugin_config: HashMap<String, HashMap<String, Value>>
 }
 
 impl InteractionBuilder {
@@ -51,7 +54,8 @@ impl InteractionBuilder {
       transport: None,
       request: RequestBuilder::default(),
       response: ResponseBuilder::default(),
-      plugin_configuration: Default::default()
+      plugin_configuration: Default::default(),
+      interaction_plugin_config: Default::default()
     }
   }
 
@@ -119,6 +123,18 @@ impl InteractionBuilder {
     self
   }
 
+  /// Adds a configuration block for a plugin to this interaction. This will be persisted in the
+  /// plugin configuration of the interaction in the Pact file, and allows plugin specific
+  /// configuration to be provided without needing to load the plugin.
+  pub fn with_plugin_config<S: Into<String>>(
+    &mut self,
+    plugin_name: S,
+    config: HashMap<String, Value>
+  ) -> &mut Self {
+    self.interaction_plugin_config.entry(plugin_name.into()).or_default().extend(config);
+    self
+  }
+
   /// The interaction we've built.
   pub fn build(&self) -> RequestResponseInteraction {
     RequestResponseInteraction {
@@ -149,13 +165,14 @@ impl InteractionBuilder {
       pending: self.pending.unwrap_or(false),
       plugin_config: self.plugin_config(),
       interaction_markup: markup,
+      config: Default::default(),
       transport: self.transport.clone()
     }
   }
 
   /// Any plugin configuration returned from plugins to add to the interaction
   pub fn plugin_config(&self) -> HashMap<String, HashMap<String, Value>> {
-    #[allow(unused_mut)] let mut config = hashmap!{};
+    let mut config = hashmap!{};
 
     #[cfg(feature = "plugins")]
     {
@@ -173,6 +190,10 @@ impl InteractionBuilder {
       }
     }
 
+    for (key, value) in &self.interaction_plugin_config {
+      config.entry(key.clone()).or_insert_with(HashMap::new).extend(value.clone());
+    }
+
     config
   }
 
@@ -195,3 +216,22 @@ impl InteractionBuilder {
     config
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+  use maplit::hashmap;
+  use serde_json::json;
+
+  use super::InteractionBuilder;
+
+  #[test]
+  fn supports_adding_plugin_configuration() {
+    let interaction = InteractionBuilder::new("test", "")
+      .with_plugin_config("protobuf", hashmap! { "descriptorKey".to_string() => json!("abc123") })
+      .build_v4();
+    expect!(interaction.plugin_config).to(be_equal_to(hashmap! {
+      "protobuf".to_string() => hashmap! { "descriptorKey".to_string() => json!("abc123") }
+    }));
+  }
+}
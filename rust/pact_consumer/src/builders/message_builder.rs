@@ -8,7 +8,7 @@ use maplit::hashmap;
 use pact_models::content_types::ContentType;
 use pact_models::json_utils::json_to_string;
 use pact_models::matchingrules::MatchingRuleCategory;
-use pact_models::generators::Generators;
+use pact_models::generators::{GeneratorCategory, Generators};
 use pact_models::message::Message;
 use pact_models::path_exp::DocPath;
 #[cfg(feature = "plugins")] use pact_models::plugins::PluginData;
@@ -22,6 +22,7 @@ use pact_models::v4::message_parts::MessageContents;
 use serde_json::{json, Map, Value};
 use tracing::debug;
 
+use crate::builders::expressions::process_json_expressions;
 use crate::patterns::JsonPattern;
 use crate::prelude::Pattern;
 #[cfg(feature = "plugins")] use crate::prelude::PluginInteractionBuilder;
@@ -113,7 +114,7 @@ pub struct MessageInteractionBuilder {
   /// Contents of the message. This will include the payload as well as any metadata
   pub message_contents: InteractionContents,
   #[allow(dead_code)] contents_plugin: Option<PactPluginManifest>,
-  #[allow(dead_code)] plugin_config: HashMap<String, PluginConfiguration>
+  plugin_config: HashMap<String, PluginConfiguration>
 }
 
 impl MessageInteractionBuilder {
@@ -177,11 +178,11 @@ impl MessageInteractionBuilder {
   }
 
   /// Adds a key/value pair to the message metadata. The key can be anything that is convertible
-  /// into a string, and the value must be conveyable into a JSON value.
+  /// into a string, and the value must be conveyable into a JSON value. Any matching rule
+  /// definition expressions in the value (i.e. `matching(regex, '\d+', '100')`) will be
+  /// replaced with the example value, and the matching rules and generators added for the key.
   pub fn metadata<S: Into<String>, J: Into<Value>>(&mut self, key: S, value: J) -> &mut Self {
-    let metadata = self.message_contents.metadata
-      .get_or_insert_with(|| hashmap!{});
-    metadata.insert(key.into(), value.into());
+    add_metadata(&mut self.message_contents, key.into(), value.into());
     self
   }
 
@@ -199,13 +200,25 @@ impl MessageInteractionBuilder {
     self
   }
 
+  /// Adds a configuration block for a plugin to this interaction. This will be persisted in the
+  /// plugin configuration of the interaction in the Pact file, and allows plugin specific
+  /// configuration to be provided without needing to load the plugin.
+  pub fn with_plugin_config<S: Into<String>>(
+    &mut self,
+    plugin_name: S,
+    config: HashMap<String, Value>
+  ) -> &mut Self {
+    self.plugin_config.entry(plugin_name.into()).or_default()
+      .interaction_configuration.extend(config);
+    self
+  }
+
   /// The interaction we've built (in V4 format).
   pub fn build(&self) -> AsynchronousMessage {
     debug!("Building V4 AsynchronousMessage interaction: {:?}", self);
 
-    let mut rules = MatchingRules::default();
-    rules.add_category("body")
-      .add_rules(self.message_contents.rules.as_ref().cloned().unwrap_or_default());
+    let mut rules = message_matching_rules(&self.message_contents);
+    rules.add_category("body");
 
     #[allow(unused_mut, unused_assignments)] let mut plugin_config = hashmap!{};
     #[cfg(feature = "plugins")]
@@ -217,6 +230,13 @@ impl MessageInteractionBuilder {
       }).unwrap_or_default();
     }
 
+    for (plugin_name, config) in &self.plugin_config {
+      if !config.interaction_configuration.is_empty() {
+        plugin_config.entry(plugin_name.clone()).or_insert_with(HashMap::new)
+          .extend(config.interaction_configuration.clone());
+      }
+    }
+
     #[allow(unused_mut, unused_assignments)] let mut interaction_markup = InteractionMarkup::default();
     #[cfg(feature = "plugins")]
     {
@@ -244,6 +264,7 @@ impl MessageInteractionBuilder {
       pending: self.pending.unwrap_or(false),
       plugin_config,
       interaction_markup,
+      config: Default::default(),
       transport: None
     }
   }
@@ -252,9 +273,8 @@ impl MessageInteractionBuilder {
   pub fn build_v3(&self) -> Message {
     debug!("Building V3 Message interaction: {:?}", self);
 
-    let mut rules = MatchingRules::default();
-    rules.add_category("body")
-      .add_rules(self.message_contents.rules.as_ref().cloned().unwrap_or_default());
+    let mut rules = message_matching_rules(&self.message_contents);
+    rules.add_category("body");
 
         Message {
       id: None,
@@ -320,17 +340,10 @@ impl MessageInteractionBuilder {
 
       #[cfg(not(feature = "plugins"))]
       {
-        self.message_contents = InteractionContents {
-          body: if let Some(contents) = contents_hashmap.get("contents") {
-            OptionalBody::Present(
-              Bytes::from(contents.to_string()),
-              Some(ct.clone()),
-              None
-            )
-          } else {
-            OptionalBody::Missing
-          },
-          .. InteractionContents::default()
+        self.message_contents = if let Some(contents) = contents_hashmap.get("contents") {
+          core_interaction_contents("", contents, Some(&ct))
+        } else {
+          InteractionContents::default()
         };
       }
     } else {
@@ -356,17 +369,10 @@ impl MessageInteractionBuilder {
     config: &HashMap<String, Value>,
     content_matcher: Option<ContentMatcher>
   ) {
-    self.message_contents = InteractionContents {
-      body: if let Some(contents) = config.get("contents") {
-        OptionalBody::Present(
-          Bytes::from(contents.to_string()),
-          Some(content_type.clone()),
-          None
-        )
-      } else {
-        OptionalBody::Missing
-      },
-      .. InteractionContents::default()
+    self.message_contents = if let Some(contents) = config.get("contents") {
+      core_interaction_contents("", contents, Some(content_type))
+    } else {
+      InteractionContents::default()
     };
 
     if let Some(_content_matcher) = content_matcher {
@@ -448,12 +454,77 @@ impl MessageInteractionBuilder {
   }
 }
 
+/// Sets up the contents of a message from the JSON configuration for the core content matchers.
+/// Matching rule definition expressions in JSON contents are replaced with the example values.
+pub(crate) fn core_interaction_contents(
+  part_name: &str,
+  value: &Value,
+  content_type: Option<&ContentType>
+) -> InteractionContents {
+  let mut rules = MatchingRuleCategory::empty("body");
+  let mut generators = Generators::default();
+  let value = if content_type.map(|ct| ct.is_json()).unwrap_or(true) {
+    process_json_expressions(value, DocPath::root(), &mut rules, &mut generators, &GeneratorCategory::BODY)
+  } else {
+    value.clone()
+  };
+
+  let mut body = OptionalBody::from(&value);
+  if let Some(ct) = content_type {
+    body.set_content_type(ct);
+  }
+
+  InteractionContents {
+    part_name: part_name.to_string(),
+    body,
+    rules: if rules.is_not_empty() { Some(rules) } else { None },
+    generators: if generators.is_empty() { None } else { Some(generators) },
+    metadata: content_type.map(|ct| hashmap!{ "contentType".to_string() => Value::String(ct.to_string()) }),
+    .. InteractionContents::default()
+  }
+}
+
+/// Collects the body and metadata matching rules for a message.
+pub(crate) fn message_matching_rules(contents: &InteractionContents) -> MatchingRules {
+  let mut rules = MatchingRules::default();
+  if let Some(body_rules) = contents.rules.as_ref().filter(|rules| rules.is_not_empty()) {
+    rules.add_category("body").add_rules(body_rules.clone());
+  }
+  if let Some(metadata_rules) = contents.metadata_rules.as_ref().filter(|rules| rules.is_not_empty()) {
+    rules.add_category("metadata").add_rules(metadata_rules.clone());
+  }
+  rules
+}
+
+/// Adds a metadata value to the message contents. Any matching rule definition expressions in the
+/// value are replaced with the example values, and the rules and generators added to the contents.
+pub(crate) fn add_metadata(contents: &mut InteractionContents, key: String, value: Value) {
+  let mut rules = MatchingRuleCategory::empty("metadata");
+  let mut generators = Generators::default();
+  let path = DocPath::new(key.as_str()).unwrap_or_else(|_| DocPath::root().join(key.as_str()));
+  let value = process_json_expressions(&value, path, &mut rules, &mut generators, &GeneratorCategory::METADATA);
+
+  contents.metadata.get_or_insert_with(|| hashmap!{}).insert(key, value);
+  if rules.is_not_empty() {
+    contents.metadata_rules.get_or_insert_with(|| MatchingRuleCategory::empty("metadata"))
+      .add_rules(rules);
+  }
+  if !generators.is_empty() {
+    contents.generators.get_or_insert_with(Generators::default).add_generators(generators);
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use expectest::prelude::*;
   use maplit::hashmap;
   use serde_json::json;
 
+  use pact_models::generators::{Generator, GeneratorCategory, Generators};
+  use pact_models::matchingrules;
+  use pact_models::matchingrules::MatchingRule;
+  use pact_models::path_exp::DocPath;
+
   use crate::builders::MessageInteractionBuilder;
 
   #[test]
@@ -469,4 +540,59 @@ mod tests {
       "c".to_string() => json!([1, 2, 3])
     }));
   }
+
+  #[test]
+  fn metadata_supports_matching_rule_expressions() {
+    let message = MessageInteractionBuilder::new("test")
+      .metadata("id", "matching(regex, '\\d+', '100')")
+      .metadata("ts", "matching(datetime, 'yyyy-MM-dd', '2000-01-01')")
+      .build();
+    expect!(message.contents.metadata).to(be_equal_to(hashmap! {
+      "id".to_string() => json!("100"),
+      "ts".to_string() => json!("2000-01-01")
+    }));
+    expect!(message.contents.matching_rules.rules_for_category("metadata")).to(be_some().value(
+      matchingrules! {
+        "metadata" => {
+          "id" => [ MatchingRule::Regex("\\d+".to_string()) ],
+          "ts" => [ MatchingRule::Timestamp("yyyy-MM-dd".to_string()) ]
+        }
+      }.rules_for_category("metadata").unwrap()
+    ));
+    let mut generators = Generators::default();
+    generators.add_generator_with_subcategory(&GeneratorCategory::METADATA, DocPath::new_unwrap("ts"),
+      Generator::DateTime(Some("yyyy-MM-dd".to_string()), None));
+    expect!(message.contents.generators).to(be_equal_to(generators));
+  }
+
+  #[tokio::test]
+  async fn contents_from_supports_matching_rule_expressions() {
+    let message = MessageInteractionBuilder::new("test")
+      .contents_from(json!({
+        "pact:content-type": "application/json",
+        "contents": { "id": "matching(integer, 100)", "name": "Fred" }
+      }))
+      .await
+      .build();
+
+    expect!(message.contents.contents.value_as_string()).to(be_some().value("{\"id\":100,\"name\":\"Fred\"}"));
+    expect!(message.contents.metadata.get("contentType").cloned()).to(be_some().value(json!("application/json")));
+    expect!(message.contents.matching_rules).to(be_equal_to(matchingrules! {
+      "body" => { "$.id" => [ MatchingRule::Integer ] }
+    }));
+  }
+
+  #[test]
+  fn supports_adding_plugin_configuration() {
+    let message = MessageInteractionBuilder::new("test")
+      .with_plugin_config("protobuf", hashmap! { "descriptorKey".to_string() => json!("abc123") })
+      .with_plugin_config("protobuf", hashmap! { "message".to_string() => json!(".Request") })
+      .build();
+    expect!(message.plugin_config).to(be_equal_to(hashmap! {
+      "protobuf".to_string() => hashmap! {
+        "descriptorKey".to_string() => json!("abc123"),
+        "message".to_string() => json!(".Request")
+      }
+    }));
+  }
 }
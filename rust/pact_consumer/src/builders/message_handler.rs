@@ -0,0 +1,164 @@
+//! Support for verifying a message consumer against the asynchronous messages in a Pact. The
+//! message handler is invoked with each message (with any generators applied), and the Pact file
+//! is only written if the handler was able to process all the messages.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use bytes::Bytes;
+use pact_models::bodies::OptionalBody;
+use pact_models::content_types::ContentType;
+use pact_models::pact::{Pact, WritePactOptions};
+use pact_models::provider_states::ProviderState;
+use pact_models::v4::async_message::AsynchronousMessage;
+use pact_models::v4::pact::V4Pact;
+use pact_models::v4::V4InteractionType;
+use serde_json::Value;
+use tracing::{debug, error};
+
+use crate::builders::message_iter::{generate_async_message, generate_messages, write_pact_file};
+
+/// Message with all the generators applied, as it would be received by the message consumer
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReifiedMessage {
+  /// Description of the message interaction
+  pub description: String,
+  /// Provider states for the message interaction
+  pub provider_states: Vec<ProviderState>,
+  /// Contents of the message
+  pub contents: OptionalBody,
+  /// Metadata associated with the message
+  pub metadata: HashMap<String, Value>,
+  /// Content type of the message
+  pub content_type: Option<ContentType>
+}
+
+impl ReifiedMessage {
+  /// Returns the contents of the message as bytes. Returns an empty value if the message has no
+  /// contents.
+  pub fn body(&self) -> Bytes {
+    self.contents.value().unwrap_or_default()
+  }
+
+  /// Parses the contents of the message as JSON
+  pub fn json(&self) -> anyhow::Result<Value> {
+    serde_json::from_slice(&self.body())
+      .map_err(|err| anyhow!("Message contents are not valid JSON - {}", err))
+  }
+}
+
+impl From<&AsynchronousMessage> for ReifiedMessage {
+  fn from(message: &AsynchronousMessage) -> Self {
+    ReifiedMessage {
+      description: message.description.clone(),
+      provider_states: message.provider_states.clone(),
+      contents: message.contents.contents.clone(),
+      metadata: message.contents.metadata.clone(),
+      content_type: message.message_content_type()
+    }
+  }
+}
+
+/// Message that the message handler failed to process
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageHandlerFailure {
+  /// Description of the message interaction
+  pub description: String,
+  /// Error returned from the message handler
+  pub error: String
+}
+
+/// Error returned when verifying the messages with a message handler
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageVerificationError {
+  /// The message handler failed to process one or more of the messages. The Pact file will not
+  /// have been written.
+  HandlerFailed(Vec<MessageHandlerFailure>),
+  /// All the messages were processed, but the Pact file could not be written
+  PactWriteFailed(String)
+}
+
+impl Display for MessageVerificationError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      MessageVerificationError::HandlerFailed(failures) => {
+        write!(f, "The message handler failed to process {} message(s):", failures.len())?;
+        for failure in failures {
+          write!(f, "\n  '{}': {}", failure.description, failure.error)?;
+        }
+        Ok(())
+      }
+      MessageVerificationError::PactWriteFailed(err) => write!(f, "{}", err)
+    }
+  }
+}
+
+impl std::error::Error for MessageVerificationError {}
+
+fn async_messages(pact: &V4Pact) -> Vec<AsynchronousMessage> {
+  pact.filter_interactions(V4InteractionType::Asynchronous_Messages)
+    .iter()
+    .filter_map(|interaction| interaction.as_v4_async_message())
+    .collect()
+}
+
+/// Returns the asynchronous messages in the Pact with all the generators applied, using the
+/// current Tokio runtime (or a new one if there is no current runtime).
+pub(crate) fn reify_messages(
+  pact: &V4Pact,
+  provider_state_values: &HashMap<String, Value>,
+  generator_seed: Option<u64>
+) -> Vec<ReifiedMessage> {
+  let messages = async_messages(pact);
+  let values = provider_state_values.clone();
+  let generated = generate_messages(messages.clone(), move |message| {
+    let values = values.clone();
+    async move { generate_async_message(message, &values, generator_seed).await }
+  }).unwrap_or_else(|| {
+    error!("Was not able to apply generators to the messages");
+    messages
+  });
+  generated.iter().map(ReifiedMessage::from).collect()
+}
+
+/// Returns the asynchronous messages in the Pact with all the generators applied
+pub(crate) async fn reify_messages_async(
+  pact: &V4Pact,
+  provider_state_values: &HashMap<String, Value>,
+  generator_seed: Option<u64>
+) -> Vec<ReifiedMessage> {
+  let mut messages = vec![];
+  for message in async_messages(pact) {
+    messages.push(ReifiedMessage::from(&generate_async_message(message, provider_state_values, generator_seed).await));
+  }
+  messages
+}
+
+/// Records the result of invoking the message handler with each of the messages, writing the Pact
+/// file if all the messages were processed.
+pub(crate) fn record_results(
+  pact: &(dyn Pact + Send + Sync),
+  results: Vec<(ReifiedMessage, anyhow::Result<()>)>,
+  output_dir: &Option<PathBuf>,
+  write_options: &WritePactOptions
+) -> Result<(), MessageVerificationError> {
+  let failures = results.iter()
+    .filter_map(|(message, result)| result.as_ref().err().map(|err| {
+      error!("Message handler failed to process message '{}' - {}", message.description, err);
+      MessageHandlerFailure {
+        description: message.description.clone(),
+        error: err.to_string()
+      }
+    }))
+    .collect::<Vec<_>>();
+
+  if failures.is_empty() {
+    debug!("Message handler processed all {} message(s)", results.len());
+    write_pact_file(pact, output_dir, write_options)
+      .map_err(|err| MessageVerificationError::PactWriteFailed(err.to_string()))
+  } else {
+    Err(MessageVerificationError::HandlerFailed(failures))
+  }
+}
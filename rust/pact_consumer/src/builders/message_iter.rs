@@ -1,23 +1,31 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::env;
+use std::future::Future;
 use std::panic::RefUnwindSafe;
 use std::path::PathBuf;
 use std::sync::mpsc::channel;
+use anyhow::anyhow;
 use itertools::Itertools;
 
 use maplit::hashmap;
-use pact_models::generators::GeneratorTestMode;
+use pact_models::generators::{Generator, Generators, GeneratorTestMode, RANDOM_SEED};
 use pact_models::message::Message;
-use pact_models::pact::write_pact;
+use pact_models::pact::{write_pact_with_options, WritePactOptions};
 use pact_models::prelude::{MessagePact, Pact};
 use pact_models::prelude::v4::V4Pact;
 use pact_models::v4::async_message::AsynchronousMessage;
+use pact_models::v4::message_parts::MessageContents;
 use pact_models::v4::sync_message::SynchronousMessage;
 use pact_models::v4::V4InteractionType;
+use serde_json::Value;
 use tokio::runtime::Handle;
 use tracing::{debug, error, info, warn};
 
-use pact_matching::generators::{apply_generators_to_sync_message, generate_message};
+use pact_matching::generators::{
+  apply_generators_to_async_message,
+  apply_generators_to_sync_message,
+  generate_message
+};
 
 /// Iterator over the messages build with the PactBuilder
 pub struct MessageIterator<MT> {
@@ -25,70 +33,107 @@ pub struct MessageIterator<MT> {
   message_list: VecDeque<MT>,
   // Output directory to write pact files to when done
   output_dir: Option<PathBuf>,
+  // Options for the order and formatting of the pact file
+  write_options: WritePactOptions
 }
 
-/// Construct a new iterator over the asynchronous messages in the pact
-pub fn asynchronous_messages_iter(pact: V4Pact, output_dir: &Option<PathBuf>) -> MessageIterator<AsynchronousMessage> {
+/// Construct a new iterator over the asynchronous messages in the pact. Any provider state values
+/// will be applied to the provider state generators in the messages.
+pub fn asynchronous_messages_iter(
+  pact: V4Pact,
+  output_dir: &Option<PathBuf>,
+  provider_state_values: &HashMap<String, Value>
+) -> MessageIterator<AsynchronousMessage> {
+  let original_messages = pact.filter_interactions(V4InteractionType::Asynchronous_Messages)
+    .iter()
+    .map(|item| item.as_v4_async_message().unwrap())
+    .collect_vec();
+
+  let message_list = if provider_state_values.is_empty() {
+    original_messages
+  } else {
+    let values = provider_state_values.clone();
+    match generate_messages(original_messages.clone(), move |message| {
+      let values = values.clone();
+      async move {
+        let context: HashMap<&str, Value> = values.iter().map(|(k, v)| (k.as_str(), v.clone())).collect();
+        let provider_state_message = AsynchronousMessage {
+          contents: provider_state_contents(&message.contents),
+          .. message.clone()
+        };
+        let contents = apply_generators_to_async_message(&provider_state_message, &GeneratorTestMode::Provider,
+          &context, &vec![], &hashmap!{}).await;
+        AsynchronousMessage {
+          contents: MessageContents { generators: message.contents.generators.clone(), .. contents },
+          .. message
+        }
+      }
+    }) {
+      Some(messages) => messages,
+      None => {
+        error!("Was not able to apply the provider state values to the messages");
+        original_messages
+      }
+    }
+  };
+
   MessageIterator {
     pact: pact.boxed(),
-    message_list: pact.filter_interactions(V4InteractionType::Asynchronous_Messages)
-      .iter()
-      .map(|item| item.as_v4_async_message().unwrap())
-      .collect(),
-    output_dir: output_dir.clone()
+    message_list: message_list.iter().cloned().collect(),
+    output_dir: output_dir.clone(),
+    write_options: WritePactOptions::default()
   }
 }
 
-/// Construct a new iterator over the synchronous messages in the pact
-pub fn synchronous_messages_iter(pact: V4Pact, output_dir: &Option<PathBuf>) -> MessageIterator<SynchronousMessage> {
+/// Construct a new iterator over the synchronous messages in the pact. Any provider state values
+/// will be applied to the provider state generators in the messages, and the generator seed (if
+/// set) is used for the random generators.
+pub fn synchronous_messages_iter(
+  pact: V4Pact,
+  output_dir: &Option<PathBuf>,
+  provider_state_values: &HashMap<String, Value>,
+  generator_seed: Option<u64>
+) -> MessageIterator<SynchronousMessage> {
   let original_messages = pact.filter_interactions(V4InteractionType::Synchronous_Messages)
     .iter()
     .map(|item| item.as_v4_sync_message().unwrap())
     .collect_vec();
 
-  let (sx, rx) = channel();
-  match Handle::try_current() {
-    Ok(handle) => {
-      let messages_to_generate = original_messages.clone();
-      handle.spawn(async move {
-        let mut messages = vec![];
-        for message in messages_to_generate {
-          let (req, res) = apply_generators_to_sync_message(&message, &GeneratorTestMode::Consumer, &hashmap! {}, &vec![], &hashmap! {}).await;
-          messages.push(SynchronousMessage {
-            request: req,
-            response: res,
-            ..      message
-          });
+  let values = provider_state_values.clone();
+  let message_list = match generate_messages(original_messages.clone(), move |message| {
+    let values = values.clone();
+    async move {
+      let context: HashMap<&str, Value> = values.iter().map(|(k, v)| (k.as_str(), v.clone())).collect();
+      let message = if context.is_empty() {
+        message
+      } else {
+        let provider_state_message = SynchronousMessage {
+          request: provider_state_contents(&message.request),
+          response: message.response.iter().map(provider_state_contents).collect(),
+          .. message.clone()
+        };
+        let (req, res) = apply_generators_to_sync_message(&provider_state_message, &GeneratorTestMode::Provider,
+          &context, &vec![], &hashmap!{}).await;
+        SynchronousMessage {
+          request: MessageContents { generators: message.request.generators.clone(), .. req },
+          response: res.into_iter().zip(message.response.iter())
+            .map(|(contents, original)| MessageContents { generators: original.generators.clone(), .. contents })
+            .collect(),
+          .. message
         }
-        let _ = sx.send(messages);
-      })
-    },
-    Err(err) => {
-      warn!("Could not access the Tokio runtime, will start a new one: {}", err);
-      let messages_to_generate = original_messages.clone();
-      tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()
-        .expect("Could not start a Tokio runtime for running async tasks")
-        .spawn(async move {
-          let mut messages = vec![];
-          for message in messages_to_generate {
-            let (req, res) = apply_generators_to_sync_message(&message, &GeneratorTestMode::Consumer, &hashmap! {}, &vec![], &hashmap! {}).await;
-            messages.push(SynchronousMessage {
-              request: req,
-              response: res,
-              ..       message
-            });
-          }
-          let _ = sx.send(messages);
-        })
+      };
+      let (req, res) = apply_generators_to_sync_message(&message, &GeneratorTestMode::Consumer,
+        &consumer_context(generator_seed), &vec![], &hashmap! {}).await;
+      SynchronousMessage {
+        request: req,
+        response: res,
+        ..      message
+      }
     }
-  };
-
-  let message_list = match rx.recv() {
-    Ok(messages) => messages,
-    Err(err) => {
-      error!("Was not able to apply generators to the messages: {}", err);
+  }) {
+    Some(messages) => messages,
+    None => {
+      error!("Was not able to apply generators to the messages");
       original_messages
     }
   };
@@ -96,43 +141,146 @@ pub fn synchronous_messages_iter(pact: V4Pact, output_dir: &Option<PathBuf>) ->
   MessageIterator {
     pact: pact.boxed(),
     message_list: message_list.iter().cloned().collect(),
-    output_dir: output_dir.clone()
+    output_dir: output_dir.clone(),
+    write_options: WritePactOptions::default()
   }
 }
 
-/// Construct a new iterator over the messages in the Message Pact
-// TODO: This needs a mechanism to pass in the test context and plugin data
-pub fn messages_iter(pact: MessagePact, output_dir: &Option<PathBuf>) -> MessageIterator<Message> {
-  let original_messages = pact.messages.clone();
+/// Construct a new iterator over the messages in the Message Pact. Any provider state values
+/// will be applied to the provider state generators in the messages, and the generator seed (if
+/// set) is used for the random generators.
+// TODO: This needs a mechanism to pass in the plugin data
+pub fn messages_iter(
+  pact: MessagePact,
+  output_dir: &Option<PathBuf>,
+  provider_state_values: &HashMap<String, Value>,
+  generator_seed: Option<u64>
+) -> MessageIterator<Message> {
+  let values = provider_state_values.clone();
+  let message_list = generate_messages(pact.messages.clone(), move |message| {
+    let values = values.clone();
+    async move {
+      let context: HashMap<&str, Value> = values.iter().map(|(k, v)| (k.as_str(), v.clone())).collect();
+      let message = if context.is_empty() {
+        message
+      } else {
+        let provider_state_message = Message {
+          generators: provider_state_generators(&message.generators),
+          .. message.clone()
+        };
+        Message {
+          generators: message.generators.clone(),
+          .. generate_message(&provider_state_message, &GeneratorTestMode::Provider, &context, &vec![], &hashmap!{}).await
+        }
+      };
+      generate_message(&message, &GeneratorTestMode::Consumer, &consumer_context(generator_seed), &vec![], &hashmap!{}).await
+    }
+  }).expect("Did not receive any messages");
+
+  MessageIterator {
+    pact: pact.boxed(),
+    message_list: message_list.into_iter().collect(),
+    output_dir: output_dir.clone(),
+    write_options: WritePactOptions::default()
+  }
+}
+
+/// Applies the generators to the asynchronous message, as it would be received by the message
+/// consumer. Any provider state values are applied to the provider state generators first.
+pub(crate) async fn generate_async_message(
+  message: AsynchronousMessage,
+  provider_state_values: &HashMap<String, Value>,
+  generator_seed: Option<u64>
+) -> AsynchronousMessage {
+  let context: HashMap<&str, Value> = provider_state_values.iter().map(|(k, v)| (k.as_str(), v.clone())).collect();
+  let message = if context.is_empty() {
+    message
+  } else {
+    let provider_state_message = AsynchronousMessage {
+      contents: provider_state_contents(&message.contents),
+      .. message.clone()
+    };
+    let contents = apply_generators_to_async_message(&provider_state_message, &GeneratorTestMode::Provider,
+      &context, &vec![], &hashmap!{}).await;
+    AsynchronousMessage {
+      contents: MessageContents { generators: message.contents.generators.clone(), .. contents },
+      .. message
+    }
+  };
+  let contents = apply_generators_to_async_message(&message, &GeneratorTestMode::Consumer,
+    &consumer_context(generator_seed), &vec![], &hashmap!{}).await;
+  AsynchronousMessage {
+    contents,
+    .. message
+  }
+}
+
+/// Returns the context to use when applying the generators in consumer mode
+fn consumer_context(generator_seed: Option<u64>) -> HashMap<&'static str, Value> {
+  generator_seed.map(|seed| hashmap!{ RANDOM_SEED => Value::from(seed) }).unwrap_or_default()
+}
+
+/// Applies the generate function to each of the messages using the current Tokio runtime, starting
+/// a new one if there is no current runtime.
+pub(crate) fn generate_messages<MT, F, R>(messages: Vec<MT>, generate: F) -> Option<Vec<MT>>
+  where MT: Send + 'static,
+        F: Fn(MT) -> R + Send + 'static,
+        R: Future<Output = MT> + Send
+{
   let (sx, rx) = channel();
+  let future = async move {
+    let mut generated = vec![];
+    for message in messages {
+      generated.push(generate(message).await);
+    }
+    let _ = sx.send(generated);
+  };
   match Handle::try_current() {
-    Ok(handle) => handle.spawn(async move {
-      let mut messages = VecDeque::new();
-      for message in original_messages {
-        messages.push_back(generate_message(&message, &GeneratorTestMode::Consumer, &hashmap!{}, &vec![], &hashmap!{}).await);
-      }
-      let _ = sx.send(messages);
-    }),
+    Ok(handle) => {
+      handle.spawn(future);
+    },
     Err(err) => {
       warn!("Could not access the Tokio runtime, will start a new one: {}", err);
       tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
         .expect("Could not start a Tokio runtime for running async tasks")
-        .spawn(async move {
-          let mut messages = VecDeque::new();
-          for message in original_messages {
-            messages.push_back(generate_message(&message, &GeneratorTestMode::Consumer, &hashmap!{}, &vec![], &hashmap!{}).await);
-          }
-          let _ = sx.send(messages);
-        })
+        .spawn(future);
     }
   };
 
-  MessageIterator {
-    pact: pact.boxed(),
-    message_list: rx.recv().expect("Did not receive any messages"),
-    output_dir: output_dir.clone()
+  rx.recv().ok()
+}
+
+/// Returns a copy of the message contents with only the provider state generators. Provider state
+/// generators are normally only applied when verifying the provider, so these contents are used to
+/// apply the provider state values in provider mode.
+fn provider_state_contents(contents: &MessageContents) -> MessageContents {
+  MessageContents {
+    generators: provider_state_generators(&contents.generators),
+    .. contents.clone()
+  }
+}
+
+/// Returns a copy of the generators that only contains the provider state generators
+fn provider_state_generators(generators: &Generators) -> Generators {
+  Generators {
+    categories: generators.categories.iter()
+      .map(|(category, generators)| {
+        (*category, generators.iter()
+          .filter(|(_, generator)| matches!(generator, Generator::ProviderStateGenerator(_, _)))
+          .map(|(path, generator)| (path.clone(), generator.clone()))
+          .collect())
+      })
+      .collect()
+  }
+}
+
+impl <MT> MessageIterator<MT> {
+  /// Sets the options used to write the pact file when the iterator is dropped
+  pub(crate) fn with_write_options(mut self, write_options: WritePactOptions) -> Self {
+    self.write_options = write_options;
+    self
   }
 }
 
@@ -147,28 +295,37 @@ impl <MT> Iterator for MessageIterator<MT> {
 impl <MT> Drop for MessageIterator<MT> {
   fn drop(&mut self) {
     if !::std::thread::panicking() {
-
-      // Write out the Pact file
-      let output_dir = self.output_dir.as_ref().map(|dir| dir.to_string_lossy().to_string())
-        .unwrap_or_else(|| {
-          let val = env::var("PACT_OUTPUT_DIR");
-          debug!("env:PACT_OUTPUT_DIR = {:?}", val);
-          val.unwrap_or_else(|_| "target/pacts".to_owned())
-        });
-      let overwrite = env::var("PACT_OVERWRITE");
-      debug!("env:PACT_OVERWRITE = {:?}", overwrite);
-
-      let pact_file_name = self.pact.default_file_name();
-      let mut path = PathBuf::from(output_dir);
-      path.push(pact_file_name);
-
-      info!("Writing pact out to '{}'", path.display());
-      let specification = self.pact.specification_version();
-      if let Err(err) = write_pact(self.pact.boxed(), path.as_path(), specification,
-                                   overwrite.unwrap_or_else(|_| String::default()) == "true") {
-        error!("Failed to write pact to file - {}", err);
-        panic!("Failed to write pact to file - {}", err);
+      if let Err(err) = write_pact_file(self.pact.as_ref(), &self.output_dir, &self.write_options) {
+        error!("{}", err);
+        panic!("{}", err);
       }
     }
   }
 }
+
+/// Writes out the Pact file to the output directory. If no output directory is given, the
+/// `PACT_OUTPUT_DIR` environment variable is used, defaulting to `target/pacts`.
+pub(crate) fn write_pact_file(
+  pact: &(dyn Pact + Send + Sync),
+  output_dir: &Option<PathBuf>,
+  write_options: &WritePactOptions
+) -> anyhow::Result<()> {
+  let output_dir = output_dir.as_ref().map(|dir| dir.to_string_lossy().to_string())
+    .unwrap_or_else(|| {
+      let val = env::var("PACT_OUTPUT_DIR");
+      debug!("env:PACT_OUTPUT_DIR = {:?}", val);
+      val.unwrap_or_else(|_| "target/pacts".to_owned())
+    });
+  let overwrite = env::var("PACT_OVERWRITE");
+  debug!("env:PACT_OVERWRITE = {:?}", overwrite);
+
+  let pact_file_name = pact.default_file_name();
+  let mut path = PathBuf::from(output_dir);
+  path.push(pact_file_name);
+
+  info!("Writing pact out to '{}'", path.display());
+  let specification = pact.specification_version();
+  write_pact_with_options(pact.boxed(), path.as_path(), specification,
+                          overwrite.unwrap_or_else(|_| String::default()) == "true", write_options)
+    .map_err(|err| anyhow!("Failed to write pact to file - {}", err))
+}
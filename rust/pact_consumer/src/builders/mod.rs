@@ -10,7 +10,9 @@ pub mod plugin_builder;
 mod request_builder;
 mod response_builder;
 mod message_builder;
+mod message_handler;
 mod message_iter;
+pub(crate) mod expressions;
 mod sync_message_builder;
 
 pub use self::interaction_builder::*;
@@ -20,6 +22,7 @@ pub use self::pact_builder_async::*;
 pub use self::request_builder::*;
 pub use self::response_builder::*;
 pub use self::message_builder::*;
+pub use self::message_handler::{MessageHandlerFailure, MessageVerificationError, ReifiedMessage};
 pub use self::sync_message_builder::*;
 
 #[test]
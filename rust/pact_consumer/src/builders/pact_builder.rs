@@ -1,11 +1,13 @@
+use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
 use std::panic::RefUnwindSafe;
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 use pact_models::{Consumer, Provider};
 use pact_models::interaction::Interaction;
 use pact_models::message::Message;
-use pact_models::pact::Pact;
+use pact_models::pact::{Pact, set_created_at, WritePactOptions};
 use pact_models::prelude::MessagePact;
 use pact_models::sync_pact::RequestResponsePact;
 use pact_models::v4::async_message::AsynchronousMessage;
@@ -15,11 +17,14 @@ use pact_models::v4::sync_message::SynchronousMessage;
 #[cfg(feature = "plugins")] use pact_plugin_driver::catalogue_manager::CatalogueEntryType;
 #[cfg(feature = "plugins")] use pact_plugin_driver::plugin_manager::load_plugin;
 #[cfg(feature = "plugins")] use pact_plugin_driver::plugin_models::PluginDependency;
+use serde_json::Value;
 use tracing::trace;
 
 use pact_matching::metrics::{MetricEvent, send_metrics};
+use pact_mock_server::mock_server::MockServerConfig;
 
 use crate::builders::message_builder::MessageInteractionBuilder;
+use crate::builders::message_handler::{MessageVerificationError, record_results, ReifiedMessage, reify_messages};
 use crate::builders::message_iter::{
   asynchronous_messages_iter,
   messages_iter,
@@ -57,7 +62,11 @@ use super::interaction_builder::InteractionBuilder;
 /// ```
 pub struct PactBuilder {
   pact: Box<dyn Pact + Send + Sync + RefUnwindSafe>,
-  output_dir: Option<PathBuf>
+  output_dir: Option<PathBuf>,
+  provider_state_values: HashMap<String, Value>,
+  write_options: WritePactOptions,
+  cors_preflight: bool,
+  generator_seed: Option<u64>
 }
 
 impl PactBuilder {
@@ -83,7 +92,7 @@ impl PactBuilder {
           pact.add_md_version("consumer", version);
         }
 
-        PactBuilder { pact: pact.boxed(), output_dir: None }
+        PactBuilder { pact: pact.boxed(), output_dir: None, provider_state_values: HashMap::new(), write_options: WritePactOptions::default(), cors_preflight: false, generator_seed: None }
     }
 
   /// Create a new `PactBuilder`, specifying the names of the service
@@ -109,7 +118,7 @@ impl PactBuilder {
       pact.add_md_version("consumer", version);
     }
 
-    PactBuilder { pact: pact.boxed(), output_dir: None }
+    PactBuilder { pact: pact.boxed(), output_dir: None, provider_state_values: HashMap::new(), write_options: WritePactOptions::default(), cors_preflight: false, generator_seed: None }
   }
 
     /// Create a new `PactBuilder` for a V4 specification Pact, specifying the names of the service
@@ -132,7 +141,7 @@ impl PactBuilder {
         pact.add_md_version("consumer", version);
       }
 
-      PactBuilder { pact: pact.boxed(), output_dir: None }
+      PactBuilder { pact: pact.boxed(), output_dir: None, provider_state_values: HashMap::new(), write_options: WritePactOptions::default(), cors_preflight: false, generator_seed: None }
     }
 
   ///  Sets the output directory to write any pact files to. If this is not set, will default
@@ -169,9 +178,31 @@ impl PactBuilder {
         Err(err) => panic!("Could not load plugin - {}", err)
       }
 
-      PactBuilderAsync::from_builder(pact, self.output_dir.clone())
+      PactBuilderAsync::from_builder(pact, self.output_dir.clone(), self.provider_state_values.clone(),
+        self.write_options, self.cors_preflight, self.generator_seed)
     }
 
+  /// Adds a configuration block for a plugin to the Pact metadata. This allows the plugin
+  /// configuration to be provided without needing to load the plugin, for instance when building
+  /// a Pact for a language binding that manages the plugins itself.
+  ///
+  /// Panics:
+  /// Plugin configuration is only supported with V4 specification pacts. This method will panic
+  /// if the pact being built is V3 format.
+  pub fn with_plugin_config<N, V>(&mut self, name: N, version: V, config: HashMap<String, Value>) -> &mut Self
+    where
+      N: Into<String>,
+      V: Into<String>
+  {
+    if !self.pact.is_v4() {
+      panic!("Plugin configuration requires V4 specification pacts. Use PactBuilder::new_v4");
+    }
+
+    self.pact.add_plugin(name.into().as_str(), version.into().as_str(), Some(config))
+      .expect("Could not add plugin configuration to pact");
+    self
+  }
+
     /// Add a new HTTP `Interaction` to the `Pact`. Needs to return a clone of the builder
     /// that is passed in.
     pub fn interaction<D, F>(&mut self, description: D, interaction_type: D, build_fn: F) -> &mut Self
@@ -183,6 +214,13 @@ impl PactBuilder {
         let interaction = build_fn(interaction);
 
         if self.pact.is_v4() {
+          #[cfg(feature = "plugins")]
+          for (plugin_name, plugin_config) in interaction.pact_plugin_config() {
+            if let Some(plugin_data) = self.pact.plugin_data().iter().find(|pl| pl.name == plugin_name) {
+              self.pact.add_plugin(plugin_data.name.as_str(), plugin_data.version.as_str(),
+                Some(plugin_config)).expect("Could not update Pact with plugin data");
+            }
+          }
           self.push_interaction(&interaction.build_v4())
         } else {
           self.push_interaction(&interaction.build())
@@ -210,6 +248,60 @@ impl PactBuilder {
     self
   }
 
+  /// Sets a value to use for provider state generators (i.e. `from_provider_state` expressions)
+  /// when generating the messages returned from the message iterators. Provider state generators
+  /// are normally only applied when verifying the provider, so this allows the consumer's handling
+  /// of the generated values to be tested. Note that the values are not applied to the responses
+  /// from the HTTP mock server.
+  pub fn with_provider_state_value<K, V>(&mut self, key: K, value: V) -> &mut Self
+    where
+      K: Into<String>,
+      V: Into<Value>
+  {
+    self.provider_state_values.insert(key.into(), value.into());
+    self
+  }
+
+  /// Sets the seed for the random generators (i.e. RandomInt, Uuid, RandomString), so the same
+  /// values are generated for the messages returned from the message iterators each time the
+  /// test is run. Note that the seed is not applied to the responses from the HTTP mock server.
+  pub fn with_generator_seed(&mut self, seed: u64) -> &mut Self {
+    self.generator_seed = Some(seed);
+    self
+  }
+
+  /// Records the current time as the creation time of the pact (the `createdAt` value in the
+  /// `pactRust` metadata). Verifiers can use this to detect stale pacts that have not been
+  /// re-published recently.
+  pub fn with_created_at(&mut self) -> &mut Self {
+    set_created_at(self.pact.as_mut(), SystemTime::now());
+    self
+  }
+
+  /// Sets the options used to write the pact file, for instance to sort the interactions so that
+  /// the pact file does not change when the tests are run in a different order. Note that these
+  /// options are not used by mock servers provided by plugins.
+  pub fn with_write_options(&mut self, write_options: WritePactOptions) -> &mut Self {
+    self.write_options = write_options;
+    self
+  }
+
+  /// Automatically respond to CORS preflight requests (`OPTIONS` requests with an
+  /// `Access-Control-Request-Method` header) from the mock server, so that browser based tests do
+  /// not need to add interactions for them. Note that this is only supported by the HTTP mock
+  /// server, and not by mock servers provided by plugins.
+  pub fn with_cors_preflight(&mut self, enabled: bool) -> &mut Self {
+    self.cors_preflight = enabled;
+    self
+  }
+
+  fn mock_server_config(&self) -> MockServerConfig {
+    MockServerConfig {
+      cors_preflight: self.cors_preflight,
+      .. MockServerConfig::default()
+    }
+  }
+
   /// Add a new Asynchronous message `Interaction` to the `Pact`
   pub fn message_interaction<D, F>(&mut self, description: D, build_fn: F) -> &mut Self
     where
@@ -255,7 +347,32 @@ impl PactBuilder {
       app_name: "pact_consumer".to_string(),
       app_version: env!("CARGO_PKG_VERSION").to_string()
     });
-    asynchronous_messages_iter(self.pact.as_v4_pact().unwrap(), &self.output_dir)
+    asynchronous_messages_iter(self.pact.as_v4_pact().unwrap(), &self.output_dir, &self.provider_state_values)
+      .with_write_options(self.write_options)
+  }
+
+  /// Verifies the message consumer with the asynchronous messages in the Pact. The handler is
+  /// invoked with each message after any generators have been applied (as it would be received
+  /// from the message queue), and should process it in the same way as the consumer code does.
+  /// The Pact file is only written if the handler was able to process all the messages, otherwise
+  /// an error is returned with the messages that failed.
+  pub fn verify_messages<F>(&self, mut handler: F) -> Result<(), MessageVerificationError>
+    where F: FnMut(&ReifiedMessage) -> anyhow::Result<()>
+  {
+    send_metrics(MetricEvent::ConsumerTestRun {
+      interactions: self.pact.interactions().len(),
+      test_framework: "pact_consumer".to_string(),
+      app_name: "pact_consumer".to_string(),
+      app_version: env!("CARGO_PKG_VERSION").to_string()
+    });
+    let results = reify_messages(&self.pact.as_v4_pact().unwrap(), &self.provider_state_values, self.generator_seed)
+      .into_iter()
+      .map(|message| {
+        let result = handler(&message);
+        (message, result)
+      })
+      .collect();
+    record_results(self.pact.as_ref(), results, &self.output_dir, &self.write_options)
   }
 
   /// Returns an iterator over the asynchronous messages in a V3 Message Pact
@@ -266,7 +383,9 @@ impl PactBuilder {
       app_name: "pact_consumer".to_string(),
       app_version: env!("CARGO_PKG_VERSION").to_string()
     });
-    messages_iter(self.pact.as_message_pact().unwrap(), &self.output_dir)
+    messages_iter(self.pact.as_message_pact().unwrap(), &self.output_dir, &self.provider_state_values,
+      self.generator_seed)
+      .with_write_options(self.write_options)
   }
 
   /// Returns an iterator over the synchronous req/res messages in the Pact
@@ -277,7 +396,9 @@ impl PactBuilder {
       app_name: "pact_consumer".to_string(),
       app_version: env!("CARGO_PKG_VERSION").to_string()
     });
-    synchronous_messages_iter(self.pact.as_v4_pact().unwrap(), &self.output_dir)
+    synchronous_messages_iter(self.pact.as_v4_pact().unwrap(), &self.output_dir, &self.provider_state_values,
+      self.generator_seed)
+      .with_write_options(self.write_options)
   }
 }
 
@@ -295,13 +416,15 @@ impl StartMockServer for PactBuilder {
           }
           None => panic!("Did not find a catalogue entry for key '{}'", entry_name)
         }
-        None => ValidatingHttpMockServer::start(self.build(), self.output_dir.clone())
+        None => ValidatingHttpMockServer::start_with_config(self.build(), self.output_dir.clone(), self.write_options,
+          self.mock_server_config())
       }
     }
 
     #[cfg(not(feature = "plugins"))]
     {
-      ValidatingHttpMockServer::start(self.build(), self.output_dir.clone())
+      ValidatingHttpMockServer::start_with_config(self.build(), self.output_dir.clone(), self.write_options,
+          self.mock_server_config())
     }
   }
 }
@@ -314,17 +437,25 @@ impl Debug for PactBuilder {
 
 #[cfg(test)]
 mod tests {
+  use std::time::{Duration, SystemTime};
+
   use bytes::Bytes;
   use expectest::prelude::*;
   use maplit::hashmap;
   use pact_models::bodies::OptionalBody;
+  use pact_models::generators::{Generator, GeneratorCategory, Generators};
   use pact_models::matchingrules::{Category, MatchingRuleCategory, MatchingRules};
+  use pact_models::pact::{created_at, WritePactOptions};
+  use pact_models::path_exp::DocPath;
   use pact_models::provider_states::ProviderState;
+  use pact_models::v4::async_message::AsynchronousMessage;
   use pact_models::v4::http_parts::{HttpRequest, HttpResponse};
+  use pact_models::v4::message_parts::MessageContents;
   use pact_models::v4::synch_http::SynchronousHttp;
-  use serde_json::Value;
+  use serde_json::{json, Value};
 
   use crate::builders::{HttpPartBuilder, PactBuilder};
+  use crate::builders::message_handler::reify_messages;
 
   #[test]
   fn v4_calc_key_test() {
@@ -414,4 +545,155 @@ mod tests {
     }, v4interaction);
     expect!(v4interaction.key.as_ref().unwrap()).to(be_equal_to("93371e6e7ae2556"));
   }
+
+  #[test]
+  fn messages_applies_the_provider_state_values() {
+    let mut generators = Generators::default();
+    generators.add_generator_with_subcategory(&GeneratorCategory::BODY, DocPath::new_unwrap("$.id"),
+      Generator::ProviderStateGenerator("${id}".to_string(), None));
+    let message = AsynchronousMessage {
+      description: "a message with a provider state value".to_string(),
+      contents: MessageContents {
+        contents: OptionalBody::from(json!({ "id": 1 })),
+        generators: generators.clone(),
+        .. MessageContents::default()
+      },
+      .. AsynchronousMessage::default()
+    };
+
+    let mut pact_builder = PactBuilder::new_v4("Consumer", "Provider");
+    pact_builder.push_interaction(&message);
+
+    let messages = pact_builder.messages().collect::<Vec<_>>();
+    expect!(messages[0].contents.contents.value_as_string()).to(be_some().value("{\"id\":1}".to_string()));
+
+    pact_builder.with_provider_state_value("id", 1000);
+    let messages = pact_builder.messages().collect::<Vec<_>>();
+    expect!(messages[0].contents.contents.value_as_string()).to(be_some().value("{\"id\":1000}".to_string()));
+    expect!(&messages[0].contents.generators).to(be_equal_to(&generators));
+  }
+
+  #[test]
+  fn verify_messages_invokes_the_handler_with_the_generated_messages() {
+    let mut generators = Generators::default();
+    generators.add_generator_with_subcategory(&GeneratorCategory::BODY, DocPath::new_unwrap("$.id"),
+      Generator::ProviderStateGenerator("${id}".to_string(), None));
+    let message = AsynchronousMessage {
+      description: "a message with a provider state value".to_string(),
+      contents: MessageContents {
+        contents: OptionalBody::from(json!({ "id": 1 })),
+        generators,
+        .. MessageContents::default()
+      },
+      .. AsynchronousMessage::default()
+    };
+
+    let mut pact_builder = PactBuilder::new_v4("Consumer", "Provider");
+    pact_builder.push_interaction(&message)
+      .with_output_dir("target/message_pacts")
+      .with_provider_state_value("id", 1000);
+
+    let mut ids = vec![];
+    let result = pact_builder.verify_messages(|message| {
+      ids.push(message.json()?["id"].clone());
+      Ok(())
+    });
+    expect!(result).to(be_ok());
+    expect!(ids).to(be_equal_to(vec![json!(1000)]));
+  }
+
+  #[test]
+  fn with_generator_seed_generates_the_same_values_for_the_messages() {
+    let mut generators = Generators::default();
+    generators.add_generator_with_subcategory(&GeneratorCategory::BODY, DocPath::new_unwrap("$.id"),
+      Generator::RandomInt(0, 1000000));
+    let message = AsynchronousMessage {
+      description: "a message with a random value".to_string(),
+      contents: MessageContents {
+        contents: OptionalBody::from(json!({ "id": 1 })),
+        generators,
+        .. MessageContents::default()
+      },
+      .. AsynchronousMessage::default()
+    };
+
+    let mut pact_builder = PactBuilder::new_v4("Consumer", "Provider");
+    pact_builder.push_interaction(&message)
+      .with_generator_seed(1234);
+    let pact = pact_builder.pact.as_v4_pact().unwrap();
+
+    let generated = reify_messages(&pact, &hashmap!{}, pact_builder.generator_seed);
+    expect!(reify_messages(&pact, &hashmap!{}, pact_builder.generator_seed)).to(be_equal_to(generated.clone()));
+    expect!(reify_messages(&pact, &hashmap!{}, Some(5678))).to_not(be_equal_to(generated));
+  }
+
+  #[test]
+  fn supports_adding_plugin_configuration_to_the_pact() {
+    let pact = PactBuilder::new_v4("Consumer", "Provider")
+      .with_plugin_config("protobuf", "0.3.0", hashmap! { "descriptorKey".to_string() => json!("abc123") })
+      .message_interaction("a message", |mut i| {
+        i.with_plugin_config("protobuf", hashmap! { "message".to_string() => json!(".Request") });
+        i
+      })
+      .build();
+
+    let plugin_data = pact.plugin_data();
+    expect!(plugin_data.len()).to(be_equal_to(1));
+    expect!(plugin_data[0].name.as_str()).to(be_equal_to("protobuf"));
+    expect!(plugin_data[0].version.as_str()).to(be_equal_to("0.3.0"));
+    expect!(plugin_data[0].configuration.clone()).to(be_equal_to(hashmap! {
+      "descriptorKey".to_string() => json!("abc123")
+    }));
+    let interactions = pact.interactions();
+    expect!(interactions[0].as_v4().unwrap().plugin_config()).to(be_equal_to(hashmap! {
+      "protobuf".to_string() => hashmap! { "message".to_string() => json!(".Request") }
+    }));
+  }
+
+  #[test]
+  #[should_panic]
+  fn with_plugin_config_panics_with_a_v3_pact() {
+    PactBuilder::new("Consumer", "Provider")
+      .with_plugin_config("protobuf", "0.3.0", hashmap! {});
+  }
+
+  #[test]
+  fn with_created_at_records_the_creation_time_in_the_pact_metadata() {
+    let pact = PactBuilder::new("Consumer", "Provider").build();
+    expect!(created_at(pact.as_ref())).to(be_none());
+
+    let before = SystemTime::now() - Duration::from_secs(1);
+    let pact = PactBuilder::new_v4("Consumer", "Provider")
+      .with_created_at()
+      .build();
+    let created = created_at(pact.as_ref()).unwrap();
+    expect!(created >= before).to(be_true());
+    expect!(created <= SystemTime::now()).to(be_true());
+  }
+
+  #[test]
+  fn with_write_options_controls_the_order_and_format_of_the_pact_file() {
+    let output_dir = std::env::temp_dir().join(format!("pact_write_options_{}", std::process::id()));
+    {
+      let mut builder = PactBuilder::new_v4("write-options-consumer", "write-options-provider");
+      builder
+        .with_output_dir(&output_dir)
+        .with_write_options(WritePactOptions { indent: 4, .. WritePactOptions::deterministic() })
+        .message_interaction("b message", |i| i)
+        .message_interaction("a message", |i| i);
+      let descriptions = builder.messages().map(|m| m.description).collect::<Vec<_>>();
+      expect!(descriptions).to(be_equal_to(vec!["b message".to_string(), "a message".to_string()]));
+    }
+
+    let contents = std::fs::read_to_string(output_dir.join("write-options-consumer-write-options-provider.json"));
+    let _ = std::fs::remove_dir_all(&output_dir);
+    let contents = contents.unwrap();
+    let json: Value = serde_json::from_str(&contents).unwrap();
+    let descriptions = json["interactions"].as_array().unwrap().iter()
+      .map(|i| i["description"].as_str().unwrap())
+      .collect::<Vec<_>>();
+    expect!(descriptions).to(be_equal_to(vec!["a message", "b message"]));
+    expect!(contents.starts_with("{\n    \"consumer\": {\n        \"name\"")).to(be_true());
+  }
 }
+
@@ -1,10 +1,12 @@
+use std::collections::HashMap;
 use std::future::Future;
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 use async_trait::async_trait;
 use pact_models::{Consumer, Provider};
 use pact_models::interaction::Interaction;
-use pact_models::pact::Pact;
+use pact_models::pact::{Pact, set_created_at, WritePactOptions};
 use pact_models::sync_pact::RequestResponsePact;
 use pact_models::v4::async_message::AsynchronousMessage;
 use pact_models::v4::pact::V4Pact;
@@ -13,11 +15,14 @@ use pact_models::v4::sync_message::SynchronousMessage;
 #[cfg(feature = "plugins")] use pact_plugin_driver::catalogue_manager::CatalogueEntryType;
 #[cfg(feature = "plugins")] use pact_plugin_driver::plugin_manager::{drop_plugin_access, load_plugin};
 #[cfg(feature = "plugins")] use pact_plugin_driver::plugin_models::{PluginDependency, PluginDependencyType};
+use serde_json::Value;
 use tracing::trace;
 
 use pact_matching::metrics::{MetricEvent, send_metrics};
+use pact_mock_server::mock_server::MockServerConfig;
 
 use crate::builders::message_builder::MessageInteractionBuilder;
+use crate::builders::message_handler::{MessageVerificationError, record_results, ReifiedMessage, reify_messages_async};
 use crate::builders::message_iter::{asynchronous_messages_iter, MessageIterator, synchronous_messages_iter};
 use crate::builders::sync_message_builder::SyncMessageInteractionBuilder;
 use crate::mock_server::http_mock_server::ValidatingHttpMockServer;
@@ -54,7 +59,11 @@ use super::interaction_builder::InteractionBuilder;
 #[derive(Debug)]
 pub struct PactBuilderAsync {
   pact: Box<dyn Pact + Send + Sync>,
-  output_dir: Option<PathBuf>
+  output_dir: Option<PathBuf>,
+  provider_state_values: HashMap<String, Value>,
+  write_options: WritePactOptions,
+  cors_preflight: bool,
+  generator_seed: Option<u64>
 }
 
 impl PactBuilderAsync {
@@ -80,7 +89,7 @@ impl PactBuilderAsync {
           pact.add_md_version("consumer", version);
         }
 
-      PactBuilderAsync { pact: pact.boxed(), output_dir: None }
+      PactBuilderAsync { pact: pact.boxed(), output_dir: None, provider_state_values: HashMap::new(), write_options: WritePactOptions::default(), cors_preflight: false, generator_seed: None }
     }
 
     /// Create a new `PactBuilder` for a V4 specification Pact, specifying the names of the service
@@ -103,14 +112,25 @@ impl PactBuilderAsync {
         pact.add_md_version("consumer", version);
       }
 
-      PactBuilderAsync { pact: pact.boxed(), output_dir: None }
+      PactBuilderAsync { pact: pact.boxed(), output_dir: None, provider_state_values: HashMap::new(), write_options: WritePactOptions::default(), cors_preflight: false, generator_seed: None }
     }
 
     #[cfg(feature = "plugins")]
-    pub(crate) fn from_builder(pact: Box<dyn Pact + Send + Sync>, output_dir: Option<PathBuf>) -> Self {
+    pub(crate) fn from_builder(
+      pact: Box<dyn Pact + Send + Sync>,
+      output_dir: Option<PathBuf>,
+      provider_state_values: HashMap<String, Value>,
+      write_options: WritePactOptions,
+      cors_preflight: bool,
+      generator_seed: Option<u64>
+    ) -> Self {
       PactBuilderAsync {
         pact,
-        output_dir
+        output_dir,
+        provider_state_values,
+        write_options,
+        cors_preflight,
+        generator_seed
       }
     }
 
@@ -186,6 +206,60 @@ impl PactBuilderAsync {
     self
   }
 
+  /// Sets a value to use for provider state generators (i.e. `from_provider_state` expressions)
+  /// when generating the messages returned from the message iterators. Provider state generators
+  /// are normally only applied when verifying the provider, so this allows the consumer's handling
+  /// of the generated values to be tested. Note that the values are not applied to the responses
+  /// from the HTTP mock server.
+  pub fn with_provider_state_value<K, V>(&mut self, key: K, value: V) -> &mut Self
+    where
+      K: Into<String>,
+      V: Into<Value>
+  {
+    self.provider_state_values.insert(key.into(), value.into());
+    self
+  }
+
+  /// Sets the seed for the random generators (i.e. RandomInt, Uuid, RandomString), so the same
+  /// values are generated for the messages returned from the message iterators each time the
+  /// test is run. Note that the seed is not applied to the responses from the HTTP mock server.
+  pub fn with_generator_seed(&mut self, seed: u64) -> &mut Self {
+    self.generator_seed = Some(seed);
+    self
+  }
+
+  /// Records the current time as the creation time of the pact (the `createdAt` value in the
+  /// `pactRust` metadata). Verifiers can use this to detect stale pacts that have not been
+  /// re-published recently.
+  pub fn with_created_at(&mut self) -> &mut Self {
+    set_created_at(self.pact.as_mut(), SystemTime::now());
+    self
+  }
+
+  /// Sets the options used to write the pact file, for instance to sort the interactions so that
+  /// the pact file does not change when the tests are run in a different order. Note that these
+  /// options are not used by mock servers provided by plugins.
+  pub fn with_write_options(&mut self, write_options: WritePactOptions) -> &mut Self {
+    self.write_options = write_options;
+    self
+  }
+
+  /// Automatically respond to CORS preflight requests (`OPTIONS` requests with an
+  /// `Access-Control-Request-Method` header) from the mock server, so that browser based tests do
+  /// not need to add interactions for them. Note that this is only supported by the HTTP mock
+  /// server, and not by mock servers provided by plugins.
+  pub fn with_cors_preflight(&mut self, enabled: bool) -> &mut Self {
+    self.cors_preflight = enabled;
+    self
+  }
+
+  fn mock_server_config(&self) -> MockServerConfig {
+    MockServerConfig {
+      cors_preflight: self.cors_preflight,
+      .. MockServerConfig::default()
+    }
+  }
+
   /// Add a new Asynchronous message `Interaction` to the `Pact`. Needs to return a clone of the builder
   /// that is passed in.
   pub async fn message_interaction<D, F, O>(&mut self, description: D, build_fn: F) -> &mut Self
@@ -235,7 +309,32 @@ impl PactBuilderAsync {
       app_name: "pact_consumer".to_string(),
       app_version: env!("CARGO_PKG_VERSION").to_string()
     });
-    asynchronous_messages_iter(self.pact.as_v4_pact().unwrap(), &self.output_dir)
+    asynchronous_messages_iter(self.pact.as_v4_pact().unwrap(), &self.output_dir, &self.provider_state_values)
+      .with_write_options(self.write_options)
+  }
+
+  /// Verifies the message consumer with the asynchronous messages in the Pact. The handler is
+  /// invoked with each message after any generators have been applied (as it would be received
+  /// from the message queue), and should process it in the same way as the consumer code does.
+  /// The Pact file is only written if the handler was able to process all the messages, otherwise
+  /// an error is returned with the messages that failed.
+  pub async fn verify_messages<F, O>(&self, mut handler: F) -> Result<(), MessageVerificationError>
+    where
+      F: FnMut(ReifiedMessage) -> O,
+      O: Future<Output = anyhow::Result<()>>
+  {
+    send_metrics(MetricEvent::ConsumerTestRun {
+      interactions: self.pact.interactions().len(),
+      test_framework: "pact_consumer".to_string(),
+      app_name: "pact_consumer".to_string(),
+      app_version: env!("CARGO_PKG_VERSION").to_string()
+    });
+    let mut results = vec![];
+    for message in reify_messages_async(&self.pact.as_v4_pact().unwrap(), &self.provider_state_values, self.generator_seed).await {
+      let result = handler(message.clone()).await;
+      results.push((message, result));
+    }
+    record_results(self.pact.as_ref(), results, &self.output_dir, &self.write_options)
   }
 
   /// Returns an iterator over the synchronous req/res messages in the Pact
@@ -246,7 +345,9 @@ impl PactBuilderAsync {
       app_name: "pact_consumer".to_string(),
       app_version: env!("CARGO_PKG_VERSION").to_string()
     });
-    synchronous_messages_iter(self.pact.as_v4_pact().unwrap(), &self.output_dir)
+    synchronous_messages_iter(self.pact.as_v4_pact().unwrap(), &self.output_dir, &self.provider_state_values,
+      self.generator_seed)
+      .with_write_options(self.write_options)
   }
 }
 
@@ -264,13 +365,15 @@ impl StartMockServer for PactBuilderAsync {
           }
           None => panic!("Did not find a catalogue entry for key '{}'", entry_name)
         }
-        None => ValidatingHttpMockServer::start(self.build(), self.output_dir.clone())
+        None => ValidatingHttpMockServer::start_with_config(self.build(), self.output_dir.clone(), self.write_options,
+          self.mock_server_config())
       }
     }
 
     #[cfg(not(feature = "plugins"))]
     {
-      ValidatingHttpMockServer::start(self.build(), self.output_dir.clone())
+      ValidatingHttpMockServer::start_with_config(self.build(), self.output_dir.clone(), self.write_options,
+          self.mock_server_config())
     }
   }
 }
@@ -290,13 +393,15 @@ impl StartMockServerAsync for PactBuilderAsync {
           }
           None => panic!("Did not find a catalogue entry for key '{}'", entry_name)
         }
-        None => ValidatingHttpMockServer::start_async(self.build(), self.output_dir.clone()).await
+        None => ValidatingHttpMockServer::start_async_with_config(self.build(), self.output_dir.clone(), self.write_options,
+          self.mock_server_config()).await
       }
     }
 
     #[cfg(not(feature = "plugins"))]
     {
-      ValidatingHttpMockServer::start_async(self.build(), self.output_dir.clone()).await
+      ValidatingHttpMockServer::start_async_with_config(self.build(), self.output_dir.clone(), self.write_options,
+          self.mock_server_config()).await
     }
   }
 }
@@ -146,6 +146,41 @@ impl RequestBuilder {
         self
     }
 
+    /// Specify a query parameter with a value generated from the provider state. The expression
+    /// is evaluated against the provider state values when the request is sent to the provider,
+    /// and the example value is used in the consumer test.
+    ///
+    /// ```
+    /// use pact_consumer::*;
+    /// use pact_consumer::builders::RequestBuilder;
+    /// use regex::Regex;
+    ///
+    /// RequestBuilder::default()
+    ///     .query_param_from_provider_state("id", "${id}", "100")
+    ///     .query_param_from_provider_state("digits", "digits", term!("^[0-9]+$", "123"));
+    /// ```
+    pub fn query_param_from_provider_state<K, E, V>(&mut self, key: K, expression: E, value: V) -> &mut Self
+    where
+        K: Into<String>,
+        E: Into<String>,
+        V: Into<StringPattern>,
+    {
+        let key = key.into();
+        let expression = expression.into();
+        self.query_param(&key, value);
+        let mut path = DocPath::root();
+        path.push_field(key);
+        {
+            let generators = self.generators();
+            generators.add_generator_with_subcategory(
+                &GeneratorCategory::QUERY,
+                path,
+                Generator::ProviderStateGenerator(expression, Some(DataType::STRING)),
+            )
+        }
+        self
+    }
+
     /// Build the specified `Request` object.
     pub fn build(&self) -> Request {
          self.request.as_v3_request()
@@ -358,6 +393,31 @@ fn query_param_pattern() {
     assert_requests_do_not_match!(bad, pattern);
 }
 
+#[test]
+fn query_param_generator() {
+    let actual = PactBuilder::new("C", "P")
+      .interaction("I", "", |mut i| {
+          i.request.query_param_from_provider_state("id", "${id}", "100");
+          i
+      })
+      .build();
+
+    let expected = PactBuilder::new("C", "P")
+      .interaction("I", "", |mut i| {
+          i.request.query_param("id", "1000");
+          i
+      })
+      .build();
+
+    let good_context = &mut HashMap::new();
+    good_context.insert("id", json!(1000));
+    assert_requests_with_context_match!(actual, expected, good_context);
+
+    let bad_context = &mut HashMap::new();
+    bad_context.insert("id", json!(2000));
+    assert_requests_with_context_do_not_match!(actual, expected, bad_context);
+}
+
 #[test]
 fn query_param_with_underscore() {
     let pattern = PactBuilder::new("C", "P")
@@ -6,6 +6,7 @@ use pact_models::bodies::OptionalBody;
 use pact_models::generators::Generators;
 #[cfg(feature = "plugins")] use pact_models::http_parts::HttpPart;
 use pact_models::matchingrules::MatchingRules;
+use pact_models::path_exp::DocPath;
 use pact_models::prelude::ContentType;
 use pact_models::response::Response;
 use pact_models::v4::http_parts::{body_from_json, HttpResponse};
@@ -77,6 +78,39 @@ impl ResponseBuilder {
         self.status(404)
     }
 
+    /// Specify a response trailer, which is sent after the body (HTTP/2 only). The value can be a
+    /// literal or a pattern. Trailers are only included in V4 pacts.
+    ///
+    /// ```
+    /// use pact_consumer::prelude::*;
+    /// use pact_consumer::*;
+    /// use pact_consumer::builders::ResponseBuilder;
+    /// use regex::Regex;
+    ///
+    /// let response = ResponseBuilder::default()
+    ///     .trailer("grpc-status", term!("^[0-9]+$", "0"))
+    ///     .build_v4();
+    /// assert_eq!(response.trailers.unwrap()["grpc-status"], vec!["0".to_string()]);
+    /// ```
+    pub fn trailer<N, V>(&mut self, name: N, value: V) -> &mut Self
+    where
+        N: Into<String>,
+        V: Into<StringPattern>,
+    {
+        let name = name.into();
+        let value = value.into();
+        let trailers = self.response.trailers.get_or_insert(hashmap!{});
+        match trailers.keys().find(|k| k.eq_ignore_ascii_case(&name)).cloned() {
+          Some(key) => trailers.entry(key).or_default().push(value.to_example()),
+          None => {
+            trailers.insert(name.clone(), vec![value.to_example()]);
+          }
+        }
+        let path = DocPath::root().join(name);
+        value.extract_matching_rules(path, self.response.matching_rules.add_category("trailer"));
+        self
+    }
+
     /// Build the specified `Response` object.
     pub fn build(&self) -> Response {
         self.response.as_v3_response()
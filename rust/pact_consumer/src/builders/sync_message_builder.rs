@@ -16,12 +16,18 @@ use pact_models::generators::Generators;
 use pact_models::json_utils::json_to_string;
 use pact_models::path_exp::DocPath;
 #[cfg(feature = "plugins")] use pact_models::plugins::PluginData;
-use pact_models::prelude::{MatchingRuleCategory, MatchingRules, OptionalBody, ProviderState};
+use pact_models::prelude::{MatchingRuleCategory, OptionalBody, ProviderState};
 use pact_models::v4::interaction::InteractionMarkup;
 use pact_models::v4::message_parts::MessageContents;
 use pact_models::v4::sync_message::SynchronousMessage;
 
-use crate::builders::message_builder::{InteractionContents, PluginConfiguration};
+use crate::builders::message_builder::{
+  add_metadata,
+  core_interaction_contents,
+  InteractionContents,
+  message_matching_rules,
+  PluginConfiguration
+};
 #[cfg(not(feature = "plugins"))] use crate::builders::message_builder::PactPluginManifest;
 use crate::prelude::{JsonPattern, Pattern};
 #[cfg(feature = "plugins")] use crate::prelude::PluginInteractionBuilder;
@@ -40,7 +46,7 @@ pub struct SyncMessageInteractionBuilder {
   /// Response contents of the message. This will include the payloads as well as any metadata
   pub response_contents: Vec<InteractionContents>,
   #[allow(dead_code)] contents_plugin: Option<PactPluginManifest>,
-  #[allow(dead_code)] plugin_config: HashMap<String, PluginConfiguration>
+  plugin_config: HashMap<String, PluginConfiguration>
 }
 
 impl SyncMessageInteractionBuilder {
@@ -117,12 +123,37 @@ impl SyncMessageInteractionBuilder {
     self
   }
 
+  /// Adds a configuration block for a plugin to this interaction. This will be persisted in the
+  /// plugin configuration of the interaction in the Pact file, and allows plugin specific
+  /// configuration to be provided without needing to load the plugin.
+  pub fn with_plugin_config<S: Into<String>>(
+    &mut self,
+    plugin_name: S,
+    config: HashMap<String, Value>
+  ) -> &mut Self {
+    self.plugin_config.entry(plugin_name.into()).or_default()
+      .interaction_configuration.extend(config);
+    self
+  }
+
   /// Adds a key/value pair to the message request metadata. The key can be anything that is
-  /// convertible into a string, and the value must be conveyable into a JSON value.
+  /// convertible into a string, and the value must be conveyable into a JSON value. The value can
+  /// be a matching rule definition expression (i.e. `matching(regex, '\d+', '100')`), in which
+  /// case the example value will be used and the matching rules and generators added.
   pub fn request_metadata<S: Into<String>, J: Into<Value>>(&mut self, key: S, value: J) -> &mut Self {
-    let metadata = self.request_contents.metadata
-      .get_or_insert_with(|| hashmap!{});
-    metadata.insert(key.into(), value.into());
+    add_metadata(&mut self.request_contents, key.into(), value.into());
+    self
+  }
+
+  /// Adds a key/value pair to the metadata of the last response message added to the
+  /// interaction. The value can be a matching rule definition expression, the same as with
+  /// `request_metadata`.
+  ///
+  /// Panics if no response message has been added yet.
+  pub fn response_metadata<S: Into<String>, J: Into<Value>>(&mut self, key: S, value: J) -> &mut Self {
+    let response = self.response_contents.last_mut()
+      .expect("response_metadata must be called after a response message has been added");
+    add_metadata(response, key.into(), value.into());
     self
   }
 
@@ -130,12 +161,6 @@ impl SyncMessageInteractionBuilder {
   pub fn build(&self) -> SynchronousMessage {
     debug!("Building V4 SynchronousMessages interaction: {:?}", self);
 
-    let mut rules = MatchingRules::default();
-    rules.add_category("body")
-      .add_rules(self.request_contents.rules.as_ref().cloned().unwrap_or_default());
-    rules.add_category("metadata")
-      .add_rules(self.request_contents.metadata_rules.as_ref().cloned().unwrap_or_default());
-
     #[allow(unused_mut, unused_assignments)] let mut plugin_config = hashmap!{};
     #[cfg(feature = "plugins")]
     {
@@ -146,6 +171,13 @@ impl SyncMessageInteractionBuilder {
       }).unwrap_or_default();
     }
 
+    for (plugin_name, config) in &self.plugin_config {
+      if !config.interaction_configuration.is_empty() {
+        plugin_config.entry(plugin_name.clone()).or_insert_with(HashMap::new)
+          .extend(config.interaction_configuration.clone());
+      }
+    }
+
     #[allow(unused_mut, unused_assignments)] let mut interaction_markup = InteractionMarkup::default();
     #[cfg(feature = "plugins")]
     {
@@ -163,20 +195,15 @@ impl SyncMessageInteractionBuilder {
       request: MessageContents {
         contents: self.request_contents.body.clone(),
         metadata: self.request_contents.metadata.as_ref().cloned().unwrap_or_default(),
-        matching_rules: rules,
+        matching_rules: message_matching_rules(&self.request_contents),
         generators: self.request_contents.generators.as_ref().cloned().unwrap_or_default()
       },
       response: self.response_contents.iter().map(|contents| {
-        let mut rules = MatchingRules::default();
-        rules.add_category("body")
-          .add_rules(contents.rules.as_ref().cloned().unwrap_or_default());
-        rules.add_category("metadata")
-          .add_rules(contents.metadata_rules.as_ref().cloned().unwrap_or_default());
         MessageContents {
           contents: contents.body.clone(),
           metadata: contents.metadata.as_ref().cloned().unwrap_or_default(),
-          matching_rules: rules,
-          generators: self.request_contents.generators.as_ref().cloned().unwrap_or_default()
+          matching_rules: message_matching_rules(contents),
+          generators: contents.generators.as_ref().cloned().unwrap_or_default()
         }
       }).collect(),
       comments: hashmap!{
@@ -186,6 +213,7 @@ impl SyncMessageInteractionBuilder {
       pending: self.pending.unwrap_or(false),
       plugin_config,
       interaction_markup,
+      config: Default::default(),
       transport: None
     }
   }
@@ -294,42 +322,7 @@ impl SyncMessageInteractionBuilder {
     config: &HashMap<String, Value>,
     content_matcher: Option<ContentMatcher>
   ) {
-    if let Some(request) = config.get("request") {
-      let mut body = OptionalBody::from(request);
-      if let Some(ct) = content_type.as_ref() {
-        body.set_content_type(ct);
-      }
-
-      self.request_contents = InteractionContents {
-        body, .. InteractionContents::default()
-      };
-    }
-    if let Some(responses) = config.get("response") {
-      match responses {
-        Value::Array(responses) => {
-          for response in responses {
-            let mut body = OptionalBody::from(response);
-            if let Some(ct) = content_type.as_ref() {
-              body.set_content_type(ct);
-            }
-
-            self.response_contents.push(InteractionContents {
-              body, .. InteractionContents::default()
-            });
-          }
-        }
-        _ => {
-          let mut body = OptionalBody::from(responses);
-          if let Some(ct) = content_type.as_ref() {
-            body.set_content_type(ct);
-          }
-
-          self.response_contents.push(InteractionContents {
-            body, .. InteractionContents::default()
-          });
-        }
-      }
-    }
+    self.setup_core_contents(content_type.as_ref(), config);
 
     if let Some(_content_matcher) = content_matcher {
       // TODO: get the content matcher to apply the matching rules and generators
@@ -348,40 +341,21 @@ impl SyncMessageInteractionBuilder {
     content_type: Option<ContentType>,
     config: &HashMap<String, Value>
   ) {
-    if let Some(request) = config.get("request") {
-      let mut body = OptionalBody::from(request);
-      if let Some(ct) = content_type.as_ref() {
-        body.set_content_type(ct);
-      }
+    self.setup_core_contents(content_type.as_ref(), config);
+  }
 
-      self.request_contents = InteractionContents {
-        body, .. InteractionContents::default()
-      };
+  fn setup_core_contents(&mut self, content_type: Option<&ContentType>, config: &HashMap<String, Value>) {
+    if let Some(request) = config.get("request") {
+      self.request_contents = core_interaction_contents("request", request, content_type);
     }
     if let Some(responses) = config.get("response") {
       match responses {
         Value::Array(responses) => {
           for response in responses {
-            let mut body = OptionalBody::from(response);
-            if let Some(ct) = content_type.as_ref() {
-              body.set_content_type(ct);
-            }
-
-            self.response_contents.push(InteractionContents {
-              body, .. InteractionContents::default()
-            });
+            self.response_contents.push(core_interaction_contents("response", response, content_type));
           }
         }
-        _ => {
-          let mut body = OptionalBody::from(responses);
-          if let Some(ct) = content_type.as_ref() {
-            body.set_content_type(ct);
-          }
-
-          self.response_contents.push(InteractionContents {
-            body, .. InteractionContents::default()
-          });
-        }
+        _ => self.response_contents.push(core_interaction_contents("response", responses, content_type))
       }
     }
   }
@@ -513,7 +487,7 @@ impl SyncMessageInteractionBuilder {
     );
     let mut metadata = hashmap!{};
     if let Some(content_type) = content_type {
-      metadata.insert(" contentType".to_string(), Value::String(content_type.clone()));
+      metadata.insert("contentType".to_string(), Value::String(content_type.clone()));
     }
     let response = InteractionContents {
       part_name: "response".to_string(),
@@ -527,7 +501,7 @@ impl SyncMessageInteractionBuilder {
       interaction_markup_type: "".to_string(),
     };
 
-        self.response_contents.push(response);
+    self.response_contents.push(response);
     self
   }
 
@@ -557,12 +531,15 @@ mod tests {
   use maplit::hashmap;
   use serde_json::json;
 
+  use pact_models::generators;
+  use pact_models::generators::Generator;
   use pact_models::matchingrules;
   use pact_models::matchingrules::{Category, MatchingRule, MatchingRules, RuleLogic};
+  use pact_models::PactSpecification;
   use pact_models::path_exp::DocPath;
   use pact_models::v4::message_parts::MessageContents;
 
-  use crate::builders::SyncMessageInteractionBuilder;
+  use crate::builders::{PactBuilder, SyncMessageInteractionBuilder};
 
   #[test]
   fn supports_setting_metadata_values() {
@@ -694,4 +671,117 @@ mod tests {
     expect!(message.response.len()).to(be_equal_to(1));
     expect!(message.response[0].clone().matching_rules).to(be_equal_to(rules.clone()));
   }
+
+  #[tokio::test]
+  async fn contents_from_supports_matching_rule_expressions() {
+    let message = SyncMessageInteractionBuilder::new("test")
+      .contents_from(json!({
+        "pact:content-type": "application/json",
+        "request": { "id": "matching(integer, 100)" },
+        "response": [
+          { "name": "matching(type, 'Fred')" },
+          { "name": "matching(regex, '\\w+', 'Mary')" }
+        ]
+      }))
+      .await
+      .build();
+
+    expect!(message.request.contents.value_as_string()).to(be_some().value("{\"id\":100}"));
+    expect!(message.request.metadata.get("contentType").cloned()).to(be_some().value(json!("application/json")));
+    expect!(message.request.matching_rules).to(be_equal_to(matchingrules! {
+      "body" => { "$.id" => [ MatchingRule::Integer ] }
+    }));
+    expect!(message.response.len()).to(be_equal_to(2));
+    expect!(message.response[0].contents.value_as_string()).to(be_some().value("{\"name\":\"Fred\"}"));
+    expect!(message.response[0].matching_rules.clone()).to(be_equal_to(matchingrules! {
+      "body" => { "$.name" => [ MatchingRule::Type ] }
+    }));
+    expect!(message.response[1].contents.value_as_string()).to(be_some().value("{\"name\":\"Mary\"}"));
+    expect!(message.response[1].matching_rules.clone()).to(be_equal_to(matchingrules! {
+      "body" => { "$.name" => [ MatchingRule::Regex("\\w+".to_string()) ] }
+    }));
+  }
+
+  #[test]
+  fn supports_matching_rule_expressions_for_metadata_values() {
+    let message = SyncMessageInteractionBuilder::new("test")
+      .request_metadata("id", "matching(regex, '\\d+', '100')")
+      .response_json_body(json!({ "a": 1 }))
+      .response_metadata("ts", "matching(datetime, 'yyyy-MM-dd', '2000-01-01')")
+      .response_json_body(json!({ "b": 2 }))
+      .build();
+
+    expect!(message.request.metadata.get("id").cloned()).to(be_some().value(json!("100")));
+    expect!(message.request.matching_rules).to(be_equal_to(matchingrules! {
+      "metadata" => { "id" => [ MatchingRule::Regex("\\d+".to_string()) ] }
+    }));
+    expect!(message.request.generators.is_empty()).to(be_true());
+
+    expect!(message.response[0].metadata.get("ts").cloned()).to(be_some().value(json!("2000-01-01")));
+    expect!(message.response[0].matching_rules.clone()).to(be_equal_to(matchingrules! {
+      "metadata" => { "ts" => [ MatchingRule::Timestamp("yyyy-MM-dd".to_string()) ] }
+    }));
+    expect!(message.response[0].generators.clone()).to(be_equal_to(generators! {
+      "METADATA" => { "ts" => Generator::DateTime(Some("yyyy-MM-dd".to_string()), None) }
+    }));
+    expect!(message.response[1].metadata.is_empty()).to(be_true());
+    expect!(message.response[1].generators.is_empty()).to(be_true());
+  }
+
+  #[test]
+  #[should_panic]
+  fn response_metadata_requires_a_response() {
+    SyncMessageInteractionBuilder::new("test").response_metadata("a", "b");
+  }
+
+  #[test]
+  fn response_body_sets_the_content_type_metadata() {
+    let message = SyncMessageInteractionBuilder::new("test")
+      .response_body("hello", Some("text/plain".to_string()))
+      .build();
+    expect!(message.response[0].metadata.get("contentType").cloned()).to(be_some().value(json!("text/plain")));
+  }
+
+  #[test]
+  fn produces_v4_synchronous_message_pact_json() {
+    let pact = PactBuilder::new_v4("consumer", "provider")
+      .synchronous_message_interaction("a sync message", |mut i| {
+        i.given("a user exists")
+          .request_json_body(json!({ "id": 100 }))
+          .request_metadata("id", "matching(regex, '\\d+', '100')")
+          .response_json_body(crate::json_pattern!({ "name": crate::like!("Fred") }));
+        i
+      })
+      .build();
+
+    let json = pact.to_json(PactSpecification::V4).unwrap();
+    let interaction = &json["interactions"][0];
+    expect!(interaction["type"].clone()).to(be_equal_to(json!("Synchronous/Messages")));
+    expect!(interaction["description"].clone()).to(be_equal_to(json!("a sync message")));
+    expect!(interaction["request"].clone()).to(be_equal_to(json!({
+      "contents": {
+        "content": { "id": 100 },
+        "contentType": "application/json",
+        "encoded": false
+      },
+      "matchingRules": {
+        "metadata": {
+          "id": { "combine": "AND", "matchers": [ { "match": "regex", "regex": "\\d+" } ] }
+        }
+      },
+      "metadata": { "id": "100" }
+    })));
+    expect!(interaction["response"].clone()).to(be_equal_to(json!([{
+      "contents": {
+        "content": { "name": "Fred" },
+        "contentType": "application/json",
+        "encoded": false
+      },
+      "matchingRules": {
+        "body": {
+          "$.name": { "combine": "AND", "matchers": [ { "match": "type" } ] }
+        }
+      }
+    }])));
+  }
 }
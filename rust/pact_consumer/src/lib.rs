@@ -149,6 +149,27 @@
 //! fixtures. This can be done using the string passed to `given` in the
 //! examples above.
 //!
+//! Matching rule definition expressions, as used by the other Pact language implementations
+//! (i.e. `matching(type, 'Fred')`), can also be used with the `matching_expression!` macro. This
+//! works on any node of a JSON pattern, as well as any string pattern (headers, paths, etc.):
+//!
+//! ```
+//! use pact_consumer::prelude::*;
+//! use pact_consumer::*;
+//!
+//! PactBuilder::new_v4("quotes client", "quotes service")
+//!     .interaction("fetch a quote", "", |mut i| {
+//!         i.request.path(matching_expression!("matching(regex, '^/quotes/\\d+$', '/quotes/12')"));
+//!         i.response
+//!             .json_utf8()
+//!             .json_body(json_pattern!({
+//!                  "id": matching_expression!("matching(integer, 12)"),
+//!                  "quote": matching_expression!("notEmpty('Eureka!')")
+//!              }));
+//!         i
+//!     });
+//! ```
+//!
 //! ## Testing using domain objects
 //!
 //! Normally, it's best to generate your JSON using your actual domain objects.
@@ -245,6 +266,38 @@
 //! }
 //! ```
 //!
+//! #### Verifying messages with a message handler
+//!
+//! Instead of iterating over the messages, the `verify_messages` function on the Pact builder can be given a closure that
+//! invokes the message handler with each message. The messages will have any generators applied to the contents and
+//! metadata, and the Pact file is only written if the handler was able to process all the messages. Otherwise, an error is
+//! returned with the messages that could not be processed. `PactBuilderAsync` has an async version, which takes a closure
+//! that returns a future.
+//!
+//! ```rust
+//! use pact_consumer::prelude::*;
+//! use pact_consumer::*;
+//! use expectest::prelude::*;
+//!
+//! let mut pact_builder = PactBuilder::new_v4("message-consumer", "message-provider");
+//! pact_builder
+//!   .message_interaction("Mallory Message", |mut i| {
+//!     i.metadata("destination", "mallory-queue");
+//!     i.json_body(json_pattern!({
+//!       "mallory": like!("That is some good Mallory.")
+//!     }));
+//!     i
+//!   });
+//!
+//! // The handler is invoked with each message as it would come off the queue
+//! let result = pact_builder.verify_messages(|message| {
+//!   let json = message.json()?;
+//!   expect!(json["mallory"].as_str()).to(be_some().value("That is some good Mallory."));
+//!   Ok(())
+//! });
+//! assert!(result.is_ok());
+//! ```
+//!
 //! ### Synchronous request/response messages
 //!
 //! Synchronous request/response messages are a form of message interchange were a request message is sent to another service and
@@ -393,15 +446,19 @@ pub mod util;
 /// ```
 pub mod prelude {
     pub use crate::builders::{HttpPartBuilder, PactBuilder, PactBuilderAsync};
+    pub use crate::builders::{MessageHandlerFailure, MessageVerificationError, ReifiedMessage};
     #[cfg(feature = "plugins")] pub use crate::builders::plugin_builder::PluginInteractionBuilder;
     pub use crate::mock_server::{StartMockServer, ValidatingMockServer};
+    #[cfg(feature = "test-client")] pub use crate::mock_server::test_client::{TestClient, TestResponse};
     pub use crate::patterns::{
         EachLike,
+        IgnoreOrder,
         Like,
         Term,
         ObjectMatching,
         EachKey,
         EachValue,
+        MatchingExpression,
         JsonPattern,
         Pattern,
         StringPattern,
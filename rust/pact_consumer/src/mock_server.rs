@@ -12,6 +12,7 @@ use crate::mock_server::http_mock_server::ValidatingHttpMockServer;
 
 pub(crate) mod http_mock_server;
 #[cfg(feature = "plugins")] pub(crate) mod plugin_mock_server;
+#[cfg(feature = "test-client")] pub mod test_client;
 
 /// A mock server that handles the requests described in a `Pact`, intended
 /// for use in tests, and validates that the requests made to that server are
@@ -36,6 +37,13 @@ pub trait ValidatingMockServer {
 
   /// Returns the metrics collected by the mock server
   fn metrics(&self) -> MockServerMetrics;
+
+  /// Returns a simple HTTP client for making requests to the mock server (if it is a HTTP-based
+  /// mock server). This requires the `test-client` feature.
+  #[cfg(feature = "test-client")]
+  fn client(&self) -> test_client::TestClient {
+    test_client::TestClient::new(self.url())
+  }
 }
 
 /// This trait is implemented by types which allow us to start a mock server.
@@ -5,11 +5,11 @@ use std::fmt::Write;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
-use pact_models::pact::Pact;
+use pact_models::pact::{Pact, write_pact_with_options, WritePactOptions};
 #[cfg(feature = "plugins")] use pact_models::plugins::PluginData;
 #[cfg(feature = "plugins")] use pact_plugin_driver::plugin_manager::{drop_plugin_access, increment_plugin_access};
 #[cfg(feature = "plugins")] use pact_plugin_driver::plugin_models::{PluginDependency, PluginDependencyType};
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 use url::Url;
 use uuid::Uuid;
 
@@ -39,7 +39,9 @@ pub struct ValidatingHttpMockServer {
   // Output directory to write pact files
   output_dir: Option<PathBuf>,
   // overwrite or merge Pact files
-  overwrite: bool
+  overwrite: bool,
+  // Options for the order and formatting of the pact file
+  write_options: WritePactOptions
 }
 
 impl ValidatingHttpMockServer {
@@ -49,6 +51,36 @@ impl ValidatingHttpMockServer {
   /// Panics:
   /// Will panic if the provided Pact can not be sent to the background thread.
   pub fn start(pact: Box<dyn Pact + Send + Sync>, output_dir: Option<PathBuf>) -> Box<dyn ValidatingMockServer> {
+    Self::start_with_write_options(pact, output_dir, WritePactOptions::default())
+  }
+
+  /// Create a new mock server which handles requests as described in the
+  /// pact, and runs in a background thread. The pact file will be written using the provided
+  /// options.
+  ///
+  /// Panics:
+  /// Will panic if the provided Pact can not be sent to the background thread.
+  pub fn start_with_write_options(
+    pact: Box<dyn Pact + Send + Sync>,
+    output_dir: Option<PathBuf>,
+    write_options: WritePactOptions
+  ) -> Box<dyn ValidatingMockServer> {
+    Self::start_with_config(pact, output_dir, write_options, MockServerConfig::default())
+  }
+
+  /// Create a new mock server which handles requests as described in the
+  /// pact, and runs in a background thread. The mock server will be started with the provided
+  /// config (i.e. to automatically respond to CORS preflight requests), and the pact file will
+  /// be written using the provided options.
+  ///
+  /// Panics:
+  /// Will panic if the provided Pact can not be sent to the background thread.
+  pub fn start_with_config(
+    pact: Box<dyn Pact + Send + Sync>,
+    output_dir: Option<PathBuf>,
+    write_options: WritePactOptions,
+    config: MockServerConfig
+  ) -> Box<dyn ValidatingMockServer> {
     debug!("Starting mock server from pact {:?}", pact);
 
     #[allow(unused_variables)] let plugin_data = pact.plugin_data();
@@ -57,7 +89,7 @@ impl ValidatingHttpMockServer {
     // Spawn new runtime in thread to prevent reactor execution context conflict
     let (pact_tx, pact_rx) = std::sync::mpsc::channel::<Box<dyn Pact + Send + Sync>>();
     pact_tx.send(pact).expect("INTERNAL ERROR: Could not pass pact into mock server thread");
-    let (mock_server, done_rx) = std::thread::spawn(|| {
+    let (mock_server, done_rx) = std::thread::spawn(move || {
       let runtime = tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
@@ -68,7 +100,7 @@ impl ValidatingHttpMockServer {
           Uuid::new_v4().to_string(),
           pact_rx.recv().unwrap(),
           ([0, 0, 0, 0], 0).into(),
-          MockServerConfig::default()
+          config
         )
           .await
           .unwrap()
@@ -108,7 +140,8 @@ impl ValidatingHttpMockServer {
       mock_server,
       done_rx,
       output_dir,
-      overwrite: false
+      overwrite: false,
+      write_options
     })
   }
 
@@ -142,6 +175,36 @@ impl ValidatingHttpMockServer {
   /// Panics:
   /// Will panic if unable to get the URL to the spawned mock server
   pub async fn start_async(pact: Box<dyn Pact + Send + Sync>, output_dir: Option<PathBuf>) -> Box<dyn ValidatingMockServer> {
+    Self::start_async_with_write_options(pact, output_dir, WritePactOptions::default()).await
+  }
+
+  /// Create a new mock server which handles requests as described in the
+  /// pact, and runs in a background task in the current Tokio runtime. The pact file will be
+  /// written using the provided options.
+  ///
+  /// Panics:
+  /// Will panic if unable to get the URL to the spawned mock server
+  pub async fn start_async_with_write_options(
+    pact: Box<dyn Pact + Send + Sync>,
+    output_dir: Option<PathBuf>,
+    write_options: WritePactOptions
+  ) -> Box<dyn ValidatingMockServer> {
+    Self::start_async_with_config(pact, output_dir, write_options, MockServerConfig::default()).await
+  }
+
+  /// Create a new mock server which handles requests as described in the
+  /// pact, and runs in a background task in the current Tokio runtime. The mock server will be
+  /// started with the provided config, and the pact file will be written using the provided
+  /// options.
+  ///
+  /// Panics:
+  /// Will panic if unable to get the URL to the spawned mock server
+  pub async fn start_async_with_config(
+    pact: Box<dyn Pact + Send + Sync>,
+    output_dir: Option<PathBuf>,
+    write_options: WritePactOptions,
+    config: MockServerConfig
+  ) -> Box<dyn ValidatingMockServer> {
     debug!("Starting mock server from pact {:?}", pact);
 
     #[allow(unused_variables)] let plugin_data = pact.plugin_data();
@@ -151,7 +214,7 @@ impl ValidatingHttpMockServer {
       Uuid::new_v4().to_string(),
       pact,
       ([0, 0, 0, 0], 0 as u16).into(),
-      MockServerConfig::default()
+      config
     )
       .await
       .unwrap();
@@ -177,7 +240,8 @@ impl ValidatingHttpMockServer {
       mock_server,
       done_rx,
       output_dir,
-      overwrite: false
+      overwrite: false,
+      write_options
     })
   }
 
@@ -232,8 +296,17 @@ impl ValidatingHttpMockServer {
         })
         .ok()
         .unwrap_or(self.overwrite);
-      ms.write_pact(&Some(output_dir), overwrite)
-        .map_err(|err| format!("error writing pact: {}", err))?;
+      if self.write_options == WritePactOptions::default() {
+        ms.write_pact(&Some(output_dir), overwrite)
+          .map_err(|err| format!("error writing pact: {}", err))?;
+      } else {
+        let mut path = PathBuf::from(output_dir);
+        path.push(ms.pact.default_file_name());
+        info!("Writing pact out to '{}'", path.display());
+        let specification = ms.pact.specification_version();
+        write_pact_with_options(ms.pact.boxed(), path.as_path(), specification, overwrite, &self.write_options)
+          .map_err(|err| format!("error writing pact: {}", err))?;
+      }
       Ok(())
     } else {
       // Failure. Format our errors.
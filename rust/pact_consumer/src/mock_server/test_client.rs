@@ -0,0 +1,166 @@
+//! Lightweight HTTP client for making requests against a mock server in examples and smoke
+//! tests. This requires the `test-client` feature.
+//!
+//! ```no_run
+//! use pact_consumer::prelude::*;
+//! use pact_consumer::*;
+//! use serde_json::json;
+//!
+//! # tokio_test::block_on(async {
+//! let mock_server = PactBuilder::new_v4("Consumer", "Provider")
+//!   .interaction("create an order", "", |mut i| {
+//!     i.request.post().path("/orders").json_body(json_pattern!({ "id": 1 }));
+//!     i.response.created();
+//!     i
+//!   })
+//!   .start_mock_server(None);
+//!
+//! let response = mock_server.client().post_json("/orders", &json!({ "id": 1 })).await.unwrap();
+//! assert_eq!(response.status, 201);
+//! # });
+//! ```
+
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use bytes::Bytes;
+use reqwest::Method;
+use serde_json::Value;
+use url::Url;
+
+/// Simple HTTP client for a mock server. Request paths are resolved against the base URL of the
+/// mock server.
+#[derive(Debug, Clone)]
+pub struct TestClient {
+  base_url: Url,
+  headers: Vec<(String, String)>,
+  client: reqwest::Client
+}
+
+/// Response returned from a request made with the `TestClient`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResponse {
+  /// Status code of the response
+  pub status: u16,
+  /// Response headers. Header names are in lower case.
+  pub headers: HashMap<String, Vec<String>>,
+  /// Body of the response
+  pub body: Bytes
+}
+
+impl TestClient {
+  /// Creates a client for the mock server with the given base URL
+  pub fn new(base_url: Url) -> Self {
+    TestClient {
+      base_url,
+      headers: vec![],
+      client: reqwest::Client::new()
+    }
+  }
+
+  /// Adds a header to be sent with every request from this client
+  pub fn with_header<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> Self {
+    self.headers.push((name.into(), value.into()));
+    self
+  }
+
+  /// Makes a GET request to the path
+  pub async fn get(&self, path: &str) -> anyhow::Result<TestResponse> {
+    self.request(Method::GET, path, None, None).await
+  }
+
+  /// Makes a DELETE request to the path
+  pub async fn delete(&self, path: &str) -> anyhow::Result<TestResponse> {
+    self.request(Method::DELETE, path, None, None).await
+  }
+
+  /// Makes a POST request to the path with the JSON as the body
+  pub async fn post_json(&self, path: &str, body: &Value) -> anyhow::Result<TestResponse> {
+    self.request(Method::POST, path, Some("application/json"), Some(Bytes::from(body.to_string()))).await
+  }
+
+  /// Makes a PUT request to the path with the JSON as the body
+  pub async fn put_json(&self, path: &str, body: &Value) -> anyhow::Result<TestResponse> {
+    self.request(Method::PUT, path, Some("application/json"), Some(Bytes::from(body.to_string()))).await
+  }
+
+  /// Makes a request to the path with the given method, and optional content type and body
+  pub async fn request(
+    &self,
+    method: Method,
+    path: &str,
+    content_type: Option<&str>,
+    body: Option<Bytes>
+  ) -> anyhow::Result<TestResponse> {
+    let url = self.base_url.join(path)
+      .map_err(|err| anyhow!("'{}' is not a valid path - {}", path, err))?;
+    let mut request = self.client.request(method, url);
+    for (name, value) in &self.headers {
+      request = request.header(name.as_str(), value.as_str());
+    }
+    if let Some(content_type) = content_type {
+      request = request.header("Content-Type", content_type);
+    }
+    if let Some(body) = body {
+      request = request.body(body);
+    }
+
+    let response = request.send().await?;
+    let status = response.status().as_u16();
+    let mut headers: HashMap<String, Vec<String>> = HashMap::new();
+    for (name, value) in response.headers() {
+      headers.entry(name.as_str().to_string())
+        .or_default()
+        .push(String::from_utf8_lossy(value.as_bytes()).to_string());
+    }
+    let body = response.bytes().await?;
+    Ok(TestResponse { status, headers, body })
+  }
+}
+
+impl TestResponse {
+  /// Returns the first value of the header with the given name (case-insensitive)
+  pub fn header(&self, name: &str) -> Option<&str> {
+    self.headers.get(&name.to_lowercase())
+      .and_then(|values| values.first())
+      .map(|value| value.as_str())
+  }
+
+  /// Returns the body as a UTF-8 string
+  pub fn text(&self) -> anyhow::Result<String> {
+    String::from_utf8(self.body.to_vec()).map_err(|err| anyhow!("Body is not valid UTF-8 - {}", err))
+  }
+
+  /// Parses the body as JSON
+  pub fn json(&self) -> anyhow::Result<Value> {
+    serde_json::from_slice(&self.body).map_err(|err| anyhow!("Body is not valid JSON - {}", err))
+  }
+
+  /// If the status code is in the 2xx range
+  pub fn is_success(&self) -> bool {
+    (200..300).contains(&self.status)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+  use maplit::hashmap;
+  use serde_json::json;
+
+  use super::TestResponse;
+
+  #[test]
+  fn test_response_accessors() {
+    let response = TestResponse {
+      status: 201,
+      headers: hashmap!{ "content-type".to_string() => vec!["application/json".to_string()] },
+      body: r#"{"id":1}"#.into()
+    };
+    expect!(response.is_success()).to(be_true());
+    expect!(response.header("Content-Type")).to(be_some().value("application/json"));
+    expect!(response.header("Location")).to(be_none());
+    expect!(response.text().unwrap()).to(be_equal_to(r#"{"id":1}"#));
+    expect!(response.json().unwrap()).to(be_equal_to(json!({ "id": 1 })));
+  }
+}
@@ -5,10 +5,13 @@ use std::marker::PhantomData;
 use itertools::{Either, Itertools};
 
 use pact_models::matchingrules::{MatchingRule, MatchingRuleCategory, RuleLogic};
-use pact_models::matchingrules::expressions::{MatchingRuleDefinition, ValueType};
+use pact_models::matchingrules::expressions::{MatchingRuleDefinition, parse_matcher_def, ValueType};
 use pact_models::path_exp::DocPath;
 use regex::Regex;
 use serde_json::Value;
+use tracing::warn;
+
+use crate::builders::expressions::example_value;
 
 use super::json_pattern::JsonPattern;
 use super::Pattern;
@@ -103,6 +106,74 @@ macro_rules! like {
     }
 }
 
+/// Match an array that contains the same elements as the example, but in any order.
+#[derive(Debug)]
+pub struct IgnoreOrder {
+    example: JsonPattern,
+}
+
+impl IgnoreOrder {
+    /// Match all arrays with the same elements as `example`, in any order.
+    pub fn new<E: Into<JsonPattern>>(example: E) -> Self {
+        IgnoreOrder { example: example.into() }
+    }
+}
+
+impl Pattern for IgnoreOrder {
+    type Matches = Value;
+
+    fn to_example(&self) -> Self::Matches {
+        self.example.to_example()
+    }
+
+    fn to_example_bytes(&self) -> Vec<u8> {
+        self.example.to_example_bytes()
+    }
+
+    fn extract_matching_rules(&self, path: DocPath, rules_out: &mut MatchingRuleCategory) {
+        rules_out.add_rule(path.clone(), MatchingRule::EqualsIgnoreOrder, RuleLogic::And);
+        self.example.extract_matching_rules(path, rules_out);
+    }
+}
+
+impl_from_for_pattern!(IgnoreOrder, JsonPattern);
+
+#[test]
+fn ignore_order_is_pattern() {
+    use maplit::*;
+    use pact_matching::s;
+    use serde_json::*;
+
+    let matchable = IgnoreOrder::new(json_pattern!([1, like!(2)]));
+    assert_eq!(matchable.to_example(), json!([1, 2]));
+    let mut rules = MatchingRuleCategory::empty("body");
+    matchable.extract_matching_rules(DocPath::root(), &mut rules);
+    assert_eq!(rules.to_v2_json(), hashmap!(
+        s!("$.body") => json!({"match": "ignore-order"}),
+        s!("$.body[1]") => json!({"match": "type"})
+    ));
+}
+
+/// Generates the specified array, and matches any array with the same elements in any order.
+/// This is intended for use inside `json_pattern!`, and it interprets its arguments as a
+/// `json_pattern!`.
+///
+/// ```
+/// use pact_consumer::*;
+///
+/// # fn main() {
+/// json_pattern!({
+///   "tags": ignore_order!(["red", "green", "blue"]),
+/// });
+/// # }
+/// ```
+#[macro_export]
+macro_rules! ignore_order {
+    ($($json_pattern:tt)+) => {
+        $crate::patterns::IgnoreOrder::new(json_pattern!($($json_pattern)+))
+    }
+}
+
 /// Match an array with the specified "shape".
 #[derive(Debug)]
 pub struct EachLike {
@@ -412,6 +483,156 @@ macro_rules! matching_regex {
     }
 }
 
+/// Match and generate values using a matching rule definition expression (i.e.
+/// `matching(type, 'Fred')`). The example value is taken from the expression. Note that any
+/// generator in the expression is ignored, as patterns do not support generators.
+#[derive(Debug)]
+pub struct MatchingExpression<Nested: Pattern> {
+    /// The parsed matching rule definition.
+    definition: MatchingRuleDefinition,
+    /// We need to mention our `Nested` type somewhere, so use `PhantomData`.
+    phantom: PhantomData<Nested>,
+}
+
+impl<Nested: Pattern> MatchingExpression<Nested> {
+    /// Construct a new `MatchingExpression` from a parsed matching rule definition.
+    pub fn new(definition: MatchingRuleDefinition) -> Self {
+        MatchingExpression {
+            definition,
+            phantom: PhantomData,
+        }
+    }
+
+    fn add_rules(&self, path: DocPath, rules_out: &mut MatchingRuleCategory) {
+        for rule in &self.definition.rules {
+            match rule {
+                Either::Left(rule) => rules_out.add_rule(path.clone(), rule.clone(), RuleLogic::And),
+                Either::Right(reference) => warn!("Matching rule references are not supported in \
+                  patterns, ignoring the reference to '{}'", reference.name)
+            }
+        }
+    }
+}
+
+impl Pattern for MatchingExpression<JsonPattern> {
+    type Matches = Value;
+
+    fn to_example(&self) -> Self::Matches {
+        example_value(&self.definition)
+    }
+
+    fn to_example_bytes(&self) -> Vec<u8> {
+        self.to_example().to_string().into_bytes()
+    }
+
+    fn extract_matching_rules(&self, path: DocPath, rules_out: &mut MatchingRuleCategory) {
+        self.add_rules(path, rules_out);
+    }
+}
+
+impl Pattern for MatchingExpression<StringPattern> {
+    type Matches = String;
+
+    fn to_example(&self) -> Self::Matches {
+        self.definition.value.clone()
+    }
+
+    fn to_example_bytes(&self) -> Vec<u8> {
+        self.to_example().into_bytes()
+    }
+
+    fn extract_matching_rules(&self, path: DocPath, rules_out: &mut MatchingRuleCategory) {
+        self.add_rules(path, rules_out);
+    }
+}
+
+impl_from_for_pattern!(MatchingExpression<JsonPattern>, JsonPattern);
+impl_from_for_pattern!(MatchingExpression<StringPattern>, StringPattern);
+
+#[test]
+fn matching_expression_is_pattern() {
+    use expectest::prelude::*;
+    use pact_models::matchingrules_list;
+    use serde_json::json;
+
+    let matchable = MatchingExpression::<JsonPattern>::new(
+        parse_matching_expression("matching(integer, 100)"));
+    expect!(matchable.to_example()).to(be_equal_to(json!(100)));
+
+    let mut rules = MatchingRuleCategory::empty("body");
+    matchable.extract_matching_rules(DocPath::root(), &mut rules);
+    expect!(rules).to(be_equal_to(matchingrules_list! {
+      "body"; "$" => [ MatchingRule::Integer ]
+    }));
+
+    let matchable = MatchingExpression::<StringPattern>::new(
+        parse_matching_expression("matching(regex, '\\d+', '100')"));
+    expect!(matchable.to_example()).to(be_equal_to("100".to_string()));
+
+    let mut rules = MatchingRuleCategory::empty("header");
+    matchable.extract_matching_rules(DocPath::root(), &mut rules);
+    expect!(rules).to(be_equal_to(matchingrules_list! {
+      "header"; "$" => [ MatchingRule::Regex("\\d+".to_string()) ]
+    }));
+}
+
+#[test]
+#[should_panic]
+fn matching_expression_panics_with_an_invalid_expression() {
+    parse_matching_expression("matching(type,");
+}
+
+/// Internal helper function called by `matching_expression!` to parse a matching rule definition
+/// expression. Panics if the expression is invalid.
+#[doc(hidden)]
+pub fn parse_matching_expression<S: AsRef<str>>(expression: S) -> MatchingRuleDefinition {
+    let expression = expression.as_ref();
+    match parse_matcher_def(expression) {
+        Ok(definition) => definition,
+        Err(err) => panic!("could not parse matching rule definition {:?}: {}", expression, err),
+    }
+}
+
+/// A pattern which applies the matching rules from a matching rule definition expression (as
+/// used by the other language DSLs and the FFI), and which generates the example value from the
+/// expression.
+///
+/// ```
+/// use pact_consumer::*;
+///
+/// # fn main() {
+/// json_pattern!({
+///   // Match any integer, and generate `100`.
+///   "id": matching_expression!("matching(integer, 100)"),
+///   // Match a non-empty string, and generate `"Fred"`.
+///   "name": matching_expression!("notEmpty('Fred')")
+/// });
+/// # }
+/// ```
+#[macro_export]
+macro_rules! matching_expression {
+    ($expression:expr) => {
+        {
+            $crate::patterns::MatchingExpression::new($crate::patterns::parse_matching_expression($expression))
+        }
+    }
+}
+
+#[test]
+fn matching_expression_supports_multiple_rules() {
+    use expectest::prelude::*;
+    use serde_json::json;
+
+    let matchable = matching_expression!("notEmpty('test'), matching(type, 'test')");
+    let pattern: JsonPattern = matchable.into();
+    expect!(pattern.to_example()).to(be_equal_to(json!("test")));
+
+    let mut rules = MatchingRuleCategory::empty("body");
+    pattern.extract_matching_rules(DocPath::root(), &mut rules);
+    expect!(rules.rules.get(&DocPath::root()).map(|list| list.rules.clone()))
+        .to(be_some().value(vec![MatchingRule::NotEmpty, MatchingRule::Type]));
+}
+
 /// Match keys and values in an Object based on associated matching rules
 #[derive(Debug)]
 pub struct ObjectMatching {
@@ -1,3 +1,6 @@
+use std::fs;
+use std::path::Path;
+
 use anyhow::anyhow;
 use bytes::Bytes;
 use expectest::prelude::*;
@@ -120,3 +123,68 @@ fn test_req_res_message_client() {
     expect!(processed.state).to(be_equal_to("VA"));
   }
 }
+
+// This is a test for async messages using the message handler harness. The handler is invoked with
+// each message configured by the builder, and the pact file is only written if all the messages
+// were processed.
+#[test_log::test]
+fn test_verify_messages_with_message_handler() {
+  let output_dir = Path::new("target/message_pacts");
+  let _ = fs::remove_file(output_dir.join("verify-message-consumer-verify-message-provider.json"));
+
+  let mut pact_builder = PactBuilder::new_v4("verify-message-consumer", "verify-message-provider");
+  pact_builder
+    .with_output_dir(output_dir)
+    .message_interaction("hello message", |mut i| {
+      i.test_name("test_verify_messages_with_message_handler");
+      i.metadata("destination", "addresses");
+      i.json_body(json_pattern!({
+        "name": like!("mai"),
+        "street": like!("5th"),
+        "state": like!("VA"),
+      }));
+      i
+    });
+
+  let handler = MessageHandler::new();
+  let mut destinations = vec![];
+  let result = pact_builder.verify_messages(|message| {
+    destinations.push(message.metadata.get("destination").cloned());
+    let processed = handler.process(message.body())?;
+    expect!(processed.name).to(be_equal_to("mai"));
+    Ok(())
+  });
+
+  expect!(result).to(be_ok());
+  expect!(destinations).to(be_equal_to(vec![Some(serde_json::json!("addresses"))]));
+  expect!(output_dir.join("verify-message-consumer-verify-message-provider.json").exists()).to(be_true());
+}
+
+#[test_log::test]
+fn test_verify_messages_does_not_write_the_pact_when_the_handler_fails() {
+  let output_dir = Path::new("target/message_pacts");
+  let pact_file = output_dir.join("failing-message-consumer-failing-message-provider.json");
+  let _ = fs::remove_file(&pact_file);
+
+  let mut pact_builder = PactBuilder::new_v4("failing-message-consumer", "failing-message-provider");
+  pact_builder
+    .with_output_dir(output_dir)
+    .message_interaction("invalid message", |mut i| {
+      i.json_body(json_pattern!({
+        "name": like!("mai"),
+        "state": like!("!!!"),
+      }));
+      i
+    });
+
+  let handler = MessageHandler::new();
+  let result = pact_builder.verify_messages(|message| handler.process(message.body()).map(|_| ()));
+
+  expect!(result).to(be_err().value(MessageVerificationError::HandlerFailed(vec![
+    MessageHandlerFailure {
+      description: "invalid message".to_string(),
+      error: "Failed to parse message: state is not valid".to_string()
+    }
+  ])));
+  expect!(pact_file.exists()).to(be_false());
+}
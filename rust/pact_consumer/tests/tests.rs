@@ -294,6 +294,62 @@ async fn post_json_with_incorrect_content_type() {
   assert_eq!(Data::default(), body);
 }
 
+#[test_log::test(tokio::test)]
+async fn mock_server_responds_to_cors_preflight_requests() {
+  let service = PactBuilder::new_v4("CORS Consumer", "CORS Provider")
+    .with_cors_preflight(true)
+    .interaction("create an order", "", |mut i| {
+      i.request.post().path("/orders").json_body(json_pattern!({ "id": 1 }));
+      i.response.created();
+      i.clone()
+    })
+    .start_mock_server(None);
+
+  let client = Client::new();
+  let preflight = client.request(reqwest::Method::OPTIONS, service.path("/orders"))
+    .header("Origin", "http://localhost:3000")
+    .header("Access-Control-Request-Method", "POST")
+    .header("Access-Control-Request-Headers", "content-type")
+    .send()
+    .await
+    .expect("could not send the preflight request");
+  expect!(preflight.status().is_success()).to(be_true());
+  expect!(preflight.headers().contains_key("access-control-allow-origin")).to(be_true());
+
+  let response = client.post(service.path("/orders"))
+    .json(&json!({ "id": 1 }))
+    .send()
+    .await
+    .expect("could not send the request");
+  expect!(response.status()).to(be_equal_to(StatusCode::CREATED));
+}
+
+#[test_log::test(tokio::test)]
+#[cfg(feature = "test-client")]
+async fn mock_server_test_client() {
+  let service = PactBuilder::new_v4("Test Client Consumer", "Test Client Provider")
+    .interaction("create an order", "", |mut i| {
+      i.request.post().path("/orders").json_body(json_pattern!({ "id": like!(1) }));
+      i.response.created().json_body(json_pattern!({ "id": 1, "status": "new" }));
+      i.clone()
+    })
+    .interaction("get an order", "", |mut i| {
+      i.request.path("/orders/1").header("Authorization", "Bearer 1234");
+      i.response.ok().json_body(json_pattern!({ "id": 1, "status": "new" }));
+      i.clone()
+    })
+    .start_mock_server(None);
+
+  let client = service.client();
+  let response = client.post_json("/orders", &json!({ "id": 100 })).await.unwrap();
+  expect!(response.status).to(be_equal_to(201));
+  expect!(response.json().unwrap()).to(be_equal_to(json!({ "id": 1, "status": "new" })));
+
+  let response = client.with_header("Authorization", "Bearer 1234").get("/orders/1").await.unwrap();
+  expect!(response.is_success()).to(be_true());
+  expect!(response.header("content-type")).to(be_some().value("application/json"));
+}
+
 // Issue #300
 #[test_log::test(tokio::test)]
 async fn multi_value_headers()     {
@@ -40,6 +40,8 @@ int main(void) {
             case ExpressionValueType_Integer: printf("    %d == Integer\n", valueType); break;
             case ExpressionValueType_Decimal: printf("    %d == Decimal\n", valueType); break;
             case ExpressionValueType_Boolean: printf("    %d == Boolean\n", valueType); break;
+            case ExpressionValueType_Array: printf("    %d == Array\n", valueType); break;
+            case ExpressionValueType_Object: printf("    %d == Object\n", valueType); break;
         }
 
         const Generator* generator = pactffi_matcher_definition_generator(result);
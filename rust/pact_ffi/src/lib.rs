@@ -6,11 +6,13 @@
 #![warn(missing_copy_implementations)]
 
 use std::ffi::CStr;
+use std::fs::OpenOptions;
 use std::panic::RefUnwindSafe;
 use std::str::FromStr;
+use std::sync::Mutex;
 
 use lazy_static::lazy_static;
-use libc::c_char;
+use libc::{c_char, c_int};
 use tracing::{debug, error, info, trace, warn};
 use tracing_core::{Level, LevelFilter};
 use tracing_log::AsLog;
@@ -33,6 +35,7 @@ pub mod mock_server;
 pub mod verifier;
 pub mod plugins;
 pub mod matching;
+pub mod publish;
 
 const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "\0");
 
@@ -103,6 +106,53 @@ pub unsafe extern "C" fn pactffi_init_with_log_level(level: *const c_char) {
   };
 }
 
+/// Initialises logging, writing the log entries as JSON to the given file. Each entry will
+/// include the fields of the tracing spans it was logged in (i.e. the interaction being matched,
+/// the path in the body and the matching rules applied). This function should only be called
+/// once, as it tries to install a global tracing subscriber.
+///
+/// * `file_name` - String. Path to the file to write the log entries to. Entries will be appended if the file exists.
+/// * `log_level` - String. One of TRACE, DEBUG, INFO, WARN, ERROR, NONE/OFF. Case-insensitive. Defaults to INFO.
+///
+/// # Errors
+///
+/// Returns 0 on success, -1 if the file name is not valid, -2 if the file could not be opened
+/// and -3 if a global tracing subscriber has already been installed.
+///
+/// # Safety
+///
+/// Exported functions are inherently unsafe.
+#[no_mangle]
+pub unsafe extern "C" fn pactffi_init_with_json_log_file(file_name: *const c_char, log_level: *const c_char) -> c_int {
+  let file_name = match convert_cstr("file_name", file_name) {
+    Some(file_name) => file_name,
+    None => return -1
+  };
+  let file = match OpenOptions::new().create(true).append(true).open(file_name) {
+    Ok(file) => file,
+    Err(err) => {
+      error!("Failed to open log file '{}' - {}", file_name, err);
+      return -2;
+    }
+  };
+
+  let log_level = log_level_filter_from_c_char(log_level);
+  let subscriber = FmtSubscriber::builder()
+    .json()
+    .with_current_span(true)
+    .with_span_list(true)
+    .with_max_level(log_level)
+    .with_thread_names(true)
+    .with_writer(Mutex::new(file))
+    .finish();
+  if let Err(err) = tracing::subscriber::set_global_default(subscriber) {
+    eprintln!("Failed to initialise global tracing subscriber - {err}");
+    return -3;
+  };
+
+  0
+}
+
 /// Enable ANSI coloured output on Windows. On non-Windows platforms, this function is a no-op.
 ///
 /// # Safety
@@ -266,6 +316,23 @@ ffi_fn! {
     }
 }
 
+ffi_fn! {
+    /// Get a JSON representation of the matching rule that was applied to produce the mismatch.
+    /// This has the path the rule was defined against, if the rule cascaded from a parent path,
+    /// the matching rules that were evaluated, and the origin of the rules (`core` or `plugin`).
+    ///
+    /// Returns NULL if the mismatch did not have a matching rule applied, or if the function panics.
+    fn pactffi_mismatch_rule(mismatch: *const Mismatch) -> *const c_char {
+        let mismatch = as_ref!(mismatch);
+        match mismatch.rule() {
+            Some(rule) => string::to_c(&rule.to_json().to_string())? as *const c_char,
+            None => std::ptr::null()
+        }
+    } {
+        std::ptr::null()
+    }
+}
+
 ffi_fn! {
     /// Get the type of a mismatch.
     fn pactffi_mismatch_type(mismatch: *const Mismatch) -> *const c_char {
@@ -384,4 +451,27 @@ mod tests {
     let result = unsafe { log_level_from_c_char(value.as_ptr()) };
     expect!(result).to(be_equal_to(level));
   }
+
+  #[test]
+  fn mismatch_rule_test() {
+    let mismatch = Mismatch::BodyMismatch {
+      path: "$.a.b".to_string(),
+      expected: None,
+      actual: None,
+      mismatch: "Expected 100 (Integer) to be the same type as '100' (String)".to_string(),
+      rule: Some(pm::RuleTrace {
+        rule_path: "$.a".to_string(),
+        cascaded: true,
+        rules: vec![ pact_models::matchingrules::MatchingRule::Type ],
+        origin: pm::RuleOrigin::Core
+      })
+    };
+    let result = pactffi_mismatch_rule(&mismatch);
+    let json = unsafe { CString::from_raw(result as *mut _) };
+    expect!(json.to_string_lossy().to_string()).to(be_equal_to(
+      r#"{"cascaded":true,"origin":"core","path":"$.a","rules":[{"match":"type"}]}"#.to_string()));
+
+    let mismatch = Mismatch::MethodMismatch { expected: "GET".to_string(), actual: "POST".to_string() };
+    expect!(pactffi_mismatch_rule(&mismatch).is_null()).to(be_true());
+  }
 }
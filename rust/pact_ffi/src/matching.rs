@@ -1,13 +1,24 @@
 //! Module provides FFI functions to match values using Pact matching rules
 
+use anyhow::{anyhow, Context};
 use bytes::Bytes;
 use libc::{c_char, c_uchar};
 use pact_models::matchingrules::MatchingRule;
-use serde_json::Value;
+use pact_models::interaction::Interaction;
+use pact_models::pact::Pact;
+use pact_models::PactSpecification;
+use pact_models::request::Request;
+use pact_models::response::Response;
+use pact_models::v4::http_parts::{HttpRequest, HttpResponse};
+use pact_models::v4::interaction::V4Interaction;
+use pact_models::v4::pact::V4Pact;
+use pact_models::v4::synch_http::SynchronousHttp;
+use serde_json::{json, Value};
 
 use pact_matching::matchers::Matches;
 
-use crate::{as_ref, ffi_fn, safe_str};
+use crate::{as_ref, ffi_fn, RUNTIME, safe_str};
+use crate::models::pact_specification::PactSpecification as CPactSpecification;
 use crate::util::string;
 
 ffi_fn! {
@@ -260,14 +271,113 @@ ffi_fn! {
     }
 }
 
+ffi_fn! {
+    /// Matches an actual HTTP request and/or response against the expected ones using the core
+    /// Pact matching, returning the mismatches as a JSON NULL terminated string. The returned
+    /// string will need to be deleted with the `pactffi_string_delete` function once it is no
+    /// longer required.
+    ///
+    /// * expected_json - JSON of the expected HTTP parts, in the same format as an interaction in
+    ///   a Pact file (i.e. with `request` and/or `response` attributes)
+    /// * actual_json - JSON of the actual HTTP parts, in the same format as the expected JSON
+    /// * spec_version - Pact specification version to use when parsing the expected JSON
+    ///
+    /// The result will contain a `request` and/or `response` attribute for each part in the
+    /// expected JSON, with the list of mismatches for that part (which will be empty if it matched).
+    /// For example: `{"request":[],"response":[{"type":"StatusMismatch",...}]}`.
+    ///
+    /// # Safety
+    ///
+    /// The JSON parameters must be valid pointers to NULL terminated strings.
+    ///
+    /// # Error Handling
+    ///
+    /// If either JSON string is invalid, or the expected JSON has no request or response, this
+    /// function will return a NULL pointer.
+    fn pactffi_match_http_parts(
+      expected_json: *const c_char,
+      actual_json: *const c_char,
+      spec_version: CPactSpecification
+    ) -> *const c_char {
+      let expected_json: Value = serde_json::from_str(safe_str!(expected_json))
+        .context("Failed to parse expected JSON")?;
+      let actual_json: Value = serde_json::from_str(safe_str!(actual_json))
+        .context("Failed to parse actual JSON")?;
+      let spec_version: PactSpecification = spec_version.into();
+
+      let expected_request = http_request_from_json(expected_json.get("request"), &spec_version)?;
+      let expected_response = http_response_from_json(expected_json.get("response"), &spec_version)?;
+      if expected_request.is_none() && expected_response.is_none() {
+        return Err(anyhow!("Expected JSON must have a request or response attribute"));
+      }
+      let actual_request = http_request_from_json(actual_json.get("request"), &spec_version)?;
+      let actual_response = http_response_from_json(actual_json.get("response"), &spec_version)?;
+
+      let interaction = SynchronousHttp {
+        request: expected_request.clone().unwrap_or_default(),
+        response: expected_response.clone().unwrap_or_default(),
+        .. SynchronousHttp::default()
+      };
+      let pact = V4Pact {
+        interactions: vec![ interaction.boxed_v4() ],
+        .. V4Pact::default()
+      }.boxed();
+      let interaction = interaction.boxed();
+
+      let result = RUNTIME.block_on(async {
+        let mut result = serde_json::Map::new();
+        if let Some(expected) = expected_request {
+          let actual = actual_request.unwrap_or_default();
+          let mismatches = pact_matching::match_request(expected, actual, &pact, &interaction).await
+            .mismatches();
+          result.insert("request".to_string(), json!(mismatches.iter().map(|m| m.to_json()).collect::<Vec<_>>()));
+        }
+        if let Some(expected) = expected_response {
+          let actual = actual_response.unwrap_or_default();
+          let mismatches = pact_matching::match_response(expected, actual, &pact, &interaction).await;
+          result.insert("response".to_string(), json!(mismatches.iter().map(|m| m.to_json()).collect::<Vec<_>>()));
+        }
+        Value::Object(result)
+      });
+
+      string::to_c(&result.to_string())? as *const c_char
+    } {
+      std::ptr::null()
+    }
+}
+
+fn http_request_from_json(
+  json: Option<&Value>,
+  spec_version: &PactSpecification
+) -> anyhow::Result<Option<HttpRequest>> {
+  match json {
+    Some(json) if *spec_version >= PactSpecification::V4 => HttpRequest::from_json(json).map(Some),
+    Some(json) => Request::from_json(json, spec_version).map(|request| Some(request.as_v4_request())),
+    None => Ok(None)
+  }
+}
+
+fn http_response_from_json(
+  json: Option<&Value>,
+  spec_version: &PactSpecification
+) -> anyhow::Result<Option<HttpResponse>> {
+  match json {
+    Some(json) if *spec_version >= PactSpecification::V4 => HttpResponse::from_json(json).map(Some),
+    Some(json) => Response::from_json(json, spec_version).map(|response| Some(response.as_v4_response())),
+    None => Ok(None)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use std::ffi::{c_char, CString};
 
   use expectest::prelude::*;
   use pact_models::matchingrules::MatchingRule;
+  use serde_json::{json, Value};
 
-  use crate::matching::{pactffi_matches_binary_value, pactffi_matches_bool_value, pactffi_matches_f64_value, pactffi_matches_i64_value, pactffi_matches_json_value, pactffi_matches_string_value, pactffi_matches_u64_value};
+  use crate::matching::{pactffi_match_http_parts, pactffi_matches_binary_value, pactffi_matches_bool_value, pactffi_matches_f64_value, pactffi_matches_i64_value, pactffi_matches_json_value, pactffi_matches_string_value, pactffi_matches_u64_value};
+  use crate::models::pact_specification::PactSpecification;
 
   #[test_log::test]
   fn pactffi_matches_string_value_test() {
@@ -386,4 +496,46 @@ mod tests {
     let string = unsafe { CString::from_raw(err_result as *mut c_char) };
     expect!(string.to_string_lossy()).to(be_equal_to("Failed to parse actual JSON: EOF while parsing a string at line 1 column 11"));
   }
+
+  #[test_log::test]
+  fn pactffi_match_http_parts_test() {
+    let expected = CString::new(json!({
+      "request": {
+        "method": "GET",
+        "path": "/items/100",
+        "matchingRules": {
+          "$.path": { "match": "regex", "regex": "/items/\\d+" }
+        }
+      },
+      "response": {
+        "status": 200,
+        "body": { "id": 100 },
+        "matchingRules": {
+          "$.body.id": { "match": "type" }
+        }
+      }
+    }).to_string()).unwrap();
+    let actual = CString::new(json!({
+      "request": { "method": "GET", "path": "/items/200" },
+      "response": { "status": 404, "body": { "id": 200 } }
+    }).to_string()).unwrap();
+
+    let result = pactffi_match_http_parts(expected.as_ptr(), actual.as_ptr(), PactSpecification::V3);
+    expect!(result.is_null()).to(be_false());
+    let string = unsafe { CString::from_raw(result as *mut c_char) };
+    let json: Value = serde_json::from_str(string.to_str().unwrap()).unwrap();
+    expect!(json["request"].as_array().unwrap().iter()).to(be_empty());
+    expect!(json["response"].clone()).to(be_equal_to(json!([
+      {
+        "type": "StatusMismatch",
+        "expected": 200,
+        "actual": 404,
+        "mismatch": "expected 200 but was 404"
+      }
+    ])));
+
+    let invalid = CString::new("{}").unwrap();
+    let result = pactffi_match_http_parts(invalid.as_ptr(), actual.as_ptr(), PactSpecification::V3);
+    expect!(result.is_null()).to(be_true());
+  }
 }
@@ -6,6 +6,9 @@ use anyhow::{anyhow, bail};
 use bytes::{Bytes, BytesMut};
 use either::Either;
 use lazy_static::lazy_static;
+use rand::distributions::Alphanumeric;
+use rand::prelude::*;
+use rand::rngs::StdRng;
 use regex::Regex;
 use serde_json::{Map, Value};
 use tracing::{debug, error, trace, warn};
@@ -23,6 +26,11 @@ use crate::mock_server::generator_category;
 
 const CONTENT_TYPE_HEADER: &str = "Content-Type";
 
+/// Environment variable that can be set to a number to seed the generated multipart boundaries.
+/// When it is set, the same boundaries will be generated on every run, so the Pact files do not
+/// change between runs.
+pub const MULTIPART_BOUNDARY_SEED_ENV_VAR: &str = "PACT_MULTIPART_BOUNDARY_SEED";
+
 lazy_static! {
   static ref MULTIPART_MARKER: Regex = Regex::new("\\-\\-([a-zA-Z0-9'\\(\\)+_,-.\\/:=? ]*)\r\n").unwrap();
 }
@@ -400,10 +408,10 @@ pub fn file_as_multipart_body(file: &str, part_name: &str) -> Result<MultipartBo
   multipart.write_file(part_name, Path::new(file)).map_err(format_multipart_error)?;
   let http_buffer = multipart.send().map_err(format_multipart_error)?;
 
-  Ok(MultipartBody {
+  Ok(with_seeded_boundary(MultipartBody {
     body: OptionalBody::Present(Bytes::from(http_buffer.buf), Some("multipart/form-data".into()), None),
     boundary: http_buffer.boundary
-  })
+  }, part_name))
 }
 
 /// Create an empty MIME Multipart body
@@ -411,10 +419,48 @@ pub fn empty_multipart_body() -> Result<MultipartBody, String> {
   let multipart = multipart::client::Multipart::from_request(multipart::mock::ClientRequest::default()).unwrap();
   let http_buffer = multipart.send().map_err(format_multipart_error)?;
 
-  Ok(MultipartBody {
+  Ok(with_seeded_boundary(MultipartBody {
     body: OptionalBody::Present(Bytes::from(http_buffer.buf), Some("multipart/form-data".into()), None),
     boundary: http_buffer.boundary
-  })
+  }, ""))
+}
+
+/// Replaces the randomly generated boundary of the multipart body with one generated from the
+/// seed set with the `PACT_MULTIPART_BOUNDARY_SEED` environment variable. Returns the body
+/// unchanged if the environment variable is not set.
+fn with_seeded_boundary(body: MultipartBody, part_name: &str) -> MultipartBody {
+  match std::env::var(MULTIPART_BOUNDARY_SEED_ENV_VAR) {
+    Ok(seed) => match seed.trim().parse::<u64>() {
+      Ok(seed) => {
+        let boundary = seeded_boundary(seed, part_name);
+        trace!("Replacing multipart boundary '{}' with seeded boundary '{}'", body.boundary, boundary);
+        let part = part_body_replace_marker(&body.body, body.boundary.as_str(), boundary.as_str());
+        MultipartBody {
+          body: OptionalBody::Present(part, body.body.content_type(), get_content_type_hint(&body.body)),
+          boundary
+        }
+      }
+      Err(err) => {
+        warn!("Ignoring {} as '{}' is not a valid seed - {}", MULTIPART_BOUNDARY_SEED_ENV_VAR, seed, err);
+        body
+      }
+    }
+    Err(_) => body
+  }
+}
+
+/// Generates a multipart boundary from the seed and part name. The same boundary will always be
+/// generated for the same seed and part name.
+pub fn seeded_boundary(seed: u64, part_name: &str) -> String {
+  // FNV-1a hash of the part name, so different parts get different boundaries
+  let part_hash = part_name.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+    (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+  });
+  StdRng::seed_from_u64(seed ^ part_hash)
+    .sample_iter(&Alphanumeric)
+    .map(char::from)
+    .take(30)
+    .collect()
 }
 
 fn format_multipart_error(e: std::io::Error) -> String {
@@ -998,4 +1044,14 @@ Content-Type: application/json\r\n\r\n{}\r\n--ABCD\r\nContent-Disposition: form-
 name=\"part-2\"; filename=\"2.txt\"\r\nContent-Type: text/plain\r\n\r\nTEXT\r\n--ABCD--\r\n",
                response.body.value_as_string().unwrap());
   }
+
+  #[test]
+  fn seeded_boundary_test() {
+    let boundary = seeded_boundary(1234, "file");
+    expect!(boundary.len()).to(be_equal_to(30));
+    expect!(boundary.chars().all(|ch| ch.is_ascii_alphanumeric())).to(be_true());
+    expect!(seeded_boundary(1234, "file")).to(be_equal_to(boundary.clone()));
+    expect!(seeded_boundary(1234, "other-file")).to_not(be_equal_to(boundary.clone()));
+    expect!(seeded_boundary(5678, "file")).to_not(be_equal_to(boundary));
+  }
 }
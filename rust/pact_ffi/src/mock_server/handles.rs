@@ -100,15 +100,35 @@
 //! // Should be no mismatches
 //! expect!(mismatches).to(be_equal_to("[]"));
 //! ```
+//!
+//! # Thread safety
+//!
+//! All the Pact models are stored in a global registry behind a single lock, and each function
+//! that uses a handle holds the lock for the duration of the call. This means that:
+//!
+//! * Pacts and interactions can be created and updated concurrently from multiple threads. Each
+//!   call is applied atomically, but calls from different threads to the same Pact may be
+//!   applied in any order.
+//! * Pact handles are unique. References are allocated sequentially and are only reused after all
+//!   the other 65,534 references have been allocated, so a handle to a Pact that has been freed
+//!   will not refer to a Pact created after it (the functions will fail for the freed handle).
+//!   A zeroed handle never refers to a Pact, and is returned when a Pact is created while all the
+//!   references are in use.
+//! * Interaction handles refer to the position of the interaction in its Pact. Creating an
+//!   interaction with the same description as an existing one replaces it, so the handle to the
+//!   existing interaction will then refer to the new one. Interactions being set up concurrently
+//!   must have different descriptions.
+//! * A panic in one call will not prevent calls from other threads from using the registry.
 
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::collections::hash_map::Entry;
 use std::ffi::{CStr, CString};
 use std::path::PathBuf;
 use std::ptr::null_mut;
 use std::str::from_utf8;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicU16, Ordering};
 
 use anyhow::anyhow;
 use bytes::Bytes;
@@ -120,18 +140,20 @@ use maplit::*;
 use pact_models::{Consumer, PactSpecification, Provider};
 use pact_models::bodies::OptionalBody;
 use pact_models::content_types::{ContentType, detect_content_type_from_string, JSON, TEXT, XML};
-use pact_models::generators::{Generator, Generators};
+use pact_models::generators::{Generator, GeneratorCategory, Generators, generators_from_json};
 use pact_models::headers::parse_header;
 use pact_models::http_parts::HttpPart;
 use pact_models::interaction::Interaction;
 use pact_models::json_utils::json_to_string;
 use pact_models::matchingrules::{matchers_from_json, Category, MatchingRule, MatchingRuleCategory, MatchingRules, RuleLogic};
-use pact_models::pact::{ReadWritePact, write_pact};
+use pact_models::matchingrules::expressions::{is_matcher_def, parse_matcher_def, ValueType};
+use pact_models::pact::{ReadWritePact, write_pact, write_pact_with_options, WritePactOptions};
 use pact_models::path_exp::DocPath;
 use pact_models::prelude::Pact;
 use pact_models::prelude::v4::V4Pact;
 use pact_models::provider_states::ProviderState;
 use pact_models::v4::async_message::AsynchronousMessage;
+use pact_models::v4::http_parts::{HttpRequest, HttpResponse};
 use pact_models::v4::interaction::V4Interaction;
 use pact_models::v4::message_parts::MessageContents;
 use pact_models::v4::sync_message::SynchronousMessage;
@@ -139,8 +161,9 @@ use pact_models::v4::synch_http::SynchronousHttp;
 use serde_json::{json, Value};
 use tracing::*;
 
-use pact_matching::generators::generate_message;
-use pact_models::generators::GeneratorTestMode;
+use pact_matching::{generate_request_with_report, generate_response_with_report};
+use pact_matching::generators::{apply_generators_to_sync_message, generate_message, GeneratorReport};
+use pact_models::generators::{GeneratorTestMode, RANDOM_SEED};
 use futures::executor::block_on;
 
 use crate::{convert_cstr, ffi_fn, safe_str};
@@ -169,13 +192,76 @@ use crate::ptr;
 pub struct PactHandleInner {
   pub(crate) pact: V4Pact,
   pub(crate) mock_server_started: bool,
-  pub(crate) specification_version: PactSpecification
+  pub(crate) specification_version: PactSpecification,
+  pub(crate) write_options: WritePactOptions,
+  pub(crate) generator_seed: Option<u64>,
+  /// Reports of the values generated the last time the generators were applied to the HTTP
+  /// interactions, keyed by the interaction index and part
+  pub(crate) generator_reports: HashMap<(u16, InteractionPart), GeneratorReport>
 }
 
 lazy_static! {
   static ref PACT_HANDLES: Arc<Mutex<HashMap<u16, RefCell<PactHandleInner>>>> = Arc::new(Mutex::new(hashmap![]));
 }
 
+/// Next reference to try when allocating a Pact handle. References are allocated sequentially
+/// (instead of randomly), so that a reference is only reused once all the other references have
+/// been allocated. This prevents a stale handle to a Pact that has been freed from referring to a
+/// Pact that was created after it.
+static NEXT_PACT_REF: AtomicU16 = AtomicU16::new(1);
+
+/// Acquires the lock on the Pact handles. If a thread panicked while holding the lock, the lock is
+/// recovered, as all the mutations of the handles are done via single method calls, so the map can
+/// not be left in an inconsistent state. Otherwise a single failure would cause all subsequent
+/// calls from any other thread to fail.
+fn lock_pact_handles() -> MutexGuard<'static, HashMap<u16, RefCell<PactHandleInner>>> {
+  PACT_HANDLES.lock().unwrap_or_else(|err| {
+    warn!("Lock on the Pact handles was poisoned by a panic in another thread, recovering");
+    err.into_inner()
+  })
+}
+
+/// Stores the Pact and returns the reference to it. Zero is never used as a reference, so a
+/// zeroed handle will never refer to a Pact. If all the references are in use, an error is logged
+/// and zero is returned.
+fn register_pact(pact: V4Pact) -> u16 {
+  let mut handles = lock_pact_handles();
+
+  // Only make one pass over the references, so this does not spin while holding the lock if the
+  // registry is full
+  let id = (0..=u16::MAX)
+    .map(|_| NEXT_PACT_REF.fetch_add(1, Ordering::SeqCst))
+    .find(|id| *id != 0 && !handles.contains_key(id));
+  let id = match id {
+    Some(id) => id,
+    None => {
+      error!("Failed to create a new Pact as all the {} Pact handles are in use", u16::MAX);
+      return 0;
+    }
+  };
+
+  handles.insert(id, RefCell::new(PactHandleInner {
+    pact,
+    mock_server_started: false,
+    specification_version: PactSpecification::V3,
+    write_options: WritePactOptions::default(),
+    generator_seed: None,
+    generator_reports: HashMap::new()
+  }));
+
+  id
+}
+
+/// Returns the context to use to apply the generators (in consumer mode) to the interactions of
+/// the Pact with the given reference. This must not be called while the lock on the Pact handles
+/// is held.
+fn generator_context(pact_ref: u16) -> HashMap<&'static str, Value> {
+  lock_pact_handles().get(&pact_ref)
+    .and_then(|inner| inner.borrow().generator_seed)
+    .map(|seed| hashmap!{ RANDOM_SEED => Value::from(seed) })
+    .unwrap_or_default()
+}
+
 #[repr(transparent)]
 #[derive(Debug, Clone, Copy)]
 /// Wraps a Pact model struct
@@ -193,7 +279,7 @@ pub struct InteractionHandle {
 }
 
 #[repr(C)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// Request or Response enum
 pub enum InteractionPart {
   /// Request part
@@ -212,36 +298,19 @@ impl PactHandle {
     };
     pact.add_md_version("ffi", option_env!("CARGO_PKG_VERSION").unwrap_or("unknown"));
 
-    let id = {
-      let mut handles = PACT_HANDLES.lock().unwrap();
-
-      let keys: HashSet<&u16> = handles.keys().collect();
-      let mut id: u16 = rand::random();
-      while keys.contains(&id) {
-        id = rand::random();
-      }
-
-      handles.insert(id, RefCell::new(PactHandleInner {
-        pact,
-        mock_server_started: false,
-        specification_version: PactSpecification::V3
-      }));
-
-      id
-    };
-
     PactHandle {
-      pact_ref: id
+      pact_ref: register_pact(pact)
     }
   }
 
   /// Invokes the closure with the inner Pact model
   ///
   /// # Errors
-  /// This function acquires a lock on the PACT_HANDLES mutex. If the closure panics, this mutex
-  /// will be poisoned. So panics must be avoided.
+  /// This function acquires a lock on the PACT_HANDLES mutex, so the closure must not use any
+  /// other handles (otherwise it will deadlock). If the closure panics, the Pact model may be left
+  /// partially updated. So panics must be avoided.
   pub(crate) fn with_pact<R>(&self, f: &dyn Fn(u16, &mut PactHandleInner) -> R) -> Option<R> {
-    let mut handles = PACT_HANDLES.lock().unwrap();
+    let mut handles = lock_pact_handles();
     trace!("with_pact - ref = {}, keys = {:?}", self.pact_ref, handles.keys());
     handles.get_mut(&self.pact_ref).map(|inner| {
       trace!("with_pact before - ref = {}, inner = {:?}", self.pact_ref, inner);
@@ -266,10 +335,11 @@ impl InteractionHandle {
   /// Invokes the closure with the inner Pact model
   ///
   /// # Errors
-  /// This function acquires a lock on the PACT_HANDLES mutex. If the closure panics, this mutex
-  /// will be poisoned. So panics must be avoided.
+  /// This function acquires a lock on the PACT_HANDLES mutex, so the closure must not use any
+  /// other handles (otherwise it will deadlock). If the closure panics, the Pact model may be left
+  /// partially updated. So panics must be avoided.
   pub fn with_pact<R>(&self, f: &dyn Fn(u16, &mut PactHandleInner) -> R) -> Option<R> {
-    let mut handles = PACT_HANDLES.lock().unwrap();
+    let mut handles = lock_pact_handles();
     let index = (self.interaction_ref >> 16) as u16;
     handles.get_mut(&index).map(|inner| f(index, &mut inner.borrow_mut()))
   }
@@ -277,10 +347,11 @@ impl InteractionHandle {
   /// Invokes the closure with the inner Interaction model
   ///
   /// # Errors
-  /// This function acquires a lock on the PACT_HANDLES mutex. If the closure panics, this mutex
-  /// will be poisoned. So panics must be avoided.
+  /// This function acquires a lock on the PACT_HANDLES mutex, so the closure must not use any
+  /// other handles (otherwise it will deadlock). If the closure panics, the Pact model may be left
+  /// partially updated. So panics must be avoided.
   pub fn with_interaction<R>(&self, f: &dyn Fn(u16, bool, &mut dyn V4Interaction) -> R) -> Option<R> {
-    let mut handles = PACT_HANDLES.lock().unwrap();
+    let mut handles = lock_pact_handles();
     let index = (self.interaction_ref >> 16) as u16;
     let interaction = (self.interaction_ref & 0x0000FFFF) as u16;
 
@@ -291,9 +362,9 @@ impl InteractionHandle {
       let inner_mut = &mut *inner.borrow_mut();
       trace!("with_interaction - inner = {:?}", inner_mut);
       let interactions = &mut inner_mut.pact.interactions;
-      match interactions.get_mut((interaction - 1) as usize) {
-        Some(inner_i) => {
-          Some(f(interaction - 1, inner_mut.mock_server_started, inner_i.as_mut()))
+      match interaction.checked_sub(1).and_then(|i| interactions.get_mut(i as usize).map(|inner_i| (i, inner_i))) {
+        Some((i, inner_i)) => {
+          Some(f(i, inner_mut.mock_server_started, inner_i.as_mut()))
         },
         None => {
           debug!("Did not find interaction for index = {}, interaction = {}, pact has {} interactions",
@@ -331,31 +402,14 @@ impl MessagePactHandle {
     };
     pact.add_md_version("ffi", option_env!("CARGO_PKG_VERSION").unwrap_or("unknown"));
 
-    let id = {
-      let mut handles = PACT_HANDLES.lock().unwrap();
-
-      let keys: HashSet<&u16> = handles.keys().collect();
-      let mut id: u16 = rand::random();
-      while keys.contains(&id) {
-        id = rand::random();
-      }
-
-      handles.insert(id, RefCell::new(PactHandleInner {
-        pact,
-        mock_server_started: false,
-        specification_version: PactSpecification::V3
-      }));
-      id
-    };
-
     MessagePactHandle {
-      pact_ref: id
+      pact_ref: register_pact(pact)
     }
   }
 
   /// Invokes the closure with the inner model
   pub fn with_pact<R>(&self, f: &dyn Fn(u16, &mut V4Pact, PactSpecification) -> R) -> Option<R> {
-    let mut handles = PACT_HANDLES.lock().unwrap();
+    let mut handles = lock_pact_handles();
     handles.get_mut(&self.pact_ref).map(|inner| {
       let mut ref_mut = inner.borrow_mut();
       let specification = ref_mut.specification_version;
@@ -387,7 +441,7 @@ impl MessageHandle {
 
   /// Invokes the closure with the inner model
   pub fn with_pact<R>(&self, f: &dyn Fn(u16, &mut V4Pact, PactSpecification) -> R) -> Option<R> {
-    let mut handles = PACT_HANDLES.lock().unwrap();
+    let mut handles = lock_pact_handles();
     let index = (self.interaction_ref >> 16) as u16;
     handles.get_mut(&index).map(|inner| {
       let mut ref_mut = inner.borrow_mut();
@@ -398,16 +452,17 @@ impl MessageHandle {
 
   /// Invokes the closure with the inner Interaction model
   pub fn with_message<R>(&self, f: &dyn Fn(u16, &mut dyn V4Interaction, PactSpecification) -> R) -> Option<R> {
-    let mut handles = PACT_HANDLES.lock().unwrap();
+    let mut handles = lock_pact_handles();
     let index = (self.interaction_ref >> 16) as u16;
     let interaction = self.interaction_ref as u16;
     handles.get_mut(&index).map(|inner| {
       let mut ref_mut = inner.borrow_mut();
       let specification = ref_mut.specification_version;
-      ref_mut.pact.interactions.get_mut((interaction - 1) as usize)
-        .map(|inner_i| {
+      interaction.checked_sub(1)
+        .and_then(|i| ref_mut.pact.interactions.get_mut(i as usize).map(|inner_i| (i, inner_i)))
+        .map(|(i, inner_i)| {
           if inner_i.is_message() {
-            Some(f(interaction - 1, inner_i.as_mut(), specification))
+            Some(f(i, inner_i.as_mut(), specification))
           } else {
             error!("Interaction {:#x} is not a message interaction, it is {}", self.interaction_ref, inner_i.type_of());
             None
@@ -424,7 +479,8 @@ impl MessageHandle {
 /// * `provider_name` - The name of the provider for the pact.
 ///
 /// Returns a new `PactHandle`. The handle will need to be freed with the `pactffi_free_pact_handle`
-/// method to release its resources.
+/// method to release its resources. If all the Pact handles are in use, a zeroed handle is
+/// returned, which does not refer to any Pact.
 #[no_mangle]
 pub extern fn pactffi_new_pact(consumer_name: *const c_char, provider_name: *const c_char) -> PactHandle {
   let consumer = convert_cstr("consumer_name", consumer_name).unwrap_or("Consumer");
@@ -591,7 +647,47 @@ ffi_fn! {
       interaction.with_interaction(&|_, started, inner| {
         if !started {
           if let Some(i) = inner.as_v4_mut() {
-            i.comments_mut().insert("testname".to_string(), json!(test_name));
+            i.set_test_name(test_name);
+            0
+          } else {
+            4
+          }
+        } else {
+          3
+        }
+      }).unwrap_or(2)
+    } {
+      1
+    }
+}
+
+ffi_fn! {
+    /// Sets the markup used to render the interaction in UIs (i.e. the Pact Broker). The markup
+    /// type should be either `COMMON_MARK` or `HTML`. This can only be used with V4 interactions.
+    ///
+    /// # Safety
+    ///
+    /// The markup and markup type parameters must be valid pointers to NULL terminated strings.
+    ///
+    /// # Error Handling
+    ///
+    /// If the markup can not be set, this will return a positive value.
+    ///
+    /// * `1` - Function panicked. Error message will be available by calling `pactffi_get_error_message`.
+    /// * `2` - Handle was not valid.
+    /// * `3` - Mock server was already started and the integration can not be modified.
+    /// * `4` - Not a V4 interaction.
+    fn pactffi_interaction_markup(
+      interaction: InteractionHandle,
+      markup: *const c_char,
+      markup_type: *const c_char
+    ) -> c_uint {
+      let markup = safe_str!(markup);
+      let markup_type = safe_str!(markup_type);
+      interaction.with_interaction(&|_, started, inner| {
+        if !started {
+          if let Some(i) = inner.as_v4_mut() {
+            i.set_interaction_markup(markup, markup_type);
             0
           } else {
             4
@@ -1977,6 +2073,93 @@ ffi_fn!{
   }
 }
 
+ffi_fn!{
+  /// Add generators to the interaction.
+  ///
+  /// * `interaction` - Interaction handle to set the generators for.
+  /// * `part` - Request or response part (if applicable).
+  /// * `generators` - JSON string of the generators to add to the interaction.
+  ///
+  /// This function can be called multiple times, in which case the generators
+  /// will be merged. The function will return `true` if the generators were
+  /// successfully added, and `false` if an error occurred.
+  ///
+  /// For synchronous messages which allow multiple responses, the generators
+  /// will be added to all the responses.
+  ///
+  /// # Safety
+  ///
+  /// The generators parameter must be a valid pointer to a NULL terminated UTF-8
+  /// string.
+  fn pactffi_with_generators(
+    interaction: InteractionHandle,
+    part: InteractionPart,
+    generators: *const c_char
+  ) -> bool {
+    let generators = match convert_cstr("generators", generators) {
+      Some(generators) => generators,
+      None => {
+        error!("with_generators: Generators value is not valid (NULL or non-UTF-8)");
+        return Ok(false);
+      }
+    };
+
+    let generators = match serde_json::from_str::<Value>(generators) {
+      Ok(Value::Object(generators)) => generators,
+      Ok(_) => {
+        error!("with_generators: Generators value is not a JSON object");
+        return Ok(false);
+      },
+      Err(err) => {
+        error!("with_generators: Failed to parse the generators: {}", err);
+        return Ok(false);
+      }
+    };
+
+    // Wrap the generators in a object with a "generators" key if it is not
+    // already, as this is required for `generators_from_json`.
+    let generators = if generators.contains_key("generators") {
+      Value::Object(generators)
+    } else {
+      json!({ "generators": generators })
+    };
+    let generators = match generators_from_json(&generators) {
+      Ok(generators) => generators,
+      Err(err) => {
+        error!("with_generators: Failed to load the generators: {}", err);
+        return Ok(false);
+      }
+    };
+
+    interaction.with_interaction(&move |_, _, inner| {
+      if let Some(reqres) = inner.as_v4_http_mut() {
+        match part {
+          InteractionPart::Request => reqres.request.generators.add_generators(generators.clone()),
+          InteractionPart::Response => reqres.response.generators.add_generators(generators.clone())
+        };
+        Ok(())
+      } else if let Some(message) = inner.as_v4_async_message_mut() {
+        message.contents.generators.add_generators(generators.clone());
+        Ok(())
+      } else if let Some(sync_message) = inner.as_v4_sync_message_mut() {
+        match part {
+          InteractionPart::Request => sync_message.request.generators.add_generators(generators.clone()),
+          InteractionPart::Response => sync_message.response.iter_mut()
+            .for_each(|response| response.generators.add_generators(generators.clone()))
+        };
+        Ok(())
+      } else {
+        error!("Interaction is an unknown type, is {}", inner.type_of());
+        Err(())
+      }
+    }).unwrap_or(Err(())).is_ok()
+  }
+  // Failure block
+  {
+    false
+  }
+}
+
 fn add_content_type_matching_rule_to_body(is_supported: bool, matching_rules: &mut MatchingRules, content_type: &str) {
   if is_supported {
     matching_rules.add_category("body").add_rule(
@@ -2006,7 +2189,9 @@ fn add_content_type_matching_rule_to_body(is_supported: bool, matching_rules: &m
 ///
 /// # Error Handling
 ///
-/// If the boundary is a NULL pointer, a random string will be used.
+/// If the boundary is a NULL pointer, a random string will be used. Set the
+/// `PACT_MULTIPART_BOUNDARY_SEED` environment variable to a number to have the same boundary
+/// generated on every run.
 /// If the file path is a NULL pointer, it will set the body contents as as an empty mime-part.
 /// If the file path does not point to a valid file, or is not able to be read, it will return an
 /// error result. If the content type is a null pointer, or can't be parsed, it will return an error result.
@@ -2277,14 +2462,7 @@ ffi_fn!{
     };
 
     interaction.with_interaction(&|_, _, inner| {
-      match inner.comments_mut().entry("text".to_string()).or_insert(serde_json::Value::Array(vec![])) {
-        serde_json::Value::Array(array) => {
-          array.push(serde_json::Value::String(comment.to_string()));
-        },
-        other_value => {
-          *other_value = serde_json::Value::Array(vec![serde_json::Value::String(comment.to_string())]);
-        }
-      };
+      inner.add_text_comment(comment);
       Ok(())
     }).unwrap_or(Err(anyhow!("Not value to unwrap"))).is_ok()
   } {
@@ -2588,6 +2766,119 @@ pub extern fn pactffi_message_with_metadata_v2(message_handle: MessageHandle, ke
   }
 }
 
+ffi_fn!{
+  /// Adds a matching rule (and optionally a generator) to a metadata key of the Message. The
+  /// metadata value is not changed, unless there is no value for the key, in which case the example
+  /// value from the rule will be used.
+  ///
+  /// * `key` - metadata key
+  /// * `rule` - the matching rule. This can either be a matching rule definition expression, or
+  ///   the matching rule in the JSON format used for the other integration functions.
+  ///
+  /// For example, to match the value of the `Correlation-Id` key with a regular expression:
+  ///
+  /// ```c
+  /// pactffi_message_with_metadata_rule(handle, "Correlation-Id", "matching(regex, '[0-9a-f]{16}', '3f2a9c1d4b5e6f70')");
+  /// pactffi_message_with_metadata_rule(handle, "Correlation-Id", "{\"pact:matcher:type\": \"regex\", \"regex\": \"[0-9a-f]{16}\"}");
+  /// ```
+  ///
+  /// Returns true if the rule was added, and false if the rule could not be parsed or the message
+  /// can't be modified.
+  ///
+  /// # Safety
+  /// The key and rule parameters must be valid pointers to NULL terminated strings.
+  fn pactffi_message_with_metadata_rule(
+    message_handle: MessageHandle,
+    key: *const c_char,
+    rule: *const c_char
+  ) -> bool {
+    let key = match convert_cstr("key", key) {
+      Some(key) => key,
+      None => {
+        error!("message_with_metadata_rule: Key value is not valid (NULL or non-UTF-8)");
+        return Ok(false);
+      }
+    };
+    let rule = match convert_cstr("rule", rule) {
+      Some(rule) => rule,
+      None => {
+        error!("message_with_metadata_rule: Rule value is not valid (NULL or non-UTF-8)");
+        return Ok(false);
+      }
+    };
+    trace!("pactffi_message_with_metadata_rule(message_handle: {:?}, key: {:?}, rule: {})", message_handle, key, rule);
+
+    let (rules, generator, example) = match metadata_rule(rule) {
+      Ok(result) => result,
+      Err(err) => {
+        error!("message_with_metadata_rule: Failed to parse the matching rule '{}' - {}", rule, err);
+        return Ok(false);
+      }
+    };
+
+    let mut path = DocPath::root();
+    path.push_field(key);
+    message_handle.with_message(&|_, inner, _| {
+      if let Some(message) = inner.as_v4_async_message_mut() {
+        let category = message.contents.matching_rules.add_category(Category::METADATA);
+        for rule in &rules {
+          category.add_rule(path.clone(), rule.clone(), RuleLogic::And);
+        }
+        if let Some(generator) = &generator {
+          message.contents.generators.add_generator_with_subcategory(&GeneratorCategory::METADATA,
+            path.clone(), generator.clone());
+        }
+        if let Some(example) = &example {
+          message.contents.metadata.entry(key.to_string()).or_insert_with(|| example.clone());
+        }
+        true
+      } else {
+        error!("Interaction is not an asynchronous message, is {}", inner.type_of());
+        false
+      }
+    }).unwrap_or(false)
+  }
+  {
+    false
+  }
+}
+
+/// Parses a matching rule for a metadata value, either as a matching rule definition or integration
+/// JSON. Returns the rules, any generator and the example value.
+fn metadata_rule(rule: &str) -> anyhow::Result<(Vec<MatchingRule>, Option<Generator>, Option<Value>)> {
+  if is_matcher_def(rule) {
+    let definition = parse_matcher_def(rule)?;
+    let rules = definition.rules.iter()
+      .map(|rule| rule.clone().left()
+        .ok_or_else(|| anyhow!("references to other attributes are not supported for metadata")))
+      .collect::<anyhow::Result<Vec<_>>>()?;
+    let example = match definition.value_type {
+      ValueType::String | ValueType::Unknown => Value::String(definition.value.clone()),
+      _ => serde_json::from_str(definition.value.as_str())
+        .unwrap_or_else(|_| Value::String(definition.value.clone()))
+    };
+    Ok((rules, definition.generator.clone(), Some(example)))
+  } else {
+    match serde_json::from_str::<Value>(rule)? {
+      Value::Object(map) => {
+        let (rules, generator) = if map.contains_key("pact:matcher:type") {
+          matchers_from_integration_json(&map)?
+        } else {
+          (vec![ MatchingRule::from_json(&Value::Object(map.clone()))? ], None)
+        };
+        let generator = generator.or_else(|| map.get("pact:generator:type")
+          .and_then(|gen| Generator::from_map(&json_to_string(gen), &map)));
+        if rules.is_empty() {
+          Err(anyhow!("no matching rule was found"))
+        } else {
+          Ok((rules, generator, map.get("value").cloned()))
+        }
+      }
+      _ => Err(anyhow!("expected a matching rule definition or a JSON object"))
+    }
+  }
+}
+
 /// Reifies the given message
 ///
 /// Reification is the process of stripping away any matchers, and returning the original contents.
@@ -2599,6 +2890,7 @@ pub extern fn pactffi_message_with_metadata_v2(message_handle: MessageHandle, ke
 /// that has a Tokio runtime in its call stack can result in a deadlock.
 #[no_mangle]
 pub extern fn pactffi_message_reify(message_handle: MessageHandle) -> *const c_char {
+  let context = generator_context((message_handle.interaction_ref >> 16) as u16);
   let res = message_handle.with_message(&|_, inner, spec_version| {
     trace!("pactffi_message_reify(message: {:?}, spec_version: {})", inner, spec_version);
     if let Some(message) = inner.as_v4_async_message() {
@@ -2606,7 +2898,7 @@ pub extern fn pactffi_message_reify(message_handle: MessageHandle) -> *const c_c
         OptionalBody::Null => "null".to_string(),
         OptionalBody::Present(_, _, _) => if spec_version <= pact_models::PactSpecification::V3 {
           let message = message.as_message().unwrap_or_default();
-          let message = block_on(generate_message(&message, &GeneratorTestMode::Consumer, &hashmap!{}, &vec![], &hashmap!{}));
+          let message = block_on(generate_message(&message, &GeneratorTestMode::Consumer, &context, &vec![], &hashmap!{}));
           message.to_json(&spec_version).to_string()
         } else {
           message.to_json().to_string()
@@ -2627,6 +2919,152 @@ pub extern fn pactffi_message_reify(message_handle: MessageHandle) -> *const c_c
   }
 }
 
+/// Reifies the given synchronous message interaction, returning the request and response contents
+/// as a JSON string in the form `{"request": {...}, "response": [...]}`. Any generators will be
+/// applied (in consumer mode), and the matching rules and generators will be stripped from the
+/// contents.
+///
+/// Returns an empty string if the interaction is not a synchronous message.
+///
+/// # Safety
+///
+/// The returned string needs to be deallocated with the `free_string` function.
+/// This function must only ever be called from a foreign language. Calling it from a Rust function
+/// that has a Tokio runtime in its call stack can result in a deadlock.
+#[no_mangle]
+pub extern fn pactffi_sync_message_reify(interaction: InteractionHandle) -> *const c_char {
+  let context = generator_context((interaction.interaction_ref >> 16) as u16);
+  let res = interaction.with_interaction(&|_, _, inner| {
+    trace!("pactffi_sync_message_reify(interaction: {:?})", inner);
+    if let Some(message) = inner.as_v4_sync_message() {
+      let (request, responses) = block_on(apply_generators_to_sync_message(&message,
+        &GeneratorTestMode::Consumer, &context, &vec![], &hashmap!{}));
+      json!({
+        "request": reified_message_contents(request),
+        "response": responses.into_iter().map(reified_message_contents).collect::<Vec<_>>()
+      }).to_string()
+    } else {
+      error!("Interaction is not a synchronous message, is {}", inner.type_of());
+      "".to_string()
+    }
+  });
+
+  match res {
+    Some(res) => {
+      let string = CString::new(res).unwrap();
+      string.into_raw() as *const c_char
+    },
+    None => CString::default().into_raw() as *const c_char
+  }
+}
+
+/// Reifies the request or response of the given HTTP interaction, returning it as a JSON string.
+/// Any generators will be applied (in consumer mode, as the mock server does), and the matching
+/// rules and generators will be stripped. The values that the generators produced are recorded,
+/// and can be retrieved with `pactffi_interaction_generator_report`.
+///
+/// Returns an empty string if the interaction is not an HTTP interaction.
+///
+/// # Safety
+///
+/// The returned string needs to be deallocated with the `free_string` function.
+/// This function must only ever be called from a foreign language. Calling it from a Rust function
+/// that has a Tokio runtime in its call stack can result in a deadlock.
+#[no_mangle]
+pub extern fn pactffi_interaction_reify(
+  interaction: InteractionHandle,
+  part: InteractionPart
+) -> *const c_char {
+  let context = generator_context((interaction.interaction_ref >> 16) as u16);
+  let index = (interaction.interaction_ref & 0x0000FFFF) as u16;
+  let res = interaction.with_pact(&|_, inner| {
+    let http = index.checked_sub(1)
+      .and_then(|i| inner.pact.interactions.get(i as usize))
+      .map(|i| (i.as_v4_http(), i.type_of()));
+    match http {
+      Some((Some(http), _)) => {
+        trace!("pactffi_interaction_reify(interaction: {:?}, part: {:?})", http, part);
+        let (json, report) = match part {
+          InteractionPart::Request => {
+            let (request, report) = block_on(generate_request_with_report(&http.request,
+              &GeneratorTestMode::Consumer, &context));
+            (HttpRequest { matching_rules: MatchingRules::default(), generators: Generators::default(), .. request }.to_json(), report)
+          }
+          InteractionPart::Response => {
+            let (response, report) = block_on(generate_response_with_report(&http.response,
+              &GeneratorTestMode::Consumer, &context));
+            (HttpResponse { matching_rules: MatchingRules::default(), generators: Generators::default(), .. response }.to_json(), report)
+          }
+        };
+        inner.generator_reports.insert((index, part), report);
+        json.to_string()
+      }
+      Some((None, type_of)) => {
+        error!("Interaction is not an HTTP interaction, is {}", type_of);
+        "".to_string()
+      }
+      None => {
+        error!("Interaction handle {:#x} is not valid", interaction.interaction_ref);
+        "".to_string()
+      }
+    }
+  });
+
+  match res {
+    Some(res) => {
+      let string = CString::new(res).unwrap();
+      string.into_raw() as *const c_char
+    },
+    None => CString::default().into_raw() as *const c_char
+  }
+}
+
+/// Returns a report of the values that the generators produced the last time they were applied to
+/// the request or response of the given HTTP interaction with `pactffi_interaction_reify`, as a
+/// JSON string. This can be used to debug which generators were applied and the values they
+/// generated. The report is a JSON array, with an entry for each generator in the form
+/// `{"category": "body", "path": "$.id", "generator": {...}, "value": ...}`. The value will be
+/// null if it could not be determined (i.e. body generators with wildcard paths).
+///
+/// Returns an empty string if the generators have not been applied to the interaction part.
+///
+/// # Safety
+///
+/// The returned string needs to be deallocated with the `free_string` function.
+#[no_mangle]
+pub extern fn pactffi_interaction_generator_report(
+  interaction: InteractionHandle,
+  part: InteractionPart
+) -> *const c_char {
+  let index = (interaction.interaction_ref & 0x0000FFFF) as u16;
+  let res = interaction.with_pact(&|_, inner| {
+    trace!("pactffi_interaction_generator_report(interaction: {:#x}, part: {:?})", interaction.interaction_ref, part);
+    match inner.generator_reports.get(&(index, part)) {
+      Some(report) => report.to_json().to_string(),
+      None => {
+        debug!("The generators have not been applied to the {:?} of interaction {:#x}", part, interaction.interaction_ref);
+        "".to_string()
+      }
+    }
+  });
+
+  match res {
+    Some(res) => {
+      let string = CString::new(res).unwrap();
+      string.into_raw() as *const c_char
+    },
+    None => CString::default().into_raw() as *const c_char
+  }
+}
+
+fn reified_message_contents(contents: MessageContents) -> Value {
+  MessageContents {
+    matching_rules: MatchingRules::default(),
+    generators: Generators::default(),
+    .. contents
+  }.to_json()
+}
+
 /// External interface to write out the message pact file. This function should
 /// be called if all the consumer tests have passed. The directory to write the file to is passed
 /// as the second parameter. If a NULL pointer is passed, the current working directory is used.
@@ -2743,7 +3181,8 @@ ffi_fn! {
     let result = pact.with_pact(&|_, inner| {
       let pact_file = inner.pact.default_file_name();
       let filename = path_from_dir(directory, Some(pact_file.as_str()));
-      write_pact(inner.pact.boxed(), &filename.unwrap_or_else(|| PathBuf::from(pact_file.as_str())), inner.specification_version, overwrite)
+      write_pact_with_options(inner.pact.boxed(), &filename.unwrap_or_else(|| PathBuf::from(pact_file.as_str())),
+        inner.specification_version, overwrite, &inner.write_options)
     });
 
     match result {
@@ -2764,6 +3203,43 @@ ffi_fn! {
   }
 }
 
+/// Sets the options used to write the pact file for the Pact model with `pactffi_pact_handle_write_file`.
+/// Sorting the interactions and keys makes the contents of the pact file independent of the order
+/// the tests are run in, which avoids noisy diffs when the pact files are committed to source control.
+///
+/// * `pact` - Handle to a Pact model
+/// * `sort_interactions` - Sort the interactions by description, then provider states and then type
+/// * `sort_keys` - Sort the keys of all the objects in the pact file (i.e. headers, matching rules)
+/// * `indent` - Number of spaces to indent the JSON with (the default is 2). Zero will write the JSON on a single line.
+///
+/// Returns false if the Pact handle is not valid.
+#[no_mangle]
+pub extern fn pactffi_with_pact_write_options(pact: PactHandle, sort_interactions: bool, sort_keys: bool, indent: c_uint) -> bool {
+  pact.with_pact(&|_, inner| {
+    inner.write_options = WritePactOptions {
+      sort_interactions,
+      sort_keys,
+      indent: indent as usize
+    };
+  }).is_some()
+}
+
+/// Sets the seed for the random generators (i.e. RandomInt, Uuid, RandomString) used when the
+/// generators are applied to the interactions of the Pact model by `pactffi_message_reify`,
+/// `pactffi_sync_message_reify` and `pactffi_interaction_reify`. The same values are
+/// then generated each time the test is run. Note that the seed is not used by the mock server.
+///
+/// * `pact` - Handle to a Pact model
+/// * `seed` - Seed for the random generators
+///
+/// Returns false if the Pact handle is not valid.
+#[no_mangle]
+pub extern fn pactffi_with_generator_seed(pact: PactHandle, seed: u64) -> bool {
+  pact.with_pact(&|_, inner| {
+    inner.generator_seed = Some(seed);
+  }).is_some()
+}
+
 /// Creates a new V4 asynchronous message and returns a handle to it.
 ///
 /// * `description` - The message description. It needs to be unique for each Message.
@@ -2799,7 +3275,7 @@ pub extern fn pactffi_new_async_message(pact: PactHandle, description: *const c_
 ///
 #[no_mangle]
 pub extern fn pactffi_free_pact_handle(pact: PactHandle) -> c_uint {
-  let mut handles = PACT_HANDLES.lock().unwrap();
+  let mut handles = lock_pact_handles();
   trace!("pactffi_free_pact_handle - removing pact with index {}", pact.pact_ref);
   handles.remove(&pact.pact_ref).map(|_| 0).unwrap_or(1)
 }
@@ -2814,7 +3290,7 @@ pub extern fn pactffi_free_pact_handle(pact: PactHandle) -> c_uint {
 ///
 #[no_mangle]
 pub extern fn pactffi_free_message_pact_handle(pact: MessagePactHandle) -> c_uint {
-  let mut handles = PACT_HANDLES.lock().unwrap();
+  let mut handles = lock_pact_handles();
   handles.remove(&pact.pact_ref).map(|_| 0).unwrap_or(1)
 }
 
@@ -2834,7 +3310,7 @@ mod tests {
   use maplit::hashmap;
   use pact_models::content_types::JSON;
   use pact_models::{generators, matchingrules, HttpStatus};
-  use pact_models::matchingrules::{Category, MatchingRule};
+  use pact_models::matchingrules::{Category, MatchingRule, RuleList};
   use pact_models::path_exp::DocPath;
   use pact_models::prelude::{Generators, MatchingRules};
   use pretty_assertions::assert_eq;
@@ -2878,6 +3354,89 @@ mod tests {
     pactffi_free_pact_handle(pact_handle);
   }
 
+  #[test]
+  fn pact_handles_are_not_reused_after_being_freed() {
+    let pact_handle = PactHandle::new("TestHandlesC", "TestHandlesP");
+    expect!(pactffi_free_pact_handle(pact_handle)).to(be_equal_to(0));
+
+    let pact_handle2 = PactHandle::new("TestHandlesC", "TestHandlesP");
+    expect!(pact_handle2.pact_ref).to_not(be_equal_to(pact_handle.pact_ref));
+    expect!(pact_handle.with_pact(&|_, _| ())).to(be_none());
+
+    let description = CString::new("first interaction").unwrap();
+    let i_handle = pactffi_new_interaction(pact_handle, description.as_ptr());
+    expect!(i_handle.with_interaction(&|_, _, _| ())).to(be_none());
+    pact_handle2.with_pact(&|_, inner| {
+      expect!(inner.pact.interactions.len()).to(be_equal_to(0));
+    });
+
+    pactffi_free_pact_handle(pact_handle2);
+  }
+
+  #[test]
+  fn zero_interaction_handle_does_not_refer_to_an_interaction() {
+    let pact_handle = PactHandle::new("TestHandlesC", "TestHandlesP");
+    let description = CString::new("first interaction").unwrap();
+    pactffi_new_interaction(pact_handle, description.as_ptr());
+
+    let i_handle = InteractionHandle::new(pact_handle, 0);
+    expect!(i_handle.with_interaction(&|_, _, _| ())).to(be_none());
+    expect!(pactffi_upon_receiving(i_handle, description.as_ptr())).to(be_false());
+
+    pactffi_free_pact_handle(pact_handle);
+  }
+
+  #[test]
+  fn pact_handles_can_be_used_after_a_panic_in_another_call() {
+    let pact_handle = PactHandle::new("TestHandlesC", "TestHandlesP");
+
+    let result = std::panic::catch_unwind(|| {
+      let _: Option<()> = pact_handle.with_pact(&|_, _| panic!("boom"));
+    });
+    expect!(result.is_err()).to(be_true());
+
+    let description = CString::new("first interaction").unwrap();
+    let i_handle = pactffi_new_interaction(pact_handle, description.as_ptr());
+    expect!(i_handle.interaction_ref).to(be_equal_to(((pact_handle.pact_ref as u32) << 16) + 1));
+
+    pactffi_free_pact_handle(pact_handle);
+  }
+
+  #[test]
+  fn pact_handles_can_be_used_concurrently() {
+    let threads = (0..8).map(|t| {
+      std::thread::spawn(move || {
+        let pact_handle = PactHandle::new(format!("TestConcurrentC{}", t).as_str(), "TestConcurrentP");
+        for i in 0..20 {
+          let description = CString::new(format!("interaction {} from thread {}", i, t)).unwrap();
+          let i_handle = pactffi_new_interaction(pact_handle, description.as_ptr());
+          let state = CString::new(format!("state {}", i)).unwrap();
+          let method = CString::new("GET").unwrap();
+          let path = CString::new(format!("/{}/{}", t, i)).unwrap();
+          expect!(pactffi_given(i_handle, state.as_ptr())).to(be_true());
+          expect!(pactffi_with_request(i_handle, method.as_ptr(), path.as_ptr())).to(be_true());
+        }
+        pact_handle
+      })
+    }).collect::<Vec<_>>();
+
+    for (t, thread) in threads.into_iter().enumerate() {
+      let pact_handle = thread.join().unwrap();
+      pact_handle.with_pact(&|_, inner| {
+        expect!(inner.pact.consumer.name.clone()).to(be_equal_to(format!("TestConcurrentC{}", t)));
+        expect!(inner.pact.interactions.len()).to(be_equal_to(20));
+        for (i, interaction) in inner.pact.interactions.iter().enumerate() {
+          let http = interaction.as_v4_http().unwrap();
+          expect!(http.description).to(be_equal_to(format!("interaction {} from thread {}", i, t)));
+          expect!(http.provider_states.iter().map(|s| s.name.clone()).collect::<Vec<_>>())
+            .to(be_equal_to(vec![format!("state {}", i)]));
+          expect!(http.request.path).to(be_equal_to(format!("/{}/{}", t, i)));
+        }
+      });
+      pactffi_free_pact_handle(pact_handle);
+    }
+  }
+
   #[test]
   fn simple_query_parameter() {
     let pact_handle = PactHandle::new("TestC1", "TestP");
@@ -3866,4 +4425,169 @@ mod tests {
       }
     });
   }
+
+  #[test]
+  fn pactffi_with_generators_and_reify_for_sync_messages_test() {
+    let pact_handle = PactHandle::new("Consumer", "Provider");
+    let description = CString::new("Sync Message Test").unwrap();
+    let i_handle = pactffi_new_sync_message_interaction(pact_handle, description.as_ptr());
+
+    let content_type = CString::new("application/json").unwrap();
+    let request = CString::new(r#"{"action": "get-item", "id": 10}"#).unwrap();
+    let response = CString::new(r#"{"id": 10, "name": "Item"}"#).unwrap();
+    expect!(pactffi_with_body(i_handle, InteractionPart::Request, content_type.as_ptr(), request.as_ptr())).to(be_true());
+    expect!(pactffi_with_body(i_handle, InteractionPart::Response, content_type.as_ptr(), response.as_ptr())).to(be_true());
+
+    let generators = CString::new(r#"{
+      "body": {
+        "$.id": { "type": "RandomInt", "min": 100, "max": 200 }
+      }
+    }"#).unwrap();
+    expect!(pactffi_with_generators(i_handle, InteractionPart::Response, generators.as_ptr())).to(be_true());
+
+    let invalid = CString::new("[]").unwrap();
+    expect!(pactffi_with_generators(i_handle, InteractionPart::Response, invalid.as_ptr())).to(be_false());
+
+    let interaction = i_handle.with_interaction(&|_, _, inner| {
+      inner.as_v4_sync_message().unwrap()
+    }).unwrap();
+    expect!(interaction.request.generators.is_empty()).to(be_true());
+    assert_eq!(interaction.response[0].generators, generators! {
+      "body" => { "$.id" => Generator::RandomInt(100, 200) }
+    });
+
+    let reified = pactffi_sync_message_reify(i_handle);
+    let reified = unsafe { CString::from_raw(reified as *mut _) };
+    let json: serde_json::Value = serde_json::from_str(reified.to_str().unwrap()).unwrap();
+    pactffi_free_pact_handle(pact_handle);
+
+    expect!(json["request"]["contents"]["content"].clone()).to(be_equal_to(serde_json::json!({
+      "action": "get-item",
+      "id": 10
+    })));
+    let id = json["response"][0]["contents"]["content"]["id"].as_u64().unwrap();
+    expect!(id).to(be_greater_or_equal_to(100));
+    expect!(id).to(be_less_or_equal_to(200));
+    expect!(json["response"][0].get("generators")).to(be_none());
+  }
+
+  #[test]
+  fn pactffi_interaction_generator_report_test() {
+    let pact_handle = PactHandle::new("Consumer", "Provider");
+    let description = CString::new("Generator report test").unwrap();
+    let i_handle = pactffi_new_interaction(pact_handle, description.as_ptr());
+
+    let content_type = CString::new("application/json").unwrap();
+    let response = CString::new(r#"{"id": 10, "name": "Item"}"#).unwrap();
+    expect!(pactffi_with_body(i_handle, InteractionPart::Response, content_type.as_ptr(), response.as_ptr())).to(be_true());
+
+    let generators = CString::new(r#"{
+      "body": {
+        "$.id": { "type": "RandomInt", "min": 100, "max": 200 }
+      }
+    }"#).unwrap();
+    expect!(pactffi_with_generators(i_handle, InteractionPart::Response, generators.as_ptr())).to(be_true());
+
+    let empty_report = pactffi_interaction_generator_report(i_handle, InteractionPart::Response);
+    let empty_report = unsafe { CString::from_raw(empty_report as *mut _) };
+
+    let response = pactffi_interaction_reify(i_handle, InteractionPart::Response);
+    let response = unsafe { CString::from_raw(response as *mut _) };
+    let response: serde_json::Value = serde_json::from_str(response.to_str().unwrap()).unwrap();
+
+    let report = pactffi_interaction_generator_report(i_handle, InteractionPart::Response);
+    let report = unsafe { CString::from_raw(report as *mut _) };
+    let json: serde_json::Value = serde_json::from_str(report.to_str().unwrap()).unwrap();
+
+    let request = pactffi_interaction_reify(i_handle, InteractionPart::Request);
+    unsafe { drop(CString::from_raw(request as *mut _)) };
+    let request_report = pactffi_interaction_generator_report(i_handle, InteractionPart::Request);
+    let request_report = unsafe { CString::from_raw(request_report as *mut _) };
+    pactffi_free_pact_handle(pact_handle);
+
+    expect!(empty_report.to_str().unwrap()).to(be_equal_to(""));
+    expect!(response.get("generators")).to(be_none());
+    expect!(response.get("matchingRules")).to(be_none());
+    expect!(response["body"]["content"]["id"].clone()).to(be_equal_to(json[0]["value"].clone()));
+
+    expect!(json[0]["category"].clone()).to(be_equal_to(serde_json::json!("body")));
+    expect!(json[0]["path"].clone()).to(be_equal_to(serde_json::json!("$.id")));
+    expect!(json[0]["generator"].clone()).to(be_equal_to(serde_json::json!({
+      "type": "RandomInt", "min": 100, "max": 200
+    })));
+    let id = json[0]["value"].as_u64().unwrap();
+    expect!(id).to(be_greater_or_equal_to(100));
+    expect!(id).to(be_less_or_equal_to(200));
+    expect!(request_report.to_str().unwrap()).to(be_equal_to("[]"));
+  }
+
+  #[test]
+  fn pactffi_with_generator_seed_test() {
+    let pact_handle = PactHandle::new("Consumer", "Provider");
+    let description = CString::new("Generator seed test").unwrap();
+    let i_handle = pactffi_new_interaction(pact_handle, description.as_ptr());
+
+    let content_type = CString::new("application/json").unwrap();
+    let response = CString::new(r#"{"id": 10}"#).unwrap();
+    expect!(pactffi_with_body(i_handle, InteractionPart::Response, content_type.as_ptr(), response.as_ptr())).to(be_true());
+    let generators = CString::new(r#"{
+      "body": {
+        "$.id": { "type": "RandomInt", "min": 0, "max": 1000000 }
+      }
+    }"#).unwrap();
+    expect!(pactffi_with_generators(i_handle, InteractionPart::Response, generators.as_ptr())).to(be_true());
+    expect!(pactffi_with_generator_seed(pact_handle, 1234)).to(be_true());
+
+    let generated_value = || {
+      let response = pactffi_interaction_reify(i_handle, InteractionPart::Response);
+      unsafe { drop(CString::from_raw(response as *mut _)) };
+      let report = pactffi_interaction_generator_report(i_handle, InteractionPart::Response);
+      let report = unsafe { CString::from_raw(report as *mut _) };
+      let json: serde_json::Value = serde_json::from_str(report.to_str().unwrap()).unwrap();
+      json[0]["value"].clone()
+    };
+    let value = generated_value();
+    let value2 = generated_value();
+    pactffi_free_pact_handle(pact_handle);
+
+    expect!(value.is_u64()).to(be_true());
+    expect!(value2).to(be_equal_to(value));
+    expect!(pactffi_with_generator_seed(pact_handle, 1234)).to(be_false());
+  }
+
+  #[test]
+  fn pactffi_message_with_metadata_rule_test() {
+    let pact_handle = PactHandle::new("TestMetadataC", "TestMetadataP");
+    let description = CString::new("message with metadata rules").unwrap();
+    #[allow(deprecated)]
+    let message_handle = pactffi_new_async_message(pact_handle, description.as_ptr());
+
+    let key = CString::new("Correlation-Id").unwrap();
+    let value = CString::new("\"1234567890abcdef\"").unwrap();
+    pactffi_message_with_metadata_v2(message_handle, key.as_ptr(), value.as_ptr());
+    let rule = CString::new(r#"{"pact:matcher:type": "regex", "regex": "[0-9a-f]{16}"}"#).unwrap();
+    expect!(pactffi_message_with_metadata_rule(message_handle, key.as_ptr(), rule.as_ptr())).to(be_true());
+
+    let key2 = CString::new("Retries").unwrap();
+    let rule2 = CString::new("matching(integer, 3)").unwrap();
+    expect!(pactffi_message_with_metadata_rule(message_handle, key2.as_ptr(), rule2.as_ptr())).to(be_true());
+
+    let invalid = CString::new("matching(blah)").unwrap();
+    expect!(pactffi_message_with_metadata_rule(message_handle, key2.as_ptr(), invalid.as_ptr())).to(be_false());
+    let reference = CString::new("matching($'item')").unwrap();
+    expect!(pactffi_message_with_metadata_rule(message_handle, key2.as_ptr(), reference.as_ptr())).to(be_false());
+
+    message_handle.with_message(&|_, inner, _| {
+      let message = inner.as_v4_async_message().unwrap();
+      expect!(message.contents.metadata.get("Correlation-Id").cloned()).to(be_some().value(json!("1234567890abcdef")));
+      expect!(message.contents.metadata.get("Retries").cloned()).to(be_some().value(json!(3)));
+      let rules = message.contents.matching_rules.rules_for_category("metadata").unwrap();
+      expect!(rules.rules.get(&DocPath::new_unwrap("$.Correlation-Id")).cloned())
+        .to(be_some().value(RuleList::new(MatchingRule::Regex("[0-9a-f]{16}".to_string()))));
+      expect!(rules.rules.get(&DocPath::new_unwrap("$.Retries")).cloned())
+        .to(be_some().value(RuleList::new(MatchingRule::Integer)));
+    });
+
+    pactffi_free_pact_handle(pact_handle);
+  }
 }
@@ -22,6 +22,12 @@
 //! code using the mock server is complete. The [`cleanup_mock_server`](fn.cleanup_mock_server.html) function is provided for this purpose.
 //! If the mock server is not cleaned up properly, this will result in memory leaks as the Rust heap will not be reclaimed.
 //!
+//! ## [mock_server_matched_interactions](fn.pactffi_mock_server_matched_interactions.html)
+//!
+//! This returns each interaction of the mock server in JSON format, with the number of times it
+//! was matched, given the port number of the mock server. This allows a test to check how many
+//! times a request was made, not just that it was made.
+//!
 //! ## [cleanup_mock_server](fn.cleanup_mock_server.html)
 //!
 //! This function will try terminate the mock server with the given port number and cleanup any memory allocated for it by
@@ -43,40 +49,49 @@
 
 #![warn(missing_docs)]
 
-use std::{ptr, str};
+use std::{env, ptr, str};
 use std::any::Any;
+use std::collections::HashSet;
 use std::ffi::CStr;
 use std::ffi::CString;
 use std::net::ToSocketAddrs;
+use std::path::PathBuf;
 use std::panic::catch_unwind;
 use std::str::from_utf8;
+use std::sync::Mutex;
 
 use chrono::Local;
 use either::Either;
-use libc::c_char;
+use lazy_static::lazy_static;
+use libc::{c_char, c_uint};
 use onig::Regex;
-use pact_models::pact::Pact;
-use pact_models::time_utils::{parse_pattern, to_chrono_pattern};
+use pact_models::pact::{Pact, write_pact_with_options, WritePactOptions};
+use pact_models::datetime_format::to_chrono_pattern;
 use rand::prelude::*;
-use serde_json::Value;
+use serde_json::{json, Value};
 use tokio_rustls::rustls::ServerConfig;
-use tracing::{error, warn};
+use tracing::{debug, error, warn};
 use uuid::Uuid;
 
 use pact_matching::logging::fetch_buffer_contents;
 use pact_matching::metrics::{MetricEvent, send_metrics};
 use pact_mock_server::{MANAGER, mock_server_mismatches, MockServerError, tls::TlsConfigBuilder, WritePactFileErr};
+use pact_mock_server::matching::MatchResult;
 use pact_mock_server::mock_server::MockServerConfig;
 use pact_mock_server::server_manager::ServerManager;
 use pact_models::generators::GeneratorCategory;
+use pact_models::v4::interaction::V4Interaction;
 use pact_models::matchingrules::{Category, MatchingRuleCategory};
 
 use crate::{convert_cstr, ffi_fn, safe_str};
+use crate::error::set_error_msg;
 use crate::mock_server::handles::{PactHandle, path_from_dir};
+use crate::mock_server::tls::TlsOptions;
 use crate::string::optional_str;
 
 pub mod handles;
 pub mod bodies;
+mod tls;
 mod xml;
 
 /// [DEPRECATED] External interface to create a HTTP mock server. A pointer to the pact JSON as a NULL-terminated C
@@ -272,6 +287,31 @@ fn setup_tls_config(tls: bool) -> Result<Option<ServerConfig>, i32> {
   }
 }
 
+/// Sets up the TLS configuration for the https transport if any TLS options have been provided
+/// in the transport config
+fn setup_tls_options(transport: &str, transport_config: &Value) -> Result<Option<ServerConfig>, i32> {
+  match TlsOptions::from_json(transport_config) {
+    Ok(Some(options)) => if transport.eq_ignore_ascii_case("https") {
+      match setup_tls_config(true)?.map(|config| options.apply(&config)) {
+        Some(Ok(tls_config)) => Ok(Some(tls_config)),
+        Some(Err(err)) => {
+          error!("Failed to build TLS configuration - {}", err);
+          Err(-6)
+        }
+        None => Ok(None)
+      }
+    } else {
+      warn!("TLS options are only supported with the https transport, ignoring them for the '{}' transport", transport);
+      Ok(None)
+    },
+    Ok(None) => Ok(None),
+    Err(err) => {
+      error!("Invalid TLS options in the transport config - {}", err);
+      Err(-6)
+    }
+  }
+}
+
 ffi_fn! {
   /// Create a mock server for the provided Pact handle and transport. If the transport is not
   /// provided (it is a NULL pointer or an empty string), will default to an HTTP transport. The
@@ -287,6 +327,16 @@ ffi_fn! {
   ///
   /// The port of the mock server is returned.
   ///
+  /// # TLS options
+  ///
+  /// For the https transport, the TLS protocol versions and cipher suites offered by the mock server
+  /// can be restricted with the following transport config attributes:
+  /// * `tlsMinVersion` - Minimum TLS protocol version to offer (`TLS1.2` or `TLS1.3`).
+  /// * `tlsCipherSuites` - List of cipher suites to offer, using the IANA names (i.e. `TLS13_AES_256_GCM_SHA384`
+  ///   or `TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256`). Can also be a comma separated string.
+  ///
+  /// For example: `{ "tlsMinVersion": "TLS1.3", "tlsCipherSuites": ["TLS13_AES_256_GCM_SHA384"] }`
+  ///
   /// # Safety
   /// NULL pointers or empty strings can be passed in for the address, transport and transport_config,
   /// in which case a default value will be used. Passing in an invalid pointer will result in undefined behaviour.
@@ -302,6 +352,7 @@ ffi_fn! {
   /// | -3 | The mock server could not be started |
   /// | -4 | The method panicked |
   /// | -5 | The address is not valid |
+  /// | -6 | The TLS options are not valid, or the TLS configuration could not be created |
   ///
   #[tracing::instrument(level = "trace")]
   fn pactffi_create_mock_server_for_transport(
@@ -317,16 +368,22 @@ ffi_fn! {
     let transport_config = match optional_str(transport_config).map(|config| str::parse::<Value>(config.as_str())) {
       None => Ok(None),
       Some(result) => match result {
-        Ok(value) => Ok(Some(MockServerConfig::from_json(&value))),
+        Ok(value) => Ok(Some(value)),
         Err(err) => {
           error!("Failed to parse transport_config as JSON - {}", err);
           Err(-2)
         }
       }
     };
+    let tls_config = match &transport_config {
+      Ok(Some(value)) => setup_tls_options(transport, value),
+      _ => Ok(None)
+    };
+    let transport_config = transport_config
+      .map(|config| config.map(|value| MockServerConfig::from_json(&value)));
 
-    match transport_config {
-      Ok(transport_config) => if let Ok(mut socket_addr) = (addr, port).to_socket_addrs() {
+    match (transport_config, tls_config) {
+      (Ok(transport_config), Ok(tls_config)) => if let Ok(mut socket_addr) = (addr, port).to_socket_addrs() {
         // Seems ok to unwrap this here, as it doesn't make sense that to_socket_addrs will return
         // a success with an iterator that is empty
         let socket_addr = socket_addr.next().unwrap();
@@ -337,8 +394,15 @@ ffi_fn! {
             .. transport_config.unwrap_or_default()
           };
 
-          match pact_mock_server::start_mock_server_for_transport(Uuid::new_v4().to_string(),
-            inner.pact.boxed(), socket_addr, transport, config) {
+          let server_result = match &tls_config {
+            Some(tls_config) => pact_mock_server::start_tls_mock_server_with_config(
+              Uuid::new_v4().to_string(), inner.pact.boxed(), socket_addr, tls_config, config)
+              .map_err(|err| err.to_string()),
+            None => pact_mock_server::start_mock_server_for_transport(Uuid::new_v4().to_string(),
+              inner.pact.boxed(), socket_addr, transport, config)
+              .map_err(|err| err.to_string())
+          };
+          match server_result {
             Ok(ms_port) => {
               inner.mock_server_started = true;
               ms_port
@@ -353,7 +417,7 @@ ffi_fn! {
         error!("Failed to parse '{}', {} as an address", addr, port);
         -5
       }
-      Err(err) => err
+      (Err(err), _) | (_, Err(err)) => err
     }
   } {
     -4
@@ -418,6 +482,122 @@ pub extern fn pactffi_mock_server_mismatches(mock_server_port: i32) -> *mut c_ch
   }
 }
 
+/// External interface to get the interactions of a mock server, and the number of times each one
+/// was matched. The port number of the mock server is passed in, and a pointer to a C string with
+/// the interactions in JSON format is returned.
+///
+/// An interaction is `matched` if at least one request matched it and no requests for it had
+/// mismatches. Only HTTP interactions are included.
+///
+/// ```json
+/// {
+///   "matched": false,
+///   "interactions": [
+///     {
+///       "key": "296966511eff3f2e",
+///       "description": "a request for a user",
+///       "providerStates": ["a user exists"],
+///       "matched": true,
+///       "matchedCount": 2,
+///       "mismatchedCount": 0
+///     }
+///   ],
+///   "unexpectedRequests": 1
+/// }
+/// ```
+///
+/// **NOTE:** The JSON string for the result is allocated on the heap, and will have to be freed
+/// once the code using the mock server is complete. The [`cleanup_mock_server`](fn.cleanup_mock_server.html) function is
+/// provided for this purpose.
+///
+/// # Errors
+///
+/// If there is no mock server with the provided port number, the mock server is provided by a
+/// plugin, or the function panics, a NULL pointer will be returned.
+#[no_mangle]
+pub extern fn pactffi_mock_server_matched_interactions(mock_server_port: i32) -> *mut c_char {
+  let result = catch_unwind(|| {
+    let result = pact_mock_server::find_mock_server_by_port(mock_server_port as u16, &|_, _, mock_server| {
+      match mock_server {
+        Either::Left(ms) => Some(matched_interactions_json(ms.pact.as_ref(), &ms.matches()).to_string()),
+        Either::Right(_) => {
+          warn!("The matched interactions are not available for mock servers provided by plugins");
+          None
+        }
+      }
+    }).flatten();
+    match result {
+      Some(str) => {
+        let s = CString::new(str).unwrap();
+        let p = s.as_ptr() as *mut _;
+        MANAGER.lock().unwrap()
+          .get_or_insert_with(ServerManager::new)
+          .store_mock_server_resource(mock_server_port as u16, s);
+        p
+      },
+      None => std::ptr::null_mut()
+    }
+  });
+
+  match result {
+    Ok(val) => val,
+    Err(cause) => {
+      error!("{}", error_message(cause, "mock_server_matched_interactions"));
+      std::ptr::null_mut()
+    }
+  }
+}
+
+/// Returns the HTTP interactions of the Pact with the number of requests that matched and did not
+/// match each one. Each match result is attributed to the first interaction (by index) with the
+/// same expected request, so interactions that share a request are not counted more than once.
+fn matched_interactions_json(pact: &(dyn Pact + Send + Sync), matches: &[MatchResult]) -> Value {
+  let http_interactions = pact.interactions().iter()
+    .filter_map(|interaction| interaction.as_v4_http())
+    .collect::<Vec<_>>();
+  let mut matched_counts = vec![0_usize; http_interactions.len()];
+  let mut mismatched_counts = vec![0_usize; http_interactions.len()];
+  for result in matches {
+    match result {
+      MatchResult::RequestMatch(request, response, _) => {
+        if let Some(index) = http_interactions.iter()
+          .position(|interaction| interaction.request == *request && interaction.response == *response) {
+          matched_counts[index] += 1;
+        }
+      }
+      MatchResult::RequestMismatch(request, _, _) => {
+        if let Some(index) = http_interactions.iter()
+          .position(|interaction| interaction.request == *request) {
+          mismatched_counts[index] += 1;
+        }
+      }
+      _ => {}
+    }
+  }
+  let interactions = http_interactions.iter().enumerate()
+    .map(|(index, interaction)| {
+      let matched_count = matched_counts[index];
+      let mismatched_count = mismatched_counts[index];
+      json!({
+        "key": interaction.key.clone().unwrap_or_else(|| interaction.unique_key()),
+        "description": interaction.description,
+        "providerStates": interaction.provider_states.iter().map(|state| state.name.clone()).collect::<Vec<_>>(),
+        "matched": matched_count > 0 && mismatched_count == 0,
+        "matchedCount": matched_count,
+        "mismatchedCount": mismatched_count
+      })
+    })
+    .collect::<Vec<_>>();
+  let unexpected_requests = matches.iter()
+    .filter(|result| matches!(result, MatchResult::RequestNotFound(_)))
+    .count();
+  json!({
+    "matched": unexpected_requests == 0 && interactions.iter().all(|interaction| interaction["matched"] == json!(true)),
+    "interactions": interactions,
+    "unexpectedRequests": unexpected_requests
+  })
+}
+
 /// External interface to cleanup a mock server. This function will try terminate the mock server
 /// with the given port number and cleanup any memory allocated for it. Returns true, unless a
 /// mock server with the given port number does not exist, or the function panics.
@@ -499,6 +679,215 @@ pub extern fn pactffi_write_pact_file(mock_server_port: i32, directory: *const c
   }
 }
 
+/// External interface to trigger a mock server to write out its pact file, using the provided
+/// options to control the order and formatting of the pact file. Sorting the interactions and keys
+/// makes the contents of the pact file independent of the order the tests are run in, which avoids
+/// noisy diffs when the pact files are committed to source control. Otherwise, this is the same as
+/// `pactffi_write_pact_file`.
+///
+/// * `mock_server_port` - Port of the running mock server
+/// * `directory` - Directory to write the pact file to. If NULL, the current working directory is used.
+/// * `overwrite` - If the pact file should be overwritten, otherwise it will be merged with any existing pact file
+/// * `sort_interactions` - Sort the interactions by description, then provider states and then type
+/// * `sort_keys` - Sort the keys of all the objects in the pact file (i.e. headers, matching rules)
+/// * `indent` - Number of spaces to indent the JSON with (the default is 2). Zero will write the JSON on a single line.
+///
+/// # Errors
+///
+/// Errors are returned as positive values.
+///
+/// | Error | Description |
+/// |-------|-------------|
+/// | 1 | A general panic was caught |
+/// | 2 | The pact file was not able to be written |
+/// | 3 | A mock server with the provided port was not found |
+#[no_mangle]
+pub extern fn pactffi_write_pact_file_with_options(
+  mock_server_port: i32,
+  directory: *const c_char,
+  overwrite: bool,
+  sort_interactions: bool,
+  sort_keys: bool,
+  indent: c_uint
+) -> i32 {
+  let result = catch_unwind(|| {
+    let options = WritePactOptions {
+      sort_interactions,
+      sort_keys,
+      indent: indent as usize
+    };
+    pact_mock_server::find_mock_server_by_port(mock_server_port as u16, &|_, _, mock_server| {
+      let pact = match mock_server {
+        Either::Left(ms) => ms.pact.boxed(),
+        Either::Right(ms) => ms.pact.boxed()
+      };
+      let pact_file = pact.default_file_name();
+      let filename = path_from_dir(directory, Some(pact_file.as_str()))
+        .unwrap_or_else(|| PathBuf::from(pact_file.as_str()));
+      let specification = pact.specification_version();
+      write_pact_with_options(pact, &filename, specification, overwrite, &options)
+    })
+  });
+
+  match result {
+    Ok(val) => match val {
+      Some(Ok(_)) => 0,
+      Some(Err(err)) => {
+        error!("Failed to write the pact file - {}", err);
+        2
+      }
+      None => 3
+    },
+    Err(cause) => {
+      error!("{}", error_message(cause, "write_pact_file_with_options"));
+      1
+    }
+  }
+}
+
+/// Mode to use when writing a pact file with `pactffi_write_pact_file_v2`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WritePactFileMode {
+  /// Merge the interactions with any existing pact file
+  Merge,
+  /// Replace any existing pact file with the contents of the current pact
+  Overwrite,
+  /// Replace any existing pact file the first time it is written by this process, and merge with
+  /// it after that. This removes any stale interactions from previous test runs, while still
+  /// allowing multiple mock servers in the same test run to write to the same pact file.
+  Clean
+}
+
+/// Options for writing a pact file with `pactffi_write_pact_file_v2`
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct WritePactFileOptions {
+  /// How to treat any existing pact file
+  pub mode: WritePactFileMode,
+  /// Directory to write the pact file to. If NULL or empty, the current working directory is used.
+  pub directory: *const c_char,
+  /// File name to use for the pact file. If NULL or empty, the default file name
+  /// (`<consumer>-<provider>.json`) is used.
+  pub filename: *const c_char,
+  /// Sort the interactions by description, then provider states and then type
+  pub sort_interactions: bool,
+  /// Sort the keys of all the objects in the pact file (i.e. headers, matching rules)
+  pub sort_keys: bool,
+  /// Number of spaces to indent the JSON with. Zero will write the JSON on a single line.
+  pub indent: c_uint
+}
+
+lazy_static! {
+  static ref CLEANED_PACT_FILES: Mutex<HashSet<PathBuf>> = Mutex::new(HashSet::new());
+}
+
+/// External interface to trigger a mock server to write out its pact file, using the provided
+/// options. This is the same as `pactffi_write_pact_file`, except that the way any existing pact
+/// file is treated is explicit, the file name can be overridden and the absolute path of the
+/// written file is returned.
+///
+/// * `mock_server_port` - Port of the running mock server
+/// * `options` - Options to use to write the pact file. If NULL, the pact file is merged with any
+///   existing file with the default file name in the current working directory, and is written
+///   with the default order and formatting (unsorted, indented with 2 spaces).
+/// * `path` - If not NULL, will be set to the absolute path of the written pact file. The string
+///   must be freed with `pactffi_string_delete`. It will not be set if the file could not be written.
+///
+/// Returns 0 if the pact file was successfully written, otherwise a positive error code. The
+/// error message can be retrieved with `pactffi_get_error_message`.
+///
+/// # Errors
+///
+/// Errors are returned as positive values.
+///
+/// | Error | Description |
+/// |-------|-------------|
+/// | 1 | A general panic was caught |
+/// | 2 | The pact file was not able to be written |
+/// | 3 | A mock server with the provided port was not found |
+///
+/// # Safety
+///
+/// The options pointer must be NULL or point to a valid `WritePactFileOptions` struct, and the
+/// strings in it must be NULL or valid NULL-terminated strings. The path pointer must be NULL or
+/// a valid pointer to a string pointer.
+#[no_mangle]
+pub unsafe extern fn pactffi_write_pact_file_v2(
+  mock_server_port: i32,
+  options: *const WritePactFileOptions,
+  path: *mut *mut c_char
+) -> i32 {
+  let result = catch_unwind(|| {
+    let (mode, directory, filename, write_options) = match options.as_ref() {
+      Some(options) => (options.mode, optional_str(options.directory), optional_str(options.filename),
+        WritePactOptions {
+          sort_interactions: options.sort_interactions,
+          sort_keys: options.sort_keys,
+          indent: options.indent as usize
+        }),
+      None => (WritePactFileMode::Merge, None, None, WritePactOptions::default())
+    };
+    pact_mock_server::find_mock_server_by_port(mock_server_port as u16, &|_, _, mock_server| {
+      let pact = match mock_server {
+        Either::Left(ms) => ms.pact.boxed(),
+        Either::Right(ms) => ms.pact.boxed()
+      };
+      let filename = filename.clone().unwrap_or_else(|| pact.default_file_name());
+      let mut pact_file = match &directory {
+        Some(dir) => PathBuf::from(dir),
+        None => env::current_dir()?
+      };
+      pact_file.push(filename);
+      if pact_file.is_relative() {
+        pact_file = env::current_dir()?.join(pact_file);
+      }
+
+      let overwrite = match mode {
+        WritePactFileMode::Merge => false,
+        WritePactFileMode::Overwrite => true,
+        WritePactFileMode::Clean => {
+          let mut cleaned = CLEANED_PACT_FILES.lock().unwrap_or_else(|err| err.into_inner());
+          cleaned.insert(pact_file.clone())
+        }
+      };
+      debug!("Writing pact file {:?} with mode {:?} (overwrite = {})", pact_file, mode, overwrite);
+      let specification = pact.specification_version();
+      write_pact_with_options(pact, &pact_file, specification, overwrite, &write_options)
+        .map(|_| pact_file)
+    })
+  });
+
+  match result {
+    Ok(val) => match val {
+      Some(Ok(pact_file)) => {
+        if !path.is_null() {
+          *path = CString::new(pact_file.to_string_lossy().to_string()).unwrap_or_default().into_raw();
+        }
+        0
+      }
+      Some(Err(err)) => {
+        let message = format!("Failed to write the pact file - {}", err);
+        error!("{}", message);
+        set_error_msg(message);
+        2
+      }
+      None => {
+        let message = format!("No mock server found for port {}", mock_server_port);
+        error!("{}", message);
+        set_error_msg(message);
+        3
+      }
+    },
+    Err(cause) => {
+      let message = error_message(cause, "write_pact_file_v2");
+      error!("{}", message);
+      set_error_msg(message);
+      1
+    }
+  }
+}
+
 /// Fetch the logs for the mock server. This needs the memory buffer log sink to be setup before
 /// the mock server is started. Returned string will be freed with the `cleanup_mock_server`
 /// function call.
@@ -584,14 +973,14 @@ pub unsafe extern fn pactffi_generate_datetime_string(format: *const c_char) ->
   } else {
     let c_str = CStr::from_ptr(format);
     match c_str.to_str() {
-      Ok(s) => match parse_pattern(s) {
-        Ok(pattern_tokens) => {
-          let result = Local::now().format(to_chrono_pattern(&pattern_tokens).as_str()).to_string();
+      Ok(s) => match to_chrono_pattern(s) {
+        Ok(chrono_pattern) => {
+          let result = Local::now().format(chrono_pattern.as_str()).to_string();
           let result_str = CString::new(result.as_str()).unwrap();
           StringResult::Ok(result_str.into_raw())
         },
         Err(err) => {
-          let error = format!("Error parsing '{}': {:?}", s, err);
+          let error = format!("Error parsing '{}': {}", s, err);
           let error_str = CString::new(error.as_str()).unwrap();
           StringResult::Failed(error_str.into_raw())
         }
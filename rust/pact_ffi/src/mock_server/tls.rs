@@ -0,0 +1,168 @@
+//! Options for restricting the TLS protocol versions and cipher suites offered by a TLS mock server
+
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use itertools::Itertools;
+use serde_json::Value;
+use tokio_rustls::rustls::{ALL_CIPHER_SUITES, ServerConfig, SupportedCipherSuite, SupportedProtocolVersion};
+use tokio_rustls::rustls::version::{TLS12, TLS13};
+
+/// Transport config key for the minimum TLS protocol version
+pub const TLS_MIN_VERSION_KEY: &str = "tlsMinVersion";
+/// Transport config key for the list of TLS cipher suites
+pub const TLS_CIPHER_SUITES_KEY: &str = "tlsCipherSuites";
+
+/// TLS options that can be provided in the transport config of the mock server
+#[derive(Debug, Clone, Default)]
+pub struct TlsOptions {
+  /// Minimum protocol version to offer. If not set, TLS 1.2 and TLS 1.3 are offered.
+  pub min_version: Option<&'static SupportedProtocolVersion>,
+  /// Cipher suites to offer. If empty, all the supported cipher suites are offered.
+  pub cipher_suites: Vec<SupportedCipherSuite>
+}
+
+impl TlsOptions {
+  /// Parses the TLS options from the transport config JSON. Returns `None` if no TLS options were
+  /// provided.
+  pub fn from_json(json: &Value) -> anyhow::Result<Option<TlsOptions>> {
+    let min_version = match json.get(TLS_MIN_VERSION_KEY) {
+      Some(Value::String(version)) => Some(parse_protocol_version(version)?),
+      Some(Value::Null) | None => None,
+      Some(value) => return Err(anyhow!("'{}' must be a string, got '{}'", TLS_MIN_VERSION_KEY, value))
+    };
+    let cipher_suites = match json.get(TLS_CIPHER_SUITES_KEY) {
+      Some(Value::Array(suites)) => suites.iter()
+        .map(|suite| match suite {
+          Value::String(name) => parse_cipher_suite(name),
+          _ => Err(anyhow!("'{}' must be a list of strings, got '{}'", TLS_CIPHER_SUITES_KEY, suite))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?,
+      Some(Value::String(suites)) => suites.split(',')
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .map(parse_cipher_suite)
+        .collect::<anyhow::Result<Vec<_>>>()?,
+      Some(Value::Null) | None => vec![],
+      Some(value) => return Err(anyhow!("'{}' must be a list of strings, got '{}'", TLS_CIPHER_SUITES_KEY, value))
+    };
+
+    if min_version.is_none() && cipher_suites.is_empty() {
+      Ok(None)
+    } else {
+      Ok(Some(TlsOptions { min_version, cipher_suites }))
+    }
+  }
+
+  /// Protocol versions to offer, based on the minimum version
+  pub fn protocol_versions(&self) -> Vec<&'static SupportedProtocolVersion> {
+    match self.min_version {
+      Some(version) if version.version == TLS13.version => vec![&TLS13],
+      _ => vec![&TLS12, &TLS13]
+    }
+  }
+
+  /// Applies the options to the TLS configuration, returning a new configuration that uses the same
+  /// server certificate. This will fail if none of the cipher suites can be used with the protocol
+  /// versions.
+  pub fn apply(&self, config: &ServerConfig) -> anyhow::Result<ServerConfig> {
+    let cipher_suites = if self.cipher_suites.is_empty() {
+      ALL_CIPHER_SUITES.to_vec()
+    } else {
+      self.cipher_suites.clone()
+    };
+    let mut tls_config = ServerConfig::builder()
+      .with_cipher_suites(&cipher_suites)
+      .with_safe_default_kx_groups()
+      .with_protocol_versions(&self.protocol_versions())
+      .map_err(|err| anyhow!("Invalid TLS options - {}", err))?
+      .with_no_client_auth()
+      .with_cert_resolver(Arc::clone(&config.cert_resolver));
+    tls_config.alpn_protocols = config.alpn_protocols.clone();
+    tls_config.ignore_client_order = config.ignore_client_order;
+    Ok(tls_config)
+  }
+}
+
+fn parse_protocol_version(version: &str) -> anyhow::Result<&'static SupportedProtocolVersion> {
+  let normalised = version.trim().to_uppercase().replace(['V', ' ', '_'], "");
+  match normalised.trim_start_matches("TLS") {
+    "1.2" | "12" => Ok(&TLS12),
+    "1.3" | "13" => Ok(&TLS13),
+    "1.0" | "10" | "1.1" | "11" => Err(anyhow!("TLS version '{}' is not supported, the minimum supported version is TLS1.2", version)),
+    _ => Err(anyhow!("'{}' is not a valid TLS version, it must be one of TLS1.2 or TLS1.3", version))
+  }
+}
+
+fn parse_cipher_suite(name: &str) -> anyhow::Result<SupportedCipherSuite> {
+  ALL_CIPHER_SUITES.iter()
+    .find(|suite| format!("{:?}", suite.suite()).eq_ignore_ascii_case(name))
+    .cloned()
+    .ok_or_else(|| anyhow!("'{}' is not a supported TLS cipher suite, it must be one of {}", name,
+      ALL_CIPHER_SUITES.iter().map(|suite| format!("{:?}", suite.suite())).join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+  use serde_json::json;
+  use tokio_rustls::rustls::cipher_suite::{TLS13_AES_128_GCM_SHA256, TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256};
+  use tokio_rustls::rustls::version::{TLS12, TLS13};
+
+  use super::TlsOptions;
+
+  #[test]
+  fn from_json_returns_none_if_there_are_no_tls_options() {
+    expect!(TlsOptions::from_json(&json!({})).unwrap().is_none()).to(be_true());
+    expect!(TlsOptions::from_json(&json!({ "corsPreflight": true })).unwrap().is_none()).to(be_true());
+  }
+
+  #[test]
+  fn from_json_parses_the_minimum_version() {
+    for version in ["1.3", "TLS1.3", "TLSv1.3", "tlsv1.3"] {
+      let options = TlsOptions::from_json(&json!({ "tlsMinVersion": version })).unwrap().unwrap();
+      expect!(options.protocol_versions().iter().map(|v| v.version).collect::<Vec<_>>())
+        .to(be_equal_to(vec![TLS13.version]));
+    }
+    let options = TlsOptions::from_json(&json!({ "tlsMinVersion": "1.2" })).unwrap().unwrap();
+    expect!(options.protocol_versions().iter().map(|v| v.version).collect::<Vec<_>>())
+      .to(be_equal_to(vec![TLS12.version, TLS13.version]));
+
+    expect!(TlsOptions::from_json(&json!({ "tlsMinVersion": "1.1" }))).to(be_err());
+    expect!(TlsOptions::from_json(&json!({ "tlsMinVersion": "2.0" }))).to(be_err());
+    expect!(TlsOptions::from_json(&json!({ "tlsMinVersion": 1.2 }))).to(be_err());
+  }
+
+  #[test]
+  fn from_json_parses_the_cipher_suites() {
+    let options = TlsOptions::from_json(&json!({
+      "tlsCipherSuites": [ "TLS13_AES_128_GCM_SHA256", "tls_ecdhe_rsa_with_aes_128_gcm_sha256" ]
+    })).unwrap().unwrap();
+    expect!(options.cipher_suites).to(be_equal_to(vec![TLS13_AES_128_GCM_SHA256, TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256]));
+
+    let options = TlsOptions::from_json(&json!({
+      "tlsCipherSuites": "TLS13_AES_128_GCM_SHA256, TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256"
+    })).unwrap().unwrap();
+    expect!(options.cipher_suites).to(be_equal_to(vec![TLS13_AES_128_GCM_SHA256, TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256]));
+
+    expect!(TlsOptions::from_json(&json!({ "tlsCipherSuites": [ "TLS_RSA_WITH_RC4_128_MD5" ] }))).to(be_err());
+    expect!(TlsOptions::from_json(&json!({ "tlsCipherSuites": [ 100 ] }))).to(be_err());
+  }
+
+  #[test]
+  fn apply_builds_a_new_config_using_the_same_certificate() {
+    let config = super::super::setup_tls_config(true).unwrap().unwrap();
+
+    let options = TlsOptions::from_json(&json!({
+      "tlsMinVersion": "1.2",
+      "tlsCipherSuites": [ "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256" ]
+    })).unwrap().unwrap();
+    expect!(options.apply(&config)).to(be_ok());
+
+    let options = TlsOptions::from_json(&json!({
+      "tlsMinVersion": "1.3",
+      "tlsCipherSuites": [ "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256" ]
+    })).unwrap().unwrap();
+    expect!(options.apply(&config)).to(be_err());
+  }
+}
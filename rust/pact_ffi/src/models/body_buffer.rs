@@ -0,0 +1,186 @@
+//! Zero-copy access to the bodies of interactions and messages.
+//!
+//! Two forms of access are provided, neither of which duplicates the body into a newly allocated
+//! C string:
+//!
+//! * A `BodyView` is a pointer and length pair that borrows directly from the body buffer of the
+//!   interaction. It is only valid while the interaction it was obtained from is not mutated or
+//!   deleted.
+//! * A `BodyBuffer` is an opaque handle that shares the underlying body buffer with the
+//!   interaction (the buffer is reference counted, so no bytes are copied). It remains valid
+//!   after the interaction is changed or deleted, and must be released with
+//!   `pactffi_body_buffer_delete`. Any changes to the body of the interaction after the buffer was
+//!   obtained are not visible through the buffer (copy-on-write semantics).
+
+use bytes::Bytes;
+use libc::{c_uchar, size_t};
+use pact_models::bodies::OptionalBody;
+
+use crate::{as_ref, ffi_fn};
+use crate::util::ptr;
+
+/// Borrowed view into the body buffer of an interaction or message.
+///
+/// The data pointer will be NULL and the length 0 if the body is missing, empty or null.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BodyView {
+  /// Pointer to the first byte of the body
+  pub data: *const c_uchar,
+  /// Number of bytes in the body
+  pub len: size_t
+}
+
+impl BodyView {
+  /// View for a missing, empty or null body
+  pub fn empty() -> Self {
+    BodyView {
+      data: std::ptr::null(),
+      len: 0
+    }
+  }
+
+  /// Create a view that borrows from the given body. The view is only valid while the body is not
+  /// changed or dropped.
+  pub fn from_body(body: &OptionalBody) -> Self {
+    match body {
+      OptionalBody::Present(bytes, _, _) if !bytes.is_empty() => BodyView {
+        data: bytes.as_ptr(),
+        len: bytes.len() as size_t
+      },
+      _ => BodyView::empty()
+    }
+  }
+}
+
+/// Opaque type for a body buffer that shares its data with the body of an interaction or message,
+/// but is not affected by changes to it.
+#[derive(Debug, Clone)]
+pub struct BodyBuffer {
+  bytes: Bytes
+}
+
+impl BodyBuffer {
+  /// Create a buffer that shares the data of the given body. Returns `None` if the body is missing.
+  pub fn from_body(body: &OptionalBody) -> Option<Self> {
+    match body {
+      OptionalBody::Missing => None,
+      OptionalBody::Empty | OptionalBody::Null => Some(BodyBuffer { bytes: Bytes::new() }),
+      OptionalBody::Present(bytes, _, _) => Some(BodyBuffer { bytes: bytes.clone() })
+    }
+  }
+
+  /// Returns a raw pointer to the heap allocated buffer for the given body, or NULL if the body is
+  /// missing.
+  pub(crate) fn raw_from_body(body: &OptionalBody) -> *mut BodyBuffer {
+    match BodyBuffer::from_body(body) {
+      Some(buffer) => ptr::raw_to(buffer),
+      None => std::ptr::null_mut()
+    }
+  }
+}
+
+ffi_fn! {
+    /// Get a pointer to the data of a body buffer.
+    ///
+    /// # Safety
+    ///
+    /// The number of bytes will be returned by `pactffi_body_buffer_len`. The pointer is valid
+    /// until the buffer is deleted with `pactffi_body_buffer_delete`.
+    ///
+    /// # Error Handling
+    ///
+    /// If the buffer is NULL or the body is empty, returns NULL.
+    fn pactffi_body_buffer_data(buffer: *const BodyBuffer) -> *const c_uchar {
+        let buffer = as_ref!(buffer);
+
+        if buffer.bytes.is_empty() {
+            std::ptr::null()
+        } else {
+            buffer.bytes.as_ptr()
+        }
+    } {
+        std::ptr::null()
+    }
+}
+
+ffi_fn! {
+    /// Get the number of bytes in a body buffer.
+    ///
+    /// # Safety
+    ///
+    /// This function is safe.
+    ///
+    /// # Error Handling
+    ///
+    /// If the buffer is NULL, returns 0.
+    fn pactffi_body_buffer_len(buffer: *const BodyBuffer) -> size_t {
+        let buffer = as_ref!(buffer);
+        buffer.bytes.len() as size_t
+    } {
+        0 as size_t
+    }
+}
+
+ffi_fn! {
+    /// Delete a body buffer. The interaction or message the buffer was obtained from is not affected.
+    fn pactffi_body_buffer_delete(buffer: *mut BodyBuffer) {
+        ptr::drop_raw(buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+  use bytes::Bytes;
+  use expectest::prelude::*;
+  use pact_models::bodies::OptionalBody;
+
+  use super::*;
+
+  #[test]
+  fn body_view_borrows_from_the_body() {
+    let body = OptionalBody::Present(Bytes::from_static(b"\x00\x01\x02"), None, None);
+    let view = BodyView::from_body(&body);
+    expect!(view.len).to(be_equal_to(3));
+    let slice = unsafe { std::slice::from_raw_parts(view.data, view.len) };
+    expect!(slice).to(be_equal_to(b"\x00\x01\x02".as_slice()));
+    expect!(view.data).to(be_equal_to(body.value().unwrap().as_ptr()));
+  }
+
+  #[test]
+  fn body_view_for_a_body_without_contents() {
+    expect!(BodyView::from_body(&OptionalBody::Missing)).to(be_equal_to(BodyView::empty()));
+    expect!(BodyView::from_body(&OptionalBody::Empty)).to(be_equal_to(BodyView::empty()));
+    expect!(BodyView::from_body(&OptionalBody::Null)).to(be_equal_to(BodyView::empty()));
+  }
+
+  #[test]
+  fn body_buffer_shares_the_data_and_outlives_the_body() {
+    let mut body = OptionalBody::Present(Bytes::from(vec![1_u8; 1024]), None, None);
+    let original_ptr = body.value().unwrap().as_ptr();
+    let buffer = BodyBuffer::raw_from_body(&body);
+
+    expect!(pactffi_body_buffer_data(buffer)).to(be_equal_to(original_ptr));
+    expect!(pactffi_body_buffer_len(buffer)).to(be_equal_to(1024));
+
+    body = OptionalBody::Present(Bytes::from_static(b"changed"), None, None);
+    drop(body);
+
+    let data = pactffi_body_buffer_data(buffer);
+    let slice = unsafe { std::slice::from_raw_parts(data, pactffi_body_buffer_len(buffer)) };
+    expect!(slice.iter().all(|b| *b == 1)).to(be_true());
+
+    pactffi_body_buffer_delete(buffer);
+  }
+
+  #[test]
+  fn body_buffer_for_a_missing_body() {
+    expect!(BodyBuffer::raw_from_body(&OptionalBody::Missing).is_null()).to(be_true());
+
+    let buffer = BodyBuffer::raw_from_body(&OptionalBody::Empty);
+    expect!(buffer.is_null()).to(be_false());
+    expect!(pactffi_body_buffer_len(buffer)).to(be_equal_to(0));
+    expect!(pactffi_body_buffer_data(buffer).is_null()).to(be_true());
+    pactffi_body_buffer_delete(buffer);
+  }
+}
@@ -8,6 +8,7 @@ use pact_models::v4::http_parts::{HttpRequest, HttpResponse};
 use pact_models::v4::message_parts::MessageContents;
 
 use crate::{as_mut, as_ref, ffi_fn, safe_str};
+use crate::models::body_buffer::{BodyBuffer, BodyView};
 use crate::models::generators::{GeneratorCategory, GeneratorCategoryIterator};
 use crate::models::matching_rules::{MatchingRuleCategory, MatchingRuleCategoryIterator};
 use crate::models::message::MessageMetadataIterator;
@@ -128,6 +129,49 @@ ffi_fn! {
     }
 }
 
+ffi_fn! {
+    /// Get the contents of the message contents as a borrowed view (pointer and length) into the body
+    /// buffer, without copying it.
+    ///
+    /// # Safety
+    ///
+    /// The view borrows from the contents. It is safe to use while the contents is not deleted or
+    /// changed. Using the view after the contents is mutated or deleted may lead to undefined
+    /// behaviour. Use `pactffi_message_contents_get_contents_buffer` if the data needs to outlive the contents.
+    ///
+    /// # Error Handling
+    ///
+    /// If the contents is NULL, or the body is missing, empty or null, returns a view with a NULL
+    /// data pointer and a length of 0.
+    fn pactffi_message_contents_get_contents_view(contents: *const MessageContents) -> BodyView {
+        let contents = as_ref!(contents);
+        BodyView::from_body(&contents.contents)
+    } {
+        BodyView::empty()
+    }
+}
+
+ffi_fn! {
+    /// Get the contents of the message contents as a body buffer that shares the data with the
+    /// contents, without copying it.
+    ///
+    /// # Safety
+    ///
+    /// The returned buffer must be deleted with `pactffi_body_buffer_delete`. The buffer remains
+    /// valid after the contents is changed or deleted, and changes to the body of the contents
+    /// will not be visible through it.
+    ///
+    /// # Error Handling
+    ///
+    /// If the contents is NULL, or the body is missing, returns NULL.
+    fn pactffi_message_contents_get_contents_buffer(contents: *const MessageContents) -> *mut BodyBuffer {
+        let contents = as_ref!(contents);
+        BodyBuffer::raw_from_body(&contents.contents)
+    } {
+        std::ptr::null_mut()
+    }
+}
+
 ffi_fn! {
   /// Sets the contents of the message as an array of bytes.
   ///
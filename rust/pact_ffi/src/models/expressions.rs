@@ -166,7 +166,11 @@ pub enum ExpressionValueType {
   /// Decimal numeric type (at least one significant figure after the decimal point)
   Decimal,
   /// Boolean type
-  Boolean
+  Boolean,
+  /// Array type (the value will be the JSON form of the array)
+  Array,
+  /// Object type (the value will be the JSON form of the object)
+  Object
 }
 
 impl ExpressionValueType {
@@ -178,7 +182,9 @@ impl ExpressionValueType {
       ValueType::Number => ExpressionValueType::Number,
       ValueType::Integer => ExpressionValueType::Integer,
       ValueType::Decimal => ExpressionValueType::Decimal,
-      ValueType::Boolean => ExpressionValueType::Boolean
+      ValueType::Boolean => ExpressionValueType::Boolean,
+      ValueType::Array => ExpressionValueType::Array,
+      ValueType::Object => ExpressionValueType::Object
     }
   }
 }
@@ -226,6 +232,19 @@ ffi_fn! {
 /// | Semver | 21 |
 /// | EachKey | 22 |
 /// | EachValue | 23 |
+/// | MinSize | 24 |
+/// | MaxSize | 25 |
+/// | BytesEqual | 26 |
+/// | Duration | 27 |
+/// | EpochTimestamp | 28 |
+/// | AnyOf | 29 |
+/// | Not | 30 |
+/// | AllowUnexpectedElements | 31 |
+/// | EqualsIgnoreOrder | 32 |
+/// | Url | 33 |
+/// | NumericRange | 34 |
+/// | Exists | 35 |
+/// | Absent | 36 |
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MatchingRuleResult {
   /// The matching rule from the expression.
@@ -291,7 +310,29 @@ impl MatchingRuleIterator {
               MatchingRule::NotEmpty => None,
               MatchingRule::Semver => None,
               MatchingRule::EachKey(_) => None,
-              MatchingRule::EachValue(_) => None
+              MatchingRule::EachValue(_) => None,
+              MatchingRule::MinSize(m) => Some(CString::new(m.to_string()).unwrap()),
+              MatchingRule::MaxSize(m) => Some(CString::new(m.to_string()).unwrap()),
+              MatchingRule::BytesEqual(offset, bytes) => {
+                let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+                let s = format!("{}:{}", offset, hex);
+                Some(CString::new(s).unwrap())
+              }
+              MatchingRule::Duration => None,
+              MatchingRule::EpochTimestamp(unit, tolerance) => if let Some(tolerance) = tolerance {
+                Some(CString::new(format!("{}:{}", unit, tolerance)).unwrap())
+              } else {
+                Some(CString::new(unit.to_string()).unwrap())
+              }
+              MatchingRule::AllowUnexpectedElements => None,
+              MatchingRule::EqualsIgnoreOrder => None,
+              MatchingRule::Url(rules) => Some(CString::new(rules.to_v3_json().to_string()).unwrap()),
+              MatchingRule::NumericRange(range) => Some(CString::new(serde_json::json!(range.to_map()).to_string()).unwrap()),
+              MatchingRule::AnyOf(rules) => Some(CString::new(serde_json::Value::Array(rules.iter()
+                .map(|rule| rule.to_json()).collect()).to_string()).unwrap()),
+              MatchingRule::Not(rule) => Some(CString::new(rule.to_json().to_string()).unwrap()),
+              MatchingRule::Exists => None,
+              MatchingRule::Absent => None
             };
             let rule_value = val.as_ref().map(|v| v.as_ptr()).unwrap_or_else(|| null());
             let rule_result = MatchingRuleResult::MatchingRule(rule_id(rule), rule_value, rule.clone());
@@ -345,7 +386,20 @@ fn rule_id(rule: &MatchingRule) -> u16 {
     MatchingRule::NotEmpty => 20,
     MatchingRule::Semver => 21,
     MatchingRule::EachKey(_) => 22,
-    MatchingRule::EachValue(_) => 23
+    MatchingRule::EachValue(_) => 23,
+    MatchingRule::MinSize(_) => 24,
+    MatchingRule::MaxSize(_) => 25,
+    MatchingRule::BytesEqual(_, _) => 26,
+    MatchingRule::Duration => 27,
+    MatchingRule::EpochTimestamp(_, _) => 28,
+    MatchingRule::AnyOf(_) => 29,
+    MatchingRule::Not(_) => 30,
+    MatchingRule::AllowUnexpectedElements => 31,
+    MatchingRule::EqualsIgnoreOrder => 32,
+    MatchingRule::Url(_) => 33,
+    MatchingRule::NumericRange(_) => 34,
+    MatchingRule::Exists => 35,
+    MatchingRule::Absent => 36
   }
 }
 
@@ -424,6 +478,19 @@ ffi_fn! {
     /// | Semver | 21 |
     /// | EachKey | 22 |
     /// | EachValue | 23 |
+    /// | MinSize | 24 |
+    /// | MaxSize | 25 |
+    /// | BytesEqual | 26 |
+    /// | Duration | 27 |
+    /// | EpochTimestamp | 28 |
+    /// | AnyOf | 29 |
+    /// | Not | 30 |
+    /// | AllowUnexpectedElements | 31 |
+    /// | EqualsIgnoreOrder | 32 |
+    /// | Url | 33 |
+    /// | NumericRange | 34 |
+    /// | Exists | 35 |
+    /// | Absent | 36 |
     ///
     /// # Safety
     ///
@@ -470,6 +537,19 @@ ffi_fn! {
     /// | Semver | 21 | NULL |
     /// | EachKey | 22 | NULL |
     /// | EachValue | 23 | NULL |
+    /// | MinSize | 24 | Minimum size in bytes |
+    /// | MaxSize | 25 | Maximum size in bytes |
+    /// | BytesEqual | 26 | "offset:hex bytes" |
+    /// | Duration | 27 | NULL |
+    /// | EpochTimestamp | 28 | "unit" or "unit:tolerance" |
+    /// | AnyOf | 29 | JSON of the rules |
+    /// | Not | 30 | JSON of the rule |
+    /// | AllowUnexpectedElements | 31 | NULL |
+    /// | EqualsIgnoreOrder | 32 | NULL |
+    /// | Url | 33 | JSON of the URL component rules |
+    /// | NumericRange | 34 | JSON of the range bounds |
+    /// | Exists | 35 | NULL |
+    /// | Absent | 36 | NULL |
     ///
     /// Will return a NULL pointer if the matching rule was a reference or does not have an
     /// associated value.
@@ -10,6 +10,7 @@ use pact_models::v4::http_parts::{HttpRequest, HttpResponse};
 use pact_models::v4::synch_http::SynchronousHttp;
 
 use crate::{as_mut, as_ref, ffi_fn, safe_str};
+use crate::models::body_buffer::{BodyBuffer, BodyView};
 use crate::models::message::ProviderStateIterator;
 use crate::ptr;
 use crate::util::*;
@@ -177,6 +178,49 @@ ffi_fn! {
     }
 }
 
+ffi_fn! {
+    /// Get the request contents of a `SynchronousHttp` interaction as a borrowed view (pointer and length) into the body
+    /// buffer, without copying it.
+    ///
+    /// # Safety
+    ///
+    /// The view borrows from the interaction. It is safe to use while the interaction is not deleted or
+    /// changed. Using the view after the interaction is mutated or deleted may lead to undefined
+    /// behaviour. Use `pactffi_sync_http_get_request_contents_buffer` if the data needs to outlive the interaction.
+    ///
+    /// # Error Handling
+    ///
+    /// If the interaction is NULL, or the body is missing, empty or null, returns a view with a NULL
+    /// data pointer and a length of 0.
+    fn pactffi_sync_http_get_request_contents_view(interaction: *const SynchronousHttp) -> BodyView {
+        let interaction = as_ref!(interaction);
+        BodyView::from_body(&interaction.request.body)
+    } {
+        BodyView::empty()
+    }
+}
+
+ffi_fn! {
+    /// Get the request contents of a `SynchronousHttp` interaction as a body buffer that shares the data with the
+    /// interaction, without copying it.
+    ///
+    /// # Safety
+    ///
+    /// The returned buffer must be deleted with `pactffi_body_buffer_delete`. The buffer remains
+    /// valid after the interaction is changed or deleted, and changes to the body of the interaction
+    /// will not be visible through it.
+    ///
+    /// # Error Handling
+    ///
+    /// If the interaction is NULL, or the body is missing, returns NULL.
+    fn pactffi_sync_http_get_request_contents_buffer(interaction: *const SynchronousHttp) -> *mut BodyBuffer {
+        let interaction = as_ref!(interaction);
+        BodyBuffer::raw_from_body(&interaction.request.body)
+    } {
+        std::ptr::null_mut()
+    }
+}
+
 ffi_fn! {
   /// Sets the request contents of the interaction as an array of bytes.
   ///
@@ -351,6 +395,49 @@ ffi_fn! {
     }
 }
 
+ffi_fn! {
+    /// Get the response contents of a `SynchronousHttp` interaction as a borrowed view (pointer and length) into the body
+    /// buffer, without copying it.
+    ///
+    /// # Safety
+    ///
+    /// The view borrows from the interaction. It is safe to use while the interaction is not deleted or
+    /// changed. Using the view after the interaction is mutated or deleted may lead to undefined
+    /// behaviour. Use `pactffi_sync_http_get_response_contents_buffer` if the data needs to outlive the interaction.
+    ///
+    /// # Error Handling
+    ///
+    /// If the interaction is NULL, or the body is missing, empty or null, returns a view with a NULL
+    /// data pointer and a length of 0.
+    fn pactffi_sync_http_get_response_contents_view(interaction: *const SynchronousHttp) -> BodyView {
+        let interaction = as_ref!(interaction);
+        BodyView::from_body(&interaction.response.body)
+    } {
+        BodyView::empty()
+    }
+}
+
+ffi_fn! {
+    /// Get the response contents of a `SynchronousHttp` interaction as a body buffer that shares the data with the
+    /// interaction, without copying it.
+    ///
+    /// # Safety
+    ///
+    /// The returned buffer must be deleted with `pactffi_body_buffer_delete`. The buffer remains
+    /// valid after the interaction is changed or deleted, and changes to the body of the interaction
+    /// will not be visible through it.
+    ///
+    /// # Error Handling
+    ///
+    /// If the interaction is NULL, or the body is missing, returns NULL.
+    fn pactffi_sync_http_get_response_contents_buffer(interaction: *const SynchronousHttp) -> *mut BodyBuffer {
+        let interaction = as_ref!(interaction);
+        BodyBuffer::raw_from_body(&interaction.response.body)
+    } {
+        std::ptr::null_mut()
+    }
+}
+
 ffi_fn! {
   /// Sets the response contents of the `SynchronousHttp` interaction as an array of bytes.
   ///
@@ -501,14 +588,20 @@ mod tests {
   use expectest::prelude::*;
   use libc::c_char;
 
+  use crate::models::body_buffer::{pactffi_body_buffer_data, pactffi_body_buffer_delete, pactffi_body_buffer_len};
   use crate::models::http_interaction::{
     pactffi_sync_http_delete,
     pactffi_sync_http_get_request_contents,
+    pactffi_sync_http_get_request_contents_bin,
+    pactffi_sync_http_get_request_contents_buffer,
     pactffi_sync_http_get_request_contents_length,
+    pactffi_sync_http_get_request_contents_view,
+    pactffi_sync_http_get_response_contents_view,
     pactffi_sync_http_get_response_contents,
     pactffi_sync_http_get_response_contents_length,
     pactffi_sync_http_new,
     pactffi_sync_http_set_request_contents,
+    pactffi_sync_http_set_request_contents_bin,
     pactffi_sync_http_set_response_contents
   };
 
@@ -538,4 +631,29 @@ mod tests {
     expect!(response_str.to_str().unwrap()).to(be_equal_to("This is another string"));
     expect!(response_len).to(be_equal_to(22));
   }
+
+  #[test]
+  fn get_http_contents_without_copying() {
+    let http = pactffi_sync_http_new();
+    let data = [0xFF_u8, 0xD8, 0xFF, 0xE0];
+    let content_type = CString::new("image/jpeg").unwrap();
+    pactffi_sync_http_set_request_contents_bin(http, data.as_ptr(), data.len(), content_type.as_ptr());
+
+    let view = pactffi_sync_http_get_request_contents_view(http);
+    expect!(view.data).to(be_equal_to(pactffi_sync_http_get_request_contents_bin(http)));
+    expect!(view.len).to(be_equal_to(4));
+
+    let response_view = pactffi_sync_http_get_response_contents_view(http);
+    expect!(response_view.data.is_null()).to(be_true());
+    expect!(response_view.len).to(be_equal_to(0));
+
+    let buffer = pactffi_sync_http_get_request_contents_buffer(http);
+    expect!(pactffi_body_buffer_data(buffer)).to(be_equal_to(view.data));
+    pactffi_sync_http_delete(http);
+
+    let len = pactffi_body_buffer_len(buffer);
+    let contents = unsafe { std::slice::from_raw_parts(pactffi_body_buffer_data(buffer), len) };
+    expect!(contents).to(be_equal_to(data.as_slice()));
+    pactffi_body_buffer_delete(buffer);
+  }
 }
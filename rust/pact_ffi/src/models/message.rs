@@ -23,6 +23,7 @@ use pact_models::interaction::Interaction;
 use pact_models::json_utils::json_to_string;
 
 use crate::{as_mut, as_ref, cstr, ffi_fn, safe_str};
+use crate::models::body_buffer::{BodyBuffer, BodyView};
 use crate::models::pact_specification::PactSpecification;
 use crate::util::*;
 use crate::util::string::optional_str;
@@ -260,6 +261,49 @@ ffi_fn! {
     }
 }
 
+ffi_fn! {
+    /// Get the contents of a `Message` as a borrowed view (pointer and length) into the body
+    /// buffer, without copying it.
+    ///
+    /// # Safety
+    ///
+    /// The view borrows from the message. It is safe to use while the message is not deleted or
+    /// changed. Using the view after the message is mutated or deleted may lead to undefined
+    /// behaviour. Use `pactffi_message_get_contents_buffer` if the data needs to outlive the message.
+    ///
+    /// # Error Handling
+    ///
+    /// If the message is NULL, or the body is missing, empty or null, returns a view with a NULL
+    /// data pointer and a length of 0.
+    fn pactffi_message_get_contents_view(message: *const Message) -> BodyView {
+        let message = as_ref!(message);
+        BodyView::from_body(&message.contents)
+    } {
+        BodyView::empty()
+    }
+}
+
+ffi_fn! {
+    /// Get the contents of a `Message` as a body buffer that shares the data with the
+    /// message, without copying it.
+    ///
+    /// # Safety
+    ///
+    /// The returned buffer must be deleted with `pactffi_body_buffer_delete`. The buffer remains
+    /// valid after the message is changed or deleted, and changes to the body of the message
+    /// will not be visible through it.
+    ///
+    /// # Error Handling
+    ///
+    /// If the message is NULL, or the body is missing, returns NULL.
+    fn pactffi_message_get_contents_buffer(message: *const Message) -> *mut BodyBuffer {
+        let message = as_ref!(message);
+        BodyBuffer::raw_from_body(&message.contents)
+    } {
+        std::ptr::null_mut()
+    }
+}
+
 ffi_fn! {
   /// Sets the contents of the message as an array of bytes.
   ///
@@ -14,6 +14,7 @@ use crate::models::pact_specification::PactSpecification;
 use crate::util::ptr;
 
 pub mod async_message;
+pub mod body_buffer;
 pub mod consumer;
 pub mod contents;
 pub mod expressions;
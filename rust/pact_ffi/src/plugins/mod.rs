@@ -195,7 +195,7 @@ pub extern fn pactffi_interaction_contents(
                 part.generators_mut().add_generators(generators.clone());
               }
               if !contents.plugin_config.is_empty() {
-                interaction.plugin_config_mut().insert(plugin_name, contents.plugin_config.interaction_configuration.clone());
+                interaction.add_plugin_config(&plugin_name, &contents.plugin_config.interaction_configuration);
               }
               *interaction.interaction_markup_mut() = InteractionMarkup {
                 markup: contents.interaction_markup.clone(),
@@ -215,7 +215,7 @@ pub extern fn pactffi_interaction_contents(
                 message.contents.generators.add_generators(generators.clone());
               }
               if !contents.plugin_config.is_empty() {
-                message.plugin_config.insert(plugin_name, contents.plugin_config.interaction_configuration.clone());
+                message.add_plugin_config(&plugin_name, &contents.plugin_config.interaction_configuration);
               }
               message.interaction_markup = InteractionMarkup {
                 markup: contents.interaction_markup.clone(),
@@ -275,7 +275,7 @@ fn setup_sync_message_contents(
       message.request.generators.add_generators(generators.clone());
     }
     if !contents.plugin_config.interaction_configuration.is_empty() {
-      message.plugin_config.insert(plugin_name.clone(), contents.plugin_config.interaction_configuration.clone());
+      message.add_plugin_config(&plugin_name, &contents.plugin_config.interaction_configuration);
     }
     message.interaction_markup = InteractionMarkup {
       markup: contents.interaction_markup.clone(),
@@ -301,7 +301,7 @@ fn setup_sync_message_contents(
     });
 
     if !c.plugin_config.is_empty() {
-      message.plugin_config.insert(plugin_name.clone(), c.plugin_config.interaction_configuration.clone());
+      message.add_plugin_config(&plugin_name, &c.plugin_config.interaction_configuration);
     }
     if !c.interaction_markup.is_empty() {
       message.interaction_markup = InteractionMarkup {
@@ -0,0 +1,214 @@
+//! The `publish` module provides exported functions using C bindings for publishing consumer pact
+//! files to a Pact Broker, without needing the standalone Pact Broker CLI.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use libc::{c_char, c_ushort};
+use pact_models::prelude::HttpAuth;
+use serde_json::{json, Value};
+use tracing::{debug, error, warn};
+
+use pact_verifier::pact_broker::{detect_version_properties, publish_pacts, PublishPactsOptions};
+
+use crate::{ffi_fn, RUNTIME, safe_str};
+use crate::util::string;
+use crate::util::string::{if_null, optional_str};
+use crate::verifier::get_vector;
+
+ffi_fn! {
+    /// Publishes consumer pact files to a Pact Broker. Each entry in `pact_files` can be the path
+    /// to a pact file, or a directory, in which case all the JSON files in the directory will be
+    /// published. All the pacts must be for the same consumer.
+    ///
+    /// If the consumer version is NULL or empty, the version will be detected from the Git
+    /// repository in the current directory (the SHA of the current commit). If the branch is NULL
+    /// or empty, it will also be detected from the CI environment variables or the checked out
+    /// Git branch.
+    ///
+    /// If a username and password is given, then basic authentication will be used when publishing
+    /// the pacts. If a token is provided, then bearer token authentication will be used.
+    ///
+    /// Returns a JSON document with the result, which will need to be freed with the
+    /// `pactffi_string_delete` function once it is no longer required. If the pacts were published,
+    /// the result will have the form `{ "success": true, "consumerVersion": "1.0.0", "branch": "main",
+    /// "notices": [] }`, where `notices` are the messages from the Pact Broker to display to the
+    /// user. Otherwise, it will have the form `{ "success": false, "error": "..." }`.
+    ///
+    /// Returns a NULL pointer if the function panics.
+    ///
+    /// # Safety
+    ///
+    /// The broker URL must point to a valid NULL terminated string. The pact files and tags must
+    /// be pointers to arrays of NULL terminated strings with the given lengths. All string fields
+    /// must contain valid UTF-8. Invalid UTF-8 will be replaced with U+FFFD REPLACEMENT CHARACTER.
+    fn pactffi_publish_pacts(
+      broker_url: *const c_char,
+      pact_files: *const *const c_char,
+      pact_files_len: c_ushort,
+      consumer_version: *const c_char,
+      branch: *const c_char,
+      tags: *const *const c_char,
+      tags_len: c_ushort,
+      build_url: *const c_char,
+      username: *const c_char,
+      password: *const c_char,
+      token: *const c_char
+    ) -> *const c_char {
+      let broker_url = safe_str!(broker_url);
+      let pact_files = get_vector(pact_files, pact_files_len);
+      let username = if_null(username, "");
+      let password = if_null(password, "");
+      let token = if_null(token, "");
+
+      let auth = if !username.is_empty() {
+        if !password.is_empty() {
+          HttpAuth::User(username, Some(password))
+        } else {
+          HttpAuth::User(username, None)
+        }
+      } else if !token.is_empty() {
+        HttpAuth::Token(token)
+      } else {
+        HttpAuth::None
+      };
+
+      let result = publish(broker_url, &pact_files, PublishArgs {
+        consumer_version: optional_str(consumer_version),
+        branch: optional_str(branch),
+        tags: get_vector(tags, tags_len),
+        build_url: optional_str(build_url)
+      }, auth);
+      let json = match result {
+        Ok(json) => json,
+        Err(err) => {
+          error!("Failed to publish pacts - {}", err);
+          json!({ "success": false, "error": err.to_string() })
+        }
+      };
+      string::to_c(&json.to_string())? as *const c_char
+    } {
+      std::ptr::null()
+    }
+}
+
+struct PublishArgs {
+  consumer_version: Option<String>,
+  branch: Option<String>,
+  tags: Vec<String>,
+  build_url: Option<String>
+}
+
+fn publish(
+  broker_url: &str,
+  pact_files: &[String],
+  args: PublishArgs,
+  auth: HttpAuth
+) -> anyhow::Result<Value> {
+  let pacts = load_pacts(pact_files)?;
+
+  let (consumer_version, branch) = match args.consumer_version {
+    Some(version) => (version, args.branch),
+    None => {
+      let (version, detected_branch) = detect_version_properties();
+      let version = version
+        .ok_or_else(|| anyhow!("No consumer version was given, and it could not be detected from Git"))?;
+      debug!("Detected consumer version {} and branch {:?}", version, detected_branch);
+      (version, args.branch.or(detected_branch))
+    }
+  };
+
+  let options = PublishPactsOptions {
+    consumer_version,
+    branch,
+    tags: args.tags,
+    build_url: args.build_url
+  };
+  let auth = match auth {
+    HttpAuth::None => None,
+    _ => Some(auth)
+  };
+  let result = RUNTIME.block_on(publish_pacts(broker_url, auth, &pacts, &options))?;
+
+  Ok(json!({
+    "success": true,
+    "consumerVersion": options.consumer_version,
+    "branch": options.branch,
+    "notices": result.get("notices").cloned().unwrap_or_else(|| json!([]))
+  }))
+}
+
+fn load_pacts(pact_files: &[String]) -> anyhow::Result<Vec<Value>> {
+  let mut paths = vec![];
+  for file in pact_files {
+    let path = Path::new(file);
+    if path.is_dir() {
+      let mut dir_paths = fs::read_dir(path)
+        .with_context(|| format!("Could not read the directory '{}'", file))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().map(|ext| ext == "json").unwrap_or(false))
+        .collect::<Vec<PathBuf>>();
+      if dir_paths.is_empty() {
+        warn!("There are no pact files in the directory '{}'", file);
+      }
+      dir_paths.sort();
+      paths.extend(dir_paths);
+    } else {
+      paths.push(path.to_path_buf());
+    }
+  }
+
+  if paths.is_empty() {
+    return Err(anyhow!("There are no pact files to publish"));
+  }
+
+  paths.iter()
+    .map(|path| {
+      let contents = fs::read_to_string(path)
+        .with_context(|| format!("Could not read the pact file '{}'", path.display()))?;
+      serde_json::from_str(&contents)
+        .with_context(|| format!("The pact file '{}' is not valid JSON", path.display()))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use expectest::prelude::*;
+  use serde_json::json;
+
+  use super::load_pacts;
+
+  #[test]
+  fn load_pacts_loads_the_pact_files_and_directories() {
+    let dir = tempfile::tempdir().unwrap();
+    let pact_dir = dir.path().join("pacts");
+    fs::create_dir(&pact_dir).unwrap();
+    fs::write(pact_dir.join("b.json"), r#"{"consumer":{"name":"C"},"provider":{"name":"B"}}"#).unwrap();
+    fs::write(pact_dir.join("a.json"), r#"{"consumer":{"name":"C"},"provider":{"name":"A"}}"#).unwrap();
+    fs::write(pact_dir.join("notes.txt"), "not a pact").unwrap();
+    let pact_file = dir.path().join("other.json");
+    fs::write(&pact_file, r#"{"consumer":{"name":"C"},"provider":{"name":"D"}}"#).unwrap();
+
+    let result = load_pacts(&[
+      pact_dir.to_string_lossy().to_string(),
+      pact_file.to_string_lossy().to_string()
+    ]).unwrap();
+    expect!(result).to(be_equal_to(vec![
+      json!({"consumer":{"name":"C"},"provider":{"name":"A"}}),
+      json!({"consumer":{"name":"C"},"provider":{"name":"B"}}),
+      json!({"consumer":{"name":"C"},"provider":{"name":"D"}})
+    ]));
+  }
+
+  #[test]
+  fn load_pacts_returns_an_error_if_there_are_no_pacts() {
+    let dir = tempfile::tempdir().unwrap();
+    expect!(load_pacts(&[])).to(be_err());
+    expect!(load_pacts(&[dir.path().to_string_lossy().to_string()])).to(be_err());
+    expect!(load_pacts(&[dir.path().join("missing.json").to_string_lossy().to_string()])).to(be_err());
+  }
+}
@@ -1,14 +1,22 @@
 //! Handle interface to creating a verifier
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::sync::{Arc, Mutex};
 
-use itertools::Itertools;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use bytes::Bytes;
+use itertools::{Either, Itertools};
+use libc::c_char;
+use pact_models::bodies::OptionalBody;
 use pact_models::prelude::HttpAuth;
+use pact_models::v4::http_parts::HttpRequest;
 use serde_json::Value;
 use tracing::{debug, error};
 
-use pact_verifier::{ConsumerVersionSelector, FilterInfo, NullRequestFilterExecutor, PactSource, ProviderInfo, ProviderTransport, PublishOptions, VerificationOptions, verify_provider_async};
-use pact_verifier::callback_executors::HttpRequestProviderStateExecutor;
+use pact_verifier::{ConsumerVersionSelector, FilterInfo, PactSource, ProviderInfo, ProviderTransport, PublishOptions, StateParameterFilter, VerificationOptions, verify_provider_async};
+use pact_verifier::callback_executors::{HttpRequestProviderStateExecutor, RequestFilterExecutor};
 use pact_verifier::metrics::VerificationMetrics;
 use pact_verifier::verification_result::VerificationExecutionResult;
 
@@ -20,8 +28,10 @@ pub struct VerifierHandle {
   provider: ProviderInfo,
   sources: Vec<PactSource>,
   filter: FilterInfo,
+  /// Filters on the parameters of provider states
+  state_parameter_filters: Vec<StateParameterFilter>,
   state_change: Arc<HttpRequestProviderStateExecutor>,
-  verification_options: VerificationOptions<NullRequestFilterExecutor>,
+  verification_options: VerificationOptions<CallbackRequestFilterExecutor>,
   publish_options: Option<PublishOptions>,
   consumers: Vec<String>,
   /// Calling application name and version
@@ -38,6 +48,7 @@ impl VerifierHandle {
       provider: ProviderInfo::default(),
       sources: Vec::new(),
       filter: FilterInfo::None,
+      state_parameter_filters: vec![],
       state_change: Arc::new(HttpRequestProviderStateExecutor::default()),
       verification_options: VerificationOptions::default(),
       publish_options: None,
@@ -53,6 +64,7 @@ impl VerifierHandle {
       provider: ProviderInfo::default(),
       sources: Vec::new(),
       filter: FilterInfo::None,
+      state_parameter_filters: vec![],
       state_change: Arc::new(HttpRequestProviderStateExecutor::default()),
       verification_options: VerificationOptions::default(),
       publish_options: None,
@@ -146,6 +158,19 @@ impl VerifierHandle {
     self.sources.push(PactSource::Dir(dir.to_string()));
   }
 
+  /// Add a directory source to be verified, only including the pact files whose paths (relative
+  /// to the directory) match the include globs and do not match any of the exclude globs. If no
+  /// include globs are given, all the pact files in the directory are included.
+  pub fn add_directory_source_with_filters(
+    &mut self,
+    dir: &str,
+    include: &[String],
+    exclude: &[String]
+  ) -> anyhow::Result<()> {
+    self.sources.push(PactSource::dir_with_filters(dir, include, exclude)?);
+    Ok(())
+  }
+
   /// Add a URL source to be verified. This will fetch the pact file from the URL. If a username
   /// and password is given, then basic authentication will be used when fetching the pact file.
   /// If a token is provided, then bearer token authentication will be used.
@@ -242,6 +267,12 @@ impl VerifierHandle {
     self.verification_options.no_pacts_is_error = is_error;
   }
 
+  /// Sets the maximum number of interactions to verify concurrently. A value of zero is treated
+  /// as one.
+  pub fn set_parallelism(&mut self, parallelism: usize) {
+    self.verification_options.parallelism = parallelism.max(1);
+  }
+
   /// Update the details used when publishing results
   /// 
   /// # Args
@@ -290,7 +321,7 @@ impl VerifierHandle {
       verify_provider_async(
         self.provider.clone(),
         self.sources.clone(),
-        self.filter.clone(),
+        self.filter.clone().with_state_parameters(self.state_parameter_filters.clone()),
         self.consumers.clone(),
         &self.verification_options,
         self.publish_options.as_ref(),
@@ -330,10 +361,32 @@ impl VerifierHandle {
     self.verifier_output.output = out.split('\n').map(|s| s.to_string()).collect();
   }
 
+  #[cfg(test)]
+  pub fn sources(&self) -> &Vec<PactSource> {
+    &self.sources
+  }
+
   /// Add a custom header to be included in the call to the provider
   pub fn add_custom_header(&mut self, header_name: &str, header_value: &str) {
     self.verification_options.custom_headers.insert(header_name.to_string(), header_value.to_string());
   }
+
+  /// Set the callback used to filter the requests made to the provider, and the callback used to
+  /// free the strings it returns. Passing `None` for the callback will remove any existing
+  /// request filter.
+  pub fn set_request_filter(
+    &mut self,
+    callback: Option<RequestFilterCallback>,
+    free_callback: Option<RequestFilterFreeCallback>
+  ) {
+    self.verification_options.request_filter = callback
+      .map(|callback| Arc::new(CallbackRequestFilterExecutor::new(callback, free_callback)));
+  }
+
+  /// Add a filter on the parameters of the provider states
+  pub fn add_state_parameter_filter(&mut self, filter: StateParameterFilter) {
+    self.state_parameter_filters.push(filter);
+  }
 }
 
 impl Default for VerifierHandle {
@@ -342,3 +395,84 @@ impl Default for VerifierHandle {
      Self::new()
    }
 }
+
+/// Callback used to filter the requests made to the provider. It is called with the request
+/// (after any generators have been applied) as a JSON document, and must return the request to
+/// send, also as a JSON document, or NULL if the request could not be filtered. The returned
+/// string is copied as soon as the callback returns, and is then passed to the free callback (if
+/// one was provided).
+pub type RequestFilterCallback = extern "C" fn(request_json: *const c_char) -> *const c_char;
+
+/// Callback used to free the strings returned by a `RequestFilterCallback`
+pub type RequestFilterFreeCallback = extern "C" fn(request_json: *const c_char);
+
+/// Request filter executor that invokes a callback supplied by the calling application. The
+/// callback is only invoked by one thread at a time, so the calling application does not need to
+/// synchronise access to any state it uses, even when the interactions are verified in parallel.
+#[derive(Debug, Clone)]
+pub struct CallbackRequestFilterExecutor {
+  callback: RequestFilterCallback,
+  free_callback: Option<RequestFilterFreeCallback>,
+  lock: Arc<Mutex<()>>
+}
+
+impl CallbackRequestFilterExecutor {
+  /// Create a new executor for the callback, and the callback to free the returned strings
+  pub fn new(callback: RequestFilterCallback, free_callback: Option<RequestFilterFreeCallback>) -> Self {
+    CallbackRequestFilterExecutor {
+      callback,
+      free_callback,
+      lock: Arc::new(Mutex::new(()))
+    }
+  }
+
+  fn invoke(&self, request: &HttpRequest) -> anyhow::Result<HttpRequest> {
+    let request_json = CString::new(request.to_json().to_string())?;
+    let result = {
+      let _guard = self.lock.lock().unwrap_or_else(|err| err.into_inner());
+      let result = (self.callback)(request_json.as_ptr());
+      if result.is_null() {
+        None
+      } else {
+        let copy = unsafe { CStr::from_ptr(result) }.to_owned();
+        if let Some(free_callback) = self.free_callback {
+          free_callback(result);
+        }
+        Some(copy)
+      }
+    };
+    match result {
+      Some(result) => {
+        let json: Value = serde_json::from_str(result.to_str()?)
+          .map_err(|err| anyhow!("Request filter callback returned invalid JSON - {}", err))?;
+        HttpRequest::from_json(&json)
+      }
+      None => Err(anyhow!("Request filter callback did not return a request"))
+    }
+  }
+}
+
+#[async_trait]
+impl RequestFilterExecutor for CallbackRequestFilterExecutor {
+  fn call(self: Arc<Self>, request: &HttpRequest) -> HttpRequest {
+    match self.invoke(request) {
+      Ok(request) => request,
+      Err(err) => {
+        error!("Request filter callback failed, using the original request: {}", err);
+        request.clone()
+      }
+    }
+  }
+
+  async fn call_async(self: Arc<Self>, request: &HttpRequest) -> anyhow::Result<HttpRequest> {
+    self.invoke(request)
+  }
+
+  fn call_non_http(
+    &self,
+    request_body: &OptionalBody,
+    metadata: &HashMap<String, Either<Value, Bytes>>
+  ) -> (OptionalBody, HashMap<String, Either<Value, Bytes>>) {
+    (request_body.clone(), metadata.clone())
+  }
+}
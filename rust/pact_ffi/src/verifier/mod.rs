@@ -6,17 +6,19 @@
 use std::env;
 use std::ffi::{CStr, CString, OsStr, OsString};
 use std::panic::catch_unwind;
-use std::str::from_utf8;
+use std::str::{from_utf8, FromStr};
+use std::sync::Arc;
 
 use clap::ArgSettings;
 use lazy_static::lazy_static;
-use libc::{c_char, c_int, c_uchar, c_ulong, c_ushort, EXIT_FAILURE, EXIT_SUCCESS};
+use libc::{c_char, c_int, c_uchar, c_uint, c_ulong, c_ushort, EXIT_FAILURE, EXIT_SUCCESS};
 use log::*;
 use pact_models::prelude::HttpAuth;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 use pact_matching::logging::fetch_buffer_contents;
+use pact_verifier::StateParameterFilter;
 use pact_verifier::selectors::{consumer_tags_to_selectors, json_to_selectors};
 
 use crate::{as_mut, as_ref, ffi_fn, RUNTIME, safe_str};
@@ -26,6 +28,7 @@ use crate::util::string::{if_null, optional_str};
 mod args;
 pub mod verifier;
 pub mod handle;
+pub mod transports;
 
 /// External interface to verifier a provider
 ///
@@ -319,6 +322,32 @@ ffi_fn! {
     }
 }
 
+ffi_fn! {
+    /// Sets the maximum number of interactions to verify concurrently. By default, the
+    /// interactions are verified one after the other. A value of zero is treated as one.
+    ///
+    /// Only interactions without provider states are verified in parallel. Interactions with
+    /// provider states are verified one at a time, and no other interactions are verified while
+    /// their states are set up.
+    ///
+    /// # Safety
+    ///
+    /// This function is safe as long as the handle pointer points to a valid handle.
+    ///
+    fn pactffi_verifier_set_parallelism(
+      handle: *mut handle::VerifierHandle,
+      parallelism: c_uint
+    ) -> c_int {
+      let handle = as_mut!(handle);
+
+      handle.set_parallelism(parallelism as usize);
+
+      EXIT_SUCCESS
+    } {
+      EXIT_FAILURE
+    }
+}
+
 ffi_fn! {
   /// Set the options used when publishing verification results to the Pact Broker
   ///
@@ -412,6 +441,141 @@ ffi_fn! {
     }
 }
 
+ffi_fn! {
+    /// Sets a callback that is invoked for each request made to the provider, which can be used
+    /// to modify or sign the request before it is sent (for example, to add an AWS SigV4 or OAuth
+    /// authorization header, which the custom headers can not support as the value is dynamic).
+    ///
+    /// The callback is called with the request as a JSON document (after any generators have been
+    /// applied) and must return the request to send as a JSON document, in the same format. If it
+    /// returns NULL or invalid JSON, the interaction will fail with an error. The returned string
+    /// is copied as soon as the callback returns, and is then passed to the free callback, so the
+    /// calling application can release it. If the free callback is NULL, the verifier does not
+    /// free the string, and the calling application remains responsible for it. Passing a NULL
+    /// callback removes any request filter.
+    ///
+    /// The callback is only invoked by one thread at a time, including when the interactions are
+    /// verified in parallel.
+    ///
+    /// # Safety
+    ///
+    /// The callbacks must be valid function pointers, and the string returned by the callback must
+    /// be a valid NULL terminated string containing UTF-8 that is valid until it is passed to the
+    /// free callback (or, if there is no free callback, until the callback is next called or the
+    /// verifier is shutdown).
+    fn pactffi_verifier_set_request_filter(
+      handle: *mut handle::VerifierHandle,
+      callback: Option<handle::RequestFilterCallback>,
+      free_callback: Option<handle::RequestFilterFreeCallback>
+    ) {
+      let handle = as_mut!(handle);
+      handle.set_request_filter(callback, free_callback);
+    }
+}
+
+ffi_fn! {
+    /// Registers a custom transport implemented by the calling application (for instance, an
+    /// in-process queue). Any V4 interactions with the transport key will be verified by invoking
+    /// the callback instead of making a request to the provider. The transport is available to all
+    /// verifiers, and registering a callback for an existing key replaces the previous one.
+    ///
+    /// The callback is called with the transport key and the request as a JSON document, and must
+    /// return the response as a JSON document. For HTTP interactions, the request has the
+    /// `description`, `providerStates` and `request` (the HTTP request) attributes, and the
+    /// response must be an HTTP response (i.e. `{ "status": 200, "body": {} }`). For asynchronous
+    /// messages, the request has the `description` and `providerStates` attributes, and the
+    /// response must be the message contents (i.e. `{ "contents": {}, "metadata": {} }`). For
+    /// synchronous messages, the request also has the `request` message contents, and the
+    /// response must be the contents of the response message. If the callback returns NULL or
+    /// invalid JSON, the interaction will fail with an error. The returned string is copied and
+    /// is not freed by the verifier.
+    ///
+    /// Returns 0 if the transport was registered, or 1 if the transport key is empty or the
+    /// callback is NULL.
+    ///
+    /// # Safety
+    ///
+    /// The transport key must point to a valid NULL terminated string and must contain valid
+    /// UTF-8. The callback must be a valid function pointer, and the string it returns must be a
+    /// valid NULL terminated string containing UTF-8 that is not freed until the callback is next
+    /// called.
+    fn pactffi_verifier_register_transport(
+      transport: *const c_char,
+      callback: Option<transports::TransportCallback>
+    ) -> c_int {
+      let transport = safe_str!(transport);
+
+      match callback {
+        Some(callback) if !transport.is_empty() => {
+          pact_verifier::custom_transports::register_transport(transport,
+            Arc::new(transports::CallbackTransportExecutor::new(callback)));
+          EXIT_SUCCESS
+        }
+        _ => {
+          error!("A transport key and callback are required to register a custom transport");
+          EXIT_FAILURE
+        }
+      }
+    } {
+      EXIT_FAILURE
+    }
+}
+
+ffi_fn! {
+    /// Removes a custom transport registered with `pactffi_verifier_register_transport`.
+    ///
+    /// Returns 0 if the transport was removed, or 1 if no transport was registered with the key.
+    ///
+    /// # Safety
+    ///
+    /// The transport key must point to a valid NULL terminated string and must contain valid
+    /// UTF-8.
+    fn pactffi_verifier_unregister_transport(transport: *const c_char) -> c_int {
+      let transport = safe_str!(transport);
+
+      if pact_verifier::custom_transports::unregister_transport(transport) {
+        EXIT_SUCCESS
+      } else {
+        EXIT_FAILURE
+      }
+    } {
+      EXIT_FAILURE
+    }
+}
+
+ffi_fn! {
+    /// Adds a filter on the parameters of the provider states of the interactions to verify. The
+    /// filter has the form `NAME=VALUE`, where `VALUE` is a regular expression that must match the
+    /// whole value of the parameter, or just `NAME` to only require that the parameter is present.
+    /// This can be called multiple times, and all the filters must match the same provider state.
+    ///
+    /// Returns 0 if the filter was added, or 1 if the filter is not valid.
+    ///
+    /// # Safety
+    ///
+    /// The filter must point to a valid NULL terminated string and must contain valid UTF-8.
+    fn pactffi_verifier_add_provider_state_param_filter(
+      handle: *mut handle::VerifierHandle,
+      filter: *const c_char
+    ) -> c_int {
+      let handle = as_mut!(handle);
+      let filter = safe_str!(filter);
+
+      match StateParameterFilter::from_str(filter) {
+        Ok(filter) => {
+          handle.add_state_parameter_filter(filter);
+          EXIT_SUCCESS
+        }
+        Err(err) => {
+          error!("Invalid provider state parameter filter '{}': {}", filter, err);
+          EXIT_FAILURE
+        }
+      }
+    } {
+      EXIT_FAILURE
+    }
+}
+
 ffi_fn! {
     /// Adds a Pact file as a source to verify.
     ///
@@ -451,6 +615,45 @@ ffi_fn! {
     }
 }
 
+ffi_fn! {
+    /// Adds a Pact directory as a source to verify, filtering the pact files with a set of globs.
+    /// Only the pact files whose paths (relative to the directory) match one of the include globs
+    /// and none of the exclude globs will be loaded. If no include globs are given, all files in the
+    /// directory will be included. As with `pactffi_verifier_add_directory_source`, only pacts that
+    /// match the provider name will be verified.
+    ///
+    /// Returns EXIT_FAILURE (1) if any of the globs are not valid.
+    ///
+    /// # Safety
+    ///
+    /// All string fields must contain valid UTF-8. Invalid UTF-8
+    /// will be replaced with U+FFFD REPLACEMENT CHARACTER.
+    ///
+    fn pactffi_verifier_add_directory_source_with_filters(
+      handle: *mut handle::VerifierHandle,
+      directory: *const c_char,
+      include_globs: *const *const c_char,
+      include_globs_len: c_ushort,
+      exclude_globs: *const *const c_char,
+      exclude_globs_len: c_ushort
+    ) -> c_int {
+      let handle = as_mut!(handle);
+      let directory = safe_str!(directory);
+      let include = get_vector(include_globs, include_globs_len);
+      let exclude = get_vector(exclude_globs, exclude_globs_len);
+
+      match handle.add_directory_source_with_filters(directory, &include, &exclude) {
+        Ok(_) => EXIT_SUCCESS,
+        Err(err) => {
+          error!("Failed to add the directory source '{}' - {}", directory, err);
+          EXIT_FAILURE
+        }
+      }
+    } {
+      EXIT_FAILURE
+    }
+}
+
 ffi_fn! {
     /// Adds a URL as a source to verify. The Pact file will be fetched from the URL.
     ///
@@ -794,7 +997,7 @@ fn parse_argument(long: Option<&str>, short: Option<char>, help: Option<&str>, p
     arg
 }
 
-fn get_vector(items_ptr: *const *const c_char, items_len: c_ushort) -> Vec<String> {
+pub(crate) fn get_vector(items_ptr: *const *const c_char, items_len: c_ushort) -> Vec<String> {
   if !items_ptr.is_null() && items_len > 0 {
     let mut items = Vec::with_capacity(items_len as usize);
     for index in 0..items_len {
@@ -901,13 +1104,24 @@ ffi_fn! {
 
 #[cfg(test)]
 mod tests {
-  use std::ffi::CString;
+  use std::ffi::{CStr, CString};
+  use std::sync::Arc;
+  use std::sync::atomic::{AtomicUsize, Ordering};
 
   use expectest::prelude::*;
   use libc::c_char;
-
-  use crate::verifier::handle::VerifierHandle;
-  use crate::verifier::pactffi_verifier_output;
+  use pact_models::v4::http_parts::HttpRequest;
+  use pact_verifier::callback_executors::RequestFilterExecutor;
+  use serde_json::{json, Value};
+
+  use crate::verifier::handle::{CallbackRequestFilterExecutor, VerifierHandle};
+  use crate::verifier::{
+    pactffi_verifier_add_directory_source_with_filters,
+    pactffi_verifier_output,
+    pactffi_verifier_register_transport,
+    pactffi_verifier_set_parallelism,
+    pactffi_verifier_unregister_transport
+  };
 
   #[test]
   fn pactffi_verifier_output_test() {
@@ -956,4 +1170,85 @@ We are tracking events anonymously to gather important usage statistics like Pac
 1) Verifying a pact between test_consumer and test_provider Given test state - test interaction - error sending request for url (http://localhost/): error trying to connect: tcp connect error: Connection refused (os error 111)\n\
 \n\nThere were 1 pact failures\n\n"));
   }
+
+  #[test]
+  fn pactffi_verifier_add_directory_source_with_filters_test() {
+    let mut handle = VerifierHandle::new_for_application("tests", "1.0.0");
+    let dir = CString::new("/tmp/pacts").unwrap();
+    let include_1 = CString::new("*.json").unwrap();
+    let include_2 = CString::new("web/**/*.json").unwrap();
+    let exclude = CString::new("pact-1.json").unwrap();
+    let include_globs = [include_1.as_ptr(), include_2.as_ptr()];
+    let exclude_globs = [exclude.as_ptr()];
+
+    let result = pactffi_verifier_add_directory_source_with_filters(&mut handle, dir.as_ptr(),
+      include_globs.as_ptr(), 2, exclude_globs.as_ptr(), 1);
+    expect!(result).to(be_equal_to(0));
+    let result = pactffi_verifier_add_directory_source_with_filters(&mut handle, dir.as_ptr(),
+      std::ptr::null(), 0, std::ptr::null(), 0);
+    expect!(result).to(be_equal_to(0));
+
+    let invalid = CString::new("pact-[1.json").unwrap();
+    let invalid_globs = [invalid.as_ptr()];
+    let result = pactffi_verifier_add_directory_source_with_filters(&mut handle, dir.as_ptr(),
+      invalid_globs.as_ptr(), 1, std::ptr::null(), 0);
+    expect!(result).to(be_equal_to(1));
+
+    let sources = handle.sources().iter().map(|source| source.to_string()).collect::<Vec<_>>();
+    expect!(sources).to(be_equal_to(vec![
+      "Dir(/tmp/pacts, include=[\"*.json\", \"web/**/*.json\"], exclude=[\"pact-1.json\"], recursive=false)".to_string(),
+      "Dir(/tmp/pacts, include=[], exclude=[], recursive=false)".to_string()
+    ]));
+  }
+
+  extern "C" fn test_transport(_transport: *const c_char, _request: *const c_char) -> *const c_char {
+    std::ptr::null()
+  }
+
+  #[test]
+  fn pactffi_verifier_register_transport_test() {
+    let transport = CString::new("ffi-test-queue").unwrap();
+    let empty = CString::new("").unwrap();
+
+    expect!(pactffi_verifier_register_transport(empty.as_ptr(), Some(test_transport))).to(be_equal_to(1));
+    expect!(pactffi_verifier_register_transport(transport.as_ptr(), None)).to(be_equal_to(1));
+    expect!(pactffi_verifier_register_transport(transport.as_ptr(), Some(test_transport))).to(be_equal_to(0));
+    expect!(pact_verifier::custom_transports::lookup_transport("ffi-test-queue").is_some()).to(be_true());
+    expect!(pactffi_verifier_unregister_transport(transport.as_ptr())).to(be_equal_to(0));
+    expect!(pactffi_verifier_unregister_transport(transport.as_ptr())).to(be_equal_to(1));
+  }
+
+  #[test]
+  fn pactffi_verifier_set_parallelism_test() {
+    let mut handle = VerifierHandle::new();
+    expect!(pactffi_verifier_set_parallelism(&mut handle, 4)).to(be_equal_to(0));
+    expect!(pactffi_verifier_set_parallelism(std::ptr::null_mut(), 4)).to(be_equal_to(1));
+  }
+
+  static FREED_REQUESTS: AtomicUsize = AtomicUsize::new(0);
+
+  extern "C" fn test_request_filter(request: *const c_char) -> *const c_char {
+    let request = unsafe { CStr::from_ptr(request) }.to_str().unwrap();
+    let mut json: Value = serde_json::from_str(request).unwrap();
+    json["headers"] = json!({ "Authorization": "Bearer 1234" });
+    CString::new(json.to_string()).unwrap().into_raw()
+  }
+
+  extern "C" fn test_request_filter_free(request: *const c_char) {
+    drop(unsafe { CString::from_raw(request as *mut c_char) });
+    FREED_REQUESTS.fetch_add(1, Ordering::SeqCst);
+  }
+
+  #[test]
+  fn callback_request_filter_copies_and_frees_the_returned_request() {
+    let executor = Arc::new(CallbackRequestFilterExecutor::new(test_request_filter, Some(test_request_filter_free)));
+    let request = HttpRequest { path: "/users".to_string(), .. HttpRequest::default() };
+
+    let filtered = executor.clone().call(&request);
+    expect!(filtered.path).to(be_equal_to("/users"));
+    expect!(filtered.headers).to(be_some().value(maplit::hashmap!{
+      "Authorization".to_string() => vec!["Bearer 1234".to_string()]
+    }));
+    expect!(FREED_REQUESTS.load(Ordering::SeqCst)).to(be_equal_to(1));
+  }
 }
@@ -0,0 +1,51 @@
+//! Custom transports implemented by the calling application
+
+use std::ffi::{CStr, CString};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use libc::c_char;
+use serde_json::Value;
+
+use pact_verifier::custom_transports::TransportExecutor;
+
+/// Callback used to send the requests for a custom transport. It is called with the transport key
+/// and the request as a JSON document, and must return the response as a JSON document, or NULL
+/// if the request failed. The returned string is copied and not freed by the verifier.
+pub type TransportCallback = extern "C" fn(
+  transport: *const c_char,
+  request_json: *const c_char
+) -> *const c_char;
+
+/// Transport executor that invokes a callback supplied by the calling application
+#[derive(Debug, Clone)]
+pub struct CallbackTransportExecutor {
+  callback: TransportCallback
+}
+
+impl CallbackTransportExecutor {
+  /// Create a new executor for the callback
+  pub fn new(callback: TransportCallback) -> Self {
+    CallbackTransportExecutor { callback }
+  }
+
+  fn invoke(&self, transport: &str, request: &Value) -> anyhow::Result<Value> {
+    let transport = CString::new(transport)?;
+    let request_json = CString::new(request.to_string())?;
+    let result = (self.callback)(transport.as_ptr(), request_json.as_ptr());
+    if result.is_null() {
+      Err(anyhow!("Transport callback did not return a response"))
+    } else {
+      let result = unsafe { CStr::from_ptr(result) }.to_str()?;
+      serde_json::from_str(result)
+        .map_err(|err| anyhow!("Transport callback returned invalid JSON - {}", err))
+    }
+  }
+}
+
+#[async_trait]
+impl TransportExecutor for CallbackTransportExecutor {
+  async fn execute(&self, transport: &str, request: &Value) -> anyhow::Result<Value> {
+    self.invoke(transport, request)
+  }
+}
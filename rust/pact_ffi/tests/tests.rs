@@ -3,7 +3,7 @@ use std::ffi::{CStr, CString};
 use std::fs::File;
 use std::io::Read;
 use std::path::PathBuf;
-use std::ptr::null;
+use std::ptr::{null, null_mut};
 
 use bytes::Bytes;
 use expectest::prelude::*;
@@ -26,9 +26,13 @@ use pact_ffi::mock_server::{
   pactffi_cleanup_mock_server,
   pactffi_create_mock_server,
   pactffi_create_mock_server_for_pact,
+  pactffi_mock_server_matched_interactions,
   pactffi_mock_server_mismatches,
   pactffi_write_pact_file,
+  pactffi_write_pact_file_v2,
   pactffi_mock_server_logs,
+  WritePactFileMode,
+  WritePactFileOptions,
 };
 #[allow(deprecated)]
 use pact_ffi::mock_server::handles::{
@@ -38,6 +42,7 @@ use pact_ffi::mock_server::handles::{
   pactffi_add_text_comment,
   pactffi_free_pact_handle,
   pactffi_given_with_params,
+  pactffi_interaction_markup,
   pactffi_message_expects_to_receive,
   pactffi_message_given,
   pactffi_message_reify,
@@ -69,6 +74,7 @@ use pact_ffi::mock_server::handles::{
 use pact_ffi::verifier::{
   OptionsFlags,
   pactffi_verifier_add_directory_source,
+  pactffi_verifier_add_directory_source_with_filters,
   pactffi_verifier_add_file_source,
   pactffi_verifier_cli_args,
   pactffi_verifier_execute,
@@ -105,6 +111,84 @@ fn post_to_mock_server_with_mismatches() {
   );
 }
 
+#[test]
+fn mock_server_matched_interactions() {
+  let consumer_name = CString::new("matched-interactions-consumer").unwrap();
+  let provider_name = CString::new("matched-interactions-provider").unwrap();
+  let pact_handle = pactffi_new_pact(consumer_name.as_ptr(), provider_name.as_ptr());
+  let description = CString::new("a request for a user").unwrap();
+  let interaction = pactffi_new_interaction(pact_handle.clone(), description.as_ptr());
+  let method = CString::new("GET").unwrap();
+  let path = CString::new("/users/1").unwrap();
+  pactffi_with_request(interaction.clone(), method.as_ptr(), path.as_ptr());
+  pactffi_response_status(interaction.clone(), 200);
+  let description = CString::new("a request for an order").unwrap();
+  let interaction = pactffi_new_interaction(pact_handle.clone(), description.as_ptr());
+  let path = CString::new("/orders/1").unwrap();
+  pactffi_with_request(interaction.clone(), method.as_ptr(), path.as_ptr());
+  pactffi_response_status(interaction.clone(), 200);
+  let description = CString::new("another request for a user").unwrap();
+  let interaction = pactffi_new_interaction(pact_handle.clone(), description.as_ptr());
+  let path = CString::new("/users/1").unwrap();
+  pactffi_with_request(interaction.clone(), method.as_ptr(), path.as_ptr());
+  pactffi_response_status(interaction.clone(), 200);
+
+  let address = CString::new("127.0.0.1:0").unwrap();
+  let port = pactffi_create_mock_server_for_pact(pact_handle.clone(), address.as_ptr(), false);
+  expect!(port).to(be_greater_than(0));
+
+  let client = Client::default();
+  for _ in 0..2 {
+    client.get(format!("http://127.0.0.1:{}/users/1", port).as_str())
+      .send().expect("Sent GET request to mock server");
+  }
+  client.get(format!("http://127.0.0.1:{}/accounts/1", port).as_str())
+    .send().expect("Sent GET request to mock server");
+
+  let matched = unsafe {
+    CStr::from_ptr(pactffi_mock_server_matched_interactions(port)).to_string_lossy().into_owned()
+  };
+  let no_mock_server = pactffi_mock_server_matched_interactions(1);
+
+  pactffi_cleanup_mock_server(port);
+  pactffi_free_pact_handle(pact_handle);
+
+  let mut json: Value = serde_json::from_str(matched.as_str()).unwrap();
+  let keys = json["interactions"].as_array_mut().unwrap().iter_mut()
+    .map(|interaction| interaction.as_object_mut().unwrap().remove("key").unwrap().as_str().unwrap().to_string())
+    .unique()
+    .count();
+  expect!(keys).to(be_equal_to(3));
+  expect!(json).to(be_equal_to(json!({
+    "matched": false,
+    "interactions": [
+      {
+        "description": "a request for a user",
+        "providerStates": [],
+        "matched": true,
+        "matchedCount": 2,
+        "mismatchedCount": 0
+      },
+      {
+        "description": "a request for an order",
+        "providerStates": [],
+        "matched": false,
+        "matchedCount": 0,
+        "mismatchedCount": 0
+      },
+      {
+        "description": "another request for a user",
+        "providerStates": [],
+        "matched": false,
+        "matchedCount": 0,
+        "mismatchedCount": 0
+      }
+    ],
+    "unexpectedRequests": 1
+  })));
+  expect!(no_mock_server.is_null()).to(be_true());
+}
+
 #[test]
 #[allow(deprecated)]
 fn create_header_with_multiple_values() {
@@ -396,6 +480,24 @@ fn add_text_comment() {
   });
 }
 
+#[test]
+fn interaction_markup() {
+  let consumer_name = CString::new("consumer").unwrap();
+  let provider_name = CString::new("provider").unwrap();
+  let pact_handle = pactffi_new_pact(consumer_name.as_ptr(), provider_name.as_ptr());
+  let description = CString::new("interaction_markup").unwrap();
+  let interaction = pactffi_new_interaction(pact_handle, description.as_ptr());
+  let markup = CString::new("# A request for a user").unwrap();
+  let markup_type = CString::new("COMMON_MARK").unwrap();
+
+  expect!(pactffi_interaction_markup(interaction, markup.as_ptr(), markup_type.as_ptr())).to(be_equal_to(0));
+  interaction.with_interaction(&|_, _, i| {
+    let interaction = i.as_v4_http().unwrap();
+    assert_eq!(interaction.interaction_markup.markup, "# A request for a user");
+    assert_eq!(interaction.interaction_markup.markup_type, "COMMON_MARK");
+  });
+}
+
 #[test_log::test]
 #[allow(deprecated)]
 fn http_consumer_feature_test() {
@@ -743,6 +845,41 @@ fn http_verification_from_directory_feature_test() {
   expect!(output.to_string_lossy().contains("Verifying a pact between test_consumer and test_provider2")).to(be_false());
 }
 
+#[test_log::test]
+#[allow(deprecated)]
+fn http_verification_from_directory_with_filters_feature_test() {
+  let name = CString::new("tests").unwrap();
+  let version = CString::new("1.0.0").unwrap();
+  let handle = pactffi_verifier_new_for_application(name.as_ptr(), version.as_ptr());
+
+  let provider_name = CString::new("test_provider").unwrap();
+  pactffi_verifier_set_provider_info(handle, provider_name.as_ptr(), null(), null(), 0, null());
+
+  let pacts_path = fixture_path("pacts");
+  let path_str = CString::new(pacts_path.to_string_lossy().to_string()).unwrap();
+  let include = CString::new("*.json").unwrap();
+  let exclude = CString::new("pact-1.json").unwrap();
+  let include_globs = [include.as_ptr()];
+  let exclude_globs = [exclude.as_ptr()];
+  let result = pactffi_verifier_add_directory_source_with_filters(handle, path_str.as_ptr(),
+    include_globs.as_ptr(), 1, exclude_globs.as_ptr(), 1);
+  expect!(result).to(be_equal_to(0));
+
+  let invalid = CString::new("pact-[1.json").unwrap();
+  let invalid_globs = [invalid.as_ptr()];
+  let result = pactffi_verifier_add_directory_source_with_filters(handle, path_str.as_ptr(),
+    invalid_globs.as_ptr(), 1, null(), 0);
+  expect!(result).to(be_equal_to(1));
+
+  let _result = pactffi_verifier_execute(handle);
+  let output_ptr = pactffi_verifier_output(handle, 0);
+  let output = unsafe { CString::from_raw(output_ptr as *mut c_char) };
+
+  pactffi_verifier_shutdown(handle);
+
+  expect!(output.to_string_lossy().contains("Verifying a pact between test_consumer and test_provider")).to(be_false());
+}
+
 #[test_log::test]
 fn test_missing_plugin() {
   let name = CString::new("tests").unwrap();
@@ -1112,6 +1249,94 @@ fn merging_pact_file() {
   );
 }
 
+#[test_log::test]
+fn write_pact_file_v2_returns_the_path_and_applies_the_write_mode() {
+  let pact_handle = PactHandle::new("WritePactV2C", "WritePactV2P");
+  let description = CString::new("a request for the current interaction").unwrap();
+  let i_handle = pactffi_new_interaction(pact_handle, description.as_ptr());
+  let method = CString::new("GET").unwrap();
+  let path = CString::new("/current").unwrap();
+  pactffi_with_request(i_handle, method.as_ptr(), path.as_ptr());
+  pactffi_response_status(i_handle, 200);
+  let address = CString::new("127.0.0.1:0").unwrap();
+  let port = pactffi_create_mock_server_for_pact(pact_handle, address.as_ptr(), false);
+  expect!(port).to(be_greater_than(0));
+
+  let tmp = tempfile::tempdir().unwrap();
+  let tmp_dir = CString::new(tmp.path().to_string_lossy().as_bytes().to_vec()).unwrap();
+  let file_name = CString::new("custom-pact.json").unwrap();
+  let pact_path = tmp.path().join("custom-pact.json");
+  let stale_pact = json!({
+    "consumer": { "name": "WritePactV2C" },
+    "provider": { "name": "WritePactV2P" },
+    "interactions": [
+      {
+        "description": "a request for a stale interaction",
+        "request": { "method": "GET", "path": "/stale" },
+        "response": { "status": 200 }
+      }
+    ],
+    "metadata": { "pactSpecification": { "version": "3.0.0" } }
+  });
+  let interactions = |path: &PathBuf| {
+    let json: Value = serde_json::from_reader(File::open(path).unwrap()).unwrap();
+    json["interactions"].as_array().unwrap().iter()
+      .map(|i| i["description"].as_str().unwrap_or_default().to_string())
+      .sorted()
+      .collect::<Vec<_>>()
+  };
+  let write_with_indent = |mode: WritePactFileMode, indent: u32, written_path: &mut *mut c_char| {
+    let options = WritePactFileOptions {
+      mode,
+      directory: tmp_dir.as_ptr(),
+      filename: file_name.as_ptr(),
+      sort_interactions: true,
+      sort_keys: true,
+      indent
+    };
+    unsafe { pactffi_write_pact_file_v2(port, &options, written_path) }
+  };
+  let write = |mode: WritePactFileMode, written_path: &mut *mut c_char| write_with_indent(mode, 2, written_path);
+
+  // The first clean write replaces the existing file
+  std::fs::write(&pact_path, stale_pact.to_string()).unwrap();
+  let mut written_path = null_mut();
+  expect!(write(WritePactFileMode::Clean, &mut written_path)).to(be_equal_to(0));
+  expect!(written_path.is_null()).to(be_false());
+  let returned_path = unsafe { CString::from_raw(written_path) };
+  expect!(PathBuf::from(returned_path.to_string_lossy().to_string())).to(be_equal_to(pact_path.clone()));
+  expect!(interactions(&pact_path)).to(be_equal_to(vec!["a request for the current interaction".to_string()]));
+
+  // Subsequent clean writes merge with it
+  std::fs::write(&pact_path, stale_pact.to_string()).unwrap();
+  let mut written_path = null_mut();
+  expect!(write(WritePactFileMode::Clean, &mut written_path)).to(be_equal_to(0));
+  drop(unsafe { CString::from_raw(written_path) });
+  expect!(interactions(&pact_path)).to(be_equal_to(vec![
+    "a request for a stale interaction".to_string(),
+    "a request for the current interaction".to_string()
+  ]));
+
+  let mut written_path = null_mut();
+  expect!(write(WritePactFileMode::Overwrite, &mut written_path)).to(be_equal_to(0));
+  drop(unsafe { CString::from_raw(written_path) });
+  expect!(interactions(&pact_path)).to(be_equal_to(vec!["a request for the current interaction".to_string()]));
+  expect!(std::fs::read_to_string(&pact_path).unwrap().trim().contains('\n')).to(be_true());
+
+  let mut written_path = null_mut();
+  expect!(write_with_indent(WritePactFileMode::Overwrite, 0, &mut written_path)).to(be_equal_to(0));
+  drop(unsafe { CString::from_raw(written_path) });
+  expect!(interactions(&pact_path)).to(be_equal_to(vec!["a request for the current interaction".to_string()]));
+  expect!(std::fs::read_to_string(&pact_path).unwrap().trim().contains('\n')).to(be_false());
+
+  pactffi_cleanup_mock_server(port);
+  pactffi_free_pact_handle(pact_handle);
+
+  let mut written_path = null_mut();
+  expect!(write(WritePactFileMode::Merge, &mut written_path)).to(be_equal_to(3));
+  expect!(written_path.is_null()).to(be_true());
+}
+
 // Issue #389
 #[test_log::test]
 fn repeated_interaction() {
@@ -0,0 +1,221 @@
+//! Functions for matching Avro encoded message payloads. The payloads are decoded using the Avro
+//! schema attached to the expected message metadata, and the matching rules are then applied to
+//! the decoded records.
+
+use anyhow::anyhow;
+use apache_avro::{from_avro_datum, Reader, Schema};
+use serde_json::Value;
+use tracing::debug;
+
+use pact_models::path_exp::DocPath;
+use pact_models::v4::message_parts::MessageContents;
+
+use crate::{MatchingContext, Mismatch};
+use crate::json::compare_json;
+
+/// Message metadata key that the Avro schema is stored under
+pub const AVRO_SCHEMA_KEY: &str = "avroSchema";
+
+/// Magic bytes at the start of an Avro object container file
+const AVRO_CONTAINER_MAGIC: &[u8] = b"Obj\x01";
+
+/// Parses the Avro schema from the message metadata value. The schema can either be a JSON string
+/// or an embedded JSON document.
+pub fn parse_schema(schema: &Value) -> anyhow::Result<Schema> {
+  let result = match schema {
+    Value::String(s) => Schema::parse_str(s),
+    _ => Schema::parse(schema)
+  };
+  result.map_err(|err| anyhow!("Failed to parse the Avro schema - {}", err))
+}
+
+/// Decodes the Avro encoded data using the schema, returning the decoded value as JSON. Both
+/// single binary encoded values and Avro object container files are supported. The records from
+/// an object container file are returned as a JSON array.
+pub fn decode_avro(schema: &Schema, data: &[u8]) -> anyhow::Result<Value> {
+  if data.starts_with(AVRO_CONTAINER_MAGIC) {
+    let reader = Reader::with_schema(schema, data)
+      .map_err(|err| anyhow!("Failed to read the Avro object container - {}", err))?;
+    let records = reader
+      .map(|record| record
+        .map_err(|err| anyhow!("Failed to decode the Avro record - {}", err))
+        .and_then(avro_to_json))
+      .collect::<anyhow::Result<Vec<Value>>>()?;
+    Ok(Value::Array(records))
+  } else {
+    let mut reader = data;
+    let value = from_avro_datum(schema, &mut reader, None)
+      .map_err(|err| anyhow!("Failed to decode the Avro value - {}", err))?;
+    avro_to_json(value)
+  }
+}
+
+fn avro_to_json(value: apache_avro::types::Value) -> anyhow::Result<Value> {
+  Value::try_from(value).map_err(|err| anyhow!("Failed to convert the Avro value to JSON - {}", err))
+}
+
+/// Matches the Avro encoded expected and actual message contents, using the schema from the
+/// expected message metadata
+pub fn match_avro(
+  schema: &Value,
+  expected: &MessageContents,
+  actual: &MessageContents,
+  context: &(dyn MatchingContext + Send + Sync)
+) -> Result<(), Vec<Mismatch>> {
+  let expected_body = expected.contents.value().unwrap_or_default();
+  let actual_body = actual.contents.value().unwrap_or_default();
+  debug!("Matching Avro payloads: expected {} bytes, actual {} bytes", expected_body.len(), actual_body.len());
+
+  let schema = parse_schema(schema).map_err(|err| vec![Mismatch::BodyMismatch {
+    path: "$".to_string(),
+    expected: expected.contents.value(),
+    actual: actual.contents.value(),
+    mismatch: err.to_string(),
+    rule: None
+  }])?;
+
+  let expected_json = decode_avro(&schema, &expected_body);
+  let actual_json = decode_avro(&schema, &actual_body);
+  match (expected_json, actual_json) {
+    (Ok(expected_json), Ok(actual_json)) => {
+      compare_json(&DocPath::root(), &expected_json, &actual_json, context)
+        .map_err(|mismatches| mismatches.iter().map(|mismatch| mismatch.to_body_mismatch()).collect())
+    }
+    (expected_json, actual_json) => {
+      let mut mismatches = vec![];
+      if let Err(err) = expected_json {
+        mismatches.push(Mismatch::BodyMismatch {
+          path: "$".to_string(),
+          expected: expected.contents.value(),
+          actual: actual.contents.value(),
+          mismatch: format!("Failed to decode the expected Avro payload: '{}'", err),
+          rule: None
+        });
+      }
+      if let Err(err) = actual_json {
+        mismatches.push(Mismatch::BodyMismatch {
+          path: "$".to_string(),
+          expected: expected.contents.value(),
+          actual: actual.contents.value(),
+          mismatch: format!("Failed to decode the actual Avro payload: '{}'", err),
+          rule: None
+        });
+      }
+      Err(mismatches)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use apache_avro::{Schema, to_avro_datum, Writer};
+  use apache_avro::types::Record;
+  use expectest::prelude::*;
+  use maplit::hashmap;
+  use pact_models::bodies::OptionalBody;
+  use pact_models::matchingrules;
+  use pact_models::matchingrules::MatchingRule;
+  use pact_models::v4::message_parts::MessageContents;
+  use serde_json::json;
+
+  use crate::{CoreMatchingContext, DiffConfig, Mismatch};
+
+  use super::{decode_avro, match_avro, parse_schema};
+
+  fn schema_json() -> serde_json::Value {
+    json!({
+      "type": "record",
+      "name": "User",
+      "fields": [
+        { "name": "id", "type": "long" },
+        { "name": "name", "type": "string" }
+      ]
+    })
+  }
+
+  fn encode(schema: &Schema, id: i64, name: &str) -> Vec<u8> {
+    let mut record = Record::new(schema).unwrap();
+    record.put("id", id);
+    record.put("name", name);
+    to_avro_datum(schema, record).unwrap()
+  }
+
+  fn contents(data: Vec<u8>) -> MessageContents {
+    MessageContents {
+      contents: OptionalBody::Present(data.into(), None, None),
+      metadata: hashmap!{ "avroSchema".to_string() => schema_json() },
+      .. MessageContents::default()
+    }
+  }
+
+  #[test]
+  fn parse_schema_supports_strings_and_json_documents() {
+    expect!(parse_schema(&schema_json())).to(be_ok());
+    expect!(parse_schema(&json!(schema_json().to_string()))).to(be_ok());
+    expect!(parse_schema(&json!({ "type": "unknown" }))).to(be_err());
+  }
+
+  #[test]
+  fn decode_avro_test() {
+    let schema = parse_schema(&schema_json()).unwrap();
+    expect!(decode_avro(&schema, &encode(&schema, 100, "Fred")).unwrap()).to(
+      be_equal_to(json!({ "id": 100, "name": "Fred" })));
+
+    let mut writer = Writer::new(&schema, Vec::new());
+    let mut record = Record::new(&schema).unwrap();
+    record.put("id", 100_i64);
+    record.put("name", "Fred");
+    writer.append(record).unwrap();
+    let container = writer.into_inner().unwrap();
+    expect!(decode_avro(&schema, &container).unwrap()).to(
+      be_equal_to(json!([{ "id": 100, "name": "Fred" }])));
+  }
+
+  #[test]
+  fn match_avro_applies_matching_rules_to_the_decoded_records() {
+    let schema = parse_schema(&schema_json()).unwrap();
+    let expected = contents(encode(&schema, 100, "Fred"));
+    let actual = contents(encode(&schema, 200, "Fred"));
+
+    let context = CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys);
+    let result = match_avro(&schema_json(), &expected, &actual, &context);
+    expect!(result.unwrap_err().iter().map(|m| m.description()).collect::<Vec<_>>()).to(be_equal_to(vec![
+      "$.id -> Expected 200 (Integer) to be equal to 100 (Integer)".to_string()
+    ]));
+
+    let rules = matchingrules! {
+      "content" => { "$.id" => [ MatchingRule::Integer ] }
+    };
+    let context = CoreMatchingContext::new(
+      DiffConfig::AllowUnexpectedKeys,
+      &rules.rules_for_category("content").unwrap_or_default(), &hashmap!{}
+    );
+    expect!(match_avro(&schema_json(), &expected, &actual, &context)).to(be_ok());
+  }
+
+  #[test]
+  fn match_avro_returns_a_mismatch_if_the_payload_can_not_be_decoded() {
+    let schema = parse_schema(&schema_json()).unwrap();
+    let expected = contents(encode(&schema, 100, "Fred"));
+    let actual = contents(vec![]);
+
+    let context = CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys);
+    let mismatches = match_avro(&schema_json(), &expected, &actual, &context).unwrap_err();
+    expect!(mismatches.len()).to(be_equal_to(1));
+    expect!(matches!(&mismatches[0], Mismatch::BodyMismatch { mismatch, .. } if mismatch.starts_with("Failed to decode the actual Avro payload"))).to(be_true());
+  }
+
+  #[tokio::test]
+  async fn match_message_contents_uses_the_avro_schema_from_the_metadata() {
+    let schema = parse_schema(&schema_json()).unwrap();
+    let expected = contents(encode(&schema, 100, "Fred"));
+    let actual = MessageContents {
+      contents: OptionalBody::Present(encode(&schema, 100, "Fred").into(), None, None),
+      .. MessageContents::default()
+    };
+
+    let context = CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys);
+    expect!(crate::match_message_contents(&expected, &actual, &context).await).to(be_ok());
+    expect!(crate::match_message_metadata(&expected, &actual, &context).is_empty()).to(be_true());
+  }
+}
@@ -15,21 +15,23 @@ use bytes::Bytes;
 #[cfg(feature = "multipart")] use futures::stream::once;
 #[cfg(feature = "multipart")] use http::header::{HeaderMap, HeaderName};
 #[cfg(feature = "multipart")] use itertools::Itertools;
+#[cfg(feature = "multipart")] use maplit::hashmap;
 #[cfg(feature = "multipart")] use multer::Multipart;
 #[cfg(feature = "multipart")] use onig::Regex;
 #[cfg(feature = "multipart")] use pact_models::bodies::OptionalBody;
 use pact_models::content_types::{ContentType, detect_content_type_from_bytes};
 use pact_models::http_parts::HttpPart;
 use pact_models::matchingrules::RuleLogic;
-#[cfg(feature = "multipart")] use pact_models::matchingrules::MatchingRule;
+#[cfg(feature = "multipart")] use pact_models::matchingrules::{MatchingRule, MatchingRuleCategory};
 use pact_models::path_exp::DocPath;
+#[cfg(feature = "multipart")] use pact_models::path_exp::PathToken;
 #[cfg(feature = "multipart")] use pact_models::v4::http_parts::HttpRequest;
 use serde_json::Value;
 #[allow(unused_imports)] use tracing::{debug, error, warn};
 
 use crate::{MatchingContext, Mismatch};
-#[cfg(feature = "multipart")] use crate::{BodyMatchResult, CoreMatchingContext, HeaderMatchingContext};
-use crate::matchers::Matches;
+#[cfg(feature = "multipart")] use crate::{BodyMatchResult, CoreMatchingContext};
+use crate::matchers::{match_rule, Matches};
 #[cfg(feature = "multipart")] use crate::matchers::match_values;
 
 /// Compares the binary data using a magic test and comparing the resulting detected content
@@ -110,6 +112,40 @@ where
   ))
 }
 
+/// Checks that the binary data is at least the given number of bytes
+pub fn match_min_size(data: &[u8], min: usize) -> anyhow::Result<()> {
+  debug!("Matching binary contents by minimum size: expected at least {} bytes, actual {} bytes", min, data.len());
+  if data.len() >= min {
+    Ok(())
+  } else {
+    Err(anyhow!("Expected binary contents to be at least {} bytes, but was {} bytes", min, data.len()))
+  }
+}
+
+/// Checks that the binary data is at most the given number of bytes
+pub fn match_max_size(data: &[u8], max: usize) -> anyhow::Result<()> {
+  debug!("Matching binary contents by maximum size: expected at most {} bytes, actual {} bytes", max, data.len());
+  if data.len() <= max {
+    Ok(())
+  } else {
+    Err(anyhow!("Expected binary contents to be at most {} bytes, but was {} bytes", max, data.len()))
+  }
+}
+
+/// Checks that the binary data contains the expected bytes at the given offset
+pub fn match_bytes_at_offset(data: &[u8], offset: usize, expected: &[u8]) -> anyhow::Result<()> {
+  debug!("Matching binary contents at offset {}: expected {}", offset, hex::encode(expected));
+  let end = offset.checked_add(expected.len())
+    .ok_or_else(|| anyhow!("Byte range starting at offset {} with length {} is not valid", offset, expected.len()))?;
+  match data.get(offset..end) {
+    Some(actual) if actual == expected => Ok(()),
+    Some(actual) => Err(anyhow!("Expected binary contents at offset {} to be {}, but was {}",
+      offset, hex::encode(expected), hex::encode(actual))),
+    None => Err(anyhow!("Expected binary contents at offset {} to be {}, but the contents is only {} bytes",
+      offset, hex::encode(expected), data.len()))
+  }
+}
+
 pub(crate) fn convert_data(data: &Value) -> Vec<u8> {
   match data {
     Value::String(s) => BASE64.decode(s.as_str()).unwrap_or_else(|_| s.clone().into_bytes()),
@@ -136,10 +172,11 @@ pub fn match_octet_stream(
         expected: Some(expected_body),
         actual: Some(actual_body),
         mismatch: format!("No matcher found for category 'body' and path '{}'", path),
+        rule: None
       })
     } else {
       let results = matchers.rules.iter().map(|rule|
-        expected_body.matches_with(&actual_body, rule, matchers.cascaded)).collect::<Vec<anyhow::Result<()>>>();
+        match_rule(&expected_body, &actual_body, rule, matchers.cascaded)).collect::<Vec<anyhow::Result<()>>>();
       match matchers.rule_logic {
         RuleLogic::And => for result in results {
           if let Err(err) = result {
@@ -148,6 +185,7 @@ pub fn match_octet_stream(
               expected: Some(expected_body.clone()),
               actual: Some(actual_body.clone()),
               mismatch: err.to_string(),
+              rule: None
             })
           }
         },
@@ -160,6 +198,7 @@ pub fn match_octet_stream(
                   expected: Some(expected_body.clone()),
                   actual: Some(actual_body.clone()),
                   mismatch: err.to_string(),
+                  rule: None
                 })
               }
             }
@@ -176,7 +215,8 @@ pub fn match_octet_stream(
       actual: Some(actual_body.clone()),
       mismatch: format!("Actual body [{}, {} bytes, starting with {}] is not equal to the expected body [{}, {} bytes, starting with {}]",
         actual_ct, actual_body.len(), display_bytes(&actual_body, 32),
-        expected_ct, expected_body.len(), display_bytes(&expected_body, 32))
+        expected_ct, expected_body.len(), display_bytes(&expected_body, 32)),
+      rule: None
     });
   }
 
@@ -220,6 +260,15 @@ impl MimePart {
       Self::File(file) => file.index,
     }
   }
+
+  /// Path of the part in the multipart body with the given path. Parts without a name use their index.
+  fn path(&self, parent: &DocPath) -> DocPath {
+    if self.name().is_empty() {
+      parent.join(self.index().to_string())
+    } else {
+      parent.join(self.name())
+    }
+  }
 }
 
 #[cfg(feature = "multipart")]
@@ -299,7 +348,7 @@ pub fn match_mime_multipart(
         Ok(rt) => {
           debug!("Spawning task on existing Tokio runtime");
           rt.block_on(async move {
-            let results = match_mime_multipart_inner(&context,
+            let results = match_mime_multipart_inner(&context, &DocPath::root(),
                                                      &expected_body, &actual_body, &expected_headers, &actual_headers).await;
             if let Err(err) = sender.send(results) {
               error!("Failed to send results back via channel: {}", err);
@@ -313,7 +362,7 @@ pub fn match_mime_multipart(
             .build()
             .expect("Could not start a Tokio runtime for running async tasks")
             .block_on(async move {
-              let results = match_mime_multipart_inner(&context,
+              let results = match_mime_multipart_inner(&context, &DocPath::root(),
                                                        &expected_body, &actual_body, &expected_headers, &actual_headers).await;
               if let Err(err) = sender.send(results) {
                 error!("Failed to send results back via channel: {}", err);
@@ -330,7 +379,8 @@ pub fn match_mime_multipart(
             path: "$".into(),
             expected: expected.body().value(),
             actual: actual.body().value(),
-            mismatch: format!("Timeout error, failed to parse the expected body as a MIME multipart body: {}", err)
+            mismatch: format!("Timeout error, failed to parse the expected body as a MIME multipart body: {}", err),
+            rule: None
           }
         ]
       })?;
@@ -348,9 +398,12 @@ pub fn match_mime_multipart(
   }
 }
 
+/// Matches the parts of a multipart body. The path is the path of the multipart body, which will
+/// be the root for the request or response body, or the path of the part for nested multipart parts.
 #[cfg(feature = "multipart")]
 async fn match_mime_multipart_inner(
-  context: &CoreMatchingContext,
+  context: &(dyn MatchingContext + Send + Sync),
+  path: &DocPath,
   expected_body: &OptionalBody,
   actual_body: &OptionalBody,
   expected_headers: &Option<HashMap<String, Vec<String>>>,
@@ -365,18 +418,20 @@ async fn match_mime_multipart_inner(
   if expected_parts.is_err() || actual_parts.is_err() {
     if let Err(e) = expected_parts {
       mismatches.push(Mismatch::BodyMismatch {
-        path: "$".into(),
+        path: path.to_string(),
         expected: expected_body.value(),
         actual: actual_body.value(),
-        mismatch: format!("Failed to parse the expected body as a MIME multipart body: '{}'", e)
+        mismatch: format!("Failed to parse the expected body as a MIME multipart body: '{}'", e),
+        rule: None
       });
     }
     if let Err(e) = actual_parts {
       mismatches.push(Mismatch::BodyMismatch {
-        path: "$".into(),
+        path: path.to_string(),
         expected: expected_body.value(),
         actual: actual_body.value(),
-        mismatch: format!("Failed to parse the actual body as a MIME multipart body: '{}'", e)
+        mismatch: format!("Failed to parse the actual body as a MIME multipart body: '{}'", e),
+        rule: None
       });
     }
   } else {
@@ -387,6 +442,7 @@ async fn match_mime_multipart_inner(
 
     for expected_part in expected_parts {
       let name = expected_part.name();
+      let part_path = expected_part.path(path);
 
       debug!("Comparing MIME multipart {}:'{}'", expected_part.index(), expected_part.name());
       match actual_parts.iter().find(|part| {
@@ -397,17 +453,18 @@ async fn match_mime_multipart_inner(
           name == expected_part.name()
         }
       }) {
-        Some(actual_part) => for error in match_mime_part(&expected_part, actual_part, context).await
+        Some(actual_part) => for error in match_mime_part(&part_path, &expected_part, actual_part, context).await
           .err().unwrap_or_default() {
           mismatches.push(error);
         },
         None => {
           debug!("MIME multipart '{}' is missing in the actual body", name);
           mismatches.push(Mismatch::BodyMismatch {
-            path: "$".into(),
+            path: part_path.to_string(),
             expected: Some(Bytes::from(name.clone())),
             actual: None,
-            mismatch: format!("Expected a MIME part '{}' but was missing", name)
+            mismatch: format!("Expected a MIME part '{}' but was missing", name),
+            rule: None
           });
         }
       }
@@ -418,6 +475,7 @@ async fn match_mime_multipart_inner(
 
 #[cfg(feature = "multipart")]
 async fn match_mime_part(
+  path: &DocPath,
   expected: &MimePart,
   actual: &MimePart,
   context: &(dyn MatchingContext + Send + Sync)
@@ -426,25 +484,25 @@ async fn match_mime_part(
 
   match (expected, actual) {
     (MimePart::Field(expected_field), MimePart::Field(actual_field)) => {
-      match_field(key, &expected_field, &actual_field, context)
+      match_field(path, key, &expected_field, &actual_field, context)
     },
     (MimePart::File(expected_file), MimePart::File(actual_file)) => {
-      match_file_part(key, expected_file, actual_file, context).await
+      match_file_part(path, key, expected_file, actual_file, context).await
     }
     (MimePart::Field(_), MimePart::File(_)) => {
       Err(vec![
-        Mismatch::BodyMismatch { path: "$".into(),
+        Mismatch::BodyMismatch { path: path.to_string(),
           expected: Some(Bytes::from(key.clone())),
           actual: None,
-          mismatch: format!("Expected a MIME field '{}' but was file", key)}
+          mismatch: format!("Expected a MIME field '{}' but was file", key), rule: None}
       ])
     },
     (MimePart::File(_), MimePart::Field(_)) => {
       Err(vec![
-        Mismatch::BodyMismatch { path: "$".into(),
+        Mismatch::BodyMismatch { path: path.to_string(),
           expected: Some(Bytes::from(key.clone())),
           actual: None,
-          mismatch: format!("Expected a MIME file '{}' but was field", key)}
+          mismatch: format!("Expected a MIME file '{}' but was field", key), rule: None}
       ])
     }
   }
@@ -452,17 +510,13 @@ async fn match_mime_part(
 
 #[cfg(feature = "multipart")]
 fn match_field(
+  path: &DocPath,
   key: &str,
   expected: &MimeField,
   actual: &MimeField,
   context: &(dyn MatchingContext + Send + Sync)
 ) -> Result<(), Vec<Mismatch>> {
   debug!("Comparing MIME part '{}' as a field", key);
-  let path = if key.is_empty() {
-    DocPath::root().join(expected.index.to_string())
-  } else {
-    DocPath::root().join(key)
-  };
   let expected_str = match expected.decode_data() {
     Ok(data) => String::from_utf8_lossy(data.as_ref()).to_string(),
     Err(err) => {
@@ -478,12 +532,16 @@ fn match_field(
     }
   };
 
-  let header_result = match_headers(&path, &expected.headers, &actual.headers, context);
+  let header_result = match_headers(path, &expected.headers, &actual.headers, context);
   debug!("Comparing headers at path '{}' -> {:?}", path, header_result);
 
-  let matcher_result = if context.matcher_is_defined(&path) {
-    debug!("Calling match_values for path $.{}", key);
-    match_values(&path, &context.select_best_matcher(&path), expected_str.as_str(), actual_str.as_str())
+  let body_path = path.join("body");
+  let matcher_result = if context.matcher_is_defined(&body_path) {
+    debug!("Calling match_values for path {}", body_path);
+    match_values(&body_path, &context.select_best_matcher(&body_path), expected_str.as_str(), actual_str.as_str())
+  } else if context.matcher_is_defined(path) {
+    debug!("Calling match_values for path {}", path);
+    match_values(path, &context.select_best_matcher(path), expected_str.as_str(), actual_str.as_str())
   } else {
     expected_str.matches_with(actual_str.as_str(), &MatchingRule::Equality, false).map_err(|err|
       vec![format!("MIME part '{}': {}", key, err)]
@@ -501,7 +559,8 @@ fn match_field(
         path: path.to_string(),
         expected: Some(expected.data.clone()),
         actual: Some(actual.data.clone()),
-        mismatch: message.clone()
+        mismatch: message.clone(),
+        rule: None
       }
     }));
   }
@@ -513,6 +572,45 @@ fn match_field(
   }
 }
 
+/// Header names are case-insensitive (and the keys in a HeaderMap are lowercase), so the header
+/// name in the matching rule paths for the headers of the part at `path` is lowercased. The part
+/// names in the paths are case-sensitive, so are left as is.
+#[cfg(feature = "multipart")]
+fn part_header_context(
+  path: &DocPath,
+  context: &(dyn MatchingContext + Send + Sync)
+) -> CoreMatchingContext {
+  let matchers = context.matchers();
+  CoreMatchingContext::new(
+    context.config(),
+    &MatchingRuleCategory {
+      name: matchers.name.clone(),
+      rules: matchers.rules.iter()
+        .map(|(rule_path, rules)| (lower_case_header_name(path, rule_path), rules.clone()))
+        .collect()
+    },
+    &context.plugin_configuration()
+  )
+}
+
+#[cfg(feature = "multipart")]
+fn lower_case_header_name(part_path: &DocPath, rule_path: &DocPath) -> DocPath {
+  let part_tokens = part_path.tokens();
+  let tokens = rule_path.tokens();
+  let is_header_path = tokens.starts_with(part_tokens) && match &tokens[part_tokens.len()..] {
+    [PathToken::Field(_)] => true,
+    [PathToken::Field(headers), PathToken::Field(_)] => headers == "headers",
+    _ => false
+  };
+  match (tokens.last(), rule_path.parent()) {
+    (Some(PathToken::Field(name)), Some(mut path)) if is_header_path => {
+      path.push_field(name.to_lowercase());
+      path
+    }
+    _ => rule_path.clone()
+  }
+}
+
 #[cfg(feature = "multipart")]
 pub(crate) fn match_headers(
   path: &DocPath,
@@ -521,10 +619,11 @@ pub(crate) fn match_headers(
   context: &(dyn MatchingContext + Send + Sync)
 ) -> Result<(), Vec<Mismatch>> {
   let mut results = vec![];
-  let header_context = HeaderMatchingContext::new(context);
+  let header_context = part_header_context(path, context);
 
   for key in expected.keys() {
     let key_path = path.join(key.to_string());
+    let headers_key_path = path.join("headers").join(key.to_string());
     let expected_value = expected.get(key).unwrap().clone();
     let expected_value_bin = expected_value.as_bytes();
     let expected_value_str = String::from_utf8_lossy(expected_value_bin).to_string();
@@ -534,12 +633,21 @@ pub(crate) fn match_headers(
     if let Some(actual_value) = actual.get(key) {
       let actual_value_bin = actual_value.as_bytes();
       let actual_value_str = String::from_utf8_lossy(actual_value_bin).to_string();
-      let matcher_result = if header_context.direct_matcher_defined(&key_path, &Default::default()) {
+      let matcher_result = if header_context.direct_matcher_defined(&headers_key_path, &Default::default()) {
+        debug!("Matcher is defined, calling match_values for path {}", headers_key_path);
+        match_values(&headers_key_path, &header_context.select_best_matcher(&headers_key_path),
+                     expected_value_str.as_str(), actual_value_str.as_str())
+      } else if header_context.direct_matcher_defined(&key_path, &Default::default()) {
         debug!("Matcher is defines, calling match_values for path {}", key_path);
         match_values(&key_path, &header_context.select_best_matcher(&key_path),
                      expected_value_str.as_str(), actual_value_str.as_str())
       } else if key == "content-disposition" {
         Ok(())
+      } else if key == "content-type" && is_multipart_content_type(&expected_value_str) {
+        // The boundaries of nested multipart parts are generated, so only compare the base types
+        match_base_content_type(&expected_value_str, &actual_value_str).map_err(|err|
+          vec![format!("header '{}': {}", key, err)]
+        )
       } else {
         expected_value_str.matches_with(actual_value_str.as_str(), &MatchingRule::Equality, false).map_err(|err|
           vec![format!("header '{}': {}", key, err)]
@@ -552,7 +660,8 @@ pub(crate) fn match_headers(
             path: key_path.to_string(),
             expected: Some(Bytes::from(expected_value_str.clone())),
             actual: Some(Bytes::from(actual_value_str.clone())),
-            mismatch: format!("MIME part '{}': {}", part_name, m)
+            mismatch: format!("MIME part '{}': {}", part_name, m),
+            rule: None
           }
         }));
       }
@@ -564,7 +673,8 @@ pub(crate) fn match_headers(
         expected: Some(Bytes::from(expected_value_str.clone())),
         actual: None,
         mismatch: format!("MIME part '{}': Expected multipart header '{}' with value '{}' but was missing",
-          part_name, key, expected_value_str)
+          part_name, key, expected_value_str),
+        rule: None
       });
     }
   }
@@ -576,6 +686,24 @@ pub(crate) fn match_headers(
   }
 }
 
+#[cfg(feature = "multipart")]
+fn is_multipart_content_type(content_type: &str) -> bool {
+  ContentType::parse(content_type)
+    .map(|ct| ct.main_type == "multipart")
+    .unwrap_or(false)
+}
+
+#[cfg(feature = "multipart")]
+fn match_base_content_type(expected: &str, actual: &str) -> anyhow::Result<()> {
+  let expected_type = ContentType::parse(expected)?.canonical_type();
+  let actual_type = ContentType::parse(actual)?.canonical_type();
+  if expected_type == actual_type {
+    Ok(())
+  } else {
+    Err(anyhow!("Expected '{}' to be equal to '{}'", actual, expected))
+  }
+}
+
 #[cfg(feature = "multipart")]
 fn first(bytes: &[u8], len: usize) -> &[u8] {
   if bytes.len() <= len {
@@ -634,6 +762,7 @@ impl Matches<&MimeFile> for &MimeFile {
 
 #[cfg(feature = "multipart")]
 async fn match_file_part(
+  path: &DocPath,
   key: &str,
   expected: &MimeFile,
   actual: &MimeFile,
@@ -645,13 +774,8 @@ async fn match_file_part(
     key.to_string()
   };
   debug!("Comparing MIME part '{}' as binary data", part_name);
-  let path = if key.is_empty() {
-    DocPath::root().join(expected.index.to_string())
-  } else {
-    DocPath::root().join(key)
-  };
 
-  let header_result = match_headers(&path, &expected.headers, &actual.headers, context);
+  let header_result = match_headers(path, &expected.headers, &actual.headers, context);
   debug!("Comparing headers at path '{}' -> {:?}", path, header_result);
 
   debug!("Expected part headers: {:?}", expected.headers);
@@ -665,6 +789,22 @@ async fn match_file_part(
     .map(|mime| ContentType::from(mime)). unwrap_or_default();
 
   debug!("Comparing mime part '{}': {} -> {}", part_name, expected_content_type, actual_content_type);
+  if expected_content_type.main_type == "multipart" && actual_content_type.main_type == "multipart" {
+    debug!("MIME part '{}' is a nested multipart body", part_name);
+    let mut results = header_result.err().unwrap_or_default();
+    let expected_body = OptionalBody::Present(expected.decode_data().unwrap_or_else(|_| expected.data.clone()), Some(expected_content_type.clone()), None);
+    let actual_body = OptionalBody::Present(actual.decode_data().unwrap_or_else(|_| actual.data.clone()), Some(actual_content_type.clone()), None);
+    let expected_headers = Some(hashmap!{ "Content-Type".to_string() => vec![expected_content_type.to_string()] });
+    let actual_headers = Some(hashmap!{ "Content-Type".to_string() => vec![actual_content_type.to_string()] });
+    results.extend(Box::pin(match_mime_multipart_inner(context, path, &expected_body, &actual_body,
+      &expected_headers, &actual_headers)).await);
+    return if results.is_empty() {
+      Ok(())
+    } else {
+      Err(results)
+    };
+  }
+
   let matcher_result = if expected_content_type.is_unknown() || actual_content_type.is_unknown() ||
       expected_content_type.is_equivalent_to(&actual_content_type) ||
       expected_content_type.is_equivalent_to(&actual_content_type.base_type()) {
@@ -676,19 +816,26 @@ async fn match_file_part(
       body: OptionalBody::Present(actual.decode_data().unwrap_or_else(|_| actual.data.clone()), Some(actual_content_type.clone()), None),
       .. HttpRequest::default()
     };
+    let body_path = path.join("body");
+    let headers_path = path.join("headers");
     let mut rule_category = context.matchers().clone();
     rule_category.rules = rule_category.rules.iter().filter_map(|(p, rules)| {
       let p_vec = p.to_vec();
       let path_slice = p_vec.iter().map(|p| p.as_str()).collect_vec();
-      if path.matches_path(&path_slice) {
-        let mut child_path = DocPath::root();
-        for path_part in p.tokens().iter().dropping(path.len()) {
-          child_path.push(path_part.clone());
-        }
-        Some((child_path, rules.clone()))
+      let prefix_len = if body_path.matches_path(&path_slice) {
+        body_path.len()
+      } else if headers_path.matches_path(&path_slice) {
+        return None;
+      } else if path.matches_path(&path_slice) {
+        path.len()
       } else {
-        None
+        return None;
+      };
+      let mut child_path = DocPath::root();
+      for path_part in p.tokens().iter().dropping(prefix_len) {
+        child_path.push(path_part.clone());
       }
+      Some((child_path, rules.clone()))
     }).collect();
     let context = context.clone_with(&rule_category);
     super::compare_bodies(&expected_content_type, &expected_part, &actual_part, context.as_ref()).await
@@ -710,12 +857,13 @@ async fn match_file_part(
     results.extend(header_mismatches);
   }
   results.extend(matcher_result.mismatches().iter().map(|m| {
-    if let Mismatch::BodyMismatch { path, expected, actual, mismatch } = m {
+    if let Mismatch::BodyMismatch { path: mismatch_path, expected, actual, mismatch, rule } = m {
       Mismatch::BodyMismatch {
-        path: path.clone(),
+        path: format!("{}{}", path, mismatch_path.strip_prefix('$').unwrap_or(mismatch_path)),
         expected: expected.clone(),
         actual: actual.clone(),
-        mismatch: format!("MIME part '{}': {}", part_name, mismatch)
+        mismatch: format!("MIME part '{}': {}", part_name, mismatch),
+        rule: rule.clone()
       }
     } else {
       m.clone()
@@ -744,7 +892,11 @@ async fn parse_multipart(
     let content_type = field.content_type().cloned();
     let headers = field.headers().clone();
 
-    if headers.contains_key("Content-Disposition") {
+    let is_multipart = content_type.as_ref()
+      .map(|content_type| content_type.type_() == mime::MULTIPART)
+      .unwrap_or(false);
+
+    if headers.contains_key("Content-Disposition") && !is_multipart {
       if let Some(filename) = field.file_name() {
         parts.push(MimePart::File(MimeFile {
           index,
@@ -763,11 +915,12 @@ async fn parse_multipart(
         }));
       }
     } else {
+      let filename = field.file_name().map(|filename| filename.to_string()).unwrap_or_default();
       parts.push(MimePart::File(MimeFile {
         index,
         name,
         content_type,
-        filename: String::default(),
+        filename,
         data: field.bytes().await?,
         headers
       }));
@@ -836,6 +989,14 @@ mod tests {
     }
   }
 
+  #[cfg(feature = "multipart")]
+  fn path(m: &Mismatch) -> &str {
+    match m {
+      Mismatch::BodyMismatch { path, .. } => path.as_str(),
+      _ => ""
+    }
+  }
+
   #[test_log::test]
   #[cfg(feature = "multipart")]
   fn match_mime_multipart_error_when_not_multipart() {
@@ -1190,6 +1351,107 @@ mod tests {
     ]));
   }
 
+  #[test]
+  #[cfg(feature = "multipart")]
+  fn match_mime_multipart_with_nested_multipart_part() {
+    let expected_body = Bytes::from("--1234\r\n\
+      Content-Type: text/plain\r\n\
+      Content-Disposition: form-data; name=\"name\"\r\n\r\nBaxter\r\n\
+      --1234\r\n\
+      Content-Type: multipart/mixed; boundary=abcd\r\n\
+      Content-Disposition: form-data; name=\"files\"\r\n\r\n\
+      --abcd\r\n\
+      Content-Type: text/plain\r\n\
+      Content-Disposition: attachment; name=\"first\"\r\n\r\nHello\r\n\
+      --abcd\r\n\
+      Content-Type: text/csv\r\n\
+      Content-Disposition: attachment; name=\"second\"; filename=\"008.csv\"\r\n\r\n\
+      1,2,3,4\r\n\
+      --abcd--\r\n\
+      --1234--\r\n");
+    let expected = Request {
+      headers: Some(hashmap!{ "Content-Type".into() => vec![ "multipart/form-data; boundary=1234".into() ] }),
+      body: OptionalBody::Present(expected_body, None, None),
+      ..Request::default()
+    };
+    let actual_body = Bytes::from("--4567\r\n\
+      Content-Type: text/plain\r\n\
+      Content-Disposition: form-data; name=\"name\"\r\n\r\nBaxter\r\n\
+      --4567\r\n\
+      Content-Type: multipart/mixed; boundary=efgh\r\n\
+      Content-Disposition: form-data; name=\"files\"\r\n\r\n\
+      --efgh\r\n\
+      Content-Type: text/plain\r\n\
+      Content-Disposition: attachment; name=\"first\"\r\n\r\nGoodbye\r\n\
+      --efgh\r\n\
+      Content-Type: text/csv\r\n\
+      Content-Disposition: attachment; name=\"second\"; filename=\"008.csv\"\r\n\r\n\
+      1,2,3,4\r\n\
+      --efgh--\r\n\
+      --4567--\r\n");
+    let actual = Request {
+      headers: Some(hashmap!{ "Content-Type".into() => vec![ "multipart/form-data; boundary=4567".into() ] }),
+      body: OptionalBody::Present(actual_body, None, None),
+      ..Request::default()
+    };
+    let context = CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys);
+
+    let result = match_mime_multipart(&expected, &actual, &context);
+
+    let mismatches = result.unwrap_err();
+    expect!(mismatches.iter().map(|m| (path(m), mismatch(m))).collect::<Vec<_>>()).to(be_equal_to(vec![
+      ("$.files.first", "MIME part 'first': Expected 'Goodbye' to be equal to 'Hello'")
+    ]));
+  }
+
+  #[test]
+  #[cfg(feature = "multipart")]
+  fn match_mime_multipart_with_matching_rules_for_part_headers_and_body() {
+    let expected_body = Bytes::from("--1234\r\n\
+      Content-Type: text/plain\r\n\
+      Content-Disposition: form-data; name=\"name\"\r\n\r\nBaxter\r\n\
+      --1234\r\n\
+      Content-Type: text/csv\r\n\
+      X-Custom: abc\r\n\
+      Content-Disposition: form-data; name=\"file\"; filename=\"008.csv\"\r\n\r\n\
+      1,2,3,4\r\n\
+      --1234--\r\n");
+    let expected = Request {
+      headers: Some(hashmap!{ "Content-Type".into() => vec![ "multipart/form-data; boundary=1234".into() ] }),
+      body: OptionalBody::Present(expected_body, None, None),
+      matching_rules: matchingrules! {
+        "body" => {
+          "$.name.body" => [ MatchingRule::Regex("^[A-Z][a-z]+$".to_string()) ],
+          "$.file.headers['X-Custom']" => [ MatchingRule::Regex("^[a-z]+$".to_string()) ]
+        }
+      },
+      ..Request::default()
+    };
+    let actual_body = Bytes::from("--4567\r\n\
+      Content-Type: text/plain\r\n\
+      Content-Disposition: form-data; name=\"name\"\r\n\r\nfred\r\n\
+      --4567\r\n\
+      Content-Type: text/csv\r\n\
+      X-Custom: xyz\r\n\
+      Content-Disposition: form-data; name=\"file\"; filename=\"009.csv\"\r\n\r\n\
+      1,2,3,4\r\n\
+      --4567--\r\n");
+    let actual = Request {
+      headers: Some(hashmap!{ "Content-Type".into() => vec![ "multipart/form-data; boundary=4567".into() ] }),
+      body: OptionalBody::Present(actual_body, None, None),
+      ..Request::default()
+    };
+    let context = CoreMatchingContext::new(DiffConfig::AllowUnexpectedKeys,
+      &expected.matching_rules.rules_for_category("body").unwrap(), &hashmap!{});
+
+    let result = match_mime_multipart(&expected, &actual, &context);
+
+    let mismatches = result.unwrap_err();
+    expect!(mismatches.iter().map(|m| (path(m), mismatch(m))).collect::<Vec<_>>()).to(be_equal_to(vec![
+      ("$.name", "Expected 'fred' to match '^[A-Z][a-z]+$'")
+    ]));
+  }
+
   #[test]
   #[cfg(feature = "multipart")]
   fn match_content_type_equals() {
@@ -1420,6 +1682,31 @@ mod tests {
     expect!(result).to(be_ok());
   }
 
+  #[test_log::test]
+  #[cfg(feature = "multipart")]
+  fn match_headers_with_a_matcher_only_lowercases_the_header_name() {
+    let path = DocPath::new_unwrap("$.Upload");
+    let context = CoreMatchingContext::new(DiffConfig::AllowUnexpectedKeys,
+      &matchingrules_list! {
+        "body"; "$.Upload.headers['X-Two']" => [ MatchingRule::Regex("^[0-9]+$".to_string()) ],
+        "$.upload.headers['x-one']" => [ MatchingRule::Regex("^[0-9]+$".to_string()) ]
+      }, &hashmap!{});
+
+    let mut expected = HeaderMap::new();
+    expected.insert("x-one", "example.com".parse().unwrap());
+    expected.insert("x-two", "123".parse().unwrap());
+
+    let mut actual = HeaderMap::new();
+    actual.insert("x-one", "example.org".parse().unwrap());
+    actual.insert("x-two", "456".parse().unwrap());
+
+    let result = super::match_headers(&path, &expected, &actual, &context);
+    let mismatches = result.unwrap_err();
+    expect!(mismatches.iter().map(|m| mismatch(m)).collect::<Vec<&str>>()).to(be_equal_to(vec![
+      "MIME part 'Upload': header 'x-one': Expected 'example.org' to be equal to 'example.com'"
+    ]));
+  }
+
   #[test_log::test]
   #[cfg(feature = "multipart")]
   fn match_headers_ignores_content_disposition() {
@@ -1465,4 +1752,32 @@ mod tests {
     let result = match_mime_multipart(&expected, &actual, &context);
     expect!(result).to(be_ok());
   }
+
+  #[test]
+  fn match_min_size_test() {
+    expect!(super::match_min_size(&[1, 2, 3], 2)).to(be_ok());
+    expect!(super::match_min_size(&[1, 2, 3], 3)).to(be_ok());
+    expect!(super::match_min_size(&[1, 2, 3], 4).unwrap_err().to_string()).to(
+      be_equal_to("Expected binary contents to be at least 4 bytes, but was 3 bytes"));
+  }
+
+  #[test]
+  fn match_max_size_test() {
+    expect!(super::match_max_size(&[1, 2, 3], 4)).to(be_ok());
+    expect!(super::match_max_size(&[1, 2, 3], 3)).to(be_ok());
+    expect!(super::match_max_size(&[1, 2, 3], 2).unwrap_err().to_string()).to(
+      be_equal_to("Expected binary contents to be at most 2 bytes, but was 3 bytes"));
+  }
+
+  #[test]
+  fn match_bytes_at_offset_test() {
+    let data = [0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a];
+    expect!(super::match_bytes_at_offset(&data, 0, &[0x89, 0x50, 0x4e, 0x47])).to(be_ok());
+    expect!(super::match_bytes_at_offset(&data, 4, &[0x0d, 0x0a])).to(be_ok());
+    expect!(super::match_bytes_at_offset(&data, 1, &[0x89]).unwrap_err().to_string()).to(
+      be_equal_to("Expected binary contents at offset 1 to be 89, but was 50"));
+    expect!(super::match_bytes_at_offset(&data, 5, &[0x0a, 0x00]).unwrap_err().to_string()).to(
+      be_equal_to("Expected binary contents at offset 5 to be 0a00, but the contents is only 6 bytes"));
+    expect!(super::match_bytes_at_offset(&data, usize::MAX, &[0x00])).to(be_err());
+  }
 }
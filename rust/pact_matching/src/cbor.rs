@@ -0,0 +1,200 @@
+//! Functions for matching CBOR encoded bodies. The bodies are decoded into a JSON tree, so the
+//! normal JSON matching rules and generators can be applied to them.
+
+use anyhow::anyhow;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use ciborium::value::Value as CborValue;
+use serde_json::{Map, Number, Value};
+use tracing::debug;
+
+use pact_models::http_parts::HttpPart;
+use pact_models::path_exp::DocPath;
+
+use crate::{MatchingContext, Mismatch};
+use crate::json::compare_json;
+
+/// Decodes the CBOR encoded data into a JSON tree. Byte strings are converted to Base64 encoded
+/// strings, tags are dropped and any map keys that are not strings are converted to their JSON
+/// form.
+pub fn decode_cbor(data: &[u8]) -> anyhow::Result<Value> {
+  let value: CborValue = ciborium::de::from_reader(data)
+    .map_err(|err| anyhow!("Failed to decode the CBOR value - {}", err))?;
+  Ok(cbor_to_json(value))
+}
+
+/// Encodes the JSON tree as CBOR
+pub fn encode_cbor(value: &Value) -> anyhow::Result<Vec<u8>> {
+  let mut buffer = vec![];
+  ciborium::ser::into_writer(value, &mut buffer)
+    .map_err(|err| anyhow!("Failed to encode the value as CBOR - {}", err))?;
+  Ok(buffer)
+}
+
+fn cbor_to_json(value: CborValue) -> Value {
+  match value {
+    CborValue::Null => Value::Null,
+    CborValue::Bool(b) => Value::Bool(b),
+    CborValue::Integer(i) => {
+      let i = i128::from(i);
+      if let Ok(i) = i64::try_from(i) {
+        Value::Number(Number::from(i))
+      } else if let Ok(i) = u64::try_from(i) {
+        Value::Number(Number::from(i))
+      } else {
+        Number::from_f64(i as f64).map(Value::Number).unwrap_or(Value::Null)
+      }
+    }
+    CborValue::Float(f) => Number::from_f64(f).map(Value::Number).unwrap_or(Value::Null),
+    CborValue::Text(s) => Value::String(s),
+    CborValue::Bytes(b) => Value::String(BASE64.encode(b)),
+    CborValue::Tag(_, value) => cbor_to_json(*value),
+    CborValue::Array(values) => Value::Array(values.into_iter().map(cbor_to_json).collect()),
+    CborValue::Map(entries) => Value::Object(entries.into_iter()
+      .map(|(key, value)| {
+        let key = match cbor_to_json(key) {
+          Value::String(s) => s,
+          key => key.to_string()
+        };
+        (key, cbor_to_json(value))
+      })
+      .collect::<Map<String, Value>>()),
+    _ => Value::Null
+  }
+}
+
+/// Matches the CBOR encoded expected and actual bodies
+pub fn match_cbor(
+  expected: &(dyn HttpPart + Send + Sync),
+  actual: &(dyn HttpPart + Send + Sync),
+  context: &(dyn MatchingContext + Send + Sync)
+) -> Result<(), Vec<Mismatch>> {
+  let expected_body = expected.body().value().unwrap_or_default();
+  let actual_body = actual.body().value().unwrap_or_default();
+  debug!("Matching CBOR bodies: expected {} bytes, actual {} bytes", expected_body.len(), actual_body.len());
+
+  match (decode_cbor(&expected_body), decode_cbor(&actual_body)) {
+    (Ok(expected_json), Ok(actual_json)) => {
+      compare_json(&DocPath::root(), &expected_json, &actual_json, context)
+        .map_err(|mismatches| mismatches.iter().map(|mismatch| mismatch.to_body_mismatch()).collect())
+    }
+    (expected_json, actual_json) => {
+      let mut mismatches = vec![];
+      if let Err(err) = expected_json {
+        mismatches.push(Mismatch::BodyMismatch {
+          path: "$".to_string(),
+          expected: expected.body().value(),
+          actual: actual.body().value(),
+          mismatch: format!("Failed to decode the expected CBOR body: '{}'", err),
+          rule: None
+        });
+      }
+      if let Err(err) = actual_json {
+        mismatches.push(Mismatch::BodyMismatch {
+          path: "$".to_string(),
+          expected: expected.body().value(),
+          actual: actual.body().value(),
+          mismatch: format!("Failed to decode the actual CBOR body: '{}'", err),
+          rule: None
+        });
+      }
+      Err(mismatches)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ciborium::value::Value as CborValue;
+  use expectest::prelude::*;
+  use maplit::hashmap;
+  use pact_models::bodies::OptionalBody;
+  use pact_models::matchingrules;
+  use pact_models::matchingrules::MatchingRule;
+  use pact_models::request::Request;
+  use serde_json::json;
+
+  use crate::{CoreMatchingContext, DiffConfig, Mismatch};
+
+  use super::{decode_cbor, encode_cbor, match_cbor};
+
+  fn request(data: Vec<u8>) -> Request {
+    Request {
+      headers: Some(hashmap!{ "Content-Type".to_string() => vec!["application/cbor".to_string()] }),
+      body: OptionalBody::Present(data.into(), None, None),
+      .. Request::default()
+    }
+  }
+
+  #[test]
+  fn decode_cbor_test() {
+    let value = CborValue::Map(vec![
+      (CborValue::Text("id".into()), CborValue::Integer(100.into())),
+      (CborValue::Text("name".into()), CborValue::Text("Fred".into())),
+      (CborValue::Text("data".into()), CborValue::Bytes(vec![1, 2, 3])),
+      (CborValue::Integer(1.into()), CborValue::Tag(1, Box::new(CborValue::Float(1.5))))
+    ]);
+    let mut buffer = vec![];
+    ciborium::ser::into_writer(&value, &mut buffer).unwrap();
+    expect!(decode_cbor(&buffer).unwrap()).to(be_equal_to(json!({
+      "id": 100,
+      "name": "Fred",
+      "data": "AQID",
+      "1": 1.5
+    })));
+    expect!(decode_cbor(&[0xff])).to(be_err());
+  }
+
+  #[test]
+  fn encode_cbor_round_trips_json_values() {
+    let value = json!({ "id": 100, "name": "Fred", "tags": [ true, null, -1.5 ] });
+    expect!(decode_cbor(&encode_cbor(&value).unwrap()).unwrap()).to(be_equal_to(value));
+  }
+
+  #[test]
+  fn match_cbor_applies_matching_rules_to_the_decoded_bodies() {
+    let expected = request(encode_cbor(&json!({ "id": 100, "name": "Fred" })).unwrap());
+    let actual = request(encode_cbor(&json!({ "id": 200, "name": "Fred" })).unwrap());
+
+    let context = CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys);
+    let result = match_cbor(&expected, &actual, &context);
+    expect!(result.unwrap_err().iter().map(|m| m.description()).collect::<Vec<_>>()).to(be_equal_to(vec![
+      "$.id -> Expected 200 (Integer) to be equal to 100 (Integer)".to_string()
+    ]));
+
+    let rules = matchingrules! {
+      "body" => { "$.id" => [ MatchingRule::Integer ] }
+    };
+    let context = CoreMatchingContext::new(
+      DiffConfig::AllowUnexpectedKeys,
+      &rules.rules_for_category("body").unwrap_or_default(), &hashmap!{}
+    );
+    expect!(match_cbor(&expected, &actual, &context)).to(be_ok());
+  }
+
+  #[test]
+  fn match_cbor_returns_a_mismatch_if_the_body_can_not_be_decoded() {
+    let expected = request(encode_cbor(&json!({ "id": 100 })).unwrap());
+    let actual = request(vec![0xff]);
+
+    let context = CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys);
+    let mismatches = match_cbor(&expected, &actual, &context).unwrap_err();
+    expect!(mismatches.len()).to(be_equal_to(1));
+    expect!(matches!(&mismatches[0], Mismatch::BodyMismatch { mismatch, .. } if mismatch.starts_with("Failed to decode the actual CBOR body"))).to(be_true());
+  }
+
+  #[tokio::test]
+  async fn match_body_uses_the_cbor_matcher_for_cbor_content_types() {
+    let expected = request(encode_cbor(&json!({ "id": 100, "name": "Fred" })).unwrap());
+    let actual = request(encode_cbor(&json!({ "name": "Fred", "id": 100 })).unwrap());
+
+    let context = CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys);
+    expect!(crate::match_body(&expected, &actual, &context, &context).await.all_matched()).to(be_true());
+
+    let actual = request(encode_cbor(&json!({ "id": 100, "name": "Mary" })).unwrap());
+    let mismatches = crate::match_body(&expected, &actual, &context, &context).await.mismatches();
+    expect!(mismatches.iter().map(|m| m.description()).collect::<Vec<_>>()).to(be_equal_to(vec![
+      "$.name -> Expected 'Mary' (String) to be equal to 'Fred' (String)".to_string()
+    ]));
+  }
+}
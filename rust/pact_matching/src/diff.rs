@@ -0,0 +1,350 @@
+//! Functions for comparing two versions of a pact, and reporting the interactions that have been
+//! added, removed or changed. This can be used to check for contract changes without needing a
+//! Pact Broker.
+
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+use itertools::Itertools;
+use pact_models::interaction::Interaction;
+use pact_models::pact::Pact;
+use serde_json::{json, Value};
+
+/// Reference to an interaction in a pact. Interactions are matched between the two pacts by their
+/// description and provider state names.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct InteractionRef {
+  /// Description of the interaction
+  pub description: String,
+  /// Names of the provider states of the interaction
+  pub provider_states: Vec<String>
+}
+
+impl InteractionRef {
+  fn from_interaction(interaction: &dyn Interaction) -> Self {
+    InteractionRef {
+      description: interaction.description(),
+      provider_states: interaction.provider_states().iter().map(|state| state.name.clone()).collect()
+    }
+  }
+
+  /// Returns a JSON representation of the reference
+  pub fn to_json(&self) -> Value {
+    json!({
+      "description": self.description,
+      "providerStates": self.provider_states
+    })
+  }
+}
+
+impl Display for InteractionRef {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    if self.provider_states.is_empty() {
+      write!(f, "{}", self.description)
+    } else {
+      write!(f, "{} (given {})", self.description, self.provider_states.join(", "))
+    }
+  }
+}
+
+/// Change to a field of an interaction
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldChange {
+  /// The field was added in the new pact
+  Added {
+    /// Path to the field in the V4 JSON form of the interaction (i.e. `request.headers.Accept`)
+    path: String,
+    /// New value of the field
+    value: Value
+  },
+  /// The field was removed from the new pact
+  Removed {
+    /// Path to the field in the V4 JSON form of the interaction
+    path: String,
+    /// Old value of the field
+    value: Value
+  },
+  /// The value of the field was changed
+  Changed {
+    /// Path to the field in the V4 JSON form of the interaction
+    path: String,
+    /// Old value of the field
+    old: Value,
+    /// New value of the field
+    new: Value
+  }
+}
+
+impl FieldChange {
+  /// Path to the field in the V4 JSON form of the interaction
+  pub fn path(&self) -> &str {
+    match self {
+      FieldChange::Added { path, .. } => path,
+      FieldChange::Removed { path, .. } => path,
+      FieldChange::Changed { path, .. } => path
+    }
+  }
+
+  /// Returns a JSON representation of the change
+  pub fn to_json(&self) -> Value {
+    match self {
+      FieldChange::Added { path, value } => json!({ "type": "added", "path": path, "new": value }),
+      FieldChange::Removed { path, value } => json!({ "type": "removed", "path": path, "old": value }),
+      FieldChange::Changed { path, old, new } => json!({ "type": "changed", "path": path, "old": old, "new": new })
+    }
+  }
+}
+
+impl Display for FieldChange {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      FieldChange::Added { path, value } => write!(f, "{}: added {}", path, value),
+      FieldChange::Removed { path, value } => write!(f, "{}: removed {}", path, value),
+      FieldChange::Changed { path, old, new } => write!(f, "{}: changed from {} to {}", path, old, new)
+    }
+  }
+}
+
+/// Changes to an interaction that is in both pacts
+#[derive(Debug, Clone, PartialEq)]
+pub struct InteractionDiff {
+  /// The interaction that was changed
+  pub interaction: InteractionRef,
+  /// The changes to the fields of the interaction
+  pub changes: Vec<FieldChange>
+}
+
+/// Differences between two versions of a pact
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PactDiff {
+  /// Interactions that are only in the new pact
+  pub added: Vec<InteractionRef>,
+  /// Interactions that are only in the old pact
+  pub removed: Vec<InteractionRef>,
+  /// Interactions that are in both pacts, but have different contents
+  pub changed: Vec<InteractionDiff>
+}
+
+impl PactDiff {
+  /// If there are no differences between the pacts
+  pub fn is_empty(&self) -> bool {
+    self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+  }
+
+  /// Returns a JSON representation of the differences
+  pub fn to_json(&self) -> Value {
+    json!({
+      "added": self.added.iter().map(|i| i.to_json()).collect_vec(),
+      "removed": self.removed.iter().map(|i| i.to_json()).collect_vec(),
+      "changed": self.changed.iter().map(|diff| {
+        let mut json = diff.interaction.to_json();
+        json["changes"] = diff.changes.iter().map(|change| change.to_json()).collect();
+        json
+      }).collect_vec()
+    })
+  }
+}
+
+impl Display for PactDiff {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    if self.is_empty() {
+      return writeln!(f, "There are no differences between the pacts");
+    }
+
+    if !self.added.is_empty() {
+      writeln!(f, "Added interactions:")?;
+      for interaction in &self.added {
+        writeln!(f, "  + {}", interaction)?;
+      }
+    }
+    if !self.removed.is_empty() {
+      writeln!(f, "Removed interactions:")?;
+      for interaction in &self.removed {
+        writeln!(f, "  - {}", interaction)?;
+      }
+    }
+    if !self.changed.is_empty() {
+      writeln!(f, "Changed interactions:")?;
+      for diff in &self.changed {
+        writeln!(f, "  * {}", diff.interaction)?;
+        for change in &diff.changes {
+          writeln!(f, "      {}", change)?;
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Compares two versions of a pact, and returns the interactions that have been added, removed or
+/// changed. Interactions are matched by their description and provider state names, and are
+/// compared in their V4 form (so a V3 pact can be compared to a V4 one), ignoring fields that do
+/// not form part of the contract, like the interaction key and comments. Changed interactions
+/// include the changes to each field (request and response parts, message contents, matching
+/// rules, generators, etc.).
+pub fn diff_pacts(old: &dyn Pact, new: &dyn Pact) -> PactDiff {
+  let old_interactions = old.interactions().iter()
+    .map(|i| (InteractionRef::from_interaction(i.as_ref()), canonical_json(i.as_ref())))
+    .collect_vec();
+  let mut new_interactions = new.interactions().iter()
+    .map(|i| (InteractionRef::from_interaction(i.as_ref()), canonical_json(i.as_ref())))
+    .map(Some)
+    .collect_vec();
+
+  let mut diff = PactDiff::default();
+  for (interaction, old_json) in old_interactions {
+    let matched = new_interactions.iter_mut()
+      .find(|entry| entry.as_ref().map(|(i, _)| *i == interaction).unwrap_or(false))
+      .and_then(|entry| entry.take());
+    match matched {
+      Some((_, new_json)) => {
+        let mut changes = vec![];
+        diff_json("", &old_json, &new_json, &mut changes);
+        if !changes.is_empty() {
+          diff.changed.push(InteractionDiff { interaction, changes });
+        }
+      }
+      None => diff.removed.push(interaction)
+    }
+  }
+  diff.added = new_interactions.into_iter().flatten().map(|(i, _)| i).collect();
+
+  diff
+}
+
+fn canonical_json(interaction: &dyn Interaction) -> Value {
+  match interaction.normalised() {
+    Some(normalised) => {
+      let mut json = normalised.canonical_json();
+      if let Some(map) = json.as_object_mut() {
+        map.remove("description");
+      }
+      json
+    }
+    None => Value::Null
+  }
+}
+
+fn join_path(path: &str, field: &str) -> String {
+  if path.is_empty() {
+    field.to_string()
+  } else {
+    format!("{}.{}", path, field)
+  }
+}
+
+fn diff_json(path: &str, old: &Value, new: &Value, changes: &mut Vec<FieldChange>) {
+  match (old, new) {
+    (Value::Object(old_map), Value::Object(new_map)) => {
+      let keys: BTreeSet<&String> = old_map.keys().chain(new_map.keys()).collect();
+      for key in keys {
+        let field_path = join_path(path, key);
+        match (old_map.get(key), new_map.get(key)) {
+          (Some(old_value), Some(new_value)) => diff_json(&field_path, old_value, new_value, changes),
+          (Some(old_value), None) => changes.push(FieldChange::Removed { path: field_path, value: old_value.clone() }),
+          (None, Some(new_value)) => changes.push(FieldChange::Added { path: field_path, value: new_value.clone() }),
+          (None, None) => {}
+        }
+      }
+    }
+    (Value::Array(old_list), Value::Array(new_list)) => {
+      for index in 0..old_list.len().max(new_list.len()) {
+        let item_path = format!("{}[{}]", path, index);
+        match (old_list.get(index), new_list.get(index)) {
+          (Some(old_value), Some(new_value)) => diff_json(&item_path, old_value, new_value, changes),
+          (Some(old_value), None) => changes.push(FieldChange::Removed { path: item_path, value: old_value.clone() }),
+          (None, Some(new_value)) => changes.push(FieldChange::Added { path: item_path, value: new_value.clone() }),
+          (None, None) => {}
+        }
+      }
+    }
+    _ => if old != new {
+      changes.push(FieldChange::Changed { path: path.to_string(), old: old.clone(), new: new.clone() });
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+  use pact_models::matchingrules::MatchingRule;
+  use pact_models::path_exp::DocPath;
+  use pact_models::prelude::*;
+  use serde_json::json;
+
+  use super::*;
+
+  fn interaction(description: &str, state: &str, method: &str, status: u16) -> RequestResponseInteraction {
+    RequestResponseInteraction {
+      description: description.to_string(),
+      provider_states: vec![ProviderState::default(state)],
+      request: Request { method: method.to_string(), path: "/users".to_string(), .. Request::default() },
+      response: Response { status, .. Response::default() },
+      .. RequestResponseInteraction::default()
+    }
+  }
+
+  fn pact(interactions: Vec<RequestResponseInteraction>) -> RequestResponsePact {
+    RequestResponsePact {
+      consumer: Consumer { name: "consumer".to_string() },
+      provider: Provider { name: "provider".to_string() },
+      interactions,
+      specification_version: PactSpecification::V3,
+      .. RequestResponsePact::default()
+    }
+  }
+
+  #[test]
+  fn diff_pacts_returns_no_differences_for_the_same_pact() {
+    let old = pact(vec![interaction("get users", "users exist", "GET", 200)]);
+    let new = old.as_v4_pact().unwrap();
+    let diff = diff_pacts(&old, &new);
+    expect!(diff.is_empty()).to(be_true());
+    expect!(diff.to_string()).to(be_equal_to("There are no differences between the pacts\n"));
+  }
+
+  #[test]
+  fn diff_pacts_reports_added_removed_and_changed_interactions() {
+    let old = pact(vec![
+      interaction("get users", "users exist", "GET", 200),
+      interaction("delete user", "user 1 exists", "DELETE", 204)
+    ]);
+    let mut changed = interaction("get users", "users exist", "POST", 201);
+    changed.response.matching_rules.add_category("body")
+      .add_rule(DocPath::new_unwrap("$.id"), MatchingRule::Type, RuleLogic::And);
+    let new = pact(vec![
+      changed,
+      interaction("get user", "user 1 exists", "GET", 200)
+    ]).as_v4_pact().unwrap();
+
+    let diff = diff_pacts(&old, &new);
+    expect!(diff.added.clone()).to(be_equal_to(vec![InteractionRef {
+      description: "get user".to_string(),
+      provider_states: vec!["user 1 exists".to_string()]
+    }]));
+    expect!(diff.removed.clone()).to(be_equal_to(vec![InteractionRef {
+      description: "delete user".to_string(),
+      provider_states: vec!["user 1 exists".to_string()]
+    }]));
+    expect!(diff.changed.len()).to(be_equal_to(1));
+    expect!(diff.changed[0].changes.clone()).to(be_equal_to(vec![
+      FieldChange::Changed { path: "request.method".to_string(), old: json!("GET"), new: json!("POST") },
+      FieldChange::Added {
+        path: "response.matchingRules".to_string(),
+        value: json!({ "body": { "$.id": { "combine": "AND", "matchers": [ { "match": "type" } ] } } })
+      },
+      FieldChange::Changed { path: "response.status".to_string(), old: json!(200), new: json!(201) }
+    ]));
+
+    expect!(diff.to_string()).to(be_equal_to(r#"Added interactions:
+  + get user (given user 1 exists)
+Removed interactions:
+  - delete user (given user 1 exists)
+Changed interactions:
+  * get users (given users exist)
+      request.method: changed from "GET" to "POST"
+      response.matchingRules: added {"body":{"$.id":{"combine":"AND","matchers":[{"match":"type"}]}}}
+      response.status: changed from 200 to 201
+"#));
+  }
+}
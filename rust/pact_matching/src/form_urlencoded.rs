@@ -24,7 +24,8 @@ pub(crate) fn match_form_urlencoded(
           path: "$".into(),
           expected: expected_body.value(),
           actual: actual_body.value(),
-          mismatch: format!("Expected an empty body, but got '{}'", actual_body.value_as_string().unwrap_or(actual_body.display_string()))
+          mismatch: format!("Expected an empty body, but got '{}'", actual_body.value_as_string().unwrap_or(actual_body.display_string())),
+          rule: None
         }
       ])
     }
@@ -35,7 +36,8 @@ pub(crate) fn match_form_urlencoded(
             path: "$".into(),
             expected: expected_body.value(),
             actual: actual_body.value(),
-            mismatch: format!("Could not parse expected body: {}", err)
+            mismatch: format!("Could not parse expected body: {}", err),
+            rule: None
           }
         });
       let actual_bytes = actual_body.value().unwrap_or_default();
@@ -45,7 +47,8 @@ pub(crate) fn match_form_urlencoded(
             path: "$".into(),
             expected: expected_body.value(),
             actual: actual_body.value(),
-            mismatch: format!("Could not parse actual body: {}", err)
+            mismatch: format!("Could not parse actual body: {}", err),
+            rule: None
           }
         });
       match (expected_form, actual_form) {
@@ -68,14 +71,16 @@ pub(crate) fn match_form_urlencoded(
                 path: format!("$.{}", parameter),
                 expected: Some(expected.clone().into()),
                 actual: Some(actual.clone().into()),
-                mismatch: mismatch.replace("query parameter", "form post parameter")
+                mismatch: mismatch.replace("query parameter", "form post parameter"),
+                rule: None
               }
             } else {
               Mismatch::BodyMismatch {
                 path: "$".to_string(),
                 expected: None,
                 actual: None,
-                mismatch: mismatch.description()
+                mismatch: mismatch.description(),
+                rule: None
               }
             }
           })).collect();
@@ -199,6 +204,7 @@ mod tests {
       expected: Some("".into()),
       actual: Some("[\"b\"]".into()),
       mismatch: "".to_string(),
+      rule: None
     });
     assert_eq!(mismatches[0].description(), "$.a -> Unexpected form post parameter 'a' received");
   }
@@ -220,7 +226,8 @@ mod tests {
       path: "$.a".to_string(),
       expected: Some("[\"b\"]".into()),
       actual: Some("".into()),
-      mismatch: "Expected form post parameter 'a' but was missing".to_string()
+      mismatch: "Expected form post parameter 'a' but was missing".to_string(),
+      rule: None
     });
     assert_eq!(mismatches[0].description(), "$.a -> Expected form post parameter 'a' but was missing");
   }
@@ -243,6 +250,7 @@ mod tests {
       expected: Some("".into()),
       actual: Some("[\"d\"]".into()),
       mismatch: "".to_string(),
+      rule: None
     });
     assert_eq!(mismatches[0].description(), "$.c -> Unexpected form post parameter 'c' received");
   }
@@ -265,6 +273,7 @@ mod tests {
       expected: Some("[\"d\"]".into()),
       actual: Some("".into()),
       mismatch: "".to_string(),
+      rule: None
     });
     assert_eq!(mismatches[0].description(), "$.c -> Expected form post parameter 'c' but was missing");
   }
@@ -287,6 +296,7 @@ mod tests {
       expected: Some("".into()),
       actual: Some("d".into()),
       mismatch: "".to_string(),
+      rule: None
     });
     assert_eq!(mismatches[0].description(), "$.c -> Expected form post parameter 'c' with value '' but was 'd'");
   }
@@ -309,6 +319,7 @@ mod tests {
       expected: Some("[\"d\", \"e\"]".into()),
       actual: Some("[\"d\"]".into()),
       mismatch: "".to_string(),
+      rule: None
     });
     assert_eq!(mismatches[0].description(), "$.c -> Expected form post parameter 'c' value 'e' but was missing");
     assert_eq!(mismatches[1], Mismatch::BodyMismatch {
@@ -316,6 +327,7 @@ mod tests {
       expected: Some("[\"d\", \"e\"]".into()),
       actual: Some("[\"d\"]".into()),
       mismatch: "".to_string(),
+      rule: None
     });
     assert_eq!(mismatches[1].description(), "$.c -> Expected form post parameter 'c' with 2 value(s) but received 1 value(s)");
   }
@@ -338,6 +350,7 @@ mod tests {
       expected: Some("b".into()),
       actual: Some("c".into()),
       mismatch: "".to_string(),
+      rule: None
     });
     assert_eq!(mismatches[0].description(), "$.a -> Expected form post parameter 'a' with value 'b' but was 'c'");
   }
@@ -409,6 +422,7 @@ mod tests {
       expected: Some("1".into()),
       actual: Some("hgjhghgh".into()),
       mismatch: "".to_string(),
+      rule: None
     });
     assert_eq!(mismatches[0].description(), "$.a -> Expected 'hgjhghgh' to match '\\d+'");
   }
@@ -5,7 +5,7 @@ use expectest::prelude::*;
 use pact_models::bodies::OptionalBody;
 use pact_models::content_types::JSON;
 use pact_models::generators;
-use pact_models::generators::{ContentTypeHandler, Generator, JsonHandler};
+use pact_models::generators::{ContentTypeHandler, Generator, JsonHandler, RANDOM_SEED};
 use pact_models::message::Message;
 use pact_models::path_exp::DocPath;
 use serde_json::Value;
@@ -114,6 +114,87 @@ async fn applies_provider_state_generator_for_query_parameters_with_square_brack
   expect!(q_val).to(be_equal_to(&vec![Some("5678".to_string()), Some("5678".to_string())]));
 }
 
+#[tokio::test]
+async fn applies_provider_state_generators_to_the_path_and_headers() {
+  let mut request = HttpRequest {
+    path: "/orders/1".to_string(),
+    headers: Some(hashmap!{
+      "x-order-id".to_string() => vec!["1".to_string()],
+      "Accept".to_string() => vec!["application/json".to_string()]
+    }),
+    generators: generators! {
+      "HEADER" => {
+        "X-Order-ID" => Generator::ProviderStateGenerator("id".to_string(), None),
+        "X-Tags" => Generator::ProviderStateGenerator("tags".to_string(), None)
+      }
+    }, .. HttpRequest::default()
+  };
+  request.generators.add_generator(&GeneratorCategory::PATH,
+    Generator::ProviderStateGenerator("/orders/${id}".to_string(), None));
+  let context = hashmap! {
+    "id" => json!(1234),
+    "tags" => json!(["a", "b"])
+  };
+  let result = generate_request(&request, &GeneratorTestMode::Provider, &context).await;
+  expect!(result.path).to(be_equal_to("/orders/1234"));
+  expect!(result.headers).to(be_some().value(hashmap!{
+    "x-order-id".to_string() => vec!["1234".to_string()],
+    "Accept".to_string() => vec!["application/json".to_string()],
+    "X-Tags".to_string() => vec!["a".to_string(), "b".to_string()]
+  }));
+}
+
+#[tokio::test]
+async fn does_not_add_headers_if_the_provider_state_value_is_missing() {
+  let request = HttpRequest {
+    generators: generators! {
+      "HEADER" => {
+        "X-Order-ID" => Generator::ProviderStateGenerator("id".to_string(), None)
+      }
+    }, .. HttpRequest::default()
+  };
+  let result = generate_request(&request, &GeneratorTestMode::Provider, &hashmap!{}).await;
+  expect!(result.headers).to(be_none());
+}
+
+#[cfg(feature = "datetime")]
+#[tokio::test]
+async fn applies_query_generators_loaded_from_the_pact_json() {
+  let interaction = pact_models::v4::synch_http::SynchronousHttp::from_json(&json!({
+    "type": "Synchronous/HTTP",
+    "description": "a request with generated query parameters",
+    "request": {
+      "method": "GET",
+      "path": "/items",
+      "query": {
+        "page": ["1"],
+        "date": ["2000-01-01"],
+        "id": ["100"]
+      },
+      "generators": {
+        "query": {
+          "page": { "type": "RandomInt", "min": 10, "max": 20 },
+          "date": { "type": "Date", "format": "yyyy-MM-dd" },
+          "id": { "type": "ProviderState", "expression": "${id}" }
+        }
+      }
+    },
+    "response": {}
+  }), 0).unwrap();
+
+  let context = hashmap!{ "id" => json!(1000) };
+  let result = generate_request(&interaction.request, &GeneratorTestMode::Provider, &context).await;
+  let query = result.query.unwrap();
+  let page: u16 = query.get("page").unwrap()[0].as_ref().unwrap().parse().unwrap();
+  expect!(page).to(be_greater_or_equal_to(10));
+  expect!(page).to(be_less_or_equal_to(20));
+  expect!(query.get("date").unwrap()[0].as_ref().unwrap()).to_not(be_equal_to("2000-01-01"));
+  expect!(query.get("id").unwrap()).to(be_equal_to(&vec![Some("1000".to_string())]));
+
+  let result = generate_request(&interaction.request, &GeneratorTestMode::Consumer, &context).await;
+  expect!(result.query.unwrap().get("id").unwrap()).to(be_equal_to(&vec![Some("100".to_string())]));
+}
+
 #[tokio::test]
 async fn applies_body_generator_to_the_copy_of_the_request() {
   let request = HttpRequest { body: OptionalBody::Present("{\"a\": 100, \"b\": \"B\"}".into(), None, None),
@@ -144,6 +225,90 @@ async fn applies_body_generator_to_the_copy_of_the_response() {
   expect!(&body["b"]).to(be_equal_to(&json!("B")));
 }
 
+#[tokio::test]
+async fn generate_request_with_a_random_seed_generates_different_values_for_each_header() {
+  let request = HttpRequest {
+    headers: Some(hashmap!{
+      "A".to_string() => vec!["a".to_string()],
+      "B".to_string() => vec!["b".to_string()]
+    }),
+    generators: generators! {
+      "HEADER" => {
+        "A" => Generator::RandomInt(0, 1000000),
+        "B" => Generator::RandomInt(0, 1000000)
+      }
+    }, .. HttpRequest::default()
+  };
+  let context = hashmap!{ RANDOM_SEED => json!(1234) };
+
+  let generated = generate_request(&request, &GeneratorTestMode::Provider, &context).await;
+  let headers = generated.headers.clone().unwrap();
+  expect!(headers.get("A")).to_not(be_equal_to(headers.get("B")));
+  expect!(generate_request(&request, &GeneratorTestMode::Provider, &context).await.headers)
+    .to(be_equal_to(generated.headers));
+}
+
+#[tokio::test]
+async fn generate_request_with_report_returns_the_generated_values() {
+  let mut request = HttpRequest {
+    path: "/path".to_string(),
+    headers: Some(hashmap!{
+      "A".to_string() => vec!["a".to_string()]
+    }),
+    body: OptionalBody::Present("{\"a\": 100, \"b\": [\"B\"]}".into(), None, None),
+    generators: generators! {
+      "HEADER" => {
+        "A" => Generator::ProviderStateGenerator("id".to_string(), None)
+      },
+      "BODY" => {
+        "$.a" => Generator::ProviderStateGenerator("id".to_string(), None),
+        "$.b[*]" => Generator::ProviderStateGenerator("id".to_string(), None)
+      }
+    }, .. HttpRequest::default()
+  };
+  request.generators.add_generator(&GeneratorCategory::PATH,
+    Generator::ProviderStateGenerator("/path/${id}".to_string(), None));
+  let context = hashmap!{ "id" => json!(1234) };
+  let (_, report) = generate_request_with_report(&request, &GeneratorTestMode::Provider, &context).await;
+
+  let values = report.values.iter()
+    .map(|value| (value.category, value.path.to_string(), value.value.clone()))
+    .sorted_by(|a, b| Ord::cmp(&a.1, &b.1))
+    .collect::<Vec<_>>();
+  expect!(values).to(be_equal_to(vec![
+    (GeneratorCategory::PATH, "".to_string(), Some(json!("/path/1234"))),
+    (GeneratorCategory::BODY, "$.a".to_string(), Some(json!(1234))),
+    (GeneratorCategory::BODY, "$.b[*]".to_string(), None),
+    (GeneratorCategory::HEADER, "A".to_string(), Some(json!(["1234"])))
+  ]));
+}
+
+#[tokio::test]
+async fn generate_response_with_report_returns_the_generated_values() {
+  let response = HttpResponse { status: 200, generators: generators! {
+    "STATUS" => Generator::ProviderStateGenerator("status".to_string(), None)
+  }, .. HttpResponse::default() };
+  let context = hashmap!{ "status" => json!(201) };
+  let (response, report) = generate_response_with_report(&response, &GeneratorTestMode::Provider, &context).await;
+  expect!(response.status).to(be_equal_to(201));
+  expect!(report.to_json()).to(be_equal_to(json!([
+    {
+      "category": "status",
+      "path": "",
+      "generator": { "type": "ProviderState", "expression": "status" },
+      "value": 201
+    }
+  ])));
+  expect!(report.values[0].to_string()).to(be_equal_to("status -> ProviderStateGenerator = 201"));
+}
+
+#[tokio::test]
+async fn generate_response_with_report_returns_an_empty_report_if_there_are_no_generators() {
+  let response = HttpResponse::default();
+  let (_, report) = generate_response_with_report(&response, &GeneratorTestMode::Provider, &hashmap!{}).await;
+  expect!(report.is_empty()).to(be_true());
+}
+
 #[test]
 fn applies_the_generator_to_a_json_map_entry() {
   let map = json!({"a": 100, "b": "B", "c": "C"});
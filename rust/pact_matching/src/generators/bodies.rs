@@ -67,6 +67,30 @@ pub async fn generators_process_body(
         warn!("Generating XML documents requires the xml feature to be enabled");
         Ok(body.clone())
       }
+    } else if content_type.is_cbor() {
+      debug!("apply_body_generators: CBOR content type");
+      #[cfg(feature = "cbor")]
+      {
+        Ok(generate_encoded_body(mode, body, &content_type, context, generators, matcher,
+          crate::cbor::decode_cbor, crate::cbor::encode_cbor))
+      }
+      #[cfg(not(feature = "cbor"))]
+      {
+        warn!("Generating CBOR bodies requires the cbor feature to be enabled");
+        Ok(body.clone())
+      }
+    } else if content_type.is_msgpack() {
+      debug!("apply_body_generators: MessagePack content type");
+      #[cfg(feature = "msgpack")]
+      {
+        Ok(generate_encoded_body(mode, body, &content_type, context, generators, matcher,
+          crate::msgpack::decode_msgpack, crate::msgpack::encode_msgpack))
+      }
+      #[cfg(not(feature = "msgpack"))]
+      {
+        warn!("Generating MessagePack bodies requires the msgpack feature to be enabled");
+        Ok(body.clone())
+      }
     }
     else {
       #[cfg(feature = "plugins")]
@@ -92,6 +116,44 @@ pub async fn generators_process_body(
   }
 }
 
+/// Applies the generators to a binary encoded body (like CBOR or MessagePack) by decoding it into
+/// a JSON tree, and then re-encoding the result
+#[cfg(any(feature = "cbor", feature = "msgpack"))]
+#[allow(clippy::too_many_arguments)]
+fn generate_encoded_body(
+  mode: &GeneratorTestMode,
+  body: &OptionalBody,
+  content_type: &ContentType,
+  context: &HashMap<&str, Value>,
+  generators: &HashMap<DocPath, Generator>,
+  matcher: &(dyn VariantMatcher + Send + Sync),
+  decode: fn(&[u8]) -> anyhow::Result<Value>,
+  encode: fn(&Value) -> anyhow::Result<Vec<u8>>
+) -> OptionalBody {
+  match decode(&body.value().unwrap_or_default()) {
+    Ok(val) => {
+      let mut handler = JsonHandler { value: val };
+      match handler.process_body(generators, mode, context, &matcher.boxed()) {
+        Ok(_) => match encode(&handler.value) {
+          Ok(data) => OptionalBody::Present(data.into(), Some(content_type.clone()), None),
+          Err(err) => {
+            error!("Failed to encode the generated body: {}", err);
+            body.clone()
+          }
+        },
+        Err(err) => {
+          error!("Failed to generate the body: {}", err);
+          body.clone()
+        }
+      }
+    },
+    Err(err) => {
+      error!("Failed to parse the body, so not applying any generators: {}", err);
+      body.clone()
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use expectest::prelude::*;
@@ -125,6 +187,52 @@ mod tests {
       be_equal_to(body));
   }
 
+  #[cfg(feature = "cbor")]
+  #[tokio::test]
+  async fn apply_generator_to_cbor_body_test() {
+    use pact_models::content_types::ContentType;
+    use pact_models::generators::Generator;
+    use pact_models::path_exp::DocPath;
+    use serde_json::json;
+
+    use crate::cbor::{decode_cbor, encode_cbor};
+
+    let content_type = ContentType::parse("application/cbor").unwrap();
+    let body = OptionalBody::Present(encode_cbor(&json!({ "a": 100, "b": "B" })).unwrap().into(), None, None);
+    let generators = hashmap!{ DocPath::new_unwrap("$.a") => Generator::RandomInt(1, 10) };
+    let result = generators_process_body(&GeneratorTestMode::Provider, &body, Some(content_type.clone()),
+      &hashmap!{}, &generators, &DefaultVariantMatcher{}, &vec![], &hashmap!{}).await.unwrap();
+
+    expect!(result.content_type()).to(be_some().value(content_type));
+    let value = decode_cbor(&result.value().unwrap()).unwrap();
+    expect!(value["b"].clone()).to(be_equal_to(json!("B")));
+    expect!(value["a"].as_u64().unwrap()).to(be_less_or_equal_to(10));
+    expect!(value["a"].as_u64().unwrap()).to(be_greater_or_equal_to(1));
+  }
+
+  #[cfg(feature = "msgpack")]
+  #[tokio::test]
+  async fn apply_generator_to_msgpack_body_test() {
+    use pact_models::content_types::ContentType;
+    use pact_models::generators::Generator;
+    use pact_models::path_exp::DocPath;
+    use serde_json::json;
+
+    use crate::msgpack::{decode_msgpack, encode_msgpack};
+
+    let content_type = ContentType::parse("application/msgpack").unwrap();
+    let body = OptionalBody::Present(encode_msgpack(&json!({ "a": 100, "b": "B" })).unwrap().into(), None, None);
+    let generators = hashmap!{ DocPath::new_unwrap("$.a") => Generator::RandomInt(1, 10) };
+    let result = generators_process_body(&GeneratorTestMode::Provider, &body, Some(content_type.clone()),
+      &hashmap!{}, &generators, &DefaultVariantMatcher{}, &vec![], &hashmap!{}).await.unwrap();
+
+    expect!(result.content_type()).to(be_some().value(content_type));
+    let value = decode_msgpack(&result.value().unwrap()).unwrap();
+    expect!(value["b"].clone()).to(be_equal_to(json!("B")));
+    expect!(value["a"].as_u64().unwrap()).to(be_less_or_equal_to(10));
+    expect!(value["a"].as_u64().unwrap()).to(be_greater_or_equal_to(1));
+  }
+
   #[tokio::test]
   async fn apply_generator_to_text_body_test() {
     let body = OptionalBody::Present("some text".into(), None, None);
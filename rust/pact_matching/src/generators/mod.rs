@@ -1,6 +1,7 @@
 //! `generators` module includes all the classes to deal with V3 format generators
 
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 
 use maplit::hashmap;
 use pact_models::bodies::OptionalBody;
@@ -12,7 +13,8 @@ use pact_models::generators::{
   GeneratorCategory,
   GeneratorTestMode,
   NoopVariantMatcher,
-  VariantMatcher
+  VariantMatcher,
+  with_random_seed_path
 };
 use pact_models::http_parts::HttpPart;
 use pact_models::matchingrules::MatchingRuleCategory;
@@ -22,7 +24,7 @@ use pact_models::plugins::PluginData;
 use pact_models::v4::async_message::AsynchronousMessage;
 use pact_models::v4::message_parts::MessageContents;
 use pact_models::v4::sync_message::SynchronousMessage;
-use serde_json::{self, Value};
+use serde_json::{self, json, Value};
 #[cfg(feature = "xml")] use sxd_document::dom::Document;
 use tracing::{debug, error, trace};
 
@@ -134,7 +136,7 @@ pub async fn apply_generators_to_sync_message(
     apply_generators(mode, &generators, &mut |key, generator| {
       if let Some(k) = key.first_field() {
         let value = request.metadata.get(k).cloned().unwrap_or_default();
-        if let Ok(v) = generator.generate_value(&value, context, &vm_boxed) {
+        if let Ok(v) = generator.generate_value(&value, &with_random_seed_path(context, &format!("metadata:{}", k)), &vm_boxed) {
           request.metadata.insert(k.to_string(), v);
         }
       }
@@ -159,7 +161,7 @@ pub async fn apply_generators_to_sync_message(
       apply_generators(mode, &generators, &mut |key, generator| {
         if let Some(k) = key.first_field() {
           let value = response.metadata.get(k).cloned().unwrap_or_default();
-          if let Ok(v) = generator.generate_value(&value, context, &vm_boxed) {
+          if let Ok(v) = generator.generate_value(&value, &with_random_seed_path(context, &format!("metadata:{}", k)), &vm_boxed) {
             response.metadata.insert(k.to_string(), v);
           }
         }
@@ -198,7 +200,7 @@ pub async fn apply_generators_to_async_message(
     apply_generators(mode, &generators, &mut |key, generator| {
       if let Some(k) = key.first_field() {
         let value = message.contents.metadata.get(k).cloned().unwrap_or_default();
-        if let Ok(v) = generator.generate_value(&value, context, &vm_boxed) {
+        if let Ok(v) = generator.generate_value(&value, &with_random_seed_path(context, &format!("metadata:{}", k)), &vm_boxed) {
           copy.metadata.insert(k.to_string(), v);
         }
       }
@@ -234,12 +236,13 @@ pub async fn generate_message(
     debug!("Applying metadata generators...");
     apply_generators(mode, &generators, &mut |key, generator| {
       if let Some(header) = key.first_field() {
+        let context = with_random_seed_path(context, &format!("metadata:{}", header));
         if message.metadata.contains_key(header) {
-          if let Ok(v) = generator.generate_value(&message.metadata.get(header).unwrap().clone(), context, &DefaultVariantMatcher.boxed()) {
+          if let Ok(v) = generator.generate_value(&message.metadata.get(header).unwrap().clone(), &context, &DefaultVariantMatcher.boxed()) {
             message.metadata.insert(header.to_string(), v);
           }
         } else {
-          if let Ok(v) = generator.generate_value(&Value::Null, context, &DefaultVariantMatcher.boxed()) {
+          if let Ok(v) = generator.generate_value(&Value::Null, &context, &DefaultVariantMatcher.boxed()) {
             message.metadata.insert(header.to_string(), v);
           }
         }
@@ -260,6 +263,105 @@ pub async fn generate_message(
   message
 }
 
+/// Value produced by a generator when generating a request or response
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedValue {
+  /// Category the generator was applied to
+  pub category: GeneratorCategory,
+  /// Path within the category that the generator was applied to
+  pub path: DocPath,
+  /// Generator that was applied
+  pub generator: Generator,
+  /// The generated value. This will be None if the value could not be determined (i.e. body
+  /// generators with wildcard paths or non-JSON bodies).
+  pub value: Option<Value>
+}
+
+impl GeneratedValue {
+  /// Converts this generated value to a JSON structure
+  pub fn to_json(&self) -> Value {
+    let category: String = self.category.into();
+    json!({
+      "category": category,
+      "path": self.path.to_string(),
+      "generator": self.generator.to_json().unwrap_or_else(|| json!({ "type": self.generator.name() })),
+      "value": self.value.clone().unwrap_or_default()
+    })
+  }
+}
+
+impl Display for GeneratedValue {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let category: &str = self.category.into();
+    if self.path.tokens().is_empty() {
+      write!(f, "{} -> {}", category, self.generator.name())?;
+    } else {
+      write!(f, "{} {} -> {}", category, self.path, self.generator.name())?;
+    }
+    if let Some(value) = &self.value {
+      write!(f, " = {}", value)?;
+    }
+    Ok(())
+  }
+}
+
+/// Report of the generators that were applied when generating a request or response, and the
+/// values they produced
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GeneratorReport {
+  /// Generated values, in the order the generators were applied
+  pub values: Vec<GeneratedValue>
+}
+
+impl GeneratorReport {
+  /// If no generators were applied
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+  }
+
+  /// Adds a generated value to the report
+  pub fn add(
+    &mut self,
+    category: GeneratorCategory,
+    path: &DocPath,
+    generator: &Generator,
+    value: Option<Value>
+  ) {
+    self.values.push(GeneratedValue {
+      category,
+      path: path.clone(),
+      generator: generator.clone(),
+      value
+    });
+  }
+
+  /// Adds the values from the generated body for any body generators that apply for the mode
+  pub fn add_body_values(
+    &mut self,
+    mode: &GeneratorTestMode,
+    generators: &HashMap<DocPath, Generator>,
+    body: &OptionalBody,
+    content_type: Option<ContentType>
+  ) {
+    let json = if content_type.unwrap_or_default().is_json() {
+      serde_json::from_slice::<Value>(&body.value().unwrap_or_default()).ok()
+    } else {
+      None
+    };
+    apply_generators(mode, generators, &mut |key, generator| {
+      let value = json.as_ref().and_then(|json| {
+        key.as_json_pointer().ok().and_then(|pointer| json.pointer(&pointer).cloned())
+      });
+      self.add(GeneratorCategory::BODY, key, generator, value);
+    });
+  }
+
+  /// Converts this report to a JSON array
+  pub fn to_json(&self) -> Value {
+    Value::Array(self.values.iter().map(|value| value.to_json()).collect())
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use expectest::prelude::*;
@@ -5,12 +5,13 @@ use std::iter::FromIterator;
 
 use itertools::Itertools;
 use maplit::hashmap;
+use pact_models::content_types::ContentType;
 use pact_models::headers::PARAMETERISED_HEADERS;
 use pact_models::matchingrules::MatchingRule;
-use pact_models::path_exp::DocPath;
+use pact_models::path_exp::{DocPath, PathToken};
 use tracing::{instrument, debug};
 
-use crate::{matchers, MatchingContext, Mismatch, CommonMismatch};
+use crate::{matchers, MatchingContext, Mismatch, CommonMismatch, RuleTrace};
 use crate::matchers::Matches;
 use crate::matchingrules::compare_lists_with_matchingrules;
 
@@ -72,6 +73,31 @@ pub(crate) fn match_parameter_header(
   }
 }
 
+/// Matches content type values using the parsed content types, so the type and parameter names
+/// are compared ignoring case and the order of the parameters does not matter. The boundary
+/// parameter of multipart content types is ignored, as the parts of the body are compared with
+/// the boundaries from each side. Falls back to `match_parameter_header` if either value is not a
+/// valid content type.
+pub(crate) fn match_content_type_header(
+  expected: &str,
+  actual: &str,
+  header: &str,
+  value_type: &str,
+  index: usize,
+  single_value: bool
+) -> Result<(), Vec<String>> {
+  match (ContentType::parse(expected).map(|ct| ct.without_boundary()), ContentType::parse(actual).map(|ct| ct.without_boundary())) {
+    (Ok(expected_content_type), Ok(actual_content_type)) => if expected_content_type.is_compatible_with(&actual_content_type) {
+      Ok(())
+    } else if single_value {
+      Err(vec![format!("Expected {} '{}' to have value '{}' but was '{}'", value_type, header, expected, actual)])
+    } else {
+      Err(vec![format!("Expected {} '{}' at index {} to have value '{}' but was '{}'", value_type, header, index, expected, actual)])
+    },
+    _ => match_parameter_header(expected, actual, header, value_type, index, single_value)
+  }
+}
+
 #[instrument(level = "trace")]
 pub(crate) fn match_header_value(
   key: &str,
@@ -81,36 +107,61 @@ pub(crate) fn match_header_value(
   context: &dyn MatchingContext,
   single_value: bool
 ) -> Result<(), Vec<CommonMismatch>> {
-  let path = DocPath::root().join(key.to_lowercase());
-  let indexed_path = path.join(index.to_string());
+  match_value(key, index, expected, actual, context, single_value, "header")
+}
+
+fn match_value(
+  key: &str,
+  index: usize,
+  expected: &str,
+  actual: &str,
+  context: &dyn MatchingContext,
+  single_value: bool,
+  value_type: &str
+) -> Result<(), Vec<CommonMismatch>> {
+  let indexed_path = DocPath::root().join(key.to_lowercase()).join(index.to_string());
+  match_value_at_path(key, index, &indexed_path, expected, actual, context, single_value, value_type)
+}
+
+/// Matches a single value, using the best matching rules for the path to the value (i.e.
+/// `$.x-id[1]`). Rules defined for the value index or with an index wildcard (`$.x-id[*]`) take
+/// precedence over the rules defined for the header.
+#[allow(clippy::too_many_arguments)]
+fn match_value_at_path(
+  key: &str,
+  index: usize,
+  indexed_path: &DocPath,
+  expected: &str,
+  actual: &str,
+  context: &dyn MatchingContext,
+  single_value: bool,
+  value_type: &str
+) -> Result<(), Vec<CommonMismatch>> {
   let expected = expected.trim();
   let actual = actual.trim();
 
-  let matcher_result = if context.matcher_is_defined(&path) {
-    let result = matchers::match_values(&path, &context.select_best_matcher(&path), expected, actual);
-    if single_value {
+  let (matcher_result, rule) = if context.matcher_is_defined(indexed_path) {
+    let result = matchers::match_values(indexed_path, &context.select_best_matcher(indexed_path), expected, actual);
+    let result = if single_value {
       result
     } else {
       result.map_err(|err| err.iter().map(|e| format!("{} for value at index {}", e, index)).collect())
-    }
-  } else if context.matcher_is_defined(&indexed_path) {
-    let result = matchers::match_values(&indexed_path, &context.select_best_matcher(&indexed_path), expected, actual);
-    if single_value {
-      result
-    } else {
-      result.map_err(|err| err.iter().map(|e| format!("{} for value at index {}", e, index)).collect())
-    }
+    };
+    (result, header_rule_trace(indexed_path, context))
+  } else if key.eq_ignore_ascii_case("content-type") {
+    (match_content_type_header(expected, actual, key, value_type, index, single_value), None)
   } else if PARAMETERISED_HEADERS.contains(&key.to_lowercase().as_str()) {
-    match_parameter_header(expected, actual, key, "header", index, single_value)
+    (match_parameter_header(expected, actual, key, value_type, index, single_value), None)
   } else {
-    Matches::matches_with(&expected.to_string(), &actual.to_string(), &MatchingRule::Equality, false)
+    let result = Matches::matches_with(&expected.to_string(), &actual.to_string(), &MatchingRule::Equality, false)
       .map_err(|err| {
         if single_value {
           vec![format!("{}", err)]
         } else {
           vec![format!("{} for value at index {}", err, index)]
         }
-      })
+      });
+    (result, None)
   };
 
   matcher_result.map_err(|messages| {
@@ -119,12 +170,19 @@ pub(crate) fn match_header_value(
         path: key.to_string(),
         expected: expected.to_string(),
         actual: actual.to_string(),
-        description: format!("Mismatch with header '{}': {}", key, message)
+        description: format!("Mismatch with {} '{}': {}", value_type, key, message),
+        rule: rule.clone()
       }
     }).collect()
   })
 }
 
+/// Returns the matching rule that will be applied to the header value. Rules defined for the
+/// header apply to each of its values, so are not treated as having cascaded.
+fn header_rule_trace(indexed_path: &DocPath, context: &dyn MatchingContext) -> Option<RuleTrace> {
+  context.rule_trace(indexed_path).map(|trace| RuleTrace { cascaded: false, ..trace })
+}
+
 fn find_entry<T>(map: &HashMap<String, T>, key: &str) -> Option<(String, T)> where T: Clone {
   match map.keys().find(|k| k.to_lowercase() == key.to_lowercase() ) {
     Some(k) => map.get(k).map(|v| (key.to_string(), v.clone()) ),
@@ -135,23 +193,27 @@ fn find_entry<T>(map: &HashMap<String, T>, key: &str) -> Option<(String, T)> whe
 fn match_header_maps(
   expected: HashMap<String, Vec<String>>,
   actual: HashMap<String, Vec<String>>,
-  context: &dyn MatchingContext
+  context: &dyn MatchingContext,
+  value_type: &str
 ) -> HashMap<String, Vec<Mismatch>> {
   let mut result = hashmap!{};
   for (key, value) in &expected {
     match find_entry(&actual, key) {
       Some((_, actual_values)) => if value.is_empty() && !actual_values.is_empty() {
-        result.insert(key.clone(), vec![Mismatch::HeaderMismatch { key: key.clone(),
+        result.insert(key.clone(), vec![to_mismatch(&CommonMismatch {
+          path: key.clone(),
           expected: "".to_string(),
-          actual: format!("{}", actual_values.join(", ")),
-          mismatch: format!("Expected an empty header '{}' but actual value was '{}'", key, actual_values.join(", ")) }]);
+          actual: actual_values.join(", "),
+          description: format!("Expected an empty {} '{}' but actual value was '{}'", value_type, key, actual_values.join(", ")),
+          rule: None
+        }, value_type)]);
       } else {
         let mut mismatches = vec![];
 
         // Special case when the headers only have 1 value to improve messaging
         if value.len() == 1 && actual_values.len() == 1 {
-          let comparison_result = match_header_value(key, 0, value.first().unwrap(),
-            actual_values.first().unwrap(), context, true)
+          let comparison_result = match_value(key, 0, value.first().unwrap(),
+            actual_values.first().unwrap(), context, true, value_type)
             .err()
             .unwrap_or_default();
           mismatches.extend(comparison_result.iter().cloned());
@@ -159,8 +221,12 @@ fn match_header_maps(
           let path = DocPath::root().join(key.to_lowercase());
           if context.matcher_is_defined(&path) {
             debug!("match_header_maps: Matcher is defined for path {}", path);
-            let values_result = compare_lists_with_matchingrules(&path, &context.select_best_matcher(&path), value, &actual_values, context.clone_with(context.matchers()).as_ref(), &mut |_, expected, actual, context| {
-              match_header_value(key, 0, expected, actual, context, false)
+            let values_result = compare_lists_with_matchingrules(&path, &context.select_best_matcher(&path), value, &actual_values, context.clone_with(context.matchers()).as_ref(), &mut |p, expected, actual, context| {
+              if let Some(PathToken::Index(_)) = p.tokens().last() {
+                match_value_at_path(key, 0, p, expected, actual, context, false, value_type)
+              } else {
+                match_value(key, 0, expected, actual, context, false, value_type)
+              }
             });
             mismatches.extend(values_result.err().unwrap_or_default());
           } else {
@@ -169,8 +235,8 @@ fn match_header_maps(
               .pad_using(actual_values.len(), |_| &empty)
               .enumerate() {
               if let Some(actual_value) = actual_values.get(index) {
-                let comparison_result = match_header_value(key, index, val,
-                  actual_value, context, false)
+                let comparison_result = match_value(key, index, val,
+                  actual_value, context, false, value_type)
                   .err()
                   .unwrap_or_default();
                 mismatches.extend(comparison_result.iter().cloned());
@@ -179,41 +245,73 @@ fn match_header_maps(
                   path: key.clone(),
                   expected: val.clone(),
                   actual: "".to_string(),
-                  description: format!("Mismatch with header '{}': Expected value '{}' at index {} but was missing (actual has {} value(s))",
-                                    key, val, index, actual_values.len())
+                  description: format!("Mismatch with {} '{}': Expected value '{}' at index {} but was missing (actual has {} value(s))",
+                                    value_type, key, val, index, actual_values.len()),
+                  rule: None
                 });
               }
             }
           }
         }
 
-        result.insert(key.clone(), mismatches.iter().map(|mismatch| mismatch.to_header_mismatch()).collect());
+        result.insert(key.clone(), mismatches.iter().map(|mismatch| to_mismatch(mismatch, value_type)).collect());
       },
       None => {
-        result.insert(key.clone(), vec![Mismatch::HeaderMismatch { key: key.clone(),
-          expected: format!("{:?}", value.join(", ")),
-          actual: "".to_string(),
-          mismatch: format!("Expected a header '{}' but was missing", key) }]);
+        result.insert(key.clone(), vec![missing_value_mismatch(key, value, value_type)]);
       }
     }
   }
   result
 }
 
+fn to_mismatch(mismatch: &CommonMismatch, value_type: &str) -> Mismatch {
+  if value_type == "trailer" {
+    mismatch.to_trailer_mismatch()
+  } else {
+    mismatch.to_header_mismatch()
+  }
+}
+
+fn missing_value_mismatch(key: &str, value: &[String], value_type: &str) -> Mismatch {
+  to_mismatch(&CommonMismatch {
+    path: key.to_string(),
+    expected: format!("{:?}", value.join(", ")),
+    actual: "".to_string(),
+    description: format!("Expected a {} '{}' but was missing", value_type, key),
+    rule: None
+  }, value_type)
+}
+
 /// Matches the actual headers to the expected ones.
 pub fn match_headers(
   expected: Option<HashMap<String, Vec<String>>>,
   actual: Option<HashMap<String, Vec<String>>>,
   context: &(dyn MatchingContext + Send + Sync)
+) -> HashMap<String, Vec<Mismatch>> {
+  match_header_or_trailer_maps(expected, actual, context, "header")
+}
+
+/// Matches the actual response trailers to the expected ones. Trailers are matched in the same
+/// way as headers, using the matching rules from the trailer category.
+pub fn match_trailers(
+  expected: Option<HashMap<String, Vec<String>>>,
+  actual: Option<HashMap<String, Vec<String>>>,
+  context: &(dyn MatchingContext + Send + Sync)
+) -> HashMap<String, Vec<Mismatch>> {
+  match_header_or_trailer_maps(expected, actual, context, "trailer")
+}
+
+fn match_header_or_trailer_maps(
+  expected: Option<HashMap<String, Vec<String>>>,
+  actual: Option<HashMap<String, Vec<String>>>,
+  context: &(dyn MatchingContext + Send + Sync),
+  value_type: &str
 ) -> HashMap<String, Vec<Mismatch>> {
   match (actual, expected) {
-    (Some(aqm), Some(eqm)) => match_header_maps(eqm, aqm, context),
+    (Some(aqm), Some(eqm)) => match_header_maps(eqm, aqm, context, value_type),
     (Some(_), None) => hashmap!{},
     (None, Some(eqm)) => eqm.iter().map(|(key, value)| {
-      (key.clone(), vec![Mismatch::HeaderMismatch { key: key.clone(),
-        expected: format!("{:?}", value.join(", ")),
-        actual: "".to_string(),
-        mismatch: format!("Expected a header '{}' but was missing", key) }])
+      (key.clone(), vec![missing_value_mismatch(key, value, value_type)])
     }).collect(),
     (None, None) => hashmap!{}
   }
@@ -222,14 +320,15 @@ pub fn match_headers(
 #[cfg(test)]
 mod tests {
   use expectest::prelude::*;
+  use itertools::Itertools;
   use maplit::*;
   use pact_models::matchingrules;
   use pact_models::matchingrules::MatchingRule;
   use pact_models::matchingrules::expressions::{MatchingRuleDefinition, ValueType};
   use pretty_assertions::assert_eq;
 
-  use crate::{CoreMatchingContext, DiffConfig, HeaderMatchingContext, Mismatch, CommonMismatch};
-  use crate::headers::{match_header_value, match_headers, parse_charset_parameters};
+  use crate::{CoreMatchingContext, DiffConfig, HeaderMatchingContext, Mismatch, CommonMismatch, RuleOrigin, RuleTrace};
+  use crate::headers::{match_header_value, match_headers, match_trailers, parse_charset_parameters};
 
   #[test]
   fn matching_headers_be_true_when_headers_are_equal() {
@@ -249,7 +348,8 @@ mod tests {
       path: "HEADER".to_string(),
       expected: "HEADER".to_string(),
       actual: "HEADER2".to_string(),
-      description: "".to_string()
+      description: "".to_string(),
+      rule: None
     });
   }
 
@@ -327,6 +427,40 @@ mod tests {
     expect!(mismatches).to(be_ok());
   }
 
+  #[test]
+  fn content_type_header_matches_ignoring_the_case_of_the_type_and_order_of_the_parameters() {
+    let mismatches = match_header_value("Content-Type", 0, "multipart/form-data; boundary=ABC; charset=UTF-8",
+      "Multipart/Form-Data; Charset=utf-8; boundary=ABC", &CoreMatchingContext::default(), true
+    );
+    expect!(mismatches).to(be_ok());
+  }
+
+  #[test]
+  fn content_type_header_does_not_match_when_non_charset_parameters_are_different_case() {
+    let mismatches = match_header_value("Content-Type", 0, "application/json; version=ABC",
+      "application/json; version=abc", &CoreMatchingContext::default(), true
+    );
+    expect!(mismatches).to(be_err());
+  }
+
+  #[test]
+  fn content_type_header_ignores_the_multipart_boundary() {
+    let mismatches = match_header_value("Content-Type", 0, "multipart/form-data; boundary=ABC",
+      "multipart/form-data; boundary=1234", &CoreMatchingContext::default(), true
+    );
+    expect!(mismatches).to(be_ok());
+
+    let mismatches = match_header_value("Content-Type", 0, "multipart/form-data; boundary=ABC; charset=UTF-8",
+      "multipart/form-data; boundary=1234; charset=UTF-16", &CoreMatchingContext::default(), true
+    );
+    expect!(mismatches).to(be_err());
+
+    let mismatches = match_header_value("Content-Type", 0, "multipart/form-data; boundary=ABC",
+      "multipart/mixed; boundary=ABC", &CoreMatchingContext::default(), true
+    );
+    expect!(mismatches).to(be_err());
+  }
+
   #[test]
   fn mismatched_header_description_reports_content_type_mismatches_correctly() {
     let mismatches = match_header_value("CONTENT-TYPE", 0, "CONTENT-TYPE-VALUE", "HEADER2",
@@ -425,6 +559,7 @@ mod tests {
       expected: "HEADER".to_string(),
       actual: "HEADER".to_string(),
       description: String::default(),
+      rule: None
     } ]));
   }
 
@@ -464,6 +599,35 @@ mod tests {
     expect!(mismatches).to(be_ok());
   }
 
+  #[test_log::test]
+  fn match_headers_with_min_max_type_matcher_and_index_wildcard() {
+    let context = HeaderMatchingContext::new(&CoreMatchingContext::new(
+      DiffConfig::AllowUnexpectedKeys,
+      &matchingrules! {
+        "header" => {
+          "$['X-Id']" => [ MatchingRule::MinMaxType(1, 3) ],
+          "$['X-Id'][*]" => [ MatchingRule::Regex("^\\d+$".to_string()) ]
+        }
+      }.rules_for_category("header").unwrap_or_default(), &hashmap!{}
+    ));
+    let expected = hashmap! { "X-Id".to_string() => vec!["1".to_string(), "2".to_string()] };
+
+    let actual = hashmap! { "X-Id".to_string() => vec!["100".to_string(), "200".to_string(), "300".to_string()] };
+    let result = match_headers(Some(expected.clone()), Some(actual), &context);
+    expect!(result.values().flatten()).to(be_empty());
+
+    let actual = hashmap! { "X-Id".to_string() => vec!["100".to_string(), "abc".to_string()] };
+    let result = match_headers(Some(expected.clone()), Some(actual), &context);
+    let mismatches: Vec<String> = result.values().flatten().map(|m| m.description()).collect();
+    expect!(mismatches).to(be_equal_to(vec![
+      "Mismatch with header 'X-Id': Expected 'abc' to match '^\\d+$' for value at index 0".to_string()
+    ]));
+
+    let actual = hashmap! { "X-Id".to_string() => vec!["1".to_string()] };
+    let result = match_headers(Some(expected), Some(actual), &context);
+    expect!(result.values().flatten()).to(be_empty());
+  }
+
   #[test_log::test]
   fn match_headers_returns_nothing_if_there_are_no_headers() {
     let expected = None;
@@ -512,7 +676,8 @@ mod tests {
       key: "a".to_string(),
       expected: "\"b\"".to_string(),
       actual: "".to_string(),
-      mismatch: "Expected a header 'a' but was missing".to_string()
+      mismatch: "Expected a header 'a' but was missing".to_string(),
+      rule: None
     });
   }
 
@@ -533,6 +698,7 @@ mod tests {
       expected: "\"b\"".to_string(),
       actual: "".to_string(),
       mismatch: "Expected a header 'a' but was missing".to_string(),
+      rule: None
     });
   }
 
@@ -554,6 +720,7 @@ mod tests {
       expected: "".to_string(),
       actual: "d".to_string(),
       mismatch: "Expected an empty header 'c' but actual value was 'd'".to_string(),
+      rule: None
     });
   }
 
@@ -575,6 +742,7 @@ mod tests {
       expected: "e".to_string(),
       actual: "".to_string(),
       mismatch: "Mismatch with header 'c': Expected value 'e' at index 1".to_string(),
+      rule: None
     }));
 
     let expected = Some(hashmap!{
@@ -591,12 +759,14 @@ mod tests {
       expected: "d".to_string(),
       actual: "e".to_string(),
       mismatch: "Mismatch with header 'c': Expected 'd' to be equal to 'e' for value at index 0".to_string(),
+      rule: None
     }));
     expect!(mismatches[1].clone()).to(be_equal_to(Mismatch::HeaderMismatch {
       key: "c".to_string(),
       expected: "e".to_string(),
       actual: "".to_string(),
       mismatch: "Mismatch with header 'c': Expected value 'e' at index 1 but was missing (actual has 1 value(s))".to_string(),
+      rule: None
     }));
   }
 
@@ -645,6 +815,7 @@ mod tests {
         expected: "[\"1\",\"2\",\"3\",\"4\"]".to_string(),
         actual: "[\"1\"]".to_string(),
         mismatch: "Expected [1] (size 1) to have minimum size of 2".to_string(),
+        rule: None
       }
     ]));
   }
@@ -730,12 +901,14 @@ mod tests {
         expected: "like".to_string(),
         actual: "regex".to_string(),
         mismatch: "Mismatch with header 'X-IMPROVED': Expected 'regex' to be equal to 'like' for value at index 0".to_string(),
+        rule: None
       },
       Mismatch::HeaderMismatch {
         key: "X-IMPROVED".to_string(),
         expected: "regex".to_string(),
         actual: "like".to_string(),
         mismatch: "Mismatch with header 'X-IMPROVED': Expected 'like' to be equal to 'regex' for value at index 1".to_string(),
+        rule: None
       }
     ]));
 
@@ -791,6 +964,7 @@ mod tests {
         expected: "1".to_string(),
         actual: "[\"2\",\"3\",\"4\"]".to_string(),
         mismatch: "Variant at index 0 (1) was not found in the actual list".to_string(),
+        rule: None
       }
     ]));
   }
@@ -814,6 +988,40 @@ mod tests {
     expect!(result.values().flatten()).to(be_empty());
   }
 
+  #[test]
+  fn match_headers_records_the_matching_rule_applied_to_the_mismatch() {
+    let context = HeaderMatchingContext::new(&CoreMatchingContext::new(
+      DiffConfig::AllowUnexpectedKeys,
+      &matchingrules! {
+        "header" => {
+          "X-Id" => [ MatchingRule::Regex("\\d+".to_string()) ]
+        }
+      }.rules_for_category("header").unwrap_or_default(), &hashmap!{}
+    ));
+    let expected = hashmap! {
+      "X-Id".to_string() => vec!["1".to_string()],
+      "X-Other".to_string() => vec!["a".to_string()]
+    };
+    let actual = hashmap! {
+      "X-Id".to_string() => vec!["abc".to_string()],
+      "X-Other".to_string() => vec!["b".to_string()]
+    };
+    let result = match_headers(Some(expected), Some(actual), &context);
+
+    let mismatch = result.get("X-Id").unwrap().first().unwrap();
+    expect!(mismatch.rule().cloned()).to(be_some().value(RuleTrace {
+      rule_path: "x-id".to_string(),
+      cascaded: false,
+      rules: vec![ MatchingRule::Regex("\\d+".to_string()) ],
+      origin: RuleOrigin::Core
+    }));
+    expect!(mismatch.to_json()["rule"]["origin"].as_str()).to(be_some().value("core"));
+
+    let mismatch = result.get("X-Other").unwrap().first().unwrap();
+    expect!(mismatch.rule()).to(be_none());
+    expect!(mismatch.to_json().get("rule")).to(be_none());
+  }
+
   #[test]
   fn match_headers_with_each_value_matcher_fails() {
     let context = HeaderMatchingContext::new(&CoreMatchingContext::new(
@@ -837,12 +1045,57 @@ mod tests {
         expected: "2".to_string(),
         actual: "abc123".to_string(),
         mismatch: "Mismatch with header 'X-Id': Expected 'abc123' to match '\\d+' for value at index 0".to_string(),
+        rule: None
       },
       Mismatch::HeaderMismatch {
         key: "X-Id".to_string(),
         expected: "1".to_string(),
         actual: "test".to_string(),
         mismatch: "Mismatch with header 'X-Id': Expected 'test' to match '\\d+' for value at index 0".to_string(),
+        rule: None
+      }
+    ]));
+  }
+
+  #[test]
+  fn match_trailers_uses_the_trailer_matching_rules() {
+    let context = HeaderMatchingContext::new(&CoreMatchingContext::new(
+      DiffConfig::AllowUnexpectedKeys,
+      &matchingrules! {
+        "trailer" => {
+          "grpc-status" => [ MatchingRule::Regex("\\d+".to_string()) ]
+        }
+      }.rules_for_category("trailer").unwrap_or_default(), &hashmap!{}
+    ));
+    let expected = hashmap! {
+      "grpc-status".to_string() => vec!["0".to_string()],
+      "grpc-message".to_string() => vec!["OK".to_string()]
+    };
+
+    let actual = hashmap! {
+      "Grpc-Status".to_string() => vec!["14".to_string()],
+      "Grpc-Message".to_string() => vec!["OK".to_string()]
+    };
+    let result = match_trailers(Some(expected.clone()), Some(actual), &context);
+    expect!(result.values().flatten()).to(be_empty());
+
+    let actual = hashmap! {
+      "grpc-status".to_string() => vec!["UNAVAILABLE".to_string()]
+    };
+    let result = match_trailers(Some(expected), Some(actual), &context);
+    let mismatches: Vec<Mismatch> = result.values().flatten().cloned().sorted_by_key(|m| m.description()).collect();
+    expect!(mismatches).to(be_equal_to(vec![
+      Mismatch::TrailerMismatch {
+        key: "grpc-message".to_string(),
+        expected: "\"OK\"".to_string(),
+        actual: "".to_string(),
+        mismatch: "Expected a trailer 'grpc-message' but was missing".to_string()
+      },
+      Mismatch::TrailerMismatch {
+        key: "grpc-status".to_string(),
+        expected: "0".to_string(),
+        actual: "UNAVAILABLE".to_string(),
+        mismatch: "Mismatch with trailer 'grpc-status': Expected 'UNAVAILABLE' to match '\\d+'".to_string()
       }
     ]));
   }
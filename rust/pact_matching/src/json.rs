@@ -13,11 +13,11 @@ use serde_json::{json, Value};
 use pact_models::http_parts::HttpPart;
 use pact_models::json_utils::json_to_string;
 use pact_models::matchingrules::MatchingRule;
-use pact_models::path_exp::DocPath;
+use pact_models::path_exp::{DocPath, PathToken};
 #[cfg(feature = "datetime")] use pact_models::time_utils::validate_datetime;
-use tracing::debug;
+use tracing::{debug, instrument};
 
-use crate::{DiffConfig, MatchingContext, Mismatch, CommonMismatch, merge_result};
+use crate::{DiffConfig, MatchingContext, Mismatch, CommonMismatch, RuleOrigin, RuleTrace, merge_result};
 use crate::binary_utils::{convert_data, match_content_type};
 use crate::matchers::*;
 use crate::matchingrules::{compare_lists_with_matchingrules, compare_maps_with_matchingrule};
@@ -292,6 +292,30 @@ impl Matches<&Value> for Value {
         }
         _ => Err(anyhow!("Expected something that matches a semantic version, but got '{}'", actual))
       }
+      MatchingRule::Duration => match actual {
+        Value::String(s) => match_duration(s),
+        _ => Err(anyhow!("Expected something that matches an ISO-8601 duration, but got '{}'", actual))
+      }
+      MatchingRule::EpochTimestamp(unit, tolerance) => match actual {
+        Value::Number(n) if n.is_u64() => match_epoch_timestamp(n.as_u64().unwrap_or_default(), unit, tolerance),
+        Value::String(s) => match s.parse::<u64>() {
+          Ok(timestamp) => match_epoch_timestamp(timestamp, unit, tolerance),
+          Err(_) => Err(anyhow!("Expected '{}' (String) to be an epoch timestamp in {}", s, unit))
+        }
+        _ => Err(anyhow!("Expected {} ({}) to be an epoch timestamp in {}", value_of(actual), type_of(actual), unit))
+      }
+      MatchingRule::Url(rules) => match actual {
+        Value::String(s) => match_url(json_to_string(self).as_str(), s, rules),
+        _ => Err(anyhow!("Expected {} ({}) to be a URL", value_of(actual), type_of(actual)))
+      }
+      MatchingRule::NumericRange(range) => match actual {
+        Value::Number(n) => match_numeric_range(n.as_f64().unwrap_or_default(), range),
+        Value::String(s) => match s.parse::<f64>() {
+          Ok(value) => match_numeric_range(value, range),
+          Err(_) => Err(anyhow!("Expected '{}' (String) to be a number {}", s, range))
+        }
+        _ => Err(anyhow!("Expected {} ({}) to be a number {}", value_of(actual), type_of(actual), range))
+      }
       _ => Ok(())
     };
     debug!("JSON -> JSON: Comparing '{}' to '{}' using {:?} -> {:?}", self, actual, matcher, result);
@@ -300,6 +324,7 @@ impl Matches<&Value> for Value {
 }
 
 /// Matches the expected JSON to the actual, and populates the mismatches vector with any differences
+#[instrument(level = "trace", skip_all)]
 pub fn match_json(
   expected: &(dyn HttpPart + Send + Sync),
   actual: &(dyn HttpPart + Send + Sync),
@@ -316,6 +341,7 @@ pub fn match_json(
         expected: expected.body().value(),
         actual: actual.body().value(),
         mismatch: format!("Failed to parse the expected body: '{}'", e),
+        rule: None
       });
     }
     if let Err(e) = actual_json {
@@ -324,6 +350,7 @@ pub fn match_json(
         expected: expected.body().value(),
         actual: actual.body().value(),
         mismatch: format!("Failed to parse the actual body: '{}'", e),
+        rule: None
       });
     }
     Err(mismatches.clone())
@@ -333,6 +360,119 @@ pub fn match_json(
   }
 }
 
+/// Matches the actual JSON body against a set of expectations, which are the body matching rules
+/// of an interaction that has no example body (i.e. `$.data.id exists`). Each path must resolve
+/// to at least one value in the actual body, and the rules for the path are applied to those
+/// values. Paths with an `Absent` rule must not resolve to any value.
+#[instrument(level = "trace", skip_all)]
+pub fn match_json_expectations(
+  actual: &(dyn HttpPart + Send + Sync),
+  context: &(dyn MatchingContext + Send + Sync)
+) -> Result<(), Vec<super::Mismatch>> {
+  let body = actual.body().value().unwrap_or_default();
+  let actual_json = if body.is_empty() {
+    None
+  } else {
+    match serde_json::from_slice::<Value>(&body) {
+      Ok(json) => Some(json),
+      Err(err) => return Err(vec![Mismatch::BodyMismatch {
+        path: "$".to_string(),
+        expected: None,
+        actual: actual.body().value(),
+        mismatch: format!("Failed to parse the actual body: '{}'", err),
+        rule: None
+      }])
+    }
+  };
+
+  let mut expectations = context.matchers().rules.iter().collect::<Vec<_>>();
+  expectations.sort_by_key(|(path, _)| path.to_string());
+  let mut mismatches = vec![];
+  for (path, rules) in expectations {
+    let values = actual_json.as_ref()
+      .map(|json| resolve_json_path(json, path))
+      .unwrap_or_default();
+    let rule = Some(RuleTrace {
+      rule_path: path.to_string(),
+      cascaded: false,
+      rules: rules.rules.clone(),
+      origin: RuleOrigin::Core
+    });
+    if rules.rules.contains(&MatchingRule::Absent) {
+      for (value_path, value) in values {
+        mismatches.push(Mismatch::BodyMismatch {
+          path: value_path.to_string(),
+          expected: None,
+          actual: Some(json_to_string(value).into()),
+          mismatch: format!("Expected no value at '{}', but got {} ({})", path, value_of(value), type_of(value)),
+          rule: rule.clone()
+        });
+      }
+    } else if values.is_empty() {
+      mismatches.push(Mismatch::BodyMismatch {
+        path: path.to_string(),
+        expected: None,
+        actual: None,
+        mismatch: format!("Expected a value at '{}', but it was missing", path),
+        rule: rule.clone()
+      });
+    } else {
+      for (value_path, value) in values {
+        if let Err(errors) = match_values(&value_path, rules, value, value) {
+          for error in errors {
+            mismatches.push(Mismatch::BodyMismatch {
+              path: value_path.to_string(),
+              expected: None,
+              actual: Some(json_to_string(value).into()),
+              mismatch: error,
+              rule: rule.clone()
+            });
+          }
+        }
+      }
+    }
+  }
+
+  if mismatches.is_empty() {
+    Ok(())
+  } else {
+    Err(mismatches)
+  }
+}
+
+/// Resolves the values in the JSON that the path refers to, along with the concrete path to each
+/// value. Wildcards resolve to all the fields of an object or all the items of an array.
+fn resolve_json_path<'a>(json: &'a Value, path: &DocPath) -> Vec<(DocPath, &'a Value)> {
+  let mut values = vec![ (DocPath::root(), json) ];
+  for token in path.tokens() {
+    values = values.into_iter().flat_map(|(value_path, value)| {
+      let child = |token: PathToken| {
+        let mut child_path = value_path.clone();
+        child_path.push(token);
+        child_path
+      };
+      match (token, value) {
+        (PathToken::Root, _) => vec![ (value_path.clone(), value) ],
+        (PathToken::Field(name), Value::Object(map)) => map.get(name)
+          .map(|value| vec![ (child(PathToken::Field(name.clone())), value) ])
+          .unwrap_or_default(),
+        (PathToken::Index(index), Value::Array(items)) => items.get(*index)
+          .map(|value| vec![ (child(PathToken::Index(*index)), value) ])
+          .unwrap_or_default(),
+        (PathToken::Star, Value::Object(map)) => map.iter()
+          .map(|(key, value)| (child(PathToken::Field(key.clone())), value))
+          .collect(),
+        (PathToken::Star | PathToken::StarIndex, Value::Array(items)) => items.iter()
+          .enumerate()
+          .map(|(index, value)| (child(PathToken::Index(index)), value))
+          .collect(),
+        _ => vec![]
+      }
+    }).collect();
+  }
+  values
+}
+
 fn walk_json(json: &Value, path: &mut dyn Iterator<Item=&str>) -> Option<Value> {
   match path.next() {
     Some(p) => match json {
@@ -390,6 +530,7 @@ pub fn display_diff(expected: &str, actual: &str, path: &str, indent: &str) -> S
 }
 
 /// Compares the actual JSON to the expected one
+#[instrument(level = "trace", skip_all, fields(%path))]
 pub fn compare_json(
   path: &DocPath,
   expected: &Value,
@@ -405,7 +546,8 @@ pub fn compare_json(
         expected: json_to_string(expected),
         actual: json_to_string(actual),
         description: format!("Type mismatch: Expected {} ({}) to be the same type as {} ({})",
-          value_of(actual), type_of(actual), value_of(expected), type_of(expected))
+          value_of(actual), type_of(actual), value_of(expected), type_of(expected)),
+        rule: None
       } ])
     }
     (&Value::Array(ref elist), &Value::Array(ref alist)) => compare_lists(path, elist, alist, context),
@@ -416,6 +558,7 @@ pub fn compare_json(
         actual: json_to_string(actual),
         description: format!("Type mismatch: Expected {} ({}) to be the same type as {} ({})",
           value_of(actual), type_of(actual), value_of(expected), type_of(expected)),
+        rule: None
       } ])
     }
     (_, _) => compare_values(path, expected, actual, context)
@@ -437,6 +580,7 @@ fn compare_maps(
       expected: json_to_string(&json!(expected)),
       actual: json_to_string(&json!(actual)),
       description: format!("Expected an empty Map but received {}", json_to_string(&json!(actual))),
+      rule: None
     } ])
   } else {
     let mut result = Ok(());
@@ -452,6 +596,7 @@ fn compare_maps(
         });
         result = merge_result(result, result1);
       }
+      result = with_rule_trace(result, path, context);
     } else {
       let expected_keys = expected.keys().cloned().collect();
       let actual_keys = actual.keys().cloned().collect();
@@ -476,15 +621,17 @@ fn compare_lists(
   let spath = path.to_string();
   if context.matcher_is_defined(path) {
     debug!("compare_lists: matcher defined for path '{}'", path);
-    compare_lists_with_matchingrules(path, &context.select_best_matcher(path), expected, actual, context, &mut |p, expected, actual, context| {
+    let result = compare_lists_with_matchingrules(path, &context.select_best_matcher(path), expected, actual, context, &mut |p, expected, actual, context| {
         compare_json(p, expected, actual, context)
-    })
+    });
+    with_rule_trace(result, path, context)
   } else if expected.is_empty() && !actual.is_empty() {
     Err(vec![ CommonMismatch {
       path: spath,
       expected: json_to_string(&json!(expected)),
       actual: json_to_string(&json!(actual)),
       description: format!("Expected an empty List but received {}", json_to_string(&json!(actual))),
+      rule: None
     } ])
   } else {
     let result = compare_list_content(path, expected, actual, context);
@@ -495,6 +642,7 @@ fn compare_lists(
         actual: json_to_string(&json!(actual)),
         description: format!("Expected a List with {} elements but received {} elements",
                           expected.len(), actual.len()),
+        rule: None
       } ]))
     } else {
       result
@@ -520,7 +668,7 @@ fn compare_list_content(
         path: path.to_string(),
         expected: json_to_string(&json!(expected)),
         actual: json_to_string(&json!(actual)),
-        description: format!("Expected {} but was missing", json_to_string(value)) } ]))
+        description: format!("Expected {} but was missing", json_to_string(value)), rule: None } ]))
     }
   }
   result
@@ -532,11 +680,11 @@ fn compare_values(
   actual: &Value,
   context: &(dyn MatchingContext + Send + Sync)
 ) -> Result<(), Vec<CommonMismatch>> {
-  let matcher_result = if context.matcher_is_defined(path) {
+  let (matcher_result, rule) = if context.matcher_is_defined(path) {
     debug!("compare_values: Calling match_values for path {}", path);
-    match_values(path, &context.select_best_matcher(&path), expected, actual)
+    (match_values(path, &context.select_best_matcher(&path), expected, actual), context.rule_trace(path))
   } else {
-    expected.matches_with(actual, &MatchingRule::Equality, false).map_err(|err| vec![err.to_string()])
+    (expected.matches_with(actual, &MatchingRule::Equality, false).map_err(|err| vec![err.to_string()]), None)
   };
   debug!("compare_values: Comparing '{:?}' to '{:?}' at path '{}' -> {:?}", expected, actual, path.to_string(), matcher_result);
   matcher_result.map_err(|messages| {
@@ -545,12 +693,28 @@ fn compare_values(
         path: path.to_string(),
         expected: format!("{}", expected),
         actual: format!("{}", actual),
-        description: message.clone()
+        description: message.clone(),
+        rule: rule.clone()
       }
     }).collect()
   })
 }
 
+/// Records the matching rule applied at the path against any mismatches for that path
+fn with_rule_trace(
+  result: Result<(), Vec<CommonMismatch>>,
+  path: &DocPath,
+  context: &(dyn MatchingContext + Send + Sync)
+) -> Result<(), Vec<CommonMismatch>> {
+  result.map_err(|mismatches| {
+    let rule = context.rule_trace(path);
+    let spath = path.to_string();
+    mismatches.into_iter()
+      .map(|mismatch| if mismatch.path == spath { mismatch.with_rule(rule.as_ref()) } else { mismatch })
+      .collect()
+  })
+}
+
 #[cfg(test)]
 mod tests {
   use std::collections::HashMap;
@@ -564,7 +728,7 @@ mod tests {
   use pact_models::matchingrules::expressions::{MatchingRuleDefinition, ValueType};
   use pact_models::request::Request;
 
-  use crate::{CoreMatchingContext, DiffConfig};
+  use crate::{ArrayMatchingOptions, CoreMatchingContext, DiffConfig, RuleTrace};
   use crate::Mismatch;
   use crate::Mismatch::BodyMismatch;
 
@@ -583,7 +747,7 @@ mod tests {
       path: s!("$"),
       expected: expected.body.value(),
       actual: actual.body.value(),
-      mismatch: s!("") }]));
+      mismatch: s!(""), rule: None }]));
   }
 
   #[test]
@@ -597,7 +761,8 @@ mod tests {
           path: s!("$"),
           expected: expected.body.value(),
           actual: actual.body.value(),
-          mismatch: s!("Type mismatch: Expected List [{}] but received Map {}")
+          mismatch: s!("Type mismatch: Expected List [{}] but received Map {}"),
+          rule: None
         }
       ]
     ));
@@ -623,7 +788,8 @@ mod tests {
       path: "$".to_string(),
       expected: expected.body.value(),
       actual: actual.body.value(),
-      mismatch: "".to_string()
+      mismatch: "".to_string(),
+      rule: None
     }]));
   }
 
@@ -637,7 +803,8 @@ mod tests {
       path: "$".to_string(),
       expected: expected.body.value(),
       actual: actual.body.value(),
-      mismatch: "".to_string()
+      mismatch: "".to_string(),
+      rule: None
     }]));
   }
 
@@ -654,7 +821,8 @@ mod tests {
       path: "$".to_string(),
       expected: val1.body.value(),
       actual: val2.body.value(),
-      mismatch: "".to_string()
+      mismatch: "".to_string(),
+      rule: None
     } ]));
   }
 
@@ -671,7 +839,8 @@ mod tests {
       path: "$".to_string(),
       expected: val1.body.value(),
       actual: val2.body.value(),
-      mismatch: "".to_string()
+      mismatch: "".to_string(),
+      rule: None
     } ]));
   }
 
@@ -688,7 +857,8 @@ mod tests {
       path: "$".to_string(),
       expected: val1.body.value(),
       actual: val2.body.value(),
-      mismatch: "".to_string()
+      mismatch: "".to_string(),
+      rule: None
     } ]));
   }
 
@@ -705,7 +875,8 @@ mod tests {
       path: "$".to_string(),
       expected: val1.body.value(),
       actual: val2.body.value(),
-      mismatch: "".to_string()
+      mismatch: "".to_string(),
+      rule: None
     } ]));
   }
 
@@ -722,7 +893,8 @@ mod tests {
       path: "$".to_string(),
       expected: val1.clone().body.value(),
       actual: val2.clone().body.value(),
-      mismatch: "".to_string()
+      mismatch: "".to_string(),
+      rule: None
     } ]));
   }
 
@@ -749,13 +921,13 @@ mod tests {
     let result = match_json(&val2.clone(), &val3.clone(), &CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys));
     expect!(mismatch_message(&result)).to(be_equal_to("Expected 44 (Integer) to be equal to 22 (Integer)".to_string()));
     expect!(result).to(be_err().value(vec![ Mismatch::BodyMismatch { path: "$[1]".to_string(),
-        expected: Some("22".into()), actual: Some("44".into()), mismatch: "".to_string() } ]));
+        expected: Some("22".into()), actual: Some("44".into()), mismatch: "".to_string(), rule: None } ]));
 
     let result = match_json(&val3.clone(), &val4.clone(), &CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys));
     expect!(mismatch_message(&result)).to(be_equal_to("Expected a List with 3 elements but received 4 elements".to_string()));
     expect!(result).to(be_err().value(vec![ Mismatch::BodyMismatch { path: "$".to_string(),
         expected: Some("[11,44,33]".into()),
-        actual: Some("[11,44,33,66]".into()), mismatch: "".to_string() } ]));
+        actual: Some("[11,44,33,66]".into()), mismatch: "".to_string(), rule: None } ]));
 
     let result = match_json(&val2.clone(), &val4.clone(), &CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys));
     let mismatches = result.unwrap_err();
@@ -763,12 +935,12 @@ mod tests {
     let mismatch = mismatches[0].clone();
     expect!(&mismatch).to(be_equal_to(&Mismatch::BodyMismatch { path: "$[1]".to_string(),
         expected: Some("22".into()),
-        actual: Some("44".into()), mismatch: "".to_string()}));
+        actual: Some("44".into()), mismatch: "".to_string(), rule: None}));
     expect!(mismatch.description()).to(be_equal_to("$[1] -> Expected 44 (Integer) to be equal to 22 (Integer)".to_string()));
     let mismatch = mismatches[1].clone();
     expect!(&mismatch).to(be_equal_to(&Mismatch::BodyMismatch { path: "$".to_string(),
         expected: Some("[11,22,33]".into()),
-        actual: Some("[11,44,33,66]".into()), mismatch: "".to_string()}));
+        actual: Some("[11,44,33,66]".into()), mismatch: "".to_string(), rule: None}));
     expect!(mismatch.description()).to(be_equal_to("$ -> Expected a List with 3 elements but received 4 elements".to_string()));
 
     let context = CoreMatchingContext::new(DiffConfig::AllowUnexpectedKeys, &matchingrules! {
@@ -804,7 +976,7 @@ mod tests {
     let result = match_json(&val2.clone(), &val3.clone(), &CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys));
     expect!(mismatch_message(&result).as_str()).to(be_equal_to("Expected 3 (Integer) to be equal to 2 (Integer)"));
     expect!(result).to(be_err().value(vec![ Mismatch::BodyMismatch { path: "$.b".to_string(),
-        expected: Some("2".into()), actual: Some("3".into()), mismatch: "".to_string() } ]));
+        expected: Some("2".into()), actual: Some("3".into()), mismatch: "".to_string(), rule: None } ]));
 
     let result = match_json(&val2.clone(), &val4.clone(), &CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys));
     expect!(result).to(be_ok());
@@ -813,14 +985,15 @@ mod tests {
     expect!(mismatch_message(&result)).to(be_equal_to("Expected a Map with keys [a, b] but received one with keys [a, b, c]".to_string()));
     expect!(result).to(be_err().value(vec![ Mismatch::BodyMismatch { path: "$".to_string(),
         expected: Some("[\"a\",\"b\"]".into()),
-        actual: Some("[\"a\",\"b\",\"c\"]".into()), mismatch: "Expected a Map with keys [a, b] but received one with keys [a, b, c]".to_string()
+        actual: Some("[\"a\",\"b\",\"c\"]".into()), mismatch: "Expected a Map with keys [a, b] but received one with keys [a, b, c]".to_string(),
+        rule: None
     } ]));
 
     let result = match_json(&val3.clone(), &val4.clone(), &CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys));
     expect!(mismatch_message(&result).as_str()).to(be_equal_to("Expected 2 (Integer) to be equal to 3 (Integer)"));
     expect!(result).to(be_err().value(vec![ Mismatch::BodyMismatch { path: "$.b".to_string(),
         expected: Some("3".into()),
-        actual: Some("2".into()), mismatch: "".to_string() } ]));
+        actual: Some("2".into()), mismatch: "".to_string(), rule: None } ]));
 
     let result = match_json(&val3.clone(), &val4.clone(), &CoreMatchingContext::with_config(DiffConfig::NoUnexpectedKeys));
     let mismatches = result.unwrap_err();
@@ -828,12 +1001,12 @@ mod tests {
     let mismatch = mismatches[0].clone();
     expect!(&mismatch).to(be_equal_to(&Mismatch::BodyMismatch { path: "$".to_string(),
         expected: Some("[\"a\",\"b\"]".into()),
-        actual: Some("[\"a\",\"b\",\"c\"]".into()), mismatch: "".to_string()}));
+        actual: Some("[\"a\",\"b\",\"c\"]".into()), mismatch: "".to_string(), rule: None}));
     expect!(mismatch.description()).to(be_equal_to("$ -> Expected a Map with keys [a, b] but received one with keys [a, b, c]".to_string()));
     let mismatch = mismatches[1].clone();
     expect!(&mismatch).to(be_equal_to(&Mismatch::BodyMismatch { path: "$.b".to_string(),
         expected: Some("3".into()),
-        actual: Some("2".into()), mismatch: "".to_string()}));
+        actual: Some("2".into()), mismatch: "".to_string(), rule: None}));
     expect!(mismatch.description()).to(be_equal_to("$.b -> Expected 2 (Integer) to be equal to 3 (Integer)".to_string()));
 
     let result = match_json(&val4.clone(), &val2.clone(), &CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys));
@@ -842,7 +1015,7 @@ mod tests {
     let mismatch = mismatches[0].clone();
     expect!(&mismatch).to(be_equal_to(&Mismatch::BodyMismatch { path: "$".to_string(),
         expected: Some("[\"a\",\"b\",\"c\"]".into()),
-        actual: Some("[\"a\",\"b\"]".into()), mismatch: "".to_string()}));
+        actual: Some("[\"a\",\"b\"]".into()), mismatch: "".to_string(), rule: None}));
     expect!(mismatch.description()).to(be_equal_to("$ -> Actual map is missing the following keys: c".to_string()));
 
     let result = match_json(&val3, &val2, &CoreMatchingContext::new(DiffConfig::AllowUnexpectedKeys, &matchingrules!{
@@ -1096,7 +1269,8 @@ mod tests {
         path: "$".to_string(),
         expected: Some("3".into()),
         actual: Some("[\"10\",\"22\",\"6\",\"1\",\"5\",\"2\"]".into()),
-        mismatch: "Variant at index 2 (3) was not found in the actual list".to_string()
+        mismatch: "Variant at index 2 (3) was not found in the actual list".to_string(),
+        rule: None
       }
     ]));
   }
@@ -1150,14 +1324,187 @@ mod tests {
         expected: Some("2".into()),
         actual: Some("\"abc123\"".into()),
         mismatch: "Expected 'abc123' (String) to be an integer number".to_string(),
+        rule: None
       },
       BodyMismatch {
         path: "$[2]".to_string(),
         expected: Some("1".into()),
         actual: Some("\"test\"".into()),
         mismatch: "Expected 'test' (String) to be an integer number".to_string(),
+        rule: None
+      }
+    ]));
+  }
+
+  #[test]
+  fn compare_lists_with_min_type_matcher_compares_additional_elements_to_the_first_element() {
+    let expected = request!(r#"{"items": [{"id": 1}, {"name": "a"}]}"#);
+    let actual = request!(r#"{"items": [{"id": 2}, {"name": "b"}, {"name": "c"}]}"#);
+
+    let rules = matchingrules! {
+      "body" => { "$.items" => [ MatchingRule::MinType(1) ] }
+    };
+    let context = CoreMatchingContext::new(
+      DiffConfig::NoUnexpectedKeys,
+      &rules.rules_for_category("body").unwrap_or_default(),
+      &hashmap!{}
+    );
+
+    let result = match_json(&expected, &actual, &context);
+    expect!(result).to(be_err());
+  }
+
+  #[test]
+  fn compare_lists_with_min_type_matcher_and_allow_unexpected_elements_option() {
+    let expected = request!(r#"{"items": [{"id": 1}, {"name": "a"}]}"#);
+    let actual = request!(r#"{"items": [{"id": 2}, {"name": "b"}, {"name": "c"}]}"#);
+
+    let rules = matchingrules! {
+      "body" => { "$.items" => [ MatchingRule::MinType(1) ] }
+    };
+    let context = CoreMatchingContext {
+      array_options: ArrayMatchingOptions { allow_unexpected_elements: true },
+      .. CoreMatchingContext::new(
+        DiffConfig::NoUnexpectedKeys,
+        &rules.rules_for_category("body").unwrap_or_default(),
+        &hashmap!{}
+      )
+    };
+
+    let result = match_json(&expected, &actual, &context);
+    expect!(result).to(be_ok());
+
+    // The elements that are expected are still compared
+    let actual = request!(r#"{"items": [{"id": 2}, {"id": 3}, {"name": "c"}]}"#);
+    let result = match_json(&expected, &actual, &context);
+    expect!(result).to(be_err());
+  }
+
+  #[test]
+  fn compare_lists_with_min_type_matcher_and_allow_unexpected_elements_rule() {
+    let expected = request!(r#"{"items": [{"id": 1}, {"name": "a"}], "other": [{"id": 1}]}"#);
+    let actual = request!(r#"{"items": [{"id": 2}, {"name": "b"}, {"name": "c"}], "other": [{"id": 1}, {"name": "c"}]}"#);
+
+    let rules = matchingrules! {
+      "body" => {
+        "$.items" => [ MatchingRule::MinType(1), MatchingRule::AllowUnexpectedElements ],
+        "$.other" => [ MatchingRule::MinType(1) ]
+      }
+    };
+    let context = CoreMatchingContext::new(
+      DiffConfig::NoUnexpectedKeys,
+      &rules.rules_for_category("body").unwrap_or_default(),
+      &hashmap!{}
+    );
+
+    let result = match_json(&expected, &actual, &context);
+    let paths = result.unwrap_err().iter().map(|m| match m {
+      BodyMismatch { path, .. } => path.clone(),
+      _ => String::default()
+    }).collect::<Vec<_>>();
+    expect!(paths).to(be_equal_to(vec!["$.other[1]".to_string()]));
+  }
+
+  #[test]
+  fn compare_lists_with_allow_unexpected_elements_still_applies_the_maximum() {
+    let expected = request!(r#"[1]"#);
+    let actual = request!(r#"[1, "2", "3"]"#);
+
+    let rules = matchingrules! {
+      "body" => { "$" => [ MatchingRule::MinMaxType(1, 2), MatchingRule::AllowUnexpectedElements ] }
+    };
+    let context = CoreMatchingContext::new(
+      DiffConfig::AllowUnexpectedKeys,
+      &rules.rules_for_category("body").unwrap_or_default(),
+      &hashmap!{}
+    );
+
+    let result = match_json(&expected, &actual, &context);
+    expect!(mismatch_message(&result)).to(be_equal_to("Expected [1, \"2\", \"3\"] (size 3) to have maximum size of 2".to_string()));
+  }
+
+  #[test]
+  fn compare_lists_with_ignore_order_matcher() {
+    let expected = request!(r#"{"items": [{"id": 1, "name": "a"}, {"id": 2, "name": "b"}]}"#);
+    let actual = request!(r#"{"items": [{"id": 2, "name": "b"}, {"id": 1, "name": "a"}]}"#);
+
+    let rules = matchingrules! {
+      "body" => { "$.items" => [ MatchingRule::EqualsIgnoreOrder ] }
+    };
+    let context = CoreMatchingContext::new(
+      DiffConfig::NoUnexpectedKeys,
+      &rules.rules_for_category("body").unwrap_or_default(),
+      &hashmap!{}
+    );
+
+    expect!(match_json(&expected, &actual, &context)).to(be_ok());
+
+    // The items are still compared with equality
+    let actual = request!(r#"{"items": [{"id": 2, "name": "b"}, {"id": 1, "name": "c"}]}"#);
+    let result = match_json(&expected, &actual, &context);
+    expect!(mismatch_message(&result)).to(be_equal_to("Expected 'c' (String) to be equal to 'a' (String)".to_string()));
+  }
+
+  #[test]
+  fn compare_lists_with_ignore_order_matcher_finds_the_best_assignment() {
+    let expected = request!(r#"[1, 2]"#);
+    let actual = request!(r#"[2, 3]"#);
+
+    // The first expected item would match either actual item, so it needs to be assigned to the
+    // second one for the second expected item to match
+    let rules = matchingrules! {
+      "body" => {
+        "$" => [ MatchingRule::EqualsIgnoreOrder ],
+        "$[0]" => [ MatchingRule::Integer ]
       }
+    };
+    let context = CoreMatchingContext::new(
+      DiffConfig::NoUnexpectedKeys,
+      &rules.rules_for_category("body").unwrap_or_default(),
+      &hashmap!{}
+    );
+
+    expect!(match_json(&expected, &actual, &context)).to(be_ok());
+  }
+
+  #[test]
+  fn compare_lists_with_ignore_order_matcher_reports_the_unmatched_items() {
+    let expected = request!(r#"[1, 2, 3]"#);
+    let actual = request!(r#"[3, 1, 4, 5]"#);
+
+    let rules = matchingrules! {
+      "body" => { "$" => [ MatchingRule::EqualsIgnoreOrder ] }
+    };
+    let context = CoreMatchingContext::new(
+      DiffConfig::NoUnexpectedKeys,
+      &rules.rules_for_category("body").unwrap_or_default(),
+      &hashmap!{}
+    );
+
+    let result = match_json(&expected, &actual, &context);
+    let mismatches = result.unwrap_err().iter().map(|m| match m {
+      BodyMismatch { path, mismatch, .. } => format!("{}: {}", path, mismatch),
+      _ => String::default()
+    }).collect::<Vec<_>>();
+    expect!(mismatches).to(be_equal_to(vec![
+      "$[1]: Expected 4 (Integer) to be equal to 2 (Integer)".to_string(),
+      "$: Unexpected item 5 (3) was not matched by any expected item".to_string()
     ]));
+
+    let rules = matchingrules! {
+      "body" => { "$" => [ MatchingRule::EqualsIgnoreOrder, MatchingRule::AllowUnexpectedElements ] }
+    };
+    let context = CoreMatchingContext::new(
+      DiffConfig::NoUnexpectedKeys,
+      &rules.rules_for_category("body").unwrap_or_default(),
+      &hashmap!{}
+    );
+    let actual = request!(r#"[3, 1, 5, 2]"#);
+    expect!(match_json(&expected, &actual, &context)).to(be_ok());
+
+    let actual = request!(r#"[3, 1]"#);
+    let result = match_json(&expected, &actual, &context);
+    expect!(mismatch_message(&result)).to(be_equal_to("Expected 2 (1) but was missing".to_string()));
   }
 
   #[test]
@@ -1280,6 +1627,74 @@ mod tests {
     expect!(result).to(be_ok());
   }
 
+  #[test]
+  fn match_json_records_the_matching_rule_applied_to_the_mismatch() {
+    let expected = request!(r#"{"a": {"b": "100"}, "c": "100"}"#);
+    let actual = request!(r#"{"a": {"b": 100}, "c": 100}"#);
+    let context = CoreMatchingContext::new(DiffConfig::AllowUnexpectedKeys, &matchingrules! {
+      "body" => {
+        "$.a" => [ MatchingRule::Type ],
+        "$.c" => [ MatchingRule::Type ]
+      }
+    }.rules_for_category("body").unwrap(), &hashmap!{});
+
+    let mut mismatches = match_json(&expected, &actual, &context).unwrap_err();
+    mismatches.sort_by(|a, b| a.to_json()["path"].as_str().cmp(&b.to_json()["path"].as_str()));
+    expect!(mismatches.len()).to(be_equal_to(2));
+
+    match &mismatches[0] {
+      Mismatch::BodyMismatch { path, rule, .. } => {
+        expect!(path.as_str()).to(be_equal_to("$.a.b"));
+        expect!(rule.clone()).to(be_some().value(RuleTrace {
+          rule_path: "$.a".to_string(),
+          cascaded: true,
+          rules: vec![ MatchingRule::Type ],
+          origin: RuleOrigin::Core
+        }));
+      }
+      _ => panic!("Expected a body mismatch")
+    }
+    expect!(mismatches[0].description()).to(be_equal_to(
+      "$.a.b -> Expected 100 (Integer) to be the same type as '100' (String) (rule cascaded from '$.a')"));
+    expect!(mismatches[0].to_json()["rule"].clone()).to(be_equal_to(json!({
+      "path": "$.a",
+      "cascaded": true,
+      "rules": [ { "match": "type" } ],
+      "origin": "core"
+    })));
+
+    match &mismatches[1] {
+      Mismatch::BodyMismatch { path, rule, .. } => {
+        expect!(path.as_str()).to(be_equal_to("$.c"));
+        expect!(rule.clone()).to(be_some().value(RuleTrace {
+          rule_path: "$.c".to_string(),
+          cascaded: false,
+          rules: vec![ MatchingRule::Type ],
+          origin: RuleOrigin::Core
+        }));
+      }
+      _ => panic!("Expected a body mismatch")
+    }
+    expect!(mismatches[1].description()).to(be_equal_to(
+      "$.c -> Expected 100 (Integer) to be the same type as '100' (String)"));
+  }
+
+  #[test]
+  fn match_json_does_not_record_a_matching_rule_when_none_was_applied() {
+    let expected = request!(r#"{"a": "100"}"#);
+    let actual = request!(r#"{"a": "200"}"#);
+    let context = CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys);
+
+    let mismatches = match_json(&expected, &actual, &context).unwrap_err();
+    match &mismatches[0] {
+      Mismatch::BodyMismatch { rule, .. } => {
+        expect!(rule.clone()).to(be_none());
+      }
+      _ => panic!("Expected a body mismatch")
+    }
+    expect!(mismatches[0].to_json().get("rule")).to(be_none());
+  }
+
   #[test]
   fn compare_maps_handles_empty_expected_maps() {
     let expected_json = json!({});
@@ -1417,4 +1832,33 @@ mod tests2 {
       expect!(result).to(be_err());
     }
   }
+
+  #[test]
+  fn match_json_expectations_test() {
+    let context = CoreMatchingContext::new(DiffConfig::AllowUnexpectedKeys, &matchingrules! {
+      "body" => {
+        "$.data.id" => [ MatchingRule::Exists ],
+        "$.data.items" => [ MatchingRule::MinType(1) ],
+        "$.data.items[*].name" => [ MatchingRule::Type ],
+        "$.data.error" => [ MatchingRule::Absent ]
+      }
+    }.rules_for_category("body").unwrap(), &hashmap!{});
+
+    let actual = request!(r#"{"data": {"id": 100, "items": [{"name": "one"}, {"name": "two"}], "other": true}}"#);
+    expect!(match_json_expectations(&actual, &context)).to(be_ok());
+
+    let actual = request!(r#"{"data": {"items": [], "error": "failed"}}"#);
+    let mismatches = match_json_expectations(&actual, &context).unwrap_err();
+    expect!(mismatches.iter().map(|m| m.description()).collect::<Vec<_>>()).to(be_equal_to(vec![
+      "$.data.error -> Expected no value at '$.data.error', but got 'failed' (String)".to_string(),
+      "$.data.id -> Expected a value at '$.data.id', but it was missing".to_string(),
+      "$.data.items -> Expected '[]' to have at least 1 item(s)".to_string(),
+      "$.data.items[*].name -> Expected a value at '$.data.items[*].name', but it was missing".to_string()
+    ]));
+
+    let actual = request!("not json");
+    expect!(match_json_expectations(&actual, &context)).to(be_err());
+    let actual = Request::default();
+    expect!(match_json_expectations(&actual, &context).unwrap_err().len()).to(be_equal_to(3));
+  }
 }
@@ -48,6 +48,11 @@
 //! For the most part, matching involves matching request and response bodies in JSON or XML format.
 //! Other formats will either have their own matching rules, or will follow the JSON one.
 //!
+//! Before the bodies are matched, any content encoding (the `Content-Encoding` header, or the
+//! `contentEncoding` message metadata value) is removed by the body transformers registered for
+//! it. gzip and deflate are supported by default, and other transformers can be registered with
+//! [`transformers::register_body_transformer`].
+//!
 //! #### JSON body matching rules
 //!
 //! Bodies consist of Objects (Maps of Key-Value pairs), Arrays (Lists) and values (Strings, Numbers, true, false, null).
@@ -56,7 +61,8 @@
 //! The following method is used to determine if two bodies match:
 //!
 //! 1. If both the actual body and expected body are empty, the bodies match.
-//! 2. If the actual body is non-empty, and the expected body empty, the bodies match.
+//! 2. If the actual body is non-empty, and the expected body empty, the bodies match (unless there
+//!    are body matching rules, see [Partial body expectations](#partial-body-expectations)).
 //! 3. If the actual body is empty, and the expected body non-empty, the bodies don't match.
 //! 4. Otherwise do a comparison on the contents of the bodies.
 //!
@@ -343,15 +349,71 @@
 //! | ContentType  | V3 | `{ "match": "contentType", "value": "image/jpeg" }` | Match binary data by its content type (magic file check) |
 //! | Values  | V3 | `{ "match": "values" }` | Match the values in a map, ignoring the keys |
 //! | ArrayContains | V4 | `{ "match": "arrayContains", "variants": [...] }` | Checks if all the variants are present in an array. |
-//! | StatusCode | V4 | `{ "match": "statusCode", "status": "success" }` | Matches the response status code. |
+//! | StatusCode | V4 | `{ "match": "statusCode", "status": "success" }` | Matches the response status code. The status can be a class (`info`, `success`, `redirect`, `clientError`, `serverError`, `nonError` or `error`, or in the form `2xx`), a range (`"200-204"`) or a list of status codes (`[200, 204]`). |
 //! | NotEmpty | V4 | `{ "match": "notEmpty" }` | Value must be present and not empty (not null or the empty string) |
 //! | Semver | V4 | `{ "match": "semver" }` | Value must be valid based on the semver specification |
 //! | Semver | V4 | `{ "match": "semver" }` | Value must be valid based on the semver specification |
 //! | EachKey | V4 | `{ "match": "eachKey", "rules": [{"match": "regex", "regex": "\\$(\\.\\w+)+"}], "value": "$.test.one" }` | Allows defining matching rules to apply to the keys in a map |
 //! | EachValue | V4 | `{ "match": "eachValue", "rules": [{"match": "regex", "regex": "\\$(\\.\\w+)+"}], "value": "$.test.one" }` | Allows defining matching rules to apply to the values in a collection. For maps, delgates to the Values matcher. |
+//! | Duration | V4 | `{ "match": "duration" }` | Value must be an ISO-8601 duration (i.e. `PT5M30S`) |
+//! | EpochTimestamp | V4 | `{ "match": "epoch", "unit": "millis", "tolerance": 60000 }` | Value must be an integer timestamp since the Unix epoch in the given unit (seconds, millis, micros or nanos). If a tolerance is given, the value must be within that many units of the current time. |
+//! | AllowUnexpectedElements | V4 | `{ "match": "type", "min": 1, "allowUnexpectedElements": true }` | Not a matcher by itself, but modifies the min type matchers for an array so that additional elements at the end of the actual array are allowed and ignored. |
+//! | EqualsIgnoreOrder | V4 | `{ "match": "ignore-order" }` | Array must contain the same elements as the expected array, but in any order (`matching(equalsIgnoringOrder, ...)` in matching rule expressions). |
+//! | Url | V4 | `{ "match": "url", "rules": { "$.host": { "matchers": [{ "match": "regex", "regex": ".+\\.example\\.com" }] } } }` | Value must be an absolute URL. Each component of the URL is matched with the rules for that component, and must be equal to the expected component otherwise. |
+//! | NumericRange | V4 | `{ "match": "range", "minimum": 0, "exclusiveMaximum": 100 }` | Value must be a number within the bounds. Each bound is optional and can be inclusive (`minimum`, `maximum`) or exclusive (`exclusiveMinimum`, `exclusiveMaximum`). The `greaterThan` and `lessThan` forms (`{ "match": "greaterThan", "value": 0, "inclusive": true }`) are accepted when loading rules. |
+//! | AnyOf | V4 | `{ "match": "anyOf", "rules": [ { "match": "regex", "regex": "\\d+" }, { "match": "null" } ] }` | Value must match at least one of the rules (`anyOf(...)` in matching rule expressions). |
+//! | Not | V4 | `{ "match": "not", "rules": [ { "match": "include", "value": "admin" } ] }` | Value must not match the rule (`not(...)` in matching rule expressions). |
+//! | Exists | V4 | `{ "match": "exists" }` | Value must be present. Used for partial body expectations (`$.data.id exists`). |
+//! | Absent | V4 | `{ "match": "absent" }` | Value must not be present. Used for partial body expectations (`$.data.error absent`). |
+//!
+//! ### Additional elements in arrays
+//!
+//! As per the Pact specification, additional elements in an actual array are only allowed when
+//! the array has a type matcher, and then they must match the first expected element (see the
+//! `array with type matcher` and `objects in array type matching` spec cases). Without a type
+//! matcher for the array, they result in a mismatch (see the `objects in array second matches`
+//! and `array with type matcher mismatch` spec cases, where the matcher is only defined for the
+//! array items).
+//!
+//! When the expected array has elements of different shapes, this can be too strict. Additional
+//! elements at the end of the actual array can be ignored for arrays with a min type matcher,
+//! either for a single array with the `allowUnexpectedElements` attribute on the min type matcher,
+//! or for all arrays by setting `allow_unexpected_elements` in the `ArrayMatchingOptions` of the
+//! matching context. The expected elements and the array size limits are still checked.
+//!
+//! ### Arrays in any order
+//!
+//! With the `ignore-order` matcher, the actual elements of an array are matched against the
+//! expected elements in any order. Each expected element is assigned to a different actual element
+//! that it matches, using the assignment that matches the most elements. Any expected elements that
+//! could not be assigned are reported against the remaining actual elements (or as missing), and
+//! any remaining actual elements are reported as unexpected, unless unexpected elements are allowed
+//! for the array (with an `allowUnexpectedElements` matcher or the `ArrayMatchingOptions`).
+//!
+//! ### URLs
+//!
+//! The `url` matcher parses the expected and actual values as absolute URLs and matches the
+//! components separately, so that (for instance) the host can vary between environments while
+//! the path still has to be equal. The rules for the components are keyed by `$.scheme`,
+//! `$.host`, `$.port`, `$.path`, `$.fragment`, `$.query` (applies to all query parameters) or
+//! `$.query.<name>` (a single query parameter). Any component without a rule must be equal to the
+//! expected one, and query parameters that are missing or unexpected are mismatches. In matching
+//! rule expressions, this is `matching(url, 'https://api.example.com/v1/users', 'host', matching(regex, '.+\\.example\\.com', 'api.example.com'))`.
+//!
+//! ### Partial body expectations
+//!
+//! A JSON body can be described by a set of expectations on the values at some paths, instead
+//! of an example body (for instance, `$.data.id exists` and `$.data.items length >= 1`). The
+//! expectations are stored as the body matching rules of an interaction that has no body, and
+//! can be created with `pact_models::matchingrules::expectations::expectation_matching_rules`.
+//! When the expected body is missing and there are body matching rules, each path must resolve to
+//! at least one value in the actual body and the rules for the path are applied to those values
+//! (with the actual value used as the expected value). Paths with an `absent` rule must not
+//! resolve to any value. Wildcards in the paths resolve to all the fields or items at that level.
 
 #![warn(missing_docs)]
 
+use std::cmp::Ordering;
 use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt::{Debug, Display};
 use std::fmt::Formatter;
@@ -369,7 +431,7 @@ use lazy_static::*;
 use maplit::{hashmap, hashset};
 use pact_models::bodies::OptionalBody;
 use pact_models::content_types::ContentType;
-use pact_models::generators::{apply_generators, GenerateValue, GeneratorCategory, GeneratorTestMode, VariantMatcher};
+use pact_models::generators::{apply_generators, GenerateValue, Generator, GeneratorCategory, GeneratorTestMode, VariantMatcher, with_random_seed_path};
 use pact_models::http_parts::HttpPart;
 use pact_models::interaction::Interaction;
 use pact_models::json_utils::json_to_string;
@@ -386,9 +448,10 @@ use serde::__private::from_utf8_lossy;
 use serde_json::{json, Value};
 use tracing::{debug, error, info, instrument, trace, warn};
 
-use crate::generators::DefaultVariantMatcher;
+use crate::generators::{DefaultVariantMatcher, GeneratorReport};
 use crate::generators::bodies::generators_process_body;
-use crate::headers::{match_header_value, match_headers};
+use crate::headers::{match_header_value, match_headers, match_trailers};
+use crate::json::compare_json;
 #[cfg(feature = "plugins")] use crate::json::match_json;
 use crate::matchers::*;
 use crate::matchingrules::DisplayForMismatch;
@@ -411,10 +474,17 @@ pub mod metrics;
 pub mod generators;
 
 #[cfg(feature = "xml")] mod xml;
+#[cfg(feature = "avro")] pub mod avro;
+#[cfg(feature = "cbor")] pub mod cbor;
+#[cfg(feature = "msgpack")] pub mod msgpack;
 pub mod binary_utils;
 pub mod headers;
 pub mod query;
 pub mod form_urlencoded;
+pub mod transformers;
+pub mod diff;
+
+pub use diff::{diff_pacts, PactDiff};
 
 #[cfg(not(feature = "plugins"))]
 #[derive(Clone, Debug, PartialEq)]
@@ -450,8 +520,32 @@ pub trait MatchingContext: Debug {
   /// Configuration to apply when matching with the context
   fn config(&self) -> DiffConfig;
 
+  /// Options to apply when matching XML documents with the context
+  fn xml_options(&self) -> XmlMatchingOptions {
+    XmlMatchingOptions::default()
+  }
+
+  /// Options to apply when matching arrays with the context
+  fn array_options(&self) -> ArrayMatchingOptions {
+    ArrayMatchingOptions::default()
+  }
+
   /// Clones the current context with the provided matching rules
   fn clone_with(&self, matchers: &MatchingRuleCategory) -> Box<dyn MatchingContext + Send + Sync>;
+
+  /// Returns the path of the matching rule that will be applied at the given path, and if it has
+  /// cascaded from a parent path
+  fn rule_trace(&self, path: &DocPath) -> Option<RuleTrace> {
+    let path_vec = path.to_vec();
+    let path_slice = path_vec.iter().map(|p| p.as_str()).collect_vec();
+    self.matchers().select_best_matcher_path(path_slice.as_slice())
+      .map(|rule_path| RuleTrace {
+        rule_path: rule_path.to_string(),
+        cascaded: rule_path.len() != path.len(),
+        rules: self.select_best_matcher(path).rules,
+        origin: RuleOrigin::Core
+      })
+  }
 }
 
 #[derive(Debug, Clone)]
@@ -464,7 +558,11 @@ pub struct CoreMatchingContext {
   /// Specification version to apply when matching with the context
   pub matching_spec: PactSpecification,
   /// Any plugin configuration available for the interaction
-  pub plugin_configuration: HashMap<String, PluginInteractionConfig>
+  pub plugin_configuration: HashMap<String, PluginInteractionConfig>,
+  /// Options to apply when matching XML documents
+  pub xml_options: XmlMatchingOptions,
+  /// Options to apply when matching arrays
+  pub array_options: ArrayMatchingOptions
 }
 
 impl CoreMatchingContext {
@@ -510,6 +608,8 @@ impl CoreMatchingContext {
       matchers: context.matchers().clone(),
       config: context.config().clone(),
       plugin_configuration: context.plugin_configuration().clone(),
+      xml_options: context.xml_options(),
+      array_options: context.array_options(),
       .. CoreMatchingContext::default()
     }
   }
@@ -521,7 +621,9 @@ impl Default for CoreMatchingContext {
       matchers: Default::default(),
       config: DiffConfig::AllowUnexpectedKeys,
       matching_spec: PactSpecification::V3,
-      plugin_configuration: Default::default()
+      plugin_configuration: Default::default(),
+      xml_options: Default::default(),
+      array_options: Default::default()
     }
   }
 }
@@ -575,6 +677,7 @@ impl MatchingContext for CoreMatchingContext {
             expected: expected.for_mismatch(),
             actual: actual.for_mismatch(),
             description: format!("Actual map is missing the following keys: {}", missing_keys.join(", ")),
+            rule: None
           });
         }
         DiffConfig::NoUnexpectedKeys if expected_keys != actual_keys => {
@@ -584,6 +687,7 @@ impl MatchingContext for CoreMatchingContext {
             actual: actual.for_mismatch(),
             description: format!("Expected a Map with keys [{}] but received one with keys [{}]",
                               expected_keys.join(", "), actual_keys.join(", ")),
+            rule: None
           });
         }
         _ => {}
@@ -606,6 +710,7 @@ impl MatchingContext for CoreMatchingContext {
                         expected: "".to_string(),
                         actual: key.clone(),
                         description: err.to_string(),
+                        rule: None
                       });
                     }
                   }
@@ -617,6 +722,7 @@ impl MatchingContext for CoreMatchingContext {
                     actual: actual.for_mismatch(),
                     description: format!("Expected a matching rule, found an unresolved reference '{}'",
                       name.name),
+                    rule: None
                   });
                 }
               }
@@ -646,12 +752,22 @@ impl MatchingContext for CoreMatchingContext {
     self.config
   }
 
+  fn xml_options(&self) -> XmlMatchingOptions {
+    self.xml_options
+  }
+
+  fn array_options(&self) -> ArrayMatchingOptions {
+    self.array_options
+  }
+
   fn clone_with(&self, matchers: &MatchingRuleCategory) -> Box<dyn MatchingContext + Send + Sync> {
     Box::new(CoreMatchingContext {
       matchers: matchers.clone(),
       config: self.config.clone(),
       matching_spec: self.matching_spec,
-      plugin_configuration: self.plugin_configuration.clone()
+      plugin_configuration: self.plugin_configuration.clone(),
+      xml_options: self.xml_options,
+      array_options: self.array_options
     })
   }
 }
@@ -720,13 +836,23 @@ impl MatchingContext for HeaderMatchingContext {
     self.inner_context.config()
   }
 
+  fn xml_options(&self) -> XmlMatchingOptions {
+    self.inner_context.xml_options()
+  }
+
+  fn array_options(&self) -> ArrayMatchingOptions {
+    self.inner_context.array_options()
+  }
+
   fn clone_with(&self, matchers: &MatchingRuleCategory) -> Box<dyn MatchingContext + Send + Sync> {
     Box::new(HeaderMatchingContext::new(
       &CoreMatchingContext {
         matchers: matchers.clone(),
         config: self.inner_context.config.clone(),
         matching_spec: self.inner_context.matching_spec,
-        plugin_configuration: self.inner_context.plugin_configuration.clone()
+        plugin_configuration: self.inner_context.plugin_configuration.clone(),
+        xml_options: self.inner_context.xml_options,
+        array_options: self.inner_context.array_options
       }
     ))
   }
@@ -735,10 +861,12 @@ impl MatchingContext for HeaderMatchingContext {
 lazy_static! {
   static ref BODY_MATCHERS: [
     (fn(content_type: &ContentType) -> bool,
-    fn(expected: &(dyn HttpPart + Send + Sync), actual: &(dyn HttpPart + Send + Sync), context: &(dyn MatchingContext + Send + Sync)) -> Result<(), Vec<Mismatch>>); 5]
+    fn(expected: &(dyn HttpPart + Send + Sync), actual: &(dyn HttpPart + Send + Sync), context: &(dyn MatchingContext + Send + Sync)) -> Result<(), Vec<Mismatch>>); 7]
      = [
       (|content_type| { content_type.is_json() }, json::match_json),
       (|content_type| { content_type.is_xml() }, match_xml),
+      (|content_type| { content_type.is_cbor() }, match_cbor),
+      (|content_type| { content_type.is_msgpack() }, match_msgpack),
       (|content_type| { content_type.main_type == "multipart" }, binary_utils::match_mime_multipart),
       (|content_type| { content_type.base_type() == "application/x-www-form-urlencoded" }, form_urlencoded::match_form_urlencoded),
       (|content_type| { content_type.is_binary() || content_type.base_type() == "application/octet-stream" }, binary_utils::match_octet_stream)
@@ -761,6 +889,123 @@ fn match_xml(
   }
 }
 
+fn match_cbor(
+  expected: &(dyn HttpPart + Send + Sync),
+  actual: &(dyn HttpPart + Send + Sync),
+  context: &(dyn MatchingContext + Send + Sync)
+) -> Result<(), Vec<Mismatch>> {
+  #[cfg(feature = "cbor")]
+  {
+    cbor::match_cbor(expected, actual, context)
+  }
+  #[cfg(not(feature = "cbor"))]
+  {
+    warn!("Matching CBOR bodies requires the cbor feature to be enabled");
+    binary_utils::match_octet_stream(expected, actual, context)
+  }
+}
+
+fn match_msgpack(
+  expected: &(dyn HttpPart + Send + Sync),
+  actual: &(dyn HttpPart + Send + Sync),
+  context: &(dyn MatchingContext + Send + Sync)
+) -> Result<(), Vec<Mismatch>> {
+  #[cfg(feature = "msgpack")]
+  {
+    msgpack::match_msgpack(expected, actual, context)
+  }
+  #[cfg(not(feature = "msgpack"))]
+  {
+    warn!("Matching MessagePack bodies requires the msgpack feature to be enabled");
+    binary_utils::match_octet_stream(expected, actual, context)
+  }
+}
+
+/// Where the matching rules that were applied to produce a mismatch were evaluated
+#[derive(Debug, Clone, Default, PartialEq, PartialOrd, Ord, Eq, Hash)]
+pub enum RuleOrigin {
+  /// Rules were evaluated by the core matching functions
+  #[default]
+  Core,
+  /// Rules were evaluated by the content matcher provided by the named plugin
+  Plugin(String)
+}
+
+impl Display for RuleOrigin {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      RuleOrigin::Core => write!(f, "core"),
+      RuleOrigin::Plugin(name) => write!(f, "plugin '{}'", name)
+    }
+  }
+}
+
+/// Records which matching rule was applied to produce a mismatch
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RuleTrace {
+  /// Path the matching rule was defined against
+  pub rule_path: String,
+  /// If the rule was defined against a parent of the path (i.e. has cascaded)
+  pub cascaded: bool,
+  /// Matching rules that were evaluated
+  pub rules: Vec<MatchingRule>,
+  /// Where the matching rules were evaluated
+  pub origin: RuleOrigin
+}
+
+impl RuleTrace {
+  /// Converts the rule trace to a `Value` struct.
+  pub fn to_json(&self) -> serde_json::Value {
+    let mut json = json!({
+      "path": self.rule_path,
+      "cascaded": self.cascaded,
+      "rules": self.rules.iter().map(|rule| rule.to_json()).collect_vec(),
+      "origin": match self.origin {
+        RuleOrigin::Core => "core",
+        RuleOrigin::Plugin(_) => "plugin"
+      }
+    });
+    if let RuleOrigin::Plugin(name) = &self.origin {
+      json["plugin"] = json!(name);
+    }
+    json
+  }
+
+  /// Sets where the matching rules were evaluated
+  pub fn with_origin(self, origin: RuleOrigin) -> Self {
+    RuleTrace { origin, ..self }
+  }
+
+  fn rules_key(&self) -> Vec<String> {
+    self.rules.iter().map(|rule| rule.to_json().to_string()).collect()
+  }
+}
+
+impl PartialOrd for RuleTrace {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for RuleTrace {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.rule_path.cmp(&other.rule_path)
+      .then_with(|| self.cascaded.cmp(&other.cascaded))
+      .then_with(|| self.origin.cmp(&other.origin))
+      .then_with(|| self.rules_key().cmp(&other.rules_key()))
+  }
+}
+
+impl Display for RuleTrace {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    if self.cascaded {
+      write!(f, "rule cascaded from '{}'", self.rule_path)
+    } else {
+      write!(f, "rule defined at '{}'", self.rule_path)
+    }
+  }
+}
+
 /// Store common mismatch information so it can be converted to different type of mismatches
 #[derive(Debug, Clone, PartialOrd, Ord, Eq)]
 pub struct CommonMismatch {
@@ -771,7 +1016,9 @@ pub struct CommonMismatch {
   /// actual value (as a string)
   actual: String,
   /// Description of the mismatch
-  description: String
+  description: String,
+  /// Matching rule that was applied, if any
+  rule: Option<RuleTrace>
 }
 
 impl CommonMismatch {
@@ -781,8 +1028,17 @@ impl CommonMismatch {
       path: self.path.clone(),
       expected: Some(self.expected.clone().into()),
       actual: Some(self.actual.clone().into()),
-      mismatch: self.description.clone()
+      mismatch: self.description.clone(),
+      rule: self.rule.clone()
+    }
+  }
+
+  /// Sets the matching rule that was applied at the path, if one has not already been set
+  pub(crate) fn with_rule(mut self, rule: Option<&RuleTrace>) -> Self {
+    if self.rule.is_none() {
+      self.rule = rule.cloned();
     }
+    self
   }
 
   /// Convert common mismatch to query mismatch
@@ -801,6 +1057,17 @@ impl CommonMismatch {
       key: self.path.clone(),
       expected: self.expected.clone().into(),
       actual: self.actual.clone().into(),
+      mismatch: self.description.clone(),
+      rule: self.rule.clone()
+    }
+  }
+
+  /// Convert common mismatch to trailer mismatch
+  pub fn to_trailer_mismatch(&self) -> Mismatch {
+    Mismatch::TrailerMismatch {
+      key: self.path.clone(),
+      expected: self.expected.clone(),
+      actual: self.actual.clone(),
       mismatch: self.description.clone()
     }
   }
@@ -825,49 +1092,64 @@ impl From<Mismatch> for CommonMismatch {
         path: "".to_string(),
         expected: expected.clone(),
         actual: actual.clone(),
-        description: "Method mismatch".to_string()
+        description: "Method mismatch".to_string(),
+        rule: None
       },
       Mismatch::PathMismatch { expected, actual, mismatch } => CommonMismatch {
         path: "".to_string(),
         expected: expected.clone(),
         actual: actual.clone(),
-        description: mismatch.clone()
+        description: mismatch.clone(),
+        rule: None
       },
       Mismatch::StatusMismatch { expected, actual, mismatch } => CommonMismatch {
         path: "".to_string(),
         expected: expected.to_string(),
         actual: actual.to_string(),
-        description: mismatch.clone()
+        description: mismatch.clone(),
+        rule: None
       },
       Mismatch::QueryMismatch { parameter, expected, actual, mismatch } => CommonMismatch {
         path: parameter.clone(),
         expected: expected.clone(),
         actual: actual.clone(),
-        description: mismatch.clone()
+        description: mismatch.clone(),
+        rule: None
       },
-      Mismatch::HeaderMismatch { key, expected, actual, mismatch } => CommonMismatch {
+      Mismatch::HeaderMismatch { key, expected, actual, mismatch, rule } => CommonMismatch {
         path: key.clone(),
         expected: expected.clone(),
         actual: actual.clone(),
-        description: mismatch.clone()
+        description: mismatch.clone(),
+        rule
+      },
+      Mismatch::TrailerMismatch { key, expected, actual, mismatch } => CommonMismatch {
+        path: key.clone(),
+        expected: expected.clone(),
+        actual: actual.clone(),
+        description: mismatch.clone(),
+        rule: None
       },
       Mismatch::BodyTypeMismatch { expected, actual, mismatch, .. } => CommonMismatch {
         path: "".to_string(),
         expected: expected.clone(),
         actual: actual.clone(),
-        description: mismatch.clone()
+        description: mismatch.clone(),
+        rule: None
       },
-      Mismatch::BodyMismatch { path, expected, actual, mismatch } => CommonMismatch {
+      Mismatch::BodyMismatch { path, expected, actual, mismatch, rule } => CommonMismatch {
         path: path.clone(),
         expected: from_utf8_lossy(expected.unwrap_or_default().as_ref()).to_string(),
         actual: from_utf8_lossy(actual.unwrap_or_default().as_ref()).to_string(),
-        description: mismatch.clone()
+        description: mismatch.clone(),
+        rule
       },
       Mismatch::MetadataMismatch { key, expected, actual, mismatch } => CommonMismatch {
         path: key.clone(),
         expected: expected.clone(),
         actual: actual.clone(),
-        description: mismatch.clone()
+        description: mismatch.clone(),
+        rule: None
       }
     }
   }
@@ -921,6 +1203,19 @@ pub enum Mismatch {
         /// actual value
         actual: String,
         /// description of the mismatch
+        mismatch: String,
+        /// Matching rule that was applied to produce the mismatch, if any
+        rule: Option<RuleTrace>
+    },
+    /// Response trailer mismatch
+    TrailerMismatch {
+        /// trailer key
+        key: String,
+        /// expected value
+        expected: String,
+        /// actual value
+        actual: String,
+        /// description of the mismatch
         mismatch: String
     },
     /// Mismatch in the content type of the body
@@ -945,7 +1240,9 @@ pub enum Mismatch {
       /// actual value
       actual: Option<Bytes>,
       /// description of the mismatch
-      mismatch: String
+      mismatch: String,
+      /// Matching rule that was applied to produce the mismatch, if any
+      rule: Option<RuleTrace>
     },
     /// Message metadata mismatch
     MetadataMismatch {
@@ -996,13 +1293,26 @@ impl Mismatch {
           "mismatch" : m
         })
       },
-      Mismatch::HeaderMismatch { key: k, expected: e, actual: a, mismatch: m } => {
-        json!({
+      Mismatch::HeaderMismatch { key: k, expected: e, actual: a, mismatch: m, rule } => {
+        let mut json = json!({
           "type" : "HeaderMismatch",
           "key" : k,
           "expected" : e,
           "actual" : a,
           "mismatch" : m
+        });
+        if let Some(rule) = rule {
+          json["rule"] = rule.to_json();
+        }
+        json
+      },
+      Mismatch::TrailerMismatch { key: k, expected: e, actual: a, mismatch: m } => {
+        json!({
+          "type" : "TrailerMismatch",
+          "key" : k,
+          "expected" : e,
+          "actual" : a,
+          "mismatch" : m
         })
       },
       Mismatch::BodyTypeMismatch {
@@ -1029,8 +1339,8 @@ impl Mismatch {
           }
         })
       },
-      Mismatch::BodyMismatch { path, expected, actual, mismatch } => {
-        json!({
+      Mismatch::BodyMismatch { path, expected, actual, mismatch, rule } => {
+        let mut json = json!({
           "type" : "BodyMismatch",
           "path" : path,
           "expected" : match expected {
@@ -1042,7 +1352,11 @@ impl Mismatch {
             None => serde_json::Value::Null
           },
           "mismatch" : mismatch
-        })
+        });
+        if let Some(rule) = rule {
+          json["rule"] = rule.to_json();
+        }
+        json
       }
       Mismatch::MetadataMismatch { key, expected, actual, mismatch } => {
         json!({
@@ -1056,6 +1370,15 @@ impl Mismatch {
     }
   }
 
+    /// Returns the matching rule that was applied to produce the mismatch, if known
+    pub fn rule(&self) -> Option<&RuleTrace> {
+      match self {
+        Mismatch::BodyMismatch { rule, .. } => rule.as_ref(),
+        Mismatch::HeaderMismatch { rule, .. } => rule.as_ref(),
+        _ => None
+      }
+    }
+
     /// Returns the type of the mismatch as a string
     pub fn mismatch_type(&self) -> &str {
       match *self {
@@ -1064,6 +1387,7 @@ impl Mismatch {
         Mismatch::StatusMismatch { .. } => "StatusMismatch",
         Mismatch::QueryMismatch { .. } => "QueryMismatch",
         Mismatch::HeaderMismatch { .. } => "HeaderMismatch",
+        Mismatch::TrailerMismatch { .. } => "TrailerMismatch",
         Mismatch::BodyTypeMismatch { .. } => "BodyTypeMismatch",
         Mismatch::BodyMismatch { .. } => "BodyMismatch",
         Mismatch::MetadataMismatch { .. } => "MetadataMismatch"
@@ -1078,6 +1402,7 @@ impl Mismatch {
         Mismatch::StatusMismatch { expected: ref e, .. } => format!("has status code {}", e),
         Mismatch::QueryMismatch { ref parameter, expected: ref e, .. } => format!("includes parameter '{}' with value '{}'", parameter, e),
         Mismatch::HeaderMismatch { ref key, expected: ref e, .. } => format!("includes header '{}' with value '{}'", key, e),
+        Mismatch::TrailerMismatch { ref key, expected: ref e, .. } => format!("includes trailer '{}' with value '{}'", key, e),
         Mismatch::BodyTypeMismatch { .. } => "has a matching body".to_string(),
         Mismatch::BodyMismatch { .. } => "has a matching body".to_string(),
         Mismatch::MetadataMismatch { .. } => "has matching metadata".to_string()
@@ -1092,8 +1417,11 @@ impl Mismatch {
         Mismatch::StatusMismatch { mismatch, .. } => mismatch.clone(),
         Mismatch::QueryMismatch { mismatch, .. } => mismatch.clone(),
         Mismatch::HeaderMismatch { mismatch, .. } => mismatch.clone(),
+        Mismatch::TrailerMismatch { mismatch, .. } => mismatch.clone(),
         Mismatch::BodyTypeMismatch {  expected: e, actual: a, .. } =>
           format!("Expected a body of '{}' but the actual content type was '{}'", e, a),
+        Mismatch::BodyMismatch { path, mismatch, rule: Some(rule), .. } if rule.cascaded =>
+          format!("{} -> {} ({})", path, mismatch, rule),
         Mismatch::BodyMismatch { path, mismatch, .. } => format!("{} -> {}", path, mismatch),
         Mismatch::MetadataMismatch { mismatch, .. } => mismatch.clone()
       }
@@ -1104,13 +1432,22 @@ impl Mismatch {
       match self {
         Mismatch::MethodMismatch { expected: e, actual: a } => format!("expected {} but was {}", Red.paint(e.clone()), Green.paint(a.clone())),
         Mismatch::PathMismatch { expected: e, actual: a, .. } => format!("expected '{}' but was '{}'", Red.paint(e.clone()), Green.paint(a.clone())),
-        Mismatch::StatusMismatch { expected: e, actual: a, .. } => format!("expected {} but was {}", Red.paint(e.to_string()), Green.paint(a.to_string())),
+        Mismatch::StatusMismatch { expected: e, actual: a, mismatch } => if mismatch.is_empty() || mismatch.starts_with("expected ") {
+          format!("expected {} but was {}", Red.paint(e.to_string()), Green.paint(a.to_string()))
+        } else {
+          // The status was matched with a status code matching rule, so show the statuses it allows
+          mismatch.replacen(&a.to_string(), &Green.paint(a.to_string()).to_string(), 1)
+        },
         Mismatch::QueryMismatch { expected: e, actual: a, parameter: p, .. } => format!("Expected '{}' but received '{}' for query parameter '{}'",
           Red.paint(e.to_string()), Green.paint(a.to_string()), Style::new().bold().paint(p.clone())),
         Mismatch::HeaderMismatch { expected: e, actual: a, key: k, .. } => format!("Expected header '{}' to have value '{}' but was '{}'",
           Style::new().bold().paint(k.clone()), Red.paint(e.to_string()), Green.paint(a.to_string())),
+        Mismatch::TrailerMismatch { expected: e, actual: a, key: k, .. } => format!("Expected trailer '{}' to have value '{}' but was '{}'",
+          Style::new().bold().paint(k.clone()), Red.paint(e.to_string()), Green.paint(a.to_string())),
         Mismatch::BodyTypeMismatch {  expected: e, actual: a, .. } =>
           format!("expected a body of '{}' but the actual content type was '{}'", Red.paint(e.clone()), Green.paint(a.clone())),
+        Mismatch::BodyMismatch { path, mismatch, rule: Some(rule), .. } if rule.cascaded =>
+          format!("{} -> {} ({})", Style::new().bold().paint(path.clone()), mismatch, rule),
         Mismatch::BodyMismatch { path, mismatch, .. } => format!("{} -> {}", Style::new().bold().paint(path.clone()), mismatch),
         Mismatch::MetadataMismatch { expected: e, actual: a, key: k, .. } => format!("Expected message metadata '{}' to have value '{}' but was '{}'",
           Style::new().bold().paint(k.clone()), Red.paint(e.to_string()), Green.paint(a.to_string()))
@@ -1145,6 +1482,10 @@ impl PartialEq for Mismatch {
         Mismatch::HeaderMismatch { key: p2, expected: e2, actual: a2, .. }) => {
         p1 == p2 && e1 == e2 && a1 == a2
       },
+      (Mismatch::TrailerMismatch { key: p1, expected: e1, actual: a1, .. },
+        Mismatch::TrailerMismatch { key: p2, expected: e2, actual: a2, .. }) => {
+        p1 == p2 && e1 == e2 && a1 == a2
+      },
       (Mismatch::BodyMismatch { path: p1, expected: e1, actual: a1, .. },
         Mismatch::BodyMismatch { path: p2, expected: e2, actual: a2, .. }) => {
         p1 == p2 && e1 == e2 && a1 == a2
@@ -1334,6 +1675,42 @@ pub enum DiffConfig {
     NoUnexpectedKeys
 }
 
+/// Options for how the text of XML documents is compared. Attributes are always compared
+/// regardless of their order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct XmlMatchingOptions {
+  /// If leading and trailing whitespace is removed from the text nodes before they are compared.
+  /// Defaults to true.
+  pub trim_text: bool,
+  /// If text nodes that only contain whitespace (i.e. the formatting between elements) are
+  /// ignored. Defaults to true.
+  pub strip_whitespace_text: bool
+}
+
+impl Default for XmlMatchingOptions {
+  fn default() -> Self {
+    XmlMatchingOptions {
+      trim_text: true,
+      strip_whitespace_text: true
+    }
+  }
+}
+
+/// Options for how arrays are compared.
+///
+/// By default (as per the Pact specification), when an array has a min type matcher (i.e. eachLike)
+/// and the actual array has more elements than the expected one, the additional elements must
+/// match the first expected element (see the `array with type matcher` and
+/// `objects in array type matching` spec cases). Without an array matcher, any additional
+/// elements result in a mismatch (see the `objects in array second matches` spec case).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ArrayMatchingOptions {
+  /// If additional elements at the end of the actual array are allowed and ignored when a min type
+  /// matcher is defined for the array. This can also be enabled for a single array with the
+  /// `allowUnexpectedElements` attribute on the min type matcher. Defaults to false.
+  pub allow_unexpected_elements: bool
+}
+
 /// Matches the actual text body to the expected one.
 pub fn match_text(expected: &Option<Bytes>, actual: &Option<Bytes>, context: &dyn MatchingContext) -> Result<(), Vec<Mismatch>> {
   let path = DocPath::root();
@@ -1347,7 +1724,8 @@ pub fn match_text(expected: &Option<Bytes>, actual: &Option<Bytes>, context: &dy
           path: "$".to_string(),
           expected: expected.clone(),
           actual: actual.clone(),
-          mismatch: format!("Could not parse expected value as UTF-8 text: {}", err)
+          mismatch: format!("Could not parse expected value as UTF-8 text: {}", err),
+          rule: None
         });
         ""
       }
@@ -1359,7 +1737,8 @@ pub fn match_text(expected: &Option<Bytes>, actual: &Option<Bytes>, context: &dy
           path: "$".to_string(),
           expected: expected.clone(),
           actual: actual.clone(),
-          mismatch: format!("Could not parse actual value as UTF-8 text: {}", err)
+          mismatch: format!("Could not parse actual value as UTF-8 text: {}", err),
+          rule: None
         });
         ""
       }
@@ -1370,7 +1749,8 @@ pub fn match_text(expected: &Option<Bytes>, actual: &Option<Bytes>, context: &dy
           path: "$".to_string(),
           expected: expected.clone(),
           actual: actual.clone(),
-          mismatch: message.clone()
+          mismatch: message.clone(),
+          rule: context.rule_trace(&path)
         })
       }
     };
@@ -1390,7 +1770,8 @@ pub fn match_text(expected: &Option<Bytes>, actual: &Option<Bytes>, context: &dy
         path: "$".to_string(),
         expected: Some(expected.clone()),
         actual: Some(actual.clone()),
-        mismatch
+        mismatch,
+        rule: None
       }
     ])
   } else {
@@ -1465,6 +1846,7 @@ fn group_by<I, F, K>(items: I, f: F) -> HashMap<K, Vec<I::Item>>
   m
 }
 
+#[instrument(level = "trace", skip_all, fields(%content_type))]
 pub(crate) async fn compare_bodies(
   content_type: &ContentType,
   expected: &(dyn HttpPart + Send + Sync),
@@ -1475,7 +1857,9 @@ pub(crate) async fn compare_bodies(
 
   #[cfg(feature = "plugins")]
   {
-    match find_content_matcher(content_type) {
+    let content_matcher = find_content_matcher(content_type)
+      .or_else(|| find_content_matcher(&content_type.canonical_type()));
+    match content_matcher {
       Some(matcher) => {
         debug!("Using content matcher {} for content type '{}'", matcher.catalogue_entry_key(), content_type);
         if matcher.is_core() {
@@ -1512,11 +1896,16 @@ pub(crate) async fn compare_bodies(
             // TODO: group the mismatches by key
             for (_key, list) in map {
               for mismatch in list {
+                let rule = DocPath::new(&mismatch.path).ok()
+                  .filter(|path| context.matcher_is_defined(path))
+                  .and_then(|path| context.rule_trace(&path))
+                  .map(|rule| rule.with_origin(RuleOrigin::Plugin(matcher.plugin_name())));
                 mismatches.push(Mismatch::BodyMismatch {
                   path: mismatch.path.clone(),
                   expected: Some(Bytes::from(mismatch.expected)),
                   actual: Some(Bytes::from(mismatch.actual)),
-                  mismatch: mismatch.mismatch.clone()
+                  mismatch: mismatch.mismatch.clone(),
+                  rule
                 });
               }
             }
@@ -1563,8 +1952,9 @@ fn compare_bodies_core(
       debug!("No body matcher defined for content type '{}', checking for a content type matcher", content_type);
       let path = DocPath::root();
       if context.matcher_is_defined(&path) && context.select_best_matcher(&path).rules
-        .iter().any(|rule| if let MatchingRule::ContentType(_) = rule { true } else { false }) {
-        debug!("Found a content type matcher");
+        .iter().any(|rule| matches!(rule, MatchingRule::ContentType(_) | MatchingRule::MinSize(_) |
+          MatchingRule::MaxSize(_) | MatchingRule::BytesEqual(_, _))) {
+        debug!("Found a content type or binary matcher");
         if let Err(m) = binary_utils::match_octet_stream(expected, actual, context) {
           mismatches.extend_from_slice(&*m);
         }
@@ -1588,16 +1978,20 @@ async fn match_body_content(
   let expected_body = expected.body();
   let actual_body = actual.body();
   match (expected_body, actual_body) {
-    (&OptionalBody::Missing, _) => BodyMatchResult::Ok,
+    (&OptionalBody::Missing, _) => if context.matchers().is_not_empty() {
+      match_body_expectations(actual, context)
+    } else {
+      BodyMatchResult::Ok
+    },
     (&OptionalBody::Null, &OptionalBody::Present(ref b, _, _)) => {
       BodyMatchResult::BodyMismatches(hashmap!{ "$".into() => vec![Mismatch::BodyMismatch { expected: None, actual: Some(b.clone()),
         mismatch: format!("Expected empty body but received {}", actual_body),
-        path: s!("/")}]})
+        path: s!("/"), rule: None}]})
     },
     (&OptionalBody::Empty, &OptionalBody::Present(ref b, _, _)) => {
       BodyMatchResult::BodyMismatches(hashmap!{ "$".into() => vec![Mismatch::BodyMismatch { expected: None, actual: Some(b.clone()),
         mismatch: format!("Expected empty body but received {}", actual_body),
-        path: s!("/")}]})
+        path: s!("/"), rule: None}]})
     },
     (&OptionalBody::Null, _) => BodyMatchResult::Ok,
     (&OptionalBody::Empty, _) => BodyMatchResult::Ok,
@@ -1606,26 +2000,50 @@ async fn match_body_content(
         expected: e.value(),
         actual: None,
         mismatch: format!("Expected body {} but was missing", e),
-        path: s!("/")}]})
+        path: s!("/"), rule: None}]})
     },
     (e, &OptionalBody::Empty) => {
       BodyMatchResult::BodyMismatches(hashmap!{ "$".into() => vec![Mismatch::BodyMismatch {
         expected: e.value(),
         actual: None,
         mismatch: format!("Expected body {} but was empty", e),
-        path: s!("/")}]})
+        path: s!("/"), rule: None}]})
     },
     (_, _) => compare_bodies(content_type, expected, actual, context).await
   }
 }
 
+/// Matches the actual body against the body matching rules, for expected bodies that are
+/// described by a set of expectations instead of an example body. Only JSON bodies are supported.
+fn match_body_expectations(
+  actual: &(dyn HttpPart + Send + Sync),
+  context: &(dyn MatchingContext + Send + Sync)
+) -> BodyMatchResult {
+  debug!("No expected body, matching the actual body against the body expectations");
+  match json::match_json_expectations(actual, context) {
+    Ok(_) => BodyMatchResult::Ok,
+    Err(mismatches) => BodyMatchResult::BodyMismatches(group_by(mismatches, |m| match m {
+      Mismatch::BodyMismatch { path: m, ..} => m.to_string(),
+      _ => String::default()
+    }))
+  }
+}
+
 /// Matches the actual body to the expected one. This takes into account the content type of each.
+#[instrument(level = "debug", skip_all)]
 pub async fn match_body(
   expected: &(dyn HttpPart + Send + Sync),
   actual: &(dyn HttpPart + Send + Sync),
   context: &(dyn MatchingContext + Send + Sync),
   header_context: &(dyn MatchingContext + Send + Sync)
 ) -> BodyMatchResult {
+  let (expected_part, actual_part) = match transform_bodies(expected, actual) {
+    Ok(parts) => parts,
+    Err(result) => return result
+  };
+  let expected = expected_part.as_ref().map(|part| part as &(dyn HttpPart + Send + Sync)).unwrap_or(expected);
+  let actual = actual_part.as_ref().map(|part| part as &(dyn HttpPart + Send + Sync)).unwrap_or(actual);
+
   let expected_content_type = expected.content_type().unwrap_or_default();
   let actual_content_type = actual.content_type().unwrap_or_default();
   debug!("expected content type = '{}', actual content type = '{}'", expected_content_type,
@@ -1635,6 +2053,8 @@ pub async fn match_body(
   if expected_content_type.is_unknown() || actual_content_type.is_unknown() ||
     expected_content_type.is_equivalent_to(&actual_content_type) ||
     expected_content_type.is_equivalent_to(&actual_content_type.base_type()) ||
+    (expected_content_type.is_multipart() &&
+      expected_content_type.without_boundary().is_equivalent_to(&actual_content_type.without_boundary())) ||
     (!content_type_matcher.is_empty() &&
       match_header_value("Content-Type", 0, expected_content_type.to_string().as_str(),
                          actual_content_type.to_string().as_str(), header_context, true
@@ -1654,8 +2074,99 @@ pub async fn match_body(
   }
 }
 
+/// Matches the actual body to the expected one using the content matcher for the given content
+/// type, regardless of the content types of the bodies.
+pub async fn match_body_with_content_type(
+  content_type: &ContentType,
+  expected: &(dyn HttpPart + Send + Sync),
+  actual: &(dyn HttpPart + Send + Sync),
+  context: &(dyn MatchingContext + Send + Sync)
+) -> BodyMatchResult {
+  debug!("Matching body using the content matcher for '{}'", content_type);
+  let (expected_part, actual_part) = match transform_bodies(expected, actual) {
+    Ok(parts) => parts,
+    Err(result) => return result
+  };
+  let expected = expected_part.as_ref().map(|part| part as &(dyn HttpPart + Send + Sync)).unwrap_or(expected);
+  let actual = actual_part.as_ref().map(|part| part as &(dyn HttpPart + Send + Sync)).unwrap_or(actual);
+  match_body_content(content_type, expected, actual, context).await
+}
+
+/// Matches the actual body against the expected one using the content matcher for the content
+/// type and the given body matching rules. This does not require a request, response or
+/// interaction, so can be used to validate payloads against fragments of a contract. Unexpected
+/// keys in the actual body are allowed, as they are for response bodies. Returns all the
+/// mismatches that were found.
+pub async fn match_body_with_rules(
+  content_type: &ContentType,
+  expected: Bytes,
+  actual: Bytes,
+  rules: &MatchingRuleCategory
+) -> Vec<Mismatch> {
+  let context = CoreMatchingContext::new(DiffConfig::AllowUnexpectedKeys, rules, &hashmap!{});
+  let body_part = |body: Bytes| HttpRequest {
+    body: if body.is_empty() {
+      OptionalBody::Empty
+    } else {
+      OptionalBody::Present(body, Some(content_type.clone()), None)
+    },
+    .. HttpRequest::default()
+  };
+  let expected = body_part(expected);
+  let actual = body_part(actual);
+  match_body_content(content_type, &expected, &actual, &context).await.mismatches()
+}
+
+/// Applies the body transformers for the content encodings of the expected and actual parts.
+/// Returns a body mismatch if either body could not be transformed.
+fn transform_bodies(
+  expected: &(dyn HttpPart + Send + Sync),
+  actual: &(dyn HttpPart + Send + Sync)
+) -> Result<(Option<transformers::TransformedPart>, Option<transformers::TransformedPart>), BodyMatchResult> {
+  let expected_part = transformers::transform_http_part(expected)
+    .map_err(|err| body_transform_mismatch("expected", err, expected.body(), actual.body()))?;
+  let actual_part = transformers::transform_http_part(actual)
+    .map_err(|err| body_transform_mismatch("actual", err, expected.body(), actual.body()))?;
+  Ok((expected_part, actual_part))
+}
+
+fn body_transform_mismatch(
+  label: &str,
+  err: anyhow::Error,
+  expected: &OptionalBody,
+  actual: &OptionalBody
+) -> BodyMatchResult {
+  BodyMatchResult::BodyMismatches(hashmap!{ "$".into() => vec![Mismatch::BodyMismatch {
+    expected: expected.value(),
+    actual: actual.value(),
+    mismatch: format!("Could not decode the {} body - {}", label, err),
+    path: s!("/"), rule: None}]})
+}
+
+/// Returns the content type configured with the `contentMatcher` entry of the V4 interaction
+/// configuration. This is used to select the content matcher regardless of the content type
+/// of the bodies.
+pub fn content_matcher_override(interaction: &(dyn Interaction + Send + Sync + RefUnwindSafe)) -> Option<ContentType> {
+  interaction.as_v4()
+    .and_then(|i| i.config().get("contentMatcher").cloned())
+    .and_then(|value| match value {
+      Value::String(ct) => match ContentType::parse(ct.as_str()) {
+        Ok(ct) => Some(ct),
+        Err(err) => {
+          warn!("Ignoring invalid content matcher '{}' configured for the interaction - {}", ct, err);
+          None
+        }
+      },
+      _ => {
+        warn!("Ignoring content matcher configured for the interaction as it is not a string - {}", value);
+        None
+      }
+    })
+}
+
 /// Matches the expected and actual requests
 #[allow(unused_variables)]
+#[instrument(level = "debug", skip_all, fields(interaction.id = ?interaction.id(), interaction.description = %interaction.description()))]
 pub async fn match_request<'a>(
   expected: HttpRequest,
   actual: HttpRequest,
@@ -1692,7 +2203,10 @@ pub async fn match_request<'a>(
   let result = RequestMatchResult {
     method: match_method(&expected.method, &actual.method).err(),
     path: match_path(&expected.path, &actual.path, &path_context).err(),
-    body: match_body(&expected, &actual, &body_context, &header_context).await,
+    body: match content_matcher_override(interaction.as_ref()) {
+      Some(content_type) => match_body_with_content_type(&content_type, &expected, &actual, &body_context).await,
+      None => match_body(&expected, &actual, &body_context, &header_context).await
+    },
     query: match_query(expected.query, actual.query, &query_context),
     headers: match_headers(expected.headers, actual.headers, &header_context)
   };
@@ -1729,6 +2243,7 @@ pub fn match_status(expected: u16, actual: u16, context: &dyn MatchingContext) -
 
 /// Matches the actual and expected responses.
 #[allow(unused_variables)]
+#[instrument(level = "debug", skip_all, fields(interaction.id = ?interaction.id(), interaction.description = %interaction.description()))]
 pub async fn match_response<'a>(
   expected: HttpResponse,
   actual: HttpResponse,
@@ -1758,8 +2273,11 @@ pub async fn match_response<'a>(
     )
   );
 
-  mismatches.extend_from_slice(match_body(&expected, &actual, &body_context, &header_context).await
-    .mismatches().as_slice());
+  let body_result = match content_matcher_override(interaction.as_ref()) {
+    Some(content_type) => match_body_with_content_type(&content_type, &expected, &actual, &body_context).await,
+    None => match_body(&expected, &actual, &body_context, &header_context).await
+  };
+  mismatches.extend_from_slice(body_result.mismatches().as_slice());
   if let Err(m) = match_status(expected.status, actual.status, &status_context) {
     mismatches.extend_from_slice(&m);
   }
@@ -1768,6 +2286,18 @@ pub async fn match_response<'a>(
   for values in result.values() {
     mismatches.extend_from_slice(values.as_slice());
   }
+  if expected.trailers.is_some() {
+    let trailer_context = HeaderMatchingContext::new(
+      &CoreMatchingContext::new(DiffConfig::NoUnexpectedKeys,
+        &expected.matching_rules.rules_for_category("trailer").unwrap_or_default(),
+        &plugin_data
+      )
+    );
+    let result = match_trailers(expected.trailers, actual.trailers, &trailer_context);
+    for values in result.values() {
+      mismatches.extend_from_slice(values.as_slice());
+    }
+  }
 
     trace!(?mismatches, "match response");
 
@@ -1799,10 +2329,22 @@ pub async fn match_message_contents(
   actual: &MessageContents,
   context: &(dyn MatchingContext + Send + Sync)
 ) -> Result<(), Vec<Mismatch>> {
+  let expected_contents = transformers::transform_message_contents(expected)
+    .map_err(|err| message_transform_mismatch("expected", err, expected, actual))?;
+  let actual_contents = transformers::transform_message_contents(actual)
+    .map_err(|err| message_transform_mismatch("actual", err, expected, actual))?;
+  let expected = expected_contents.as_ref().unwrap_or(expected);
+  let actual = actual_contents.as_ref().unwrap_or(actual);
+
   let expected_content_type = expected.message_content_type().unwrap_or_default();
   let actual_content_type = actual.message_content_type().unwrap_or_default();
   debug!("expected content type = '{}', actual content type = '{}'", expected_content_type,
          actual_content_type);
+  #[cfg(feature = "avro")]
+  if let Some(schema) = expected.metadata.get(avro::AVRO_SCHEMA_KEY) {
+    debug!("Found an Avro schema in the message metadata, matching the contents as Avro");
+    return avro::match_avro(schema, expected, actual, context);
+  }
   if expected_content_type.is_equivalent_to(&actual_content_type) {
     let result = match_body_content(&expected_content_type, expected, actual, context).await;
     match result {
@@ -1834,11 +2376,26 @@ pub async fn match_message_contents(
   }
 }
 
+fn message_transform_mismatch(
+  label: &str,
+  err: anyhow::Error,
+  expected: &MessageContents,
+  actual: &MessageContents
+) -> Vec<Mismatch> {
+  vec![ Mismatch::BodyMismatch {
+    expected: expected.contents.value(),
+    actual: actual.contents.value(),
+    mismatch: format!("Could not decode the {} message contents - {}", label, err),
+    path: s!("/"),
+    rule: None
+  } ]
+}
+
 /// Matches the actual message metadata to the expected one.
 pub fn match_message_metadata(
   expected: &MessageContents,
   actual: &MessageContents,
-  context: &dyn MatchingContext
+  context: &(dyn MatchingContext + Send + Sync)
 ) -> HashMap<String, Vec<Mismatch>> {
   debug!("Matching message metadata");
   let mut result = hashmap!{};
@@ -1848,6 +2405,10 @@ pub fn match_message_metadata(
 
   if !expected_metadata.is_empty() || context.config() == DiffConfig::NoUnexpectedKeys {
     for (key, value) in expected_metadata {
+      #[cfg(feature = "avro")]
+      if key == avro::AVRO_SCHEMA_KEY {
+        continue;
+      }
       match actual_metadata.get(key) {
         Some(actual_value) => {
           result.insert(key.clone(), match_metadata_value(key, value,
@@ -1870,15 +2431,23 @@ fn match_metadata_value(
   key: &str,
   expected: &Value,
   actual: &Value,
-  context: &dyn MatchingContext
+  context: &(dyn MatchingContext + Send + Sync)
 ) -> Result<(), Vec<Mismatch>> {
   debug!("Comparing metadata values for key '{}'", key);
-  let path = DocPath::root().join(key);
+  let mut path = DocPath::root();
+  path.push_field(key);
   let matcher_result = if context.matcher_is_defined(&path) {
     match_values(&path, &context.select_best_matcher(&path), expected, actual)
+  } else if (expected.is_object() || expected.is_array()) && context.matchers().rules.keys()
+    .any(|rule_path| rule_path.len() > path.len() && rule_path.tokens().starts_with(path.tokens())) {
+    debug!("Comparing structured metadata value for key '{}' using the matching rules for its attributes", key);
+    compare_json(&path, expected, actual, context)
+      .map_err(|mismatches| mismatches.iter()
+        .map(|mismatch| format!("{} -> {}", mismatch.path, mismatch.description))
+        .collect())
   } else if key.to_ascii_lowercase() == "contenttype" || key.to_ascii_lowercase() == "content-type" {
     debug!("Comparing message context type '{}' => '{}'", expected, actual);
-    headers::match_parameter_header(expected.as_str().unwrap_or_default(), actual.as_str().unwrap_or_default(),
+    headers::match_content_type_header(expected.as_str().unwrap_or_default(), actual.as_str().unwrap_or_default(),
       key, "metadata", 0, true)
   } else {
     expected.matches_with(actual, &MatchingRule::Equality, false).map_err(|err| vec![err.to_string()])
@@ -1920,7 +2489,9 @@ pub async fn match_message<'a>(
         matchers: matching_rules.rules_for_category("content").unwrap_or_default(),
         config: DiffConfig::AllowUnexpectedKeys,
         matching_spec: PactSpecification::V4,
-        plugin_configuration: plugin_data.clone()
+        plugin_configuration: plugin_data.clone(),
+        xml_options: Default::default(),
+        array_options: Default::default()
       }
     } else {
       CoreMatchingContext::new(DiffConfig::AllowUnexpectedKeys,
@@ -1978,7 +2549,9 @@ pub async fn match_sync_message_request<'a>(
     matchers: matching_rules.rules_for_category("content").unwrap_or_default(),
     config: DiffConfig::AllowUnexpectedKeys,
     matching_spec: PactSpecification::V4,
-    plugin_configuration: plugin_data.clone()
+    plugin_configuration: plugin_data.clone(),
+    xml_options: Default::default(),
+    array_options: Default::default()
   };
 
   let metadata_context = CoreMatchingContext::new(DiffConfig::AllowUnexpectedKeys,
@@ -2037,7 +2610,9 @@ pub async fn match_sync_message_response<'a>(
         matchers: matching_rules.rules_for_category("content").unwrap_or_default(),
         config: DiffConfig::AllowUnexpectedKeys,
         matching_spec: PactSpecification::V4,
-        plugin_configuration: plugin_data.clone()
+        plugin_configuration: plugin_data.clone(),
+        xml_options: Default::default(),
+        array_options: Default::default()
       };
 
       let metadata_context = CoreMatchingContext::new(DiffConfig::AllowUnexpectedKeys,
@@ -2057,14 +2632,27 @@ pub async fn match_sync_message_response<'a>(
 /// Generates the request by applying any defined generators
 // TODO: Need to pass in any plugin data
 pub async fn generate_request(request: &HttpRequest, mode: &GeneratorTestMode, context: &HashMap<&str, Value>) -> HttpRequest {
+  generate_request_with_report(request, mode, context).await.0
+}
+
+/// Generates the request by applying any defined generators, and returns it along with a report
+/// of the values that the generators produced
+// TODO: Need to pass in any plugin data
+pub async fn generate_request_with_report(
+  request: &HttpRequest,
+  mode: &GeneratorTestMode,
+  context: &HashMap<&str, Value>
+) -> (HttpRequest, GeneratorReport) {
   trace!(?request, ?mode, ?context, "generate_request");
   let mut request = request.clone();
+  let mut report = GeneratorReport::default();
 
   let generators = request.build_generators(&GeneratorCategory::PATH);
   if !generators.is_empty() {
     debug!("Applying path generator...");
-    apply_generators(mode, &generators, &mut |_, generator| {
-      if let Ok(v) = generator.generate_value(&request.path, context, &DefaultVariantMatcher.boxed()) {
+    apply_generators(mode, &generators, &mut |key, generator| {
+      if let Ok(v) = generator.generate_value(&request.path, &with_random_seed_path(context, "path"), &DefaultVariantMatcher.boxed()) {
+        report.add(GeneratorCategory::PATH, key, generator, Some(json!(v)));
         request.path = v;
       }
     });
@@ -2075,22 +2663,8 @@ pub async fn generate_request(request: &HttpRequest, mode: &GeneratorTestMode, c
     debug!("Applying header generators...");
     apply_generators(mode, &generators, &mut |key, generator| {
       if let Some(header) = key.first_field() {
-        if let Some(ref mut headers) = request.headers {
-          if headers.contains_key(header) {
-            if let Ok(v) = generator.generate_value(&headers.get(header).unwrap().clone(), context, &DefaultVariantMatcher.boxed()) {
-              headers.insert(header.to_string(), v);
-            }
-          } else {
-            if let Ok(v) = generator.generate_value(&"".to_string(), context, &DefaultVariantMatcher.boxed()) {
-              headers.insert(header.to_string(), vec![ v.to_string() ]);
-            }
-          }
-        } else {
-          if let Ok(v) = generator.generate_value(&"".to_string(), context, &DefaultVariantMatcher.boxed()) {
-            request.headers = Some(hashmap!{
-              header.to_string() => vec![ v.to_string() ]
-            })
-          }
+        if let Some(v) = generate_header_value(&mut request.headers, header, generator, context) {
+          report.add(GeneratorCategory::HEADER, key, generator, Some(json!(v)));
         }
       }
     });
@@ -2106,15 +2680,21 @@ pub async fn generate_request(request: &HttpRequest, mode: &GeneratorTestMode, c
             let mut generated = parameter.clone();
             for (index, val) in parameter.iter().enumerate() {
               let value = val.clone().unwrap_or_default();
-              if let Ok(v) = generator.generate_value(&value, context, &DefaultVariantMatcher.boxed()) {
+              let context = with_random_seed_path(context, &format!("query:{}:{}", param, index));
+              if let Ok(v) = generator.generate_value(&value, &context, &DefaultVariantMatcher.boxed()) {
                 generated[index] = Some(v);
               }
             }
+            report.add(GeneratorCategory::QUERY, key, generator, Some(json!(generated)));
             *parameter = generated;
-          } else if let Ok(v) = generator.generate_value(&"".to_string(), context, &DefaultVariantMatcher.boxed()) {
+          } else if let Ok(v) = generator.generate_value(&"".to_string(),
+            &with_random_seed_path(context, &format!("query:{}:0", param)), &DefaultVariantMatcher.boxed()) {
+            report.add(GeneratorCategory::QUERY, key, generator, Some(json!(v)));
             parameters.insert(param.to_string(), vec![ Some(v.to_string()) ]);
           }
-        } else if let Ok(v) = generator.generate_value(&"".to_string(), context, &DefaultVariantMatcher.boxed()) {
+        } else if let Ok(v) = generator.generate_value(&"".to_string(),
+          &with_random_seed_path(context, &format!("query:{}:0", param)), &DefaultVariantMatcher.boxed()) {
+          report.add(GeneratorCategory::QUERY, key, generator, Some(json!(v)));
           request.query = Some(hashmap!{
             param.to_string() => vec![ Some(v.to_string()) ]
           })
@@ -2128,25 +2708,66 @@ pub async fn generate_request(request: &HttpRequest, mode: &GeneratorTestMode, c
     debug!("Applying body generators...");
     match generators_process_body(mode, &request.body, request.content_type(),
                                   context, &generators, &DefaultVariantMatcher {}, &vec![], &hashmap!{}).await {
-      Ok(body) => request.body = body,
+      Ok(body) => {
+        report.add_body_values(mode, &generators, &body, request.content_type());
+        request.body = body
+      },
       Err(err) => error!("Failed to generate the body, will use the original: {}", err)
     }
   }
 
-  request
+  (request, report)
+}
+
+/// Applies the generator to the header values, returning the generated values. Headers are
+/// looked up case-insensitively, and the header is added if it does not exist.
+fn generate_header_value(
+  headers: &mut Option<HashMap<String, Vec<String>>>,
+  header: &str,
+  generator: &Generator,
+  context: &HashMap<&str, Value>
+) -> Option<Vec<String>> {
+  let (key, values) = headers.as_ref()
+    .and_then(|headers| headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(header)))
+    .map(|(k, v)| (k.clone(), v.clone()))
+    .unwrap_or_else(|| (header.to_string(), vec![]));
+  let context = with_random_seed_path(context, &format!("header:{}", key.to_lowercase()));
+  match generator.generate_value(&values, &context, &DefaultVariantMatcher.boxed()) {
+    Ok(v) => {
+      headers.get_or_insert_with(HashMap::new).insert(key, v.clone());
+      Some(v)
+    }
+    Err(err) => {
+      debug!("Failed to generate a value for header '{}' - {}", header, err);
+      None
+    }
+  }
 }
 
 /// Generates the response by applying any defined generators
 // TODO: Need to pass in any plugin data
 pub async fn generate_response(response: &HttpResponse, mode: &GeneratorTestMode, context: &HashMap<&str, Value>) -> HttpResponse {
+  generate_response_with_report(response, mode, context).await.0
+}
+
+/// Generates the response by applying any defined generators, and returns it along with a report
+/// of the values that the generators produced
+// TODO: Need to pass in any plugin data
+pub async fn generate_response_with_report(
+  response: &HttpResponse,
+  mode: &GeneratorTestMode,
+  context: &HashMap<&str, Value>
+) -> (HttpResponse, GeneratorReport) {
   trace!(?response, ?mode, ?context, "generate_response");
   let mut response = response.clone();
+  let mut report = GeneratorReport::default();
   let generators = response.build_generators(&GeneratorCategory::STATUS);
   if !generators.is_empty() {
     debug!("Applying status generator...");
-    apply_generators(mode, &generators, &mut |_, generator| {
-      if let Ok(v) = generator.generate_value(&response.status, context, &DefaultVariantMatcher.boxed()) {
+    apply_generators(mode, &generators, &mut |key, generator| {
+      if let Ok(v) = generator.generate_value(&response.status, &with_random_seed_path(context, "status"), &DefaultVariantMatcher.boxed()) {
         debug!("Generated value for status: {}", v);
+        report.add(GeneratorCategory::STATUS, key, generator, Some(json!(v)));
         response.status = v;
       }
     });
@@ -2156,22 +2777,8 @@ pub async fn generate_response(response: &HttpResponse, mode: &GeneratorTestMode
     debug!("Applying header generators...");
     apply_generators(mode, &generators, &mut |key, generator| {
       if let Some(header) = key.first_field() {
-        if let Some(ref mut headers) = response.headers {
-          if headers.contains_key(header) {
-            if let Ok(v) = generator.generate_value(&headers.get(header).unwrap().clone(), context, &DefaultVariantMatcher.boxed()) {
-              headers.insert(header.to_string(), v);
-            }
-          } else {
-            if let Ok(v) = generator.generate_value(&"".to_string(), context, &DefaultVariantMatcher.boxed()) {
-              headers.insert(header.to_string(), vec![ v.to_string() ]);
-            }
-          }
-        } else {
-          if let Ok(v) = generator.generate_value(&"".to_string(), context, &DefaultVariantMatcher.boxed()) {
-            response.headers = Some(hashmap!{
-              header.to_string() => vec![ v.to_string() ]
-            })
-          }
+        if let Some(v) = generate_header_value(&mut response.headers, header, generator, context) {
+          report.add(GeneratorCategory::HEADER, key, generator, Some(json!(v)));
         }
       }
     });
@@ -2181,11 +2788,14 @@ pub async fn generate_response(response: &HttpResponse, mode: &GeneratorTestMode
     debug!("Applying body generators...");
     match generators_process_body(mode, &response.body, response.content_type(),
       context, &generators, &DefaultVariantMatcher{}, &vec![], &hashmap!{}).await {
-      Ok(body) => response.body = body,
+      Ok(body) => {
+        report.add_body_values(mode, &generators, &body, response.content_type());
+        response.body = body
+      },
       Err(err) => error!("Failed to generate the body, will use the original: {}", err)
     }
   }
-  response
+  (response, report)
 }
 
 /// Matches the request part of the interaction
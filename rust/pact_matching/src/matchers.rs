@@ -1,5 +1,6 @@
 //! Matching rule implementations
 
+use std::collections::BTreeMap;
 use std::str::from_utf8;
 
 use anyhow::anyhow;
@@ -8,9 +9,9 @@ use bytes::Bytes;
 #[cfg(feature = "plugins")] use maplit::hashmap;
 use onig::Regex;
 use pact_models::HttpStatus;
-use pact_models::matchingrules::{MatchingRule, RuleList, RuleLogic};
+use pact_models::matchingrules::{EpochUnit, MatchingRule, MatchingRuleCategory, NumericRange, RuleList, RuleLogic};
 use pact_models::path_exp::DocPath;
-#[cfg(feature = "datetime")] use pact_models::time_utils::validate_datetime;
+#[cfg(feature = "datetime")] use pact_models::time_utils::{validate_datetime, validate_duration};
 #[cfg(feature = "plugins")]  use pact_plugin_driver::catalogue_manager::{
   CatalogueEntry,
   CatalogueEntryProviderType,
@@ -19,8 +20,9 @@ use pact_models::path_exp::DocPath;
 };
 use semver::Version;
 use tracing::{debug, instrument, trace};
+use url::Url;
 
-use crate::binary_utils::match_content_type;
+use crate::binary_utils::{match_bytes_at_offset, match_content_type, match_max_size, match_min_size};
 use crate::{MatchingContext, CommonMismatch};
 
 #[cfg(feature = "plugins")]
@@ -290,6 +292,18 @@ impl Matches<&str> for &str {
         }
       }
       MatchingRule::ContentType(content_type) => match_content_type(actual.as_bytes(), content_type),
+      MatchingRule::Duration => match_duration(actual),
+      MatchingRule::EpochTimestamp(unit, tolerance) => {
+        match actual.parse::<u64>() {
+          Ok(timestamp) => match_epoch_timestamp(timestamp, unit, tolerance),
+          Err(_) => Err(anyhow!("Expected '{}' (String) to be an epoch timestamp in {}", actual, unit))
+        }
+      }
+      MatchingRule::Url(rules) => match_url(self, actual, rules),
+      MatchingRule::NumericRange(range) => match actual.parse::<f64>() {
+        Ok(value) => match_numeric_range(value, range),
+        Err(_) => Err(anyhow!("Expected '{}' (String) to be a number {}", actual, range))
+      }
       _ => if !cascaded || matcher.can_cascade() {
         Err(anyhow!("Unable to match '{}' using {:?}", self, matcher))
       } else {
@@ -341,6 +355,8 @@ impl Matches<u64> for &str {
       MatchingRule::Number | MatchingRule::Integer => Ok(()),
       MatchingRule::Decimal => Err(anyhow!("Expected {} to match a decimal number", actual)),
       MatchingRule::StatusCode(status) => match_status_code(actual as u16, status),
+      MatchingRule::EpochTimestamp(unit, tolerance) => match_epoch_timestamp(actual, unit, tolerance),
+      MatchingRule::NumericRange(range) => match_numeric_range(actual as f64, range),
       _ => if !cascaded || matcher.can_cascade() {
         Err(anyhow!("String: Unable to match {} using {:?}", self, matcher))
       } else {
@@ -388,6 +404,8 @@ impl Matches<u64> for u64 {
       MatchingRule::Number | MatchingRule::Integer => Ok(()),
       MatchingRule::Decimal => Err(anyhow!("Expected {} to match a decimal number", actual)),
       MatchingRule::StatusCode(status) => match_status_code(actual as u16, status),
+      MatchingRule::EpochTimestamp(unit, tolerance) => match_epoch_timestamp(actual, unit, tolerance),
+      MatchingRule::NumericRange(range) => match_numeric_range(actual as f64, range),
       _ => if !cascaded || matcher.can_cascade() {
         Err(anyhow!("Unable to match {} using {:?}", self, matcher))
       } else {
@@ -429,6 +447,7 @@ impl Matches<f64> for u64 {
       },
       MatchingRule::Number | MatchingRule::Decimal => Ok(()),
       MatchingRule::Integer => Err(anyhow!("Expected {} to match an integer number", actual)),
+      MatchingRule::NumericRange(range) => match_numeric_range(actual, range),
       _ => if !cascaded || matcher.can_cascade() {
         Err(anyhow!("Unable to match {} using {:?}", self, matcher))
       } else {
@@ -475,6 +494,7 @@ impl Matches<f64> for f64 {
       },
       MatchingRule::Number | MatchingRule::Decimal => Ok(()),
       MatchingRule::Integer => Err(anyhow!("Expected {} to match an integer number", actual)),
+      MatchingRule::NumericRange(range) => match_numeric_range(actual, range),
       _ => if !cascaded || matcher.can_cascade() {
         Err(anyhow!("Unable to match {} using {:?}", self, matcher))
       } else {
@@ -518,6 +538,7 @@ impl Matches<u64> for f64 {
       },
       MatchingRule::Number | MatchingRule::Integer => Ok(()),
       MatchingRule::Decimal => Err(anyhow!("Expected {} to match a decimal number", actual)),
+      MatchingRule::NumericRange(range) => match_numeric_range(actual as f64, range),
       _ => if !cascaded || matcher.can_cascade() {
         Err(anyhow!("Unable to match '{}' using {:?}", self, matcher))
       } else {
@@ -591,6 +612,12 @@ impl Matches<i64> for &str {
       },
       MatchingRule::Number | MatchingRule::Integer => Ok(()),
       MatchingRule::Decimal => Err(anyhow!("Expected {} to match a decimal number", actual)),
+      MatchingRule::EpochTimestamp(unit, tolerance) => if actual < 0 {
+        Err(anyhow!("Expected {} to be an epoch timestamp in {}", actual, unit))
+      } else {
+        match_epoch_timestamp(actual as u64, unit, tolerance)
+      },
+      MatchingRule::NumericRange(range) => match_numeric_range(actual as f64, range),
       _ => if !cascaded || matcher.can_cascade() {
         Err(anyhow!("Unable to match {} using {:?}", self, matcher))
       } else {
@@ -637,6 +664,12 @@ impl Matches<i64> for i64 {
       },
       MatchingRule::Number | MatchingRule::Integer => Ok(()),
       MatchingRule::Decimal => Err(anyhow!("Expected {} to match a decimal number", actual)),
+      MatchingRule::EpochTimestamp(unit, tolerance) => if actual < 0 {
+        Err(anyhow!("Expected {} to be an epoch timestamp in {}", actual, unit))
+      } else {
+        match_epoch_timestamp(actual as u64, unit, tolerance)
+      },
+      MatchingRule::NumericRange(range) => match_numeric_range(actual as f64, range),
       _ => if !cascaded || matcher.can_cascade() {
         Err(anyhow!("Unable to match {} using {:?}", self, matcher))
       } else {
@@ -753,6 +786,9 @@ impl Matches<&Bytes> for Bytes {
         }
       },
       MatchingRule::ContentType(content_type) => match_content_type(actual, content_type),
+      MatchingRule::MinSize(min) => match_min_size(actual, *min),
+      MatchingRule::MaxSize(max) => match_max_size(actual, *max),
+      MatchingRule::BytesEqual(offset, bytes) => match_bytes_at_offset(actual, *offset, bytes),
       MatchingRule::NotEmpty => {
         if actual.is_empty() {
           Err(anyhow!("Expected [] (0 bytes) to not be empty"))
@@ -770,6 +806,7 @@ impl Matches<&Bytes> for Bytes {
 }
 
 /// Match the provided values using the path and matching rules
+#[instrument(level = "trace", skip_all, fields(%path, rule_logic = ?matching_rules.rule_logic, cascaded = matching_rules.cascaded))]
 pub fn match_values<E, A>(path: &DocPath, matching_rules: &RuleList, expected: E, actual: A) -> Result<(), Vec<String>>
   where E: Matches<A>, A: Clone {
   trace!("match_values: {} -> {}", std::any::type_name::<E>(), std::any::type_name::<A>());
@@ -777,7 +814,9 @@ pub fn match_values<E, A>(path: &DocPath, matching_rules: &RuleList, expected: E
     Err(vec![format!("No matcher found for path '{}'", path)])
   } else {
     let results = matching_rules.rules.iter().map(|rule| {
-      expected.matches_with(actual.clone(), rule, matching_rules.cascaded)
+      let result = match_rule(&expected, actual.clone(), rule, matching_rules.cascaded);
+      trace!(?rule, ok = result.is_ok(), "applied matching rule");
+      result
     }).collect::<Vec<anyhow::Result<()>>>();
     let result = match matching_rules.rule_logic {
       RuleLogic::And => {
@@ -802,20 +841,44 @@ pub fn match_values<E, A>(path: &DocPath, matching_rules: &RuleList, expected: E
   }
 }
 
+/// Applies a single matching rule to the values. The combining rules are applied by applying the
+/// rules they contain, with `AnyOf` combining the results with OR logic (as for `RuleLogic::Or`)
+/// and `Not` inverting the result. As the values are present, `Exists` always matches and
+/// `Absent` never does.
+pub fn match_rule<E, A>(expected: &E, actual: A, rule: &MatchingRule, cascaded: bool) -> anyhow::Result<()>
+  where E: Matches<A>, A: Clone {
+  match rule {
+    MatchingRule::AnyOf(rules) => {
+      let results = rules.iter()
+        .map(|rule| match_rule(expected, actual.clone(), rule, cascaded))
+        .collect::<Vec<anyhow::Result<()>>>();
+      if results.iter().any(|result| result.is_ok()) {
+        Ok(())
+      } else {
+        Err(anyhow!("Expected the value to match any of the rules: {}", results.iter()
+          .filter_map(|result| result.as_ref().err())
+          .map(|err| err.to_string())
+          .collect::<Vec<String>>()
+          .join(", ")))
+      }
+    }
+    MatchingRule::Not(rule) => match match_rule(expected, actual, rule, cascaded) {
+      Ok(_) => Err(anyhow!("Expected the value to not match the {} rule ({})", rule.name(), rule.to_json())),
+      Err(_) => Ok(())
+    }
+    MatchingRule::Exists => Ok(()),
+    MatchingRule::Absent => Err(anyhow!("Expected the value to be absent")),
+    _ => expected.matches_with(actual, rule, cascaded)
+  }
+}
+
 #[instrument(level = "trace")]
 fn match_status_code(status_code: u16, status: &HttpStatus) -> anyhow::Result<()> {
-  let matches = match status {
-    HttpStatus::Information => (100..=199).contains(&status_code),
-    HttpStatus::Success => (200..=299).contains(&status_code),
-    HttpStatus::Redirect => (300..=399).contains(&status_code),
-    HttpStatus::ClientError => (400..=499).contains(&status_code),
-    HttpStatus::ServerError => (500..=599).contains(&status_code),
-    HttpStatus::StatusCodes(status_codes) => status_codes.contains(&status_code),
-    HttpStatus::NonError => status_code < 400,
-    HttpStatus::Error => status_code >= 400
-  };
+  let matches = status.contains(status_code);
   let result = if matches {
     Ok(())
+  } else if let HttpStatus::StatusCodes(_) = status {
+    Err(anyhow!("Expected status code {} to be one of {}", status_code, status))
   } else {
     Err(anyhow!("Expected status code {} to be a {}", status_code, status))
   };
@@ -823,6 +886,129 @@ fn match_status_code(status_code: u16, status: &HttpStatus) -> anyhow::Result<()
   result
 }
 
+#[allow(unused_variables)]
+pub(crate) fn match_duration(actual: &str) -> anyhow::Result<()> {
+  #[cfg(feature = "datetime")]
+  {
+    validate_duration(actual).map_err(|err| anyhow!(err))
+  }
+  #[cfg(not(feature = "datetime"))]
+  {
+    Err(anyhow!("Duration matchers require the datetime feature to be enabled"))
+  }
+}
+
+#[instrument(level = "trace")]
+pub(crate) fn match_epoch_timestamp(timestamp: u64, unit: &EpochUnit, tolerance: &Option<u64>) -> anyhow::Result<()> {
+  if let Some(tolerance) = tolerance {
+    let now = unit.now();
+    if now.abs_diff(timestamp) > *tolerance {
+      return Err(anyhow!("Expected epoch timestamp {} to be within {} {} of the current time ({})",
+        timestamp, tolerance, unit, now));
+    }
+  }
+  Ok(())
+}
+
+#[instrument(level = "trace")]
+pub(crate) fn match_numeric_range(value: f64, range: &NumericRange) -> anyhow::Result<()> {
+  if range.contains(value) {
+    Ok(())
+  } else {
+    Err(anyhow!("Expected {} to be a number {}", value, range))
+  }
+}
+
+/// Matches the actual value as an absolute URL. Each component of the URL (`scheme`, `host`,
+/// `port`, `path`, `query` and `fragment`) is matched using the rules defined for it, and must be
+/// equal to the expected component otherwise. Query parameters are matched by name using the rules
+/// for `$.query.<name>` (or `$.query` for all parameters).
+#[instrument(level = "trace")]
+pub(crate) fn match_url(expected: &str, actual: &str, rules: &MatchingRuleCategory) -> anyhow::Result<()> {
+  let expected_url = Url::parse(expected)
+    .map_err(|err| anyhow!("Expected value '{}' is not a valid URL - {}", expected, err))?;
+  let actual_url = Url::parse(actual)
+    .map_err(|err| anyhow!("Expected '{}' to be a valid URL - {}", actual, err))?;
+
+  let components = [
+    ("scheme", Some(expected_url.scheme().to_string()), Some(actual_url.scheme().to_string())),
+    ("host", expected_url.host_str().map(|h| h.to_string()), actual_url.host_str().map(|h| h.to_string())),
+    ("port", expected_url.port_or_known_default().map(|p| p.to_string()), actual_url.port_or_known_default().map(|p| p.to_string())),
+    ("path", Some(expected_url.path().to_string()), Some(actual_url.path().to_string())),
+    ("fragment", expected_url.fragment().map(|f| f.to_string()), actual_url.fragment().map(|f| f.to_string()))
+  ];
+  let mut mismatches = vec![];
+  for (component, expected_value, actual_value) in components {
+    let path = DocPath::root().join(component);
+    match (expected_value, actual_value) {
+      (Some(expected_value), Some(actual_value)) => if let Err(errors) = match_url_component(&path, &expected_value, &actual_value, rules) {
+        mismatches.extend(errors.iter().map(|err| format!("{}: {}", component, err)));
+      }
+      (Some(expected_value), None) => mismatches.push(format!("{}: Expected '{}' but was missing", component, expected_value)),
+      (None, Some(actual_value)) => if !rules.matcher_is_defined(&[ "$", component ]) {
+        mismatches.push(format!("{}: Expected no value but received '{}'", component, actual_value));
+      }
+      (None, None) => {}
+    }
+  }
+
+  let expected_query = url_query_parameters(&expected_url);
+  let actual_query = url_query_parameters(&actual_url);
+  for (name, expected_values) in &expected_query {
+    let path = DocPath::root().join("query").join(name);
+    match actual_query.get(name) {
+      Some(actual_values) => {
+        let has_rules = rules.matcher_is_defined(&[ "$", "query", name ]);
+        if !has_rules && expected_values.len() != actual_values.len() {
+          mismatches.push(format!("query: Expected parameter '{}' to have {} value(s) but received {}",
+            name, expected_values.len(), actual_values.len()));
+        }
+        for (index, actual_value) in actual_values.iter().enumerate() {
+          if let Some(expected_value) = expected_values.get(index).or_else(|| expected_values.last().filter(|_| has_rules)) {
+            if let Err(errors) = match_url_component(&path, expected_value, actual_value, rules) {
+              mismatches.extend(errors.iter().map(|err| format!("query '{}': {}", name, err)));
+            }
+          }
+        }
+      }
+      None => mismatches.push(format!("query: Expected parameter '{}' but was missing", name))
+    }
+  }
+  for name in actual_query.keys() {
+    if !expected_query.contains_key(name) {
+      mismatches.push(format!("query: Unexpected parameter '{}' received", name));
+    }
+  }
+
+  if mismatches.is_empty() {
+    Ok(())
+  } else {
+    Err(anyhow!("Expected URL '{}' to match '{}' - {}", actual, expected, mismatches.join(", ")))
+  }
+}
+
+fn match_url_component(
+  path: &DocPath,
+  expected: &str,
+  actual: &str,
+  rules: &MatchingRuleCategory
+) -> Result<(), Vec<String>> {
+  let path_vec = path.to_vec();
+  let path_slice = path_vec.iter().map(|p| p.as_str()).collect::<Vec<_>>();
+  if rules.matcher_is_defined(&path_slice) {
+    match_values(path, &rules.select_best_matcher(&path_slice), expected, actual)
+  } else {
+    expected.matches_with(actual, &MatchingRule::Equality, false).map_err(|err| vec![err.to_string()])
+  }
+}
+
+fn url_query_parameters(url: &Url) -> BTreeMap<String, Vec<String>> {
+  url.query_pairs().fold(BTreeMap::new(), |mut map, (name, value)| {
+    map.entry(name.to_string()).or_insert_with(Vec::new).push(value.to_string());
+    map
+  })
+}
+
 /// Basic matching implementation for string slices
 pub fn match_strings(
   path: &DocPath,
@@ -845,7 +1031,8 @@ pub fn match_strings(
         path: path.to_string(),
         expected: expected.to_string(),
         actual: actual.to_string(),
-        description: message.clone()
+        description: message.clone(),
+        rule: None
       }
     }).collect()
   })
@@ -856,6 +1043,7 @@ mod tests {
   use expectest::expect;
   use expectest::prelude::*;
   use pact_models::{matchingrules, matchingrules::RuleList, matchingrules_list};
+  use pact_models::matchingrules::NumericBound;
   use serde_json::json;
 
   use super::*;
@@ -1205,6 +1393,12 @@ mod tests {
     expect!(match_status_code(599, &HttpStatus::NonError)).to(be_err());
     expect!(match_status_code(555, &HttpStatus::Error)).to(be_ok());
     expect!(match_status_code(99, &HttpStatus::Error)).to(be_err());
+    expect!(match_status_code(204, &HttpStatus::Range(200, 204))).to(be_ok());
+    expect!(match_status_code(205, &HttpStatus::Range(200, 204))).to(be_err());
+    expect!(match_status_code(202, &HttpStatus::StatusCodes(vec![200, 204])).unwrap_err().to_string())
+      .to(be_equal_to("Expected status code 202 to be one of 200, 204".to_string()));
+    expect!(match_status_code(500, &HttpStatus::ClientError).unwrap_err().to_string())
+      .to(be_equal_to("Expected status code 500 to be a Client error (400–499)".to_string()));
   }
 
   #[test]
@@ -1232,6 +1426,141 @@ mod tests {
     expect!(json!("1.0.0").matches_with(&json!("1"), &matcher, false)).to(be_err());
   }
 
+  #[test]
+  #[cfg(feature = "datetime")]
+  fn duration_matcher_test() {
+    let matcher = MatchingRule::Duration;
+    expect!("PT5M30S".matches_with("PT1H", &matcher, false)).to(be_ok());
+    expect!("PT5M30S".matches_with("P1Y2M3DT4H5M6.5S", &matcher, false)).to(be_ok());
+    expect!("PT5M30S".matches_with("5 minutes", &matcher, false)).to(be_err());
+    expect!(json!("PT5M30S").matches_with(&json!("P2W"), &matcher, false)).to(be_ok());
+    expect!(json!("PT5M30S").matches_with(&json!("PT"), &matcher, false)).to(be_err());
+    expect!(json!("PT5M30S").matches_with(&json!(330), &matcher, false)).to(be_err());
+  }
+
+  #[test]
+  fn numeric_range_matcher_test() {
+    let matcher = MatchingRule::NumericRange(NumericRange::new(Some(NumericBound::Inclusive(0.0)),
+      Some(NumericBound::Exclusive(10.0))).unwrap());
+    expect!("5".matches_with("0", &matcher, false)).to(be_ok());
+    expect!("5".matches_with("9.5", &matcher, false)).to(be_ok());
+    expect!("5".matches_with("10", &matcher, false)).to(be_err());
+    expect!("5".matches_with("five", &matcher, false)).to(be_err());
+    expect!(5_u64.matches_with(0_u64, &matcher, false)).to(be_ok());
+    expect!(5_u64.matches_with(10_u64, &matcher, false)).to(be_err());
+    expect!(5_i64.matches_with(-1_i64, &matcher, false)).to(be_err());
+    expect!(5.5_f64.matches_with(9.99_f64, &matcher, false)).to(be_ok());
+    expect!(json!(5).matches_with(&json!(3), &matcher, false)).to(be_ok());
+    expect!(json!(5).matches_with(&json!(-0.5), &matcher, false)).to(be_err());
+    expect!(json!(5).matches_with(&json!("7.5"), &matcher, false)).to(be_ok());
+    expect!(json!(5).matches_with(&json!(true), &matcher, false)).to(be_err());
+
+    let matcher = MatchingRule::NumericRange(NumericRange::new(Some(NumericBound::Exclusive(100.0)), None).unwrap());
+    expect!(json!(200).matches_with(&json!(100), &matcher, false)).to(be_err());
+    expect!(json!(200).matches_with(&json!(100.01), &matcher, false)).to(be_ok());
+  }
+
+  #[test]
+  fn any_of_matcher_test() {
+    let path = DocPath::new_unwrap("$.id");
+    let rules = RuleList::new(MatchingRule::AnyOf(vec![
+      MatchingRule::Regex("^[0-9a-f]{8}(-[0-9a-f]{4}){3}-[0-9a-f]{12}$".to_string()),
+      MatchingRule::Regex("^$".to_string())
+    ]));
+    let expected = json!("e2490de5-5bd3-43d5-b7c4-526e33f71304");
+    expect!(match_values(&path, &rules, &expected, &json!("d1b4a2c7-8e3f-4a5b-9c6d-0e1f2a3b4c5d"))).to(be_ok());
+    expect!(match_values(&path, &rules, &expected, &json!(""))).to(be_ok());
+    expect!(match_values(&path, &rules, &expected, &json!("not-a-uuid"))).to(be_err());
+
+    let rule = MatchingRule::AnyOf(vec![ MatchingRule::Integer, MatchingRule::Null ]);
+    expect!(match_rule(&json!(1), &json!(100), &rule, false)).to(be_ok());
+    expect!(match_rule(&json!(1), &json!(null), &rule, false)).to(be_ok());
+    expect!(match_rule(&json!(1), &json!("100a"), &rule, false)).to(be_err());
+  }
+
+  #[test]
+  fn not_matcher_test() {
+    let rule = MatchingRule::Not(Box::new(MatchingRule::Include("admin".to_string())));
+    expect!(match_rule(&"Fred", "Fred", &rule, false)).to(be_ok());
+    expect!(match_rule(&"Fred", "superadmin", &rule, false)).to(be_err());
+
+    let path = DocPath::new_unwrap("$.name");
+    let rules = RuleList {
+      rules: vec![ MatchingRule::Type, rule ],
+      rule_logic: RuleLogic::And,
+      cascaded: false
+    };
+    expect!(match_values(&path, &rules, &json!("Fred"), &json!("Mary"))).to(be_ok());
+    expect!(match_values(&path, &rules, &json!("Fred"), &json!("admin"))).to(be_err());
+    expect!(match_values(&path, &rules, &json!("Fred"), &json!(100))).to(be_err());
+
+    let rule = MatchingRule::Not(Box::new(MatchingRule::AnyOf(vec![ MatchingRule::Null, MatchingRule::Boolean ])));
+    expect!(match_rule(&json!(1), &json!(100), &rule, false)).to(be_ok());
+    expect!(match_rule(&json!(1), &json!(true), &rule, false)).to(be_err());
+  }
+
+  #[test]
+  fn exists_and_absent_matcher_test() {
+    expect!(match_rule(&json!(1), &json!(100), &MatchingRule::Exists, false)).to(be_ok());
+    expect!(match_rule(&json!(1), &json!(null), &MatchingRule::Exists, false)).to(be_ok());
+    expect!(match_rule(&"Fred", "Mary", &MatchingRule::Absent, false)).to(be_err());
+  }
+
+  #[test]
+  fn epoch_timestamp_matcher_test() {
+    let matcher = MatchingRule::EpochTimestamp(EpochUnit::Millis, None);
+    expect!("1700000000000".matches_with("1600000000000", &matcher, false)).to(be_ok());
+    expect!("1700000000000".matches_with("2023-11-14", &matcher, false)).to(be_err());
+    expect!(1700000000000_u64.matches_with(1600000000000_u64, &matcher, false)).to(be_ok());
+    expect!(1700000000000_i64.matches_with(-1_i64, &matcher, false)).to(be_err());
+    expect!(json!(1700000000000_u64).matches_with(&json!(1600000000000_u64), &matcher, false)).to(be_ok());
+    expect!(json!(1700000000000_u64).matches_with(&json!("1600000000000"), &matcher, false)).to(be_ok());
+    expect!(json!(1700000000000_u64).matches_with(&json!(1600000000000.5), &matcher, false)).to(be_err());
+    expect!(json!(1700000000000_u64).matches_with(&json!(-1), &matcher, false)).to(be_err());
+
+    let matcher = MatchingRule::EpochTimestamp(EpochUnit::Seconds, Some(60));
+    let now = EpochUnit::Seconds.now();
+    expect!(json!(1700000000_u64).matches_with(&json!(now), &matcher, false)).to(be_ok());
+    expect!(json!(1700000000_u64).matches_with(&json!(now - 3600), &matcher, false)).to(be_err());
+    expect!(1700000000_u64.matches_with(now + 10, &matcher, false)).to(be_ok());
+  }
+
+  #[test]
+  fn url_matcher_test() {
+    let expected = "https://api.example.com/v1/users?page=1&sort=name";
+    let matcher = MatchingRule::Url(matchingrules! {
+      "body" => {
+        "$.host" => [ MatchingRule::Regex(".+\\.example\\.com".to_string()) ],
+        "$.query.page" => [ MatchingRule::Integer ]
+      }
+    }.rules_for_category("body").unwrap());
+    expect!(expected.matches_with(expected, &matcher, false)).to(be_ok());
+    expect!(expected.matches_with("https://api.test.example.com/v1/users?sort=name&page=100", &matcher, false)).to(be_ok());
+    expect!(expected.matches_with("https://api.test.example.com:443/v1/users?page=100&sort=name", &matcher, false)).to(be_ok());
+    expect!(expected.matches_with("https://api.other.com/v1/users?page=1&sort=name", &matcher, false)).to(be_err());
+    expect!(expected.matches_with("http://api.example.com/v1/users?page=1&sort=name", &matcher, false)).to(be_err());
+    expect!(expected.matches_with("https://api.example.com:8443/v1/users?page=1&sort=name", &matcher, false)).to(be_err());
+    expect!(expected.matches_with("https://api.example.com/v2/users?page=1&sort=name", &matcher, false)).to(be_err());
+    expect!(expected.matches_with("https://api.example.com/v1/users?page=one&sort=name", &matcher, false)).to(be_err());
+    expect!(expected.matches_with("https://api.example.com/v1/users?page=1&sort=id", &matcher, false)).to(be_err());
+    expect!(expected.matches_with("https://api.example.com/v1/users?page=1", &matcher, false)).to(be_err());
+    expect!(expected.matches_with("https://api.example.com/v1/users?page=1&sort=name&limit=10", &matcher, false)).to(be_err());
+    expect!(expected.matches_with("https://api.example.com/v1/users?page=1&sort=name#top", &matcher, false)).to(be_err());
+    expect!(expected.matches_with("/v1/users?page=1&sort=name", &matcher, false)).to(be_err());
+    expect!(json!(expected).matches_with(&json!("https://api.test.example.com/v1/users?page=2&sort=name"), &matcher, false)).to(be_ok());
+    expect!(json!(expected).matches_with(&json!(100), &matcher, false)).to(be_err());
+
+    let matcher = MatchingRule::Url(matchingrules! {
+      "body" => { "$.query" => [ MatchingRule::Type ] }
+    }.rules_for_category("body").unwrap());
+    expect!(expected.matches_with("https://api.example.com/v1/users?page=2&sort=id", &matcher, false)).to(be_ok());
+    expect!(expected.matches_with("https://api.example.com/v1/users?page=2&sort=id&sort=name", &matcher, false)).to(be_ok());
+    expect!(expected.matches_with("https://api.example.com/v1/users?page=2", &matcher, false)).to(be_err());
+
+    expect!("https://api.example.com/v1/users".matches_with("https://api.example.com/v1/users",
+      &MatchingRule::Url(MatchingRuleCategory::empty("body")), false)).to(be_ok());
+  }
+
   #[test]
   fn content_type_matcher_test() {
     let matcher = MatchingRule::ContentType("text/plain".to_string());
@@ -6,7 +6,7 @@ use std::str::from_utf8;
 
 use anyhow::anyhow;
 use itertools::Itertools;
-use maplit::hashmap;
+use maplit::{hashmap, hashset};
 use onig::Regex;
 use pact_models::matchingrules::{Category, MatchingRule, MatchingRuleCategory, RuleList, RuleLogic};
 use pact_models::path_exp::DocPath;
@@ -79,6 +79,8 @@ impl <T: Debug + Display + PartialEq + Clone> Matches<&[T]> for &[T] {
       MatchingRule::EachKey(_) => Ok(()),
       MatchingRule::EachValue(_) => Ok(()),
       MatchingRule::Values => Ok(()),
+      MatchingRule::AllowUnexpectedElements => Ok(()),
+      MatchingRule::EqualsIgnoreOrder => Ok(()),
       _ => Err(anyhow!("Unable to match {} using {:?}", self.for_mismatch(), matcher))
     };
     debug!("Comparing '{:?}' to '{:?}' using {:?} -> {:?}", self, actual, matcher, result);
@@ -218,6 +220,7 @@ impl <T: Debug + Display + Clone + PartialEq> Matches<&BTreeMap<String, T>> for
       MatchingRule::EachKey(_) => Ok(()),
       MatchingRule::EachValue(_) => Ok(()),
       MatchingRule::Values => Ok(()),
+      MatchingRule::AllowUnexpectedElements => Ok(()),
       _ => Err(anyhow!("Unable to match {} using {:?}", self.for_mismatch(), matcher))
     };
     debug!("Comparing '{:?}' to '{:?}' using {:?} -> {:?}", self, actual, matcher, result);
@@ -282,6 +285,11 @@ pub fn compare_maps_with_matchingrule<T: Display + Debug + Clone + PartialEq>(
   context: &(dyn MatchingContext + Send + Sync),
   callback: &mut dyn FnMut(&DocPath, &T, &T, &(dyn MatchingContext + Send + Sync)) -> Result<(), Vec<CommonMismatch>>
 ) -> Result<(), Vec<CommonMismatch>> {
+  if *rule == MatchingRule::AllowUnexpectedElements {
+    // This only modifies how min type matchers are applied to arrays
+    return Ok(());
+  }
+
   let mut result = Ok(());
   if !cascaded && rule.is_values_matcher() {
     debug!("Values matcher is defined for path {}", path);
@@ -295,7 +303,8 @@ pub fn compare_maps_with_matchingrule<T: Display + Debug + Clone + PartialEq>(
               path: path.to_string(),
               expected: format!("{:?}", expected),
               actual: format!("{:?}", actual),
-              description: format!("Found an un-resolved reference {}", reference.name)
+              description: format!("Found an un-resolved reference {}", reference.name),
+              rule: None
             }]));
             None
           }
@@ -330,7 +339,8 @@ pub fn compare_maps_with_matchingrule<T: Display + Debug + Clone + PartialEq>(
         path: path.to_string(),
         expected: expected.for_mismatch(),
         actual: actual.for_mismatch(),
-        description: mismatch.to_string()
+        description: mismatch.to_string(),
+        rule: None
       }]));
     }
     let expected_keys = expected.keys().cloned().collect();
@@ -361,11 +371,16 @@ pub fn compare_lists_with_matchingrule<T: Display + Debug + PartialEq + Clone +
 
   if !expected.is_empty() {
     match rule {
-      // TODO: need to implement the ignore order matchers (See Pact-JVM core/matchers/src/main/kotlin/au/com/dius/pact/core/matchers/Matchers.kt:133)
-      // is EqualsIgnoreOrderMatcher,
-      //         is MinEqualsIgnoreOrderMatcher,
-      //         is MaxEqualsIgnoreOrderMatcher,
-      //         is MinMaxEqualsIgnoreOrderMatcher -> {
+      MatchingRule::EqualsIgnoreOrder => if !cascaded {
+        debug!("Matching {} with EqualsIgnoreOrder", path);
+        let ignore_unexpected = context.array_options().allow_unexpected_elements ||
+          context.direct_matcher_defined(path, &hashset! { "allow-unexpected-elements" });
+        let context = context.clone_with(&without_list_rules(path, context.matchers()));
+        result.extend(match_list_contents_ignoring_order(path, expected, actual, context.as_ref(),
+          ignore_unexpected, callback));
+      } else {
+        result.extend(match_list_contents(path, expected, actual, context, false, callback));
+      }
       MatchingRule::ArrayContains(variants) => {
         debug!("Matching {} with ArrayContains", path);
         let variants = if variants.is_empty() {
@@ -387,7 +402,8 @@ pub fn compare_lists_with_matchingrule<T: Display + Debug + PartialEq + Clone +
                   path: path.to_string(),
                   expected: expected_value.to_string(),
                   actual: actual.for_mismatch(),
-                  description: format!("Variant at index {} ({}) was not found in the actual list", index, expected_value)
+                  description: format!("Variant at index {} ({}) was not found in the actual list", index, expected_value),
+                  rule: None
                 });
               };
             },
@@ -397,7 +413,8 @@ pub fn compare_lists_with_matchingrule<T: Display + Debug + PartialEq + Clone +
                 expected: expected.for_mismatch(),
                 actual: actual.for_mismatch(),
                 description: format!("ArrayContains: variant {} is missing from the expected list, which has {} items",
-                                  index, expected.len())
+                                  index, expected.len()),
+                rule: None
               });
             }
           }
@@ -413,7 +430,8 @@ pub fn compare_lists_with_matchingrule<T: Display + Debug + PartialEq + Clone +
                 path: path.to_string(),
                 expected: expected.for_mismatch(),
                 actual: actual.for_mismatch(),
-                description: format!("Found an un-resolved reference {}", reference.name)
+                description: format!("Found an un-resolved reference {}", reference.name),
+                rule: None
               });
               None
             }
@@ -430,19 +448,23 @@ pub fn compare_lists_with_matchingrule<T: Display + Debug + PartialEq + Clone +
           }
         };
         let context = context.clone_with(&rules);
-        result.extend(match_list_contents(path, expected, actual, context.as_ref(), callback));
+        result.extend(match_list_contents(path, expected, actual, context.as_ref(), false, callback));
       }
+      // This only modifies how the min type matchers for the array are applied
+      MatchingRule::AllowUnexpectedElements => {}
       _ => {
         if let Err(mismatch) = expected.matches_with(actual, rule, cascaded) {
           result.push(CommonMismatch {
             path: path.to_string(),
             expected: expected.for_mismatch(),
             actual: actual.for_mismatch(),
-            description: mismatch.to_string()
+            description: mismatch.to_string(),
+            rule: None
           });
         }
 
-        result.extend(match_list_contents(path, expected, actual, context, callback));
+        let ignore_tail = !cascaded && allow_unexpected_elements(rule, path, context);
+        result.extend(match_list_contents(path, expected, actual, context, ignore_tail, callback));
       }
     }
   }
@@ -471,12 +493,15 @@ pub fn compare_lists_with_matchingrules<T>(
       path: path.to_string(),
       expected: format!("{:?}", expected),
       actual: format!("{:?}", actual),
-      description: format!("No matcher found for path '{}'", path)
+      description: format!("No matcher found for path '{}'", path),
+      rule: None
     })
   } else {
-    let results = matching_rules.rules.iter().map(|rule| {
-      compare_lists_with_matchingrule(&rule, path, expected, actual, context, matching_rules.cascaded, callback)
-    }).collect::<Vec<Result<(), Vec<CommonMismatch>>>>();
+    let results = matching_rules.rules.iter()
+      .filter(|rule| **rule != MatchingRule::AllowUnexpectedElements)
+      .map(|rule| {
+        compare_lists_with_matchingrule(&rule, path, expected, actual, context, matching_rules.cascaded, callback)
+      }).collect::<Vec<Result<(), Vec<CommonMismatch>>>>();
     match matching_rules.rule_logic {
       RuleLogic::And => for result in results {
         if let Err(err) = result {
@@ -503,17 +528,148 @@ pub fn compare_lists_with_matchingrules<T>(
   }
 }
 
+/// If additional elements at the end of the actual list can be ignored for the min type matcher.
+/// This is enabled either with the context array options, or by an `AllowUnexpectedElements` rule
+/// defined for the list.
+fn allow_unexpected_elements(
+  rule: &MatchingRule,
+  path: &DocPath,
+  context: &(dyn MatchingContext + Send + Sync)
+) -> bool {
+  matches!(rule, MatchingRule::MinType(_) | MatchingRule::MinMaxType(_, _)) &&
+    (context.array_options().allow_unexpected_elements ||
+      context.direct_matcher_defined(path, &hashset! { "allow-unexpected-elements" }))
+}
+
+/// Removes the rules that only apply to the list at the given path (like the ignore order rule),
+/// so that they do not cascade to the items of the list.
+fn without_list_rules(path: &DocPath, matchers: &MatchingRuleCategory) -> MatchingRuleCategory {
+  let mut matchers = matchers.clone();
+  let path = path.to_vec();
+  let path_slice = path.iter().map(|p| p.as_str()).collect_vec();
+  if let Some(rule_path) = matchers.select_best_matcher_path(path_slice.as_slice()) {
+    if let Some(rules) = matchers.rules.get_mut(&rule_path) {
+      rules.rules.retain(|rule| rule.can_cascade());
+      if rules.is_empty() {
+        matchers.rules.remove(&rule_path);
+      }
+    }
+  }
+  matchers
+}
+
+/// Matches the items of the lists in any order. Each expected item is assigned to an actual item
+/// that it matches, using a maximal bipartite matching, so that the best assignment is found even
+/// if an actual item matches more than one expected item. Any items that can not be assigned are
+/// reported as mismatches.
+fn match_list_contents_ignoring_order<T: Display + Debug + PartialEq + Clone + Sized>(
+  path: &DocPath,
+  expected: &[T],
+  actual: &[T],
+  context: &(dyn MatchingContext + Send + Sync),
+  ignore_unexpected: bool,
+  callback: &mut dyn FnMut(&DocPath, &T, &T, &(dyn MatchingContext + Send + Sync)) -> Result<(), Vec<CommonMismatch>>
+) -> Vec<CommonMismatch> {
+  let mut candidates = vec![];
+  for (index, value) in expected.iter().enumerate() {
+    let p = path.join(index.to_string());
+    let mut matching = vec![];
+    for (actual_index, actual_value) in actual.iter().enumerate() {
+      debug!("Comparing list item {} with value '{:?}' to '{:?}'", actual_index, actual_value, value);
+      if callback(&p, value, actual_value, context).is_ok() {
+        matching.push(actual_index);
+      }
+    }
+    candidates.push(matching);
+  }
+
+  let mut assigned: Vec<Option<usize>> = vec![None; actual.len()];
+  for index in 0..expected.len() {
+    let mut visited = vec![false; actual.len()];
+    assign_list_item(index, &candidates, &mut assigned, &mut visited);
+  }
+
+  let unmatched_expected = (0..expected.len())
+    .filter(|index| !assigned.contains(&Some(*index)))
+    .collect_vec();
+  let unmatched_actual = (0..actual.len())
+    .filter(|index| assigned[*index].is_none())
+    .collect_vec();
+  trace!(?unmatched_expected, ?unmatched_actual, "match_list_contents_ignoring_order");
+
+  let mut result = vec![];
+  for (index, expected_index) in unmatched_expected.iter().enumerate() {
+    if let Some(actual_index) = unmatched_actual.get(index) {
+      // Report the mismatches against one of the remaining actual items
+      let p = path.join(expected_index.to_string());
+      if let Err(mismatches) = callback(&p, &expected[*expected_index], &actual[*actual_index], context) {
+        result.extend(mismatches);
+      }
+    } else {
+      result.push(CommonMismatch {
+        path: path.to_string(),
+        expected: expected.for_mismatch(),
+        actual: actual.for_mismatch(),
+        description: format!("Expected {} ({}) but was missing", expected[*expected_index], expected_index),
+        rule: None
+      });
+    }
+  }
+
+  if !ignore_unexpected {
+    for actual_index in unmatched_actual.iter().skip(unmatched_expected.len()) {
+      result.push(CommonMismatch {
+        path: path.to_string(),
+        expected: expected.for_mismatch(),
+        actual: actual.for_mismatch(),
+        description: format!("Unexpected item {} ({}) was not matched by any expected item",
+          actual[*actual_index], actual_index),
+        rule: None
+      });
+    }
+  }
+
+  result
+}
+
+/// Tries to assign the expected item to one of the actual items it matches, re-assigning
+/// previously assigned items to other actual items if required.
+fn assign_list_item(
+  index: usize,
+  candidates: &[Vec<usize>],
+  assigned: &mut [Option<usize>],
+  visited: &mut [bool]
+) -> bool {
+  for actual_index in &candidates[index] {
+    if !visited[*actual_index] {
+      visited[*actual_index] = true;
+      let available = match assigned[*actual_index] {
+        Some(other) => assign_list_item(other, candidates, assigned, visited),
+        None => true
+      };
+      if available {
+        assigned[*actual_index] = Some(index);
+        return true;
+      }
+    }
+  }
+  false
+}
+
 fn match_list_contents<T: Display + Debug + PartialEq + Clone + Sized>(
   path: &DocPath,
   expected: &[T],
   actual: &[T],
   context: &(dyn MatchingContext + Send + Sync),
+  ignore_tail: bool,
   callback: &mut dyn FnMut(&DocPath, &T, &T, &(dyn MatchingContext + Send + Sync)) -> Result<(), Vec<CommonMismatch>>
 ) -> Vec<CommonMismatch> {
   let mut result = vec![];
 
   let mut expected_list = expected.to_vec();
-  if actual.len() > expected.len() {
+  if actual.len() > expected.len() && ignore_tail {
+    debug!("Ignoring {} additional elements in the actual list at {}", actual.len() - expected.len(), path);
+  } else if actual.len() > expected.len() {
     if let Some(first) = expected.first() {
       expected_list.resize(actual.len(), first.clone());
     }
@@ -532,7 +688,8 @@ fn match_list_contents<T: Display + Debug + PartialEq + Clone + Sized>(
         path: path.to_string(),
         expected: expected.for_mismatch(),
         actual: actual.for_mismatch(),
-        description: format!("Expected {} ({}) but was missing", value, index)
+        description: format!("Expected {} ({}) but was missing", value, index),
+        rule: None
       });
     }
   }
@@ -761,7 +918,8 @@ mod tests {
         path: "$".to_string(),
         expected: "{\"a\":\"100\",\"b\":\"101\",\"c\":\"102\"}".to_string(),
         actual: "{\"b\":\"103\"}".to_string(),
-        description: "Expected {\"b\": \"103\"} (size 1) to have minimum size of 2".to_string()
+        description: "Expected {\"b\": \"103\"} (size 1) to have minimum size of 2".to_string(),
+        rule: None
       }
     ]));
 
@@ -799,7 +957,8 @@ mod tests {
         path: "$".to_string(),
         expected: "{\"a\":\"100\"}".to_string(),
         actual: "{\"a\":\"101\",\"b\":\"102\",\"c\":\"103\"}".to_string(),
-        description: "Expected {\"a\": \"101\", \"b\": \"102\", \"c\": \"103\"} (size 3) to have maximum size of 2".to_string()
+        description: "Expected {\"a\": \"101\", \"b\": \"102\", \"c\": \"103\"} (size 3) to have maximum size of 2".to_string(),
+        rule: None
       }
     ]));
 
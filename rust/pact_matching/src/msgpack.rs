@@ -0,0 +1,211 @@
+//! Functions for matching MessagePack encoded bodies. The bodies are decoded into a JSON tree, so
+//! the normal JSON matching rules and generators can be applied to them.
+
+use anyhow::anyhow;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use rmpv::Value as MsgPackValue;
+use serde_json::{Map, Number, Value};
+use tracing::debug;
+
+use pact_models::http_parts::HttpPart;
+use pact_models::path_exp::DocPath;
+
+use crate::{MatchingContext, Mismatch};
+use crate::json::compare_json;
+
+/// Decodes the MessagePack encoded data into a JSON tree. Binary and extension values are
+/// converted to Base64 encoded strings, and any map keys that are not strings are converted to
+/// their JSON form.
+pub fn decode_msgpack(data: &[u8]) -> anyhow::Result<Value> {
+  let mut reader = data;
+  let value = rmpv::decode::read_value(&mut reader)
+    .map_err(|err| anyhow!("Failed to decode the MessagePack value - {}", err))?;
+  if !reader.is_empty() {
+    return Err(anyhow!("Failed to decode the MessagePack value - found {} unexpected trailing bytes", reader.len()));
+  }
+  Ok(msgpack_to_json(value))
+}
+
+/// Encodes the JSON tree as MessagePack
+pub fn encode_msgpack(value: &Value) -> anyhow::Result<Vec<u8>> {
+  let mut buffer = vec![];
+  rmpv::encode::write_value(&mut buffer, &json_to_msgpack(value))
+    .map_err(|err| anyhow!("Failed to encode the value as MessagePack - {}", err))?;
+  Ok(buffer)
+}
+
+fn msgpack_to_json(value: MsgPackValue) -> Value {
+  match value {
+    MsgPackValue::Nil => Value::Null,
+    MsgPackValue::Boolean(b) => Value::Bool(b),
+    MsgPackValue::Integer(i) => if let Some(i) = i.as_i64() {
+      Value::Number(Number::from(i))
+    } else if let Some(i) = i.as_u64() {
+      Value::Number(Number::from(i))
+    } else {
+      Value::Null
+    },
+    MsgPackValue::F32(f) => Number::from_f64(f as f64).map(Value::Number).unwrap_or(Value::Null),
+    MsgPackValue::F64(f) => Number::from_f64(f).map(Value::Number).unwrap_or(Value::Null),
+    MsgPackValue::String(s) => match s.into_str() {
+      Some(s) => Value::String(s),
+      None => Value::Null
+    },
+    MsgPackValue::Binary(b) => Value::String(BASE64.encode(b)),
+    MsgPackValue::Ext(_, b) => Value::String(BASE64.encode(b)),
+    MsgPackValue::Array(values) => Value::Array(values.into_iter().map(msgpack_to_json).collect()),
+    MsgPackValue::Map(entries) => Value::Object(entries.into_iter()
+      .map(|(key, value)| {
+        let key = match msgpack_to_json(key) {
+          Value::String(s) => s,
+          key => key.to_string()
+        };
+        (key, msgpack_to_json(value))
+      })
+      .collect::<Map<String, Value>>())
+  }
+}
+
+fn json_to_msgpack(value: &Value) -> MsgPackValue {
+  match value {
+    Value::Null => MsgPackValue::Nil,
+    Value::Bool(b) => MsgPackValue::Boolean(*b),
+    Value::Number(n) => if let Some(i) = n.as_i64() {
+      MsgPackValue::from(i)
+    } else if let Some(i) = n.as_u64() {
+      MsgPackValue::from(i)
+    } else {
+      MsgPackValue::F64(n.as_f64().unwrap_or_default())
+    },
+    Value::String(s) => MsgPackValue::from(s.as_str()),
+    Value::Array(values) => MsgPackValue::Array(values.iter().map(json_to_msgpack).collect()),
+    Value::Object(entries) => MsgPackValue::Map(entries.iter()
+      .map(|(key, value)| (MsgPackValue::from(key.as_str()), json_to_msgpack(value)))
+      .collect())
+  }
+}
+
+/// Matches the MessagePack encoded expected and actual bodies
+pub fn match_msgpack(
+  expected: &(dyn HttpPart + Send + Sync),
+  actual: &(dyn HttpPart + Send + Sync),
+  context: &(dyn MatchingContext + Send + Sync)
+) -> Result<(), Vec<Mismatch>> {
+  let expected_body = expected.body().value().unwrap_or_default();
+  let actual_body = actual.body().value().unwrap_or_default();
+  debug!("Matching MessagePack bodies: expected {} bytes, actual {} bytes", expected_body.len(), actual_body.len());
+
+  match (decode_msgpack(&expected_body), decode_msgpack(&actual_body)) {
+    (Ok(expected_json), Ok(actual_json)) => {
+      compare_json(&DocPath::root(), &expected_json, &actual_json, context)
+        .map_err(|mismatches| mismatches.iter().map(|mismatch| mismatch.to_body_mismatch()).collect())
+    }
+    (expected_json, actual_json) => {
+      let mut mismatches = vec![];
+      if let Err(err) = expected_json {
+        mismatches.push(Mismatch::BodyMismatch {
+          path: "$".to_string(),
+          expected: expected.body().value(),
+          actual: actual.body().value(),
+          mismatch: format!("Failed to decode the expected MessagePack body: '{}'", err),
+          rule: None
+        });
+      }
+      if let Err(err) = actual_json {
+        mismatches.push(Mismatch::BodyMismatch {
+          path: "$".to_string(),
+          expected: expected.body().value(),
+          actual: actual.body().value(),
+          mismatch: format!("Failed to decode the actual MessagePack body: '{}'", err),
+          rule: None
+        });
+      }
+      Err(mismatches)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+  use maplit::hashmap;
+  use pact_models::bodies::OptionalBody;
+  use pact_models::matchingrules;
+  use pact_models::matchingrules::MatchingRule;
+  use pact_models::request::Request;
+  use rmpv::Value as MsgPackValue;
+  use serde_json::json;
+
+  use crate::{CoreMatchingContext, DiffConfig, Mismatch};
+
+  use super::{decode_msgpack, encode_msgpack, match_msgpack};
+
+  fn request(data: Vec<u8>) -> Request {
+    Request {
+      headers: Some(hashmap!{ "Content-Type".to_string() => vec!["application/msgpack".to_string()] }),
+      body: OptionalBody::Present(data.into(), None, None),
+      .. Request::default()
+    }
+  }
+
+  #[test]
+  fn decode_msgpack_test() {
+    let value = MsgPackValue::Map(vec![
+      (MsgPackValue::from("id"), MsgPackValue::from(100)),
+      (MsgPackValue::from("name"), MsgPackValue::from("Fred")),
+      (MsgPackValue::from("data"), MsgPackValue::Binary(vec![1, 2, 3])),
+      (MsgPackValue::from(1), MsgPackValue::F32(1.5))
+    ]);
+    let mut buffer = vec![];
+    rmpv::encode::write_value(&mut buffer, &value).unwrap();
+    expect!(decode_msgpack(&buffer).unwrap()).to(be_equal_to(json!({
+      "id": 100,
+      "name": "Fred",
+      "data": "AQID",
+      "1": 1.5
+    })));
+
+    expect!(decode_msgpack(&[0x92, 0x01])).to(be_err());
+    buffer.push(0xc0);
+    expect!(decode_msgpack(&buffer)).to(be_err());
+  }
+
+  #[test]
+  fn encode_msgpack_round_trips_json_values() {
+    let value = json!({ "id": 100, "name": "Fred", "tags": [ true, null, -1.5, 18446744073709551615_u64 ] });
+    expect!(decode_msgpack(&encode_msgpack(&value).unwrap()).unwrap()).to(be_equal_to(value));
+  }
+
+  #[test]
+  fn match_msgpack_applies_matching_rules_to_the_decoded_bodies() {
+    let expected = request(encode_msgpack(&json!({ "id": 100, "name": "Fred" })).unwrap());
+    let actual = request(encode_msgpack(&json!({ "id": 200, "name": "Fred" })).unwrap());
+
+    let context = CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys);
+    let result = match_msgpack(&expected, &actual, &context);
+    expect!(result.unwrap_err().iter().map(|m| m.description()).collect::<Vec<_>>()).to(be_equal_to(vec![
+      "$.id -> Expected 200 (Integer) to be equal to 100 (Integer)".to_string()
+    ]));
+
+    let rules = matchingrules! {
+      "body" => { "$.id" => [ MatchingRule::Integer ] }
+    };
+    let context = CoreMatchingContext::new(
+      DiffConfig::AllowUnexpectedKeys,
+      &rules.rules_for_category("body").unwrap_or_default(), &hashmap!{}
+    );
+    expect!(match_msgpack(&expected, &actual, &context)).to(be_ok());
+  }
+
+  #[test]
+  fn match_msgpack_returns_a_mismatch_if_the_body_can_not_be_decoded() {
+    let expected = request(vec![0x92, 0x01]);
+    let actual = request(encode_msgpack(&json!({ "id": 100 })).unwrap());
+
+    let context = CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys);
+    let mismatches = match_msgpack(&expected, &actual, &context).unwrap_err();
+    expect!(mismatches.len()).to(be_equal_to(1));
+    expect!(matches!(&mismatches[0], Mismatch::BodyMismatch { mismatch, .. } if mismatch.starts_with("Failed to decode the expected MessagePack body"))).to(be_true());
+  }
+}
@@ -60,7 +60,7 @@ fn match_query_values(
   if context.matcher_is_defined(&path) {
     debug!("match_query_values: Matcher defined for query parameter '{}", key);
     compare_lists_with_matchingrules(&path, &context.select_best_matcher(&path), expected, actual, context.clone_with(context.matchers()).as_ref(), &mut |p, expected, actual, context| {
-      compare_query_parameter_value(p, expected, actual, 0, context)
+      compare_query_parameter_value(p, expected, actual, context)
     })
   } else {
     if expected.is_empty() && !actual.is_empty() {
@@ -68,7 +68,8 @@ fn match_query_values(
         path: key.to_string(),
         expected: format!("{:?}", expected),
         actual: format!("{:?}", actual),
-        description: format!("Expected an empty parameter list for '{}' but received {:?}", key, actual)
+        description: format!("Expected an empty parameter list for '{}' but received {:?}", key, actual),
+        rule: None
       } ])
     } else {
       let mismatch = if expected.len() != actual.len() {
@@ -78,7 +79,8 @@ fn match_query_values(
           actual: format!("{:?}", actual),
           description: format!(
             "Expected query parameter '{}' with {} value(s) but received {} value(s)",
-            key, expected.len(), actual.len())
+            key, expected.len(), actual.len()),
+          rule: None
         } ])
       } else {
         Ok(())
@@ -88,23 +90,22 @@ fn match_query_values(
   }
 }
 
+/// Compares a single query parameter value. The path is the path to the value (i.e. `$.id[0]`),
+/// so rules defined for the values with an index or index wildcard will be applied.
 fn compare_query_parameter_value(
-  path: &DocPath,
+  index_path: &DocPath,
   expected: &str,
   actual: &str,
-  index: usize,
   context: &dyn MatchingContext
 ) -> Result<(), Vec<CommonMismatch>> {
-  let index = index.to_string();
-  let index_path = path.join(index.as_str());
-  let matcher_result = if context.matcher_is_defined(&index_path) {
-    matchers::match_values(&index_path, &context.select_best_matcher(&index_path),
+  let matcher_result = if context.matcher_is_defined(index_path) {
+    matchers::match_values(index_path, &context.select_best_matcher(index_path),
       expected.to_string(), actual.to_string())
   } else {
     expected.matches_with(actual, &MatchingRule::Equality, false)
       .map_err(|_error| vec![
         format!("Expected query parameter '{}' with value '{}' but was '{}'",
-          path.to_vec().last().cloned().unwrap_or_else(|| "??".to_string()),
+          index_path.first_field().unwrap_or("??"),
           expected,
           actual
         )
@@ -113,10 +114,11 @@ fn compare_query_parameter_value(
   matcher_result.map_err(|messages| {
     messages.iter().map(|message| {
       CommonMismatch {
-        path: path.first_field().unwrap_or_default().to_string(),
+        path: index_path.first_field().unwrap_or_default().to_string(),
         expected: expected.to_string(),
         actual: actual.to_string(),
-        description: message.clone()
+        description: message.clone(),
+        rule: None
       }
     }).collect()
   })
@@ -134,7 +136,7 @@ fn compare_query_parameter_values(
     .enumerate()
     .flat_map(|(index, val)| {
       if index < actual.len() {
-        match compare_query_parameter_value(path, val, &actual[index], index, context) {
+        match compare_query_parameter_value(&path.join(index.to_string()), val, &actual[index], context) {
           Ok(_) => vec![],
           Err(errors) => errors
         }
@@ -146,7 +148,8 @@ fn compare_query_parameter_values(
           path: key.clone(),
           expected: format!("{:?}", expected),
           actual: format!("{:?}", actual),
-          description: format!("Expected query parameter '{}' value '{}' but was missing", key, val)
+          description: format!("Expected query parameter '{}' value '{}' but was missing", key, val),
+          rule: None
         } ]
       }
     })
@@ -183,4 +186,31 @@ mod tests {
     expect!(super::match_query_values("id", &expected, &actual, &context))
       .to(be_ok());
   }
+
+  #[test]
+  fn compare_values_with_min_max_type_matcher_and_index_wildcard() {
+    let expected = ["1".to_string(), "2".to_string()];
+    let rules = matchingrules! {
+      "query" => {
+        "$.ids" => [ MatchingRule::MinMaxType(1, 3) ],
+        "$.ids[*]" => [ MatchingRule::Regex("^\\d+$".to_string()) ]
+      }
+    };
+    let context = CoreMatchingContext::new(
+      DiffConfig::AllowUnexpectedKeys,
+      &rules.rules_for_category("query").unwrap_or_default(),
+      &hashmap!{}
+    );
+
+    let actual = ["100".to_string(), "200".to_string(), "300".to_string()];
+    expect!(super::match_query_values("ids", &expected, &actual, &context)).to(be_ok());
+
+    let actual = ["100".to_string(), "abc".to_string()];
+    let result = super::match_query_values("ids", &expected, &actual, &context);
+    expect!(result.unwrap_err().iter().map(|m| m.description.clone()).collect::<Vec<_>>())
+      .to(be_equal_to(vec!["Expected 'abc' to match '^\\d+$'".to_string()]));
+
+    let actual = ["1".to_string(), "2".to_string(), "3".to_string(), "4".to_string()];
+    expect!(super::match_query_values("ids", &expected, &actual, &context)).to(be_err());
+  }
 }
@@ -9,6 +9,8 @@ use pact_models::bodies::OptionalBody;
 use pact_models::content_types::{JSON, TEXT};
 use pact_models::HttpStatus;
 use pact_models::request::Request;
+use pact_models::v4::pact::V4Pact;
+use pact_models::v4::synch_http::SynchronousHttp;
 
 use super::*;
 
@@ -506,6 +508,55 @@ async fn body_matching_uses_any_matcher_for_content_type_header() {
   expect!(mismatches.iter()).to(be_empty());
 }
 
+#[tokio::test]
+async fn body_matching_treats_registered_content_type_aliases_as_equivalent() {
+  let expected = Request {
+    headers: Some(hashmap! { "Content-Type".to_string() => vec!["application/json".to_string()] }),
+    body: OptionalBody::Present(Bytes::from("{\"a\": 100, \"b\": \"x\"}"), None, None),
+    ..Request::default()
+  };
+  let actual = Request {
+    headers: Some(hashmap! { "Content-Type".to_string() => vec!["text/json; charset=UTF-8".to_string()] }),
+    body: OptionalBody::Present(Bytes::from("{\"b\": \"x\", \"a\": 100}"), None, None),
+    ..Request::default()
+  };
+  let result = match_body(&expected, &actual, &CoreMatchingContext::default(),
+    &CoreMatchingContext::default()).await;
+  expect!(result.mismatches().iter()).to(be_empty());
+
+  let actual = Request {
+    headers: Some(hashmap! { "Content-Type".to_string() => vec!["application/vnd.api+json".to_string()] }),
+    body: OptionalBody::Present(Bytes::from("{\"b\": \"x\", \"a\": 100}"), None, None),
+    ..Request::default()
+  };
+  let result = match_body(&expected, &actual, &CoreMatchingContext::default(),
+    &CoreMatchingContext::default()).await;
+  expect!(result.mismatches().iter()).to(be_empty());
+}
+
+#[tokio::test]
+#[cfg(feature = "multipart")]
+async fn multipart_body_matching_ignores_the_boundaries() {
+  let expected = Request {
+    headers: Some(hashmap! { "Content-Type".to_string() => vec!["multipart/form-data; boundary=1234".to_string()] }),
+    body: OptionalBody::Present(Bytes::from("--1234\r\n\
+      Content-Type: text/plain\r\n\
+      Content-Disposition: form-data; name=\"name\"\r\n\r\nBaxter\r\n\
+      --1234--\r\n"), None, None),
+    ..Request::default()
+  };
+  let actual = Request {
+    headers: Some(hashmap! { "Content-Type".to_string() => vec!["multipart/form-data; boundary=x-4567-y".to_string()] }),
+    body: OptionalBody::Present(Bytes::from("--x-4567-y\r\n\
+      Content-Type: text/plain\r\n\
+      Content-Disposition: form-data; name=\"name\"\r\n\r\nBaxter\r\n\
+      --x-4567-y--\r\n"), None, None),
+    ..Request::default()
+  };
+  let result = match_body(&expected, &actual, &CoreMatchingContext::default(), &CoreMatchingContext::default()).await;
+  expect!(result.mismatches().iter()).to(be_empty());
+}
+
 #[tokio::test]
 async fn body_matches_if_expected_is_missing() {
   let expected = Request {
@@ -528,6 +579,37 @@ async fn body_matches_if_expected_is_missing() {
   expect!(result.mismatches().iter()).to(be_empty());
 }
 
+#[tokio::test]
+async fn body_matches_expectations_if_expected_is_missing_and_there_are_body_matching_rules() {
+  let expected = Request {
+    headers: Some(hashmap! { "Content-Type".to_string() => vec!["application/json".to_string()] }),
+    body: OptionalBody::Missing,
+    ..Request::default()
+  };
+  let actual = Request {
+    headers: Some(hashmap! { "Content-Type".to_string() => vec!["application/json".to_string()] }),
+    body: OptionalBody::Present(r#"{"data": {"id": 1, "items": [1, 2]}}"#.into(), None, None),
+    ..Request::default()
+  };
+  let context = CoreMatchingContext::new(DiffConfig::AllowUnexpectedKeys, &matchingrules! {
+    "body" => {
+      "$.data.id" => [ MatchingRule::Exists ],
+      "$.data.items" => [ MatchingRule::MinType(1) ]
+    }
+  }.rules_for_category("body").unwrap(), &hashmap!{});
+  let result = match_body(&expected, &actual, &context, &CoreMatchingContext::default()).await;
+  expect!(result.mismatches().iter()).to(be_empty());
+
+  let context = CoreMatchingContext::new(DiffConfig::AllowUnexpectedKeys, &matchingrules! {
+    "body" => {
+      "$.data.name" => [ MatchingRule::Exists ],
+      "$.data.id" => [ MatchingRule::Absent ]
+    }
+  }.rules_for_category("body").unwrap(), &hashmap!{});
+  let result = match_body(&expected, &actual, &context, &CoreMatchingContext::default()).await;
+  expect!(result.mismatches().len()).to(be_equal_to(2));
+}
+
 #[tokio::test]
 async fn body_matches_with_extended_mime_types() {
   let expected = Request {
@@ -621,9 +703,9 @@ fn partial_equal_for_method_mismatch() {
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::PathMismatch { expected: s!("get"), actual: s!("post"), mismatch: "".into() }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::StatusMismatch { expected: 200, actual: 300, mismatch: "".into() }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::QueryMismatch { parameter: s!(""), expected: s!(""), actual: s!(""), mismatch: "".into() }));
-  expect!(&mismatch).to_not(be_equal_to(&Mismatch::HeaderMismatch { key: s!(""), expected: s!(""), actual: s!(""), mismatch: "".into() }));
+  expect!(&mismatch).to_not(be_equal_to(&Mismatch::HeaderMismatch { key: s!(""), expected: s!(""), actual: s!(""), mismatch: "".into(), rule: None }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyTypeMismatch { expected: s!(""), actual: s!(""), mismatch: "".into(), expected_body: None, actual_body: None }));
-  expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyMismatch { expected: Some("get".into()), actual: Some("post".into()), mismatch: "".into(), path: s!("/") }));
+  expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyMismatch { expected: Some("get".into()), actual: Some("post".into()), mismatch: "".into(), path: s!("/"), rule: None }));
 }
 
 #[test]
@@ -639,9 +721,9 @@ fn partial_equal_for_path_mismatch() {
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::MethodMismatch { expected: s!("get"), actual: s!("post") }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::StatusMismatch { expected: 200, actual: 300, mismatch: "".into() }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::QueryMismatch { parameter: s!(""), expected: s!(""), actual: s!(""), mismatch: "".into() }));
-  expect!(&mismatch).to_not(be_equal_to(&Mismatch::HeaderMismatch { key: s!(""), expected: s!(""), actual: s!(""), mismatch: "".into() }));
+  expect!(&mismatch).to_not(be_equal_to(&Mismatch::HeaderMismatch { key: s!(""), expected: s!(""), actual: s!(""), mismatch: "".into(), rule: None }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyTypeMismatch { expected: s!(""), actual: s!(""), mismatch: "".into(), expected_body: None, actual_body: None }));
-  expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyMismatch { expected: Some("get".into()), actual: Some("post".into()), mismatch: "".into(), path: s!("/") }));
+  expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyMismatch { expected: Some("get".into()), actual: Some("post".into()), mismatch: "".into(), path: s!("/"), rule: None }));
 }
 
 #[test]
@@ -657,9 +739,9 @@ fn partial_equal_for_status_mismatch() {
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::MethodMismatch { expected: s!("get"), actual: s!("post") }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::PathMismatch { expected: s!("200"), actual: s!("300"), mismatch: s!("") }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::QueryMismatch { parameter: s!(""), expected: s!(""), actual: s!(""), mismatch: s!("") }));
-  expect!(&mismatch).to_not(be_equal_to(&Mismatch::HeaderMismatch { key: s!(""), expected: s!(""), actual: s!(""), mismatch: s!("") }));
+  expect!(&mismatch).to_not(be_equal_to(&Mismatch::HeaderMismatch { key: s!(""), expected: s!(""), actual: s!(""), mismatch: s!(""), rule: None }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyTypeMismatch { expected: s!(""), actual: s!(""), mismatch: s!(""), expected_body: None, actual_body: None }));
-  expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyMismatch { expected: Some("get".into()), actual: Some("post".into()), mismatch: s!(""), path: s!("/") }));
+  expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyMismatch { expected: Some("get".into()), actual: Some("post".into()), mismatch: s!(""), path: s!("/"), rule: None }));
 }
 
 #[test]
@@ -675,9 +757,9 @@ fn partial_equal_for_body_type_mismatch() {
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::MethodMismatch { expected: s!("get"), actual: s!("post") }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::StatusMismatch { expected: 200, actual: 300, mismatch: "".into() }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::QueryMismatch { parameter: s!(""), expected: s!(""), actual: s!(""), mismatch: s!("") }));
-  expect!(&mismatch).to_not(be_equal_to(&Mismatch::HeaderMismatch { key: s!(""), expected: s!(""), actual: s!(""), mismatch: s!("") }));
+  expect!(&mismatch).to_not(be_equal_to(&Mismatch::HeaderMismatch { key: s!(""), expected: s!(""), actual: s!(""), mismatch: s!(""), rule: None }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::PathMismatch { expected: s!(""), actual: s!(""), mismatch: s!("") }));
-  expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyMismatch { expected: Some("get".into()), actual: Some("post".into()), mismatch: s!(""), path: s!("/") }));
+  expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyMismatch { expected: Some("get".into()), actual: Some("post".into()), mismatch: s!(""), path: s!("/"), rule: None }));
 }
 
 #[test]
@@ -697,19 +779,19 @@ fn partial_equal_for_query_mismatch() {
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::MethodMismatch { expected: s!("get"), actual: s!("post") }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::StatusMismatch { expected: 200, actual: 300, mismatch: "".into() }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::PathMismatch { expected: s!(""), actual: s!(""), mismatch: s!("") }));
-  expect!(&mismatch).to_not(be_equal_to(&Mismatch::HeaderMismatch { key: s!(""), expected: s!(""), actual: s!(""), mismatch: s!("") }));
+  expect!(&mismatch).to_not(be_equal_to(&Mismatch::HeaderMismatch { key: s!(""), expected: s!(""), actual: s!(""), mismatch: s!(""), rule: None }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyTypeMismatch { expected: s!(""), actual: s!(""), mismatch: s!(""), expected_body: None, actual_body: None }));
-  expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyMismatch { expected: Some("get".into()), actual: Some("post".into()), mismatch: s!(""), path: s!("/") }));
+  expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyMismatch { expected: Some("get".into()), actual: Some("post".into()), mismatch: s!(""), path: s!("/"), rule: None }));
 }
 
 #[test]
 fn partial_equal_for_header_mismatch() {
-  let mismatch = Mismatch::HeaderMismatch { key: s!("key"), expected: s!("v1"), actual: s!("v2"), mismatch: s!("") };
-  let mismatch2 = Mismatch::HeaderMismatch { key: s!("key"), expected: s!("v1"), actual: s!("v2"), mismatch: s!("") };
-  let mismatch3 = Mismatch::HeaderMismatch { key: s!("key2"), expected: s!("v1"), actual: s!("v2"), mismatch: s!("") };
-  let mismatch4 = Mismatch::HeaderMismatch { key: s!("key"), expected: s!("v100"), actual: s!("v2"), mismatch: s!("") };
-  let mismatch5 = Mismatch::HeaderMismatch { key: s!("key"), expected: s!("v1"), actual: s!("v200"), mismatch: s!("") };
-  let mismatch6 = Mismatch::HeaderMismatch { key: s!("key"), expected: s!("v1"), actual: s!("v2"), mismatch: s!("did not match") };
+  let mismatch = Mismatch::HeaderMismatch { key: s!("key"), expected: s!("v1"), actual: s!("v2"), mismatch: s!(""), rule: None };
+  let mismatch2 = Mismatch::HeaderMismatch { key: s!("key"), expected: s!("v1"), actual: s!("v2"), mismatch: s!(""), rule: None };
+  let mismatch3 = Mismatch::HeaderMismatch { key: s!("key2"), expected: s!("v1"), actual: s!("v2"), mismatch: s!(""), rule: None };
+  let mismatch4 = Mismatch::HeaderMismatch { key: s!("key"), expected: s!("v100"), actual: s!("v2"), mismatch: s!(""), rule: None };
+  let mismatch5 = Mismatch::HeaderMismatch { key: s!("key"), expected: s!("v1"), actual: s!("v200"), mismatch: s!(""), rule: None };
+  let mismatch6 = Mismatch::HeaderMismatch { key: s!("key"), expected: s!("v1"), actual: s!("v2"), mismatch: s!("did not match"), rule: None };
   expect!(&mismatch).to(be_equal_to(&mismatch));
   expect!(&mismatch).to(be_equal_to(&mismatch2));
   expect!(&mismatch).to(be_equal_to(&mismatch6));
@@ -721,17 +803,17 @@ fn partial_equal_for_header_mismatch() {
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::PathMismatch { expected: s!(""), actual: s!(""), mismatch: s!("") }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::QueryMismatch { parameter: s!(""), expected: s!(""), actual: s!(""), mismatch: s!("") }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyTypeMismatch { expected: s!(""), actual: s!(""), mismatch: s!(""), expected_body: None, actual_body: None }));
-  expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyMismatch { expected: Some("get".into()), actual: Some("post".into()), mismatch: s!(""), path: s!("/") }));
+  expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyMismatch { expected: Some("get".into()), actual: Some("post".into()), mismatch: s!(""), path: s!("/"), rule: None }));
 }
 
 #[test]
 fn partial_equal_for_body_mismatch() {
-  let mismatch = Mismatch::BodyMismatch { path: s!("key"), expected: Some("v1".into()), actual: Some("v2".into()), mismatch: s!("") };
-  let mismatch2 = Mismatch::BodyMismatch { path: s!("key"), expected: Some("v1".into()), actual: Some("v2".into()), mismatch: s!("") };
-  let mismatch3 = Mismatch::BodyMismatch { path: s!("key2"), expected: Some("v1".into()), actual: Some("v2".into()), mismatch: s!("") };
-  let mismatch4 = Mismatch::BodyMismatch { path: s!("key"), expected: None, actual: Some("v2".into()), mismatch: s!("") };
-  let mismatch5 = Mismatch::BodyMismatch { path: s!("key"), expected: Some("v1".into()), actual: None, mismatch: s!("") };
-  let mismatch6 = Mismatch::BodyMismatch { path: s!("key"), expected: Some("v1".into()), actual: Some("v2".into()), mismatch: s!("did not match") };
+  let mismatch = Mismatch::BodyMismatch { path: s!("key"), expected: Some("v1".into()), actual: Some("v2".into()), mismatch: s!(""), rule: None };
+  let mismatch2 = Mismatch::BodyMismatch { path: s!("key"), expected: Some("v1".into()), actual: Some("v2".into()), mismatch: s!(""), rule: None };
+  let mismatch3 = Mismatch::BodyMismatch { path: s!("key2"), expected: Some("v1".into()), actual: Some("v2".into()), mismatch: s!(""), rule: None };
+  let mismatch4 = Mismatch::BodyMismatch { path: s!("key"), expected: None, actual: Some("v2".into()), mismatch: s!(""), rule: None };
+  let mismatch5 = Mismatch::BodyMismatch { path: s!("key"), expected: Some("v1".into()), actual: None, mismatch: s!(""), rule: None };
+  let mismatch6 = Mismatch::BodyMismatch { path: s!("key"), expected: Some("v1".into()), actual: Some("v2".into()), mismatch: s!("did not match"), rule: None };
   expect!(&mismatch).to(be_equal_to(&mismatch));
   expect!(&mismatch).to(be_equal_to(&mismatch2));
   expect!(&mismatch).to(be_equal_to(&mismatch6));
@@ -741,7 +823,7 @@ fn partial_equal_for_body_mismatch() {
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::MethodMismatch { expected: s!("get"), actual: s!("post") }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::StatusMismatch { expected: 200, actual: 300, mismatch: "".into() }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::PathMismatch { expected: s!(""), actual: s!(""), mismatch: s!("") }));
-  expect!(&mismatch).to_not(be_equal_to(&Mismatch::HeaderMismatch { key: s!(""), expected: s!(""), actual: s!(""), mismatch: s!("") }));
+  expect!(&mismatch).to_not(be_equal_to(&Mismatch::HeaderMismatch { key: s!(""), expected: s!(""), actual: s!(""), mismatch: s!(""), rule: None }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::BodyTypeMismatch { expected: s!(""), actual: s!(""), mismatch: s!(""), expected_body: None, actual_body: None }));
   expect!(&mismatch).to_not(be_equal_to(&Mismatch::QueryMismatch { parameter: s!(""), expected: s!("get"), actual: s!("post"), mismatch: s!("") }));
 }
@@ -817,6 +899,7 @@ async fn matching_text_body_be_false_when_bodies_are_not_equal() {
     expected: expected.body.value(),
     actual: actual.body.value(),
     mismatch: s!(""),
+    rule: None
   });
 }
 
@@ -936,6 +1019,29 @@ fn match_metadata_value_with_matching_rule_test() {
   expect!(result).to(be_err());
 }
 
+#[test_log::test]
+fn match_metadata_value_with_matching_rules_for_attributes_test() {
+  let expected = json!({ "ID": "sjhdjkshsdjh", "weight": 100.5 });
+  let actual = json!({ "ID": "abc", "weight": 10.0 });
+  let rules = matchingrules!{
+     "metadata" => { "$.TagData.ID" => [ MatchingRule::Type ], "$.TagData.weight" => [ MatchingRule::Number ] }
+  };
+  let context = CoreMatchingContext::new(DiffConfig::AllowUnexpectedKeys,
+    &rules.rules_for_category(Category::METADATA).unwrap_or_default(), &hashmap!{});
+
+  let result = match_metadata_value("TagData", &expected, &actual, &context);
+  expect!(result).to(be_ok());
+
+  let actual_error = json!({ "ID": 100, "weight": 10.0 });
+  let result = match_metadata_value("TagData", &expected, &actual_error, &context);
+  expect!(result.unwrap_err()).to(be_equal_to(vec![Mismatch::MetadataMismatch {
+    key: "TagData".to_string(),
+    expected: expected.to_string(),
+    actual: actual_error.to_string(),
+    mismatch: "Expected metadata key 'TagData' to have value '{\"ID\":\"sjhdjkshsdjh\",\"weight\":100.5}' but was '{\"ID\":100,\"weight\":10.0}' - $.TagData.ID -> Expected 100 (Integer) to be the same type as 'sjhdjkshsdjh' (String)".to_string()
+  }]));
+}
+
 #[test_log::test]
 fn match_metadata_value_with_content_type_test() {
   let expected = json!("application/something");
@@ -952,3 +1058,109 @@ fn match_metadata_value_with_content_type_test() {
   let result = match_metadata_value("key", &expected, &actual, &context);
   expect!(result).to(be_err());
 }
+
+#[tokio::test]
+async fn match_response_uses_the_content_matcher_configured_for_the_interaction() {
+  let expected = HttpResponse {
+    headers: Some(hashmap!{ "Content-Type".to_string() => vec!["text/plain".to_string()] }),
+    body: OptionalBody::Present("{\"a\": 100, \"b\": \"one\"}".into(), Some(TEXT.clone()), None),
+    .. HttpResponse::default()
+  };
+  let actual = HttpResponse {
+    headers: Some(hashmap!{ "Content-Type".to_string() => vec!["text/plain".to_string()] }),
+    body: OptionalBody::Present("{\"b\":\"one\",\"a\":100}".into(), Some(TEXT.clone()), None),
+    .. HttpResponse::default()
+  };
+  let pact = V4Pact::default().boxed();
+
+  let interaction = SynchronousHttp { response: expected.clone(), .. SynchronousHttp::default() };
+  let result = match_response(expected.clone(), actual.clone(), &pact, &interaction.boxed()).await;
+  expect!(result.iter()).to_not(be_empty());
+
+  let interaction = SynchronousHttp {
+    response: expected.clone(),
+    config: hashmap!{ "contentMatcher".to_string() => json!("application/json") },
+    .. SynchronousHttp::default()
+  };
+  let result = match_response(expected.clone(), actual.clone(), &pact, &interaction.boxed()).await;
+  expect!(result.iter()).to(be_empty());
+}
+
+#[tokio::test]
+async fn match_response_matches_the_trailers_if_they_are_expected() {
+  let expected = HttpResponse {
+    trailers: Some(hashmap!{ "grpc-status".to_string() => vec!["0".to_string()] }),
+    matching_rules: matchingrules! {
+      "trailer" => { "grpc-status" => [ MatchingRule::Regex("^[0-9]+$".to_string()) ] }
+    },
+    .. HttpResponse::default()
+  };
+  let pact = V4Pact::default().boxed();
+  let interaction = SynchronousHttp { response: expected.clone(), .. SynchronousHttp::default() };
+
+  let actual = HttpResponse {
+    trailers: Some(hashmap!{ "grpc-status".to_string() => vec!["5".to_string()] }),
+    .. HttpResponse::default()
+  };
+  let result = match_response(expected.clone(), actual, &pact, &interaction.boxed()).await;
+  expect!(result.iter()).to(be_empty());
+
+  let result = match_response(expected.clone(), HttpResponse::default(), &pact, &interaction.boxed()).await;
+  expect!(result).to(be_equal_to(vec![Mismatch::TrailerMismatch {
+    key: "grpc-status".to_string(),
+    expected: "\"0\"".to_string(),
+    actual: "".to_string(),
+    mismatch: "Expected a trailer 'grpc-status' but was missing".to_string()
+  }]));
+
+  let actual = HttpResponse {
+    trailers: Some(hashmap!{ "grpc-status".to_string() => vec!["13".to_string()] }),
+    .. HttpResponse::default()
+  };
+  let result = match_response(HttpResponse::default(), actual, &pact, &interaction.boxed()).await;
+  expect!(result.iter()).to(be_empty());
+}
+
+#[tokio::test]
+async fn match_body_with_rules_test() {
+  let rules = matchingrules_list! {
+    "body";
+    "$.id" => [ MatchingRule::Integer ],
+    "$.name" => [ MatchingRule::Type ]
+  };
+
+  let result = match_body_with_rules(&JSON, Bytes::from(r#"{"id": 1, "name": "Fred"}"#),
+    Bytes::from(r#"{"id": 100, "name": "Bob", "age": 30}"#), &rules).await;
+  expect!(result.iter()).to(be_empty());
+
+  let result = match_body_with_rules(&JSON, Bytes::from(r#"{"id": 1, "name": "Fred"}"#),
+    Bytes::from(r#"{"id": true, "name": "Bob"}"#), &rules).await;
+  expect!(result.iter().map(|m| m.description()).collect::<Vec<_>>()).to(be_equal_to(vec![
+    "$.id -> Expected true (Boolean) to be an integer".to_string()
+  ]));
+
+  let result = match_body_with_rules(&TEXT, Bytes::from("some text"), Bytes::from("other text"),
+    &MatchingRuleCategory::empty("body")).await;
+  expect!(result.len()).to(be_equal_to(1));
+
+  let result = match_body_with_rules(&JSON, Bytes::from(r#"{"id": 1}"#), Bytes::new(), &rules).await;
+  expect!(result.len()).to(be_equal_to(1));
+}
+
+#[test]
+fn content_matcher_override_test() {
+  let interaction = SynchronousHttp::default();
+  expect!(content_matcher_override(&interaction)).to(be_none());
+
+  let interaction = SynchronousHttp {
+    config: hashmap!{ "contentMatcher".to_string() => json!("application/json") },
+    .. SynchronousHttp::default()
+  };
+  expect!(content_matcher_override(&interaction)).to(be_some().value(JSON.clone()));
+
+  let interaction = SynchronousHttp {
+    config: hashmap!{ "contentMatcher".to_string() => json!(100) },
+    .. SynchronousHttp::default()
+  };
+  expect!(content_matcher_override(&interaction)).to(be_none());
+}
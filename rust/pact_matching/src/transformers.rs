@@ -0,0 +1,422 @@
+//! Transformations that are applied to the bodies before they are matched, for instance to
+//! decompress bodies that have a content encoding.
+//!
+//! Transformers are registered against the content encoding they handle, which is taken from the
+//! `Content-Encoding` header for HTTP requests and responses, and the `contentEncoding` metadata
+//! value for messages. If there are multiple encodings, the transformers are applied in the
+//! reverse order to how the encodings were applied. gzip and deflate are supported by default, and
+//! Brotli (`br`) and zstd with the `brotli` and `zstd` features.
+//! Other encodings (for example, decrypting JWE payloads with a known key) can be supported by
+//! registering a transformer with [`register_body_transformer`].
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io::Read;
+use std::sync::{Arc, RwLock};
+
+use anyhow::anyhow;
+use bytes::Bytes;
+use flate2::read::{MultiGzDecoder, ZlibDecoder};
+use lazy_static::lazy_static;
+use pact_models::bodies::OptionalBody;
+use pact_models::generators::Generators;
+use pact_models::http_parts::HttpPart;
+use pact_models::json_utils::json_to_string;
+use pact_models::matchingrules::MatchingRules;
+use pact_models::v4::message_parts::MessageContents;
+use tracing::{debug, warn};
+
+/// Transforms a body before it is matched
+pub trait BodyTransformer: Debug + Send + Sync {
+  /// Name of the transformer, used in log and error messages
+  fn name(&self) -> String;
+
+  /// If the transformer can be applied to the body. Bodies it can not be applied to (for
+  /// instance, an expected body from a Pact file that has not been encoded) are matched as is.
+  fn applies_to(&self, _body: &[u8]) -> bool {
+    true
+  }
+
+  /// Transforms the body, returning an error if the body is not valid for the transformation
+  fn transform(&self, body: &[u8]) -> anyhow::Result<Bytes>;
+}
+
+/// Decompresses gzip encoded bodies
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GzipTransformer;
+
+impl BodyTransformer for GzipTransformer {
+  fn name(&self) -> String {
+    "gzip".to_string()
+  }
+
+  fn applies_to(&self, body: &[u8]) -> bool {
+    body.starts_with(&[0x1f, 0x8b])
+  }
+
+  fn transform(&self, body: &[u8]) -> anyhow::Result<Bytes> {
+    let mut buffer = vec![];
+    MultiGzDecoder::new(body).read_to_end(&mut buffer)?;
+    Ok(Bytes::from(buffer))
+  }
+}
+
+/// Decompresses deflate (zlib) encoded bodies
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeflateTransformer;
+
+impl BodyTransformer for DeflateTransformer {
+  fn name(&self) -> String {
+    "deflate".to_string()
+  }
+
+  fn applies_to(&self, body: &[u8]) -> bool {
+    body.len() >= 2 && body[0] & 0x0f == 8 && (((body[0] as u16) << 8) | body[1] as u16) % 31 == 0
+  }
+
+  fn transform(&self, body: &[u8]) -> anyhow::Result<Bytes> {
+    let mut buffer = vec![];
+    ZlibDecoder::new(body).read_to_end(&mut buffer)?;
+    Ok(Bytes::from(buffer))
+  }
+}
+
+/// Decompresses Brotli (br) encoded bodies
+#[cfg(feature = "brotli")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BrotliTransformer;
+
+#[cfg(feature = "brotli")]
+impl BodyTransformer for BrotliTransformer {
+  fn name(&self) -> String {
+    "brotli".to_string()
+  }
+
+  /// Brotli streams have no magic number, so the body is only treated as Brotli encoded if it
+  /// can be decoded
+  fn applies_to(&self, body: &[u8]) -> bool {
+    self.transform(body).is_ok()
+  }
+
+  fn transform(&self, body: &[u8]) -> anyhow::Result<Bytes> {
+    let mut buffer = vec![];
+    brotli::Decompressor::new(body, 4096).read_to_end(&mut buffer)?;
+    Ok(Bytes::from(buffer))
+  }
+}
+
+/// Decompresses zstd encoded bodies
+#[cfg(feature = "zstd")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZstdTransformer;
+
+#[cfg(feature = "zstd")]
+impl BodyTransformer for ZstdTransformer {
+  fn name(&self) -> String {
+    "zstd".to_string()
+  }
+
+  fn applies_to(&self, body: &[u8]) -> bool {
+    body.starts_with(&[0x28, 0xb5, 0x2f, 0xfd])
+  }
+
+  fn transform(&self, body: &[u8]) -> anyhow::Result<Bytes> {
+    Ok(Bytes::from(zstd::stream::decode_all(body)?))
+  }
+}
+
+lazy_static! {
+  static ref BODY_TRANSFORMERS: RwLock<HashMap<String, Arc<dyn BodyTransformer>>> = {
+    let mut transformers: HashMap<String, Arc<dyn BodyTransformer>> = HashMap::new();
+    transformers.insert("gzip".to_string(), Arc::new(GzipTransformer));
+    transformers.insert("x-gzip".to_string(), Arc::new(GzipTransformer));
+    transformers.insert("deflate".to_string(), Arc::new(DeflateTransformer));
+    #[cfg(feature = "brotli")] transformers.insert("br".to_string(), Arc::new(BrotliTransformer));
+    #[cfg(feature = "zstd")] transformers.insert("zstd".to_string(), Arc::new(ZstdTransformer));
+    RwLock::new(transformers)
+  };
+}
+
+/// Registers a transformer for the content encoding, replacing any existing one. Content
+/// encodings are case-insensitive.
+pub fn register_body_transformer(content_encoding: &str, transformer: Arc<dyn BodyTransformer>) {
+  let mut transformers = BODY_TRANSFORMERS.write().unwrap();
+  transformers.insert(content_encoding.to_lowercase(), transformer);
+}
+
+/// Removes the transformer for the content encoding, returning it if there was one
+pub fn remove_body_transformer(content_encoding: &str) -> Option<Arc<dyn BodyTransformer>> {
+  let mut transformers = BODY_TRANSFORMERS.write().unwrap();
+  transformers.remove(&content_encoding.to_lowercase())
+}
+
+/// Returns the transformer registered for the content encoding
+pub fn body_transformer(content_encoding: &str) -> Option<Arc<dyn BodyTransformer>> {
+  let transformers = BODY_TRANSFORMERS.read().unwrap();
+  transformers.get(&content_encoding.to_lowercase()).cloned()
+}
+
+/// Applies the transformers for the content encodings to the body. The content encodings are
+/// given in the order they were applied (i.e. the value of a `Content-Encoding` header). If there
+/// is no transformer registered for an encoding, the body is returned as it was at that point.
+pub fn transform_body(body: &OptionalBody, content_encoding: &str) -> anyhow::Result<OptionalBody> {
+  let mut result = body.clone();
+  let encodings = content_encoding.split(',')
+    .map(|encoding| encoding.trim())
+    .filter(|encoding| !encoding.is_empty() && !encoding.eq_ignore_ascii_case("identity"))
+    .collect::<Vec<_>>();
+  for encoding in encodings.iter().rev() {
+    if let OptionalBody::Present(bytes, content_type, hint) = &result {
+      match body_transformer(encoding) {
+        Some(transformer) => if transformer.applies_to(bytes) {
+          debug!("Applying the {} transformer to the body", transformer.name());
+          let transformed = transformer.transform(bytes)
+            .map_err(|err| anyhow!("Failed to apply the {} transformer to the body - {}", transformer.name(), err))?;
+          result = OptionalBody::Present(transformed, content_type.clone(), *hint);
+        } else {
+          debug!("The {} transformer does not apply to the body, leaving it as is", transformer.name());
+        },
+        None => {
+          warn!("There is no body transformer registered for content encoding '{}'", encoding);
+          break;
+        }
+      }
+    }
+  }
+  Ok(result)
+}
+
+/// Part with a transformed body. All the other attributes are copied from the original part.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TransformedPart {
+  headers: Option<HashMap<String, Vec<String>>>,
+  body: OptionalBody,
+  matching_rules: MatchingRules,
+  generators: Generators,
+  content_type: Option<String>
+}
+
+impl HttpPart for TransformedPart {
+  fn headers(&self) -> &Option<HashMap<String, Vec<String>>> {
+    &self.headers
+  }
+
+  fn headers_mut(&mut self) -> &mut HashMap<String, Vec<String>> {
+    self.headers.get_or_insert_with(HashMap::new)
+  }
+
+  fn body(&self) -> &OptionalBody {
+    &self.body
+  }
+
+  fn body_mut(&mut self) -> &mut OptionalBody {
+    &mut self.body
+  }
+
+  fn matching_rules(&self) -> &MatchingRules {
+    &self.matching_rules
+  }
+
+  fn matching_rules_mut(&mut self) -> &mut MatchingRules {
+    &mut self.matching_rules
+  }
+
+  fn generators(&self) -> &Generators {
+    &self.generators
+  }
+
+  fn generators_mut(&mut self) -> &mut Generators {
+    &mut self.generators
+  }
+
+  fn lookup_content_type(&self) -> Option<String> {
+    self.content_type.clone()
+  }
+}
+
+/// Applies the transformers for the `Content-Encoding` header of an HTTP request or response.
+/// Returns `None` if the part does not have a content encoding.
+pub(crate) fn transform_http_part(part: &(dyn HttpPart + Send + Sync)) -> anyhow::Result<Option<TransformedPart>> {
+  match part.lookup_header_value("content-encoding") {
+    Some(encoding) if part.body().is_present() => Ok(Some(TransformedPart {
+      headers: part.headers().clone(),
+      body: transform_body(part.body(), encoding.as_str())?,
+      matching_rules: part.matching_rules().clone(),
+      generators: part.generators().clone(),
+      content_type: part.lookup_content_type()
+    })),
+    _ => Ok(None)
+  }
+}
+
+/// Applies the transformers for the `contentEncoding` metadata value of a message. Returns `None`
+/// if the message does not have a content encoding.
+pub(crate) fn transform_message_contents(contents: &MessageContents) -> anyhow::Result<Option<MessageContents>> {
+  let encoding = contents.metadata.iter()
+    .find(|(key, _)| {
+      let key = key.to_ascii_lowercase();
+      key == "contentencoding" || key == "content-encoding"
+    })
+    .map(|(_, value)| json_to_string(value));
+  match encoding {
+    Some(encoding) if contents.contents.is_present() => Ok(Some(MessageContents {
+      contents: transform_body(&contents.contents, encoding.as_str())?,
+      .. contents.clone()
+    })),
+    _ => Ok(None)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::Write;
+
+  use expectest::prelude::*;
+  use flate2::Compression;
+  use flate2::write::{GzEncoder, ZlibEncoder};
+  use maplit::hashmap;
+  use pact_models::v4::http_parts::HttpResponse;
+
+  use crate::{BodyMatchResult, CoreMatchingContext, DiffConfig, HeaderMatchingContext, match_body};
+
+  use super::*;
+
+  fn gzip(data: &str) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(data.as_bytes()).unwrap();
+    encoder.finish().unwrap()
+  }
+
+  fn deflate(data: &str) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+    encoder.write_all(data.as_bytes()).unwrap();
+    encoder.finish().unwrap()
+  }
+
+  #[cfg(feature = "brotli")]
+  fn brotli(data: &str) -> Vec<u8> {
+    let mut buffer = vec![];
+    brotli::CompressorWriter::new(&mut buffer, 4096, 5, 22).write_all(data.as_bytes()).unwrap();
+    buffer
+  }
+
+  #[derive(Debug)]
+  struct ReverseTransformer;
+
+  impl BodyTransformer for ReverseTransformer {
+    fn name(&self) -> String {
+      "reverse".to_string()
+    }
+
+    fn transform(&self, body: &[u8]) -> anyhow::Result<Bytes> {
+      Ok(Bytes::from(body.iter().rev().cloned().collect::<Vec<u8>>()))
+    }
+  }
+
+  #[test]
+  fn transform_body_test() {
+    let body = OptionalBody::Present(Bytes::from(gzip("{\"a\":1}")), None, None);
+    expect!(transform_body(&body, "gzip").unwrap().value_as_string()).to(be_some().value("{\"a\":1}"));
+    expect!(transform_body(&body, "GZIP").unwrap().value_as_string()).to(be_some().value("{\"a\":1}"));
+
+    let body = OptionalBody::Present(Bytes::from(deflate("{\"a\":1}")), None, None);
+    expect!(transform_body(&body, "deflate").unwrap().value_as_string()).to(be_some().value("{\"a\":1}"));
+
+    let body = OptionalBody::from("{\"a\":1}");
+    expect!(transform_body(&body, "gzip").unwrap()).to(be_equal_to(body.clone()));
+    expect!(transform_body(&body, "deflate").unwrap()).to(be_equal_to(body.clone()));
+    expect!(transform_body(&body, "identity").unwrap()).to(be_equal_to(body.clone()));
+    expect!(transform_body(&body, "unknown").unwrap()).to(be_equal_to(body.clone()));
+    expect!(transform_body(&OptionalBody::Missing, "gzip").unwrap()).to(be_equal_to(OptionalBody::Missing));
+
+    let mut invalid = gzip("{\"a\":1}");
+    invalid.truncate(12);
+    expect!(transform_body(&OptionalBody::Present(Bytes::from(invalid), None, None), "gzip")).to(be_err());
+  }
+
+  #[test]
+  #[cfg(feature = "brotli")]
+  fn transform_body_decodes_brotli_bodies() {
+    let body = OptionalBody::Present(Bytes::from(brotli("{\"a\":1}")), None, None);
+    expect!(transform_body(&body, "br").unwrap().value_as_string()).to(be_some().value("{\"a\":1}"));
+
+    let body = OptionalBody::Present(Bytes::from(brotli("}1:\"a\"{")), None, None);
+    register_body_transformer("test-reverse-br", Arc::new(ReverseTransformer));
+    expect!(transform_body(&body, "test-reverse-br, br").unwrap().value_as_string())
+      .to(be_some().value("{\"a\":1}"));
+    remove_body_transformer("test-reverse-br");
+
+    let body = OptionalBody::from("{\"a\":1}");
+    expect!(transform_body(&body, "br").unwrap()).to(be_equal_to(body.clone()));
+  }
+
+  #[test]
+  #[cfg(feature = "zstd")]
+  fn transform_body_decodes_zstd_bodies() {
+    let compressed = zstd::stream::encode_all("{\"a\":1}".as_bytes(), 0).unwrap();
+    let body = OptionalBody::Present(Bytes::from(compressed.clone()), None, None);
+    expect!(transform_body(&body, "zstd").unwrap().value_as_string()).to(be_some().value("{\"a\":1}"));
+
+    let body = OptionalBody::from("{\"a\":1}");
+    expect!(transform_body(&body, "zstd").unwrap()).to(be_equal_to(body.clone()));
+
+    let mut invalid = compressed;
+    invalid.truncate(6);
+    expect!(transform_body(&OptionalBody::Present(Bytes::from(invalid), None, None), "zstd")).to(be_err());
+  }
+
+  #[test]
+  fn transform_body_applies_the_transformers_in_reverse_order() {
+    register_body_transformer("test-reverse", Arc::new(ReverseTransformer));
+    let body = OptionalBody::Present(Bytes::from(gzip("}1:\"a\"{")), None, None);
+    expect!(transform_body(&body, "test-reverse, gzip").unwrap().value_as_string())
+      .to(be_some().value("{\"a\":1}"));
+    expect!(remove_body_transformer("TEST-REVERSE")).to(be_some());
+    expect!(body_transformer("test-reverse")).to(be_none());
+  }
+
+  #[test]
+  fn transform_message_contents_uses_the_content_encoding_metadata() {
+    let contents = MessageContents {
+      contents: OptionalBody::Present(Bytes::from(gzip("{\"a\":1}")), None, None),
+      metadata: hashmap!{ "contentEncoding".to_string() => serde_json::json!("gzip") },
+      .. MessageContents::default()
+    };
+    let transformed = transform_message_contents(&contents).unwrap().unwrap();
+    expect!(transformed.contents.value_as_string()).to(be_some().value("{\"a\":1}"));
+    expect!(transformed.metadata).to(be_equal_to(contents.metadata.clone()));
+
+    expect!(transform_message_contents(&MessageContents::default()).unwrap()).to(be_none());
+  }
+
+  #[test_log::test(tokio::test)]
+  async fn match_body_decompresses_gzip_encoded_bodies() {
+    let expected = HttpResponse {
+      headers: Some(hashmap!{
+        "Content-Type".to_string() => vec!["application/json".to_string()],
+        "Content-Encoding".to_string() => vec!["gzip".to_string()]
+      }),
+      body: OptionalBody::from("{\"a\":1}"),
+      .. HttpResponse::default()
+    };
+    let actual = HttpResponse {
+      body: OptionalBody::Present(Bytes::from(gzip("{\"a\":1}")), None, None),
+      .. expected.clone()
+    };
+    let context = CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys);
+    let header_context = HeaderMatchingContext::new(&context);
+    expect!(match_body(&expected, &actual, &context, &header_context).await).to(be_equal_to(BodyMatchResult::Ok));
+
+    let actual = HttpResponse {
+      body: OptionalBody::Present(Bytes::from(gzip("{\"a\":2}")), None, None),
+      .. expected.clone()
+    };
+    expect!(match_body(&expected, &actual, &context, &header_context).await.all_matched()).to(be_false());
+
+    let actual = HttpResponse {
+      body: OptionalBody::Present(Bytes::from(vec![0x1f, 0x8b, 0x08]), None, None),
+      .. expected.clone()
+    };
+    expect!(match_body(&expected, &actual, &context, &header_context).await.all_matched()).to(be_false());
+  }
+}
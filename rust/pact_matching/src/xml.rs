@@ -16,7 +16,7 @@ use pact_models::xml_utils::parse_bytes;
 use tracing::debug;
 
 use crate::matchers::*;
-use crate::MatchingContext;
+use crate::{MatchingContext, XmlMatchingOptions};
 
 use super::DiffConfig;
 use super::Mismatch;
@@ -41,14 +41,14 @@ pub fn match_xml(
             path: "$".to_string(),
             expected: expected.body().value(),
             actual: actual.body().value(),
-            mismatch: format!("Failed to parse the expected body: '{:?}'", e)});
+            mismatch: format!("Failed to parse the expected body: '{:?}'", e), rule: None});
         }
         if let Err(e) = actual_result {
           mismatches.push(Mismatch::BodyMismatch {
             path: "$".to_string(),
             expected: expected.body().value(),
             actual: actual.body().value(),
-            mismatch: format!("Failed to parse the actual body: '{:?}'", e)});
+            mismatch: format!("Failed to parse the actual body: '{:?}'", e), rule: None});
         }
       } else {
         let expected_package = expected_result.unwrap();
@@ -68,7 +68,8 @@ pub fn match_xml(
         path: "$".into(),
         expected: expected.body().value(),
         actual: None,
-        mismatch: format!("Expected an XML body {} but was missing", expected.body())
+        mismatch: format!("Expected an XML body {} but was missing", expected.body()),
+        rule: None
       });
     }
   }
@@ -152,11 +153,11 @@ fn compare_element(
   mismatches: &mut Vec<super::Mismatch>,
   context: &dyn MatchingContext
 ) {
-  let matcher_result = if context.matcher_is_defined(path) {
+  let (matcher_result, rule) = if context.matcher_is_defined(path) {
     debug!("calling match_values {:?} on {:?}", path, actual);
-    match_values(path, &context.select_best_matcher(&path), expected, actual)
+    (match_values(path, &context.select_best_matcher(&path), expected, actual), context.rule_trace(path))
   } else {
-    expected.matches_with(actual, &MatchingRule::Equality, false).map_err(|err| vec![err.to_string()])
+    (expected.matches_with(actual, &MatchingRule::Equality, false).map_err(|err| vec![err.to_string()]), None)
   };
   debug!("Comparing '{:?}' to '{:?}' at path '{}' -> {:?}", expected, actual, path, matcher_result);
   match matcher_result {
@@ -166,7 +167,8 @@ fn compare_element(
           path: path.to_string(),
           expected: Some(name(expected.name()).into()),
           actual: Some(name(actual.name()).into()),
-          mismatch: message.clone()
+          mismatch: message.clone(),
+          rule: rule.clone()
         })
       }
     },
@@ -194,7 +196,8 @@ fn compare_attributes(
         path: path.to_string(),
         expected: Some(format!("{:?}", expected_attributes).into()),
         actual: Some(format!("{:?}", actual_attributes).into()),
-        mismatch: format!("Did not expect any attributes but received {:?}", actual_attributes)
+        mismatch: format!("Did not expect any attributes but received {:?}", actual_attributes),
+        rule: None
       });
     } else {
         match context.config() {
@@ -203,14 +206,14 @@ fn compare_attributes(
                     expected: Some(format!("{:?}", expected_attributes).into()),
                     actual: Some(format!("{:?}", actual_attributes).into()),
                     mismatch: format!("Expected at least {} attribute(s) but received {} attribute(s)",
-                    expected_attributes.len(), actual_attributes.len())});
+                    expected_attributes.len(), actual_attributes.len()), rule: None});
             },
             DiffConfig::NoUnexpectedKeys if expected_attributes.len() != actual_attributes.len() => {
                 mismatches.push(Mismatch::BodyMismatch { path: path.to_string(),
                     expected: Some(format!("{:?}", expected_attributes).into()),
                     actual: Some(format!("{:?}", actual_attributes).into()),
                     mismatch: format!("Expected {} attribute(s) but received {} attribute(s)",
-                    expected_attributes.len(), actual_attributes.len())});
+                    expected_attributes.len(), actual_attributes.len()), rule: None});
             },
             _ => ()
         }
@@ -226,7 +229,8 @@ fn compare_attributes(
               path: p.to_string(),
               expected: Some(Bytes::from(key.clone())),
               actual: None,
-              mismatch: format!("Expected attribute '{}'='{}' but was missing", key, value)
+              mismatch: format!("Expected attribute '{}'='{}' but was missing", key, value),
+              rule: None
             });
           }
         }
@@ -259,7 +263,8 @@ fn compare_children(
       path: path.to_string(),
       expected: Some(desc_children(&expected_children).into()),
       actual: Some(desc_children(&actual_children).into()),
-      mismatch: format!("Expected no children but received [{}]", desc_children(&actual_children))
+      mismatch: format!("Expected no children but received [{}]", desc_children(&actual_children)),
+      rule: None
     });
   } else {
     let mut expected_children_by_name: BTreeMap<String, Vec<Element>> = btreemap!{};
@@ -296,7 +301,8 @@ fn compare_children(
                   path: p.to_string(),
                   expected: Some(desc_children(&expected_children).into()),
                   actual: Some(desc_children(&actual_children).into()),
-                  mismatch: format!("Unexpected child <{}/>", name(actual.name()))
+                  mismatch: format!("Unexpected child <{}/>", name(actual.name())),
+                  rule: None
                 });
               },
               EitherOrBoth::Left(expected) => {
@@ -304,7 +310,8 @@ fn compare_children(
                   path: p.to_string(),
                   expected: Some(desc_children(&expected_children.clone()).into()),
                   actual: Some(desc_children(&actual_children.clone()).into()),
-                  mismatch: format!("Expected child <{}/> but was missing", name(expected.name()))
+                  mismatch: format!("Expected child <{}/> but was missing", name(expected.name())),
+                  rule: None
                 });
               },
               EitherOrBoth::Both(expected, actual) => {
@@ -318,7 +325,8 @@ fn compare_children(
           path: path.to_string(),
           expected: Some(desc_children(&expected_children.clone()).into()),
           actual: Some(desc_children(&actual_children.clone()).into()),
-          mismatch: format!("Unexpected child <{}/>", key)
+          mismatch: format!("Unexpected child <{}/>", key),
+          rule: None
         });
       }
     }
@@ -329,7 +337,8 @@ fn compare_children(
           path: path.to_string(),
           expected: Some(desc_children(&expected_children.clone()).into()),
           actual: Some(desc_children(&actual_children.clone()).into()),
-          mismatch: format!("Expected child <{}/> but was missing", key)
+          mismatch: format!("Expected child <{}/> but was missing", key),
+          rule: None
         });
       }
     }
@@ -343,20 +352,16 @@ fn compare_text(
   mismatches: &mut Vec<super::Mismatch>,
   context: &dyn MatchingContext
 ) {
-    let expected_text = expected.children().iter().cloned()
-        .filter(|child| child.text().is_some())
-        .map(|child| child.text().unwrap().text().trim())
-        .collect::<String>();
-    let actual_text = actual.children().iter().cloned()
-        .filter(|child| child.text().is_some())
-        .map(|child| child.text().unwrap().text().trim())
-        .collect::<String>();
+    let options = context.xml_options();
+    let expected_text = element_text(expected, &options);
+    let actual_text = element_text(actual, &options);
     let p = path.join("#text");
-    let matcher_result = if context.matcher_is_defined(&p) {
-      match_values(&p, &context.select_best_matcher(&p), expected_text.trim(), actual_text.trim())
+    let (matcher_result, rule) = if context.matcher_is_defined(&p) {
+      (match_values(&p, &context.select_best_matcher(&p), expected_text.as_str(), actual_text.as_str()),
+        context.rule_trace(&p))
     } else {
-      expected_text.matches_with(actual_text.trim(), &MatchingRule::Equality, false)
-        .map_err(|err| vec![err.to_string()])
+      (expected_text.matches_with(actual_text.as_str(), &MatchingRule::Equality, false)
+        .map_err(|err| vec![err.to_string()]), None)
     };
     debug!("Comparing text '{}' to '{}' at path '{}' -> {:?}", expected_text, actual_text,
         path.to_string(), matcher_result);
@@ -366,22 +371,32 @@ fn compare_text(
           path: p.to_string(),
           expected: Some(expected_text.clone().into()),
           actual: Some(actual_text.clone().into()),
-          mismatch: message.clone()
+          mismatch: message.clone(),
+          rule: rule.clone()
         })
       }
     }
 }
 
+fn element_text(element: &Element, options: &XmlMatchingOptions) -> String {
+  element.children().iter()
+    .filter_map(|child| child.text())
+    .map(|text| text.text())
+    .filter(|text| !options.strip_whitespace_text || !text.trim().is_empty())
+    .map(|text| if options.trim_text { text.trim() } else { text })
+    .collect()
+}
+
 fn compare_value(
   path: &DocPath,
   expected: &str,
   actual: &str,
   context: &dyn MatchingContext
 ) -> Result<(), Vec<Mismatch>> {
-  let matcher_result = if context.matcher_is_defined(path) {
-    match_values(path, &context.select_best_matcher(&path), expected, actual)
+  let (matcher_result, rule) = if context.matcher_is_defined(path) {
+    (match_values(path, &context.select_best_matcher(&path), expected, actual), context.rule_trace(path))
   } else {
-    expected.matches_with(actual, &MatchingRule::Equality, false).map_err(|err| vec![err.to_string()])
+    (expected.matches_with(actual, &MatchingRule::Equality, false).map_err(|err| vec![err.to_string()]), None)
   };
   debug!("Comparing '{}' to '{}' at path '{}' -> {:?}", expected, actual, path, matcher_result);
   matcher_result.map_err(|messages| {
@@ -390,7 +405,8 @@ fn compare_value(
         path: path.to_string(),
         expected: Some(expected.to_string().into()),
         actual: Some(actual.to_string().into()),
-        mismatch: message.clone()
+        mismatch: message.clone(),
+        rule: rule.clone()
       }
     }).collect()
   })
@@ -409,7 +425,7 @@ mod tests {
   use pact_models::matchingrules::MatchingRule;
   use pact_models::request::Request;
 
-  use crate::{CoreMatchingContext, DiffConfig};
+  use crate::{CoreMatchingContext, DiffConfig, XmlMatchingOptions};
   use crate::Mismatch;
 
   use super::*;
@@ -466,7 +482,8 @@ mod tests {
       path: s!("$"),
       expected: Some(Bytes::new()),
       actual: Some(Bytes::new()),
-      mismatch: s!("")
+      mismatch: s!(""),
+      rule: None
     }));
   }
 
@@ -479,7 +496,8 @@ mod tests {
       path: s!("$"),
       expected: expected.body.value(),
       actual: actual.body.value(),
-      mismatch: s!("")
+      mismatch: s!(""),
+      rule: None
     } ]));
   }
 
@@ -492,7 +510,8 @@ mod tests {
       path: s!("$"),
       expected: expected.body.value(),
       actual: actual.body.value(),
-      mismatch: s!("")
+      mismatch: s!(""),
+      rule: None
     } ]));
   }
 
@@ -555,7 +574,7 @@ mod tests {
     let result = match_xml(&expected, &actual, &CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys));
     expect!(mismatch_message(&result)).to(be_equal_to(s!("Expected 'foo' to be equal to 'bar'")));
     expect!(result).to(be_err().value(vec![ Mismatch::BodyMismatch { path: s!("$.foo"), expected: Some("foo".into()),
-        actual: Some("bar".into()), mismatch: s!("") } ]));
+        actual: Some("bar".into()), mismatch: s!(""), rule: None } ]));
   }
 
   #[test]
@@ -584,15 +603,15 @@ mod tests {
     let mismatch = mismatches[0].clone();
     expect!(&mismatch).to(be_equal_to(&Mismatch::BodyMismatch { path: "$.blah".to_string(),
         expected: Some("{\"a\": \"c\", \"c\": \"b\"}".into()),
-        actual: Some("{\"a\": \"b\"}".into()), mismatch: "".to_string()}));
+        actual: Some("{\"a\": \"b\"}".into()), mismatch: "".to_string(), rule: None}));
     expect!(mismatch.description()).to(be_equal_to("$.blah -> Expected at least 2 attribute(s) but received 1 attribute(s)".to_string()));
     let mismatch = mismatches[1].clone();
     expect!(&mismatch).to(be_equal_to(&Mismatch::BodyMismatch { path: "$.blah['@a']".to_string(), expected: Some("c".into()),
-        actual: Some("b".into()), mismatch: "".to_string()}));
+        actual: Some("b".into()), mismatch: "".to_string(), rule: None}));
     expect!(mismatch.description()).to(be_equal_to("$.blah['@a'] -> Expected 'b' to be equal to 'c'".to_string()));
     let mismatch = mismatches[2].clone();
     expect!(&mismatch).to(be_equal_to(&Mismatch::BodyMismatch { path: "$.blah['@c']".to_string(), expected: Some("c".into()),
-        actual: None, mismatch: "".to_string() }));
+        actual: None, mismatch: "".to_string(), rule: None }));
     expect!(mismatch.description()).to(be_equal_to("$.blah['@c'] -> Expected attribute \'c\'=\'b\' but was missing".to_string()));
   }
 
@@ -607,7 +626,7 @@ mod tests {
     let result = match_xml(&expected, &actual, &CoreMatchingContext::with_config(DiffConfig::NoUnexpectedKeys));
     expect!(mismatch_message(&result)).to(be_equal_to(s!("Did not expect any attributes but received {\"a\": \"b\", \"c\": \"d\"}")));
     expect!(result).to(be_err().value(vec![ Mismatch::BodyMismatch { path: s!("$.blah"), expected: Some("{}".into()),
-      actual: Some("{\"a\": \"b\", \"c\": \"d\"}".into()), mismatch: s!("") } ]));
+      actual: Some("{\"a\": \"b\", \"c\": \"d\"}".into()), mismatch: s!(""), rule: None } ]));
   }
 
   #[test]
@@ -633,7 +652,7 @@ mod tests {
     let result = match_xml(&expected, &actual, &CoreMatchingContext::with_config(DiffConfig::NoUnexpectedKeys));
     expect!(mismatch_message(&result)).to(be_equal_to(s!("Expected 1 attribute(s) but received 2 attribute(s)")));
     expect!(result).to(be_err().value(vec![ Mismatch::BodyMismatch { path: s!("$.foo"), expected: Some("{\"something\": \"100\"}".into()),
-        actual: Some("{\"something\": \"100\", \"somethingElse\": \"101\"}".into()), mismatch: s!("") } ]));
+        actual: Some("{\"something\": \"100\", \"somethingElse\": \"101\"}".into()), mismatch: s!(""), rule: None } ]));
   }
 
   #[test]
@@ -647,7 +666,7 @@ mod tests {
     let result = match_xml(&expected, &actual, &CoreMatchingContext::with_config(DiffConfig::NoUnexpectedKeys));
     expect!(mismatch_message(&result)).to(be_equal_to("Expected attribute \'somethingElse\'=\'100\' but was missing".to_string()));
     expect!(result).to(be_err().value(vec![ Mismatch::BodyMismatch { path: "$.foo['@somethingElse']".to_string(), expected: Some("somethingElse".into()),
-        actual: None, mismatch: "".to_string() } ]));
+        actual: None, mismatch: "".to_string(), rule: None } ]));
   }
 
   #[test]
@@ -661,7 +680,7 @@ mod tests {
     let result = match_xml(&expected.clone(), &actual.clone(), &CoreMatchingContext::with_config(DiffConfig::NoUnexpectedKeys));
     expect!(mismatch_message(&result)).to(be_equal_to("Expected '101' to be equal to '100'".to_string()));
     expect!(result).to(be_err().value(vec![ Mismatch::BodyMismatch { path: "$.foo['@somethingElse']".to_string(), expected: Some("100".into()),
-        actual: Some("101".into()), mismatch: "".to_string() } ]));
+        actual: Some("101".into()), mismatch: "".to_string(), rule: None } ]));
 
     let result = match_xml(&expected, &actual, &CoreMatchingContext::new(DiffConfig::NoUnexpectedKeys, &matchingrules!{
       "body" => {
@@ -685,7 +704,8 @@ mod tests {
       path: s!("$.foo"),
       expected: Some(Bytes::new()),
       actual: Some("bar".into()),
-      mismatch: s!("")
+      mismatch: s!(""),
+      rule: None
     } ]));
   }
 
@@ -727,7 +747,8 @@ mod tests {
       path: s!("$.foo"),
       expected: Some("bar".into()),
       actual: Some(Bytes::new()),
-      mismatch: s!("")
+      mismatch: s!(""),
+      rule: None
     } ]));
   }
 
@@ -743,7 +764,7 @@ mod tests {
     expect!(mismatch_message(&result)).to(be_equal_to(s!("Expected child <four/> but was missing")));
     expect!(result).to(be_err().value(vec![ Mismatch::BodyMismatch { path: s!("$.foo"),
         expected: Some("one, two, three, four".into()),
-        actual: Some("one, two, three".into()), mismatch: s!("") } ]));
+        actual: Some("one, two, three".into()), mismatch: s!(""), rule: None } ]));
   }
 
   #[test]
@@ -760,12 +781,12 @@ mod tests {
     let mismatch = mismatches[0].clone();
     expect!(&mismatch).to(be_equal_to(&Mismatch::BodyMismatch { path: s!("$.foo"),
       expected: Some("one, two, three, three".into()),
-      actual: Some("one, two, three, four".into()), mismatch: s!("")}));
+      actual: Some("one, two, three, four".into()), mismatch: s!(""), rule: None}));
     expect!(mismatch.description()).to(be_equal_to(s!("$.foo -> Unexpected child <four/>")));
     let mismatch = mismatches[1].clone();
     expect!(&mismatch).to(be_equal_to(&Mismatch::BodyMismatch { path: s!("$.foo.three"),
       expected: Some("three, three".into()),
-      actual: Some("one, two, three, four".into()), mismatch: s!("")}));
+      actual: Some("one, two, three, four".into()), mismatch: s!(""), rule: None}));
     expect!(mismatch.description()).to(be_equal_to(s!("$.foo.three -> Expected child <three/> but was missing")));
   }
 
@@ -817,7 +838,7 @@ mod tests {
     expect!(mismatch_message(&result)).to(be_equal_to(s!("Expected 'hello mars' to be equal to 'hello world'")));
     expect!(result).to(be_err().value(vec![ Mismatch::BodyMismatch { path: "$.foo['#text']".to_string(),
         expected: Some("hello world".into()),
-        actual: Some("hello mars".into()), mismatch: "".to_string() } ]));
+        actual: Some("hello mars".into()), mismatch: "".to_string(), rule: None } ]));
 
     let result = match_xml(&expected, &actual, &CoreMatchingContext::new(DiffConfig::AllowUnexpectedKeys, &matchingrules!{
         "body" => {
@@ -839,7 +860,7 @@ mod tests {
     expect!(mismatch_message(&result)).to(be_equal_to("Expected 'hellomars' to be equal to 'helloworld'".to_string()));
     expect!(result).to(be_err().value(vec![ Mismatch::BodyMismatch { path: "$.foo['#text']".to_string(),
         expected: Some("helloworld".into()),
-        actual: Some("hellomars".into()), mismatch: "".to_string() } ]));
+        actual: Some("hellomars".into()), mismatch: "".to_string(), rule: None } ]));
 
     let result = match_xml(&expected, &actual, &CoreMatchingContext::new(DiffConfig::AllowUnexpectedKeys, &matchingrules!{
       "body" => {
@@ -849,6 +870,44 @@ mod tests {
     expect!(result).to(be_ok());
   }
 
+  #[test]
+  fn match_xml_ignores_the_order_of_attributes() {
+    let expected = request!(r#"<?xml version="1.0" encoding="UTF-8"?><blah a="b" c="d"/>"#);
+    let actual = request!(r#"<?xml version="1.0" encoding="UTF-8"?><blah c="d" a="b"/>"#);
+    let result = match_xml(&expected, &actual, &CoreMatchingContext::with_config(DiffConfig::NoUnexpectedKeys));
+    expect!(result).to(be_ok());
+  }
+
+  #[test]
+  fn match_xml_with_text_options() {
+    let expected = request!(r#"<?xml version="1.0" encoding="UTF-8"?><foo>hello <bar/>world</foo>"#);
+    let actual = request!(r#"<?xml version="1.0" encoding="UTF-8"?><foo>
+      hello <bar/>world
+    </foo>"#);
+
+    let context = CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys);
+    expect!(match_xml(&expected, &actual, &context)).to(be_ok());
+
+    let context = CoreMatchingContext {
+      xml_options: XmlMatchingOptions { trim_text: false, strip_whitespace_text: true },
+      .. CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys)
+    };
+    let result = match_xml(&expected, &actual, &context);
+    expect!(mismatch_message(&result)).to(be_equal_to(
+      "Expected '\n      hello world\n    ' to be equal to 'hello world'".to_string()));
+
+    let expected = request!(r#"<?xml version="1.0" encoding="UTF-8"?><foo> <bar/> </foo>"#);
+    let actual = request!(r#"<?xml version="1.0" encoding="UTF-8"?><foo><bar/></foo>"#);
+    expect!(match_xml(&expected, &actual, &context)).to(be_ok());
+
+    let context = CoreMatchingContext {
+      xml_options: XmlMatchingOptions { trim_text: false, strip_whitespace_text: false },
+      .. CoreMatchingContext::with_config(DiffConfig::AllowUnexpectedKeys)
+    };
+    let result = match_xml(&expected, &actual, &context);
+    expect!(mismatch_message(&result)).to(be_equal_to("Expected '' to be equal to '  '".to_string()));
+  }
+
   #[test]
   fn match_xml_with_a_matcher() {
     let expected = request!(r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -972,7 +1031,8 @@ mod tests {
       path: "$['urn:other:blah']".to_string(),
       expected: Some("urn:other:blah".into()),
       actual: Some("urn:ns:blah".into()),
-      mismatch: "Expected 'urn:other:blah' to be equal to 'urn:ns:blah'".to_string()
+      mismatch: "Expected 'urn:other:blah' to be equal to 'urn:ns:blah'".to_string(),
+      rule: None
     } ]));
   }
 
@@ -985,7 +1045,8 @@ mod tests {
       path: "$['urn:other:blah']".to_string(),
       expected: Some("urn:other:blah".into()),
       actual: Some("blah".into()),
-      mismatch: "Expected 'urn:other:blah' to be equal to 'blah'".to_string()
+      mismatch: "Expected 'urn:other:blah' to be equal to 'blah'".to_string(),
+      rule: None
     } ]));
   }
 
@@ -998,7 +1059,8 @@ mod tests {
       path: "$.blah".to_string(),
       expected: Some("blah".into()),
       actual: Some("urn:ns:blah".into()),
-      mismatch: "Expected 'blah' to be equal to 'urn:ns:blah'".to_string()
+      mismatch: "Expected 'blah' to be equal to 'urn:ns:blah'".to_string(),
+      rule: None
     } ]));
   }
 
@@ -1019,7 +1081,8 @@ mod tests {
       path: "$.foo['@urn:b:something']".to_string(),
       expected: Some("urn:b:something".into()),
       actual: None,
-      mismatch: "Expected attribute 'urn:b:something'='100' but was missing".to_string()
+      mismatch: "Expected attribute 'urn:b:something'='100' but was missing".to_string(),
+      rule: None
     } ]));
   }
 
@@ -1095,17 +1158,17 @@ mod tests {
     let mismatch = mismatches[0].clone();
     expect!(&mismatch).to(be_equal_to(&Mismatch::BodyMismatch { path: s!("$.animals.cat"),
       expected: Some("cat".into()),
-      actual: Some("dog, dog, cat, cat, cat, wolf".into()), mismatch: s!("")}));
+      actual: Some("dog, dog, cat, cat, cat, wolf".into()), mismatch: s!(""), rule: None}));
     expect!(mismatch.description()).to(be_equal_to(s!("$.animals.cat -> Unexpected child <cat/>")));
     let mismatch = mismatches[1].clone();
     expect!(&mismatch).to(be_equal_to(&Mismatch::BodyMismatch { path: s!("$.animals.cat"),
       expected: Some("cat".into()),
-      actual: Some("dog, dog, cat, cat, cat, wolf".into()), mismatch: s!("")}));
+      actual: Some("dog, dog, cat, cat, cat, wolf".into()), mismatch: s!(""), rule: None}));
     expect!(mismatch.description()).to(be_equal_to(s!("$.animals.cat -> Unexpected child <cat/>")));
     let mismatch = mismatches[2].clone();
     expect!(&mismatch).to(be_equal_to(&Mismatch::BodyMismatch { path: s!("$.animals.dog"),
       expected: Some("dog".into()),
-      actual: Some("dog, dog, cat, cat, cat, wolf".into()), mismatch: "Unexpected child <dog/>".into()}));
+      actual: Some("dog, dog, cat, cat, cat, wolf".into()), mismatch: "Unexpected child <dog/>".into(), rule: None}));
     expect!(mismatch.description()).to(be_equal_to(s!("$.animals.dog -> Unexpected child <dog/>")));
   }
 
@@ -1161,7 +1224,8 @@ mod tests {
         path: "$.foo['@somethingElse']".into(),
         expected: Some("true".into()),
         actual: Some("101".into()),
-        mismatch: Default::default()
+        mismatch: Default::default(),
+        rule: None
       }
     ]));
   }
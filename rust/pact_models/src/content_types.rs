@@ -4,6 +4,7 @@ use std::collections::BTreeMap;
 use std::convert::TryFrom;
 use std::fmt::{Debug, Display, Formatter};
 use std::str::{from_utf8, FromStr};
+use std::sync::RwLock;
 
 use anyhow::anyhow;
 use itertools::Itertools;
@@ -48,6 +49,14 @@ lazy_static! {
     .. ContentType::default()
   };
 
+  /// HAL JSON Content Type
+  pub static ref HAL_JSON: ContentType = ContentType {
+    main_type: "application".into(),
+    sub_type: "hal".into(),
+    suffix: Some("json".into()),
+    .. ContentType::default()
+  };
+
   /// TEXT Content Type
   pub static ref TEXT: ContentType = ContentType {
     main_type: "text".into(),
@@ -62,12 +71,103 @@ lazy_static! {
     .. ContentType::default()
   };
 
+  /// CBOR Content Type
+  pub static ref CBOR: ContentType = ContentType {
+    main_type: "application".into(),
+    sub_type: "cbor".into(),
+    .. ContentType::default()
+  };
+
+  /// MessagePack Content Type
+  pub static ref MSGPACK: ContentType = ContentType {
+    main_type: "application".into(),
+    sub_type: "msgpack".into(),
+    .. ContentType::default()
+  };
+
+  static ref CONTENT_TYPE_REGISTRY: RwLock<ContentTypeRegistry> = RwLock::new(ContentTypeRegistry::default());
+
   static ref XMLREGEXP: Regex = Regex::new(r"^\s*<\?xml\s*version.*").unwrap();
   static ref HTMLREGEXP: Regex = Regex::new(r"^\s*(<!DOCTYPE)|(<HTML>).*").unwrap();
   static ref JSONREGEXP: Regex = Regex::new(r#"^\s*(true|false|null|[0-9]+|"\w*|\{\s*(}|"\w+)|\[\s*)"#).unwrap();
   static ref XMLREGEXP2: Regex = Regex::new(r#"^\s*<\w+\s*(:\w+=["”][^"”]+["”])?.*"#).unwrap();
 }
 
+/// Registry of the content types that are handled as one of the known base types (i.e. JSON or
+/// XML). Types are looked up first by the full type, then by their structured syntax suffix
+/// (RFC 6839, i.e. the `json` in `application/vnd.api+json`).
+#[derive(Debug, Clone, PartialEq)]
+struct ContentTypeRegistry {
+  /// Content types (without parameters) that are aliases of a base type
+  aliases: BTreeMap<String, ContentType>,
+  /// Structured syntax suffixes and the base type they indicate
+  suffixes: BTreeMap<String, ContentType>
+}
+
+impl ContentTypeRegistry {
+  fn lookup(&self, content_type: &ContentType) -> Option<&ContentType> {
+    let key = format!("{}/{}", content_type.main_type, content_type.full_sub_type()).to_lowercase();
+    self.aliases.get(&key).or_else(|| {
+      // Suffixes do not change the meaning of media types like image/svg+xml
+      if content_type.main_type.eq_ignore_ascii_case("application") || content_type.main_type.eq_ignore_ascii_case("text") {
+        content_type.structured_suffix()
+          .and_then(|suffix| self.suffixes.get(&suffix.to_lowercase()))
+      } else {
+        None
+      }
+    })
+  }
+}
+
+impl Default for ContentTypeRegistry {
+  fn default() -> Self {
+    ContentTypeRegistry {
+      aliases: BTreeMap::from([
+        ("text/json".to_string(), JSON.clone()),
+        ("application/x-json".to_string(), JSON.clone()),
+        ("text/xml".to_string(), XML.clone()),
+        ("application/x-msgpack".to_string(), MSGPACK.clone()),
+        ("application/vnd.msgpack".to_string(), MSGPACK.clone())
+      ]),
+      suffixes: BTreeMap::from([
+        ("json".to_string(), JSON.clone()),
+        ("xml".to_string(), XML.clone()),
+        ("cbor".to_string(), CBOR.clone()),
+        ("msgpack".to_string(), MSGPACK.clone())
+      ])
+    }
+  }
+}
+
+/// Registers a content type that is to be handled the same as the base type, i.e. registering
+/// `application/x-ndjson` with a base type of `application/json` will match those bodies as JSON.
+/// Any parameters on the content types are ignored.
+pub fn register_content_type(content_type: &str, base_type: &str) -> anyhow::Result<()> {
+  let alias = ContentType::parse(content_type)?;
+  let base_type = ContentType::parse(base_type)?.without_parameters();
+  let key = format!("{}/{}", alias.main_type, alias.full_sub_type()).to_lowercase();
+  debug!("Registering content type '{}' as '{}'", key, base_type);
+  let mut registry = CONTENT_TYPE_REGISTRY.write()
+    .map_err(|_| anyhow!("Failed to lock the content type registry"))?;
+  registry.aliases.insert(key, base_type);
+  Ok(())
+}
+
+/// Registers a structured syntax suffix (i.e. `yaml` for `application/openapi+yaml`) for the base
+/// type. Content types with the suffix will be handled the same as the base type.
+pub fn register_structured_suffix(suffix: &str, base_type: &str) -> anyhow::Result<()> {
+  let suffix = suffix.trim().trim_start_matches('+').to_lowercase();
+  if suffix.is_empty() {
+    return Err(anyhow!("A structured syntax suffix can not be empty"));
+  }
+  let base_type = ContentType::parse(base_type)?.without_parameters();
+  debug!("Registering structured syntax suffix '+{}' as '{}'", suffix, base_type);
+  let mut registry = CONTENT_TYPE_REGISTRY.write()
+    .map_err(|_| anyhow!("Failed to lock the content type registry"))?;
+  registry.suffixes.insert(suffix, base_type);
+  Ok(())
+}
+
 const KNOWN_TEXT_TYPES: [(&'static str, &'static str); 1] = [
   ("application", "x-www-form-urlencoded")
 ];
@@ -89,17 +189,17 @@ impl ContentType {
     }
   }
 
-  /// If it is a JSON type
+  /// If it is a JSON type. This includes any type registered as JSON in the content type registry
+  /// (i.e. `text/json`) and any type with a `+json` suffix.
   pub fn is_json(&self) -> bool {
-    self.main_type == "application" && (self.sub_type.starts_with("json") ||
-      self.suffix.as_ref().unwrap_or(&String::default()) == "json" ||
-      self.sub_type == "graphql")
+    (self.main_type == "application" && (self.sub_type.starts_with("json") || self.sub_type == "graphql")) ||
+      self.canonical_type().has_same_base_type(&JSON)
   }
 
-  /// If it is a XML type
+  /// If it is a XML type. This includes `text/xml`, any type registered as XML in the content
+  /// type registry and any type with a `+xml` suffix.
   pub fn is_xml(&self) -> bool {
-    (self.main_type == "application" || self.main_type == "text") && (self.sub_type == "xml" ||
-      self.suffix.as_ref().unwrap_or(&String::default()) == "xml")
+    self.canonical_type().has_same_base_type(&XML)
   }
 
   /// If it is a XML type (not including ones with suffixes like atom+xml)
@@ -107,6 +207,16 @@ impl ContentType {
     (self.main_type == "application" || self.main_type == "text") && self.sub_type == "xml"
   }
 
+  /// If it is a CBOR type (application/cbor or a type with a +cbor suffix)
+  pub fn is_cbor(&self) -> bool {
+    self.canonical_type().has_same_base_type(&CBOR)
+  }
+
+  /// If it is a MessagePack type
+  pub fn is_msgpack(&self) -> bool {
+    self.canonical_type().has_same_base_type(&MSGPACK)
+  }
+
   /// If it is a text type
   pub fn is_text(&self) -> bool {
     self.main_type == "text" || self.is_xml() || self.is_json() || self.is_known_text_type()
@@ -137,20 +247,127 @@ impl ContentType {
     }
   }
 
+  /// Returns the type this content type is handled as, using the content type registry. This will
+  /// be the registered base type for the content type or its structured syntax suffix (i.e.
+  /// `application/json` for both `text/json` and `application/vnd.api+json`), otherwise it is the
+  /// same as `base_type`.
+  pub fn canonical_type(&self) -> ContentType {
+    let registered = CONTENT_TYPE_REGISTRY.read().ok()
+      .and_then(|registry| registry.lookup(self).cloned());
+    registered.unwrap_or_else(|| self.base_type())
+  }
+
+  /// Returns the structured syntax suffix (i.e. `json` for `application/vnd.api+json`), if there
+  /// is one
+  pub fn structured_suffix(&self) -> Option<String> {
+    self.suffix.clone().or_else(|| self.sub_type.rsplit_once('+')
+      .map(|(_, suffix)| suffix.to_string())
+      .filter(|suffix| !suffix.is_empty()))
+  }
+
+  /// Returns a copy of this content type with no parameters
+  pub fn without_parameters(&self) -> ContentType {
+    ContentType {
+      attributes: BTreeMap::new(),
+      .. self.clone()
+    }
+  }
+
   /// If the content type is the default type
   pub fn is_unknown(&self) -> bool {
     self.main_type == "*" || self.sub_type == "*"
   }
 
-  /// Equals, ignoring attributes if not present on self
-  pub fn is_equivalent_to(&self, other: &ContentType) -> bool {
-    if self.is_strict_xml() && other.is_strict_xml() {
-      self.attributes.is_empty() || self.attributes == other.attributes
+  /// Returns the value of the parameter (attribute) with the given name. Parameter names are
+  /// case-insensitive.
+  pub fn parameter(&self, name: &str) -> Option<&str> {
+    self.attributes.iter()
+      .find(|(key, _)| key.eq_ignore_ascii_case(name))
+      .map(|(_, value)| value.as_str())
+  }
+
+  /// Returns the charset parameter, if there is one
+  pub fn charset(&self) -> Option<&str> {
+    self.parameter("charset")
+  }
+
+  /// Returns the profile parameter (i.e. `application/json; profile="https://example.com/schema"`),
+  /// if there is one
+  pub fn profile(&self) -> Option<&str> {
+    self.parameter("profile")
+  }
+
+  /// If this is a multipart content type (i.e. `multipart/form-data`)
+  pub fn is_multipart(&self) -> bool {
+    self.main_type.eq_ignore_ascii_case("multipart")
+  }
+
+  /// Returns a copy of this content type without the boundary parameter if it is a multipart
+  /// content type. The boundary is generated when the body is encoded, so it can be ignored when
+  /// comparing multipart content types.
+  pub fn without_boundary(&self) -> ContentType {
+    if self.is_multipart() {
+      ContentType {
+        attributes: self.attributes.iter()
+          .filter(|(name, _)| !name.eq_ignore_ascii_case("boundary"))
+          .map(|(name, value)| (name.clone(), value.clone()))
+          .collect(),
+        .. self.clone()
+      }
+    } else {
+      self.clone()
     }
-    else if self.attributes.is_empty() {
-      self.main_type == other.main_type && self.sub_type == other.sub_type
+  }
+
+  /// If this content type has the same type, sub-type and suffix as the other one, ignoring case
+  /// and any parameters. A sub-type of `hal+json` is treated the same as a sub-type of `hal` with
+  /// a `json` suffix.
+  pub fn has_same_base_type(&self, other: &ContentType) -> bool {
+    self.main_type.eq_ignore_ascii_case(&other.main_type) &&
+      self.full_sub_type().eq_ignore_ascii_case(&other.full_sub_type())
+  }
+
+  /// If the other content type is compatible with this one. That is, it has the same base type
+  /// (see `has_same_base_type`) and all the parameters of this content type. Any additional
+  /// parameters on the other content type are ignored. Charset values are compared ignoring case,
+  /// all other parameter values must be equal.
+  pub fn is_compatible_with(&self, other: &ContentType) -> bool {
+    self.has_same_base_type(other) && self.parameters_match(other)
+  }
+
+  /// Equals, ignoring attributes if not present on self. This is the same as `is_compatible_with`,
+  /// except that content types registered as aliases of the same base type (i.e. `application/xml`
+  /// and `text/xml`) are treated as equivalent. Types with a structured syntax suffix are only
+  /// equivalent to the same type.
+  pub fn is_equivalent_to(&self, other: &ContentType) -> bool {
+    if self.has_same_base_type(other) {
+      self.parameters_match(other)
+    } else if self.structured_suffix().is_none() && other.structured_suffix().is_none() {
+      self.canonical_type().has_same_base_type(&other.canonical_type()) && self.parameters_match(other)
     } else {
-      self == other
+      false
+    }
+  }
+
+  /// If all the parameters of this content type are present on the other one
+  fn parameters_match(&self, other: &ContentType) -> bool {
+    self.attributes.iter().all(|(name, value)| {
+      match other.parameter(name) {
+        Some(other_value) => if name.eq_ignore_ascii_case("charset") {
+          value.eq_ignore_ascii_case(other_value)
+        } else {
+          value == other_value
+        },
+        None => false
+      }
+    })
+  }
+
+  /// Sub-type including any suffix
+  fn full_sub_type(&self) -> String {
+    match &self.suffix {
+      Some(suffix) => format!("{}+{}", self.sub_type, suffix),
+      None => self.sub_type.clone()
     }
   }
 
@@ -218,7 +435,9 @@ impl From<&Mime> for ContentType {
     ContentType {
       main_type: mime.type_().to_string(),
       sub_type: mime.subtype().to_string(),
-      attributes: mime.params().map(|(key, value)| (key.to_string(), value.to_string())).collect(),
+      attributes: mime.params()
+        .map(|(key, value)| (key.to_string(), value.as_str().trim_matches('"').to_string()))
+        .collect(),
       suffix: mime.suffix().map(|name| name.to_string()),
       .. ContentType::default()
     }
@@ -349,7 +568,7 @@ mod tests {
   use expectest::prelude::*;
   use maplit::btreemap;
 
-  use super::ContentType;
+  use super::{ContentType, JSON, register_content_type, register_structured_suffix};
 
   #[test]
   fn parse_test() {
@@ -471,6 +690,22 @@ mod tests {
     expect!(content_type.is_xml()).to(be_true());
   }
 
+  #[test]
+  fn is_cbor_test() {
+    expect!(ContentType::parse("application/cbor").unwrap().is_cbor()).to(be_true());
+    expect!(ContentType::parse("application/senml+cbor").unwrap().is_cbor()).to(be_true());
+    expect!(ContentType::parse("application/json").unwrap().is_cbor()).to(be_false());
+    expect!(ContentType::parse("application/msgpack").unwrap().is_cbor()).to(be_false());
+  }
+
+  #[test]
+  fn is_msgpack_test() {
+    expect!(ContentType::parse("application/msgpack").unwrap().is_msgpack()).to(be_true());
+    expect!(ContentType::parse("application/x-msgpack").unwrap().is_msgpack()).to(be_true());
+    expect!(ContentType::parse("application/vnd.msgpack").unwrap().is_msgpack()).to(be_true());
+    expect!(ContentType::parse("application/cbor").unwrap().is_msgpack()).to(be_false());
+  }
+
   #[test]
   fn base_type_test() {
     let content_type = ContentType::parse("application/atom+xml").unwrap();
@@ -566,4 +801,128 @@ mod tests {
     expect!(content_type2.is_equivalent_to(&content_type3)).to(be_true());
     expect!(content_type2.is_equivalent_to(&content_type4)).to(be_false());
   }
+
+  #[test]
+  fn equivalent_with_registered_aliases_test() {
+    let json = ContentType::parse("application/json").unwrap();
+    expect!(json.is_equivalent_to(&ContentType::parse("text/json; charset=UTF-8").unwrap())).to(be_true());
+    expect!(json.is_equivalent_to(&ContentType::parse("application/vnd.api+json").unwrap())).to(be_false());
+    expect!(ContentType::parse("application/vnd.api+json").unwrap()
+      .is_equivalent_to(&ContentType::parse("application/vnd.api+json; charset=UTF-8").unwrap())).to(be_true());
+    expect!(ContentType::parse("application/x-msgpack").unwrap()
+      .is_equivalent_to(&ContentType::parse("application/msgpack").unwrap())).to(be_true());
+    expect!(ContentType::parse("application/x-msgpack; charset=UTF-8").unwrap()
+      .is_equivalent_to(&ContentType::parse("application/msgpack").unwrap())).to(be_false());
+  }
+
+  #[test]
+  fn canonical_type_test() {
+    expect!(ContentType::parse("application/vnd.api+json; charset=UTF-8").unwrap().canonical_type()).to(be_equal_to(JSON.clone()));
+    expect!(ContentType::parse("application/soap+xml").unwrap().canonical_type().to_string()).to(be_equal_to("application/xml".to_string()));
+    expect!(ContentType::parse("text/xml").unwrap().canonical_type().to_string()).to(be_equal_to("application/xml".to_string()));
+    expect!(ContentType::parse("text/json").unwrap().canonical_type()).to(be_equal_to(JSON.clone()));
+    expect!(ContentType::parse("text/plain; charset=UTF-8").unwrap().canonical_type().to_string()).to(be_equal_to("text/plain".to_string()));
+    expect!(ContentType::parse("image/svg+xml").unwrap().canonical_type().to_string()).to(be_equal_to("image/xml".to_string()));
+    expect!(ContentType::parse("image/svg+xml").unwrap().is_xml()).to(be_false());
+    expect!(ContentType::parse("text/vnd.test+json").unwrap().is_json()).to(be_true());
+  }
+
+  #[test]
+  fn structured_suffix_test() {
+    expect!(ContentType::parse("application/vnd.api+json").unwrap().structured_suffix()).to(be_some().value("json".to_string()));
+    expect!(ContentType::parse("application/json").unwrap().structured_suffix()).to(be_none());
+    let content_type = ContentType {
+      main_type: "application".into(),
+      sub_type: "hal+json".into(),
+      .. ContentType::default()
+    };
+    expect!(content_type.structured_suffix()).to(be_some().value("json".to_string()));
+  }
+
+  #[test]
+  fn register_content_type_test() {
+    let content_type = ContentType::parse("application/x-registry-test").unwrap();
+    expect!(content_type.is_json()).to(be_false());
+    expect!(register_content_type("application/x-registry-test", "application/json; charset=UTF-8")).to(be_ok());
+    expect!(content_type.is_json()).to(be_true());
+    expect!(content_type.canonical_type()).to(be_equal_to(JSON.clone()));
+    expect!(register_content_type("not a content type", "application/json")).to(be_err());
+
+    let content_type = ContentType::parse("application/vnd.registry-test+jsontest").unwrap();
+    expect!(content_type.is_json()).to(be_false());
+    expect!(register_structured_suffix("+jsontest", "application/json")).to(be_ok());
+    expect!(content_type.is_json()).to(be_true());
+    expect!(register_structured_suffix("", "application/json")).to(be_err());
+  }
+
+  #[test]
+  fn profile_test() {
+    let content_type = ContentType::parse("application/json; profile=\"https://example.com/schema\"").unwrap();
+    expect!(content_type.profile()).to(be_some().value("https://example.com/schema"));
+    expect!(JSON.profile()).to(be_none());
+    expect!(content_type.is_equivalent_to(&ContentType::parse("application/json; profile=\"https://example.com/schema\"; charset=UTF-8").unwrap())).to(be_true());
+    expect!(content_type.is_equivalent_to(&ContentType::parse("application/json; profile=\"https://example.com/other\"").unwrap())).to(be_false());
+    expect!(content_type.is_equivalent_to(&JSON)).to(be_false());
+  }
+
+  #[test]
+  fn parameter_test() {
+    let content_type = ContentType {
+      main_type: "text".into(),
+      sub_type: "plain".into(),
+      attributes: btreemap! { "Charset".to_string() => "UTF-8".to_string() },
+      suffix: None
+    };
+    expect!(content_type.parameter("charset")).to(be_some().value("UTF-8"));
+    expect!(content_type.charset()).to(be_some().value("UTF-8"));
+    expect!(content_type.parameter("boundary")).to(be_none());
+    expect!(ContentType::parse("text/plain").unwrap().charset()).to(be_none());
+  }
+
+  #[test]
+  fn has_same_base_type_test() {
+    let content_type = ContentType::parse("application/hal+json;charset=UTF-8").unwrap();
+    let content_type2 = ContentType {
+      main_type: "Application".into(),
+      sub_type: "HAL+json".into(),
+      .. ContentType::default()
+    };
+    expect!(content_type.has_same_base_type(&content_type2)).to(be_true());
+    expect!(content_type.has_same_base_type(&ContentType::parse("application/json").unwrap())).to(be_false());
+    expect!(content_type.has_same_base_type(&ContentType::parse("application/hal").unwrap())).to(be_false());
+  }
+
+  #[test]
+  fn is_compatible_with_test() {
+    let json = ContentType::parse("application/json").unwrap();
+    let json_utf8 = ContentType::parse("application/json; charset=UTF-8").unwrap();
+    let json_utf8_lower = ContentType {
+      main_type: "application".into(),
+      sub_type: "json".into(),
+      attributes: btreemap! { "charset".to_string() => "utf-8".to_string(), "version".to_string() => "1".to_string() },
+      suffix: None
+    };
+    let json_utf16 = ContentType::parse("application/json; charset=UTF-16").unwrap();
+
+    expect!(json.is_compatible_with(&json_utf8)).to(be_true());
+    expect!(json_utf8.is_compatible_with(&json)).to(be_false());
+    expect!(json_utf8.is_compatible_with(&json_utf8_lower)).to(be_true());
+    expect!(json_utf8.is_compatible_with(&json_utf16)).to(be_false());
+    expect!(json_utf8_lower.is_compatible_with(&json_utf8)).to(be_false());
+
+    let multipart = ContentType::parse("multipart/form-data; boundary=ABC").unwrap();
+    expect!(multipart.is_compatible_with(&ContentType::parse("multipart/form-data; boundary=ABC").unwrap())).to(be_true());
+    expect!(multipart.is_compatible_with(&ContentType::parse("multipart/form-data; boundary=abc").unwrap())).to(be_false());
+  }
+
+  #[test]
+  fn without_boundary_test() {
+    let multipart = ContentType::parse("multipart/form-data; boundary=ABC; charset=UTF-8").unwrap();
+    expect!(multipart.is_multipart()).to(be_true());
+    expect!(multipart.without_boundary().to_string()).to(be_equal_to("multipart/form-data;charset=utf-8"));
+
+    let json = ContentType::parse("application/json; boundary=ABC").unwrap();
+    expect!(json.is_multipart()).to(be_false());
+    expect!(json.without_boundary()).to(be_equal_to(json));
+  }
 }
@@ -0,0 +1,312 @@
+//! Functions for converting Pacts between specification versions, reporting any constructs that
+//! can not be represented in the target version.
+
+use std::fmt::{Display, Formatter};
+use std::fmt;
+
+use itertools::Itertools;
+use serde_json::Value;
+
+use crate::generators::{Generator, Generators};
+use crate::interaction::Interaction;
+use crate::matchingrules::{MatchingRule, MatchingRules};
+use crate::message_pact::MessagePact;
+use crate::pact::Pact;
+use crate::PactSpecification;
+use crate::sync_pact::RequestResponsePact;
+use crate::v4::interaction::V4Interaction;
+use crate::v4::pact::V4Pact;
+
+/// Warning about a construct that could not be represented when converting a Pact to another
+/// specification version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LossWarning {
+  /// Description of the interaction the warning applies to. This will be None for warnings that
+  /// apply to the Pact as a whole.
+  pub interaction: Option<String>,
+  /// Description of the construct that could not be represented
+  pub description: String
+}
+
+impl LossWarning {
+  fn pact(description: impl Into<String>) -> Self {
+    LossWarning {
+      interaction: None,
+      description: description.into()
+    }
+  }
+
+  fn interaction(interaction: &dyn Interaction, description: impl Into<String>) -> Self {
+    LossWarning {
+      interaction: Some(interaction.description()),
+      description: description.into()
+    }
+  }
+}
+
+impl Display for LossWarning {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match &self.interaction {
+      Some(interaction) => write!(f, "Interaction '{}': {}", interaction, self.description),
+      None => write!(f, "{}", self.description)
+    }
+  }
+}
+
+/// Result of downgrading a V4 Pact to the V3 format. V3 Pacts can not contain both HTTP and
+/// message interactions, so these are split into separate Pacts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct V3Pacts {
+  /// Pact with the HTTP interactions. This will be None if there were no HTTP interactions.
+  pub http: Option<RequestResponsePact>,
+  /// Pact with the asynchronous message interactions. This will be None if there were no
+  /// message interactions.
+  pub messages: Option<MessagePact>
+}
+
+/// Upgrades the Pact to a V4 Pact, returning warnings for any interactions that could not be
+/// converted. V4 Pacts are returned unchanged.
+pub fn upgrade_to_v4(pact: &dyn Pact) -> anyhow::Result<(V4Pact, Vec<LossWarning>)> {
+  let v4_pact = pact.as_v4_pact()?;
+  let warnings = pact.interactions().iter()
+    .filter(|interaction| interaction.as_v4().is_none())
+    .map(|interaction| LossWarning::interaction(interaction.as_ref(),
+      format!("{} interactions can not be converted to V4", interaction.type_of())))
+    .collect();
+  Ok((v4_pact, warnings))
+}
+
+/// Downgrades the V4 Pact to the V3 format, returning warnings for every construct that can not
+/// be represented in V3. Synchronous message interactions and data only supported by V4 (like
+/// plugin configuration, comments and pending flags) are dropped. Matching rules and generators
+/// that are not part of the V3 specification are kept, but are reported as they may not be
+/// supported by other Pact implementations.
+pub fn downgrade_to_v3(pact: &V4Pact) -> (V3Pacts, Vec<LossWarning>) {
+  let mut warnings = vec![];
+
+  if !pact.plugin_data.is_empty() {
+    warnings.push(LossWarning::pact(format!("Plugin data for {} can not be represented in V3",
+      pact.plugin_data.iter().map(|plugin| format!("'{}'", plugin.name)).join(", "))));
+  }
+  for (key, value) in &pact.metadata {
+    if !matches!(value, Value::Object(_)) {
+      warnings.push(LossWarning::pact(format!("Metadata entry '{}' is not an object, and can not be represented in V3", key)));
+    }
+  }
+
+  for interaction in &pact.interactions {
+    warnings.extend(interaction_warnings(interaction.as_ref()));
+  }
+
+  let has_http = pact.interactions.iter().any(|i| i.is_v4_http());
+  let has_messages = pact.interactions.iter().any(|i| i.is_v4_async_message());
+  let http = if has_http {
+    pact.as_request_response_pact().ok()
+  } else {
+    None
+  };
+  let messages = if has_messages {
+    pact.as_message_pact().ok()
+  } else {
+    None
+  };
+
+  (V3Pacts { http, messages }, warnings)
+}
+
+fn interaction_warnings(interaction: &(dyn V4Interaction + Send + Sync)) -> Vec<LossWarning> {
+  let interaction_ref = interaction.to_super();
+  let mut warnings = vec![];
+
+  if interaction.is_v4_sync_message() {
+    warnings.push(LossWarning::interaction(interaction_ref,
+      "Synchronous message interactions can not be represented in V3"));
+    return warnings;
+  }
+
+  if interaction.pending() {
+    warnings.push(LossWarning::interaction(interaction_ref, "The pending flag can not be represented in V3"));
+  }
+  if !interaction.comments().is_empty() {
+    warnings.push(LossWarning::interaction(interaction_ref, "Comments can not be represented in V3"));
+  }
+  if let Some(transport) = interaction.transport() {
+    warnings.push(LossWarning::interaction(interaction_ref,
+      format!("The '{}' transport can not be represented in V3", transport)));
+  }
+  let plugin_config = interaction.plugin_config();
+  if !plugin_config.is_empty() {
+    warnings.push(LossWarning::interaction(interaction_ref,
+      format!("Plugin configuration for {} can not be represented in V3",
+        plugin_config.keys().sorted().map(|plugin| format!("'{}'", plugin)).join(", "))));
+  }
+  if !interaction.interaction_markup().is_empty() {
+    warnings.push(LossWarning::interaction(interaction_ref, "Interaction markup can not be represented in V3"));
+  }
+
+  let parts = if let Some(http) = interaction.as_v4_http() {
+    vec![
+      ("request", http.request.matching_rules, http.request.generators),
+      ("response", http.response.matching_rules, http.response.generators)
+    ]
+  } else if let Some(message) = interaction.as_v4_async_message() {
+    vec![ ("message", message.contents.matching_rules, message.contents.generators) ]
+  } else {
+    vec![]
+  };
+  for (part, matching_rules, generators) in parts {
+    warnings.extend(unsupported_rules(interaction_ref, part, &matching_rules, &generators));
+  }
+
+  warnings
+}
+
+fn unsupported_rules(
+  interaction: &dyn Interaction,
+  part: &str,
+  matching_rules: &MatchingRules,
+  generators: &Generators
+) -> Vec<LossWarning> {
+  let mut warnings = vec![];
+
+  for (category, rules) in matching_rules.rules.iter().sorted_by(|(a, _), (b, _)| Ord::cmp(a, b)) {
+    for (path, rule_list) in rules.rules.iter().sorted_by(|(a, _), (b, _)| Ord::cmp(a, b)) {
+      for rule in rule_list.rules.iter().filter(|rule| !is_v3_matching_rule(rule)) {
+        warnings.push(LossWarning::interaction(interaction,
+          format!("Matching rule '{}' for {} {} '{}' is not supported by the {} specification",
+            rule.name(), part, category, path, PactSpecification::V3)));
+      }
+    }
+  }
+
+  for (category, category_generators) in generators.categories.iter().sorted_by(|(a, _), (b, _)| Ord::cmp(a, b)) {
+    for (path, generator) in category_generators.iter().sorted_by(|(a, _), (b, _)| Ord::cmp(a, b)) {
+      if !is_v3_generator(generator) {
+        let category: &str = (*category).into();
+        warnings.push(LossWarning::interaction(interaction,
+          format!("Generator '{}' for {} {} '{}' is not supported by the {} specification",
+            generator.name(), part, category, path, PactSpecification::V3)));
+      }
+    }
+  }
+
+  warnings
+}
+
+fn is_v3_matching_rule(rule: &MatchingRule) -> bool {
+  matches!(rule,
+    MatchingRule::Equality | MatchingRule::Regex(_) | MatchingRule::Type |
+    MatchingRule::MinType(_) | MatchingRule::MaxType(_) | MatchingRule::MinMaxType(_, _) |
+    MatchingRule::Timestamp(_) | MatchingRule::Time(_) | MatchingRule::Date(_) |
+    MatchingRule::Include(_) | MatchingRule::Number | MatchingRule::Integer |
+    MatchingRule::Decimal | MatchingRule::Null | MatchingRule::ContentType(_) |
+    MatchingRule::Values)
+}
+
+fn is_v3_generator(generator: &Generator) -> bool {
+  !matches!(generator, Generator::MockServerURL(_, _) | Generator::ArrayContains(_) | Generator::EpochTimestamp(_) |
+    Generator::RandomNumber(_))
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+  use maplit::*;
+  use serde_json::json;
+
+  use crate::{Consumer, generators, matchingrules, PactSpecification, Provider};
+  use crate::generators::Generator;
+  use crate::matchingrules::MatchingRule;
+  use crate::plugins::PluginData;
+  use crate::sync_interaction::RequestResponseInteraction;
+  use crate::sync_pact::RequestResponsePact;
+  use crate::v4::async_message::AsynchronousMessage;
+  use crate::v4::http_parts::HttpRequest;
+  use crate::v4::pact::V4Pact;
+  use crate::v4::synch_http::SynchronousHttp;
+  use crate::v4::sync_message::SynchronousMessage;
+
+  use super::*;
+
+  #[test]
+  fn upgrade_to_v4_converts_all_the_interactions() {
+    let pact = RequestResponsePact {
+      consumer: Consumer { name: "consumer".to_string() },
+      provider: Provider { name: "provider".to_string() },
+      interactions: vec![
+        RequestResponseInteraction { description: "one".to_string(), .. RequestResponseInteraction::default() },
+        RequestResponseInteraction { description: "two".to_string(), .. RequestResponseInteraction::default() }
+      ],
+      specification_version: PactSpecification::V3,
+      .. RequestResponsePact::default()
+    };
+    let (v4_pact, warnings) = upgrade_to_v4(&pact).unwrap();
+    expect!(v4_pact.interactions.iter().map(|i| i.description()).collect::<Vec<_>>())
+      .to(be_equal_to(vec!["one".to_string(), "two".to_string()]));
+    expect!(v4_pact.interactions.iter().all(|i| i.is_v4_http())).to(be_true());
+    expect!(warnings).to(be_equal_to(vec![]));
+  }
+
+  #[test]
+  fn downgrade_to_v3_splits_the_http_and_message_interactions() {
+    let pact = V4Pact {
+      consumer: Consumer { name: "consumer".to_string() },
+      provider: Provider { name: "provider".to_string() },
+      interactions: vec![
+        SynchronousHttp { description: "http".to_string(), .. SynchronousHttp::default() }.boxed_v4(),
+        AsynchronousMessage { description: "message".to_string(), .. AsynchronousMessage::default() }.boxed_v4()
+      ],
+      .. V4Pact::default()
+    };
+    let (v3_pacts, warnings) = downgrade_to_v3(&pact);
+    let http = v3_pacts.http.unwrap();
+    expect!(http.interactions.iter().map(|i| i.description.clone()).collect::<Vec<_>>())
+      .to(be_equal_to(vec!["http".to_string()]));
+    expect!(http.specification_version).to(be_equal_to(PactSpecification::V3));
+    let messages = v3_pacts.messages.unwrap();
+    expect!(messages.messages.iter().map(|i| i.description.clone()).collect::<Vec<_>>())
+      .to(be_equal_to(vec!["message".to_string()]));
+    expect!(warnings).to(be_equal_to(vec![]));
+  }
+
+  #[test]
+  fn downgrade_to_v3_reports_constructs_that_can_not_be_represented() {
+    let pact = V4Pact {
+      interactions: vec![
+        SynchronousHttp {
+          description: "http".to_string(),
+          pending: true,
+          transport: Some("https".to_string()),
+          request: HttpRequest {
+            matching_rules: matchingrules! {
+              "body" => { "$.version" => [ MatchingRule::Semver ] }
+            },
+            generators: generators! {
+              "BODY" => { "$.url" => Generator::MockServerURL("http://localhost".to_string(), ".*".to_string()) }
+            },
+            .. HttpRequest::default()
+          },
+          .. SynchronousHttp::default()
+        }.boxed_v4(),
+        SynchronousMessage { description: "sync".to_string(), .. SynchronousMessage::default() }.boxed_v4()
+      ],
+      plugin_data: vec![
+        PluginData { name: "protobuf".to_string(), version: "0.1.0".to_string(), configuration: hashmap!{} }
+      ],
+      metadata: btreemap!{ "note".to_string() => json!("not an object") },
+      .. V4Pact::default()
+    };
+    let (v3_pacts, warnings) = downgrade_to_v3(&pact);
+    expect!(v3_pacts.http.unwrap().interactions.len()).to(be_equal_to(1));
+    expect!(v3_pacts.messages).to(be_none());
+    expect!(warnings.iter().map(|w| w.to_string()).collect::<Vec<_>>()).to(be_equal_to(vec![
+      "Plugin data for 'protobuf' can not be represented in V3".to_string(),
+      "Metadata entry 'note' is not an object, and can not be represented in V3".to_string(),
+      "Interaction 'http': The pending flag can not be represented in V3".to_string(),
+      "Interaction 'http': The 'https' transport can not be represented in V3".to_string(),
+      "Interaction 'http': Matching rule 'semver' for request body '$.version' is not supported by the V3 specification".to_string(),
+      "Interaction 'http': Generator 'MockServerURL' for request body '$.url' is not supported by the V3 specification".to_string(),
+      "Interaction 'sync': Synchronous message interactions can not be represented in V3".to_string()
+    ]));
+  }
+}
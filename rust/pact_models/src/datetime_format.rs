@@ -0,0 +1,303 @@
+//! Conversions between the Java-style date/time patterns used by Pact (i.e. `yyyy-MM-dd`) and the
+//! format strings used by the chrono crate (i.e. `%Y-%m-%d`).
+//!
+//! Pact patterns follow the Java `DateTimeFormatter` letters (see the `time_utils` module), so `u`
+//! is the year (not the day number of the week as with `SimpleDateFormat`), `Y` is the week-based
+//! year and `w` is the week of the week-based year. Both conversions return an error pointing at
+//! the first token that has no equivalent in the other format, instead of silently dropping it.
+
+use std::fmt::{Display, Formatter};
+
+use itertools::Itertools;
+
+use crate::time_utils::{chrono_pattern_for_token, DateTimePatternToken, parse_pattern, pattern_token};
+
+/// Errors from converting between date/time pattern formats
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateTimeFormatError {
+  /// The pattern could not be parsed
+  InvalidPattern(String),
+  /// The pattern contains a token that has no equivalent in the other format
+  UnsupportedToken {
+    /// The pattern being converted
+    pattern: String,
+    /// The unsupported token
+    token: String,
+    /// Character position of the token in the pattern
+    position: usize,
+    /// Why the token is not supported
+    reason: String
+  }
+}
+
+impl Display for DateTimeFormatError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      DateTimeFormatError::InvalidPattern(err) => f.write_str(err),
+      DateTimeFormatError::UnsupportedToken { pattern, token, position, reason } =>
+        write!(f, "'{}' at position {} of '{}' is not supported: {}", token, position, pattern, reason)
+    }
+  }
+}
+
+impl std::error::Error for DateTimeFormatError {}
+
+/// Converts a Java-style date/time pattern (i.e. `yyyy-MM-dd'T'HH:mm:ss`) to a chrono format string
+/// (i.e. `%Y-%m-%dT%H:%M:%S`). Returns an error if the pattern is invalid, or it contains a pattern
+/// letter that chrono can not format.
+pub fn to_chrono_pattern(pattern: &str) -> Result<String, DateTimeFormatError> {
+  parse_pattern(pattern).map_err(DateTimeFormatError::InvalidPattern)?;
+
+  let mut buffer = String::new();
+  let mut remaining = pattern;
+  while !remaining.is_empty() {
+    let (rest, token) = pattern_token(remaining)
+      .map_err(|err| DateTimeFormatError::InvalidPattern(
+        format!("Parsing datetime pattern '{}' failed with error - {}", pattern, err)))?;
+    let token_str = &remaining[..remaining.len() - rest.len()];
+    match chrono_pattern_for_token(&token) {
+      Some(chrono_pattern) => buffer.push_str(&chrono_pattern),
+      None => return Err(DateTimeFormatError::UnsupportedToken {
+        pattern: pattern.to_string(),
+        token: token_str.to_string(),
+        position: char_position(pattern, remaining),
+        reason: format!("chrono does not support {}", token_description(&token))
+      })
+    }
+    remaining = rest;
+  }
+
+  Ok(buffer)
+}
+
+/// Converts a chrono format string (i.e. `%Y-%m-%dT%H:%M:%S`) to a Java-style date/time pattern
+/// (i.e. `yyyy-MM-dd'T'HH:mm:ss`). Literal text is quoted where required. Returns an error if the
+/// format string contains a specifier that has no equivalent Java pattern.
+pub fn from_chrono_pattern(pattern: &str) -> Result<String, DateTimeFormatError> {
+  let mut buffer = String::new();
+  let mut literal = String::new();
+  let mut chars = pattern.char_indices().peekable();
+
+  while let Some((index, ch)) = chars.next() {
+    if ch != '%' {
+      literal.push(ch);
+      continue;
+    }
+
+    let mut specifier = String::new();
+    while let Some((_, next)) = chars.peek() {
+      let next = *next;
+      chars.next();
+      specifier.push(next);
+      if !matches!(next, '-' | '_' | '0'..='9' | '.' | ':' | '#') {
+        break;
+      }
+    }
+
+    let java_pattern = match specifier.as_str() {
+      "%" => { literal.push('%'); continue; },
+      "n" => { literal.push('\n'); continue; },
+      "t" => { literal.push('\t'); continue; },
+      ".3f" | ".6f" | ".9f" => {
+        literal.push('.');
+        "S".repeat(specifier[1..2].parse().unwrap_or(3))
+      },
+      "3f" | "6f" | "9f" => "S".repeat(specifier[0..1].parse().unwrap_or(3)),
+      _ => match java_pattern_for_specifier(specifier.as_str()) {
+        Some(java_pattern) => java_pattern.to_string(),
+        None => return Err(DateTimeFormatError::UnsupportedToken {
+          pattern: pattern.to_string(),
+          token: format!("%{}", specifier),
+          position: char_position(pattern, &pattern[index..]),
+          reason: format!("there is no Java date/time pattern for {}", specifier_description(specifier.as_str()))
+        })
+      }
+    };
+
+    push_literal(&mut buffer, &literal);
+    literal.clear();
+    buffer.push_str(&java_pattern);
+  }
+
+  push_literal(&mut buffer, &literal);
+  Ok(buffer)
+}
+
+fn java_pattern_for_specifier(specifier: &str) -> Option<&'static str> {
+  match specifier {
+    "Y" => Some("yyyy"),
+    "y" => Some("yy"),
+    "G" => Some("YYYY"),
+    "g" => Some("YY"),
+    "m" => Some("MM"),
+    "-m" => Some("M"),
+    "b" | "h" => Some("MMM"),
+    "B" => Some("MMMM"),
+    "d" => Some("dd"),
+    "-d" => Some("d"),
+    "j" => Some("DDD"),
+    "-j" => Some("D"),
+    "a" => Some("EEE"),
+    "A" => Some("EEEE"),
+    "u" => Some("e"),
+    "V" => Some("ww"),
+    "-V" => Some("w"),
+    "H" => Some("HH"),
+    "-H" => Some("H"),
+    "I" => Some("hh"),
+    "-I" => Some("h"),
+    "M" => Some("mm"),
+    "-M" => Some("m"),
+    "S" => Some("ss"),
+    "-S" => Some("s"),
+    "f" => Some("n"),
+    "p" => Some("a"),
+    "z" => Some("Z"),
+    ":z" => Some("xxx"),
+    "Z" => Some("z"),
+    "F" => Some("yyyy-MM-dd"),
+    "D" => Some("MM/dd/yy"),
+    "R" => Some("HH:mm"),
+    "T" => Some("HH:mm:ss"),
+    _ => None
+  }
+}
+
+fn specifier_description(specifier: &str) -> &'static str {
+  match specifier {
+    "U" => "the week of the year starting on Sunday",
+    "W" => "the week of the year starting on Monday",
+    "w" => "the day of the week starting from Sunday as 0",
+    "C" => "the century",
+    "e" | "k" | "l" => "space padded values",
+    "P" => "lower case AM/PM",
+    "s" => "UNIX timestamps",
+    "c" | "x" | "X" | "r" | "+" => "locale or combined date and time formats",
+    "" => "an incomplete format specifier",
+    _ => "this format specifier"
+  }
+}
+
+fn token_description(token: &DateTimePatternToken) -> &'static str {
+  match token {
+    DateTimePatternToken::WeekInMonth(_) => "the week of the month",
+    DateTimePatternToken::TimezoneOffsetGmt(_) => "localized zone offsets",
+    DateTimePatternToken::QuarterOfYear(_) | DateTimePatternToken::QuarterOfYearNum(_) => "the quarter of the year",
+    DateTimePatternToken::MillisecondOfDay => "the millisecond of the day",
+    DateTimePatternToken::NanosecondOfDay => "the nanosecond of the day",
+    _ => "this pattern letter"
+  }
+}
+
+fn char_position(pattern: &str, remaining: &str) -> usize {
+  pattern[..pattern.len() - remaining.len()].chars().count()
+}
+
+fn push_literal(buffer: &mut String, literal: &str) {
+  let groups = literal.chars()
+    .group_by(|ch| ch.is_ascii_alphanumeric() || "{}[]#'".contains(*ch));
+  for (needs_quoting, group) in &groups {
+    let text = group.collect::<String>().replace('\'', "''");
+    if needs_quoting {
+      buffer.push('\'');
+      buffer.push_str(&text);
+      buffer.push('\'');
+    } else {
+      buffer.push_str(&text);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+
+  use crate::time_utils::validate_datetime;
+
+  use super::*;
+
+  #[test]
+  fn to_chrono_pattern_converts_java_patterns() {
+    expect!(to_chrono_pattern("yyyy-MM-dd")).to(be_ok().value("%Y-%m-%d"));
+    expect!(to_chrono_pattern("yyyy-MM-dd'T'HH:mm:ss.SSSXXX")).to(be_ok().value("%Y-%m-%dT%H:%M:%S.%3f%:z"));
+    expect!(to_chrono_pattern("EEE, d MMM yyyy HH:mm:ss Z")).to(be_ok().value("%a, %d %b %Y %H:%M:%S %z"));
+    expect!(to_chrono_pattern("hh 'o''clock' a")).to(be_ok().value("%I o'clock %p"));
+    expect!(to_chrono_pattern("'100%' yyyy")).to(be_ok().value("100%% %Y"));
+  }
+
+  #[test]
+  fn to_chrono_pattern_uses_the_week_based_year_for_week_patterns() {
+    expect!(to_chrono_pattern("YYYY-'W'ww-e")).to(be_ok().value("%G-W%V-%u"));
+    expect!(to_chrono_pattern("YY")).to(be_ok().value("%g"));
+    expect!(to_chrono_pattern("uuuu-MM-dd")).to(be_ok().value("%Y-%m-%d"));
+  }
+
+  #[test]
+  fn to_chrono_pattern_returns_an_error_for_unsupported_tokens() {
+    expect!(to_chrono_pattern("yyyy-MM 'week' W")).to(be_err().value(DateTimeFormatError::UnsupportedToken {
+      pattern: "yyyy-MM 'week' W".to_string(),
+      token: "W".to_string(),
+      position: 15,
+      reason: "chrono does not support the week of the month".to_string()
+    }));
+    expect!(to_chrono_pattern("yyyy QQQ").unwrap_err().to_string()).to(be_equal_to(
+      "'QQQ' at position 5 of 'yyyy QQQ' is not supported: chrono does not support the quarter of the year"));
+    expect!(to_chrono_pattern("HH:mm OOOO")).to(be_err());
+    expect!(to_chrono_pattern("A")).to(be_err());
+  }
+
+  #[test]
+  fn to_chrono_pattern_returns_an_error_for_invalid_patterns() {
+    expect!(to_chrono_pattern("")).to(be_err());
+    expect!(to_chrono_pattern("yyyy-MM-dd {")).to(be_err());
+    expect!(to_chrono_pattern("mmm")).to(be_err());
+  }
+
+  #[test]
+  fn from_chrono_pattern_converts_chrono_formats() {
+    expect!(from_chrono_pattern("%Y-%m-%d")).to(be_ok().value("yyyy-MM-dd"));
+    expect!(from_chrono_pattern("%Y-%m-%dT%H:%M:%S%.3f%:z")).to(be_ok().value("yyyy-MM-dd'T'HH:mm:ss.SSSxxx"));
+    expect!(from_chrono_pattern("%a, %-d %b %Y %H:%M:%S %z")).to(be_ok().value("EEE, d MMM yyyy HH:mm:ss Z"));
+    expect!(from_chrono_pattern("%I o'clock %p")).to(be_ok().value("hh 'o''clock' a"));
+    expect!(from_chrono_pattern("%F %T")).to(be_ok().value("yyyy-MM-dd HH:mm:ss"));
+    expect!(from_chrono_pattern("%G-W%V-%u")).to(be_ok().value("YYYY-'W'ww-e"));
+    expect!(from_chrono_pattern("100%% %Y")).to(be_ok().value("'100'% yyyy"));
+  }
+
+  #[test]
+  fn from_chrono_pattern_returns_an_error_for_unsupported_specifiers() {
+    expect!(from_chrono_pattern("%Y week %U")).to(be_err().value(DateTimeFormatError::UnsupportedToken {
+      pattern: "%Y week %U".to_string(),
+      token: "%U".to_string(),
+      position: 8,
+      reason: "there is no Java date/time pattern for the week of the year starting on Sunday".to_string()
+    }));
+    expect!(from_chrono_pattern("%s")).to(be_err());
+    expect!(from_chrono_pattern("%Y-%")).to(be_err());
+  }
+
+  #[test]
+  fn conversions_round_trip() {
+    for pattern in [
+      "yyyy-MM-dd",
+      "yyyy-MM-dd'T'HH:mm:ss.SSSZ",
+      "EEEE, MMMM dd yy hh:mm a",
+      "YYYY-'W'ww-e",
+      "DDD HH:mm:ss z"
+    ] {
+      let chrono_pattern = to_chrono_pattern(pattern).unwrap();
+      expect!(from_chrono_pattern(&chrono_pattern)).to(be_ok().value(pattern.to_string()));
+    }
+  }
+
+  #[test]
+  fn converted_chrono_patterns_produce_values_that_match_the_java_pattern() {
+    let date_time = chrono::DateTime::parse_from_rfc3339("2021-01-03T10:15:30.123+10:00").unwrap();
+    for pattern in ["yyyy-MM-dd", "YYYY-'W'ww-e", "yyyy-MM-dd'T'HH:mm:ss.SSSXXX", "EEE, d MMM yyyy HH:mm:ss Z"] {
+      let value = date_time.format(&to_chrono_pattern(pattern).unwrap()).to_string();
+      expect!(validate_datetime(&value, pattern)).to(be_ok());
+    }
+    let value = date_time.format(&to_chrono_pattern("YYYY-'W'ww").unwrap()).to_string();
+    expect!(value).to(be_equal_to("2020-W53"));
+  }
+}
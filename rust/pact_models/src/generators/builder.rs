@@ -0,0 +1,101 @@
+//! # Generators builder
+//!
+//! Chainable builder for constructing generators at runtime, without having to work with the
+//! maps in `Generators` directly. The category and path of each generator is checked when it is
+//! added, and any error is returned when the generators are built.
+//!
+//! ```
+//! use pact_models::generators::{Generator, Generators};
+//!
+//! let generators = Generators::builder()
+//!   .generator("body", "$.id", Generator::RandomInt(1, 100))
+//!   .generator("header", "X-Request-Id", Generator::Uuid(None))
+//!   .generator("status", "", Generator::RandomInt(200, 299))
+//!   .build()
+//!   .unwrap();
+//! assert!(generators.is_not_empty());
+//! ```
+
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use crate::generators::{Generator, GeneratorCategory, Generators};
+use crate::matchingrules::builder::path_for_category;
+
+/// Builder for `Generators`. Use `Generators::builder()` to create one.
+#[derive(Debug, Default)]
+pub struct GeneratorsBuilder {
+  generators: Generators,
+  error: Option<anyhow::Error>
+}
+
+impl GeneratorsBuilder {
+  /// Creates a new empty builder
+  pub fn new() -> Self {
+    GeneratorsBuilder::default()
+  }
+
+  /// Adds a generator to the category for the path, replacing any existing generator for the path.
+  /// The method, path and status categories do not have sub-paths, so the path must be empty.
+  pub fn generator(mut self, category: impl ToString, path: &str, generator: Generator) -> Self {
+    if self.error.is_none() {
+      let result = GeneratorCategory::from_str(&category.to_string())
+        .map_err(|err| anyhow!(err))
+        .and_then(|category| path_for_category(&category.into(), path).map(|path| (category, path)));
+      match result {
+        Ok((category, path)) => self.generators.add_generator_with_subcategory(&category, path, generator),
+        Err(err) => self.error = Some(err)
+      }
+    }
+    self
+  }
+
+  /// Returns the generators, or the first error from adding a generator
+  pub fn build(self) -> anyhow::Result<Generators> {
+    match self.error {
+      Some(err) => Err(err),
+      None => Ok(self.generators)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+
+  use crate::generators::{Generator, GeneratorCategory, Generators};
+  use crate::path_exp::DocPath;
+
+  #[test]
+  fn builds_the_generators_for_each_category() {
+    let generators = Generators::builder()
+      .generator(GeneratorCategory::BODY, "$.id", Generator::RandomInt(1, 10))
+      .generator("header", "X-Request-Id", Generator::Uuid(None))
+      .generator("STATUS", "", Generator::RandomInt(200, 299))
+      .build();
+
+    let mut expected = Generators::default();
+    expected.add_generator_with_subcategory(&GeneratorCategory::BODY, DocPath::new_unwrap("$.id"),
+      Generator::RandomInt(1, 10));
+    expected.add_generator_with_subcategory(&GeneratorCategory::HEADER, DocPath::root().join("X-Request-Id"),
+      Generator::Uuid(None));
+    expected.add_generator(&GeneratorCategory::STATUS, Generator::RandomInt(200, 299));
+    expect!(generators).to(be_ok().value(expected));
+  }
+
+  #[test]
+  fn returns_the_first_error() {
+    expect!(Generators::builder().generator("trailer", "a", Generator::Uuid(None)).build()).to(be_err());
+    expect!(Generators::builder().generator("body", "$.a[", Generator::Uuid(None)).build()).to(be_err());
+    expect!(Generators::builder().generator("method", "$.a", Generator::Uuid(None)).build()).to(be_err());
+    expect!(Generators::builder().generator("query", "", Generator::Uuid(None)).build()).to(be_err());
+
+    let result = Generators::builder()
+      .generator("body", "a", Generator::Uuid(None))
+      .generator("header", "", Generator::Uuid(None))
+      .build();
+    expect!(result.unwrap_err().to_string()).to(
+      be_equal_to("A path expression starting with '$' is required for the body category, but the path 'a' was given"));
+  }
+}
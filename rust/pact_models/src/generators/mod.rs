@@ -1,5 +1,6 @@
 //! `generators` module includes all the classes to deal with V3/V4 spec generators
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fmt::{Debug, Display, Formatter};
@@ -23,14 +24,16 @@ use tracing::{debug, trace, warn};
 use uuid::Uuid;
 
 use crate::bodies::OptionalBody;
+use crate::generators::builder::GeneratorsBuilder;
 use crate::expression_parser::{contains_expressions, DataType, DataValue, MapValueResolver, parse_expression};
 #[cfg(feature = "datetime")] use crate::generators::datetime_expressions::{execute_date_expression, execute_datetime_expression, execute_time_expression};
 use crate::json_utils::{get_field_as_string, json_to_string, JsonToNum};
-use crate::matchingrules::{Category, MatchingRuleCategory};
+use crate::matchingrules::{Category, EpochUnit, MatchingRuleCategory, NumericRange};
 use crate::PactSpecification;
 use crate::path_exp::{DocPath, PathToken};
-#[cfg(feature = "datetime")] use crate::time_utils::{parse_pattern, to_chrono_pattern};
+#[cfg(feature = "datetime")] use crate::datetime_format::to_chrono_pattern;
 
+pub mod builder;
 #[cfg(feature = "datetime")] pub mod datetime_expressions;
 #[cfg(feature = "datetime")] mod date_expression_parser;
 #[cfg(feature = "datetime")] mod time_expression_parser;
@@ -143,7 +146,12 @@ pub enum Generator {
   /// Generates a URL with the mock server as the base URL
   MockServerURL(String, String),
   /// List of variants which can have embedded generators
-  ArrayContains(Vec<(usize, MatchingRuleCategory, HashMap<DocPath, Generator>)>)
+  ArrayContains(Vec<(usize, MatchingRuleCategory, HashMap<DocPath, Generator>)>),
+  /// Generates the current time as a timestamp since the Unix epoch in the given unit
+  EpochTimestamp(EpochUnit),
+  /// Generates a random number within the range. An integer is generated if the example value is
+  /// an integer, otherwise a decimal number.
+  RandomNumber(NumericRange)
 }
 
 impl Generator {
@@ -193,6 +201,15 @@ impl Generator {
         }
       }
       Generator::MockServerURL(example, regex) => Some(json!({ "type": "MockServerURL", "example": example, "regex": regex })),
+      Generator::EpochTimestamp(unit) => Some(json!({ "type": "EpochTimestamp", "unit": unit.to_string() })),
+      Generator::RandomNumber(range) => {
+        let mut json = json!({ "type": "RandomNumber" });
+        let map = json.as_object_mut().unwrap();
+        for (key, value) in range.to_map() {
+          map.insert(key.to_string(), value);
+        }
+        Some(json)
+      },
       _ => None
     }
   }
@@ -223,6 +240,17 @@ impl Generator {
           .map(|dt| DataType::from(dt.clone())))),
       "MockServerURL" => Some(Generator::MockServerURL(get_field_as_string("example", map).unwrap_or_default(),
                                                        get_field_as_string("regex", map).unwrap_or_default())),
+      "EpochTimestamp" => match get_field_as_string("unit", map) {
+        Some(unit) => EpochUnit::from_str(unit.as_str()).ok().map(Generator::EpochTimestamp),
+        None => Some(Generator::EpochTimestamp(EpochUnit::default()))
+      },
+      "RandomNumber" => match NumericRange::from_map(map) {
+        Ok(range) => Some(Generator::RandomNumber(range)),
+        Err(err) => {
+          warn!("RandomNumber generator is not valid - {}", err);
+          None
+        }
+      },
       _ => {
         warn!("'{}' is not a valid generator type", gen_type);
         None
@@ -255,6 +283,8 @@ impl Generator {
       Generator::ProviderStateGenerator(_, _) => "ProviderStateGenerator",
       Generator::MockServerURL(_, _) => "MockServerURL",
       Generator::ArrayContains(_) => "ArrayContains",
+      Generator::EpochTimestamp(_) => "EpochTimestamp",
+      Generator::RandomNumber(_) => "RandomNumber",
     }.to_string()
   }
 
@@ -308,7 +338,9 @@ impl Generator {
             (key.to_string(), gen.to_json().unwrap())
           }).collect())])
         }).collect()
-      }
+      },
+      Generator::EpochTimestamp(unit) => hashmap!{ "unit" => Value::String(unit.to_string()) },
+      Generator::RandomNumber(range) => range.to_map()
     }
   }
 
@@ -365,6 +397,8 @@ impl Hash for Generator {
         }
       }
       Generator::Uuid(format) => format.hash(state),
+      Generator::EpochTimestamp(unit) => unit.hash(state),
+      Generator::RandomNumber(range) => range.hash(state),
       _ => ()
     }
   }
@@ -385,6 +419,8 @@ impl PartialEq for Generator {
       (Generator::MockServerURL(ex1, re1), Generator::MockServerURL(ex2, re2)) => ex1 == ex2 && re1 == re2,
       (Generator::ArrayContains(variants1), Generator::ArrayContains(variants2)) => variants1 == variants2,
       (Generator::Uuid(format), Generator::Uuid(format2)) => format == format2,
+      (Generator::EpochTimestamp(unit1), Generator::EpochTimestamp(unit2)) => unit1 == unit2,
+      (Generator::RandomNumber(range1), Generator::RandomNumber(range2)) => range1 == range2,
       _ => mem::discriminant(self) == mem::discriminant(other)
     }
   }
@@ -457,6 +493,13 @@ impl Into<String> for GeneratorCategory {
   }
 }
 
+impl Display for GeneratorCategory {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let s: &str = (*self).into();
+    write!(f, "{}", s)
+  }
+}
+
 impl Into<Category> for GeneratorCategory {
   fn into(self) -> Category {
     match self {
@@ -512,6 +555,11 @@ pub struct Generators {
 }
 
 impl Generators {
+  /// Returns a builder for constructing generators at runtime
+  pub fn builder() -> GeneratorsBuilder {
+    GeneratorsBuilder::new()
+  }
+
   /// If the generators are empty (that is there are no rules assigned to any categories)
   pub fn is_empty(&self) -> bool {
     self.categories.values().all(|category| category.is_empty())
@@ -778,22 +826,78 @@ pub fn generate_value_from_context(expression: &str, context: &HashMap<&str, Val
   data_type.clone().unwrap_or(DataType::RAW).wrap(result)
 }
 
+/// Key in the generator context for a seed for the random generators. If set, the random
+/// generators (i.e. RandomInt, Uuid, RandomString) will generate the same values each time they
+/// are applied with the same seed, so generated requests and responses can be reproduced. The
+/// date and time generators are not affected, use the `baseDate`, `baseTime` and `baseDateTime`
+/// context values for those.
+pub const RANDOM_SEED: &str = "randomSeed";
+
+/// Key in the generator context for the location of the value being generated (i.e. the JSON
+/// pointer in a body, or `header:<name>` for a header). This is combined with the random seed so
+/// values at different locations are different.
+pub const RANDOM_SEED_PATH: &str = "randomSeedPath";
+
+/// Returns the source of random values for the generator. If the context has a random seed, a
+/// seeded source is returned that is derived from the seed, the location of the value, the
+/// generator and the source value. Otherwise the thread local random source is used.
+pub fn random_source(generator: &Generator, value: &str, context: &HashMap<&str, Value>) -> Box<dyn RngCore> {
+  let seed = context.get(RANDOM_SEED)
+    .and_then(|seed| seed.as_u64().or_else(|| seed.as_str().and_then(|seed| seed.parse().ok())));
+  match seed {
+    Some(seed) => {
+      let path = context.get(RANDOM_SEED_PATH).map(json_to_string).unwrap_or_default();
+      let generator = format!("{:?}", generator);
+      let seed = seed.to_le_bytes();
+      let parts = [ seed.as_slice(), path.as_bytes(), generator.as_bytes(), value.as_bytes() ];
+      let bytes = parts.iter()
+        .flat_map(|part| part.iter().chain(&[0xFF]))
+        .cloned()
+        .collect::<Vec<u8>>();
+      // FNV-1a is used so the derived seeds are stable across builds
+      Box::new(StdRng::seed_from_u64(hashers::fnv::fnv1a64(&bytes)))
+    }
+    None => Box::new(rand::thread_rng())
+  }
+}
+
+/// Returns the generator context to use to generate the value at the given location. If the
+/// context has a random seed, a copy of the context is returned with the location set, so the
+/// random generators generate different values for different locations. Otherwise the context is
+/// returned unchanged.
+pub fn with_random_seed_path<'a, 'b>(context: &'b HashMap<&'a str, Value>, path: &str) -> Cow<'b, HashMap<&'a str, Value>> {
+  if context.contains_key(RANDOM_SEED) {
+    let mut context = context.clone();
+    context.insert(RANDOM_SEED_PATH, json!(path));
+    Cow::Owned(context)
+  } else {
+    Cow::Borrowed(context)
+  }
+}
+
+fn random_uuid<R: Rng + ?Sized>(rnd: &mut R) -> Uuid {
+  uuid::Builder::from_random_bytes(rnd.gen()).into_uuid()
+}
+
 const DIGIT_CHARSET: &str = "0123456789";
 pub fn generate_decimal(digits: usize) -> String {
-  let mut rnd = rand::thread_rng();
+  random_decimal(digits, &mut rand::thread_rng())
+}
+
+fn random_decimal<R: Rng + ?Sized>(digits: usize, rnd: &mut R) -> String {
   let chars: Vec<char> = DIGIT_CHARSET.chars().collect();
   match digits {
     0 => "".to_string(),
-    1 => chars.choose(&mut rnd).unwrap().to_string(),
-    2 => format!("{}.{}", chars.choose(&mut rnd).unwrap(), chars.choose(&mut rnd).unwrap()),
+    1 => chars.choose(rnd).unwrap().to_string(),
+    2 => format!("{}.{}", chars.choose(rnd).unwrap(), chars.choose(rnd).unwrap()),
     _ => {
       let mut sample = String::new();
       for _ in 0..(digits + 1) {
-        sample.push(*chars.choose(&mut rnd).unwrap());
+        sample.push(*chars.choose(rnd).unwrap());
       }
       if sample.starts_with("00") {
         let chars = DIGIT_CHARSET[1..].chars();
-        sample.insert(0, chars.choose(&mut rnd).unwrap());
+        sample.insert(0, chars.choose(rnd).unwrap());
       }
       let pos = rnd.gen_range(1..digits - 1);
       let selected_digits = if pos != 1 && sample.starts_with('0') {
@@ -811,8 +915,11 @@ pub fn generate_decimal(digits: usize) -> String {
 
 const HEX_CHARSET: &str = "0123456789ABCDEF";
 pub fn generate_hexadecimal(digits: usize) -> String {
-  let mut rnd = rand::thread_rng();
-  HEX_CHARSET.chars().choose_multiple(&mut rnd, digits).iter().join("")
+  random_hexadecimal(digits, &mut rand::thread_rng())
+}
+
+fn random_hexadecimal<R: Rng + ?Sized>(digits: usize, rnd: &mut R) -> String {
+  HEX_CHARSET.chars().choose_multiple(rnd, digits).iter().join("")
 }
 
 impl GenerateValue<u16> for Generator {
@@ -823,7 +930,8 @@ impl GenerateValue<u16> for Generator {
     _matcher: &Box<dyn VariantMatcher + Send + Sync>
   ) -> anyhow::Result<u16> {
     match self {
-      &Generator::RandomInt(min, max) => Ok(rand::thread_rng().gen_range(min as u16..(max as u16).saturating_add(1))),
+      &Generator::RandomInt(min, max) => Ok(random_source(self, &value.to_string(), context)
+        .gen_range(min as u16..(max as u16).saturating_add(1))),
       &Generator::ProviderStateGenerator(ref exp, ref dt) =>
         match generate_value_from_context(exp, context, dt) {
           Ok(val) => u16::try_from(val),
@@ -835,7 +943,11 @@ impl GenerateValue<u16> for Generator {
 }
 
 pub fn generate_ascii_string(size: usize) -> String {
-  rand::thread_rng().sample_iter(&Alphanumeric).map(char::from).take(size).collect()
+  random_ascii_string(size, &mut rand::thread_rng())
+}
+
+fn random_ascii_string<R: Rng + ?Sized>(size: usize, rnd: &mut R) -> String {
+  (0..size).map(|_| char::from(rnd.sample(Alphanumeric))).collect()
 }
 
 fn strip_anchors(regex: &str) -> &str {
@@ -859,25 +971,60 @@ fn replace_with_regex(example: &String, url: String, re: Regex) -> String {
   }).to_string()
 }
 
+/// Width of the range of generated numbers when the range only has one bound
+const UNBOUNDED_RANGE_WIDTH: f64 = 1000.0;
+
+/// Generates a random number within the range. An integer will be generated if `integer` is set
+/// and there are integers within the range, otherwise a decimal number.
+pub fn generate_number_in_range(range: &NumericRange, integer: bool) -> anyhow::Result<Value> {
+  random_number_in_range(range, integer, &mut rand::thread_rng())
+}
+
+fn random_number_in_range<R: Rng + ?Sized>(range: &NumericRange, integer: bool, rnd: &mut R) -> anyhow::Result<Value> {
+  let (min, max) = match (range.min, range.max) {
+    (Some(min), Some(max)) => (min.value(), max.value()),
+    (Some(min), None) => (min.value(), min.value() + UNBOUNDED_RANGE_WIDTH),
+    (None, Some(max)) => (max.value() - UNBOUNDED_RANGE_WIDTH, max.value()),
+    (None, None) => (0.0, UNBOUNDED_RANGE_WIDTH)
+  };
+
+  if integer {
+    let low = if range.contains(min.ceil()) { min.ceil() } else { min.ceil() + 1.0 };
+    let high = if range.contains(max.floor()) { max.floor() } else { max.floor() - 1.0 };
+    if low <= high && low >= i64::MIN as f64 && high <= i64::MAX as f64 {
+      return Ok(json!(rnd.gen_range(low as i64..=high as i64)));
+    }
+  }
+
+  // An exclusive bound can be generated, so retry until the number is within the range
+  for _ in 0..100 {
+    let value = rnd.gen_range(min..=max);
+    if range.contains(value) {
+      return Ok(json!(value));
+    }
+  }
+  Err(anyhow!("Could not generate a number in the range {}", range))
+}
+
 impl GenerateValue<String> for Generator {
   fn generate_value(
     &self,
-    _: &String,
+    value: &String,
     context: &HashMap<&str, Value>,
     _matcher: &Box<dyn VariantMatcher + Send + Sync>
   ) -> anyhow::Result<String> {
-    let mut rnd = rand::thread_rng();
+    let mut rnd = random_source(self, value, context);
     let result = match self {
       Generator::RandomInt(min, max) => Ok(format!("{}", rnd.gen_range(*min..max.saturating_add(1)))),
       Generator::Uuid(format) => match format.unwrap_or_default() {
-        UuidFormat::Simple => Ok(Uuid::new_v4().as_simple().to_string()),
-        UuidFormat::LowerCaseHyphenated => Ok(Uuid::new_v4().as_hyphenated().to_string()),
-        UuidFormat::UpperCaseHyphenated => Ok(Uuid::new_v4().as_hyphenated().to_string().to_uppercase()),
-        UuidFormat::Urn => Ok(Uuid::new_v4().as_urn().to_string())
+        UuidFormat::Simple => Ok(random_uuid(&mut rnd).as_simple().to_string()),
+        UuidFormat::LowerCaseHyphenated => Ok(random_uuid(&mut rnd).as_hyphenated().to_string()),
+        UuidFormat::UpperCaseHyphenated => Ok(random_uuid(&mut rnd).as_hyphenated().to_string().to_uppercase()),
+        UuidFormat::Urn => Ok(random_uuid(&mut rnd).as_urn().to_string())
       },
-      Generator::RandomDecimal(digits) => Ok(generate_decimal(*digits as usize)),
-      Generator::RandomHexadecimal(digits) => Ok(generate_hexadecimal(*digits as usize)),
-      Generator::RandomString(size) => Ok(generate_ascii_string(*size as usize)),
+      Generator::RandomDecimal(digits) => Ok(random_decimal(*digits as usize, &mut rnd)),
+      Generator::RandomHexadecimal(digits) => Ok(random_hexadecimal(*digits as usize, &mut rnd)),
+      Generator::RandomString(size) => Ok(random_ascii_string(*size as usize, &mut rnd)),
       Generator::Regex(ref regex) => {
         let mut parser = regex_syntax::ParserBuilder::new().unicode(false).build();
         match parser.parse(strip_anchors(regex)) {
@@ -905,10 +1052,10 @@ impl GenerateValue<String> for Generator {
           };
           let date = execute_date_expression(&base, _exp.clone().unwrap_or_default().as_str())?;
           match _format {
-            Some(pattern) => match parse_pattern(pattern) {
-              Ok(tokens) => {
+            Some(pattern) => match to_chrono_pattern(pattern) {
+              Ok(chrono_pattern) => {
                 #[allow(deprecated)]
-                Ok(date.date().format(&to_chrono_pattern(&tokens)).to_string())
+                Ok(date.date().format(&chrono_pattern).to_string())
               },
               Err(err) => {
                 warn!("Date format {} is not valid - {}", pattern, err);
@@ -932,8 +1079,8 @@ impl GenerateValue<String> for Generator {
           };
           let time = execute_time_expression(&base, _exp.clone().unwrap_or_default().as_str())?;
           match _format {
-            Some(pattern) => match parse_pattern(pattern) {
-              Ok(tokens) => Ok(time.format(&to_chrono_pattern(&tokens)).to_string()),
+            Some(pattern) => match to_chrono_pattern(pattern) {
+              Ok(chrono_pattern) => Ok(time.format(&chrono_pattern).to_string()),
               Err(err) => {
                 warn!("Time format {} is not valid - {}", pattern, err);
                 Err(anyhow!("Time format {} is not valid - {}", pattern, err))
@@ -956,8 +1103,8 @@ impl GenerateValue<String> for Generator {
           };
           let date_time = execute_datetime_expression(&base, _exp.clone().unwrap_or_default().as_str())?;
           match _format {
-            Some(pattern) => match parse_pattern(pattern) {
-              Ok(tokens) => Ok(date_time.format(&to_chrono_pattern(&tokens)).to_string()),
+            Some(pattern) => match to_chrono_pattern(pattern) {
+              Ok(chrono_pattern) => Ok(date_time.format(&chrono_pattern).to_string()),
               Err(err) => {
                 warn!("DateTime format {} is not valid - {}", pattern, err);
                 Err(anyhow!("DateTime format {} is not valid - {}", pattern, err))
@@ -991,7 +1138,10 @@ impl GenerateValue<String> for Generator {
       } else {
         Err(anyhow!("MockServerURL: can not generate a value as there is no mock server details in the test context"))
       },
-      Generator::ArrayContains(_) => Err(anyhow!("can only use ArrayContains with lists"))
+      Generator::ArrayContains(_) => Err(anyhow!("can only use ArrayContains with lists")),
+      Generator::EpochTimestamp(unit) => Ok(unit.now().to_string()),
+      Generator::RandomNumber(range) => random_number_in_range(range, value.parse::<i64>().is_ok(), &mut rnd)
+        .map(|number| number.to_string())
     };
     debug!("Generator = {:?}, Generated value = {:?}", self, result);
     result
@@ -1005,6 +1155,15 @@ impl GenerateValue<Vec<String>> for Generator {
     context: &HashMap<&str, Value>,
     matcher: &Box<dyn VariantMatcher + Send + Sync>
   ) -> anyhow::Result<Vec<String>> {
+    // A provider state value that is a list generates multiple values (i.e. a multi-value header)
+    if let Generator::ProviderStateGenerator(exp, dt) = self {
+      if let Some(Value::Array(values)) = context.get(exp.as_str()) {
+        return values.iter()
+          .map(|value| generate_value_from_context(exp, &hashmap!{ exp.as_str() => value.clone() }, dt)
+            .map(|val| val.to_string()))
+          .collect();
+      }
+    }
     self.generate_value(&vals.first().cloned().unwrap_or_default(), context, matcher).map(|v| vec![v])
   }
 }
@@ -1017,9 +1176,10 @@ impl GenerateValue<Value> for Generator {
     matcher: &Box<dyn VariantMatcher + Send + Sync>
   ) -> anyhow::Result<Value> {
     debug!(context = ?context, "Generating value from {:?}", self);
+    let mut rnd = random_source(self, &value.to_string(), context);
     let result = match self {
       Generator::RandomInt(min, max) => {
-        let rand_int = rand::thread_rng().gen_range(*min..max.saturating_add(1));
+        let rand_int = rnd.gen_range(*min..max.saturating_add(1));
         match value {
           Value::String(_) => Ok(json!(format!("{}", rand_int))),
           Value::Number(_) => Ok(json!(rand_int)),
@@ -1028,29 +1188,29 @@ impl GenerateValue<Value> for Generator {
       },
       Generator::Uuid(format) => match value {
         Value::String(_) => match format.unwrap_or_default() {
-          UuidFormat::Simple => Ok(json!(Uuid::new_v4().as_simple().to_string())),
-          UuidFormat::LowerCaseHyphenated => Ok(json!(Uuid::new_v4().as_hyphenated().to_string())),
-          UuidFormat::UpperCaseHyphenated => Ok(json!(Uuid::new_v4().as_hyphenated().to_string().to_uppercase())),
-          UuidFormat::Urn => Ok(json!(Uuid::new_v4().as_urn().to_string()))
+          UuidFormat::Simple => Ok(json!(random_uuid(&mut rnd).as_simple().to_string())),
+          UuidFormat::LowerCaseHyphenated => Ok(json!(random_uuid(&mut rnd).as_hyphenated().to_string())),
+          UuidFormat::UpperCaseHyphenated => Ok(json!(random_uuid(&mut rnd).as_hyphenated().to_string().to_uppercase())),
+          UuidFormat::Urn => Ok(json!(random_uuid(&mut rnd).as_urn().to_string()))
         },
-        _ => Ok(json!(Uuid::new_v4().as_hyphenated().to_string()))
+        _ => Ok(json!(random_uuid(&mut rnd).as_hyphenated().to_string()))
       },
       Generator::RandomDecimal(digits) => match value {
-        Value::String(_) => Ok(json!(generate_decimal(*digits as usize))),
-        Value::Number(_) => match generate_decimal(*digits as usize).parse::<f64>() {
+        Value::String(_) => Ok(json!(random_decimal(*digits as usize, &mut rnd))),
+        Value::Number(_) => match random_decimal(*digits as usize, &mut rnd).parse::<f64>() {
           Ok(val) => Ok(json!(val)),
           Err(err) => Err(anyhow!("Could not generate a random decimal from {} - {}", value, err))
         },
-        _ => Ok(json!(generate_decimal(*digits as usize)))
+        _ => Ok(json!(random_decimal(*digits as usize, &mut rnd)))
       },
-      Generator::RandomHexadecimal(digits) => Ok(json!(generate_hexadecimal(*digits as usize))),
-      Generator::RandomString(size) => Ok(json!(generate_ascii_string(*size as usize))),
+      Generator::RandomHexadecimal(digits) => Ok(json!(random_hexadecimal(*digits as usize, &mut rnd))),
+      Generator::RandomString(size) => Ok(json!(random_ascii_string(*size as usize, &mut rnd))),
       Generator::Regex(ref regex) => {
         let mut parser = regex_syntax::ParserBuilder::new().unicode(false).build();
         match parser.parse(regex) {
           Ok(hir) => {
             match rand_regex::Regex::with_hir(hir, 20) {
-              Ok(gen) => Ok(json!(rand::thread_rng().sample::<String, _>(gen))),
+              Ok(gen) => Ok(json!(rnd.sample::<String, _>(gen))),
               Err(err) => {
                 warn!("Failed to generate a value from regular expression - {}", err);
                 Err(anyhow!("Failed to generate a value from regular expression - {}", err))
@@ -1072,10 +1232,10 @@ impl GenerateValue<Value> for Generator {
           };
           let date = execute_date_expression(&base, _exp.clone().unwrap_or_default().as_str())?;
           match _format {
-            Some(pattern) => match parse_pattern(pattern) {
-              Ok(tokens) => {
+            Some(pattern) => match to_chrono_pattern(pattern) {
+              Ok(chrono_pattern) => {
                 #[allow(deprecated)]
-                Ok(json!(date.date().format(&to_chrono_pattern(&tokens)).to_string()))
+                Ok(json!(date.date().format(&chrono_pattern).to_string()))
               },
               Err(err) => {
                 warn!("Date format {} is not valid - {}", pattern, err);
@@ -1099,8 +1259,8 @@ impl GenerateValue<Value> for Generator {
           };
           let time = execute_time_expression(&base, _exp.clone().unwrap_or_default().as_str())?;
           match _format {
-            Some(pattern) => match parse_pattern(pattern) {
-              Ok(tokens) => Ok(json!(time.format(&to_chrono_pattern(&tokens)).to_string())),
+            Some(pattern) => match to_chrono_pattern(pattern) {
+              Ok(chrono_pattern) => Ok(json!(time.format(&chrono_pattern).to_string())),
               Err(err) => {
                 warn!("Time format {} is not valid - {}", pattern, err);
                 Err(anyhow!("Could not generate a random time from {} - {}", pattern, err))
@@ -1123,8 +1283,8 @@ impl GenerateValue<Value> for Generator {
           };
           let date_time = execute_datetime_expression(&base, _exp.clone().unwrap_or_default().as_str())?;
           match _format {
-            Some(pattern) => match parse_pattern(pattern) {
-              Ok(tokens) => Ok(json!(date_time.format(&to_chrono_pattern(&tokens)).to_string())),
+            Some(pattern) => match to_chrono_pattern(pattern) {
+              Ok(chrono_pattern) => Ok(json!(date_time.format(&chrono_pattern).to_string())),
               Err(err) => {
                 warn!("DateTime format {} is not valid - {}", pattern, err);
                 Err(anyhow!("Could not generate a random date-time from {} - {}", pattern, err))
@@ -1138,7 +1298,7 @@ impl GenerateValue<Value> for Generator {
           Err(anyhow!("DateTime generators require the 'datetime' feature to be enabled"))
         }
       },
-      Generator::RandomBoolean => Ok(json!(rand::thread_rng().gen::<bool>())),
+      Generator::RandomBoolean => Ok(json!(rnd.gen::<bool>())),
       Generator::ProviderStateGenerator(ref exp, ref dt) =>
         match generate_value_from_context(exp, context, dt) {
           Ok(val) => val.as_json(),
@@ -1181,6 +1341,16 @@ impl GenerateValue<Value> for Generator {
           Ok(Value::Array(result))
         }
         _ => Err(anyhow!("can only use ArrayContains with lists"))
+      },
+      Generator::EpochTimestamp(unit) => match value {
+        Value::String(_) => Ok(json!(unit.now().to_string())),
+        _ => Ok(json!(unit.now()))
+      },
+      Generator::RandomNumber(range) => match value {
+        Value::String(s) => random_number_in_range(range, s.parse::<i64>().is_ok(), &mut rnd)
+          .map(|number| json!(number.to_string())),
+        Value::Number(n) => random_number_in_range(range, n.is_i64() || n.is_u64(), &mut rnd),
+        _ => random_number_in_range(range, true, &mut rnd)
       }
     };
     debug!("Generated value = {:?}", result);
@@ -1300,8 +1470,9 @@ impl ContentTypeHandler<Value> for JsonHandler {
 
     if !expanded_paths.is_empty() {
       for pointer_str in expanded_paths {
+        let context = with_random_seed_path(context, &pointer_str);
         match self.value.pointer_mut(&pointer_str) {
-          Some(json_value) => match generator.generate_value(&json_value.clone(), context, matcher) {
+          Some(json_value) => match generator.generate_value(&json_value.clone(), &context, matcher) {
             Ok(new_value) => *json_value = new_value,
             Err(_) => ()
           },
@@ -1993,6 +2164,17 @@ mod tests {
       &hashmap!{ "a".into() => json!(1234) }, &NoopVariantMatcher.boxed())).to(be_ok().value(1234));
   }
 
+  #[test]
+  fn provider_state_generator_for_a_list_of_values_test() {
+    let context = hashmap!{ "a" => json!(1234), "b" => json!(["one", 2]) };
+    expect!(Generator::ProviderStateGenerator("a".into(), None).generate_value(&vec!["x".to_string(), "y".to_string()],
+      &context, &NoopVariantMatcher.boxed())).to(be_ok().value(vec!["1234".to_string()]));
+    expect!(Generator::ProviderStateGenerator("b".into(), None).generate_value(&vec!["x".to_string()],
+      &context, &NoopVariantMatcher.boxed())).to(be_ok().value(vec!["one".to_string(), "2".to_string()]));
+    expect!(Generator::ProviderStateGenerator("/items/${a}".into(), None).generate_value(&vec![],
+      &context, &NoopVariantMatcher.boxed())).to(be_ok().value(vec!["/items/1234".to_string()]));
+  }
+
   #[test]
   #[cfg(feature = "datetime")]
   fn date_generator_test() {
@@ -2099,6 +2281,122 @@ mod tests {
     assert_that!(generated.unwrap(), matches_regex(r"^urn:uuid:[a-fA-F0-9]{8}-[a-fA-F0-9]{4}-[a-fA-F0-9]{4}-[a-fA-F0-9]{4}-[a-fA-F0-9]{12}$"));
   }
 
+  #[test]
+  fn epoch_timestamp_generator_test() {
+    let before = EpochUnit::Seconds.now();
+    let generated = Generator::EpochTimestamp(EpochUnit::Seconds).generate_value(&"".to_string(), &hashmap!{}, &NoopVariantMatcher.boxed()).unwrap();
+    let generated = generated.parse::<u64>().unwrap();
+    expect!(generated >= before && generated <= EpochUnit::Seconds.now()).to(be_true());
+
+    let generated = Generator::EpochTimestamp(EpochUnit::Millis).generate_value(&json!(1700000000000u64), &hashmap!{}, &NoopVariantMatcher.boxed()).unwrap();
+    expect!(generated.is_u64()).to(be_true());
+    let generated = Generator::EpochTimestamp(EpochUnit::Millis).generate_value(&json!("1700000000000"), &hashmap!{}, &NoopVariantMatcher.boxed()).unwrap();
+    expect!(generated.is_string()).to(be_true());
+
+    expect!(Generator::from_map("EpochTimestamp", &json!({ "unit": "nanos" }).as_object().unwrap())).to(
+      be_some().value(Generator::EpochTimestamp(EpochUnit::Nanos)));
+    expect!(Generator::from_map("EpochTimestamp", &serde_json::Map::new())).to(
+      be_some().value(Generator::EpochTimestamp(EpochUnit::Millis)));
+    expect!(Generator::EpochTimestamp(EpochUnit::Seconds).to_json()).to(
+      be_some().value(json!({ "type": "EpochTimestamp", "unit": "seconds" })));
+  }
+
+  #[test]
+  fn random_number_generator_test() {
+    use crate::matchingrules::NumericBound;
+
+    let range = NumericRange::new(Some(NumericBound::Inclusive(0.0)), Some(NumericBound::Exclusive(3.0))).unwrap();
+    for _ in 1..20 {
+      let generated = Generator::RandomNumber(range).generate_value(&json!(1), &hashmap!{}, &NoopVariantMatcher.boxed()).unwrap();
+      let number = generated.as_i64().unwrap();
+      expect!(number >= 0 && number < 3).to(be_true());
+
+      let generated = Generator::RandomNumber(range).generate_value(&json!(1.5), &hashmap!{}, &NoopVariantMatcher.boxed()).unwrap();
+      expect!(range.contains(generated.as_f64().unwrap())).to(be_true());
+
+      let generated = Generator::RandomNumber(range).generate_value(&"2".to_string(), &hashmap!{}, &NoopVariantMatcher.boxed()).unwrap();
+      expect!(range.contains(generated.parse::<i64>().unwrap() as f64)).to(be_true());
+    }
+
+    let range = NumericRange::new(Some(NumericBound::Exclusive(100.0)), None).unwrap();
+    let generated = Generator::RandomNumber(range).generate_value(&json!("150"), &hashmap!{}, &NoopVariantMatcher.boxed()).unwrap();
+    let number = generated.as_str().unwrap().parse::<i64>().unwrap();
+    expect!(number > 100).to(be_true());
+
+    let range = NumericRange::new(Some(NumericBound::Exclusive(0.1)), Some(NumericBound::Inclusive(0.9))).unwrap();
+    let generated = Generator::RandomNumber(range).generate_value(&json!(1), &hashmap!{}, &NoopVariantMatcher.boxed()).unwrap();
+    expect!(range.contains(generated.as_f64().unwrap())).to(be_true());
+
+    expect!(Generator::from_map("RandomNumber", &json!({ "minimum": 0, "exclusiveMaximum": 10 }).as_object().unwrap())).to(
+      be_some().value(Generator::RandomNumber(NumericRange::new(Some(NumericBound::Inclusive(0.0)), Some(NumericBound::Exclusive(10.0))).unwrap())));
+    expect!(Generator::from_map("RandomNumber", &serde_json::Map::new())).to(be_none());
+    expect!(Generator::RandomNumber(NumericRange::new(None, Some(NumericBound::Inclusive(1.5))).unwrap()).to_json()).to(
+      be_some().value(json!({ "type": "RandomNumber", "maximum": 1.5 })));
+  }
+
+  #[test]
+  fn random_generators_with_a_seed_generate_the_same_values() {
+    let seeded = hashmap!{ RANDOM_SEED => json!(1234) };
+    let other_seed = hashmap!{ RANDOM_SEED => json!("5678") };
+    let generators = vec![
+      Generator::RandomInt(0, 1000000),
+      Generator::Uuid(None),
+      Generator::RandomString(20),
+      Generator::RandomHexadecimal(20),
+      Generator::RandomDecimal(10),
+      Generator::Regex("[a-z]{20}".to_string())
+    ];
+
+    for generator in &generators {
+      let value = json!("example");
+      let generated = generator.generate_value(&value, &seeded, &NoopVariantMatcher.boxed()).unwrap();
+      expect!(generator.generate_value(&value, &seeded, &NoopVariantMatcher.boxed()).unwrap())
+        .to(be_equal_to(generated.clone()));
+      expect!(generator.generate_value(&value, &other_seed, &NoopVariantMatcher.boxed()).unwrap())
+        .to_not(be_equal_to(generated.clone()));
+      expect!(generator.generate_value(&value, &hashmap!{}, &NoopVariantMatcher.boxed()).unwrap())
+        .to_not(be_equal_to(generated));
+
+      let value = "example".to_string();
+      let generated = generator.generate_value(&value, &seeded, &NoopVariantMatcher.boxed()).unwrap();
+      expect!(generator.generate_value(&value, &seeded, &NoopVariantMatcher.boxed()).unwrap())
+        .to(be_equal_to(generated));
+    }
+
+    let generated = Generator::Uuid(None).generate_value(&"".to_string(), &seeded, &NoopVariantMatcher.boxed()).unwrap();
+    let uuid = Uuid::parse_str(&generated).unwrap();
+    expect!(uuid.get_version_num()).to(be_equal_to(4));
+  }
+
+  #[test]
+  fn json_handler_with_a_random_seed_generates_different_values_for_each_location() {
+    let seeded = hashmap!{ RANDOM_SEED => json!(1234) };
+    let generators = hashmap!{
+      DocPath::new_unwrap("$.a") => Generator::RandomInt(0, 1000000),
+      DocPath::new_unwrap("$.b") => Generator::RandomInt(0, 1000000)
+    };
+    let generate = || {
+      let mut handler = JsonHandler { value: json!({ "a": 1, "b": 1 }) };
+      handler.process_body(&generators, &GeneratorTestMode::Provider, &seeded, &NoopVariantMatcher.boxed()).unwrap();
+      handler.value
+    };
+
+    let generated = generate();
+    expect!(generate()).to(be_equal_to(generated.clone()));
+    expect!(generated["a"].clone()).to_not(be_equal_to(generated["b"].clone()));
+  }
+
+  #[test]
+  fn with_random_seed_path_only_sets_the_path_if_there_is_a_seed() {
+    let seeded = hashmap!{ RANDOM_SEED => json!(1234) };
+    expect!(with_random_seed_path(&seeded, "header:X-Id").into_owned()).to(be_equal_to(hashmap!{
+      RANDOM_SEED => json!(1234),
+      RANDOM_SEED_PATH => json!("header:X-Id")
+    }));
+    let context = hashmap!{ "baseDate" => json!("2020-01-01") };
+    expect!(with_random_seed_path(&context, "header:X-Id").into_owned()).to(be_equal_to(context.clone()));
+  }
+
   #[test]
   fn random_decimal_generator_test() {
     for _ in 1..10 {
@@ -1,9 +1,13 @@
 //! Models for Pact interactions
 
+use std::collections::hash_map::DefaultHasher;
 use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::panic::RefUnwindSafe;
 use std::sync::{Arc, Mutex};
 
+use itertools::Itertools;
+use regex::Regex;
 use serde_json::Value;
 
 use crate::bodies::OptionalBody;
@@ -13,6 +17,7 @@ use crate::message::Message;
 use crate::PactSpecification;
 use crate::provider_states::ProviderState;
 use crate::sync_interaction::RequestResponseInteraction;
+use crate::v4::V4InteractionType;
 use crate::v4::async_message::AsynchronousMessage;
 use crate::v4::interaction::{interaction_from_json, V4Interaction};
 use crate::v4::sync_message::SynchronousMessage;
@@ -141,6 +146,108 @@ pub trait Interaction: Debug {
 
   /// If this interaction is pending (V4 only)
   fn pending(&self) -> bool { false }
+
+  /// Returns the normalised form of this interaction, which can be used to compare interactions
+  /// by their content. See [`NormalisedInteraction`] for the fields that are compared.
+  fn normalised(&self) -> Option<NormalisedInteraction> {
+    if let Some(interaction) = self.as_v4_http() {
+      Some(NormalisedInteraction::Http(SynchronousHttp { id: None, key: None, .. interaction }))
+    } else if let Some(interaction) = self.as_v4_async_message() {
+      Some(NormalisedInteraction::AsynchronousMessage(AsynchronousMessage { id: None, key: None, .. interaction }))
+    } else if let Some(interaction) = self.as_v4_sync_message() {
+      Some(NormalisedInteraction::SynchronousMessage(SynchronousMessage { id: None, key: None, .. interaction }))
+    } else {
+      None
+    }
+  }
+
+  /// If this interaction has the same content as the other interaction, ignoring volatile fields
+  /// like the interaction ID and key. V3 interactions are compared in their V4 form, so a V3
+  /// interaction will be equal to the V4 interaction it converts to.
+  fn content_eq(&self, other: &dyn Interaction) -> bool {
+    match (self.normalised(), other.normalised()) {
+      (Some(a), Some(b)) => a == b,
+      _ => false
+    }
+  }
+
+  /// Returns a hash of the content of this interaction. Interactions where `content_eq` returns
+  /// true will have the same hash. Note that the hash is not guaranteed to be stable between
+  /// releases, so should not be persisted.
+  fn content_hash(&self) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    self.normalised().hash(&mut hasher);
+    hasher.finish()
+  }
+
+  /// Returns a fingerprint of the content of this interaction as a hex string. Unlike
+  /// `content_hash` and the V4 interaction key, the fingerprint is calculated from the canonical
+  /// JSON form of the interaction (see [`NormalisedInteraction::canonical_json`]) and is
+  /// guaranteed to be stable across releases, so it can be persisted and used for de-duplication
+  /// and caching. Interactions where `content_eq` returns true will have the same fingerprint.
+  fn fingerprint(&self) -> Option<String> {
+    self.normalised().map(|interaction| interaction.fingerprint())
+  }
+}
+
+/// Normalised form of an interaction, used to compare and de-duplicate interactions by their
+/// content. Interactions are converted to their V4 form with the ID and key cleared. The
+/// description, provider states, request/response or message contents, pending flag and the
+/// matching config (i.e. `contentMatcher`) are compared, while comments, interaction markup,
+/// plugin configuration and transport are ignored.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum NormalisedInteraction {
+  /// Synchronous HTTP request/response interaction
+  Http(SynchronousHttp),
+  /// Asynchronous message interaction
+  AsynchronousMessage(AsynchronousMessage),
+  /// Synchronous request/response message interaction
+  SynchronousMessage(SynchronousMessage)
+}
+
+impl Eq for NormalisedInteraction {}
+
+/// Attributes that are removed from the JSON form of an interaction when calculating the canonical
+/// form, as they do not form part of the content of the interaction
+const NON_CONTENT_ATTRIBUTES: [&str; 5] = [
+  "key", "comments", "pluginConfiguration", "interactionMarkup", "transport"
+];
+
+impl NormalisedInteraction {
+  /// Returns the canonical JSON form of the interaction. This is the V4 JSON form with the ID,
+  /// key, comments, interaction markup, plugin configuration and transport removed, and all
+  /// object keys sorted. The matching config is kept, as it changes how the interaction is
+  /// matched.
+  pub fn canonical_json(&self) -> Value {
+    let mut json = match self {
+      NormalisedInteraction::Http(interaction) => interaction.to_json(),
+      NormalisedInteraction::AsynchronousMessage(interaction) => interaction.to_json(),
+      NormalisedInteraction::SynchronousMessage(interaction) => interaction.to_json()
+    };
+    if let Some(map) = json.as_object_mut() {
+      for attribute in NON_CONTENT_ATTRIBUTES {
+        map.remove(attribute);
+      }
+    }
+    sort_json_keys(&json)
+  }
+
+  /// Returns the fingerprint of the interaction. This is the 64 bit FNV-1a hash of the canonical
+  /// JSON form of the interaction, as a hex string.
+  pub fn fingerprint(&self) -> String {
+    format!("{:016x}", hashers::fnv::fnv1a64(self.canonical_json().to_string().as_bytes()))
+  }
+}
+
+fn sort_json_keys(json: &Value) -> Value {
+  match json {
+    Value::Object(map) => Value::Object(map.iter()
+      .sorted_by(|(a, _), (b, _)| Ord::cmp(a, b))
+      .map(|(k, v)| (k.clone(), sort_json_keys(v)))
+      .collect()),
+    Value::Array(values) => Value::Array(values.iter().map(sort_json_keys).collect()),
+    _ => json.clone()
+  }
 }
 
 impl Display for dyn Interaction {
@@ -179,6 +286,34 @@ impl Clone for Box<dyn Interaction> {
   }
 }
 
+/// Returns the V4 type of the interaction. V3 request/response interactions are Synchronous/HTTP
+/// and V3 messages are Asynchronous/Messages.
+pub fn interaction_type(interaction: &dyn Interaction) -> V4InteractionType {
+  match interaction.as_v4() {
+    Some(v4) => v4.v4_type(),
+    None => if interaction.is_message() {
+      V4InteractionType::Asynchronous_Messages
+    } else {
+      V4InteractionType::Synchronous_HTTP
+    }
+  }
+}
+
+/// Interaction predicate that matches all interactions
+pub fn any_interaction(_interaction: &dyn Interaction) -> bool {
+  true
+}
+
+/// Returns an interaction predicate that matches interactions with a provider state with the given name
+pub fn with_provider_state(name: &str) -> impl Fn(&dyn Interaction) -> bool + '_ {
+  move |interaction| interaction.provider_states().iter().any(|state| state.name == name)
+}
+
+/// Returns an interaction predicate that matches interactions where the description matches the
+/// regular expression
+pub fn with_description_matching(regex: &Regex) -> impl Fn(&dyn Interaction) -> bool + '_ {
+  move |interaction| regex.is_match(interaction.description().as_str())
+}
 
 /// Converts the JSON struct into an HTTP Interaction
 pub fn http_interaction_from_json(source: &str, json: &Value, spec: &PactSpecification) -> anyhow::Result<Box<dyn Interaction + Send + Sync + RefUnwindSafe>> {
@@ -107,7 +107,16 @@ pub fn get_field_as_string(field: &str, map: &Map<String, Value>) -> Option<Stri
 
 /// Returns the headers from a JSON struct as Map String -> Vec<String>
 pub fn headers_from_json(request: &Value) -> Option<HashMap<String, Vec<String>>> {
-  match request.get("headers") {
+  header_values_from_json(request.get("headers"))
+}
+
+/// Extracts the trailers from a JSON struct (only applies to V4 HTTP responses)
+pub fn trailers_from_json(response: &Value) -> Option<HashMap<String, Vec<String>>> {
+  header_values_from_json(response.get("trailers"))
+}
+
+fn header_values_from_json(headers: Option<&Value>) -> Option<HashMap<String, Vec<String>>> {
+  match headers {
     Some(Value::Object(m)) => {
       Some(m.iter().map(|(key, val)| {
         match val {
@@ -2,15 +2,21 @@
 //!
 //! ## Crate features
 //!
-//! All features are enabled by default
+//! All features except `yaml` and `testing` are enabled by default
 //!
 //! * `datetime`: Enables support of date and time expressions and generators. This will add the
 //! `chronos` crate as a dependency.
 //! * `xml`: Enables support for parsing XML documents. This feature will add the `sxd-document`
 //! crate as a dependency.
+//! * `yaml`: Enables reading and writing pact files in YAML format. This feature will add the
+//! `serde_yaml` crate as a dependency.
+//! * `testing`: Provides implementations of the `arbitrary::Arbitrary` trait for the core models
+//! (see the `testing` module), for use with fuzzers and property based tests. This will add the
+//! `arbitrary` crate as a dependency.
 
 use std::fmt::{Display, Formatter};
 use std::fmt;
+use std::str::FromStr;
 
 use anyhow::anyhow;
 use itertools::Itertools;
@@ -27,6 +33,7 @@ pub mod verify_json;
 pub mod json_utils;
 pub mod expression_parser;
 #[cfg(feature = "datetime")] pub mod time_utils;
+#[cfg(feature = "datetime")] pub mod datetime_format;
 #[cfg(feature = "datetime")] mod timezone_db;
 #[cfg(not(target_family = "wasm"))] pub mod file_utils;
 #[cfg(feature = "xml")] pub mod xml_utils;
@@ -45,8 +52,12 @@ pub mod message;
 pub mod pact;
 pub mod sync_pact;
 pub mod message_pact;
+pub mod convert;
+pub mod validation;
 mod iterator_utils;
 pub mod plugins;
+pub mod pact_size;
+#[cfg(feature = "testing")] pub mod testing;
 
 /// A "prelude" or a default list of import types to include.
 pub mod prelude {
@@ -358,23 +369,16 @@ pub enum HttpStatus {
   /// Non-error response(< 400)
   NonError,
   /// Any error response (>= 400)
-  Error
+  Error,
+  /// Range of status codes (inclusive)
+  Range(u16, u16)
 }
 
 impl HttpStatus {
   /// Parse a JSON structure into a HttpStatus
   pub fn from_json(value: &Value) -> anyhow::Result<Self> {
     match value {
-      Value::String(s) => match s.as_str() {
-        "info" => Ok(HttpStatus::Information),
-        "success" => Ok(HttpStatus::Success),
-        "redirect" => Ok(HttpStatus::Redirect),
-        "clientError" => Ok(HttpStatus::ClientError),
-        "serverError" => Ok(HttpStatus::ServerError),
-        "nonError" => Ok(HttpStatus::NonError),
-        "error" => Ok(HttpStatus::Error),
-        _ => Err(anyhow!("'{}' is not a valid value for an HTTP Status", s))
-      },
+      Value::String(s) => HttpStatus::from_str(s),
       Value::Array(a) => {
         let status_codes = a.iter().map(|status| match status {
           Value::Number(n) => if n.is_u64() {
@@ -407,7 +411,67 @@ impl HttpStatus {
       HttpStatus::ClientError => json!("clientError"),
       HttpStatus::ServerError => json!("serverError"),
       HttpStatus::NonError => json!("nonError"),
-      HttpStatus::Error => json!("error")
+      HttpStatus::Error => json!("error"),
+      HttpStatus::Range(min, max) => json!(format!("{}-{}", min, max))
+    }
+  }
+
+  /// If the status code is one of the statuses
+  pub fn contains(&self, status_code: u16) -> bool {
+    match self {
+      HttpStatus::Information => (100..=199).contains(&status_code),
+      HttpStatus::Success => (200..=299).contains(&status_code),
+      HttpStatus::Redirect => (300..=399).contains(&status_code),
+      HttpStatus::ClientError => (400..=499).contains(&status_code),
+      HttpStatus::ServerError => (500..=599).contains(&status_code),
+      HttpStatus::StatusCodes(status_codes) => status_codes.contains(&status_code),
+      HttpStatus::NonError => status_code < 400,
+      HttpStatus::Error => status_code >= 400,
+      HttpStatus::Range(min, max) => (*min..=*max).contains(&status_code)
+    }
+  }
+
+  /// Returns an example status code for the statuses (the first one in the class or range)
+  pub fn example(&self) -> u16 {
+    match self {
+      HttpStatus::Information => 100,
+      HttpStatus::Success | HttpStatus::NonError => 200,
+      HttpStatus::Redirect => 300,
+      HttpStatus::ClientError => 400,
+      HttpStatus::ServerError | HttpStatus::Error => 500,
+      HttpStatus::StatusCodes(status_codes) => status_codes.first().cloned().unwrap_or(200),
+      HttpStatus::Range(min, _) => *min
+    }
+  }
+}
+
+impl FromStr for HttpStatus {
+  type Err = anyhow::Error;
+
+  /// Parses a HTTP status. This can be the name of a class of statuses (i.e. `clientError`), a
+  /// class in the form `4xx`, a range (i.e. `200-204`) or a comma separated list of status codes.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let value = s.trim();
+    match value {
+      "info" | "1xx" | "1XX" => Ok(HttpStatus::Information),
+      "success" | "2xx" | "2XX" => Ok(HttpStatus::Success),
+      "redirect" | "3xx" | "3XX" => Ok(HttpStatus::Redirect),
+      "clientError" | "4xx" | "4XX" => Ok(HttpStatus::ClientError),
+      "serverError" | "5xx" | "5XX" => Ok(HttpStatus::ServerError),
+      "nonError" => Ok(HttpStatus::NonError),
+      "error" => Ok(HttpStatus::Error),
+      _ => if let Some((min, max)) = value.split_once('-') {
+        match (min.trim().parse::<u16>(), max.trim().parse::<u16>()) {
+          (Ok(min), Ok(max)) if min <= max => Ok(HttpStatus::Range(min, max)),
+          _ => Err(anyhow!("'{}' is not a valid range for an HTTP Status", s))
+        }
+      } else {
+        value.split(',')
+          .map(|code| code.trim().parse::<u16>())
+          .collect::<Result<Vec<_>, _>>()
+          .map(HttpStatus::StatusCodes)
+          .map_err(|_| anyhow!("'{}' is not a valid value for an HTTP Status", s))
+      }
     }
   }
 }
@@ -423,7 +487,8 @@ impl Display for HttpStatus {
       HttpStatus::StatusCodes(status) =>
         write!(f, "{}", status.iter().map(|s| s.to_string()).join(", ")),
       HttpStatus::NonError => write!(f, "Non-error response (< 400)"),
-      HttpStatus::Error => write!(f, "Error response (>= 400)")
+      HttpStatus::Error => write!(f, "Error response (>= 400)"),
+      HttpStatus::Range(min, max) => write!(f, "Response status in the range {}–{}", min, max)
     }
   }
 }
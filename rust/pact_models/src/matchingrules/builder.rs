@@ -0,0 +1,198 @@
+//! # Matching rules builder
+//!
+//! Chainable builder for constructing matching rules at runtime, without having to work with the
+//! maps in `MatchingRules` directly. The category and path of each rule is checked when it is
+//! added, and any error is returned when the rules are built.
+//!
+//! ```
+//! use pact_models::matchingrules::{MatchingRule, MatchingRules};
+//!
+//! let rules = MatchingRules::builder()
+//!   .rule("body", "$.id", MatchingRule::Integer)
+//!   .rule("header", "Content-Type", MatchingRule::Regex("^application/json".to_string()))
+//!   .rule("status", "", MatchingRule::Type)
+//!   .build()
+//!   .unwrap();
+//! assert!(rules.rules_for_category("body").is_some());
+//! ```
+
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use crate::matchingrules::{Category, MatchingRule, MatchingRules, parameter_path, RuleLogic};
+use crate::path_exp::{DocPath, PathToken};
+
+/// Builder for `MatchingRules`. Use `MatchingRules::builder()` to create one.
+#[derive(Debug, Default)]
+pub struct MatchingRulesBuilder {
+  rules: MatchingRules,
+  error: Option<anyhow::Error>
+}
+
+impl MatchingRulesBuilder {
+  /// Creates a new empty builder
+  pub fn new() -> Self {
+    MatchingRulesBuilder::default()
+  }
+
+  /// Adds a matching rule to the category for the path. Rules for the same path are combined
+  /// with AND logic.
+  pub fn rule(self, category: impl ToString, path: &str, rule: MatchingRule) -> Self {
+    self.rules_with_logic(category, path, RuleLogic::And, vec![rule])
+  }
+
+  /// Adds a number of matching rules to the category for the path, combined with AND logic.
+  pub fn rules<R>(self, category: impl ToString, path: &str, rules: R) -> Self
+    where R: IntoIterator<Item = MatchingRule> {
+    self.rules_with_logic(category, path, RuleLogic::And, rules)
+  }
+
+  /// Adds a number of matching rules to the category for the path, combined with the given logic.
+  /// If there are already rules for the path, the logic of the existing rules is kept.
+  pub fn rules_with_logic<R>(
+    mut self,
+    category: impl ToString,
+    path: &str,
+    rule_logic: RuleLogic,
+    rules: R
+  ) -> Self where R: IntoIterator<Item = MatchingRule> {
+    if self.error.is_none() {
+      match parse_category(&category.to_string())
+        .and_then(|category| path_for_category(&category, path).map(|path| (category, path))) {
+        Ok((category, path)) => {
+          let category = self.rules.add_category(category);
+          for rule in rules {
+            category.add_rule(path.clone(), rule, rule_logic);
+          }
+        }
+        Err(err) => self.error = Some(err)
+      }
+    }
+    self
+  }
+
+  /// Returns the matching rules, or the first error from adding a rule
+  pub fn build(self) -> anyhow::Result<MatchingRules> {
+    match self.error {
+      Some(err) => Err(err),
+      None => Ok(self.rules)
+    }
+  }
+}
+
+fn parse_category(category: &str) -> anyhow::Result<Category> {
+  Category::from_str(category).map_err(|err| anyhow!(err))
+}
+
+/// Returns the path to store a rule or generator under for the category. Method, path and status
+/// categories do not have sub-paths, the header, query, trailer and metadata categories are keyed
+/// by name (with an optional index or `[*]` suffix to select the individual values), and the body and message contents categories require a valid path expression
+/// starting with the root marker.
+pub(crate) fn path_for_category(category: &Category, path: &str) -> anyhow::Result<DocPath> {
+  match category {
+    Category::METHOD | Category::PATH | Category::STATUS => if path.is_empty() || path == "$" {
+      Ok(DocPath::empty())
+    } else {
+      Err(anyhow!("The {} category does not have sub-paths, but the path '{}' was given", category, path))
+    },
+    Category::HEADER | Category::QUERY | Category::TRAILER | Category::METADATA => if path.is_empty() {
+      Err(anyhow!("A name is required for the {} category", category))
+    } else if path.starts_with('$') {
+      let doc_path = DocPath::new(path)
+        .map_err(|err| anyhow!("'{}' is not a valid path for the {} category - {}", path, category, err))?;
+      match doc_path.tokens().as_slice() {
+        [PathToken::Root, PathToken::Field(_)] |
+        [PathToken::Root, PathToken::Field(_), PathToken::Index(_) | PathToken::StarIndex] => Ok(doc_path),
+        _ => Err(anyhow!("'{}' is not a valid path for the {} category, it must refer to a single name", path, category))
+      }
+    } else {
+      Ok(parameter_path(path))
+    },
+    Category::BODY | Category::CONTENTS => if !path.starts_with('$') {
+      Err(anyhow!("A path expression starting with '$' is required for the {} category, but the path '{}' was given", category, path))
+    } else {
+      DocPath::new(path)
+        .map_err(|err| anyhow!("'{}' is not a valid path for the {} category - {}", path, category, err))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+
+  use crate::matchingrules::{MatchingRule, MatchingRules, RuleLogic};
+  use crate::path_exp::DocPath;
+
+  #[test]
+  fn builds_the_rules_for_each_category() {
+    let rules = MatchingRules::builder()
+      .rule("body", "$.id", MatchingRule::Integer)
+      .rule("query", "user_id", MatchingRule::Regex("^[0-9]+$".to_string()))
+      .rule("header", "$['Content-Type']", MatchingRule::Type)
+      .rule("header", "Content-Type", MatchingRule::Include("json".to_string()))
+      .rule("status", "", MatchingRule::Type)
+      .build();
+
+    let mut expected = MatchingRules::default();
+    expected.add_category("body")
+      .add_rule(DocPath::new_unwrap("$.id"), MatchingRule::Integer, RuleLogic::And);
+    expected.add_category("query")
+      .add_rule(DocPath::root().join("user_id"), MatchingRule::Regex("^[0-9]+$".to_string()), RuleLogic::And);
+    let header = expected.add_category("header");
+    header.add_rule(DocPath::root().join("Content-Type"), MatchingRule::Type, RuleLogic::And);
+    header.add_rule(DocPath::root().join("Content-Type"), MatchingRule::Include("json".to_string()), RuleLogic::And);
+    expected.add_category("status")
+      .add_rule(DocPath::empty(), MatchingRule::Type, RuleLogic::And);
+    expect!(rules).to(be_ok().value(expected));
+  }
+
+  #[test]
+  fn supports_index_wildcards_for_query_parameters_and_headers() {
+    let rules = MatchingRules::builder()
+      .rule("query", "ids", MatchingRule::MinType(1))
+      .rule("query", "ids[*]", MatchingRule::Regex("^[0-9]+$".to_string()))
+      .rule("header", "$['X-Id'][0]", MatchingRule::Integer)
+      .build()
+      .unwrap();
+
+    let mut wildcard_path = DocPath::root().join("ids");
+    wildcard_path.push_star_index();
+    let query = rules.rules_for_category("query").unwrap();
+    expect!(query.rules.contains_key(&DocPath::root().join("ids"))).to(be_true());
+    expect!(query.rules.contains_key(&wildcard_path)).to(be_true());
+    let header = rules.rules_for_category("header").unwrap();
+    expect!(header.rules.contains_key(&DocPath::root().join("X-Id").join("0"))).to(be_true());
+  }
+
+  #[test]
+  fn keeps_the_rule_logic() {
+    let rules = MatchingRules::builder()
+      .rules_with_logic("body", "$.id", RuleLogic::Or, vec![MatchingRule::Integer, MatchingRule::Null])
+      .build()
+      .unwrap();
+    let category = rules.rules_for_category("body").unwrap();
+    let rule_list = category.rules.values().next().unwrap();
+    expect!(rule_list.rule_logic).to(be_equal_to(RuleLogic::Or));
+    expect!(rule_list.rules.clone()).to(be_equal_to(vec![MatchingRule::Integer, MatchingRule::Null]));
+  }
+
+  #[test]
+  fn returns_the_first_error() {
+    expect!(MatchingRules::builder().rule("bodies", "$.id", MatchingRule::Type).build()).to(be_err());
+    expect!(MatchingRules::builder().rule("body", "$.id[", MatchingRule::Type).build()).to(be_err());
+    expect!(MatchingRules::builder().rule("body", "", MatchingRule::Type).build()).to(be_err());
+    expect!(MatchingRules::builder().rule("body", "id", MatchingRule::Type).build()).to(be_err());
+    expect!(MatchingRules::builder().rule("status", "$.status", MatchingRule::Type).build()).to(be_err());
+    expect!(MatchingRules::builder().rule("header", "", MatchingRule::Type).build()).to(be_err());
+    expect!(MatchingRules::builder().rule("query", "$.a.b", MatchingRule::Type).build()).to(be_err());
+
+    let result = MatchingRules::builder()
+      .rule("path", "/a", MatchingRule::Type)
+      .rule("body", "$.id", MatchingRule::Type)
+      .build();
+    expect!(result.unwrap_err().to_string()).to(
+      be_equal_to("The path category does not have sub-paths, but the path '/a' was given"));
+  }
+}
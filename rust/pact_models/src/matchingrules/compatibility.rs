@@ -0,0 +1,385 @@
+//! Compatibility layer for matching rule JSON written by other Pact implementations.
+//!
+//! Over time, the different language DSLs (Pact-JVM, Pact-JS, etc.) have written slightly different
+//! JSON for the same matching rule. For instance, a type matcher with a minimum length can be
+//! written as `{ "match": "type", "min": 1 }`, `{ "min": 1 }` or `{ "match": "min", "min": "1" }`.
+//! The functions in this module canonicalise the known historical variants into the form written
+//! by this library, so they are all loaded into the same internal model.
+
+use std::fmt::{Display, Formatter};
+
+use serde_json::{Map, Value};
+
+use crate::json_utils::json_to_num;
+
+/// Legacy (or alternative) matcher types, along with the canonical type they map to
+const MATCHER_TYPE_ALIASES: [(&str, &str); 16] = [
+  ("real", "decimal"),
+  ("timestamp", "datetime"),
+  ("min", "type"),
+  ("max", "type"),
+  ("min-type", "type"),
+  ("max-type", "type"),
+  ("content-type", "contentType"),
+  ("array-contains", "arrayContains"),
+  ("status-code", "statusCode"),
+  ("not-empty", "notEmpty"),
+  ("each-key", "eachKey"),
+  ("each-value", "eachValue"),
+  ("min-size", "minSize"),
+  ("max-size", "maxSize"),
+  ("bytes-equal", "bytesEqual"),
+  ("epoch-timestamp", "epoch")
+];
+
+/// Matching rule variant that was found in Pact JSON, along with where it was found
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompatibilityWarning {
+  /// Path to the matching rule in the Pact JSON
+  pub path: String,
+  /// Description of the variant that was found
+  pub description: String
+}
+
+impl Display for CompatibilityWarning {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}: {}", self.path, self.description)
+  }
+}
+
+/// Canonicalises the known historical variants of the JSON for a single matching rule. Returns
+/// the canonical JSON along with a description of each variant that was found. JSON that is
+/// already in the canonical form is returned unchanged with no warnings.
+pub fn normalise_matcher_json(json: &Value) -> (Value, Vec<String>) {
+  match json {
+    Value::Object(attributes) => {
+      let mut warnings = vec![];
+      let mut attributes = attributes.clone();
+      normalise_matcher_type(&mut attributes, &mut warnings);
+      normalise_format(&mut attributes, &mut warnings);
+      normalise_lengths(&mut attributes, &mut warnings);
+      normalise_nested_rules(&mut attributes, &mut warnings);
+      (Value::Object(attributes), warnings)
+    }
+    _ => (json.clone(), vec![])
+  }
+}
+
+fn normalise_matcher_type(attributes: &mut Map<String, Value>, warnings: &mut Vec<String>) {
+  match attributes.get("match").cloned() {
+    Some(Value::String(matcher_type)) => {
+      let alias = MATCHER_TYPE_ALIASES.iter()
+        .find(|(alias, _)| *alias == matcher_type)
+        // The min and max matchers are only a type matcher if they have the associated attribute
+        .filter(|(alias, _)| match *alias {
+          "min" | "min-type" => attributes.contains_key("min"),
+          "max" | "max-type" => attributes.contains_key("max"),
+          _ => true
+        });
+      if let Some((_, canonical)) = alias {
+        warnings.push(format!("Matcher type '{}' is a legacy alias for '{}'", matcher_type, canonical));
+        attributes.insert("match".to_string(), Value::String(canonical.to_string()));
+      }
+    }
+    Some(_) => {}
+    None => if !attributes.contains_key("pact:matcher:type") {
+      let guessed = if attributes.contains_key("regex") {
+        Some("regex")
+      } else if attributes.contains_key("min") || attributes.contains_key("max") {
+        Some("type")
+      } else if attributes.contains_key("timestamp") {
+        Some("datetime")
+      } else if attributes.contains_key("time") {
+        Some("time")
+      } else if attributes.contains_key("date") {
+        Some("date")
+      } else {
+        None
+      };
+      if let Some(matcher_type) = guessed {
+        warnings.push(format!("Matcher has no 'match' attribute, assuming a '{}' matcher", matcher_type));
+        attributes.insert("match".to_string(), Value::String(matcher_type.to_string()));
+      }
+    }
+  }
+}
+
+fn normalise_format(attributes: &mut Map<String, Value>, warnings: &mut Vec<String>) {
+  let legacy_attribute = match attributes.get("match").and_then(|m| m.as_str()) {
+    Some("datetime") => "timestamp",
+    Some("date") => "date",
+    Some("time") => "time",
+    _ => return
+  };
+  if !attributes.contains_key("format") {
+    if let Some(format) = attributes.remove(legacy_attribute) {
+      warnings.push(format!("Format is set with the legacy '{}' attribute instead of 'format'", legacy_attribute));
+      attributes.insert("format".to_string(), format);
+    }
+  }
+}
+
+fn normalise_lengths(attributes: &mut Map<String, Value>, warnings: &mut Vec<String>) {
+  for attribute in ["min", "max"] {
+    if let Some(Value::String(value)) = attributes.get(attribute).cloned() {
+      if let Some(num) = json_to_num(Some(Value::String(value.clone()))) {
+        warnings.push(format!("'{}' attribute is the string '{}' instead of a number", attribute, value));
+        attributes.insert(attribute.to_string(), Value::from(num as u64));
+      }
+    }
+  }
+}
+
+fn normalise_nested_rules(attributes: &mut Map<String, Value>, warnings: &mut Vec<String>) {
+  if let Some(Value::Array(rules)) = attributes.get_mut("rules") {
+    for rule in rules.iter_mut() {
+      let (normalised, rule_warnings) = normalise_matcher_json(rule);
+      *rule = normalised;
+      warnings.extend(rule_warnings);
+    }
+  }
+
+  if let Some(Value::Array(variants)) = attributes.get_mut("variants") {
+    for variant in variants.iter_mut() {
+      if let Some(Value::Object(rules)) = variant.get_mut("rules") {
+        for rule_list in rules.values_mut() {
+          warnings.extend(normalise_rule_list(rule_list));
+        }
+      }
+    }
+  }
+}
+
+fn normalise_rule_list(rule_list: &mut Value) -> Vec<String> {
+  let mut warnings = vec![];
+  if let Some(Value::Array(matchers)) = rule_list.get_mut("matchers") {
+    for matcher in matchers.iter_mut() {
+      let (normalised, matcher_warnings) = normalise_matcher_json(matcher);
+      *matcher = normalised;
+      warnings.extend(matcher_warnings);
+    }
+  }
+  warnings
+}
+
+/// Audits all the matching rules in the given Pact JSON, and returns any historical variants
+/// that would be canonicalised when the Pact is loaded. Supports the V2 (flat map of paths) and
+/// V3/V4 (grouped by category) formats.
+pub fn audit_pact_matching_rules(pact_json: &Value) -> Vec<CompatibilityWarning> {
+  let mut warnings = vec![];
+
+  for key in ["interactions", "messages"] {
+    if let Some(Value::Array(interactions)) = pact_json.get(key) {
+      for (index, interaction) in interactions.iter().enumerate() {
+        let base_path = format!("$.{}[{}]", key, index);
+        audit_rules_container(interaction, base_path.as_str(), &mut warnings);
+        for part in ["request", "response", "contents"] {
+          if let Some(part_json) = interaction.get(part) {
+            audit_rules_container(part_json, format!("{}.{}", base_path, part).as_str(), &mut warnings);
+          }
+        }
+        if let Some(Value::Array(responses)) = interaction.get("response") {
+          for (response_index, response) in responses.iter().enumerate() {
+            audit_rules_container(response, format!("{}.response[{}]", base_path, response_index).as_str(),
+              &mut warnings);
+          }
+        }
+      }
+    }
+  }
+
+  warnings
+}
+
+fn audit_rules_container(json: &Value, path: &str, warnings: &mut Vec<CompatibilityWarning>) {
+  if let Some(Value::Object(rules)) = json.get("matchingRules") {
+    let path = format!("{}.matchingRules", path);
+    for (key, value) in rules {
+      if key.starts_with('$') {
+        // V2 format, each key is a path to a single matcher
+        audit_matcher(value, format!("{}['{}']", path, key).as_str(), warnings);
+      } else if let Some(Value::Array(_)) = value.get("matchers") {
+        // V3 path category, which does not have sub-categories
+        audit_rule_list(value, format!("{}.{}", path, key).as_str(), warnings);
+      } else if let Value::Object(sub_categories) = value {
+        for (sub_category, rule_list) in sub_categories {
+          audit_rule_list(rule_list, format!("{}.{}['{}']", path, key, sub_category).as_str(), warnings);
+        }
+      }
+    }
+  }
+}
+
+fn audit_rule_list(rule_list: &Value, path: &str, warnings: &mut Vec<CompatibilityWarning>) {
+  if let Some(Value::Array(matchers)) = rule_list.get("matchers") {
+    for (index, matcher) in matchers.iter().enumerate() {
+      audit_matcher(matcher, format!("{}.matchers[{}]", path, index).as_str(), warnings);
+    }
+  }
+}
+
+fn audit_matcher(matcher: &Value, path: &str, warnings: &mut Vec<CompatibilityWarning>) {
+  let (_, matcher_warnings) = normalise_matcher_json(matcher);
+  warnings.extend(matcher_warnings.iter().map(|description| CompatibilityWarning {
+    path: path.to_string(),
+    description: description.clone()
+  }));
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+  use std::path::Path;
+
+  use expectest::prelude::*;
+  use serde_json::json;
+
+  use crate::matchingrules::{matchers_from_json, MatchingRule};
+  use crate::path_exp::DocPath;
+
+  use super::*;
+
+  fn load_corpus_file(name: &str) -> Value {
+    let file = Path::new(env!("CARGO_MANIFEST_DIR"))
+      .join("tests/data/matching-rule-corpus")
+      .join(name);
+    serde_json::from_str(fs::read_to_string(file).unwrap().as_str()).unwrap()
+  }
+
+  #[test]
+  fn canonical_json_is_not_changed() {
+    for rule in [
+      MatchingRule::MinMaxType(1, 2),
+      MatchingRule::Timestamp("yyyy-MM-dd".to_string()),
+      MatchingRule::Regex("\\d+".to_string()),
+      MatchingRule::Decimal,
+      MatchingRule::NotEmpty
+    ] {
+      let json = rule.to_json();
+      let (normalised, warnings) = normalise_matcher_json(&json);
+      expect!(normalised).to(be_equal_to(json));
+      expect!(warnings.is_empty()).to(be_true());
+    }
+  }
+
+  #[test]
+  fn guesses_the_matcher_type_when_the_match_attribute_is_missing() {
+    let (json, warnings) = normalise_matcher_json(&json!({ "min": 1, "max": 5 }));
+    expect!(json).to(be_equal_to(json!({ "match": "type", "min": 1, "max": 5 })));
+    expect!(warnings).to(be_equal_to(vec!["Matcher has no 'match' attribute, assuming a 'type' matcher".to_string()]));
+
+    let (json, _) = normalise_matcher_json(&json!({ "timestamp": "yyyy-MM-dd" }));
+    expect!(json).to(be_equal_to(json!({ "match": "datetime", "format": "yyyy-MM-dd" })));
+
+    let (json, warnings) = normalise_matcher_json(&json!({ "other": 1 }));
+    expect!(json).to(be_equal_to(json!({ "other": 1 })));
+    expect!(warnings.is_empty()).to(be_true());
+  }
+
+  #[test]
+  fn canonicalises_legacy_matcher_types_and_attributes() {
+    let (json, warnings) = normalise_matcher_json(&json!({ "match": "min", "min": "2" }));
+    expect!(json).to(be_equal_to(json!({ "match": "type", "min": 2 })));
+    expect!(warnings.len()).to(be_equal_to(2));
+
+    let (json, _) = normalise_matcher_json(&json!({ "match": "timestamp", "timestamp": "HH:mm" }));
+    expect!(json).to(be_equal_to(json!({ "match": "datetime", "format": "HH:mm" })));
+
+    let (json, _) = normalise_matcher_json(&json!({ "match": "real" }));
+    expect!(json).to(be_equal_to(json!({ "match": "decimal" })));
+
+    let (json, warnings) = normalise_matcher_json(&json!({
+      "match": "eachValue",
+      "rules": [ { "match": "min-type", "min": 1 } ]
+    }));
+    expect!(json).to(be_equal_to(json!({
+      "match": "eachValue",
+      "rules": [ { "match": "type", "min": 1 } ]
+    })));
+    expect!(warnings.len()).to(be_equal_to(1));
+  }
+
+  #[test]
+  fn loads_the_min_and_max_without_a_match_attribute_as_a_min_max_matcher() {
+    expect!(MatchingRule::from_json(&json!({ "min": 1, "max": 3 })))
+      .to(be_ok().value(MatchingRule::MinMaxType(1, 3)));
+    expect!(MatchingRule::from_json(&json!({ "match": "min", "min": 1, "max": 3 })))
+      .to(be_ok().value(MatchingRule::MinMaxType(1, 3)));
+  }
+
+  #[test]
+  fn pact_jvm_v2_corpus() {
+    let pact = load_corpus_file("pact-jvm-v2.json");
+    let response = &pact["interactions"][0]["response"];
+    let rules = matchers_from_json(response, &None).unwrap();
+    let body = rules.rules_for_category("body").unwrap();
+    expect!(body.rules.get(&DocPath::new_unwrap("$.items")).unwrap().rules.clone())
+      .to(be_equal_to(vec![MatchingRule::MinType(1)]));
+    expect!(body.rules.get(&DocPath::new_unwrap("$.items[*].created")).unwrap().rules.clone())
+      .to(be_equal_to(vec![MatchingRule::Timestamp("yyyy-MM-dd'T'HH:mm:ss".to_string())]));
+    expect!(body.rules.get(&DocPath::new_unwrap("$.items[*].price")).unwrap().rules.clone())
+      .to(be_equal_to(vec![MatchingRule::Decimal]));
+
+    let warnings = audit_pact_matching_rules(&pact);
+    expect!(warnings.iter().map(|w| w.to_string()).collect::<Vec<_>>()).to(be_equal_to(vec![
+      "$.interactions[0].response.matchingRules['$.body.items[*].created']: Matcher type 'timestamp' is a legacy alias for 'datetime'".to_string(),
+      "$.interactions[0].response.matchingRules['$.body.items[*].created']: Format is set with the legacy 'timestamp' attribute instead of 'format'".to_string(),
+      "$.interactions[0].response.matchingRules['$.body.items[*].price']: Matcher type 'real' is a legacy alias for 'decimal'".to_string()
+    ]));
+  }
+
+  #[test]
+  fn pact_js_v2_corpus() {
+    let pact = load_corpus_file("pact-js-v2.json");
+    let response = &pact["interactions"][0]["response"];
+    let rules = matchers_from_json(response, &None).unwrap();
+    let body = rules.rules_for_category("body").unwrap();
+    expect!(body.rules.get(&DocPath::new_unwrap("$.animals")).unwrap().rules.clone())
+      .to(be_equal_to(vec![MatchingRule::MinMaxType(1, 10)]));
+    expect!(body.rules.get(&DocPath::new_unwrap("$.animals[*].id")).unwrap().rules.clone())
+      .to(be_equal_to(vec![MatchingRule::Type]));
+    expect!(body.rules.get(&DocPath::new_unwrap("$.animals[*].gender")).unwrap().rules.clone())
+      .to(be_equal_to(vec![MatchingRule::Regex("^(M|F)$".to_string())]));
+
+    let warnings = audit_pact_matching_rules(&pact);
+    expect!(warnings.iter().map(|w| w.to_string()).collect::<Vec<_>>()).to(be_equal_to(vec![
+      "$.interactions[0].response.matchingRules['$.body.animals']: Matcher has no 'match' attribute, assuming a 'type' matcher".to_string(),
+      "$.interactions[0].response.matchingRules['$.body.animals[*].gender']: Matcher has no 'match' attribute, assuming a 'regex' matcher".to_string()
+    ]));
+  }
+
+  #[test]
+  fn pact_jvm_v3_corpus() {
+    let pact = load_corpus_file("pact-jvm-v3.json");
+    let response = &pact["interactions"][0]["response"];
+    let rules = matchers_from_json(response, &None).unwrap();
+    let body = rules.rules_for_category("body").unwrap();
+    expect!(body.rules.get(&DocPath::new_unwrap("$.orders")).unwrap().rules.clone())
+      .to(be_equal_to(vec![MatchingRule::MinMaxType(1, 5)]));
+    expect!(body.rules.get(&DocPath::new_unwrap("$.orders[*].date")).unwrap().rules.clone())
+      .to(be_equal_to(vec![MatchingRule::Date("yyyy-MM-dd".to_string())]));
+
+    let warnings = audit_pact_matching_rules(&pact);
+    expect!(warnings.iter().map(|w| w.to_string()).collect::<Vec<_>>()).to(be_equal_to(vec![
+      "$.interactions[0].response.matchingRules.body['$.orders'].matchers[0]: 'min' attribute is the string '1' instead of a number".to_string(),
+      "$.interactions[0].response.matchingRules.body['$.orders'].matchers[0]: 'max' attribute is the string '5' instead of a number".to_string(),
+      "$.interactions[0].response.matchingRules.body['$.orders[*].date'].matchers[0]: Format is set with the legacy 'date' attribute instead of 'format'".to_string()
+    ]));
+  }
+
+  #[test]
+  fn pact_js_v3_corpus() {
+    let pact = load_corpus_file("pact-js-v3.json");
+    let warnings = audit_pact_matching_rules(&pact);
+    expect!(warnings.is_empty()).to(be_true());
+
+    let response = &pact["interactions"][0]["response"];
+    let rules = matchers_from_json(response, &None).unwrap();
+    let body = rules.rules_for_category("body").unwrap();
+    expect!(body.rules.get(&DocPath::new_unwrap("$.users")).unwrap().rules.clone())
+      .to(be_equal_to(vec![MatchingRule::MinType(2)]));
+    let header = rules.rules_for_category("header").unwrap();
+    expect!(header.rules.get(&DocPath::root().join("Content-Type")).unwrap().rules.clone())
+      .to(be_equal_to(vec![MatchingRule::Regex("application/json.*".to_string())]));
+  }
+}
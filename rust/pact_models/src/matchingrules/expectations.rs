@@ -0,0 +1,149 @@
+//! # Partial body expectations
+//!
+//! Parser for expectations that describe a JSON body by the values at a set of paths, instead of
+//! an example body. Each expectation is converted into the body matching rules for the path, so
+//! they can be stored in a Pact file as matching rules without an example value.
+//!
+//! The following are examples of expectations:
+//! * `$.data.id exists` - the value must be present
+//! * `$.data.error absent` - the value must not be present
+//! * `$.data.items length >= 1` - the array must have at least one item (`>`, `<=`, `<` and `==` are also supported)
+//! * `$.data.name matching(type, 'Fred')` - the value must match the matching rule definition
+
+use anyhow::anyhow;
+use itertools::Either;
+
+use crate::matchingrules::{MatchingRule, MatchingRuleCategory, RuleLogic};
+use crate::matchingrules::expressions::{is_matcher_def, parse_matcher_def};
+use crate::path_exp::DocPath;
+
+/// Parses an expectation (i.e. `$.data.id exists`) into the path and the matching rules for it
+pub fn parse_expectation(expectation: &str) -> anyhow::Result<(DocPath, Vec<MatchingRule>)> {
+  let expectation = expectation.trim();
+  let (path, rest) = split_path(expectation);
+  if path.is_empty() || rest.is_empty() {
+    return Err(anyhow!("'{}' is not a valid expectation, expected a path followed by the expectation for it", expectation));
+  }
+
+  let path = DocPath::new(path)
+    .map_err(|err| anyhow!("'{}' is not a valid expectation, the path is not valid - {}", expectation, err))?;
+  let rules = match rest {
+    "exists" => vec![ MatchingRule::Exists ],
+    "absent" => vec![ MatchingRule::Absent ],
+    _ => if let Some(length) = rest.strip_prefix("length") {
+      vec![ length_rule(length.trim()).map_err(|err| anyhow!("'{}' is not a valid expectation - {}", expectation, err))? ]
+    } else if is_matcher_def(rest) {
+      let definition = parse_matcher_def(rest)?;
+      definition.rules.iter()
+        .map(|rule| match rule {
+          Either::Left(rule) => Ok(rule.clone()),
+          Either::Right(reference) => Err(anyhow!("'{}' is not a valid expectation, references to other attributes ({}) are not supported",
+            expectation, reference.name))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?
+    } else {
+      return Err(anyhow!("'{}' is not a valid expectation, expected 'exists', 'absent', 'length' or a matching rule definition after the path", expectation));
+    }
+  };
+  Ok((path, rules))
+}
+
+/// Converts the expectations into body matching rules. The rules for an expectation are combined
+/// with any other rules for the same path.
+pub fn expectation_matching_rules<S: AsRef<str>>(expectations: &[S]) -> anyhow::Result<MatchingRuleCategory> {
+  let mut category = MatchingRuleCategory::empty("body");
+  for expectation in expectations {
+    let (path, rules) = parse_expectation(expectation.as_ref())?;
+    for rule in rules {
+      category.add_rule(path.clone(), rule, RuleLogic::And);
+    }
+  }
+  Ok(category)
+}
+
+/// Splits the expectation at the first whitespace that is not in a bracketed path segment
+fn split_path(expectation: &str) -> (&str, &str) {
+  let mut depth = 0;
+  let mut quoted = false;
+  for (index, ch) in expectation.char_indices() {
+    match ch {
+      '\'' if depth > 0 => quoted = !quoted,
+      '[' if !quoted => depth += 1,
+      ']' if !quoted && depth > 0 => depth -= 1,
+      _ if ch.is_whitespace() && depth == 0 => {
+        return (&expectation[..index], expectation[index..].trim());
+      }
+      _ => ()
+    }
+  }
+  (expectation, "")
+}
+
+fn length_rule(length: &str) -> anyhow::Result<MatchingRule> {
+  let (op, value) = [">=", "<=", "==", ">", "<", "="].iter()
+    .find_map(|op| length.strip_prefix(op).map(|value| (*op, value.trim())))
+    .ok_or_else(|| anyhow!("expected a comparison (>=, >, <=, <, ==) after 'length'"))?;
+  let value: usize = value.parse()
+    .map_err(|_| anyhow!("expected a number to compare the length to, got '{}'", value))?;
+  match op {
+    ">=" => Ok(MatchingRule::MinType(value)),
+    ">" => Ok(MatchingRule::MinType(value + 1)),
+    "<=" => Ok(MatchingRule::MaxType(value)),
+    "<" => if value > 0 {
+      Ok(MatchingRule::MaxType(value - 1))
+    } else {
+      Err(anyhow!("the length can not be less than zero"))
+    },
+    _ => Ok(MatchingRule::MinMaxType(value, value))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+
+  use crate::matchingrules::{MatchingRule, RuleList, RuleLogic};
+  use crate::path_exp::DocPath;
+
+  use super::*;
+
+  #[test]
+  fn parse_expectation_test() {
+    expect!(parse_expectation("$.data.id exists")).to(
+      be_ok().value((DocPath::new_unwrap("$.data.id"), vec![ MatchingRule::Exists ])));
+    expect!(parse_expectation("  $.data.error   absent ")).to(
+      be_ok().value((DocPath::new_unwrap("$.data.error"), vec![ MatchingRule::Absent ])));
+    expect!(parse_expectation("$['the data'].items length >= 1")).to(
+      be_ok().value((DocPath::new_unwrap("$['the data'].items"), vec![ MatchingRule::MinType(1) ])));
+    expect!(parse_expectation("$.data.items length>2")).to(
+      be_ok().value((DocPath::new_unwrap("$.data.items"), vec![ MatchingRule::MinType(3) ])));
+    expect!(parse_expectation("$.data.items length < 5")).to(
+      be_ok().value((DocPath::new_unwrap("$.data.items"), vec![ MatchingRule::MaxType(4) ])));
+    expect!(parse_expectation("$.data.items length == 2")).to(
+      be_ok().value((DocPath::new_unwrap("$.data.items"), vec![ MatchingRule::MinMaxType(2, 2) ])));
+    expect!(parse_expectation("$.data.name matching(type, 'Fred')")).to(
+      be_ok().value((DocPath::new_unwrap("$.data.name"), vec![ MatchingRule::Type ])));
+
+    expect!(parse_expectation("")).to(be_err());
+    expect!(parse_expectation("$.data.id")).to(be_err());
+    expect!(parse_expectation("$.data.id is here")).to(be_err());
+    expect!(parse_expectation("$.data.items length")).to(be_err());
+    expect!(parse_expectation("$.data.items length >= lots")).to(be_err());
+    expect!(parse_expectation("$.data.items length < 0")).to(be_err());
+    expect!(parse_expectation("$.data.name matching($'name')")).to(be_err());
+  }
+
+  #[test]
+  fn expectation_matching_rules_test() {
+    let rules = expectation_matching_rules(&[ "$.data.id exists", "$.data.items length >= 1",
+      "$.data.items matching(type, 1)" ]).unwrap();
+    expect!(rules.rules.get(&DocPath::new_unwrap("$.data.id")).cloned()).to(
+      be_some().value(RuleList::new(MatchingRule::Exists)));
+    expect!(rules.rules.get(&DocPath::new_unwrap("$.data.items")).cloned()).to(be_some().value(RuleList {
+      rules: vec![ MatchingRule::MinType(1), MatchingRule::Type ],
+      rule_logic: RuleLogic::And,
+      cascaded: false
+    }));
+    expect!(expectation_matching_rules(&[ "$.data.id exists", "nope" ])).to(be_err());
+  }
+}
@@ -21,6 +21,18 @@
 //! * `\uXXXX` unicode hex code (4 digits)
 //! * `\u{X...}` unicode hex code (can be more than 4 digits)
 //!
+//! ## Array and object values
+//!
+//! The example values for the `equalTo`, `type` and `equalsIgnoringOrder` matchers and the
+//! `notEmpty` expression can also be array or object literals, using JSON-like syntax with
+//! single quoted strings. The elements can be primitive values or other array or object literals.
+//! For example:
+//! * `matching(type, ['a', 'b'])`
+//! * `matching(type, {'id': 1, 'tags': ['a', 'b']})`
+//!
+//! The value of the parsed definition will be the JSON form of the literal (i.e. `["a","b"]`),
+//! and the value type will be `Array` or `Object`.
+//!
 //! ## Expressions
 //!
 //! The main types of expressions are one of the following:
@@ -47,6 +59,31 @@
 //! | boolean     | Value must be a boolean                                                                               |                    | `matching(boolean, true)`                                                     |
 //! | server      | Value must match the semver specification                                                             |                    | `matching(semver, '1.0.0')`                                                   |
 //! | contentType | Value must be of the provided content type. This will preform a magic test on the bytes of the value. | Content type       | `matching(contentType, 'application/xml', '<?xml?><test/>')`                  |
+//! | duration    | Value must be an ISO-8601 duration                                                                    |                    | `matching(duration, 'PT5M30S')`                                               |
+//! | epoch       | Value must be an integer timestamp since the Unix epoch, with an optional tolerance from now          | Unit               | `matching(epoch, 'millis', 1700000000000)`                                    |
+//! | equalsIgnoringOrder | Array must contain the same elements as the expected array, but in any order                  |                    | `matching(equalsIgnoringOrder, 'item')`                                       |
+//! | url         | Value must be an absolute URL, with the components matched separately (see below)                     |                    | `matching(url, 'https://api.example.com/v1/users')`                           |
+//! | greaterThan | Value must be a number greater than the bound                                                         | Bound              | `matching(greaterThan, 0, 100)`                                               |
+//! | greaterThanOrEqual | Value must be a number greater than or equal to the bound                                      | Bound              | `matching(greaterThanOrEqual, 0, 100)`                                        |
+//! | lessThan    | Value must be a number less than the bound                                                            | Bound              | `matching(lessThan, 65536, 8080)`                                             |
+//! | lessThanOrEqual | Value must be a number less than or equal to the bound                                            | Bound              | `matching(lessThanOrEqual, 100, 99.5)`                                        |
+//! | range       | Value must be a number between the minimum and maximum (inclusive)                                    | Minimum, Maximum   | `matching(range, 1, 65535, 8080)`                                             |
+//! | statusCode  | HTTP status must be in the class (i.e. `clientError` or `4xx`), range (`200-204`) or list (`200,204`) | Statuses           | `matching(statusCode, 'success', 204)`                                        |
+//!
+//! The example status for the `statusCode` matcher is optional, and defaults to the first status
+//! of the class, range or list (i.e. `matching(statusCode, 'clientError')` will use 400).
+//!
+//! The `url` matcher can be followed by pairs of URL component names and expressions to apply to
+//! that component. Components that do not have an expression must be equal to the example. The
+//! components are `scheme`, `host`, `port`, `path`, `query` (all query parameters), `query.NAME`
+//! (a single query parameter) and `fragment`. For example:
+//!
+//! `matching(url, 'https://api.example.com/v1/users?page=1', 'host', matching(regex, '.+\.example\.com', 'api.example.com'), 'query.page', matching(integer, 1))`
+//!
+//! The numeric range matchers (`greaterThan`, `greaterThanOrEqual`, `lessThan`, `lessThanOrEqual`
+//! and `range`) require the example to be within the range, and will also generate a random
+//! number within the range (an integer if the example is an integer). A range with exclusive bounds
+//! can be defined by combining the matchers, i.e. `matching(greaterThan, 0, 5), matching(lessThan, 10, 5)`.
 //!
 //! The final form is a reference to another key. This is used to setup type matching using an example value, and is normally
 //! used for collections. The name of the key must be a string value in single quotes.
@@ -96,12 +133,48 @@
 //!
 //! For example: `atMost(2)`
 //!
+//! ### atLeastBytes(SIZE)
+//!
+//! Configures a rule for binary contents that asserts the contents is at least the given number
+//! of bytes.
+//!
+//! For example: `atLeastBytes(1024)`
+//!
+//! ### atMostBytes(SIZE)
+//!
+//! Configures a rule for binary contents that asserts the contents is at most the given number
+//! of bytes.
+//!
+//! For example: `atMostBytes(1048576)`
+//!
+//! ### bytesEqual(OFFSET, HEX)
+//!
+//! Configures a rule for binary contents that asserts the contents contains the given bytes
+//! (as a hex encoded string) at the offset. This can be used to check for magic numbers or headers.
+//!
+//! For example: `bytesEqual(0, '89504e47')`
+//!
+//! ### anyOf(EXPRESSION, EXPRESSION [, EXPRESSION]*)
+//!
+//! Configures a rule where the value must match at least one of the expressions. The example value
+//! (and any generator) is taken from the first expression that has an example.
+//!
+//! For example: `anyOf(matching(regex, '[0-9a-f]{8}(-[0-9a-f]{4}){3}-[0-9a-f]{12}', 'e2490de5-5bd3-43d5-b7c4-526e33f71304'), matching(equalTo, ''))`
+//!
+//! ### not(EXPRESSION)
+//!
+//! Configures a rule where the value must not match the expression. The example value of the
+//! expression is a value that must not match, so it is not used as the example.
+//!
+//! For example: `matching(type, 'Fred'), not(matching(equalTo, 'admin'))`
+//!
 //! ## Composing expressions
 //!
 //! Expressions can be composed by separating them with a comma. For example
 //! `atLeast(2), atMost(10), eachValue(matching(regex, '\d+', '1234'))`. This will configure an
 //! array to have to have at least 2 items, at most 10, and each item in the array must match the
-//! given regex.
+//! given regex. All the expressions separated by commas must match, use `anyOf` if only one of
+//! them needs to match.
 //!
 //! ## Grammar
 //!
@@ -109,7 +182,7 @@
 //!
 
 use std::char::REPLACEMENT_CHARACTER;
-use std::str::from_utf8;
+use std::str::{from_utf8, FromStr};
 
 use anyhow::{anyhow, Error};
 use ariadne::{Config, Label, Report, ReportKind, Source};
@@ -117,11 +190,14 @@ use bytes::{BufMut, BytesMut};
 use itertools::Either;
 use logos::{Lexer, Logos, Span};
 use semver::Version;
+use serde_json::Value;
 use tracing::{trace, warn};
 
 use crate::generators::Generator;
-use crate::matchingrules::MatchingRule;
-use crate::matchingrules::MatchingRule::{MaxType, MinType, NotEmpty};
+use crate::HttpStatus;
+use crate::path_exp::DocPath;
+use crate::matchingrules::{EpochUnit, MatchingRule, MatchingRuleCategory, NumericBound, NumericRange, RuleLogic};
+use crate::matchingrules::MatchingRule::{BytesEqual, MaxSize, MaxType, MinSize, MinType, NotEmpty};
 
 /// Type to associate with an expression element
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -131,7 +207,9 @@ pub enum ValueType {
   Number,
   Integer,
   Decimal,
-  Boolean
+  Boolean,
+  Array,
+  Object
 }
 
 impl ValueType {
@@ -163,6 +241,8 @@ impl ValueType {
       (ValueType::Boolean, ValueType::Unknown) => ValueType::Boolean,
       (ValueType::Boolean, ValueType::String) => ValueType::String,
       (ValueType::Boolean, ValueType::Boolean) => ValueType::Boolean,
+      (ValueType::Array, ValueType::Unknown) => ValueType::Array,
+      (ValueType::Object, ValueType::Unknown) => ValueType::Object,
       (ValueType::String, _) => ValueType::String,
       (_, _) => other
     }
@@ -245,6 +325,21 @@ enum MatcherDefinitionToken {
   #[token("atMost")]
   AtMost,
 
+  #[token("atLeastBytes")]
+  AtLeastBytes,
+
+  #[token("atMostBytes")]
+  AtMostBytes,
+
+  #[token("bytesEqual")]
+  BytesEqual,
+
+  #[token("anyOf")]
+  AnyOf,
+
+  #[token("not")]
+  Not,
+
   #[token("(")]
   LeftBracket,
 
@@ -254,6 +349,21 @@ enum MatcherDefinitionToken {
   #[token(",")]
   Comma,
 
+  #[token("[")]
+  LeftSquareBracket,
+
+  #[token("]")]
+  RightSquareBracket,
+
+  #[token("{")]
+  LeftBrace,
+
+  #[token("}")]
+  RightBrace,
+
+  #[token(":")]
+  Colon,
+
   #[regex(r"'(?:[^']|\\')*'")]
   String,
 
@@ -309,6 +419,9 @@ pub fn is_matcher_def(v: &str) -> bool {
       if token == MatcherDefinitionToken::Matching || token == MatcherDefinitionToken::NotEmpty ||
         token == MatcherDefinitionToken::EachKey || token == MatcherDefinitionToken::EachValue {
         true
+      } else if token == MatcherDefinitionToken::AnyOf || token == MatcherDefinitionToken::Not {
+        // These are also common words, so they need to be followed by a bracket
+        matches!(lex.next(), Some(Ok(MatcherDefinitionToken::LeftBracket)))
       } else {
         false
       }
@@ -347,6 +460,11 @@ fn matching_definition(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyh
 //       | 'eachValue' LEFT_BRACKET e=matchingDefinitionExp RIGHT_BRACKET
 //       | 'atLeast' LEFT_BRACKET DIGIT+ RIGHT_BRACKET
 //       | 'atMost' LEFT_BRACKET DIGIT+ RIGHT_BRACKET
+//       | 'atLeastBytes' LEFT_BRACKET DIGIT+ RIGHT_BRACKET
+//       | 'atMostBytes' LEFT_BRACKET DIGIT+ RIGHT_BRACKET
+//       | 'bytesEqual' LEFT_BRACKET DIGIT+ COMMA string RIGHT_BRACKET
+//       | 'anyOf' LEFT_BRACKET matchingDefinitionExp ( COMMA matchingDefinitionExp )+ RIGHT_BRACKET
+//       | 'not' LEFT_BRACKET e=matchingDefinitionExp RIGHT_BRACKET
 //     )
 //     ;
 fn matching_definition_exp(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<MatchingRuleDefinition> {
@@ -399,6 +517,34 @@ fn matching_definition_exp(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) ->
         rules: vec![Either::Left(MaxType(length))],
         generator: None
       })
+    } else if token == &MatcherDefinitionToken::AtLeastBytes {
+      let size = parse_length_param(lex, v)?;
+      Ok(MatchingRuleDefinition {
+        value: String::default(),
+        value_type: ValueType::Unknown,
+        rules: vec![Either::Left(MinSize(size))],
+        generator: None
+      })
+    } else if token == &MatcherDefinitionToken::AtMostBytes {
+      let size = parse_length_param(lex, v)?;
+      Ok(MatchingRuleDefinition {
+        value: String::default(),
+        value_type: ValueType::Unknown,
+        rules: vec![Either::Left(MaxSize(size))],
+        generator: None
+      })
+    } else if token == &MatcherDefinitionToken::BytesEqual {
+      let (offset, bytes) = parse_bytes_equal(lex, v)?;
+      Ok(MatchingRuleDefinition {
+        value: String::default(),
+        value_type: ValueType::Unknown,
+        rules: vec![Either::Left(BytesEqual(offset, bytes))],
+        generator: None
+      })
+    } else if token == &MatcherDefinitionToken::AnyOf {
+      parse_any_of(lex, v)
+    } else if token == &MatcherDefinitionToken::Not {
+      parse_not(lex, v)
     } else {
       let mut buffer = BytesMut::new().writer();
       let span = lex.span();
@@ -406,7 +552,7 @@ fn matching_definition_exp(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) ->
         .with_config(Config::default().with_color(false))
         .with_message(format!("Expected a type of matching rule definition, but got '{}'", lex.slice()))
         .with_label(Label::new(("expression", span)).with_message("Expected a matching rule definition here"))
-        .with_note("valid matching rule definitions are: matching, notEmpty, eachKey, eachValue, atLeast, atMost")
+        .with_note("valid matching rule definitions are: matching, notEmpty, eachKey, eachValue, atLeast, atMost, atLeastBytes, atMostBytes, bytesEqual, anyOf, not")
         .finish();
       report.write(("expression", Source::from(v)), &mut buffer)?;
       let message = from_utf8(&*buffer.get_ref())?.to_string();
@@ -419,7 +565,7 @@ fn matching_definition_exp(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) ->
       .with_config(Config::default().with_color(false))
       .with_message(format!("Expected a type of matching rule definition but got the end of the expression"))
       .with_label(Label::new(("expression", span)).with_message("Expected a matching rule definition here"))
-      .with_note("valid matching rule definitions are: matching, notEmpty, eachKey, eachValue, atLeast, atMost")
+      .with_note("valid matching rule definitions are: matching, notEmpty, eachKey, eachValue, atLeast, atMost, atLeastBytes, atMostBytes, bytesEqual, anyOf, not")
       .finish();
     report.write(("expression", Source::from(v)), &mut buffer)?;
     let message = from_utf8(&*buffer.get_ref())?.to_string();
@@ -515,11 +661,84 @@ fn parse_each_key(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::R
   }
 }
 
-// LEFT_BRACKET primitiveValue RIGHT_BRACKET
+// LEFT_BRACKET matchingDefinitionExp ( COMMA matchingDefinitionExp )+ RIGHT_BRACKET
+fn parse_any_of(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<MatchingRuleDefinition> {
+  let next = lex.next()
+    .ok_or_else(|| end_of_expression(v, "an opening bracket"))?;
+  if let Ok(MatcherDefinitionToken::LeftBracket) = next {
+    let mut alternatives = vec![ composite_definition_exp(lex, v)? ];
+    loop {
+      let next = lex.next().ok_or_else(|| end_of_expression(v, "a comma or closing bracket"))?;
+      match next {
+        Ok(MatcherDefinitionToken::Comma) => alternatives.push(composite_definition_exp(lex, v)?),
+        Ok(MatcherDefinitionToken::RightBracket) => break,
+        _ => return Err(anyhow!(error_message(lex, v, "Expected a comma or closing bracket", "Expected a comma or closing bracket before this")?))
+      }
+    }
+
+    if alternatives.len() < 2 {
+      return Err(anyhow!(error_message(lex, v, "Expected at least two matching rule definitions for anyOf", "Expected another matching rule definition before this")?));
+    }
+
+    // The example value and generator are taken from the first alternative that has an example
+    let example = alternatives.iter()
+      .find(|definition| !definition.value.is_empty())
+      .or_else(|| alternatives.first())
+      .cloned()
+      .unwrap();
+    Ok(MatchingRuleDefinition {
+      value: example.value,
+      value_type: example.value_type,
+      rules: vec![ Either::Left(MatchingRule::AnyOf(alternatives.iter()
+        .flat_map(|definition| definition.rules.iter().filter_map(|rule| rule.clone().left()))
+        .collect())) ],
+      generator: example.generator
+    })
+  } else {
+    Err(anyhow!(error_message(lex, v, "Expected an opening bracket", "Expected an opening bracket before this")?))
+  }
+}
+
+// LEFT_BRACKET e=matchingDefinitionExp RIGHT_BRACKET
+fn parse_not(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<MatchingRuleDefinition> {
+  let next = lex.next()
+    .ok_or_else(|| end_of_expression(v, "an opening bracket"))?;
+  if let Ok(MatcherDefinitionToken::LeftBracket) = next {
+    let result = composite_definition_exp(lex, v)?;
+    let next = lex.next().ok_or_else(|| end_of_expression(v, "a closing bracket"))?;
+    if let Ok(MatcherDefinitionToken::RightBracket) = next {
+      // The example value of the negated rule is a value that must not match, so is not used
+      let rule = result.rules.into_iter().find_map(|rule| rule.left()).unwrap();
+      Ok(MatchingRuleDefinition {
+        value: String::default(),
+        value_type: ValueType::Unknown,
+        rules: vec![ Either::Left(MatchingRule::Not(Box::new(rule))) ],
+        generator: None
+      })
+    } else {
+      Err(anyhow!(error_message(lex, v, "Expected a closing bracket", "Expected a closing bracket before this")?))
+    }
+  } else {
+    Err(anyhow!(error_message(lex, v, "Expected an opening bracket", "Expected an opening bracket before this")?))
+  }
+}
+
+// Parses a matching definition used in anyOf or not. References to other attributes can not be
+// combined, as they define the structure of the value and not a rule.
+fn composite_definition_exp(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<MatchingRuleDefinition> {
+  let definition = matching_definition_exp(lex, v)?;
+  if definition.rules.iter().any(|rule| rule.is_right()) {
+    Err(anyhow!(error_message(lex, v, "References can not be used with anyOf or not", "This definition contains a reference")?))
+  } else {
+    Ok(definition)
+  }
+}
+
+// LEFT_BRACKET value RIGHT_BRACKET
 fn parse_not_empty(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<(String, ValueType)> {
   let next = lex.next().ok_or_else(|| anyhow!("expected '('"))?;
   if let Ok(MatcherDefinitionToken::LeftBracket) = next {
-    let result = parse_primitive_value(lex, v)?;
+    let result = parse_value(lex, v)?;
     let next = lex.next().ok_or_else(|| anyhow!("expected ')'"))?;
     if let Ok(MatcherDefinitionToken::RightBracket) = next {
       Ok(result)
@@ -565,6 +784,13 @@ fn parse_matching(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::R
 //   | 'boolean' COMMA BOOLEAN_LITERAL { $rule = BooleanMatcher.INSTANCE; $value = $BOOLEAN_LITERAL.getText(); $type = ValueType.Boolean; }
 //   | 'semver' COMMA s=string { $rule = SemverMatcher.INSTANCE; $value = $s.contents; $type = ValueType.String; }
 //   | 'contentType' COMMA ct=string COMMA s=string { $rule = new ContentTypeMatcher($ct.contents); $value = $s.contents; $type = ValueType.Unknown; }
+//   | 'duration' COMMA s=string { $rule = DurationMatcher.INSTANCE; $value = $s.contents; $type = ValueType.String; }
+//   | 'epoch' COMMA unit=string COMMA val=INTEGER_LITERAL ( COMMA tolerance=INTEGER_LITERAL )? { $rule = new EpochTimestampMatcher($unit.contents, $tolerance); $value = $val.getText(); $type = ValueType.Integer; }
+//   | 'equalsIgnoringOrder' COMMA v=primitiveValue { $rule = EqualsIgnoreOrderMatcher.INSTANCE; $value = $v.value; $type = $v.type; }
+//   | 'url' COMMA s=string ( COMMA c=string COMMA e=matchingDefinitionExp )* { $rule = new UrlMatcher($c, $e); $value = $s.contents; $type = ValueType.String; }
+//   | ( 'greaterThan' | 'greaterThanOrEqual' | 'lessThan' | 'lessThanOrEqual' ) COMMA b=number COMMA v=number { $rule = new NumericRangeMatcher($b); $value = $v.value; $type = $v.type; }
+//   | 'range' COMMA min=number COMMA max=number COMMA v=number { $rule = new NumericRangeMatcher($min, $max); $value = $v.value; $type = $v.type; }
+//   | 'statusCode' COMMA s=string ( COMMA v=INTEGER_LITERAL )? { $rule = new StatusCodeMatcher($s.contents); $value = $v.getText(); $type = ValueType.Integer; }
 //   | DOLLAR ref=string { $reference = new MatchingReference($ref.contents); $type = ValueType.Unknown; }
 //   ;
 fn parse_matching_rule(lex: &mut logos::Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<(String, ValueType, Option<MatchingRule>, Option<Generator>, Option<MatchingReference>)> {
@@ -585,6 +811,15 @@ fn parse_matching_rule(lex: &mut logos::Lexer<MatcherDefinitionToken>, v: &str)
       "boolean" => parse_boolean(lex, v),
       "contentType" => parse_content_type(lex, v),
       "semver" => parse_semver(lex, v),
+      "duration" => parse_duration(lex, v),
+      "epoch" => parse_epoch(lex, v),
+      "equalsIgnoringOrder" => parse_equals_ignoring_order(lex, v),
+      "url" => parse_url(lex, v),
+      "greaterThan" | "greaterThanOrEqual" | "lessThan" | "lessThanOrEqual" | "range" => {
+        let matcher = lex.slice().to_string();
+        parse_numeric_range(lex, v, matcher.as_str())
+      }
+      "statusCode" => parse_status_code(lex, v),
       _ => {
         let mut buffer = BytesMut::new().writer();
         let span = lex.span();
@@ -592,7 +827,7 @@ fn parse_matching_rule(lex: &mut logos::Lexer<MatcherDefinitionToken>, v: &str)
           .with_config(Config::default().with_color(false))
           .with_message(format!("Expected the type of matcher, got '{}'", lex.slice()))
           .with_label(Label::new(("expression", span)).with_message("This is not a valid matcher type"))
-          .with_note("Valid matchers are: equalTo, regex, type, datetime, date, time, include, number, integer, decimal, boolean, contentType, semver")
+          .with_note("Valid matchers are: equalTo, regex, type, datetime, date, time, include, number, integer, decimal, boolean, contentType, semver, duration, epoch, equalsIgnoringOrder, url, greaterThan, greaterThanOrEqual, lessThan, lessThanOrEqual, range, statusCode")
           .finish();
         report.write(("expression", Source::from(v)), &mut buffer)?;
         let message = from_utf8(&*buffer.get_ref())?.to_string();
@@ -642,13 +877,83 @@ fn parse_semver(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Res
   }
 }
 
-//     COMMA v=primitiveValue { $value = $v.value; $type = $v.type; } )
+//     COMMA v=value { $value = $v.value; $type = $v.type; } )
 fn parse_equality(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<(String, ValueType, Option<MatchingRule>, Option<Generator>, Option<MatchingReference>)> {
   parse_comma(lex, v)?;
-  let (value, value_type) = parse_primitive_value(lex, v)?;
+  let (value, value_type) = parse_value(lex, v)?;
   Ok((value, value_type, Some(MatchingRule::Equality), None, None))
 }
 
+// COMMA v=value { $value = $v.value; $type = $v.type; } )
+fn parse_equals_ignoring_order(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<(String, ValueType, Option<MatchingRule>, Option<Generator>, Option<MatchingReference>)> {
+  parse_comma(lex, v)?;
+  let (value, value_type) = parse_value(lex, v)?;
+  Ok((value, value_type, Some(MatchingRule::EqualsIgnoreOrder), None, None))
+}
+
+// COMMA s=string ( COMMA c=string COMMA e=matchingDefinitionExp )*
+fn parse_url(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<(String, ValueType, Option<MatchingRule>, Option<Generator>, Option<MatchingReference>)> {
+  parse_comma(lex, v)?;
+  let value = parse_string(lex, v)?;
+  let mut rules = MatchingRuleCategory::empty("body");
+  while lex.remainder().trim_start().starts_with(',') {
+    parse_comma(lex, v)?;
+    let component = parse_string(lex, v)?;
+    let path = DocPath::new(format!("$.{}", component))
+      .map_err(|_| anyhow!(error_message(lex, v, "Expected a URL component", "Expected a URL component here")
+        .unwrap_or_default()))?;
+    parse_comma(lex, v)?;
+    let definition = matching_definition_exp(lex, v)?;
+    for rule in definition.rules {
+      match rule {
+        Either::Left(rule) => rules.add_rule(path.clone(), rule, RuleLogic::And),
+        Either::Right(_) => return Err(anyhow!(error_message(lex, v,
+          "References can not be used with URL components", "Expected a matching rule here")?))
+      }
+    }
+  }
+  Ok((value, ValueType::String, Some(MatchingRule::Url(rules)), None, None))
+}
+
+// COMMA v=( DECIMAL_LITERAL | INTEGER_LITERAL ) { $value = $v.getText(); }
+fn parse_numeric_value(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<(f64, String, ValueType)> {
+  parse_comma(lex, v)?;
+  if lex.remainder().trim().is_empty() {
+    return Err(end_of_expression(v, "a number"));
+  }
+  match parse_primitive_value(lex, v) {
+    Ok((value, value_type)) if value_type == ValueType::Integer || value_type == ValueType::Decimal => {
+      let number = value.parse::<f64>()
+        .map_err(|_| anyhow!(error_message(lex, v, "Expected a number", "Expected a number here").unwrap_or_default()))?;
+      Ok((number, value, value_type))
+    }
+    _ => Err(anyhow!(error_message(lex, v, "Expected a number", "Expected a number here")?))
+  }
+}
+
+// ( COMMA b=number | COMMA min=number COMMA max=number ) COMMA v=number { $rule = new NumericRangeMatcher(...); $value = $v.value; $type = $v.type; }
+fn parse_numeric_range(lex: &mut Lexer<MatcherDefinitionToken>, v: &str, matcher: &str) -> anyhow::Result<(String, ValueType, Option<MatchingRule>, Option<Generator>, Option<MatchingReference>)> {
+  let (bound, _, _) = parse_numeric_value(lex, v)?;
+  let (min, max) = match matcher {
+    "greaterThan" => (Some(NumericBound::Exclusive(bound)), None),
+    "greaterThanOrEqual" => (Some(NumericBound::Inclusive(bound)), None),
+    "lessThan" => (None, Some(NumericBound::Exclusive(bound))),
+    "lessThanOrEqual" => (None, Some(NumericBound::Inclusive(bound))),
+    _ => {
+      let (max, _, _) = parse_numeric_value(lex, v)?;
+      (Some(NumericBound::Inclusive(bound)), Some(NumericBound::Inclusive(max)))
+    }
+  };
+  let range = NumericRange::new(min, max)
+    .map_err(|err| anyhow!(error_message(lex, v, err.to_string().as_str(), "Range is not valid").unwrap_or_default()))?;
+  let (number, value, value_type) = parse_numeric_value(lex, v)?;
+  if !range.contains(number) {
+    return Err(anyhow!(error_message(lex, v, format!("Example value {} is not in the range {}", value, range).as_str(),
+      "Expected a number within the range here")?));
+  }
+  Ok((value, value_type, Some(MatchingRule::NumericRange(range)), Some(Generator::RandomNumber(range)), None))
+}
+
 // COMMA r=string COMMA s=string { $rule = new RegexMatcher($r.contents); $value = $s.contents; $type = ValueType.String; }
 fn parse_regex(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<(String, ValueType, Option<MatchingRule>, Option<Generator>, Option<MatchingReference>)> {
   parse_comma(lex, v)?;
@@ -658,10 +963,10 @@ fn parse_regex(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Resu
   Ok((value, ValueType::String, Some(MatchingRule::Regex(regex)), None, None))
 }
 
-// COMMA v=primitiveValue { $value = $v.value; $type = $v.type; } )
+// COMMA v=value { $value = $v.value; $type = $v.type; } )
 fn parse_type(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<(String, ValueType, Option<MatchingRule>, Option<Generator>, Option<MatchingReference>)> {
   parse_comma(lex, v)?;
-  let (value, value_type) = parse_primitive_value(lex, v)?;
+  let (value, value_type) = parse_value(lex, v)?;
   Ok((value, value_type, Some(MatchingRule::Type), None, None))
 }
 
@@ -708,6 +1013,67 @@ fn parse_content_type(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyho
   Ok((value, ValueType::Unknown, Some(MatchingRule::ContentType(ct)), None, None))
 }
 
+// COMMA s=string ( COMMA v=INTEGER_LITERAL )? { $rule = new StatusCodeMatcher($s.contents); $value = $v.getText(); $type = ValueType.Integer; }
+fn parse_status_code(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<(String, ValueType, Option<MatchingRule>, Option<Generator>, Option<MatchingReference>)> {
+  parse_comma(lex, v)?;
+  let value = parse_string(lex, v)?;
+  let status = HttpStatus::from_str(value.as_str())
+    .map_err(|_| anyhow!(error_message(lex, v, "Expected a HTTP status class (i.e. success or 2xx), a range or a list of status codes",
+      "This is not a valid HTTP status").unwrap_or_default()))?;
+  let example = if lex.remainder().trim_start().starts_with(',') {
+    parse_comma(lex, v)?;
+    let next = lex.next().ok_or_else(|| end_of_expression(v, "a status code"))?;
+    if let Ok(MatcherDefinitionToken::Num(example)) = next {
+      if example > u16::MAX as usize || !status.contains(example as u16) {
+        return Err(anyhow!(error_message(lex, v, format!("Expected the example status code to be a {}", status).as_str(),
+          "This status code does not match").unwrap_or_default()));
+      }
+      example as u16
+    } else {
+      return Err(anyhow!(error_message(lex, v, "Expected a status code", "Expected a status code here")?));
+    }
+  } else {
+    status.example()
+  };
+  Ok((example.to_string(), ValueType::Integer, Some(MatchingRule::StatusCode(status)), None, None))
+}
+
+// COMMA s=string { $rule = DurationMatcher.INSTANCE; $value = $s.contents; $type = ValueType.String; }
+fn parse_duration(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<(String, ValueType, Option<MatchingRule>, Option<Generator>, Option<MatchingReference>)> {
+  parse_comma(lex, v)?;
+  let value = parse_string(lex, v)?;
+  Ok((value, ValueType::String, Some(MatchingRule::Duration), None, None))
+}
+
+// COMMA unit=string COMMA val=INTEGER_LITERAL ( COMMA tolerance=INTEGER_LITERAL )? { $value = $val.getText(); $type = ValueType.Integer; }
+fn parse_epoch(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<(String, ValueType, Option<MatchingRule>, Option<Generator>, Option<MatchingReference>)> {
+  parse_comma(lex, v)?;
+  let unit = parse_string(lex, v)?;
+  let unit = EpochUnit::from_str(unit.as_str())
+    .map_err(|_| anyhow!(error_message(lex, v, "Expected an epoch unit (seconds, millis, micros or nanos)",
+      "Expected an epoch unit here").unwrap_or_default()))?;
+  parse_comma(lex, v)?;
+  let next = lex.next().ok_or_else(|| end_of_expression(v, "an unsigned number"))?;
+  let value = if let Ok(MatcherDefinitionToken::Num(_)) = next {
+    lex.slice().to_string()
+  } else {
+    return Err(anyhow!(error_message(lex, v, "Expected an unsigned number", "Expected an unsigned number here")?));
+  };
+  let tolerance = if lex.remainder().trim_start().starts_with(',') {
+    parse_comma(lex, v)?;
+    let next = lex.next().ok_or_else(|| end_of_expression(v, "an unsigned number"))?;
+    if let Ok(MatcherDefinitionToken::Num(tolerance)) = next {
+      Some(tolerance as u64)
+    } else {
+      return Err(anyhow!(error_message(lex, v, "Expected an unsigned number", "Expected an unsigned number here")?));
+    }
+  } else {
+    None
+  };
+  Ok((value, ValueType::Integer, Some(MatchingRule::EpochTimestamp(unit, tolerance)),
+    Some(Generator::EpochTimestamp(unit)), None))
+}
+
 // primitiveValue returns [ String value, ValueType type ] :
 //   string { $value = $string.contents; $type = ValueType.String; }
 //   | v=DECIMAL_LITERAL { $value = $v.getText(); $type = ValueType.Decimal; }
@@ -754,6 +1120,95 @@ fn parse_primitive_value(lex: &mut Lexer<MatcherDefinitionToken>, _v: &str) -> a
   }
 }
 
+// value returns [ String value, ValueType type ] :
+//   primitiveValue { $value = $primitiveValue.value; $type = $primitiveValue.type; }
+//   | array { $value = $array.value.toString(); $type = ValueType.Array; }
+//   | object { $value = $object.value.toString(); $type = ValueType.Object; }
+//   ;
+fn parse_value(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<(String, ValueType)> {
+  let remainder = lex.remainder().trim_start();
+  if remainder.starts_with('[') {
+    Ok((parse_literal_value(lex, v)?.to_string(), ValueType::Array))
+  } else if remainder.starts_with('{') {
+    Ok((parse_literal_value(lex, v)?.to_string(), ValueType::Object))
+  } else {
+    parse_primitive_value(lex, v)
+  }
+}
+
+// literalValue :
+//   string | DECIMAL_LITERAL | INTEGER_LITERAL | BOOLEAN_LITERAL | 'null'
+//   | LEFT_SQUARE_BRACKET ( literalValue ( COMMA literalValue )* )? RIGHT_SQUARE_BRACKET
+//   | LEFT_BRACE ( string COLON literalValue ( COMMA string COLON literalValue )* )? RIGHT_BRACE
+//   ;
+fn parse_literal_value(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<Value> {
+  let next = lex.next().ok_or_else(|| end_of_expression(v, "a value"))?;
+  match next {
+    Ok(MatcherDefinitionToken::String) => {
+      let span = lex.span();
+      process_raw_string(lex.slice().trim_matches('\''), span, v).map(Value::String)
+    }
+    Ok(MatcherDefinitionToken::Null) => Ok(Value::Null),
+    Ok(MatcherDefinitionToken::Boolean) => Ok(Value::Bool(lex.slice() == "true")),
+    Ok(MatcherDefinitionToken::Int(_) | MatcherDefinitionToken::Num(_)) => {
+      // Logos is returning an INT token when a Decimal should match. We need to now parse the
+      // remaining pattern if it is a decimal
+      let number = if lex.remainder().starts_with('.') {
+        let int_part = lex.slice();
+        let _ = lex.next().ok_or_else(|| anyhow!("expected a number"))?;
+        format!("{}{}", int_part, lex.slice())
+      } else {
+        lex.slice().to_string()
+      };
+      serde_json::from_str(number.as_str())
+        .map_err(|err| anyhow!("'{}' is not a valid number - {}", number, err))
+    }
+    Ok(MatcherDefinitionToken::Decimal) => serde_json::from_str(lex.slice())
+      .map_err(|err| anyhow!("'{}' is not a valid number - {}", lex.slice(), err)),
+    Ok(MatcherDefinitionToken::LeftSquareBracket) => {
+      let mut items = vec![];
+      if lex.remainder().trim_start().starts_with(']') {
+        lex.next();
+      } else {
+        loop {
+          items.push(parse_literal_value(lex, v)?);
+          match lex.next() {
+            Some(Ok(MatcherDefinitionToken::Comma)) => {}
+            Some(Ok(MatcherDefinitionToken::RightSquareBracket)) => break,
+            Some(_) => return Err(anyhow!("expected a comma or ']', got '{}'", lex.slice())),
+            None => return Err(end_of_expression(v, "']'"))
+          }
+        }
+      }
+      Ok(Value::Array(items))
+    }
+    Ok(MatcherDefinitionToken::LeftBrace) => {
+      let mut entries = serde_json::Map::new();
+      if lex.remainder().trim_start().starts_with('}') {
+        lex.next();
+      } else {
+        loop {
+          let key = parse_string(lex, v)?;
+          match lex.next() {
+            Some(Ok(MatcherDefinitionToken::Colon)) => {}
+            Some(_) => return Err(anyhow!("expected a colon, got '{}'", lex.slice())),
+            None => return Err(end_of_expression(v, "a colon"))
+          }
+          entries.insert(key, parse_literal_value(lex, v)?);
+          match lex.next() {
+            Some(Ok(MatcherDefinitionToken::Comma)) => {}
+            Some(Ok(MatcherDefinitionToken::RightBrace)) => break,
+            Some(_) => return Err(anyhow!("expected a comma or '}}', got '{}'", lex.slice())),
+            None => return Err(end_of_expression(v, "'}'"))
+          }
+        }
+      }
+      Ok(Value::Object(entries))
+    }
+    _ => Err(anyhow!("expected a value, got '{}'", lex.slice()))
+  }
+}
+
 // COMMA val=( DECIMAL_LITERAL | INTEGER_LITERAL ) { $value = $val.getText(); $type = ValueType.Number; }
 #[allow(clippy::if_same_then_else)]
 fn parse_number(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<(String, ValueType, Option<MatchingRule>, Option<Generator>, Option<MatchingReference>)> {
@@ -980,6 +1435,31 @@ fn parse_length_param(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyho
   }
 }
 
+// LEFT_BRACKET DIGIT+ COMMA string RIGHT_BRACKET
+fn parse_bytes_equal(lex: &mut Lexer<MatcherDefinitionToken>, v: &str) -> anyhow::Result<(usize, Vec<u8>)> {
+  let next = lex.next().ok_or_else(|| end_of_expression(v, "an opening bracket"))?;
+  if let Ok(MatcherDefinitionToken::LeftBracket) = next {
+    let next = lex.next().ok_or_else(|| end_of_expression(v, "an unsized integer"))?;
+    if let Ok(MatcherDefinitionToken::Num(offset)) = next {
+      parse_comma(lex, v)?;
+      let value = parse_string(lex, v)?;
+      let bytes = hex::decode(value.as_str())
+        .map_err(|_| anyhow!(error_message(lex, v, "Expected a hex encoded string", "Expected a hex encoded string here")
+          .unwrap_or_default()))?;
+      let next = lex.next().ok_or_else(|| end_of_expression(v, "')'"))?;
+      if let Ok(MatcherDefinitionToken::RightBracket) = next {
+        Ok((offset, bytes))
+      } else {
+        Err(anyhow!(error_message(lex, v, "Expected a closing bracket", "Expected a closing bracket before this")?))
+      }
+    } else {
+      Err(anyhow!(error_message(lex, v, "Expected an unsigned number", "Expected an unsigned number here")?))
+    }
+  } else {
+    Err(anyhow!(error_message(lex, v, "Expected an opening bracket", "Expected an opening bracket here")?))
+  }
+}
+
 #[cfg(test)]
 mod test {
   use expectest::prelude::*;
@@ -1014,6 +1494,45 @@ mod test {
       be_equal_to(MatchingRuleDefinition::new("123.4".to_string(), ValueType::Decimal, MatchingRule::Type, None)));
   }
 
+  #[test]
+  fn parse_type_matcher_with_array_and_object_values() {
+    expect!(super::parse_matcher_def("matching(type, ['a', 'b'])").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new(r#"["a","b"]"#.to_string(), ValueType::Array, MatchingRule::Type, None)));
+    expect!(super::parse_matcher_def("matching(type, [])").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("[]".to_string(), ValueType::Array, MatchingRule::Type, None)));
+    expect!(super::parse_matcher_def("matching(type, {'id': 1})").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new(r#"{"id":1}"#.to_string(), ValueType::Object, MatchingRule::Type, None)));
+    expect!(super::parse_matcher_def("matching(type, { })").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("{}".to_string(), ValueType::Object, MatchingRule::Type, None)));
+    expect!(super::parse_matcher_def(
+      "matching(type, {'id': -10.5, 'name': 'Fred', 'tags': ['a', 2, 0.5, true, null], 'owner': {'id': 100}})").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new(
+        r#"{"id":-10.5,"name":"Fred","owner":{"id":100},"tags":["a",2,0.5,true,null]}"#.to_string(),
+        ValueType::Object, MatchingRule::Type, None)));
+    expect!(super::parse_matcher_def("matching(equalTo, [[1, 2], [3]])").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("[[1,2],[3]]".to_string(), ValueType::Array, MatchingRule::Equality, None)));
+    expect!(super::parse_matcher_def("notEmpty(['a'])").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new(r#"["a"]"#.to_string(), ValueType::Array, MatchingRule::NotEmpty, None)));
+    expect!(super::parse_matcher_def("matching(type, ['a']), atLeast(1)").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition {
+        value: r#"["a"]"#.to_string(),
+        value_type: ValueType::Array,
+        rules: vec![ Either::Left(MatchingRule::Type), Either::Left(MatchingRule::MinType(1)) ],
+        generator: None
+      }));
+  }
+
+  #[test]
+  fn parse_type_matcher_with_invalid_array_and_object_values() {
+    expect!(super::parse_matcher_def("matching(type, ['a' 'b'])")).to(be_err());
+    expect!(super::parse_matcher_def("matching(type, ['a',])")).to(be_err());
+    expect!(super::parse_matcher_def("matching(type, ['a')")).to(be_err());
+    expect!(super::parse_matcher_def("matching(type, ['a'")).to(be_err());
+    expect!(super::parse_matcher_def("matching(type, {id: 1})")).to(be_err());
+    expect!(super::parse_matcher_def("matching(type, {'id' 1})")).to(be_err());
+    expect!(super::parse_matcher_def("matching(type, {'id': 1")).to(be_err());
+  }
+
   #[test]
   fn parse_number_matcher() {
     expect!(super::parse_matcher_def("matching(number,100)").unwrap()).to(
@@ -1186,6 +1705,34 @@ mod test {
     expect!(ValueType::Boolean.merge(ValueType::Decimal)).to(be_equal_to(ValueType::Decimal));
   }
 
+  #[test]
+  fn parse_equals_ignoring_order_matcher() {
+    expect!(super::parse_matcher_def("matching(equalsIgnoringOrder, 'Name')").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("Name".to_string(), ValueType::String, MatchingRule::EqualsIgnoreOrder, None)));
+    expect!(super::parse_matcher_def("matching(equalsIgnoringOrder, 100)").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("100".to_string(), ValueType::Integer, MatchingRule::EqualsIgnoreOrder, None)));
+  }
+
+  #[test]
+  fn parse_status_code_matcher() {
+    expect!(super::parse_matcher_def("matching(statusCode, 'clientError')").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("400".to_string(), ValueType::Integer,
+        MatchingRule::StatusCode(HttpStatus::ClientError), None)));
+    expect!(super::parse_matcher_def("matching(statusCode, '2xx', 204)").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("204".to_string(), ValueType::Integer,
+        MatchingRule::StatusCode(HttpStatus::Success), None)));
+    expect!(super::parse_matcher_def("matching(statusCode, '200-204')").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("200".to_string(), ValueType::Integer,
+        MatchingRule::StatusCode(HttpStatus::Range(200, 204)), None)));
+    expect!(super::parse_matcher_def("matching(statusCode, '200,204', 204)").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("204".to_string(), ValueType::Integer,
+        MatchingRule::StatusCode(HttpStatus::StatusCodes(vec![200, 204])), None)));
+
+    expect!(super::parse_matcher_def("matching(statusCode, 'okay')")).to(be_err());
+    expect!(super::parse_matcher_def("matching(statusCode, 'success', 404)")).to(be_err());
+    expect!(super::parse_matcher_def("matching(statusCode, 'success', '200')")).to(be_err());
+  }
+
   #[test]
   fn parse_semver_matcher() {
     expect!(super::parse_matcher_def("matching(semver, '1.0.0')").unwrap()).to(
@@ -1270,7 +1817,7 @@ mod test {
             |   │       ────┬─── \u{0020}
             |   │           ╰───── This is not a valid matcher type
             |   │\u{0020}
-            |   │ Note: Valid matchers are: equalTo, regex, type, datetime, date, time, include, number, integer, decimal, boolean, contentType, semver
+            |   │ Note: Valid matchers are: equalTo, regex, type, datetime, date, time, include, number, integer, decimal, boolean, contentType, semver, duration, epoch, equalsIgnoringOrder, url, greaterThan, greaterThanOrEqual, lessThan, lessThanOrEqual, range, statusCode
             |───╯
             |
             ".trim_margin().unwrap()));
@@ -1390,7 +1937,7 @@ mod test {
             |   │    │\u{0020}
             |   │    ╰─ Expected a matching rule definition here
             |   │\u{0020}
-            |   │ Note: valid matching rule definitions are: matching, notEmpty, eachKey, eachValue, atLeast, atMost
+            |   │ Note: valid matching rule definitions are: matching, notEmpty, eachKey, eachValue, atLeast, atMost, atLeastBytes, atMostBytes, bytesEqual
             |───╯
             |
             ".trim_margin().unwrap()));
@@ -1405,7 +1952,7 @@ mod test {
             |   │ ──────┬────── \u{0020}
             |   │       ╰──────── Expected a matching rule definition here
             |   │\u{0020}
-            |   │ Note: valid matching rule definitions are: matching, notEmpty, eachKey, eachValue, atLeast, atMost
+            |   │ Note: valid matching rule definitions are: matching, notEmpty, eachKey, eachValue, atLeast, atMost, atLeastBytes, atMostBytes, bytesEqual
             |───╯
             |
             ".trim_margin().unwrap()));
@@ -1839,4 +2386,176 @@ mod test {
         |
         ".trim_margin().unwrap());
   }
+
+  #[test]
+  fn parse_binary_size_test() {
+    let mut lex = MatcherDefinitionToken::lexer("atLeastBytes(1024)");
+    assert_eq!(super::matching_definition_exp(&mut lex, "atLeastBytes(1024)").unwrap(),
+      MatchingRuleDefinition {
+        value: "".to_string(),
+        value_type: ValueType::Unknown,
+        rules: vec![ Either::Left(MatchingRule::MinSize(1024)) ],
+        generator: None
+      }
+    );
+
+    let mut lex = MatcherDefinitionToken::lexer("atMostBytes(4096)");
+    assert_eq!(super::matching_definition_exp(&mut lex, "atMostBytes(4096)").unwrap(),
+      MatchingRuleDefinition {
+        value: "".to_string(),
+        value_type: ValueType::Unknown,
+        rules: vec![ Either::Left(MatchingRule::MaxSize(4096)) ],
+        generator: None
+      }
+    );
+
+    expect!(super::parse_matcher_def("atLeastBytes(-1)")).to(be_err());
+  }
+
+  #[test]
+  fn parse_bytes_equal_test() {
+    let mut lex = MatcherDefinitionToken::lexer("bytesEqual(0, '89504e47')");
+    assert_eq!(super::matching_definition_exp(&mut lex, "bytesEqual(0, '89504e47')").unwrap(),
+      MatchingRuleDefinition {
+        value: "".to_string(),
+        value_type: ValueType::Unknown,
+        rules: vec![ Either::Left(MatchingRule::BytesEqual(0, vec![0x89, 0x50, 0x4e, 0x47])) ],
+        generator: None
+      }
+    );
+
+    expect!(super::parse_matcher_def("bytesEqual(0, 'not hex')")).to(be_err());
+    expect!(super::parse_matcher_def("bytesEqual('0', '89')")).to(be_err());
+    expect!(super::parse_matcher_def("bytesEqual(0 '89')")).to(be_err());
+  }
+
+  #[test]
+  fn parse_duration_matcher() {
+    expect!(super::parse_matcher_def("matching(duration, 'PT5M30S')").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("PT5M30S".to_string(), ValueType::String, MatchingRule::Duration, None)));
+    expect!(super::parse_matcher_def("matching(duration, PT5M30S)")).to(be_err());
+  }
+
+  #[test]
+  fn parse_epoch_matcher() {
+    expect!(super::parse_matcher_def("matching(epoch, 'millis', 1700000000000)").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("1700000000000".to_string(), ValueType::Integer,
+        MatchingRule::EpochTimestamp(EpochUnit::Millis, None), Some(Generator::EpochTimestamp(EpochUnit::Millis)))));
+    expect!(super::parse_matcher_def("matching(epoch, 'seconds', 1700000000, 60)").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("1700000000".to_string(), ValueType::Integer,
+        MatchingRule::EpochTimestamp(EpochUnit::Seconds, Some(60)), Some(Generator::EpochTimestamp(EpochUnit::Seconds)))));
+    expect!(super::parse_matcher_def("matching(epoch, 'weeks', 1700000000)")).to(be_err());
+    expect!(super::parse_matcher_def("matching(epoch, 'millis', '1700000000000')")).to(be_err());
+    expect!(super::parse_matcher_def("matching(epoch, 'millis', 1700000000000, -1)")).to(be_err());
+    expect!(super::parse_matcher_def("matching(epoch, 1700000000000)")).to(be_err());
+  }
+
+  #[test]
+  fn parse_url_matcher() {
+    expect!(super::parse_matcher_def("matching(url, 'https://api.example.com/v1/users')").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("https://api.example.com/v1/users".to_string(), ValueType::String,
+        MatchingRule::Url(MatchingRuleCategory::empty("body")), None)));
+
+    let mut rules = MatchingRuleCategory::empty("body");
+    rules.add_rule(DocPath::new_unwrap("$.host"), MatchingRule::Regex(".+\\.example\\.com".to_string()), RuleLogic::And);
+    rules.add_rule(DocPath::new_unwrap("$.query.page"), MatchingRule::Integer, RuleLogic::And);
+    expect!(super::parse_matcher_def("matching(url, 'https://api.example.com/v1/users?page=1', \
+      'host', matching(regex, '.+\\.example\\.com', 'api.example.com'), 'query.page', matching(integer, 1))").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("https://api.example.com/v1/users?page=1".to_string(), ValueType::String,
+        MatchingRule::Url(rules), None)));
+    let definition = super::parse_matcher_def("matching(url, 'https://api.example.com/v1/users?page=1', \
+      'host', matching(regex, '.+\\.example\\.com', 'api.example.com'), 'query.page', matching(integer, 1)), \
+      notEmpty('x')").unwrap();
+    expect!(definition.rules.len()).to(be_equal_to(2));
+
+    expect!(super::parse_matcher_def("matching(url, 'https://api.example.com', 'host')")).to(be_err());
+    expect!(super::parse_matcher_def("matching(url, 'https://api.example.com', 'host', 'api')")).to(be_err());
+    expect!(super::parse_matcher_def("matching(url, 'https://api.example.com', 'host', matching($'host'))")).to(be_err());
+    expect!(super::parse_matcher_def("matching(url)")).to(be_err());
+  }
+
+  #[test]
+  fn parse_numeric_range_matchers() {
+    let range = NumericRange { min: Some(NumericBound::Exclusive(0.0)), max: None };
+    expect!(super::parse_matcher_def("matching(greaterThan, 0, 100)").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("100".to_string(), ValueType::Integer,
+        MatchingRule::NumericRange(range), Some(Generator::RandomNumber(range)))));
+
+    let range = NumericRange { min: Some(NumericBound::Inclusive(0.0)), max: None };
+    expect!(super::parse_matcher_def("matching(greaterThanOrEqual, 0, 0)").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("0".to_string(), ValueType::Integer,
+        MatchingRule::NumericRange(range), Some(Generator::RandomNumber(range)))));
+
+    let range = NumericRange { min: None, max: Some(NumericBound::Exclusive(-10.5)) };
+    expect!(super::parse_matcher_def("matching(lessThan, -10.5, -20.25)").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("-20.25".to_string(), ValueType::Decimal,
+        MatchingRule::NumericRange(range), Some(Generator::RandomNumber(range)))));
+
+    let range = NumericRange { min: None, max: Some(NumericBound::Inclusive(100.0)) };
+    expect!(super::parse_matcher_def("matching(lessThanOrEqual, 100, 99.5)").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("99.5".to_string(), ValueType::Decimal,
+        MatchingRule::NumericRange(range), Some(Generator::RandomNumber(range)))));
+
+    let range = NumericRange { min: Some(NumericBound::Inclusive(1.0)), max: Some(NumericBound::Inclusive(65535.0)) };
+    expect!(super::parse_matcher_def("matching(range, 1, 65535, 8080)").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("8080".to_string(), ValueType::Integer,
+        MatchingRule::NumericRange(range), Some(Generator::RandomNumber(range)))));
+
+    expect!(super::parse_matcher_def("matching(greaterThan, 0, 5), matching(lessThan, 10, 5)").unwrap().rules.len())
+      .to(be_equal_to(2));
+
+    expect!(super::parse_matcher_def("matching(greaterThan, 0, 0)")).to(be_err());
+    expect!(super::parse_matcher_def("matching(range, 10, 1, 5)")).to(be_err());
+    expect!(super::parse_matcher_def("matching(range, 1, 10)")).to(be_err());
+    expect!(super::parse_matcher_def("matching(greaterThan, 'a', 10)")).to(be_err());
+    expect!(super::parse_matcher_def("matching(greaterThan, 0, '10')")).to(be_err());
+    expect!(super::parse_matcher_def("matching(greaterThan, 0)")).to(be_err());
+  }
+
+  #[test]
+  fn parse_any_of_test() {
+    expect!(super::parse_matcher_def("anyOf(matching(regex, '\\d+', '100'), matching(equalTo, ''))").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("100".to_string(), ValueType::String,
+        MatchingRule::AnyOf(vec![ Regex("\\d+".to_string()), MatchingRule::Equality ]), None)));
+    expect!(super::parse_matcher_def("anyOf(matching(equalTo, ''), matching(datetime, 'yyyy-MM-dd', '2000-01-01'))").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("2000-01-01".to_string(), ValueType::String,
+        MatchingRule::AnyOf(vec![ MatchingRule::Equality, MatchingRule::Timestamp("yyyy-MM-dd".to_string()) ]),
+        Some(DateTime(Some("yyyy-MM-dd".to_string()), None)))));
+    expect!(super::parse_matcher_def("anyOf(matching(integer, 1), matching(boolean, true), notEmpty('a'))").unwrap().rules).to(
+      be_equal_to(vec![ Either::Left(MatchingRule::AnyOf(vec![ MatchingRule::Integer, MatchingRule::Boolean, NotEmpty ])) ]));
+
+    expect!(super::parse_matcher_def("anyOf(matching(integer, 1))")).to(be_err());
+    expect!(super::parse_matcher_def("anyOf()")).to(be_err());
+    expect!(super::parse_matcher_def("anyOf(matching(integer, 1), matching($'ref'))")).to(be_err());
+    expect!(super::parse_matcher_def("anyOf(matching(integer, 1), matching(boolean, true)")).to(be_err());
+  }
+
+  #[test]
+  fn parse_not_test() {
+    expect!(super::parse_matcher_def("not(matching(equalTo, 'admin'))").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition::new("".to_string(), ValueType::Unknown,
+        MatchingRule::Not(Box::new(MatchingRule::Equality)), None)));
+    expect!(super::parse_matcher_def("matching(type, 'Fred'), not(matching(equalTo, 'admin'))").unwrap()).to(
+      be_equal_to(MatchingRuleDefinition {
+        value: "Fred".to_string(),
+        value_type: ValueType::String,
+        rules: vec![ Either::Left(Type), Either::Left(MatchingRule::Not(Box::new(MatchingRule::Equality))) ],
+        generator: None
+      }));
+    expect!(super::parse_matcher_def("not(anyOf(matching(equalTo, 'a'), matching(equalTo, 'b')))").unwrap().rules).to(
+      be_equal_to(vec![ Either::Left(MatchingRule::Not(Box::new(MatchingRule::AnyOf(vec![
+        MatchingRule::Equality, MatchingRule::Equality ])))) ]));
+
+    expect!(super::parse_matcher_def("not(matching($'ref'))")).to(be_err());
+    expect!(super::parse_matcher_def("not(matching(equalTo, 'a'), matching(equalTo, 'b'))")).to(be_err());
+    expect!(super::parse_matcher_def("not matching(equalTo, 'a')")).to(be_err());
+  }
+
+  #[test]
+  fn is_matcher_def_with_any_of_and_not() {
+    expect!(super::is_matcher_def("anyOf(matching(integer, 1), matching(boolean, true))")).to(be_true());
+    expect!(super::is_matcher_def("not(matching(equalTo, 'a'))")).to(be_true());
+    expect!(super::is_matcher_def("not a matcher")).to(be_false());
+    expect!(super::is_matcher_def("anyOf these")).to(be_false());
+  }
 }
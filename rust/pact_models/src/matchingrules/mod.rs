@@ -11,14 +11,19 @@ use anyhow::{anyhow, Context as _};
 use itertools::{Either, Itertools};
 use maplit::hashmap;
 use serde_json::{json, Map, Value};
-use tracing::{error, trace};
+use tracing::{error, trace, warn};
 
 use crate::{HttpStatus, PactSpecification};
 use crate::generators::{Generator, GeneratorCategory, Generators};
 use crate::json_utils::{json_to_num, json_to_string};
+use crate::matchingrules::builder::MatchingRulesBuilder;
+use crate::matchingrules::compatibility::normalise_matcher_json;
 use crate::matchingrules::expressions::{MatchingReference, MatchingRuleDefinition, ValueType};
 use crate::path_exp::{DocPath, PathToken};
 
+pub mod builder;
+pub mod compatibility;
+pub mod expectations;
 pub mod expressions;
 
 fn generator_from_json(json: &Map<String, Value>) -> Option<Generator> {
@@ -105,34 +110,271 @@ pub enum MatchingRule {
   /// Matcher for keys in a map
   EachKey(MatchingRuleDefinition),
   /// Matcher for values in a collection. This delegates to the Values matcher for maps.
-  EachValue(MatchingRuleDefinition)
+  EachValue(MatchingRuleDefinition),
+  /// Match binary data that is at least the given number of bytes
+  MinSize(usize),
+  /// Match binary data that is at most the given number of bytes
+  MaxSize(usize),
+  /// Match binary data that contains the given bytes at the offset
+  BytesEqual(usize, Vec<u8>),
+  /// Value must be an ISO-8601 duration (i.e. `PT5M30S`)
+  Duration,
+  /// Value must be an integer timestamp since the Unix epoch in the given unit. If a tolerance
+  /// is given, the value must also be within that many units of the current time.
+  EpochTimestamp(EpochUnit, Option<u64>),
+  /// Modifies any min type matchers defined for the same array so that additional elements at the
+  /// end of the actual array (past the number of expected elements) are allowed and ignored,
+  /// instead of having to match the first expected element.
+  AllowUnexpectedElements,
+  /// Array must contain the same elements as the expected array, but in any order. Each actual
+  /// element is assigned to the expected element it best matches.
+  EqualsIgnoreOrder,
+  /// Value must be an absolute URL. The components of the URL are matched using the rules, which
+  /// are keyed by the component (`$.scheme`, `$.host`, `$.port`, `$.path`, `$.query`, `$.query.<name>`
+  /// or `$.fragment`). Components without a rule must be equal to the expected value.
+  Url(MatchingRuleCategory),
+  /// Value must be a number within the range (i.e. `greaterThan`, `lessThan` or `range` in
+  /// matching rule expressions)
+  NumericRange(NumericRange),
+  /// Value must match at least one of the rules (`anyOf` in matching rule expressions)
+  AnyOf(Vec<MatchingRule>),
+  /// Value must not match the rule (`not` in matching rule expressions)
+  Not(Box<MatchingRule>),
+  /// Value must be present. This is used for partial body expectations, where the expected body
+  /// is described by a set of paths instead of an example body (`<path> exists`)
+  Exists,
+  /// Value must not be present (`<path> absent` in partial body expectations)
+  Absent
+}
+
+/// Units of an epoch timestamp
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EpochUnit {
+  /// Seconds since the Unix epoch
+  Seconds,
+  /// Milliseconds since the Unix epoch
+  Millis,
+  /// Microseconds since the Unix epoch
+  Micros,
+  /// Nanoseconds since the Unix epoch
+  Nanos
+}
+
+impl EpochUnit {
+  /// Returns the given duration since the Unix epoch in this unit
+  pub fn from_duration(&self, duration: std::time::Duration) -> u64 {
+    match self {
+      EpochUnit::Seconds => duration.as_secs(),
+      EpochUnit::Millis => duration.as_millis() as u64,
+      EpochUnit::Micros => duration.as_micros() as u64,
+      EpochUnit::Nanos => duration.as_nanos() as u64
+    }
+  }
+
+  /// Returns the current system time in this unit
+  pub fn now(&self) -> u64 {
+    let now = std::time::SystemTime::now()
+      .duration_since(std::time::UNIX_EPOCH)
+      .unwrap_or_default();
+    self.from_duration(now)
+  }
+}
+
+impl Display for EpochUnit {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      EpochUnit::Seconds => write!(f, "seconds"),
+      EpochUnit::Millis => write!(f, "millis"),
+      EpochUnit::Micros => write!(f, "micros"),
+      EpochUnit::Nanos => write!(f, "nanos")
+    }
+  }
+}
+
+impl Default for EpochUnit {
+  fn default() -> Self {
+    EpochUnit::Millis
+  }
+}
+
+impl FromStr for EpochUnit {
+  type Err = anyhow::Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "seconds" | "secs" | "s" => Ok(EpochUnit::Seconds),
+      "millis" | "milliseconds" | "ms" => Ok(EpochUnit::Millis),
+      "micros" | "microseconds" | "us" => Ok(EpochUnit::Micros),
+      "nanos" | "nanoseconds" | "ns" => Ok(EpochUnit::Nanos),
+      _ => Err(anyhow!("'{}' is not a valid epoch timestamp unit", s))
+    }
+  }
+}
+
+/// Bound of a numeric range
+#[derive(Debug, Clone, Copy)]
+pub enum NumericBound {
+  /// The bound is included in the range
+  Inclusive(f64),
+  /// The bound is excluded from the range
+  Exclusive(f64)
+}
+
+impl NumericBound {
+  /// Value of the bound
+  pub fn value(&self) -> f64 {
+    match self {
+      NumericBound::Inclusive(value) => *value,
+      NumericBound::Exclusive(value) => *value
+    }
+  }
+
+  /// If the bound is included in the range
+  pub fn is_inclusive(&self) -> bool {
+    matches!(self, NumericBound::Inclusive(_))
+  }
+}
+
+impl PartialEq for NumericBound {
+  fn eq(&self, other: &Self) -> bool {
+    self.is_inclusive() == other.is_inclusive() && self.value().to_bits() == other.value().to_bits()
+  }
+}
+
+impl Eq for NumericBound {}
+
+impl Hash for NumericBound {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.is_inclusive().hash(state);
+    self.value().to_bits().hash(state);
+  }
+}
+
+/// Range of numbers, with optional lower and upper bounds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct NumericRange {
+  /// Lower bound
+  pub min: Option<NumericBound>,
+  /// Upper bound
+  pub max: Option<NumericBound>
+}
+
+impl NumericRange {
+  /// Creates a range with the given bounds. Returns an error if there are no bounds, or the lower
+  /// bound is greater than the upper bound.
+  pub fn new(min: Option<NumericBound>, max: Option<NumericBound>) -> anyhow::Result<NumericRange> {
+    match (&min, &max) {
+      (None, None) => Err(anyhow!("A numeric range requires a minimum or maximum value")),
+      (Some(min_bound), Some(max_bound)) => {
+        let (min_value, max_value) = (min_bound.value(), max_bound.value());
+        if min_value > max_value || (min_value == max_value && !(min_bound.is_inclusive() && max_bound.is_inclusive())) {
+          Err(anyhow!("The numeric range {} does not contain any values", NumericRange { min, max }))
+        } else {
+          Ok(NumericRange { min, max })
+        }
+      }
+      _ => Ok(NumericRange { min, max })
+    }
+  }
+
+  /// If the value is within the range
+  pub fn contains(&self, value: f64) -> bool {
+    let above_min = match self.min {
+      Some(NumericBound::Inclusive(min)) => value >= min,
+      Some(NumericBound::Exclusive(min)) => value > min,
+      None => true
+    };
+    let below_max = match self.max {
+      Some(NumericBound::Inclusive(max)) => value <= max,
+      Some(NumericBound::Exclusive(max)) => value < max,
+      None => true
+    };
+    above_min && below_max
+  }
+
+  /// Parses the range from the attributes of a matching rule or generator. The bounds are
+  /// `minimum`/`exclusiveMinimum` and `maximum`/`exclusiveMaximum`.
+  pub fn from_map(attributes: &Map<String, Value>) -> anyhow::Result<NumericRange> {
+    let min = numeric_bound(attributes, "minimum", "exclusiveMinimum")?;
+    let max = numeric_bound(attributes, "maximum", "exclusiveMaximum")?;
+    NumericRange::new(min, max)
+  }
+
+  /// Returns the bounds of the range as matching rule or generator attributes
+  pub fn to_map(&self) -> HashMap<&'static str, Value> {
+    let mut map = hashmap!{};
+    match self.min {
+      Some(NumericBound::Inclusive(min)) => { map.insert("minimum", bound_to_json(min)); }
+      Some(NumericBound::Exclusive(min)) => { map.insert("exclusiveMinimum", bound_to_json(min)); }
+      None => {}
+    }
+    match self.max {
+      Some(NumericBound::Inclusive(max)) => { map.insert("maximum", bound_to_json(max)); }
+      Some(NumericBound::Exclusive(max)) => { map.insert("exclusiveMaximum", bound_to_json(max)); }
+      None => {}
+    }
+    map
+  }
+}
+
+/// Integral bounds are written as integers, so they round trip through the Pact file unchanged
+fn bound_to_json(value: f64) -> Value {
+  if value.fract() == 0.0 && value.abs() < 9_007_199_254_740_992.0 {
+    json!(value as i64)
+  } else {
+    json!(value)
+  }
+}
+
+fn numeric_bound(attributes: &Map<String, Value>, inclusive: &str, exclusive: &str) -> anyhow::Result<Option<NumericBound>> {
+  let bound_value = |field: &str| match attributes.get(field) {
+    Some(Value::Number(n)) => n.as_f64().map(Some)
+      .ok_or_else(|| anyhow!("Numeric range '{}' field is not a valid number", field)),
+    Some(Value::String(s)) => s.parse::<f64>().ok().filter(|n| n.is_finite()).map(Some)
+      .ok_or_else(|| anyhow!("Numeric range '{}' field is not a valid number", field)),
+    Some(value) => Err(anyhow!("Numeric range '{}' field is not a valid number - {}", field, value)),
+    None => Ok(None)
+  };
+  match (bound_value(inclusive)?, bound_value(exclusive)?) {
+    (Some(_), Some(_)) => Err(anyhow!("Numeric range can not have both '{}' and '{}' fields", inclusive, exclusive)),
+    (Some(value), None) => Ok(Some(NumericBound::Inclusive(value))),
+    (None, Some(value)) => Ok(Some(NumericBound::Exclusive(value))),
+    (None, None) => Ok(None)
+  }
+}
+
+impl Display for NumericRange {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let min = self.min.map(|bound| if bound.is_inclusive() {
+      format!(">= {}", bound.value())
+    } else {
+      format!("> {}", bound.value())
+    });
+    let max = self.max.map(|bound| if bound.is_inclusive() {
+      format!("<= {}", bound.value())
+    } else {
+      format!("< {}", bound.value())
+    });
+    write!(f, "{}", [min, max].iter().flatten().join(" and "))
+  }
 }
 
 impl MatchingRule {
 
-  /// Builds a `MatchingRule` from a `Value` struct
+  /// Builds a `MatchingRule` from a `Value` struct. Any known historical variants of the
+  /// matching rule JSON (see the `compatibility` module) are canonicalised first.
   pub fn from_json(value: &Value) -> anyhow::Result<MatchingRule> {
-    match value {
+    let (value, warnings) = normalise_matcher_json(value);
+    for warning in &warnings {
+      warn!("Matching rule {} - {}", value, warning);
+    }
+    match &value {
       Value::Object(m) => match m.get("match").or_else(|| m.get("pact:matcher:type")) {
         Some(match_val) => {
           let val = json_to_string(match_val);
-          MatchingRule::create(val.as_str(), value)
-        }
-        None => if let Some(val) = m.get("regex") {
-          Ok(MatchingRule::Regex(json_to_string(val)))
-        } else if let Some(val) = json_to_num(m.get("min").cloned()) {
-          Ok(MatchingRule::MinType(val))
-        } else if let Some(val) = json_to_num(m.get("max").cloned()) {
-          Ok(MatchingRule::MaxType(val))
-        } else if let Some(val) = m.get("timestamp") {
-          Ok(MatchingRule::Timestamp(json_to_string(val)))
-        } else if let Some(val) = m.get("time") {
-          Ok(MatchingRule::Time(json_to_string(val)))
-        } else if let Some(val) = m.get("date") {
-          Ok(MatchingRule::Date(json_to_string(val)))
-        } else {
-          Err(anyhow!("Matching rule missing 'match' field and unable to guess its type"))
+          MatchingRule::create(val.as_str(), &value)
         }
+        None => Err(anyhow!("Matching rule missing 'match' field and unable to guess its type"))
       },
       _ => Err(anyhow!("Matching rule JSON is not an Object")),
     }
@@ -231,6 +473,32 @@ impl MatchingRule {
 
         Value::Object(map.clone())
       }
+      MatchingRule::MinSize(min) => json!({ "match": "minSize", "min": json!(*min as u64) }),
+      MatchingRule::MaxSize(max) => json!({ "match": "maxSize", "max": json!(*max as u64) }),
+      MatchingRule::BytesEqual(offset, bytes) => json!({ "match": "bytesEqual",
+        "offset": json!(*offset as u64), "value": hex::encode(bytes) }),
+      MatchingRule::Duration => json!({ "match": "duration" }),
+      MatchingRule::EpochTimestamp(unit, tolerance) => if let Some(tolerance) = tolerance {
+        json!({ "match": "epoch", "unit": unit.to_string(), "tolerance": tolerance })
+      } else {
+        json!({ "match": "epoch", "unit": unit.to_string() })
+      },
+      MatchingRule::AllowUnexpectedElements => json!({ "match": "allowUnexpectedElements" }),
+      MatchingRule::EqualsIgnoreOrder => json!({ "match": "ignore-order" }),
+      MatchingRule::Url(rules) => json!({ "match": "url", "rules": rules.to_v3_json() }),
+      MatchingRule::NumericRange(range) => {
+        let mut json = json!({ "match": "range" });
+        let map = json.as_object_mut().unwrap();
+        for (key, value) in range.to_map() {
+          map.insert(key.to_string(), value);
+        }
+        json
+      }
+      MatchingRule::AnyOf(rules) => json!({ "match": "anyOf",
+        "rules": rules.iter().map(|rule| rule.to_json()).collect::<Vec<Value>>() }),
+      MatchingRule::Not(rule) => json!({ "match": "not", "rules": [ rule.to_json() ] }),
+      MatchingRule::Exists => json!({ "match": "exists" }),
+      MatchingRule::Absent => json!({ "match": "absent" })
     }
   }
 
@@ -276,7 +544,20 @@ impl MatchingRule {
       MatchingRule::NotEmpty => "not-empty",
       MatchingRule::Semver => "semver",
       MatchingRule::EachKey(_) => "each-key",
-      MatchingRule::EachValue(_) => "each-value"
+      MatchingRule::EachValue(_) => "each-value",
+      MatchingRule::MinSize(_) => "min-size",
+      MatchingRule::MaxSize(_) => "max-size",
+      MatchingRule::BytesEqual(_, _) => "bytes-equal",
+      MatchingRule::Duration => "duration",
+      MatchingRule::EpochTimestamp(_, _) => "epoch",
+      MatchingRule::AllowUnexpectedElements => "allow-unexpected-elements",
+      MatchingRule::EqualsIgnoreOrder => "ignore-order",
+      MatchingRule::Url(_) => "url",
+      MatchingRule::NumericRange(_) => "range",
+      MatchingRule::AnyOf(_) => "any-of",
+      MatchingRule::Not(_) => "not",
+      MatchingRule::Exists => "exists",
+      MatchingRule::Absent => "absent"
     }.to_string()
   }
 
@@ -328,6 +609,28 @@ impl MatchingRule {
 
         map
       }
+      MatchingRule::MinSize(min) => hashmap!{ "min" => json!(min) },
+      MatchingRule::MaxSize(max) => hashmap!{ "max" => json!(max) },
+      MatchingRule::BytesEqual(offset, bytes) => hashmap!{
+        "offset" => json!(offset),
+        "value" => Value::String(hex::encode(bytes))
+      },
+      MatchingRule::Duration => empty,
+      MatchingRule::EpochTimestamp(unit, tolerance) => if let Some(tolerance) = tolerance {
+        hashmap!{ "unit" => Value::String(unit.to_string()), "tolerance" => json!(tolerance) }
+      } else {
+        hashmap!{ "unit" => Value::String(unit.to_string()) }
+      },
+      MatchingRule::AllowUnexpectedElements => empty,
+      MatchingRule::EqualsIgnoreOrder => empty,
+      MatchingRule::Url(rules) => hashmap!{ "rules" => rules.to_v3_json() },
+      MatchingRule::NumericRange(range) => range.to_map(),
+      MatchingRule::AnyOf(rules) => hashmap!{
+        "rules" => Value::Array(rules.iter().map(|rule| rule.to_json()).collect())
+      },
+      MatchingRule::Not(rule) => hashmap!{ "rules" => Value::Array(vec![ rule.to_json() ]) },
+      MatchingRule::Exists => empty,
+      MatchingRule::Absent => empty
     }
   }
 
@@ -469,6 +772,75 @@ impl MatchingRule {
         };
         Ok(MatchingRule::EachValue(definition))
       }
+      "minSize" | "min-size" => match json_to_num(attributes.get("min").cloned()) {
+        Some(min) => Ok(MatchingRule::MinSize(min)),
+        None => Err(anyhow!("MinSize matcher missing 'min' field")),
+      },
+      "maxSize" | "max-size" => match json_to_num(attributes.get("max").cloned()) {
+        Some(max) => Ok(MatchingRule::MaxSize(max)),
+        None => Err(anyhow!("MaxSize matcher missing 'max' field")),
+      },
+      "bytesEqual" | "bytes-equal" => match attributes.get("value") {
+        Some(value) => {
+          let bytes = hex::decode(json_to_string(value))
+            .context("BytesEqual matcher 'value' field must be a hex encoded string")?;
+          let offset = json_to_num(attributes.get("offset").cloned()).unwrap_or_default();
+          Ok(MatchingRule::BytesEqual(offset, bytes))
+        },
+        None => Err(anyhow!("BytesEqual matcher missing 'value' field")),
+      },
+      "duration" => Ok(MatchingRule::Duration),
+      "epoch" | "epochTimestamp" | "epoch-timestamp" => {
+        let unit = match attributes.get("unit") {
+          Some(unit) => EpochUnit::from_str(json_to_string(unit).as_str())
+            .context("Epoch matcher 'unit' field is not valid")?,
+          None => EpochUnit::default()
+        };
+        let tolerance = json_to_num(attributes.get("tolerance").cloned()).map(|t| t as u64);
+        Ok(MatchingRule::EpochTimestamp(unit, tolerance))
+      },
+      "allowUnexpectedElements" | "allow-unexpected-elements" => Ok(MatchingRule::AllowUnexpectedElements),
+      "ignore-order" | "ignoreOrder" | "equalsIgnoringOrder" => Ok(MatchingRule::EqualsIgnoreOrder),
+      "url" => {
+        let mut category = MatchingRuleCategory::empty("body");
+        if let Some(rules) = attributes.get("rules") {
+          category.add_rules_from_json(rules)
+            .with_context(|| format!("Unable to parse URL component matching rules: {:?}", rules))?;
+        }
+        Ok(MatchingRule::Url(category))
+      }
+      "range" | "numericRange" => Ok(MatchingRule::NumericRange(NumericRange::from_map(&attributes)?)),
+      "greaterThan" | "greater-than" | "lessThan" | "less-than" => {
+        let value = attributes.get("value")
+          .ok_or_else(|| anyhow!("{} matcher missing 'value' field", rule_type))?;
+        let key = match (rule_type.starts_with("greater"), attributes.get("inclusive").and_then(Value::as_bool).unwrap_or(false)) {
+          (true, true) => "minimum",
+          (true, false) => "exclusiveMinimum",
+          (false, true) => "maximum",
+          (false, false) => "exclusiveMaximum"
+        };
+        let mut bounds = Map::new();
+        bounds.insert(key.to_string(), value.clone());
+        Ok(MatchingRule::NumericRange(NumericRange::from_map(&bounds)?))
+      }
+      "anyOf" | "any-of" => {
+        let rules = rules_from_json(&attributes)?;
+        if rules.is_empty() {
+          Err(anyhow!("AnyOf matcher requires at least one rule in the 'rules' field"))
+        } else {
+          Ok(MatchingRule::AnyOf(rules.into_iter().filter_map(|rule| rule.left()).collect()))
+        }
+      }
+      "not" => {
+        let mut rules = rules_from_json(&attributes)?;
+        if rules.len() == 1 {
+          Ok(MatchingRule::Not(Box::new(rules.remove(0).left().unwrap())))
+        } else {
+          Err(anyhow!("Not matcher requires a single rule in the 'rules' field, but got {}", rules.len()))
+        }
+      }
+      "exists" => Ok(MatchingRule::Exists),
+      "absent" => Ok(MatchingRule::Absent),
       _ => Err(anyhow!("{} is not a valid matching rule type", rule_type)),
     }
   }
@@ -488,6 +860,11 @@ impl MatchingRule {
       MatchingRule::Values => false,
       MatchingRule::EachValue(_) => false,
       MatchingRule::EachKey(_) => false,
+      MatchingRule::AllowUnexpectedElements => false,
+      MatchingRule::EqualsIgnoreOrder => false,
+      MatchingRule::Url(_) => false,
+      MatchingRule::Exists => false,
+      MatchingRule::Absent => false,
       _ => true
     }
   }
@@ -509,6 +886,16 @@ impl Hash for MatchingRule {
       MatchingRule::Date(format) => format.hash(state),
       MatchingRule::Include(str) => str.hash(state),
       MatchingRule::ContentType(str) => str.hash(state),
+      MatchingRule::MinSize(min) => min.hash(state),
+      MatchingRule::MaxSize(max) => max.hash(state),
+      MatchingRule::BytesEqual(offset, bytes) => {
+        offset.hash(state);
+        bytes.hash(state);
+      }
+      MatchingRule::EpochTimestamp(unit, tolerance) => {
+        unit.hash(state);
+        tolerance.hash(state);
+      }
       MatchingRule::ArrayContains(variants) => {
         for (index, rules, generators) in variants {
           index.hash(state);
@@ -519,6 +906,10 @@ impl Hash for MatchingRule {
           }
         }
       }
+      MatchingRule::Url(rules) => rules.hash(state),
+      MatchingRule::NumericRange(range) => range.hash(state),
+      MatchingRule::AnyOf(rules) => rules.hash(state),
+      MatchingRule::Not(rule) => rule.hash(state),
       _ => ()
     }
   }
@@ -537,6 +928,14 @@ impl PartialEq for MatchingRule {
       (MatchingRule::Include(str1), MatchingRule::Include(str2)) => str1 == str2,
       (MatchingRule::ContentType(str1), MatchingRule::ContentType(str2)) => str1 == str2,
       (MatchingRule::ArrayContains(variants1), MatchingRule::ArrayContains(variants2)) => variants1 == variants2,
+      (MatchingRule::Url(rules1), MatchingRule::Url(rules2)) => rules1 == rules2,
+      (MatchingRule::NumericRange(range1), MatchingRule::NumericRange(range2)) => range1 == range2,
+      (MatchingRule::AnyOf(rules1), MatchingRule::AnyOf(rules2)) => rules1 == rules2,
+      (MatchingRule::Not(rule1), MatchingRule::Not(rule2)) => rule1 == rule2,
+      (MatchingRule::MinSize(min1), MatchingRule::MinSize(min2)) => min1 == min2,
+      (MatchingRule::MaxSize(max1), MatchingRule::MaxSize(max2)) => max1 == max2,
+      (MatchingRule::BytesEqual(offset1, bytes1), MatchingRule::BytesEqual(offset2, bytes2)) => offset1 == offset2 && bytes1 == bytes2,
+      (MatchingRule::EpochTimestamp(unit1, tolerance1), MatchingRule::EpochTimestamp(unit2, tolerance2)) => unit1 == unit2 && tolerance1 == tolerance2,
       _ => mem::discriminant(self) == mem::discriminant(other)
     }
   }
@@ -696,7 +1095,9 @@ pub enum Category {
   /// Message contents (body)
   CONTENTS,
   /// Message metadata
-  METADATA
+  METADATA,
+  /// Response trailers (HTTP/2)
+  TRAILER
 }
 
 impl FromStr for Category {
@@ -712,6 +1113,7 @@ impl FromStr for Category {
       "status" => Ok(Category::STATUS),
       "contents" => Ok(Category::CONTENTS),
       "metadata" => Ok(Category::METADATA),
+      "trailer" => Ok(Category::TRAILER),
       _ => Err(format!("'{}' is not a valid Category", s))
     }
   }
@@ -727,7 +1129,8 @@ impl <'a> Into<&'a str> for Category {
       Category::BODY => "body",
       Category::STATUS => "status",
       Category::CONTENTS => "contents",
-      Category::METADATA => "metadata"
+      Category::METADATA => "metadata",
+      Category::TRAILER => "trailer"
     }
   }
 }
@@ -814,10 +1217,16 @@ impl MatchingRuleCategory {
   ) -> anyhow::Result<()> {
     let matching_rule = MatchingRule::from_json(matcher_json)
       .with_context(|| format!("Could not parse matcher JSON {:?}", matcher_json))?;
+    // Min type matchers can be marked with the allowUnexpectedElements attribute
+    let allow_unexpected_elements = matches!(matching_rule, MatchingRule::MinType(_) | MatchingRule::MinMaxType(_, _))
+      && matcher_json.get("allowUnexpectedElements").and_then(Value::as_bool).unwrap_or(false);
 
     let rules = self.rules.entry(key)
       .or_insert_with(|| RuleList::empty(rule_logic));
     rules.rules.push(matching_rule);
+    if allow_unexpected_elements && !rules.rules.contains(&MatchingRule::AllowUnexpectedElements) {
+      rules.rules.push(MatchingRule::AllowUnexpectedElements);
+    }
     Ok(())
   }
 
@@ -842,11 +1251,16 @@ impl MatchingRuleCategory {
     }
   }
 
-  fn max_by_path(&self, path: &[&str]) -> RuleList {
+  fn max_entry_by_path(&self, path: &[&str]) -> Option<(&DocPath, &RuleList, usize)> {
     self.rules.iter().map(|(k, v)| (k, v, k.path_weight(path)))
       .filter(|&(_, _, (w, _))| w > 0)
       .max_by_key(|&(_, _, (w, t))| w * t)
-      .map(|(_, v, (_, t))| v.as_cascaded(t != path.len()))
+      .map(|(k, v, (_, t))| (k, v, t))
+  }
+
+  fn max_by_path(&self, path: &[&str]) -> RuleList {
+    self.max_entry_by_path(path)
+      .map(|(_, v, t)| v.as_cascaded(t != path.len()))
       .unwrap_or_default()
   }
 
@@ -854,10 +1268,8 @@ impl MatchingRuleCategory {
   pub fn to_v3_json(&self) -> Value {
     Value::Object(self.rules.iter().fold(serde_json::Map::new(), |mut map, (category, rulelist)| {
       match self.name {
-        Category::HEADER | Category::QUERY => {
-          let name = category.first_field().map(|v| v.to_string())
-            .unwrap_or_else(|| category.to_string());
-          map.insert(name, rulelist.to_v3_json());
+        Category::HEADER | Category::QUERY | Category::TRAILER => {
+          map.insert(parameter_name(category), rulelist.to_v3_json());
         }
         _ => {
           map.insert(String::from(category), rulelist.to_v3_json());
@@ -878,7 +1290,7 @@ impl MatchingRuleCategory {
       Category::BODY => for (k, v) in self.rules.clone() {
         map.insert(String::from(k).replace("$", "$.body"), v.to_v2_json());
       }
-      Category::HEADER | Category::QUERY => for (k, v) in &self.rules {
+      Category::HEADER | Category::QUERY | Category::TRAILER => for (k, v) in &self.rules {
         let mut path = DocPath::root();
         path.push_field(self.name.to_string());
         path.push_path(k);
@@ -914,7 +1326,7 @@ impl MatchingRuleCategory {
   pub fn resolve_matchers_for_path(&self, path: &[&str]) -> MatchingRuleCategory {
     match self.name {
       Category::HEADER| Category::QUERY | Category::BODY |
-      Category::CONTENTS | Category::METADATA => self.filter(|(val, _)| {
+      Category::CONTENTS | Category::METADATA | Category::TRAILER => self.filter(|(val, _)| {
         val.matches_path(path)
       }),
       _ => self.clone()
@@ -925,10 +1337,23 @@ impl MatchingRuleCategory {
   pub fn select_best_matcher(&self, path: &[&str]) -> RuleList {
     match self.name {
       Category::BODY | Category::METADATA => self.max_by_path(path),
+      Category::HEADER | Category::QUERY | Category::TRAILER => self.max_entry_by_path(path)
+        .map(|(_, rules, _)| rules.clone())
+        .unwrap_or_default(),
       _ => self.resolve_matchers_for_path(path).as_rule_list()
     }
   }
 
+  /// Returns the path of the rules that would be selected by `select_best_matcher` for the given
+  /// path. This will be a parent of the given path if the rules have cascaded.
+  pub fn select_best_matcher_path(&self, path: &[&str]) -> Option<DocPath> {
+    match self.name {
+      Category::BODY | Category::METADATA | Category::HEADER | Category::QUERY | Category::TRAILER =>
+        self.max_entry_by_path(path).map(|(k, _, _)| k.clone()),
+      _ => self.resolve_matchers_for_path(path).rules.keys().next().cloned()
+    }
+  }
+
   /// Returns this category as a matching rule list. Returns a None if there are no rules
   pub fn as_rule_list(&self) -> RuleList {
     self.rules.values().next().cloned().unwrap_or_default()
@@ -985,11 +1410,9 @@ impl MatchingRuleCategory {
     } else if let Value::Object(m) = rules {
       if m.contains_key("matchers") {
         self.add_rule_list(DocPath::empty(), rules)?;
-      } else if self.name == Category::QUERY || self.name == Category::HEADER {
+      } else if self.name == Category::QUERY || self.name == Category::HEADER || self.name == Category::TRAILER {
         for (k, v) in m {
-          let mut path = DocPath::root();
-          path.push_field(k);
-          self.add_rule_list(path, v)?;
+          self.add_rule_list(parameter_path(k), v)?;
         }
       } else {
         for (k, v) in m {
@@ -1097,6 +1520,11 @@ pub struct MatchingRules {
 
 impl MatchingRules {
 
+  /// Returns a builder for constructing matching rules at runtime
+  pub fn builder() -> MatchingRulesBuilder {
+    MatchingRulesBuilder::new()
+  }
+
   /// If the matching rules are empty (that is there are no rules assigned to any categories)
   pub fn is_empty(&self) -> bool {
     self.rules.values().all(|category| category.is_empty())
@@ -1182,7 +1610,7 @@ impl MatchingRules {
       Category::BODY => self.rules_for_category(Category::BODY).map(|category| category.filter(|&(val, _)| {
         val.matches_path_exactly(path)
       })),
-      Category::HEADER | Category::QUERY => self.rules_for_category(category.clone()).map(|category| category.filter(|&(val, _)| {
+      Category::HEADER | Category::QUERY | Category::TRAILER => self.rules_for_category(category.clone()).map(|category| category.filter(|&(val, _)| {
         path.len() == 1 && Some(path[0]) == val.first_field()
       })),
       _ => self.rules_for_category(category)
@@ -1365,6 +1793,42 @@ pub fn matchers_to_json(matchers: &MatchingRules, spec_version: &PactSpecificati
   }
 }
 
+/// Returns the path for the name of a header, query parameter or trailer. The name can have an
+/// index (`ids[0]`) or wildcard index (`ids[*]`) suffix to select the individual values.
+pub(crate) fn parameter_path(name: &str) -> DocPath {
+  let mut path = DocPath::root();
+  let index = name.strip_suffix(']')
+    .and_then(|n| n.rsplit_once('['))
+    .filter(|(field, _)| !field.is_empty());
+  match index {
+    Some((field, "*")) => {
+      path.push_field(field).push_star_index();
+    }
+    Some((field, index)) => match index.parse::<usize>() {
+      Ok(index) => {
+        path.push_field(field).push_index(index);
+      }
+      Err(_) => {
+        path.push_field(name);
+      }
+    }
+    None => {
+      path.push_field(name);
+    }
+  }
+  path
+}
+
+/// Returns the name for the path of a header, query parameter or trailer. This is the reverse of
+/// `parameter_path`.
+pub(crate) fn parameter_name(path: &DocPath) -> String {
+  match path.tokens().as_slice() {
+    [PathToken::Root, PathToken::Field(name), PathToken::StarIndex] => format!("{}[*]", name),
+    [PathToken::Root, PathToken::Field(name), PathToken::Index(index)] => format!("{}[{}]", name, index),
+    _ => path.first_field().map(|v| v.to_string()).unwrap_or_else(|| path.to_string())
+  }
+}
+
 /// Macro to ease constructing matching rules
 /// Example usage:
 /// ```
@@ -1967,6 +2431,41 @@ mod tests {
     }));
   }
 
+  #[test]
+  fn loads_v3_matching_rules_supports_headers_and_query_parameters_with_index_wildcards() {
+    let matching_rules_json = json!({"matchingRules": {
+      "query": {
+        "ids": { "matchers": [ { "match": "type", "min": 1, "max": 3 } ] },
+        "ids[*]": { "matchers": [ { "match": "regex", "regex": "\\d+" } ] },
+        "ids[1]": { "matchers": [ { "match": "integer" } ] }
+      },
+      "header": {
+        "X-Id[*]": { "matchers": [ { "match": "regex", "regex": "\\d+" } ] }
+      }
+    }});
+
+    let matching_rules = matchers_from_json(&matching_rules_json, &None).unwrap();
+    let query = matching_rules.rules_for_category("query").unwrap();
+    expect!(query.rules.keys().cloned().collect::<HashSet<_>>()).to(be_equal_to(hashset!{
+      DocPath::root().join("ids"),
+      DocPath::root().join("ids").join("*"),
+      DocPath::root().join("ids").join("1")
+    }));
+    let header = matching_rules.rules_for_category("header").unwrap();
+    let mut header_path = DocPath::root().join("X-Id");
+    header_path.push_star_index();
+    expect!(header.rules.keys().next().cloned()).to(be_some().value(header_path));
+
+    expect!(query.select_best_matcher(&["$", "ids"]).rules).to(be_equal_to(vec![MatchingRule::MinMaxType(1, 3)]));
+    expect!(query.select_best_matcher(&["$", "ids", "0"]).rules).to(be_equal_to(vec![MatchingRule::Regex("\\d+".to_string())]));
+    expect!(query.select_best_matcher(&["$", "ids", "1"]).rules).to(be_equal_to(vec![MatchingRule::Integer]));
+
+    let json = matchers_to_json(&matching_rules, &PactSpecification::V3);
+    expect!(json["query"].as_object().unwrap().keys().cloned().collect::<HashSet<_>>())
+      .to(be_equal_to(hashset!{ "ids".to_string(), "ids[*]".to_string(), "ids[1]".to_string() }));
+    expect!(json["header"].get("X-Id[*]")).to(be_some());
+  }
+
   #[test]
   fn matching_rule_from_json_test() {
     expect!(MatchingRule::from_json(&Value::from_str("\"test string\"").unwrap())).to(be_err());
@@ -2143,6 +2642,167 @@ mod tests {
       be_ok().value(MatchingRule::Date("A".to_string())));
   }
 
+  #[test]
+  fn binary_matching_rules_from_and_to_json_test() {
+    expect!(MatchingRule::from_json(&json!({ "match": "minSize", "min": 1024 }))).to(
+      be_ok().value(MatchingRule::MinSize(1024)));
+    expect!(MatchingRule::from_json(&json!({ "match": "max-size", "max": 4096 }))).to(
+      be_ok().value(MatchingRule::MaxSize(4096)));
+    expect!(MatchingRule::from_json(&json!({ "match": "bytesEqual", "offset": 0, "value": "89504e47" }))).to(
+      be_ok().value(MatchingRule::BytesEqual(0, vec![0x89, 0x50, 0x4e, 0x47])));
+    expect!(MatchingRule::from_json(&json!({ "match": "bytesEqual", "offset": 0, "value": "zz" }))).to(be_err());
+
+    expect!(MatchingRule::MinSize(1024).to_json()).to(
+      be_equal_to(json!({ "match": "minSize", "min": 1024 })));
+    expect!(MatchingRule::MaxSize(4096).to_json()).to(
+      be_equal_to(json!({ "match": "maxSize", "max": 4096 })));
+    expect!(MatchingRule::BytesEqual(8, vec![0x49, 0x48, 0x44, 0x52]).to_json()).to(
+      be_equal_to(json!({ "match": "bytesEqual", "offset": 8, "value": "49484452" })));
+  }
+
+  #[test]
+  fn duration_and_epoch_matching_rules_from_and_to_json_test() {
+    expect!(MatchingRule::from_json(&json!({ "match": "duration" }))).to(
+      be_ok().value(MatchingRule::Duration));
+    expect!(MatchingRule::from_json(&json!({ "match": "epoch", "unit": "seconds" }))).to(
+      be_ok().value(MatchingRule::EpochTimestamp(EpochUnit::Seconds, None)));
+    expect!(MatchingRule::from_json(&json!({ "match": "epoch", "unit": "millis", "tolerance": 60000 }))).to(
+      be_ok().value(MatchingRule::EpochTimestamp(EpochUnit::Millis, Some(60000))));
+    expect!(MatchingRule::from_json(&json!({ "match": "epoch" }))).to(
+      be_ok().value(MatchingRule::EpochTimestamp(EpochUnit::Millis, None)));
+    expect!(MatchingRule::from_json(&json!({ "match": "epoch", "unit": "weeks" }))).to(be_err());
+
+    expect!(MatchingRule::Duration.to_json()).to(be_equal_to(json!({ "match": "duration" })));
+    expect!(MatchingRule::EpochTimestamp(EpochUnit::Nanos, None).to_json()).to(
+      be_equal_to(json!({ "match": "epoch", "unit": "nanos" })));
+    expect!(MatchingRule::EpochTimestamp(EpochUnit::Micros, Some(100)).to_json()).to(
+      be_equal_to(json!({ "match": "epoch", "unit": "micros", "tolerance": 100 })));
+  }
+
+  #[test]
+  fn allow_unexpected_elements_matching_rule_from_and_to_json_test() {
+    expect!(MatchingRule::from_json(&json!({ "match": "allowUnexpectedElements" }))).to(
+      be_ok().value(MatchingRule::AllowUnexpectedElements));
+    expect!(MatchingRule::AllowUnexpectedElements.to_json()).to(
+      be_equal_to(json!({ "match": "allowUnexpectedElements" })));
+
+    let mut category = MatchingRuleCategory::empty("body");
+    category.add_rules_from_json(&json!({
+      "$.a": { "matchers": [ { "match": "type", "min": 1, "allowUnexpectedElements": true } ] },
+      "$.b": { "matchers": [ { "match": "type", "min": 1, "allowUnexpectedElements": false } ] },
+      "$.c": { "matchers": [ { "match": "regex", "regex": "\\d+", "allowUnexpectedElements": true } ] }
+    })).unwrap();
+    expect!(category.rules.get(&DocPath::new_unwrap("$.a")).unwrap().rules.clone()).to(
+      be_equal_to(vec![ MatchingRule::MinType(1), MatchingRule::AllowUnexpectedElements ]));
+    expect!(category.rules.get(&DocPath::new_unwrap("$.b")).unwrap().rules.clone()).to(
+      be_equal_to(vec![ MatchingRule::MinType(1) ]));
+    expect!(category.rules.get(&DocPath::new_unwrap("$.c")).unwrap().rules.clone()).to(
+      be_equal_to(vec![ MatchingRule::Regex("\\d+".to_string()) ]));
+  }
+
+  #[test]
+  fn ignore_order_matching_rule_from_and_to_json_test() {
+    expect!(MatchingRule::from_json(&json!({ "match": "ignore-order" }))).to(
+      be_ok().value(MatchingRule::EqualsIgnoreOrder));
+    expect!(MatchingRule::from_json(&json!({ "match": "equalsIgnoringOrder" }))).to(
+      be_ok().value(MatchingRule::EqualsIgnoreOrder));
+    expect!(MatchingRule::EqualsIgnoreOrder.to_json()).to(
+      be_equal_to(json!({ "match": "ignore-order" })));
+  }
+
+  #[test]
+  fn url_matching_rule_from_and_to_json_test() {
+    let json = json!({
+      "match": "url",
+      "rules": {
+        "$.host": { "combine": "AND", "matchers": [ { "match": "regex", "regex": ".+\\.example\\.com" } ] },
+        "$.query.page": { "combine": "AND", "matchers": [ { "match": "integer" } ] }
+      }
+    });
+    let mut rules = MatchingRuleCategory::empty("body");
+    rules.add_rule(DocPath::new_unwrap("$.host"), MatchingRule::Regex(".+\\.example\\.com".to_string()), RuleLogic::And);
+    rules.add_rule(DocPath::new_unwrap("$.query.page"), MatchingRule::Integer, RuleLogic::And);
+
+    expect!(MatchingRule::from_json(&json)).to(be_ok().value(MatchingRule::Url(rules.clone())));
+    expect!(MatchingRule::Url(rules).to_json()).to(be_equal_to(json));
+    expect!(MatchingRule::from_json(&json!({ "match": "url" }))).to(
+      be_ok().value(MatchingRule::Url(MatchingRuleCategory::empty("body"))));
+  }
+
+  #[test]
+  fn numeric_range_matching_rule_from_and_to_json_test() {
+    let json = json!({ "match": "range", "minimum": 0, "exclusiveMaximum": 100.5 });
+    let range = NumericRange {
+      min: Some(NumericBound::Inclusive(0.0)),
+      max: Some(NumericBound::Exclusive(100.5))
+    };
+    expect!(MatchingRule::from_json(&json)).to(be_ok().value(MatchingRule::NumericRange(range)));
+    expect!(MatchingRule::NumericRange(range).to_json()).to(be_equal_to(json));
+
+    expect!(MatchingRule::from_json(&json!({ "match": "greaterThan", "value": 0 }))).to(
+      be_ok().value(MatchingRule::NumericRange(NumericRange { min: Some(NumericBound::Exclusive(0.0)), max: None })));
+    expect!(MatchingRule::from_json(&json!({ "match": "greaterThan", "value": 0, "inclusive": true }))).to(
+      be_ok().value(MatchingRule::NumericRange(NumericRange { min: Some(NumericBound::Inclusive(0.0)), max: None })));
+    expect!(MatchingRule::from_json(&json!({ "match": "lessThan", "value": "65536" }))).to(
+      be_ok().value(MatchingRule::NumericRange(NumericRange { min: None, max: Some(NumericBound::Exclusive(65536.0)) })));
+
+    expect!(MatchingRule::from_json(&json!({ "match": "range" }))).to(be_err());
+    expect!(MatchingRule::from_json(&json!({ "match": "range", "minimum": 10, "maximum": 1 }))).to(be_err());
+    expect!(MatchingRule::from_json(&json!({ "match": "range", "minimum": 1, "exclusiveMaximum": 1 }))).to(be_err());
+    expect!(MatchingRule::from_json(&json!({ "match": "range", "minimum": 1, "exclusiveMinimum": 1 }))).to(be_err());
+    expect!(MatchingRule::from_json(&json!({ "match": "range", "minimum": "one" }))).to(be_err());
+    expect!(MatchingRule::from_json(&json!({ "match": "lessThan" }))).to(be_err());
+  }
+
+  #[test]
+  fn any_of_and_not_matching_rules_from_and_to_json_test() {
+    let json = json!({
+      "match": "anyOf",
+      "rules": [ { "match": "regex", "regex": "\\d+" }, { "match": "equality" } ]
+    });
+    let rule = MatchingRule::AnyOf(vec![ MatchingRule::Regex("\\d+".to_string()), MatchingRule::Equality ]);
+    expect!(MatchingRule::from_json(&json)).to(be_ok().value(rule.clone()));
+    expect!(rule.to_json()).to(be_equal_to(json));
+
+    let json = json!({ "match": "not", "rules": [ { "match": "include", "value": "test" } ] });
+    let rule = MatchingRule::Not(Box::new(MatchingRule::Include("test".to_string())));
+    expect!(MatchingRule::from_json(&json)).to(be_ok().value(rule.clone()));
+    expect!(rule.to_json()).to(be_equal_to(json));
+
+    expect!(MatchingRule::from_json(&json!({ "match": "any-of", "rules": [ { "match": "null" } ] }))).to(
+      be_ok().value(MatchingRule::AnyOf(vec![ MatchingRule::Null ])));
+    expect!(MatchingRule::from_json(&json!({ "match": "anyOf" }))).to(be_err());
+    expect!(MatchingRule::from_json(&json!({ "match": "not", "rules": [] }))).to(be_err());
+    expect!(MatchingRule::from_json(&json!({ "match": "not", "rules": [ { "match": "null" }, { "match": "type" } ] }))).to(be_err());
+  }
+
+  #[test]
+  fn exists_and_absent_matching_rules_from_and_to_json_test() {
+    expect!(MatchingRule::from_json(&json!({ "match": "exists" }))).to(be_ok().value(MatchingRule::Exists));
+    expect!(MatchingRule::from_json(&json!({ "match": "absent" }))).to(be_ok().value(MatchingRule::Absent));
+    expect!(MatchingRule::Exists.to_json()).to(be_equal_to(json!({ "match": "exists" })));
+    expect!(MatchingRule::Absent.to_json()).to(be_equal_to(json!({ "match": "absent" })));
+    expect!(MatchingRule::Exists.can_cascade()).to(be_false());
+  }
+
+  #[test]
+  fn numeric_range_test() {
+    let range = NumericRange::new(Some(NumericBound::Inclusive(0.0)), Some(NumericBound::Exclusive(10.0))).unwrap();
+    expect!(range.contains(0.0)).to(be_true());
+    expect!(range.contains(9.99)).to(be_true());
+    expect!(range.contains(10.0)).to(be_false());
+    expect!(range.contains(-0.1)).to(be_false());
+    expect!(range.to_string()).to(be_equal_to(">= 0 and < 10"));
+
+    let range = NumericRange::new(Some(NumericBound::Exclusive(0.5)), None).unwrap();
+    expect!(range.contains(0.5)).to(be_false());
+    expect!(range.contains(1000000.0)).to(be_true());
+    expect!(range.to_string()).to(be_equal_to("> 0.5"));
+
+    expect!(NumericRange::new(Some(NumericBound::Inclusive(1.0)), Some(NumericBound::Inclusive(1.0)))).to(be_ok());
+    expect!(NumericRange::new(None, None)).to(be_err());
+  }
+
   #[test]
   fn matching_rule_to_json_test() {
     expect!(MatchingRule::StatusCode(HttpStatus::ClientError).to_json()).to(
@@ -2173,6 +2833,32 @@ mod tests {
       })));
   }
 
+  #[test]
+  fn select_best_matcher_path_returns_the_path_of_the_selected_rules() {
+    let matchers = matchingrules!{
+      "body" => {
+        "$" => [ MatchingRule::Regex("1".to_string()) ],
+        "$.item1" => [ MatchingRule::Regex("2".to_string()) ],
+        "$.item1.level[*].id" => [ MatchingRule::Regex("3".to_string()) ]
+      },
+      "header" => {
+        "item1" => [ MatchingRule::Regex("4".to_string()) ]
+      }
+    };
+    let body_matchers = matchers.rules_for_category("body").unwrap();
+    let header_matchers = matchers.rules_for_category("header").unwrap();
+
+    expect!(body_matchers.select_best_matcher_path(&["$"])).to(be_some().value(DocPath::root()));
+    expect!(body_matchers.select_best_matcher_path(&["$", "item2"])).to(be_some().value(DocPath::root()));
+    expect!(body_matchers.select_best_matcher_path(&["$", "item1", "level"]))
+      .to(be_some().value(DocPath::new_unwrap("$.item1")));
+    expect!(body_matchers.select_best_matcher_path(&["$", "item1", "level", "2", "id"]))
+      .to(be_some().value(DocPath::new_unwrap("$.item1.level[*].id")));
+    expect!(header_matchers.select_best_matcher_path(&["$", "item1"]))
+      .to(be_some().value(DocPath::new_unwrap("item1")));
+    expect!(header_matchers.select_best_matcher_path(&["$", "item2"])).to(be_none());
+  }
+
   #[test]
   fn matcher_is_defined_returns_false_when_there_are_no_matchers() {
     let matchers = matchingrules!{};
@@ -9,10 +9,13 @@ use std::ops::Deref;
 use std::panic::RefUnwindSafe;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 
 use anyhow::{anyhow, Context};
+use itertools::Itertools;
 use lazy_static::lazy_static;
 use maplit::btreemap;
+use serde::Serialize;
 use serde_json::{json, Value};
 use tracing::{debug, error, trace, warn};
 
@@ -20,12 +23,17 @@ use crate::{Consumer, PactSpecification, Provider};
 #[cfg(not(target_family = "wasm"))] use crate::file_utils::{with_read_lock_for_open_file, with_write_lock};
 #[cfg(not(target_family = "wasm"))] use crate::http_utils;
 #[cfg(not(target_family = "wasm"))] use crate::http_utils::HttpAuth;
-use crate::interaction::Interaction;
+use crate::interaction::{Interaction, interaction_type};
+use crate::json_utils::json_to_string;
 use crate::message_pact::MessagePact;
 use crate::plugins::PluginData;
 use crate::sync_pact::RequestResponsePact;
 use crate::v4;
+use crate::v4::async_message::AsynchronousMessage;
 use crate::v4::pact::V4Pact;
+use crate::v4::sync_message::SynchronousMessage;
+use crate::v4::synch_http::SynchronousHttp;
+use crate::v4::V4InteractionType;
 use crate::verify_json::{json_type_of, PactFileVerificationResult, ResultLevel};
 
 /// Trait for a Pact (request/response or message)
@@ -93,6 +101,39 @@ pub trait Pact: Debug + ReadWritePact {
 
   /// Adds some version info to the Pact-Rust metadata section
   fn add_md_version(&mut self, key: &str, version: &str);
+
+  /// Returns the interactions of the given type that match the predicate. V3 request/response
+  /// interactions are Synchronous/HTTP and V3 messages are Asynchronous/Messages.
+  fn interactions_matching(
+    &self,
+    kind: V4InteractionType,
+    predicate: &dyn Fn(&dyn Interaction) -> bool
+  ) -> Vec<Box<dyn Interaction + Send + Sync + RefUnwindSafe>> {
+    self.interactions().into_iter()
+      .filter(|interaction| interaction_type(interaction.as_ref()) == kind && predicate(interaction.as_ref()))
+      .collect()
+  }
+
+  /// Returns the HTTP interactions that match the predicate as V4 Synchronous/HTTP interactions
+  fn http_interactions(&self, predicate: &dyn Fn(&dyn Interaction) -> bool) -> Vec<SynchronousHttp> {
+    self.interactions_matching(V4InteractionType::Synchronous_HTTP, predicate).iter()
+      .filter_map(|interaction| interaction.as_v4_http())
+      .collect()
+  }
+
+  /// Returns the asynchronous message interactions that match the predicate as V4 interactions
+  fn async_message_interactions(&self, predicate: &dyn Fn(&dyn Interaction) -> bool) -> Vec<AsynchronousMessage> {
+    self.interactions_matching(V4InteractionType::Asynchronous_Messages, predicate).iter()
+      .filter_map(|interaction| interaction.as_v4_async_message())
+      .collect()
+  }
+
+  /// Returns the synchronous message interactions that match the predicate as V4 interactions
+  fn sync_message_interactions(&self, predicate: &dyn Fn(&dyn Interaction) -> bool) -> Vec<SynchronousMessage> {
+    self.interactions_matching(V4InteractionType::Synchronous_Messages, predicate).iter()
+      .filter_map(|interaction| interaction.as_v4_sync_message())
+      .collect()
+  }
 }
 
 impl Default for Box<dyn Pact> {
@@ -148,8 +189,7 @@ pub fn read_pact_from_file(file: &mut File, path: &Path) -> anyhow::Result<Box<d
     f.read_to_string(&mut buf)?;
     Ok(buf)
   })?;
-  let pact_json = serde_json::from_str(&buf)
-    .context("Failed to parse Pact JSON")
+  let pact_json = parse_pact_file_contents(path, &buf)
     .map_err(|err| {
       error!("read_pact_from_file: {}", err);
       debug!("read_pact_from_file: file contents = '{}'", buf);
@@ -159,6 +199,126 @@ pub fn read_pact_from_file(file: &mut File, path: &Path) -> anyhow::Result<Box<d
     .map_err(|e| anyhow!(e))
 }
 
+/// If the path has a YAML file extension (`.yaml` or `.yml`)
+pub fn is_yaml_file(path: &Path) -> bool {
+  path.extension()
+    .map(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"))
+    .unwrap_or(false)
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn parse_pact_file_contents(path: &Path, contents: &str) -> anyhow::Result<Value> {
+  if is_yaml_file(path) {
+    #[cfg(feature = "yaml")]
+    {
+      serde_yaml::from_str(contents).context("Failed to parse Pact YAML")
+    }
+    #[cfg(not(feature = "yaml"))]
+    {
+      Err(anyhow!("Reading YAML pact files requires the yaml feature to be enabled"))
+    }
+  } else {
+    serde_json::from_str(contents).context("Failed to parse Pact JSON")
+  }
+}
+
+#[cfg(not(target_family = "wasm"))]
+/// Options for how pact files are written out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WritePactOptions {
+  /// Sort the interactions by description, then provider states and then type. This makes the
+  /// order of the interactions independent of the order the tests were run in.
+  pub sort_interactions: bool,
+  /// Sort the keys of all the objects in the pact file (i.e. headers, matching rules, metadata).
+  pub sort_keys: bool,
+  /// Number of spaces to indent JSON pact files with. Zero will write the JSON on a single line.
+  /// This is ignored for YAML pact files.
+  pub indent: usize
+}
+
+impl Default for WritePactOptions {
+  fn default() -> Self {
+    WritePactOptions {
+      sort_interactions: false,
+      sort_keys: false,
+      indent: 2
+    }
+  }
+}
+
+impl WritePactOptions {
+  /// Options that write the pact file in a stable order, so that the pact file does not change
+  /// if the tests are run in a different order.
+  pub fn deterministic() -> Self {
+    WritePactOptions {
+      sort_interactions: true,
+      sort_keys: true,
+      .. WritePactOptions::default()
+    }
+  }
+}
+
+fn pact_file_contents(path: &Path, pact_json: &Value, options: &WritePactOptions) -> anyhow::Result<String> {
+  let mut pact_json = pact_json.clone();
+  if options.sort_interactions {
+    sort_interactions(&mut pact_json);
+  }
+  if options.sort_keys {
+    pact_json = sort_keys(&pact_json);
+  }
+
+  if is_yaml_file(path) {
+    #[cfg(feature = "yaml")]
+    {
+      serde_yaml::to_string(&pact_json).context("Failed to convert the Pact to YAML")
+    }
+    #[cfg(not(feature = "yaml"))]
+    {
+      Err(anyhow!("Writing YAML pact files requires the yaml feature to be enabled"))
+    }
+  } else if options.indent == 0 {
+    serde_json::to_string(&pact_json).context("Failed to convert the Pact to JSON")
+  } else {
+    let indent = " ".repeat(options.indent);
+    let mut buffer = vec![];
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+    pact_json.serialize(&mut serializer).context("Failed to convert the Pact to JSON")?;
+    String::from_utf8(buffer).context("Failed to convert the Pact to JSON")
+  }
+}
+
+fn sort_interactions(pact_json: &mut Value) {
+  for key in ["interactions", "messages"] {
+    if let Some(Value::Array(interactions)) = pact_json.get_mut(key) {
+      interactions.sort_by_cached_key(|interaction| {
+        let provider_states = match interaction.get("providerStates") {
+          Some(Value::Array(states)) => states.iter()
+            .map(|state| state.get("name").map(json_to_string).unwrap_or_default())
+            .collect(),
+          _ => interaction.get("providerState").map(|state| vec![json_to_string(state)]).unwrap_or_default()
+        };
+        (
+          interaction.get("description").map(json_to_string).unwrap_or_default(),
+          provider_states,
+          interaction.get("type").map(json_to_string).unwrap_or_default()
+        )
+      });
+    }
+  }
+}
+
+fn sort_keys(json: &Value) -> Value {
+  match json {
+    Value::Object(map) => Value::Object(map.iter()
+      .sorted_by(|(a, _), (b, _)| Ord::cmp(a, b))
+      .map(|(k, v)| (k.clone(), sort_keys(v)))
+      .collect()),
+    Value::Array(values) => Value::Array(values.iter().map(sort_keys).collect()),
+    _ => json.clone()
+  }
+}
+
 /// Reads the pact file from a URL and parses the resulting JSON into a `Pact` struct
 // TODO: For next major version, refactor this to also return any associated HAL links
 #[cfg(not(target_family = "wasm"))]
@@ -213,13 +373,28 @@ lazy_static!{
 
 /// Writes the pact out to the provided path. If there is an existing pact at the path, the two
 /// pacts will be merged together unless overwrite is true. Returns an error if the file can not
-/// be written or the pacts can not be merged.
+/// be written or the pacts can not be merged. If the path has a `.yaml` or `.yml` extension,
+/// the pact will be written as YAML (requires the `yaml` feature).
 #[cfg(not(target_family = "wasm"))]
 pub fn write_pact(
   pact: Box<dyn Pact>,
   path: &Path,
   pact_spec: PactSpecification,
   overwrite: bool
+) -> anyhow::Result<()> {
+  write_pact_with_options(pact, path, pact_spec, overwrite, &WritePactOptions::default())
+}
+
+/// Writes the pact out to the provided path, using the given options to control the order and
+/// formatting of the pact file. If there is an existing pact at the path, the two pacts will be
+/// merged together unless overwrite is true.
+#[cfg(not(target_family = "wasm"))]
+pub fn write_pact_with_options(
+  pact: Box<dyn Pact>,
+  path: &Path,
+  pact_spec: PactSpecification,
+  overwrite: bool,
+  options: &WritePactOptions
 ) -> anyhow::Result<()> {
   fs::create_dir_all(path.parent().unwrap())?;
   let _lock = WRITE_LOCK.lock().unwrap();
@@ -234,7 +409,7 @@ pub fn write_pact(
     }
 
     let merged_pact = pact.merge(existing_pact.deref())?;
-    let pact_json = serde_json::to_string_pretty(&merged_pact.to_json(pact_spec)?)?;
+    let pact_json = pact_file_contents(path, &merged_pact.to_json(pact_spec)?, options)?;
 
     with_write_lock(path, &mut f, 3, &mut |f| {
       f.set_len(0)?;
@@ -244,7 +419,7 @@ pub fn write_pact(
     })
   } else {
     debug!("Writing new pact file to {:?}", path);
-    let result = serde_json::to_string_pretty(&pact.to_json(pact_spec)?)?;
+    let result = pact_file_contents(path, &pact.to_json(pact_spec)?, options)?;
     let mut file = File::create(path)?;
     with_write_lock(path, &mut file, 3, &mut |f| {
       f.write_all(result.as_bytes())?;
@@ -376,6 +551,30 @@ pub fn determine_spec_version(file: &str, metadata: &BTreeMap<String, BTreeMap<S
   }
 }
 
+/// Key in the `pactRust` metadata section that stores the time the pact was created
+pub const CREATED_AT_METADATA_KEY: &str = "createdAt";
+
+/// Records the time the pact was created in the `pactRust` metadata section (as an RFC3339
+/// timestamp). This allows verifiers to detect pacts that have not been re-published recently.
+pub fn set_created_at(pact: &mut dyn Pact, time: SystemTime) {
+  let timestamp = humantime::format_rfc3339_seconds(time).to_string();
+  pact.add_md_version(CREATED_AT_METADATA_KEY, timestamp.as_str());
+}
+
+/// Returns the time the pact was created, if it has been recorded in the pact metadata. Invalid
+/// timestamps are ignored.
+pub fn created_at(pact: &dyn Pact) -> Option<SystemTime> {
+  let metadata = pact.metadata();
+  let timestamp = metadata.get("pactRust")?.get(CREATED_AT_METADATA_KEY)?;
+  match humantime::parse_rfc3339_weak(timestamp) {
+    Ok(time) => Some(time),
+    Err(err) => {
+      warn!("Ignoring invalid '{}' value '{}' in the pact metadata - {}", CREATED_AT_METADATA_KEY, timestamp, err);
+      None
+    }
+  }
+}
+
 pub(crate) fn verify_metadata(metadata: &Value, _spec_version: PactSpecification) -> Vec<PactFileVerificationResult> {
   let mut results = vec![];
 
@@ -432,6 +631,8 @@ mod tests {
   use std::{env, fs, io};
   use std::fs::File;
   use std::io::Read;
+  use std::path::Path;
+  use std::time::{Duration, UNIX_EPOCH};
 
   use expectest::prelude::*;
   use maplit::{btreemap, hashmap};
@@ -445,17 +646,30 @@ mod tests {
   use crate::content_types::JSON;
   use crate::generators;
   use crate::generators::Generator;
+  use crate::interaction::{any_interaction, with_description_matching, with_provider_state};
   use crate::matchingrules;
   use crate::matchingrules::MatchingRule;
-  use crate::pact::{Pact, ReadWritePact, write_pact};
+  use crate::pact::{
+    created_at,
+    load_pact_from_json,
+    Pact,
+    read_pact,
+    ReadWritePact,
+    set_created_at,
+    write_pact,
+    write_pact_with_options,
+    WritePactOptions
+  };
   use crate::PACT_RUST_VERSION;
   use crate::provider_states::ProviderState;
   use crate::request::Request;
   use crate::response::Response;
   use crate::sync_interaction::RequestResponseInteraction;
   use crate::sync_pact::RequestResponsePact;
+  use crate::v4::async_message::AsynchronousMessage;
   use crate::v4::pact::V4Pact;
   use crate::v4::synch_http::SynchronousHttp;
+  use crate::v4::V4InteractionType;
 
   #[test]
   fn load_empty_pact() {
@@ -931,6 +1145,154 @@ mod tests {
 }}"#, PACT_RUST_VERSION.unwrap()));
   }
 
+  #[test]
+  fn write_pact_with_options_test() {
+    let pact = RequestResponsePact { consumer: Consumer { name: "write_pact_options_consumer".to_string() },
+      provider: Provider { name: "write_pact_options_provider".to_string() },
+      interactions: vec![
+        RequestResponseInteraction {
+          description: "b interaction".to_string(),
+          .. RequestResponseInteraction::default()
+        },
+        RequestResponseInteraction {
+          description: "a interaction".to_string(),
+          provider_states: vec![ProviderState { name: "state 2".to_string(), params: hashmap!{} }],
+          .. RequestResponseInteraction::default()
+        },
+        RequestResponseInteraction {
+          description: "a interaction".to_string(),
+          provider_states: vec![ProviderState { name: "state 1".to_string(), params: hashmap!{} }],
+          .. RequestResponseInteraction::default()
+        }
+      ],
+      .. RequestResponsePact::default() };
+    let mut dir = env::temp_dir();
+    let x = rand::random::<u16>();
+    dir.push(format!("pact_test_{}", x));
+    dir.push(pact.default_file_name());
+
+    let options = WritePactOptions { indent: 4, .. WritePactOptions::deterministic() };
+    let result = write_pact_with_options(pact.boxed(), dir.as_path(), PactSpecification::V2, true, &options);
+
+    let pact_file = read_pact_file(dir.as_path().to_str().unwrap()).unwrap_or("".to_string());
+    fs::remove_dir_all(dir.parent().unwrap()).unwrap_or(());
+
+    expect!(result).to(be_ok());
+    assert_eq!(pact_file, format!(r#"{{
+    "consumer": {{
+        "name": "write_pact_options_consumer"
+    }},
+    "interactions": [
+        {{
+            "description": "a interaction",
+            "providerState": "state 1",
+            "request": {{
+                "method": "GET",
+                "path": "/"
+            }},
+            "response": {{
+                "status": 200
+            }}
+        }},
+        {{
+            "description": "a interaction",
+            "providerState": "state 2",
+            "request": {{
+                "method": "GET",
+                "path": "/"
+            }},
+            "response": {{
+                "status": 200
+            }}
+        }},
+        {{
+            "description": "b interaction",
+            "request": {{
+                "method": "GET",
+                "path": "/"
+            }},
+            "response": {{
+                "status": 200
+            }}
+        }}
+    ],
+    "metadata": {{
+        "pactRust": {{
+            "models": "{}"
+        }},
+        "pactSpecification": {{
+            "version": "2.0.0"
+        }}
+    }},
+    "provider": {{
+        "name": "write_pact_options_provider"
+    }}
+}}"#, PACT_RUST_VERSION.unwrap()));
+  }
+
+  #[test]
+  fn pact_file_contents_with_no_indent_test() {
+    let json = json!({ "b": 1, "a": { "d": [2, 1], "c": "x" } });
+    let options = WritePactOptions { indent: 0, .. WritePactOptions::deterministic() };
+    expect!(super::pact_file_contents(Path::new("pact.json"), &json, &options).unwrap())
+      .to(be_equal_to(r#"{"a":{"c":"x","d":[2,1]},"b":1}"#.to_string()));
+  }
+
+  #[test]
+  fn is_yaml_file_test() {
+    expect!(super::is_yaml_file(Path::new("pacts/consumer-provider.yaml"))).to(be_true());
+    expect!(super::is_yaml_file(Path::new("pacts/consumer-provider.YML"))).to(be_true());
+    expect!(super::is_yaml_file(Path::new("pacts/consumer-provider.json"))).to(be_false());
+    expect!(super::is_yaml_file(Path::new("pacts/yaml"))).to(be_false());
+  }
+
+  #[test]
+  #[cfg(feature = "yaml")]
+  fn write_and_read_yaml_pact_test() {
+    let pact = RequestResponsePact { consumer: Consumer { name: "write_yaml_pact_test_consumer".to_string() },
+      provider: Provider { name: "write_yaml_pact_test_provider".to_string() },
+      interactions: vec![
+        RequestResponseInteraction {
+          description: "Test Interaction".to_string(),
+          provider_states: vec![ProviderState { name: "Good state to be in".to_string(), params: hashmap!{} }],
+          .. RequestResponseInteraction::default()
+        }
+      ],
+      .. RequestResponsePact::default() };
+    let mut dir = env::temp_dir();
+    let x = rand::random::<u16>();
+    dir.push(format!("pact_test_{}", x));
+    dir.push("write_yaml_pact_test.yaml");
+
+    let result = write_pact(pact.boxed(), dir.as_path(), PactSpecification::V3, true);
+    let pact_file = read_pact_file(dir.as_path().to_str().unwrap()).unwrap_or_default();
+    let read_result = read_pact(dir.as_path());
+    fs::remove_dir_all(dir.parent().unwrap()).unwrap_or(());
+
+    expect!(result).to(be_ok());
+    expect!(pact_file.starts_with("consumer:\n  name: write_yaml_pact_test_consumer\n")).to(be_true());
+    let read_pact = read_result.unwrap();
+    expect!(read_pact.consumer().name).to(be_equal_to("write_yaml_pact_test_consumer"));
+    expect!(read_pact.interactions().len()).to(be_equal_to(1));
+    expect!(read_pact.interactions()[0].description()).to(be_equal_to("Test Interaction"));
+  }
+
+  #[test]
+  #[cfg(not(feature = "yaml"))]
+  fn read_yaml_pact_without_the_yaml_feature_test() {
+    let mut dir = env::temp_dir();
+    let x = rand::random::<u16>();
+    dir.push(format!("pact_test_{}", x));
+    fs::create_dir_all(&dir).unwrap();
+    dir.push("read_yaml_pact_test.yml");
+    fs::write(&dir, "consumer:\n  name: consumer\n").unwrap();
+
+    let result = read_pact(dir.as_path());
+    fs::remove_dir_all(dir.parent().unwrap()).unwrap_or(());
+
+    expect!(result).to(be_err());
+  }
+
   #[test]
   fn write_pact_test_should_merge_pacts() {
     let pact = RequestResponsePact { consumer: Consumer { name: "merge_consumer".to_string() },
@@ -1915,4 +2277,85 @@ mod tests {
     let merged_pact = pact.merge(&updated_pact);
     expect(merged_pact.unwrap().as_request_response_pact().unwrap()).to(be_equal_to(updated_pact));
   }
+
+  #[test]
+  fn interactions_matching_filters_by_interaction_type_and_predicate() {
+    let pact = V4Pact {
+      interactions: vec![
+        Box::new(SynchronousHttp {
+          description: "get a user".into(),
+          provider_states: vec![ProviderState { name: "a user exists".into(), params: hashmap! {} }],
+          .. Default::default()
+        }),
+        Box::new(SynchronousHttp {
+          description: "delete a user".into(),
+          .. Default::default()
+        }),
+        Box::new(AsynchronousMessage {
+          description: "user created event".into(),
+          provider_states: vec![ProviderState { name: "a user exists".into(), params: hashmap! {} }],
+          .. Default::default()
+        })
+      ],
+      .. V4Pact::default()
+    };
+
+    let interactions = pact.interactions_matching(V4InteractionType::Synchronous_HTTP, &any_interaction);
+    expect!(interactions.iter().map(|i| i.description()).collect::<Vec<_>>())
+      .to(be_equal_to(vec!["get a user".to_string(), "delete a user".to_string()]));
+    let interactions = pact.interactions_matching(V4InteractionType::Synchronous_HTTP, &with_provider_state("a user exists"));
+    expect!(interactions.iter().map(|i| i.description()).collect::<Vec<_>>())
+      .to(be_equal_to(vec!["get a user".to_string()]));
+    let interactions = pact.interactions_matching(V4InteractionType::Synchronous_Messages, &any_interaction);
+    expect!(interactions.iter()).to(be_empty());
+
+    let regex = regex::Regex::new("^delete").unwrap();
+    let http = pact.http_interactions(&with_description_matching(&regex));
+    expect!(http.iter().map(|i| i.description.clone()).collect::<Vec<_>>()).to(be_equal_to(vec!["delete a user".to_string()]));
+
+    let messages = pact.async_message_interactions(&with_provider_state("a user exists"));
+    expect!(messages.iter().map(|i| i.description.clone()).collect::<Vec<_>>()).to(be_equal_to(vec!["user created event".to_string()]));
+  }
+
+  #[test]
+  fn http_interactions_returns_v3_interactions_as_v4_interactions() {
+    let pact = RequestResponsePact {
+      interactions: vec![
+        RequestResponseInteraction {
+          description: "Test Interaction".into(),
+          ..RequestResponseInteraction::default()
+        }
+      ],
+      .. RequestResponsePact::default()
+    };
+
+    let interactions = pact.http_interactions(&any_interaction);
+    expect!(interactions.iter().map(|i| i.description.clone()).collect::<Vec<_>>()).to(be_equal_to(vec!["Test Interaction".to_string()]));
+    expect!(pact.async_message_interactions(&any_interaction).iter()).to(be_empty());
+  }
+
+  #[test]
+  fn created_at_is_stored_in_the_pact_metadata() {
+    let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+    let mut pact = RequestResponsePact::default();
+    expect!(created_at(&pact)).to(be_none());
+    set_created_at(&mut pact, time);
+    expect!(pact.metadata_to_json(&PactSpecification::V3)["pactRust"]["createdAt"].clone())
+      .to(be_equal_to(json!("2023-11-14T22:13:20Z")));
+    expect!(created_at(&pact)).to(be_some().value(time));
+
+    let mut pact = V4Pact::default();
+    set_created_at(&mut pact, time);
+    let json = pact.to_json(PactSpecification::V4).unwrap();
+    let pact = load_pact_from_json("test", &json).unwrap();
+    expect!(created_at(pact.as_ref())).to(be_some().value(time));
+  }
+
+  #[test]
+  fn created_at_ignores_invalid_timestamps() {
+    let mut pact = V4Pact::default();
+    pact.add_md_version("createdAt", "last week");
+    expect!(created_at(&pact)).to(be_none());
+  }
 }
@@ -0,0 +1,298 @@
+//! Estimates the serialised size of a Pact file and its interactions, so pact files can be
+//! checked against a size budget
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use itertools::Itertools;
+use serde_json::Value;
+
+use crate::json_utils::json_to_string;
+use crate::pact::Pact;
+
+/// Size of the bodies of an interaction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BodySize {
+  /// Size of the body contents in bytes
+  pub size: usize,
+  /// Size of the bodies when serialised in the Pact file, including any Base64 encoding of binary
+  /// bodies
+  pub serialised_size: usize
+}
+
+impl BodySize {
+  /// Number of bytes added to the Pact file by encoding the bodies (i.e. Base64 encoded binary
+  /// bodies and escaped strings)
+  pub fn encoding_overhead(&self) -> usize {
+    self.serialised_size.saturating_sub(self.size)
+  }
+
+  fn add(&mut self, other: BodySize) {
+    self.size += other.size;
+    self.serialised_size += other.serialised_size;
+  }
+}
+
+/// Serialised size of an interaction in a Pact file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InteractionSize {
+  /// Description of the interaction
+  pub description: String,
+  /// Type of the interaction
+  pub interaction_type: String,
+  /// Size of the interaction in bytes, when serialised without any whitespace
+  pub size: usize,
+  /// Size of all the bodies of the interaction (requests, responses and message contents)
+  pub body: BodySize
+}
+
+/// Serialised size of a Pact file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PactSize {
+  /// Name of the consumer
+  pub consumer: String,
+  /// Name of the provider
+  pub provider: String,
+  /// Size of the Pact file in bytes, when serialised without any whitespace
+  pub size: usize,
+  /// Size of the metadata section of the Pact file
+  pub metadata_size: usize,
+  /// Sizes of the interactions, in the order they are in the Pact file
+  pub interactions: Vec<InteractionSize>
+}
+
+impl PactSize {
+  /// Calculates the sizes from the JSON form of a Pact file
+  pub fn from_json(json: &Value) -> PactSize {
+    let v4 = json.pointer("/metadata/pactSpecification/version")
+      .map(|version| json_to_string(version).starts_with('4'))
+      .unwrap_or(false);
+    let interactions = ["interactions", "messages"].iter()
+      .flat_map(|key| match json.get(key) {
+        Some(Value::Array(interactions)) => interactions.iter()
+          .map(|interaction| interaction_size(interaction, key, v4))
+          .collect(),
+        _ => vec![]
+      })
+      .collect();
+    PactSize {
+      consumer: json.pointer("/consumer/name").map(json_to_string).unwrap_or_default(),
+      provider: json.pointer("/provider/name").map(json_to_string).unwrap_or_default(),
+      size: serialised_size(json),
+      metadata_size: json.get("metadata").map(serialised_size).unwrap_or_default(),
+      interactions
+    }
+  }
+
+  /// Total size of the bodies of all the interactions
+  pub fn body(&self) -> BodySize {
+    let mut body = BodySize::default();
+    for interaction in &self.interactions {
+      body.add(interaction.body);
+    }
+    body
+  }
+
+  /// Returns the `n` largest interactions, largest first
+  pub fn largest_interactions(&self, n: usize) -> Vec<&InteractionSize> {
+    self.interactions.iter()
+      .sorted_by(|a, b| b.size.cmp(&a.size))
+      .take(n)
+      .collect()
+  }
+}
+
+/// Calculates the sizes of the Pact when serialised in its specification version
+pub fn pact_size(pact: &dyn Pact) -> anyhow::Result<PactSize> {
+  let json = pact.to_json(pact.specification_version())?;
+  Ok(PactSize::from_json(&json))
+}
+
+fn serialised_size(json: &Value) -> usize {
+  json.to_string().len()
+}
+
+fn interaction_size(json: &Value, key: &str, v4: bool) -> InteractionSize {
+  let interaction_type = match json.get("type") {
+    Some(interaction_type) => json_to_string(interaction_type),
+    None => if key == "messages" {
+      "Asynchronous/Messages".to_string()
+    } else {
+      "Synchronous/HTTP".to_string()
+    }
+  };
+
+  let mut body = BodySize::default();
+  let bodies = [
+    json.pointer("/request/body"),
+    json.pointer("/response/body"),
+    json.get("contents"),
+    json.pointer("/request/contents")
+  ];
+  for value in bodies.iter().flatten() {
+    body.add(body_size(value, v4));
+  }
+  if let Some(Value::Array(responses)) = json.get("response") {
+    for value in responses.iter().filter_map(|response| response.get("contents")) {
+      body.add(body_size(value, v4));
+    }
+  }
+
+  InteractionSize {
+    description: json.get("description").map(json_to_string).unwrap_or_default(),
+    interaction_type,
+    size: serialised_size(json),
+    body
+  }
+}
+
+fn body_size(json: &Value, v4: bool) -> BodySize {
+  let (content, encoded) = if v4 {
+    match json.get("content") {
+      Some(content) => {
+        let encoded = match json.get("encoded") {
+          Some(Value::String(encoding)) => encoding.to_lowercase() == "base64",
+          Some(Value::Bool(encoded)) => *encoded,
+          _ => false
+        };
+        (content, encoded)
+      }
+      None => return BodySize::default()
+    }
+  } else {
+    (json, false)
+  };
+
+  let size = match content {
+    Value::Null => 0,
+    Value::String(s) => if encoded {
+      BASE64.decode(s).map(|bytes| bytes.len()).unwrap_or(s.len())
+    } else {
+      s.len()
+    },
+    _ => serialised_size(content)
+  };
+  let serialised_size = match content {
+    Value::Null => 0,
+    _ => serialised_size(content)
+  };
+  BodySize { size, serialised_size }
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+  use serde_json::json;
+
+  use crate::bodies::OptionalBody;
+  use crate::Consumer;
+  use crate::Provider;
+  use crate::request::Request;
+  use crate::sync_interaction::RequestResponseInteraction;
+  use crate::sync_pact::RequestResponsePact;
+
+  use super::*;
+
+  #[test]
+  fn calculates_the_size_of_a_v3_pact() {
+    let pact = json!({
+      "consumer": { "name": "consumer" },
+      "provider": { "name": "provider" },
+      "interactions": [
+        {
+          "description": "a small request",
+          "request": { "method": "GET", "path": "/" },
+          "response": { "status": 200, "body": "OK" }
+        },
+        {
+          "description": "a larger request",
+          "request": { "method": "POST", "path": "/", "body": { "a": [1, 2, 3] } },
+          "response": { "status": 200, "body": "a \"quoted\" string" }
+        }
+      ],
+      "metadata": { "pactSpecification": { "version": "3.0.0" } }
+    });
+
+    let size = PactSize::from_json(&pact);
+
+    expect!(size.consumer.as_str()).to(be_equal_to("consumer"));
+    expect!(size.provider.as_str()).to(be_equal_to("provider"));
+    expect!(size.size).to(be_equal_to(pact.to_string().len()));
+    expect!(size.metadata_size).to(be_equal_to(pact["metadata"].to_string().len()));
+    expect!(size.interactions.len()).to(be_equal_to(2));
+    expect!(size.interactions[0].interaction_type.as_str()).to(be_equal_to("Synchronous/HTTP"));
+    expect!(size.interactions[0].size).to(be_equal_to(pact["interactions"][0].to_string().len()));
+    expect!(size.interactions[0].body).to(be_equal_to(BodySize { size: 2, serialised_size: 4 }));
+    expect!(size.interactions[1].body).to(be_equal_to(BodySize { size: 13 + 17, serialised_size: 13 + 21 }));
+    expect!(size.body()).to(be_equal_to(BodySize { size: 32, serialised_size: 38 }));
+    expect!(size.largest_interactions(1).iter().map(|i| i.description.as_str()).collect::<Vec<_>>())
+      .to(be_equal_to(vec!["a larger request"]));
+  }
+
+  #[test]
+  fn includes_the_base64_overhead_of_binary_bodies_in_v4_pacts() {
+    let pact = json!({
+      "consumer": { "name": "consumer" },
+      "provider": { "name": "provider" },
+      "interactions": [
+        {
+          "type": "Synchronous/Messages",
+          "description": "a binary message",
+          "request": { "contents": { "content": "AAECAwQF", "contentType": "application/octet-stream", "encoded": "base64" } },
+          "response": [
+            { "contents": { "content": { "a": true }, "contentType": "application/json", "encoded": false } }
+          ]
+        }
+      ],
+      "metadata": { "pactSpecification": { "version": "4.0" } }
+    });
+
+    let size = PactSize::from_json(&pact);
+
+    expect!(size.interactions[0].interaction_type.as_str()).to(be_equal_to("Synchronous/Messages"));
+    expect!(size.interactions[0].body).to(be_equal_to(BodySize { size: 6 + 10, serialised_size: 10 + 10 }));
+    expect!(size.interactions[0].body.encoding_overhead()).to(be_equal_to(4));
+  }
+
+  #[test]
+  fn message_pacts_default_to_asynchronous_messages() {
+    let pact = json!({
+      "consumer": { "name": "consumer" },
+      "provider": { "name": "provider" },
+      "messages": [
+        { "description": "a message", "contents": { "a": 1 }, "metadata": {} }
+      ],
+      "metadata": { "pactSpecification": { "version": "3.0.0" } }
+    });
+
+    let size = PactSize::from_json(&pact);
+
+    expect!(size.interactions[0].interaction_type.as_str()).to(be_equal_to("Asynchronous/Messages"));
+    expect!(size.interactions[0].body).to(be_equal_to(BodySize { size: 7, serialised_size: 7 }));
+  }
+
+  #[test]
+  fn calculates_the_size_of_a_pact_model() {
+    let pact = RequestResponsePact {
+      consumer: Consumer { name: "consumer".to_string() },
+      provider: Provider { name: "provider".to_string() },
+      interactions: vec![
+        RequestResponseInteraction {
+          description: "a request".to_string(),
+          request: Request {
+            body: OptionalBody::Present("some text".into(), None, None),
+            .. Request::default()
+          },
+          .. RequestResponseInteraction::default()
+        }
+      ],
+      .. RequestResponsePact::default()
+    };
+
+    let size = pact_size(&pact).unwrap();
+
+    expect!(size.interactions.len()).to(be_equal_to(1));
+    expect!(size.interactions[0].description.as_str()).to(be_equal_to("a request"));
+    expect!(size.interactions[0].body).to(be_equal_to(BodySize { size: 9, serialised_size: 11 }));
+    expect!(size.size).to(be_equal_to(pact.to_json(pact.specification_version()).unwrap().to_string().len()));
+  }
+}
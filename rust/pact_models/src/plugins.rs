@@ -4,6 +4,7 @@ use std::collections::HashMap;
 
 use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
 use crate::json_utils::json_deep_merge;
@@ -23,14 +24,7 @@ pub struct PluginData {
 impl PluginData {
   /// Deep merges the data with any existing data
   pub fn merge(&mut self, data: &HashMap<String, Value>) {
-    for (key, value) in data {
-      let value = if let Some(v) = self.configuration.get(key) {
-        json_deep_merge(v, value)
-      } else {
-        value.clone()
-      };
-      self.configuration.insert(key.clone(), value);
-    }
+    merge_configuration(&mut self.configuration, data);
   }
 }
 
@@ -41,3 +35,70 @@ impl PluginData {
       .map_err(|err| anyhow!("Could not convert plugin data to JSON - {}", err))
   }
 }
+
+/// Configuration added to an interaction by a plugin. This is persisted in the
+/// `pluginConfiguration` section of V4 interactions, keyed by the plugin name.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InteractionPluginConfig {
+  /// Name of the plugin that added the configuration
+  pub plugin_name: String,
+  /// Configuration values, keyed by the configuration key
+  pub configuration: HashMap<String, Value>
+}
+
+impl InteractionPluginConfig {
+  /// Creates the configuration for the given plugin
+  pub fn new(plugin_name: &str, configuration: HashMap<String, Value>) -> Self {
+    InteractionPluginConfig {
+      plugin_name: plugin_name.to_string(),
+      configuration
+    }
+  }
+
+  /// If there are no configuration values
+  pub fn is_empty(&self) -> bool {
+    self.configuration.is_empty()
+  }
+
+  /// Returns the configuration value for the key
+  pub fn get(&self, key: &str) -> Option<&Value> {
+    self.configuration.get(key)
+  }
+
+  /// Deserialises the configuration value for the key. Returns `Ok(None)` if there is no value
+  /// for the key, and an error if the value can not be deserialised to the type.
+  pub fn get_as<T: DeserializeOwned>(&self, key: &str) -> anyhow::Result<Option<T>> {
+    self.configuration.get(key)
+      .map(|value| serde_json::from_value(value.clone())
+        .map_err(|err| anyhow!("Configuration '{}' for plugin '{}' is not in the expected format - {}",
+          key, self.plugin_name, err)))
+      .transpose()
+  }
+
+  /// Deserialises all the configuration values as a single object
+  pub fn deserialise<T: DeserializeOwned>(&self) -> anyhow::Result<T> {
+    let json = Value::Object(self.configuration.iter()
+      .map(|(key, value)| (key.clone(), value.clone()))
+      .collect());
+    serde_json::from_value(json)
+      .map_err(|err| anyhow!("Configuration for plugin '{}' is not in the expected format - {}",
+        self.plugin_name, err))
+  }
+
+  /// Deep merges the configuration with any existing configuration. Objects are merged, arrays are
+  /// appended to and any other value replaces the existing one.
+  pub fn merge(&mut self, configuration: &HashMap<String, Value>) {
+    merge_configuration(&mut self.configuration, configuration);
+  }
+}
+
+pub(crate) fn merge_configuration(configuration: &mut HashMap<String, Value>, data: &HashMap<String, Value>) {
+  for (key, value) in data {
+    let value = if let Some(v) = configuration.get(key) {
+      json_deep_merge(v, value)
+    } else {
+      value.clone()
+    };
+    configuration.insert(key.clone(), value);
+  }
+}
@@ -95,6 +95,23 @@ impl ProviderState {
     value
   }
 
+  /// Generate the JSON schema properties for the given Pact specification
+  pub fn schema(_spec_version: PactSpecification) -> Value {
+    json!({
+      "type": "object",
+      "properties": {
+        "name": {
+          "type": "string"
+        },
+        "params": {
+          "description": "Parameters for the provider state",
+          "type": "object"
+        }
+      },
+      "required": ["name"]
+    })
+  }
+
 }
 
 impl Hash for ProviderState {
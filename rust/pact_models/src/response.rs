@@ -127,7 +127,8 @@ impl Response {
       headers: self.headers.clone(),
       body: self.body.clone(),
       matching_rules: self.matching_rules.clone(),
-      generators: self.generators.clone()
+      generators: self.generators.clone(),
+      trailers: None
     }
   }
 }
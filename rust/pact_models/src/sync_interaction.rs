@@ -334,6 +334,7 @@ mod tests {
 
   use crate::bodies::OptionalBody;
   use crate::generators::{GeneratorCategory, Generators, Generator};
+  use crate::interaction::Interaction;
   use crate::matchingrules::{Category, MatchingRule, MatchingRuleCategory, MatchingRules, RuleLogic, RuleList};
   use crate::PactSpecification;
   use crate::provider_states::ProviderState;
@@ -545,4 +546,47 @@ mod tests {
     let generators = request.get("generators").unwrap();
     expect!(generators.to_string()).to_not(be_equal_to("{}"));
   }
+
+  #[test]
+  fn content_eq_ignores_ids_and_keys() {
+    let interaction = RequestResponseInteraction {
+      id: Some("1".to_string()),
+      description: "a request".to_string(),
+      response: Response { status: 201, .. Response::default() },
+      .. RequestResponseInteraction::default()
+    };
+    let interaction2 = RequestResponseInteraction { id: Some("2".to_string()), .. interaction.clone() };
+    let mut v4_interaction = interaction.as_v4_http().unwrap();
+    v4_interaction.key = Some("1234".to_string());
+    let different = RequestResponseInteraction { description: "another request".to_string(), .. interaction.clone() };
+
+    expect!(interaction.content_eq(&interaction2)).to(be_true());
+    expect!(interaction.content_hash()).to(be_equal_to(interaction2.content_hash()));
+    expect!(interaction.content_eq(&v4_interaction)).to(be_true());
+    expect!(interaction.content_hash()).to(be_equal_to(v4_interaction.content_hash()));
+    expect!(interaction.content_eq(&different)).to(be_false());
+    expect!(interaction.content_hash()).to_not(be_equal_to(different.content_hash()));
+  }
+
+  #[test]
+  fn fingerprint_is_calculated_from_the_content() {
+    let interaction = RequestResponseInteraction {
+      id: Some("1".to_string()),
+      description: "a request".to_string(),
+      response: Response { status: 201, .. Response::default() },
+      .. RequestResponseInteraction::default()
+    };
+    let mut v4_interaction = interaction.as_v4_http().unwrap();
+    v4_interaction.key = Some("1234".to_string());
+    v4_interaction.comments.insert("text".to_string(), json!(["a comment"]));
+    let different = RequestResponseInteraction { description: "another request".to_string(), .. interaction.clone() };
+    let mut with_config = v4_interaction.clone();
+    with_config.config.insert("contentMatcher".to_string(), json!("application/json"));
+
+    expect!(interaction.fingerprint()).to(be_some().value("b1d9c8345dbc7c2f".to_string()));
+    expect!(v4_interaction.fingerprint()).to(be_equal_to(interaction.fingerprint()));
+    expect!(different.fingerprint()).to_not(be_equal_to(interaction.fingerprint()));
+    expect!(with_config.content_eq(&v4_interaction)).to(be_false());
+    expect!(with_config.fingerprint()).to_not(be_equal_to(v4_interaction.fingerprint()));
+  }
 }
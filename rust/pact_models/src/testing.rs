@@ -0,0 +1,764 @@
+//! Implementations of the `arbitrary::Arbitrary` trait for the core Pact models, so they can be
+//! generated by fuzzers and property based tests. This module requires the `testing` feature.
+//!
+//! The generated values are restricted to ones that can be represented in a Pact file, so
+//! converting a generated value to JSON and back again will result in an equivalent value.
+
+use std::collections::HashMap;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use itertools::Either;
+use serde_json::{json, Value};
+
+use crate::bodies::OptionalBody;
+use crate::content_types::{ContentType, JSON, TEXT};
+use crate::expression_parser::DataType;
+use crate::generators::{Generator, GeneratorCategory, Generators, UuidFormat};
+use crate::HttpStatus;
+use crate::matchingrules::{
+  Category,
+  EpochUnit,
+  MatchingRule,
+  MatchingRuleCategory,
+  MatchingRules,
+  NumericBound,
+  NumericRange,
+  RuleList,
+  RuleLogic
+};
+use crate::matchingrules::expressions::{MatchingRuleDefinition, ValueType};
+use crate::message::Message;
+use crate::path_exp::DocPath;
+use crate::provider_states::ProviderState;
+use crate::request::Request;
+use crate::response::Response;
+use crate::v4::async_message::AsynchronousMessage;
+use crate::v4::http_parts::{HttpRequest, HttpResponse};
+use crate::v4::message_parts::MessageContents;
+use crate::v4::sync_message::SynchronousMessage;
+use crate::v4::synch_http::SynchronousHttp;
+
+/// How deep nested matching rules (i.e. eachKey, eachValue, url and arrayContains) and JSON
+/// values are allowed to go
+const MAX_DEPTH: usize = 2;
+
+const IDENTIFIER_START: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const IDENTIFIER_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789_";
+const TOKEN_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-._~";
+const HTTP_METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+const URL_COMPONENTS: &[&str] = &["scheme", "host", "port", "path", "query", "fragment"];
+
+/// Generates a collection of up to `max` items using the provided function
+fn collection<'a, T, F>(u: &mut Unstructured<'a>, max: usize, mut f: F) -> Result<Vec<T>>
+  where F: FnMut(&mut Unstructured<'a>) -> Result<T> {
+  let len = u.int_in_range(0..=max)?;
+  (0..len).map(|_| f(u)).collect()
+}
+
+/// Generates a lowercase identifier, used for field names, parameter names and keys
+fn identifier(u: &mut Unstructured) -> Result<String> {
+  let len = u.int_in_range(1..=8)?;
+  let mut s = String::with_capacity(len);
+  s.push(*u.choose(IDENTIFIER_START)? as char);
+  for _ in 1..len {
+    s.push(*u.choose(IDENTIFIER_CHARS)? as char);
+  }
+  Ok(s)
+}
+
+/// Generates a non-empty token. Tokens contain no whitespace or commas, so they can be used as
+/// header values.
+fn token(u: &mut Unstructured) -> Result<String> {
+  let len = u.int_in_range(1..=12)?;
+  (0..len).map(|_| u.choose(TOKEN_CHARS).map(|ch| *ch as char)).collect()
+}
+
+fn json_value(u: &mut Unstructured, depth: usize) -> Result<Value> {
+  let max = if depth == 0 { 3 } else { 5 };
+  Ok(match u.int_in_range(0..=max)? {
+    0 => Value::Null,
+    1 => Value::Bool(u.arbitrary()?),
+    2 => json!(u.arbitrary::<i64>()?),
+    3 => Value::String(u.arbitrary()?),
+    4 => Value::Array(collection(u, 3, |u| json_value(u, depth - 1))?),
+    _ => json_object(u, depth - 1)?
+  })
+}
+
+fn json_object(u: &mut Unstructured, depth: usize) -> Result<Value> {
+  Ok(Value::Object(collection(u, 3, |u| Ok((u.arbitrary()?, json_value(u, depth)?)))?
+    .into_iter()
+    .collect()))
+}
+
+fn json_map(u: &mut Unstructured) -> Result<HashMap<String, Value>> {
+  Ok(collection(u, 3, |u| Ok((identifier(u)?, json_value(u, MAX_DEPTH)?)))?
+    .into_iter()
+    .collect())
+}
+
+/// Generates a path into a JSON document (i.e. `$.a[1].*`)
+fn body_path(u: &mut Unstructured) -> Result<DocPath> {
+  let mut path = DocPath::root();
+  for _ in 0..u.int_in_range(0..=4)? {
+    match u.int_in_range(0..=4)? {
+      0 => path.push_index(u.int_in_range(0..=10)?),
+      1 => path.push_star(),
+      2 => path.push_star_index(),
+      _ => path.push_field(identifier(u)?)
+    };
+  }
+  Ok(path)
+}
+
+/// Generates a path to a named item, like a header or query parameter
+fn named_path(name: String) -> DocPath {
+  DocPath::root().push_field(name).clone()
+}
+
+fn header_name(u: &mut Unstructured) -> Result<String> {
+  Ok(format!("x-{}", identifier(u)?))
+}
+
+/// Generates a body along with its content type. Null bodies can not be represented in the V4
+/// format, so are only generated if `allow_null` is set.
+fn body(u: &mut Unstructured, allow_null: bool) -> Result<(OptionalBody, Option<ContentType>)> {
+  let max = if allow_null { 4 } else { 3 };
+  Ok(match u.int_in_range(0..=max)? {
+    0 => (OptionalBody::Missing, None),
+    1 => (OptionalBody::Empty, None),
+    2 => {
+      let text = format!("{}{}", token(u)?, u.arbitrary::<String>()?);
+      (OptionalBody::Present(text.into(), Some(TEXT.clone()), None), Some(TEXT.clone()))
+    },
+    3 => {
+      let json = if u.arbitrary()? {
+        json_object(u, MAX_DEPTH)?
+      } else {
+        Value::Array(collection(u, 3, |u| json_value(u, MAX_DEPTH))?)
+      };
+      (OptionalBody::Present(json.to_string().into(), Some(JSON.clone()), None), Some(JSON.clone()))
+    },
+    _ => (OptionalBody::Null, None)
+  })
+}
+
+/// Generates the headers for an HTTP part, including a content type header if the body has
+/// a content type
+fn headers(u: &mut Unstructured, content_type: &Option<ContentType>) -> Result<Option<HashMap<String, Vec<String>>>> {
+  let mut headers: HashMap<String, Vec<String>> = collection(u, 3, |u| {
+    let values = collection(u, 2, token)?;
+    let first = token(u)?;
+    Ok((header_name(u)?, [vec![first], values].concat()))
+  })?.into_iter().collect();
+  if let Some(content_type) = content_type {
+    headers.insert("Content-Type".to_string(), vec![content_type.to_string()]);
+  }
+  if headers.is_empty() && u.arbitrary()? {
+    Ok(None)
+  } else {
+    Ok(Some(headers))
+  }
+}
+
+fn query(u: &mut Unstructured) -> Result<Option<HashMap<String, Vec<Option<String>>>>> {
+  let query: HashMap<String, Vec<Option<String>>> = collection(u, 3, |u| {
+    let values = collection(u, 2, |u| if u.arbitrary()? {
+      u.arbitrary().map(Some)
+    } else {
+      Ok(None)
+    })?;
+    let first = u.arbitrary()?;
+    Ok((identifier(u)?, [vec![Some(first)], values].concat()))
+  })?.into_iter().collect();
+  if query.is_empty() {
+    Ok(None)
+  } else {
+    Ok(Some(query))
+  }
+}
+
+fn path(u: &mut Unstructured) -> Result<String> {
+  let segments = collection(u, 3, token)?;
+  Ok(format!("/{}", segments.join("/")))
+}
+
+fn epoch_unit(u: &mut Unstructured) -> Result<EpochUnit> {
+  u.choose(&[EpochUnit::Seconds, EpochUnit::Millis, EpochUnit::Micros, EpochUnit::Nanos]).cloned()
+}
+
+/// Bounds are multiples of a quarter, so that they can be written to JSON and read back exactly
+fn numeric_range(u: &mut Unstructured) -> Result<NumericRange> {
+  let min = u.int_in_range(-4000..=4000)? as f64 / 4.0;
+  let max = min + u.int_in_range(1..=4000)? as f64 / 4.0;
+  let bound = |u: &mut Unstructured, value: f64| -> Result<NumericBound> {
+    Ok(if u.arbitrary()? { NumericBound::Inclusive(value) } else { NumericBound::Exclusive(value) })
+  };
+  Ok(match u.int_in_range(0..=2)? {
+    0 => NumericRange { min: Some(bound(u, min)?), max: None },
+    1 => NumericRange { min: None, max: Some(bound(u, max)?) },
+    _ => NumericRange { min: Some(bound(u, min)?), max: Some(bound(u, max)?) }
+  })
+}
+
+fn http_status(u: &mut Unstructured) -> Result<HttpStatus> {
+  Ok(match u.int_in_range(0..=8)? {
+    0 => HttpStatus::Information,
+    1 => HttpStatus::Success,
+    2 => HttpStatus::Redirect,
+    3 => HttpStatus::ClientError,
+    4 => HttpStatus::ServerError,
+    5 => HttpStatus::NonError,
+    6 => HttpStatus::Error,
+    7 => {
+      let min = u.int_in_range(100..=599)?;
+      HttpStatus::Range(min, u.int_in_range(min..=599)?)
+    }
+    _ => {
+      let first = u.int_in_range(100..=599)?;
+      let others = collection(u, 2, |u| u.int_in_range(100..=599))?;
+      HttpStatus::StatusCodes([vec![first], others].concat())
+    }
+  })
+}
+
+fn optional_string(u: &mut Unstructured) -> Result<Option<String>> {
+  if u.arbitrary()? {
+    u.arbitrary().map(Some)
+  } else {
+    Ok(None)
+  }
+}
+
+impl<'a> Arbitrary<'a> for Generator {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    Ok(match u.int_in_range(0..=13)? {
+      0 => Generator::RandomInt(u.arbitrary()?, u.arbitrary()?),
+      1 => Generator::Uuid(if u.arbitrary()? {
+        Some(*u.choose(&[UuidFormat::Simple, UuidFormat::LowerCaseHyphenated,
+          UuidFormat::UpperCaseHyphenated, UuidFormat::Urn])?)
+      } else {
+        None
+      }),
+      2 => Generator::RandomDecimal(u.arbitrary()?),
+      3 => Generator::RandomHexadecimal(u.arbitrary()?),
+      4 => Generator::RandomString(u.arbitrary()?),
+      5 => Generator::Regex(u.arbitrary()?),
+      6 => Generator::Date(optional_string(u)?, optional_string(u)?),
+      7 => Generator::Time(optional_string(u)?, optional_string(u)?),
+      8 => Generator::DateTime(optional_string(u)?, optional_string(u)?),
+      9 => Generator::RandomBoolean,
+      10 => Generator::ProviderStateGenerator(u.arbitrary()?, if u.arbitrary()? {
+        Some(*u.choose(&[DataType::STRING, DataType::INTEGER, DataType::DECIMAL, DataType::FLOAT,
+          DataType::RAW, DataType::BOOLEAN])?)
+      } else {
+        None
+      }),
+      11 => Generator::MockServerURL(u.arbitrary()?, u.arbitrary()?),
+      // Array contains generators can't be written to a Pact file, so are not generated
+      12 => Generator::EpochTimestamp(epoch_unit(u)?),
+      _ => Generator::RandomNumber(numeric_range(u)?)
+    })
+  }
+}
+
+fn generator_category<'a, F>(
+  u: &mut Unstructured<'a>,
+  generators: &mut Generators,
+  category: &GeneratorCategory,
+  mut key: F
+) -> Result<()> where F: FnMut(&mut Unstructured<'a>) -> Result<DocPath> {
+  match category {
+    GeneratorCategory::METHOD | GeneratorCategory::PATH | GeneratorCategory::STATUS => if u.arbitrary()? {
+      generators.add_generator(category, u.arbitrary()?);
+    },
+    _ => for _ in 0..u.int_in_range(0..=3)? {
+      let key = key(u)?;
+      generators.add_generator_with_subcategory(category, key, u.arbitrary()?);
+    }
+  }
+  Ok(())
+}
+
+/// Generates generators for the given categories
+fn generators(u: &mut Unstructured, categories: &[GeneratorCategory]) -> Result<Generators> {
+  let mut generators = Generators::default();
+  for category in categories {
+    match category {
+      GeneratorCategory::HEADER => generator_category(u, &mut generators, category,
+        |u| header_name(u).map(named_path))?,
+      GeneratorCategory::QUERY | GeneratorCategory::METADATA => generator_category(u,
+        &mut generators, category, |u| identifier(u).map(named_path))?,
+      _ => generator_category(u, &mut generators, category, body_path)?
+    }
+  }
+  Ok(generators)
+}
+
+impl<'a> Arbitrary<'a> for Generators {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    generators(u, &[GeneratorCategory::METHOD, GeneratorCategory::PATH, GeneratorCategory::HEADER,
+      GeneratorCategory::QUERY, GeneratorCategory::BODY, GeneratorCategory::STATUS,
+      GeneratorCategory::METADATA])
+  }
+}
+
+fn matching_rule_definition(u: &mut Unstructured, depth: usize) -> Result<MatchingRuleDefinition> {
+  let first = matching_rule(u, depth)?;
+  let others = collection(u, 1, |u| matching_rule(u, depth))?;
+  Ok(MatchingRuleDefinition {
+    value: u.arbitrary()?,
+    value_type: ValueType::Unknown,
+    rules: [vec![first], others].concat().into_iter().map(Either::Left).collect(),
+    generator: if u.arbitrary()? { Some(u.arbitrary()?) } else { None }
+  })
+}
+
+fn url_component_rules(u: &mut Unstructured, depth: usize) -> Result<MatchingRuleCategory> {
+  let mut category = MatchingRuleCategory::empty("body");
+  for _ in 0..u.int_in_range(1..=3)? {
+    let mut path = named_path(u.choose(URL_COMPONENTS)?.to_string());
+    if path.to_string() == "$.query" && u.arbitrary()? {
+      path.push_field(identifier(u)?);
+    }
+    category.rules.insert(path, rule_list(u, depth)?);
+  }
+  Ok(category)
+}
+
+fn array_contains(u: &mut Unstructured, depth: usize) -> Result<MatchingRule> {
+  collection(u, 2, |u| {
+    let mut rules = MatchingRuleCategory::empty("body");
+    for _ in 0..u.int_in_range(0..=2)? {
+      rules.rules.insert(body_path(u)?, rule_list(u, depth)?);
+    }
+    let generators = collection(u, 2, |u| Ok((body_path(u)?, u.arbitrary()?)))?;
+    Ok((u.int_in_range(0..=10)?, rules, generators.into_iter().collect()))
+  }).map(MatchingRule::ArrayContains)
+}
+
+/// Generates a matching rule. Rules that contain other rules are only generated if `depth`
+/// is greater than zero.
+fn matching_rule(u: &mut Unstructured, depth: usize) -> Result<MatchingRule> {
+  let max = if depth == 0 { 29 } else { 35 };
+  Ok(match u.int_in_range(0..=max)? {
+    0 => MatchingRule::Equality,
+    1 => MatchingRule::Regex(u.arbitrary()?),
+    2 => MatchingRule::Type,
+    3 => MatchingRule::MinType(u.int_in_range(1..=100)?),
+    4 => MatchingRule::MaxType(u.int_in_range(1..=100)?),
+    5 => MatchingRule::MinMaxType(u.int_in_range(1..=100)?, u.int_in_range(1..=100)?),
+    6 => MatchingRule::Timestamp(u.arbitrary()?),
+    7 => MatchingRule::Time(u.arbitrary()?),
+    8 => MatchingRule::Date(u.arbitrary()?),
+    9 => MatchingRule::Include(u.arbitrary()?),
+    10 => MatchingRule::Number,
+    11 => MatchingRule::Integer,
+    12 => MatchingRule::Decimal,
+    13 => MatchingRule::Null,
+    14 => MatchingRule::ContentType(u.arbitrary()?),
+    15 => MatchingRule::Values,
+    16 => MatchingRule::Boolean,
+    17 => MatchingRule::StatusCode(http_status(u)?),
+    18 => MatchingRule::NotEmpty,
+    19 => MatchingRule::Semver,
+    20 => MatchingRule::MinSize(u.int_in_range(1..=100)?),
+    21 => MatchingRule::MaxSize(u.int_in_range(1..=100)?),
+    22 => MatchingRule::BytesEqual(u.int_in_range(0..=100)?, u.arbitrary()?),
+    23 => MatchingRule::Duration,
+    24 => MatchingRule::EpochTimestamp(epoch_unit(u)?,
+      if u.arbitrary()? { Some(u.int_in_range(1..=1000)?) } else { None }),
+    25 => MatchingRule::AllowUnexpectedElements,
+    26 => MatchingRule::EqualsIgnoreOrder,
+    27 => MatchingRule::NumericRange(numeric_range(u)?),
+    28 => MatchingRule::Exists,
+    29 => MatchingRule::Absent,
+    30 => MatchingRule::EachKey(matching_rule_definition(u, depth - 1)?),
+    31 => MatchingRule::EachValue(matching_rule_definition(u, depth - 1)?),
+    32 => MatchingRule::Url(url_component_rules(u, depth - 1)?),
+    33 => MatchingRule::AnyOf([vec![matching_rule(u, depth - 1)?],
+      collection(u, 2, |u| matching_rule(u, depth - 1))?].concat()),
+    34 => MatchingRule::Not(Box::new(matching_rule(u, depth - 1)?)),
+    _ => array_contains(u, depth - 1)?
+  })
+}
+
+impl<'a> Arbitrary<'a> for MatchingRule {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    matching_rule(u, MAX_DEPTH)
+  }
+}
+
+fn rule_list(u: &mut Unstructured, depth: usize) -> Result<RuleList> {
+  let first = matching_rule(u, depth)?;
+  let others = collection(u, 2, |u| matching_rule(u, depth))?;
+  Ok(RuleList {
+    rules: [vec![first], others].concat(),
+    rule_logic: if u.arbitrary()? { RuleLogic::And } else { RuleLogic::Or },
+    cascaded: false
+  })
+}
+
+/// Generates matching rules for the given categories
+fn matching_rules(u: &mut Unstructured, categories: &[Category]) -> Result<MatchingRules> {
+  let mut rules = MatchingRules::default();
+  for category in categories {
+    let mut rule_category = MatchingRuleCategory::empty(category.clone());
+    match category {
+      Category::PATH | Category::METHOD | Category::STATUS => if u.arbitrary()? {
+        rule_category.rules.insert(DocPath::empty(), rule_list(u, MAX_DEPTH)?);
+      },
+      _ => for _ in 0..u.int_in_range(0..=3)? {
+        let key = match category {
+          Category::HEADER => named_path(header_name(u)?),
+          Category::QUERY | Category::METADATA => named_path(identifier(u)?),
+          _ => body_path(u)?
+        };
+        rule_category.rules.insert(key, rule_list(u, MAX_DEPTH)?);
+      }
+    }
+    if rule_category.is_not_empty() {
+      rules.add_rules(category.clone(), rule_category);
+    }
+  }
+  Ok(rules)
+}
+
+impl<'a> Arbitrary<'a> for MatchingRules {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    matching_rules(u, &[Category::PATH, Category::HEADER, Category::QUERY, Category::BODY,
+      Category::METADATA])
+  }
+}
+
+impl<'a> Arbitrary<'a> for ProviderState {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    Ok(ProviderState {
+      name: u.arbitrary()?,
+      params: json_map(u)?
+    })
+  }
+}
+
+fn provider_states(u: &mut Unstructured) -> Result<Vec<ProviderState>> {
+  collection(u, 2, ProviderState::arbitrary)
+}
+
+const REQUEST_RULES: &[Category] = &[Category::PATH, Category::HEADER, Category::QUERY, Category::BODY];
+const REQUEST_GENERATORS: &[GeneratorCategory] = &[GeneratorCategory::PATH, GeneratorCategory::HEADER,
+  GeneratorCategory::QUERY, GeneratorCategory::BODY];
+const RESPONSE_RULES: &[Category] = &[Category::HEADER, Category::BODY];
+const RESPONSE_GENERATORS: &[GeneratorCategory] = &[GeneratorCategory::STATUS, GeneratorCategory::HEADER,
+  GeneratorCategory::BODY];
+const MESSAGE_RULES: &[Category] = &[Category::BODY, Category::METADATA];
+const MESSAGE_GENERATORS: &[GeneratorCategory] = &[GeneratorCategory::BODY, GeneratorCategory::METADATA];
+
+impl<'a> Arbitrary<'a> for Request {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    let (body, content_type) = body(u, true)?;
+    Ok(Request {
+      method: u.choose(HTTP_METHODS)?.to_string(),
+      path: path(u)?,
+      query: query(u)?,
+      headers: headers(u, &content_type)?,
+      body,
+      matching_rules: matching_rules(u, REQUEST_RULES)?,
+      generators: generators(u, REQUEST_GENERATORS)?
+    })
+  }
+}
+
+impl<'a> Arbitrary<'a> for Response {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    let (body, content_type) = body(u, true)?;
+    Ok(Response {
+      status: u.int_in_range(100..=599)?,
+      headers: headers(u, &content_type)?,
+      body,
+      matching_rules: matching_rules(u, RESPONSE_RULES)?,
+      generators: generators(u, RESPONSE_GENERATORS)?
+    })
+  }
+}
+
+impl<'a> Arbitrary<'a> for HttpRequest {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    let (body, content_type) = body(u, false)?;
+    Ok(HttpRequest {
+      method: u.choose(HTTP_METHODS)?.to_string(),
+      path: path(u)?,
+      query: query(u)?,
+      headers: headers(u, &content_type)?,
+      body,
+      matching_rules: matching_rules(u, REQUEST_RULES)?,
+      generators: generators(u, REQUEST_GENERATORS)?
+    })
+  }
+}
+
+impl<'a> Arbitrary<'a> for HttpResponse {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    let (body, content_type) = body(u, false)?;
+    Ok(HttpResponse {
+      status: u.int_in_range(100..=599)?,
+      headers: headers(u, &content_type)?,
+      body,
+      matching_rules: matching_rules(u, RESPONSE_RULES)?,
+      generators: generators(u, RESPONSE_GENERATORS)?,
+      trailers: headers(u, &None)?
+    })
+  }
+}
+
+/// Generates the contents of a message along with its metadata. The content type of the message
+/// is stored in the metadata.
+fn message_contents(u: &mut Unstructured, allow_null: bool) -> Result<(OptionalBody, HashMap<String, Value>)> {
+  let (body, content_type) = body(u, allow_null)?;
+  let mut metadata = json_map(u)?;
+  if let Some(content_type) = content_type {
+    metadata.insert("contentType".to_string(), Value::String(content_type.to_string()));
+  }
+  Ok((body, metadata))
+}
+
+impl<'a> Arbitrary<'a> for Message {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    let (contents, metadata) = message_contents(u, true)?;
+    Ok(Message {
+      id: None,
+      description: u.arbitrary()?,
+      provider_states: provider_states(u)?,
+      contents,
+      metadata,
+      matching_rules: matching_rules(u, MESSAGE_RULES)?,
+      generators: generators(u, MESSAGE_GENERATORS)?
+    })
+  }
+}
+
+impl<'a> Arbitrary<'a> for MessageContents {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    let (contents, metadata) = message_contents(u, false)?;
+    Ok(MessageContents {
+      contents,
+      metadata,
+      matching_rules: matching_rules(u, MESSAGE_RULES)?,
+      generators: generators(u, MESSAGE_GENERATORS)?
+    })
+  }
+}
+
+fn comments(u: &mut Unstructured) -> Result<HashMap<String, Value>> {
+  Ok(collection(u, 2, |u| {
+    let text = collection(u, 2, |u| u.arbitrary::<String>())?;
+    Ok((identifier(u)?, json!([vec![token(u)?], text].concat())))
+  })?.into_iter().collect())
+}
+
+impl<'a> Arbitrary<'a> for SynchronousHttp {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    Ok(SynchronousHttp {
+      key: optional_string(u)?,
+      description: u.arbitrary()?,
+      provider_states: provider_states(u)?,
+      request: u.arbitrary()?,
+      response: u.arbitrary()?,
+      comments: comments(u)?,
+      pending: u.arbitrary()?,
+      config: json_map(u)?,
+      transport: optional_string(u)?,
+      .. SynchronousHttp::default()
+    })
+  }
+}
+
+impl<'a> Arbitrary<'a> for AsynchronousMessage {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    Ok(AsynchronousMessage {
+      key: optional_string(u)?,
+      description: u.arbitrary()?,
+      provider_states: provider_states(u)?,
+      contents: u.arbitrary()?,
+      comments: comments(u)?,
+      pending: u.arbitrary()?,
+      config: json_map(u)?,
+      transport: optional_string(u)?,
+      .. AsynchronousMessage::default()
+    })
+  }
+}
+
+impl<'a> Arbitrary<'a> for SynchronousMessage {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    Ok(SynchronousMessage {
+      key: optional_string(u)?,
+      description: u.arbitrary()?,
+      provider_states: provider_states(u)?,
+      request: u.arbitrary()?,
+      response: collection(u, 2, MessageContents::arbitrary)?,
+      comments: comments(u)?,
+      pending: u.arbitrary()?,
+      config: json_map(u)?,
+      transport: optional_string(u)?,
+      .. SynchronousMessage::default()
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use arbitrary::{Arbitrary, Unstructured};
+  use proptest::collection::vec;
+  use proptest::prelude::*;
+  use serde_json::json;
+
+  use crate::generators::{Generator, Generators, generators_from_json, generators_to_json};
+  use crate::matchingrules::{MatchingRule, MatchingRules, matchers_from_json, matchers_to_json};
+  use crate::message::Message;
+  use crate::PactSpecification;
+  use crate::provider_states::ProviderState;
+  use crate::request::Request;
+  use crate::response::Response;
+  use crate::v4::async_message::AsynchronousMessage;
+  use crate::v4::http_parts::{HttpRequest, HttpResponse};
+  use crate::v4::interaction::V4Interaction;
+  use crate::v4::message_parts::MessageContents;
+  use crate::v4::sync_message::SynchronousMessage;
+  use crate::v4::synch_http::SynchronousHttp;
+
+  fn generate<'a, T: Arbitrary<'a>>(bytes: &'a [u8]) -> T {
+    T::arbitrary(&mut Unstructured::new(bytes)).unwrap()
+  }
+
+  fn data() -> impl Strategy<Value = Vec<u8>> {
+    vec(any::<u8>(), 0..2048)
+  }
+
+  proptest! {
+    #[test]
+    fn matching_rule_round_trip(bytes in data()) {
+      let rule: MatchingRule = generate(&bytes);
+      let json = rule.to_json();
+      let result = MatchingRule::from_json(&json).unwrap();
+      prop_assert_eq!(&result, &rule);
+      prop_assert_eq!(result.to_json(), json);
+    }
+
+    #[test]
+    fn matching_rules_round_trip(bytes in data()) {
+      let rules: MatchingRules = generate(&bytes);
+      let json = json!({ "matchingRules": matchers_to_json(&rules, &PactSpecification::V4) });
+      let result = matchers_from_json(&json, &None).unwrap();
+      prop_assert_eq!(&result, &rules);
+      prop_assert_eq!(matchers_to_json(&result, &PactSpecification::V4), json["matchingRules"].clone());
+    }
+
+    #[test]
+    fn generator_round_trip(bytes in data()) {
+      let generator: Generator = generate(&bytes);
+      let json = generator.to_json().unwrap();
+      let result = Generator::from_map(json["type"].as_str().unwrap(), json.as_object().unwrap()).unwrap();
+      prop_assert_eq!(&result, &generator);
+      prop_assert_eq!(result.to_json().unwrap(), json);
+    }
+
+    #[test]
+    fn generators_round_trip(bytes in data()) {
+      let generators: Generators = generate(&bytes);
+      let json = json!({ "generators": generators_to_json(&generators, &PactSpecification::V4) });
+      let result = generators_from_json(&json).unwrap();
+      prop_assert_eq!(&result, &generators);
+      prop_assert_eq!(generators_to_json(&result, &PactSpecification::V4), json["generators"].clone());
+    }
+
+    #[test]
+    fn provider_state_round_trip(bytes in data()) {
+      let state: ProviderState = generate(&bytes);
+      let json = state.to_json();
+      let result = ProviderState::from_json_v3(&json);
+      prop_assert_eq!(&result, &state);
+      prop_assert_eq!(result.to_json(), json);
+    }
+
+    #[test]
+    fn request_round_trip(bytes in data()) {
+      let request: Request = generate(&bytes);
+      let json = request.to_json(&PactSpecification::V3);
+      let result = Request::from_json(&json, &PactSpecification::V3).unwrap();
+      prop_assert_eq!(&result, &request);
+      prop_assert_eq!(result.to_json(&PactSpecification::V3), json);
+    }
+
+    #[test]
+    fn response_round_trip(bytes in data()) {
+      let response: Response = generate(&bytes);
+      let json = response.to_json(&PactSpecification::V3);
+      let result = Response::from_json(&json, &PactSpecification::V3).unwrap();
+      prop_assert_eq!(&result, &response);
+      prop_assert_eq!(result.to_json(&PactSpecification::V3), json);
+    }
+
+    #[test]
+    fn message_round_trip(bytes in data()) {
+      let message: Message = generate(&bytes);
+      let json = message.to_json(&PactSpecification::V3);
+      let result = Message::from_json(0, &json, &PactSpecification::V3).unwrap();
+      prop_assert_eq!(&result, &message);
+      prop_assert_eq!(result.to_json(&PactSpecification::V3), json);
+    }
+
+    #[test]
+    fn http_request_round_trip(bytes in data()) {
+      let request: HttpRequest = generate(&bytes);
+      let json = request.to_json();
+      let result = HttpRequest::from_json(&json).unwrap();
+      prop_assert_eq!(&result, &request);
+      prop_assert_eq!(result.to_json(), json);
+    }
+
+    #[test]
+    fn http_response_round_trip(bytes in data()) {
+      let response: HttpResponse = generate(&bytes);
+      let json = response.to_json();
+      let result = HttpResponse::from_json(&json).unwrap();
+      prop_assert_eq!(&result, &response);
+      prop_assert_eq!(result.to_json(), json);
+    }
+
+    #[test]
+    fn message_contents_round_trip(bytes in data()) {
+      let contents: MessageContents = generate(&bytes);
+      let json = contents.to_json();
+      let result = MessageContents::from_json(&json).unwrap();
+      prop_assert_eq!(&result, &contents);
+      prop_assert_eq!(result.to_json(), json);
+    }
+
+    #[test]
+    fn synchronous_http_round_trip(bytes in data()) {
+      let interaction: SynchronousHttp = generate(&bytes);
+      let json = interaction.to_json();
+      let result = SynchronousHttp::from_json(&json, 0).unwrap();
+      prop_assert_eq!(&result, &interaction);
+      prop_assert_eq!(result.to_json(), json);
+    }
+
+    #[test]
+    fn asynchronous_message_round_trip(bytes in data()) {
+      let interaction: AsynchronousMessage = generate(&bytes);
+      let json = interaction.to_json();
+      let result = AsynchronousMessage::from_json(&json, 0).unwrap();
+      prop_assert_eq!(&result, &interaction);
+      prop_assert_eq!(result.to_json(), json);
+    }
+
+    #[test]
+    fn synchronous_message_round_trip(bytes in data()) {
+      let interaction: SynchronousMessage = generate(&bytes);
+      let json = interaction.to_json();
+      let result = SynchronousMessage::from_json(&json, 0).unwrap();
+      prop_assert_eq!(&result, &interaction);
+      prop_assert_eq!(result.to_json(), json);
+    }
+  }
+}
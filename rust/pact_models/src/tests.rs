@@ -24,6 +24,31 @@ fn http_status_code_from_json() {
   expect!(HttpStatus::from_json(&json!("serverError"))).to(be_ok().value(HttpStatus::ServerError));
   expect!(HttpStatus::from_json(&json!("nonError"))).to(be_ok().value(HttpStatus::NonError));
   expect!(HttpStatus::from_json(&json!([200, 201, 204]))).to(be_ok().value(HttpStatus::StatusCodes(vec![200, 201, 204])));
+  expect!(HttpStatus::from_json(&json!("2xx"))).to(be_ok().value(HttpStatus::Success));
+  expect!(HttpStatus::from_json(&json!("4XX"))).to(be_ok().value(HttpStatus::ClientError));
+  expect!(HttpStatus::from_json(&json!("200-204"))).to(be_ok().value(HttpStatus::Range(200, 204)));
+  expect!(HttpStatus::from_json(&json!("204-200"))).to(be_err());
+  expect!(HttpStatus::from_json(&json!("200, 204"))).to(be_ok().value(HttpStatus::StatusCodes(vec![200, 204])));
+  expect!(HttpStatus::from_json(&json!("6xx"))).to(be_err());
+  expect!(HttpStatus::from_json(&json!("okay"))).to(be_err());
+}
+
+#[test]
+fn http_status_code_to_json() {
+  expect!(HttpStatus::Range(200, 204).to_json()).to(be_equal_to(json!("200-204")));
+  expect!(HttpStatus::from_json(&HttpStatus::Range(200, 204).to_json())).to(be_ok().value(HttpStatus::Range(200, 204)));
+  expect!(HttpStatus::StatusCodes(vec![200, 204]).to_json()).to(be_equal_to(json!([200, 204])));
+}
+
+#[test]
+fn http_status_contains() {
+  expect!(HttpStatus::Success.contains(204)).to(be_true());
+  expect!(HttpStatus::Success.contains(301)).to(be_false());
+  expect!(HttpStatus::Range(200, 204).contains(204)).to(be_true());
+  expect!(HttpStatus::Range(200, 204).contains(205)).to(be_false());
+  expect!(HttpStatus::StatusCodes(vec![200, 204]).contains(201)).to(be_false());
+  expect!(HttpStatus::ClientError.example()).to(be_equal_to(400));
+  expect!(HttpStatus::Range(201, 204).example()).to(be_equal_to(201));
 }
 
 #[test]
@@ -323,7 +348,8 @@ fn write_pact_file_with_provider_state_generator_test() {
               }
             }
           },
-          generators: Generators { categories: hashmap!{} }
+          generators: Generators { categories: hashmap!{} },
+          trailers: None
         },
         .. SynchronousHttp::default()
       }.boxed_v4()],
@@ -60,6 +60,7 @@ use crate::timezone_db::*;
 pub enum DateTimePatternToken {
   Era(usize),
   Year(usize),
+  WeekBasedYear(usize),
   Month(usize),
   MonthNum(usize),
   Text(String),
@@ -258,7 +259,11 @@ fn day_of_week_pattern(s: &str) -> IResult<&str, DateTimePatternToken, DateTimeP
 
 fn year_pattern(s: &str) -> IResult<&str, DateTimePatternToken, DateTimePatternError<&str>> {
   alt((is_a("y"), is_a("Y"), is_a("u")))(s).map(|(remaining, result)| {
-    (remaining, DateTimePatternToken::Year(result.len()))
+    if result.starts_with('Y') {
+      (remaining, DateTimePatternToken::WeekBasedYear(result.len()))
+    } else {
+      (remaining, DateTimePatternToken::Year(result.len()))
+    }
   })
 }
 
@@ -736,9 +741,9 @@ fn quarter(s: &str, count: usize) -> IResult<&str, String, DateTimeError<&str>>
   }
 }
 
-/// Parses a string into a vector of date/time tokens
-pub fn parse_pattern(s: &str) -> Result<Vec<DateTimePatternToken>, String> {
-  match many1(alt((
+/// Parses the next date/time token from the start of the string
+pub(crate) fn pattern_token(s: &str) -> IResult<&str, DateTimePatternToken, DateTimePatternError<&str>> {
+  alt((
     era_pattern,
     year_pattern,
     month_pattern,
@@ -760,7 +765,12 @@ pub fn parse_pattern(s: &str) -> Result<Vec<DateTimePatternToken>, String> {
     quote_pattern,
     timezone_pattern,
     text_pattern
-  )))(s) {
+  ))(s)
+}
+
+/// Parses a string into a vector of date/time tokens
+pub fn parse_pattern(s: &str) -> Result<Vec<DateTimePatternToken>, String> {
+  match many1(pattern_token)(s) {
     Ok((remaining, result)) => if !remaining.is_empty() {
       let error = format!("Parsing datetime pattern '{}' failed at text '{}'", s, remaining);
       debug!("{}", error);
@@ -783,6 +793,7 @@ fn validate_datetime_string(value: &str, pattern_tokens: &[DateTimePatternToken]
     let result = match token {
       DateTimePatternToken::Era(count) => era(buffer, *count),
       DateTimePatternToken::Year(count) => year(buffer, *count),
+      DateTimePatternToken::WeekBasedYear(count) => year(buffer, *count),
       DateTimePatternToken::WeekInYear => week_in_year(buffer),
       DateTimePatternToken::WeekInMonth(from_one) => week_in_month(buffer, *from_one),
       DateTimePatternToken::DayInYear => day_in_year(buffer),
@@ -885,62 +896,136 @@ pub fn validate_datetime(value: &str, format: &str) -> Result<(), String> {
   }
 }
 
-/// Converts the date time pattern tokens to a chrono formatted string
+/// Validates that the given value is an ISO-8601 duration (i.e. `P1Y2M3DT4H5M6S`, `PT0.5S` or `P2W`)
+pub fn validate_duration(value: &str) -> Result<(), String> {
+  let components = value.strip_prefix('P')
+    .ok_or_else(|| format!("'{}' is not a valid ISO-8601 duration, it must start with 'P'", value))?;
+  let (date_part, time_part) = match components.split_once('T') {
+    Some((date_part, time_part)) => (date_part, Some(time_part)),
+    None => (components, None)
+  };
+
+  let mut count = validate_duration_components(value, date_part, &['Y', 'M', 'W', 'D'])?;
+  if let Some(time_part) = time_part {
+    let time_count = validate_duration_components(value, time_part, &['H', 'M', 'S'])?;
+    if time_count == 0 {
+      return Err(format!("'{}' is not a valid ISO-8601 duration, expected a time component after 'T'", value));
+    }
+    count += time_count;
+  }
+
+  if count == 0 {
+    Err(format!("'{}' is not a valid ISO-8601 duration, expected at least one component", value))
+  } else {
+    Ok(())
+  }
+}
+
+fn validate_duration_components(value: &str, components: &str, designators: &[char]) -> Result<usize, String> {
+  let mut count = 0;
+  let mut next_designator = 0;
+  let mut fraction_seen = false;
+  let mut number = String::new();
+
+  for ch in components.chars() {
+    if ch.is_ascii_digit() || ((ch == '.' || ch == ',') && !number.is_empty()) {
+      number.push(ch);
+    } else if let Some(index) = designators.iter().position(|d| *d == ch) {
+      if number.is_empty() || !number.ends_with(|c: char| c.is_ascii_digit()) {
+        return Err(format!("'{}' is not a valid ISO-8601 duration, expected a number before '{}'", value, ch));
+      }
+      if index < next_designator || fraction_seen {
+        return Err(format!("'{}' is not a valid ISO-8601 duration, '{}' is out of order", value, ch));
+      }
+      if number.chars().filter(|c| *c == '.' || *c == ',').count() > 1 {
+        return Err(format!("'{}' is not a valid ISO-8601 duration, '{}' is not a valid number", value, number));
+      }
+      fraction_seen = number.contains(|c| c == '.' || c == ',');
+      next_designator = index + 1;
+      count += 1;
+      number.clear();
+    } else {
+      return Err(format!("'{}' is not a valid ISO-8601 duration, '{}' is not expected", value, ch));
+    }
+  }
+
+  if number.is_empty() {
+    Ok(count)
+  } else {
+    Err(format!("'{}' is not a valid ISO-8601 duration, '{}' is missing a designator", value, number))
+  }
+}
+
+/// Converts the date time pattern tokens to a chrono formatted string. Any tokens that chrono does
+/// not support are logged and skipped. Use `datetime_format::to_chrono_pattern` to get an error
+/// pointing at the unsupported tokens instead.
 pub fn to_chrono_pattern(tokens: &[DateTimePatternToken]) -> String {
   let mut buffer = String::new();
 
   for token in tokens {
-    match token {
-      DateTimePatternToken::Era(_count) => buffer.push_str("AD"),
-      DateTimePatternToken::Year(d) => buffer.push_str(if *d == 2 { "%y" } else { "%Y" }),
-      DateTimePatternToken::WeekInYear => buffer.push_str("%U"),
-      DateTimePatternToken::WeekInMonth(_) => warn!("Chono does not support week in month"),
-      DateTimePatternToken::DayInYear => buffer.push_str("%j"),
-      DateTimePatternToken::DayInMonth => buffer.push_str("%d"),
-      DateTimePatternToken::Month(d) => buffer.push_str(if *d <= 2 { "%m" } else if *d > 3 { "%B" } else { "%b" }),
-      DateTimePatternToken::MonthNum(_d) => buffer.push_str("%m"),
-      DateTimePatternToken::Text(t) => buffer.push_str(&str::replace(t, "%", "%%")),
-      DateTimePatternToken::DayName(d) => buffer.push_str(if *d > 3 { "%A" } else { "%a" }),
-      DateTimePatternToken::DayOfWeek(_d) => buffer.push_str("%u"),
-      DateTimePatternToken::Hour24 => buffer.push_str("%H"),
-      DateTimePatternToken::Hour24ZeroBased => buffer.push_str("%H"),
-      DateTimePatternToken::Hour12 => buffer.push_str("%I"),
-      DateTimePatternToken::Hour12ZeroBased => buffer.push_str("%I"),
-      DateTimePatternToken::Minute => buffer.push_str("%M"),
-      DateTimePatternToken::Second => buffer.push_str("%S"),
-      DateTimePatternToken::Millisecond(d) => if *d < 3 {
-        // something in chrono panics
-        buffer.push_str("%3f")
-      } else {
-        buffer.push_str(&format!("%{}f", *d))
-      },
-      DateTimePatternToken::Nanosecond(_d) => buffer.push_str("%f"),
-      DateTimePatternToken::TimezoneName(_d) => buffer.push_str("%Z"),
-      DateTimePatternToken::TimezoneId(_d) => buffer.push_str("%Z"),
-      DateTimePatternToken::TimezoneOffset(_d) => buffer.push_str("%z"),
-      DateTimePatternToken::TimezoneOffsetX(_d) => buffer.push_str("%:z"),
-      DateTimePatternToken::TimezoneOffsetXZZero(_d) => buffer.push_str("%:z"),
-      DateTimePatternToken::AmPm => buffer.push_str("%p"),
-      _ => warn!("Chono does not support {:?}", token)
-    };
+    match chrono_pattern_for_token(token) {
+      Some(pattern) => buffer.push_str(&pattern),
+      None => warn!("Chono does not support {:?}", token)
+    }
   }
 
   buffer
 }
 
+/// Returns the chrono format specifier for the date time pattern token, or `None` if chrono does
+/// not support it
+pub(crate) fn chrono_pattern_for_token(token: &DateTimePatternToken) -> Option<String> {
+  match token {
+    DateTimePatternToken::Era(_count) => Some("AD".to_string()),
+    DateTimePatternToken::Year(d) => Some(if *d == 2 { "%y" } else { "%Y" }.to_string()),
+    DateTimePatternToken::WeekBasedYear(d) => Some(if *d == 2 { "%g" } else { "%G" }.to_string()),
+    DateTimePatternToken::WeekInYear => Some("%V".to_string()),
+    DateTimePatternToken::DayInYear => Some("%j".to_string()),
+    DateTimePatternToken::DayInMonth => Some("%d".to_string()),
+    DateTimePatternToken::Month(d) => Some(if *d <= 2 { "%m" } else if *d > 3 { "%B" } else { "%b" }.to_string()),
+    DateTimePatternToken::MonthNum(_d) => Some("%m".to_string()),
+    DateTimePatternToken::Text(t) => Some(str::replace(t, "%", "%%")),
+    DateTimePatternToken::DayName(d) => Some(if *d > 3 { "%A" } else { "%a" }.to_string()),
+    DateTimePatternToken::DayOfWeek(_d) => Some("%u".to_string()),
+    DateTimePatternToken::Hour24 => Some("%H".to_string()),
+    DateTimePatternToken::Hour24ZeroBased => Some("%H".to_string()),
+    DateTimePatternToken::Hour12 => Some("%I".to_string()),
+    DateTimePatternToken::Hour12ZeroBased => Some("%I".to_string()),
+    DateTimePatternToken::Minute => Some("%M".to_string()),
+    DateTimePatternToken::Second => Some("%S".to_string()),
+    DateTimePatternToken::Millisecond(d) => if *d < 3 {
+      // something in chrono panics
+      Some("%3f".to_string())
+    } else {
+      Some(format!("%{}f", *d))
+    },
+    DateTimePatternToken::Nanosecond(_d) => Some("%f".to_string()),
+    DateTimePatternToken::TimezoneName(_d) => Some("%Z".to_string()),
+    DateTimePatternToken::TimezoneId(_d) => Some("%Z".to_string()),
+    DateTimePatternToken::TimezoneOffset(_d) => Some("%z".to_string()),
+    DateTimePatternToken::TimezoneOffsetX(_d) => Some("%:z".to_string()),
+    DateTimePatternToken::TimezoneOffsetXZZero(_d) => Some("%:z".to_string()),
+    DateTimePatternToken::AmPm => Some("%p".to_string()),
+    DateTimePatternToken::WeekInMonth(_) |
+    DateTimePatternToken::TimezoneOffsetGmt(_) |
+    DateTimePatternToken::QuarterOfYear(_) |
+    DateTimePatternToken::QuarterOfYearNum(_) |
+    DateTimePatternToken::MillisecondOfDay |
+    DateTimePatternToken::NanosecondOfDay => None
+  }
+}
+
 /// Generates a date/time string from the current system clock using the provided format string
 pub fn generate_string(format: &str) -> Result<String, String> {
   trace!("generating date/time from '{}'", format);
-  match parse_pattern(format) {
-    Ok(pattern_tokens) => {
-      trace!("parsed date/time patterns: {:?}", pattern_tokens);
-      let chrono_pattern = to_chrono_pattern(&pattern_tokens);
+  match crate::datetime_format::to_chrono_pattern(format) {
+    Ok(chrono_pattern) => {
       trace!("Chrono pattern: {}", chrono_pattern);
       Ok(Local::now().format(chrono_pattern.as_str()).to_string())
     },
     Err(err) => {
-      error!("Error parsing '{}': {:?}", format, err);
-      Err(format!("Error parsing '{}': {:?}", format, err))
+      error!("Error parsing '{}': {}", format, err);
+      Err(format!("Error parsing '{}': {}", format, err))
     }
   }
 }
@@ -1067,7 +1152,7 @@ mod tests {
     expect!(parse_pattern("yyyy")).to(
       be_ok().value(vec![DateTimePatternToken::Year(4)]));
     expect!(parse_pattern("YYyy")).to(
-      be_ok().value(vec![DateTimePatternToken::Year(2), DateTimePatternToken::Year(2)]));
+      be_ok().value(vec![DateTimePatternToken::WeekBasedYear(2), DateTimePatternToken::Year(2)]));
 
     expect!(validate_datetime("2000", "yyyy")).to(be_ok());
     expect!(validate_datetime("200000", "yyyyyy")).to(be_ok());
@@ -1288,6 +1373,28 @@ mod tests {
     // expect!(validate_datetime("AEST", "zzzz")).to(be_err());
   }
 
+  #[test]
+  fn validate_duration_test() {
+    expect!(validate_duration("PT5M30S")).to(be_ok());
+    expect!(validate_duration("P1Y2M3DT4H5M6S")).to(be_ok());
+    expect!(validate_duration("P2W")).to(be_ok());
+    expect!(validate_duration("PT0.5S")).to(be_ok());
+    expect!(validate_duration("P1DT12H")).to(be_ok());
+    expect!(validate_duration("P0D")).to(be_ok());
+
+    expect!(validate_duration("")).to(be_err());
+    expect!(validate_duration("P")).to(be_err());
+    expect!(validate_duration("PT")).to(be_err());
+    expect!(validate_duration("P1DT")).to(be_err());
+    expect!(validate_duration("5M30S")).to(be_err());
+    expect!(validate_duration("PT30S5M")).to(be_err());
+    expect!(validate_duration("PT5")).to(be_err());
+    expect!(validate_duration("PT0.5M30S")).to(be_err());
+    expect!(validate_duration("P1H")).to(be_err());
+    expect!(validate_duration("PTM")).to(be_err());
+    expect!(validate_duration("P1.2.3D")).to(be_err());
+  }
+
   #[test]
   fn to_chrono_pattern_test() {
     expect!(to_chrono_pattern(&parse_pattern("yyyy-MM-dd").unwrap())).to(be_equal_to("%Y-%m-%d"));
@@ -1300,7 +1407,7 @@ mod tests {
     expect!(to_chrono_pattern(&parse_pattern("yyMMddHHmmssZ").unwrap())).to(be_equal_to("%y%m%d%H%M%S%z"));
     expect!(to_chrono_pattern(&parse_pattern("yyyy-MM-dd'T'HH:mm:ss.SSSZ").unwrap())).to(be_equal_to("%Y-%m-%dT%H:%M:%S.%3f%z"));
     expect!(to_chrono_pattern(&parse_pattern("yyyy-MM-dd'T'HH:mm:ss.SSSXXX").unwrap())).to(be_equal_to("%Y-%m-%dT%H:%M:%S.%3f%:z"));
-    expect!(to_chrono_pattern(&parse_pattern("YYYY-'W'ww-e").unwrap())).to(be_equal_to("%Y-W%U-%u"));
+    expect!(to_chrono_pattern(&parse_pattern("YYYY-'W'ww-e").unwrap())).to(be_equal_to("%G-W%V-%u"));
   }
 
   #[test]
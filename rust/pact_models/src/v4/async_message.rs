@@ -25,8 +25,9 @@ use crate::PactSpecification;
 use crate::provider_states::ProviderState;
 use crate::sync_interaction::RequestResponseInteraction;
 use crate::v4::http_parts::body_from_json;
-use crate::v4::interaction::{InteractionMarkup, parse_plugin_config, V4Interaction};
+use crate::v4::interaction::{hash_interaction_config, InteractionMarkup, parse_interaction_config, parse_plugin_config, V4Interaction};
 use crate::v4::message_parts::{MessageContents, metadata_to_headers};
+use crate::v4::schema::interaction_schema;
 use crate::v4::sync_message::SynchronousMessage;
 use crate::v4::synch_http::SynchronousHttp;
 use crate::v4::V4InteractionType;
@@ -57,11 +58,33 @@ pub struct AsynchronousMessage {
   /// Text markup to use to render the interaction in a UI
   pub interaction_markup: InteractionMarkup,
 
+  /// Configuration for how the interaction is matched
+  pub config: HashMap<String, Value>,
+
   /// Transport mechanism used with this message
   pub transport: Option<String>
 }
 
 impl AsynchronousMessage {
+  /// Generate the JSON schema properties for the given Pact specification
+  pub fn schema(_spec_version: PactSpecification) -> Value {
+    interaction_schema(V4InteractionType::Asynchronous_Messages, json!({
+      "contents": {
+        "$ref": "#/definitions/body"
+      },
+      "metadata": {
+        "description": "Message metadata",
+        "type": "object"
+      },
+      "matchingRules": {
+        "$ref": "#/definitions/matchingRules"
+      },
+      "generators": {
+        "$ref": "#/definitions/generators"
+      }
+    }), &[])
+  }
+
   fn calc_hash(&self) -> String {
     let mut s = DefaultHasher::new();
     self.hash(&mut s);
@@ -117,6 +140,7 @@ impl AsynchronousMessage {
       let as_headers = metadata_to_headers(&metadata);
 
       let plugin_config = parse_plugin_config(json);
+      let config = parse_interaction_config(json);
       let interaction_markup = json.get("interactionMarkup")
         .map(|markup| InteractionMarkup::from_json(markup)).unwrap_or_default();
 
@@ -143,6 +167,7 @@ impl AsynchronousMessage {
           .map(|value| value.as_bool().unwrap_or_default()).unwrap_or_default(),
         plugin_config,
         interaction_markup,
+        config,
         transport
       })
     } else {
@@ -206,6 +231,11 @@ impl V4Interaction for AsynchronousMessage {
       map.insert("interactionMarkup".to_string(), self.interaction_markup.to_json());
     }
 
+    if !self.config.is_empty() {
+      map.insert("config".to_string(), Value::Object(self.config.iter()
+        .map(|(k, v)| (k.clone(), v.clone())).collect()));
+    }
+
     if let Some(transport) = &self.transport {
       map.insert("transport".to_string(), Value::String(transport.clone()));
     }
@@ -257,6 +287,14 @@ impl V4Interaction for AsynchronousMessage {
     &mut self.interaction_markup
   }
 
+  fn config(&self) -> HashMap<String, Value> {
+    self.config.clone()
+  }
+
+  fn set_config(&mut self, config: HashMap<String, Value>) {
+    self.config = config;
+  }
+
   fn transport(&self) -> Option<String> {
     self.transport.clone()
   }
@@ -427,6 +465,7 @@ impl Default for AsynchronousMessage {
       pending: false,
       plugin_config: Default::default(),
       interaction_markup: Default::default(),
+      config: Default::default(),
       transport: None
     }
   }
@@ -438,7 +477,8 @@ impl PartialEq for AsynchronousMessage {
       self.description == other.description &&
       self.provider_states == other.provider_states &&
       self.contents == other.contents &&
-      self.pending == other.pending
+      self.pending == other.pending &&
+      self.config == other.config
   }
 }
 
@@ -454,6 +494,7 @@ impl Hash for AsynchronousMessage {
     self.contents.matching_rules.hash(state);
     self.contents.generators.hash(state);
     self.pending.hash(state);
+    hash_interaction_config(&self.config, state);
   }
 }
 
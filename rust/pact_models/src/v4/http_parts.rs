@@ -18,7 +18,7 @@ use crate::bodies::OptionalBody;
 use crate::content_types::{ContentType, ContentTypeHint, detect_content_type_from_bytes};
 use crate::generators::{Generators, generators_from_json, generators_to_json};
 use crate::http_parts::HttpPart;
-use crate::json_utils::{headers_from_json, json_to_string};
+use crate::json_utils::{headers_from_json, json_to_string, trailers_from_json};
 use crate::matchingrules::{matchers_from_json, matchers_to_json, MatchingRules};
 use crate::PactSpecification;
 use crate::query_strings::{query_to_json, v3_query_from_json};
@@ -118,6 +118,51 @@ impl HttpRequest {
     json
   }
 
+  /// Generate the JSON schema properties for the given Pact specification
+  pub fn schema(_spec_version: PactSpecification) -> Value {
+    json!({
+      "type": "object",
+      "properties": {
+        "method": {
+          "type": "string"
+        },
+        "path": {
+          "type": "string"
+        },
+        "query": {
+          "description": "Query parameters, keyed by name",
+          "type": "object",
+          "additionalProperties": {
+            "type": ["array", "string"],
+            "items": {
+              "type": ["string", "null"]
+            }
+          }
+        },
+        "headers": {
+          "description": "Request headers, keyed by name",
+          "type": "object",
+          "additionalProperties": {
+            "type": ["array", "string"],
+            "items": {
+              "type": "string"
+            }
+          }
+        },
+        "body": {
+          "$ref": "#/definitions/body"
+        },
+        "matchingRules": {
+          "$ref": "#/definitions/matchingRules"
+        },
+        "generators": {
+          "$ref": "#/definitions/generators"
+        }
+      },
+      "required": ["method", "path"]
+    })
+  }
+
   /// Convert this request to a V3 request struct
   pub fn as_v3_request(&self) -> Request {
     Request {
@@ -442,7 +487,9 @@ pub struct HttpResponse {
   /// Response matching rules
   pub matching_rules: MatchingRules,
   /// Response generators
-  pub generators: Generators
+  pub generators: Generators,
+  /// Response trailers (only sent with HTTP/2 and chunked HTTP/1.1 responses)
+  pub trailers: Option<HashMap<String, Vec<String>>>
 }
 
 impl Display for HttpResponse {
@@ -459,7 +506,8 @@ impl Default for HttpResponse {
       headers: None,
       body: OptionalBody::Missing,
       matching_rules: MatchingRules::default(),
-      generators: Generators::default()
+      generators: Generators::default(),
+      trailers: None
     }
   }
 }
@@ -470,7 +518,8 @@ impl PartialEq for HttpResponse {
       self.headers == other.headers &&
       self.body == other.body &&
       self.matching_rules == other.matching_rules &&
-      self.generators == other.generators
+      self.generators == other.generators &&
+      self.trailers == other.trailers
   }
 }
 
@@ -488,6 +537,13 @@ impl Hash for HttpResponse {
     self.body.hash(state);
     self.matching_rules.hash(state);
     self.generators.hash(state);
+
+    if let Some(ref trailers) = self.trailers {
+      for (k, v) in trailers.iter().sorted_by(|(a, _), (b, _)| Ord::cmp(a, b)) {
+        k.to_lowercase().hash(state);
+        v.hash(state);
+      }
+    }
   }
 }
 
@@ -505,6 +561,7 @@ impl HttpResponse {
       body: body_from_json(response, "body", &headers),
       matching_rules: matchers_from_json(response, &None)?,
       generators: generators_from_json(response)?,
+      trailers: trailers_from_json(response)
     })
   }
 
@@ -538,10 +595,61 @@ impl HttpResponse {
         map.insert("generators".to_string(), generators_to_json(
           &self.generators.clone(), &PactSpecification::V4));
       }
+
+      if let Some(ref trailers) = self.trailers {
+        map.insert("trailers".to_string(), Value::Object(
+          trailers.iter()
+            .sorted_by(|(a, _), (b, _)| Ord::cmp(a, b))
+            .map(|(k, v)| (k.clone(), json!(v)))
+            .collect()
+        ));
+      }
     }
     json
   }
 
+  /// Generate the JSON schema properties for the given Pact specification
+  pub fn schema(_spec_version: PactSpecification) -> Value {
+    json!({
+      "type": "object",
+      "properties": {
+        "status": {
+          "type": "integer"
+        },
+        "headers": {
+          "description": "Response headers, keyed by name",
+          "type": "object",
+          "additionalProperties": {
+            "type": ["array", "string"],
+            "items": {
+              "type": "string"
+            }
+          }
+        },
+        "body": {
+          "$ref": "#/definitions/body"
+        },
+        "matchingRules": {
+          "$ref": "#/definitions/matchingRules"
+        },
+        "generators": {
+          "$ref": "#/definitions/generators"
+        },
+        "trailers": {
+          "description": "Response trailers, keyed by name",
+          "type": "object",
+          "additionalProperties": {
+            "type": ["array", "string"],
+            "items": {
+              "type": "string"
+            }
+          }
+        }
+      },
+      "required": ["status"]
+    })
+  }
+
   /// Converts this response to a v3 response struct
   pub fn as_v3_response(&self) -> Response {
     Response {
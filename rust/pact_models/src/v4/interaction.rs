@@ -3,15 +3,19 @@
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::panic::RefUnwindSafe;
 use std::str::FromStr;
 
 use anyhow::anyhow;
+use itertools::Itertools;
 use serde_json::{json, Value};
 use tracing::warn;
 
 use crate::interaction::Interaction;
-use crate::json_utils::json_to_string;
+use crate::json_utils::{hash_json, json_to_string};
+use crate::PactSpecification;
+use crate::plugins::{InteractionPluginConfig, merge_configuration};
 use crate::v4::async_message::AsynchronousMessage;
 use crate::v4::sync_message::SynchronousMessage;
 use crate::v4::synch_http::SynchronousHttp;
@@ -54,6 +58,23 @@ impl InteractionMarkup {
     })
   }
 
+  /// Generate the JSON schema properties for the given Pact specification
+  pub fn schema(_spec_version: PactSpecification) -> Value {
+    json!({
+      "description": "Markup added to the interaction by a plugin",
+      "type": "object",
+      "properties": {
+        "markup": {
+          "type": "string"
+        },
+        "markupType": {
+          "description": "Type of markup (CommonMark or HTML)",
+          "type": "string"
+        }
+      }
+    })
+  }
+
   /// Merges this markup with the other
   pub fn merge(&self, other: InteractionMarkup) -> InteractionMarkup {
     if self.is_empty() {
@@ -99,6 +120,34 @@ pub trait V4Interaction: Interaction + Send + Sync {
   /// Mutable access to the annotations and comments associated with this interaction
   fn comments_mut(&mut self) -> &mut HashMap<String, Value>;
 
+  /// Text comments associated with this interaction (stored under the `text` comment key)
+  fn text_comments(&self) -> Vec<String> {
+    match self.comments().get("text") {
+      Some(Value::Array(values)) => values.iter().map(json_to_string).collect(),
+      Some(value) => vec![json_to_string(value)],
+      None => vec![]
+    }
+  }
+
+  /// Appends a text comment to this interaction. If the existing `text` comment is not an array,
+  /// it will be replaced.
+  fn add_text_comment(&mut self, comment: &str) {
+    match self.comments_mut().entry("text".to_string()).or_insert_with(|| Value::Array(vec![])) {
+      Value::Array(values) => values.push(Value::String(comment.to_string())),
+      value => *value = json!([comment])
+    }
+  }
+
+  /// Name of the test that generated this interaction (stored under the `testname` comment key)
+  fn test_name(&self) -> Option<String> {
+    self.comments().get("testname").map(json_to_string)
+  }
+
+  /// Sets the name of the test that generated this interaction
+  fn set_test_name(&mut self, test_name: &str) {
+    self.comments_mut().insert("testname".to_string(), json!(test_name));
+  }
+
   /// Type of this V4 interaction
   fn v4_type(&self) -> V4InteractionType;
 
@@ -114,6 +163,57 @@ pub trait V4Interaction: Interaction + Send + Sync {
   /// Markup added to the interaction to render in UIs
   fn interaction_markup_mut(&mut self) -> &mut InteractionMarkup;
 
+  /// Sets the markup (and the type of markup, i.e. CommonMark or HTML) to render the interaction in UIs
+  fn set_interaction_markup(&mut self, markup: &str, markup_type: &str) {
+    *self.interaction_markup_mut() = InteractionMarkup {
+      markup: markup.to_string(),
+      markup_type: markup_type.to_string()
+    };
+  }
+
+  /// Adds markup to render the interaction in UIs, appending it to any existing markup (i.e. when
+  /// more than one plugin has contributed markup)
+  fn add_interaction_markup(&mut self, markup: InteractionMarkup) {
+    let merged = self.interaction_markup_mut().merge(markup);
+    *self.interaction_markup_mut() = merged;
+  }
+
+  /// Configuration added to the interaction by the given plugin
+  fn plugin_config_for(&self, plugin_name: &str) -> Option<InteractionPluginConfig> {
+    self.plugin_config().get(plugin_name)
+      .map(|config| InteractionPluginConfig::new(plugin_name, config.clone()))
+  }
+
+  /// Configuration added to the interaction by all the plugins, sorted by plugin name
+  fn plugin_configs(&self) -> Vec<InteractionPluginConfig> {
+    self.plugin_config().iter()
+      .sorted_by(|(a, _), (b, _)| a.cmp(b))
+      .map(|(name, config)| InteractionPluginConfig::new(name, config.clone()))
+      .collect()
+  }
+
+  /// Adds configuration from a plugin to the interaction. This is deep merged with any existing
+  /// configuration from the same plugin, so that the configuration for different parts of the
+  /// interaction (i.e. the request and response) is not lost.
+  fn add_plugin_config(&mut self, plugin_name: &str, configuration: &HashMap<String, Value>) {
+    let entry = self.plugin_config_mut().entry(plugin_name.to_string()).or_default();
+    merge_configuration(entry, configuration);
+  }
+
+  /// Configuration for how the interaction is matched (i.e. `contentMatcher`). Interactions that
+  /// do not support any configuration return an empty map.
+  fn config(&self) -> HashMap<String, Value> {
+    HashMap::new()
+  }
+
+  /// Sets the configuration for how the interaction is matched. Interactions that do not support
+  /// any configuration will ignore it.
+  fn set_config(&mut self, config: HashMap<String, Value>) {
+    if !config.is_empty() {
+      warn!("Interaction '{}' does not support any configuration, ignoring it", self.description());
+    }
+  }
+
   /// Transport used with the interaction
   fn transport(&self) -> Option<String>;
 
@@ -242,14 +342,38 @@ pub(crate) fn parse_plugin_config(json: &Value) -> HashMap<String, HashMap<Strin
   }
 }
 
+pub(crate) fn parse_interaction_config(json: &Value) -> HashMap<String, Value> {
+  match json.get("config") {
+    Some(Value::Object(map)) => map.iter()
+      .map(|(k, v)| (k.clone(), v.clone()))
+      .collect(),
+    Some(_) => {
+      warn!("Interaction configuration is not correctly formatted, ignoring it");
+      Default::default()
+    }
+    None => Default::default()
+  }
+}
+
+/// Hashes the interaction configuration, in key order so that the hash is stable
+pub(crate) fn hash_interaction_config<H: Hasher>(config: &HashMap<String, Value>, state: &mut H) {
+  for (k, v) in config.iter().sorted_by(|(a, _), (b, _)| Ord::cmp(a, b)) {
+    k.hash(state);
+    hash_json(v, state);
+  }
+}
+
 #[cfg(test)]
 mod tests {
+  use std::collections::HashMap;
+
   use expectest::prelude::*;
   use maplit::hashmap;
-  use serde_json::json;
+  use serde_json::{json, Value};
 
   use crate::provider_states::ProviderState;
-  use crate::v4::interaction::interaction_from_json;
+  use crate::v4::interaction::{interaction_from_json, InteractionMarkup, V4Interaction};
+  use crate::v4::synch_http::SynchronousHttp;
 
   #[test]
   fn loading_interaction_from_json() {
@@ -311,6 +435,98 @@ mod tests {
     expect!(interaction.id()).to(be_some().value("123456789".to_string()));
   }
 
+  #[test]
+  fn comments_and_markup_accessors() {
+    let mut interaction = SynchronousHttp::default();
+    expect!(interaction.text_comments().iter()).to(be_empty());
+    expect!(interaction.test_name()).to(be_none());
+
+    interaction.add_text_comment("first comment");
+    interaction.add_text_comment("second comment");
+    interaction.set_test_name("a_test");
+    interaction.set_interaction_markup("# Interaction", "COMMON_MARK");
+    expect!(interaction.text_comments()).to(be_equal_to(vec!["first comment".to_string(), "second comment".to_string()]));
+    expect!(interaction.test_name()).to(be_some().value("a_test".to_string()));
+
+    let json = interaction.to_json();
+    let interaction = interaction_from_json("", 0, &json).unwrap().as_v4().unwrap();
+    expect!(interaction.text_comments()).to(be_equal_to(vec!["first comment".to_string(), "second comment".to_string()]));
+    expect!(interaction.test_name()).to(be_some().value("a_test".to_string()));
+    expect!(interaction.interaction_markup()).to(be_equal_to(InteractionMarkup {
+      markup: "# Interaction".to_string(),
+      markup_type: "COMMON_MARK".to_string()
+    }));
+  }
+
+  #[test]
+  fn add_text_comment_replaces_the_text_comment_if_it_is_not_an_array() {
+    let mut interaction = SynchronousHttp {
+      comments: hashmap!{ "text".to_string() => json!({ "not": "an array" }) },
+      .. SynchronousHttp::default()
+    };
+    interaction.add_text_comment("a comment");
+    expect!(interaction.comments.get("text").cloned()).to(be_some().value(json!(["a comment"])));
+  }
+
+  #[test]
+  fn plugin_config_accessors() {
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    struct ProtobufConfig {
+      descriptors: HashMap<String, Value>,
+      version: String
+    }
+
+    let mut interaction = SynchronousHttp::default();
+    expect!(interaction.plugin_config_for("protobuf")).to(be_none());
+
+    interaction.add_plugin_config("protobuf", &hashmap!{
+      "descriptors".to_string() => json!({ "a": { "messages": ["Request"] } }),
+      "version".to_string() => json!("0.1")
+    });
+    interaction.add_plugin_config("protobuf", &hashmap!{
+      "descriptors".to_string() => json!({ "a": { "messages": ["Response"] }, "b": {} }),
+      "version".to_string() => json!("0.2")
+    });
+    interaction.add_plugin_config("csv", &hashmap!{ "delimiter".to_string() => json!(",") });
+
+    let config = interaction.plugin_config_for("protobuf").unwrap();
+    expect!(config.get("version")).to(be_some().value(&json!("0.2")));
+    expect!(config.get_as::<String>("version").unwrap()).to(be_some().value("0.2".to_string()));
+    expect!(config.get_as::<String>("missing").unwrap()).to(be_none());
+    expect!(config.get_as::<u32>("version")).to(be_err());
+    expect!(config.deserialise::<ProtobufConfig>().unwrap()).to(be_equal_to(ProtobufConfig {
+      descriptors: hashmap!{
+        "a".to_string() => json!({ "messages": ["Request", "Response"] }),
+        "b".to_string() => json!({})
+      },
+      version: "0.2".to_string()
+    }));
+    expect!(interaction.plugin_configs().iter().map(|c| c.plugin_name.as_str()).collect::<Vec<_>>())
+      .to(be_equal_to(vec!["csv", "protobuf"]));
+
+    let json = interaction.to_json();
+    let interaction = interaction_from_json("", 0, &json).unwrap().as_v4().unwrap();
+    expect!(interaction.plugin_config_for("protobuf")).to(be_some().value(config));
+  }
+
+  #[test]
+  fn add_interaction_markup_appends_to_the_existing_markup() {
+    let mut interaction = SynchronousHttp::default();
+    interaction.add_interaction_markup(InteractionMarkup {
+      markup: "# Request".to_string(),
+      markup_type: "COMMON_MARK".to_string()
+    });
+    interaction.add_interaction_markup(InteractionMarkup::default());
+    interaction.add_interaction_markup(InteractionMarkup {
+      markup: "# Response".to_string(),
+      markup_type: "COMMON_MARK".to_string()
+    });
+    expect!(interaction.interaction_markup()).to(be_equal_to(InteractionMarkup {
+      markup: "# Request\n# Response".to_string(),
+      markup_type: "COMMON_MARK".to_string()
+    }));
+  }
+
   // TODO: implement these tests
   // #[test]
   // fn interactions_do_not_conflict_if_they_have_different_descriptions() {
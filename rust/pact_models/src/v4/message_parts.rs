@@ -85,6 +85,28 @@ impl MessageContents {
     json
   }
 
+  /// Generate the JSON schema properties for the given Pact specification
+  pub fn schema(_spec_version: PactSpecification) -> Value {
+    json!({
+      "type": "object",
+      "properties": {
+        "contents": {
+          "$ref": "#/definitions/body"
+        },
+        "metadata": {
+          "description": "Message metadata",
+          "type": "object"
+        },
+        "matchingRules": {
+          "$ref": "#/definitions/matchingRules"
+        },
+        "generators": {
+          "$ref": "#/definitions/generators"
+        }
+      }
+    })
+  }
+
 
   /// Returns the content type of the message by returning the content type associated with
   /// the body, or by looking it up in the message metadata
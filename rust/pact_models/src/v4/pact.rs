@@ -22,7 +22,11 @@ use crate::pact::{Pact, ReadWritePact};
 use crate::PACT_RUST_VERSION;
 use crate::plugins::PluginData;
 use crate::sync_pact::RequestResponsePact;
+use crate::v4::async_message::AsynchronousMessage;
 use crate::v4::interaction::{interactions_from_json, V4Interaction};
+use crate::v4::schema;
+use crate::v4::sync_message::SynchronousMessage;
+use crate::v4::synch_http::SynchronousHttp;
 use crate::v4::V4InteractionType;
 use crate::verify_json::{json_type_of, PactFileVerificationResult, PactJsonVerifier, ResultLevel};
 
@@ -163,6 +167,37 @@ impl V4Pact {
       plugin_data
     })
   }
+
+  /// Generate the JSON schema properties for the given Pact specification
+  pub fn schema(spec_version: PactSpecification) -> Value {
+    json!({
+      "properties": {
+        "consumer": Consumer::schema(spec_version),
+        "interactions": {
+          "description": "The interactions between the consumer and provider",
+          "type": "array",
+          "items": {
+            "oneOf": [
+              SynchronousHttp::schema(spec_version),
+              AsynchronousMessage::schema(spec_version),
+              SynchronousMessage::schema(spec_version)
+            ]
+          }
+        },
+        "metadata": {
+          "$ref": "#/definitions/metadata"
+        },
+        "provider": Provider::schema(spec_version)
+      },
+      "required": [
+        "consumer",
+        "provider",
+        "interactions",
+        "metadata"
+      ],
+      "definitions": schema::definitions()
+    })
+  }
 }
 
 impl Pact for V4Pact {
@@ -394,6 +429,7 @@ impl ReadWritePact for V4Pact {
               Both(i, _) => i.clone()
             }
           })
+          .unique_by(|i| i.normalised())
           .collect(),
         metadata: self.metadata.clone(),
         plugin_data: self.plugin_data.clone()
@@ -1271,6 +1307,35 @@ mod tests {
     expect!(merged_pact2.unwrap().interactions().len()).to(be_equal_to(1));
   }
 
+  #[test]
+  fn pact_merge_removes_duplicates_with_different_keys() {
+    let interaction = SynchronousHttp {
+      description: "Test Interaction".into(),
+      provider_states: vec![ProviderState { name: "Good state to be in".into(), params: hashmap!{} }],
+      response: HttpResponse { status: 400, .. HttpResponse::default() },
+      .. SynchronousHttp::default()
+    };
+    let pact = V4Pact {
+      consumer: Consumer { name: "test_consumer".into() },
+      provider: Provider { name: "test_provider".into() },
+      interactions: vec![
+        Box::new(SynchronousHttp { key: Some("1234567890".into()), .. interaction.clone() })
+      ],
+      .. V4Pact::default()
+    };
+    let pact2 = V4Pact {
+      consumer: Consumer { name: "test_consumer".into() },
+      provider: Provider { name: "test_provider".into() },
+      interactions: vec![
+        Box::new(SynchronousHttp { id: Some("abc".into()), key: Some("0987654321".into()), .. interaction.clone() })
+      ],
+      .. V4Pact::default()
+    };
+
+    let merged_pact = pact.merge(&pact2);
+    expect!(merged_pact.unwrap().interactions().len()).to(be_equal_to(1));
+  }
+
   #[test]
   fn write_v2_pact_test_with_matchers() {
     let pact = V4Pact {
@@ -0,0 +1,447 @@
+//! JSON Schema for the V4 Pact file format. The schema is assembled from the `schema` functions
+//! of the V4 models, and the tests check that the JSON written by the models validates against it.
+
+use serde_json::{json, Value};
+
+use crate::PactSpecification;
+use crate::v4::interaction::InteractionMarkup;
+use crate::v4::pact::V4Pact;
+use crate::v4::V4InteractionType;
+use crate::provider_states::ProviderState;
+
+/// Version of the generated V4 Pact file schema. This is incremented whenever the schema changes.
+pub const V4_SCHEMA_VERSION: &str = "1.0.0";
+
+/// Returns the JSON Schema (draft 2020-12) for V4 Pact files
+pub fn v4_pact_schema() -> Value {
+  let mut schema = json!({
+    "$schema": "https://json-schema.org/draft/2020-12/schema",
+    "$id": "https://pact.io/schema/pact-V4.json",
+    "title": "Pact File V4 Schema",
+    "description": "JSON schema for a V4 specification pact file",
+    "version": V4_SCHEMA_VERSION,
+    "type": "object"
+  });
+
+  if let (Some(map), Value::Object(attributes)) = (schema.as_object_mut(), V4Pact::schema(PactSpecification::V4)) {
+    map.extend(attributes);
+  }
+
+  schema
+}
+
+/// Shared definitions referenced by the schemas of the V4 models
+pub(crate) fn definitions() -> Value {
+  json!({
+    "body": {
+      "description": "Body of a request, response or message",
+      "type": "object",
+      "properties": {
+        "content": {
+          "description": "Contents of the body. JSON bodies are stored as JSON, text bodies as a string and binary bodies as a Base64 encoded string"
+        },
+        "contentType": {
+          "description": "Content type of the body",
+          "type": "string"
+        },
+        "contentTypeHint": {
+          "description": "Overrides how the contents are stored",
+          "enum": ["BINARY", "TEXT", "DEFAULT"]
+        },
+        "encoded": {
+          "description": "false if the contents are not encoded, otherwise the encoding used (i.e. base64)",
+          "type": ["boolean", "string"]
+        }
+      }
+    },
+    "matchingRules": {
+      "description": "Matching rules, keyed by category",
+      "type": "object",
+      "properties": {
+        "path": { "$ref": "#/definitions/ruleList" }
+      },
+      "additionalProperties": {
+        "description": "Matching rules for the category, keyed by the path or name they apply to",
+        "type": "object",
+        "additionalProperties": { "$ref": "#/definitions/ruleList" }
+      }
+    },
+    "ruleList": {
+      "type": "object",
+      "properties": {
+        "combine": {
+          "description": "How the results of the matchers are combined",
+          "enum": ["AND", "OR"]
+        },
+        "matchers": {
+          "type": "array",
+          "items": { "$ref": "#/definitions/matcher" }
+        }
+      },
+      "required": ["matchers"]
+    },
+    "matcher": {
+      "type": "object",
+      "properties": {
+        "match": {
+          "description": "Type of matcher (i.e. type, regex, equality, include, number, etc.)",
+          "type": "string"
+        }
+      },
+      "required": ["match"]
+    },
+    "generators": {
+      "description": "Generators, keyed by category",
+      "type": "object",
+      "properties": {
+        "method": { "$ref": "#/definitions/generator" },
+        "path": { "$ref": "#/definitions/generator" },
+        "status": { "$ref": "#/definitions/generator" }
+      },
+      "additionalProperties": {
+        "description": "Generators for the category, keyed by the path or name they apply to",
+        "type": "object",
+        "additionalProperties": { "$ref": "#/definitions/generator" }
+      }
+    },
+    "generator": {
+      "type": "object",
+      "properties": {
+        "type": {
+          "description": "Type of generator (i.e. RandomInt, Uuid, ProviderState, etc.)",
+          "type": "string"
+        }
+      },
+      "required": ["type"]
+    },
+    "metadata": {
+      "description": "Metadata associated with the Pact file",
+      "type": "object",
+      "properties": {
+        "pactSpecification": {
+          "type": "object",
+          "properties": {
+            "version": {
+              "type": "string"
+            }
+          },
+          "required": ["version"]
+        },
+        "pactRust": {
+          "description": "Versions of the Pact Rust libraries used to write the file",
+          "type": "object"
+        },
+        "plugins": {
+          "description": "Plugins used by the interactions",
+          "type": "array",
+          "items": {
+            "type": "object",
+            "properties": {
+              "name": { "type": "string" },
+              "version": { "type": "string" },
+              "configuration": { "type": "object" }
+            },
+            "required": ["name", "version"]
+          }
+        }
+      },
+      "required": ["pactSpecification"]
+    }
+  })
+}
+
+/// Builds the schema for a V4 interaction from the attributes common to all interactions and the
+/// attributes specific to the interaction type
+pub(crate) fn interaction_schema(
+  interaction_type: V4InteractionType,
+  properties: Value,
+  required: &[&str]
+) -> Value {
+  let mut attributes = json!({
+    "type": {
+      "const": interaction_type.to_string()
+    },
+    "key": {
+      "description": "Unique key for the interaction",
+      "type": "string"
+    },
+    "description": {
+      "type": "string"
+    },
+    "providerStates": {
+      "type": "array",
+      "items": ProviderState::schema(PactSpecification::V4)
+    },
+    "pending": {
+      "description": "If the interaction is pending and should not fail verification",
+      "type": "boolean"
+    },
+    "comments": {
+      "description": "Comments and test names associated with the interaction",
+      "type": "object"
+    },
+    "pluginConfiguration": {
+      "description": "Configuration added by plugins, keyed by plugin name",
+      "type": "object",
+      "additionalProperties": {
+        "type": "object"
+      }
+    },
+    "interactionMarkup": InteractionMarkup::schema(PactSpecification::V4),
+    "config": {
+      "description": "Configuration for the interaction",
+      "type": "object"
+    },
+    "transport": {
+      "description": "Transport the interaction is for",
+      "type": "string"
+    }
+  });
+  if let (Some(map), Value::Object(properties)) = (attributes.as_object_mut(), properties) {
+    map.extend(properties);
+  }
+
+  let mut required_attributes = vec!["type", "description"];
+  required_attributes.extend_from_slice(required);
+
+  json!({
+    "description": format!("{} interaction", interaction_type),
+    "type": "object",
+    "properties": attributes,
+    "required": required_attributes
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+  use serde_json::{json, Value};
+
+  use crate::pact::Pact;
+  use crate::PactSpecification;
+  use crate::v4::pact::V4Pact;
+
+  use super::{v4_pact_schema, V4_SCHEMA_VERSION};
+
+  // Minimal validator supporting the subset of JSON Schema used by the V4 schema
+  fn validate(root: &Value, schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+      let pointer = reference.trim_start_matches('#');
+      match root.pointer(pointer) {
+        Some(schema) => validate(root, schema, value, path, errors),
+        None => errors.push(format!("{}: unresolved reference '{}'", path, reference))
+      }
+      return;
+    }
+
+    if let Some(types) = schema.get("type") {
+      let types = match types {
+        Value::Array(types) => types.iter().filter_map(|t| t.as_str()).collect(),
+        Value::String(t) => vec![t.as_str()],
+        _ => vec![]
+      };
+      let matches_type = types.iter().any(|t| match *t {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "null" => value.is_null(),
+        _ => false
+      });
+      if !matches_type {
+        errors.push(format!("{}: expected {:?}, got {}", path, types, value));
+        return;
+      }
+    }
+
+    if let Some(expected) = schema.get("const") {
+      if expected != value {
+        errors.push(format!("{}: expected {}, got {}", path, expected, value));
+      }
+    }
+
+    if let Some(Value::Array(values)) = schema.get("enum") {
+      if !values.contains(value) {
+        errors.push(format!("{}: {} is not one of {:?}", path, value, values));
+      }
+    }
+
+    if let Some(Value::Array(schemas)) = schema.get("oneOf") {
+      let matched = schemas.iter().filter(|schema| {
+        let mut e = vec![];
+        validate(root, schema, value, path, &mut e);
+        e.is_empty()
+      }).count();
+      if matched != 1 {
+        errors.push(format!("{}: expected exactly one schema to match, but {} did", path, matched));
+      }
+    }
+
+    if let Value::Object(map) = value {
+      if let Some(Value::Array(required)) = schema.get("required") {
+        for attribute in required.iter().filter_map(|r| r.as_str()) {
+          if !map.contains_key(attribute) {
+            errors.push(format!("{}: missing required attribute '{}'", path, attribute));
+          }
+        }
+      }
+      let properties = schema.get("properties").and_then(|p| p.as_object());
+      for (key, item) in map {
+        let item_path = format!("{}/{}", path, key);
+        match properties.and_then(|p| p.get(key)) {
+          Some(schema) => validate(root, schema, item, &item_path, errors),
+          None => match schema.get("additionalProperties") {
+            Some(Value::Bool(false)) => errors.push(format!("{}: unexpected attribute", item_path)),
+            Some(schema @ Value::Object(_)) => validate(root, schema, item, &item_path, errors),
+            _ => ()
+          }
+        }
+      }
+    }
+
+    if let (Value::Array(items), Some(schema)) = (value, schema.get("items")) {
+      for (index, item) in items.iter().enumerate() {
+        validate(root, schema, item, &format!("{}/{}", path, index), errors);
+      }
+    }
+  }
+
+  fn validation_errors(pact_json: &Value) -> Vec<String> {
+    let schema = v4_pact_schema();
+    let mut errors = vec![];
+    validate(&schema, &schema, pact_json, "", &mut errors);
+    errors
+  }
+
+  #[test]
+  fn schema_is_versioned() {
+    let schema = v4_pact_schema();
+    expect!(schema["$schema"].as_str()).to(be_some().value("https://json-schema.org/draft/2020-12/schema"));
+    expect!(schema["version"].as_str()).to(be_some().value(V4_SCHEMA_VERSION));
+    expect!(schema["required"].clone()).to(be_equal_to(json!(["consumer", "provider", "interactions", "metadata"])));
+  }
+
+  #[test]
+  fn pact_written_by_the_models_validates_against_the_schema() {
+    let pact = V4Pact::pact_from_json(&json!({
+      "consumer": { "name": "consumer" },
+      "provider": { "name": "provider" },
+      "interactions": [
+        {
+          "type": "Synchronous/HTTP",
+          "key": "001",
+          "description": "get a user",
+          "providerStates": [ { "name": "a user exists", "params": { "id": 100 } } ],
+          "request": {
+            "method": "GET",
+            "path": "/users/100",
+            "query": { "fields": ["name", "email"] },
+            "headers": { "Accept": ["application/json"] },
+            "matchingRules": {
+              "path": { "combine": "AND", "matchers": [ { "match": "regex", "regex": "/users/\\d+" } ] },
+              "header": { "Accept": { "matchers": [ { "match": "include", "value": "json" } ] } }
+            },
+            "generators": {
+              "path": { "type": "ProviderState", "expression": "/users/${id}" }
+            }
+          },
+          "response": {
+            "status": 200,
+            "headers": { "Content-Type": ["application/json"] },
+            "body": {
+              "content": { "id": 100, "name": "Fred" },
+              "contentType": "application/json",
+              "encoded": false
+            },
+            "matchingRules": {
+              "body": { "$.id": { "combine": "AND", "matchers": [ { "match": "integer" } ] } }
+            },
+            "generators": {
+              "body": { "$.id": { "type": "RandomInt", "min": 1, "max": 1000 } }
+            }
+          },
+          "comments": { "testname": "get_user_test" },
+          "pluginConfiguration": { "test-plugin": { "key": "value" } },
+          "interactionMarkup": { "markup": "# Get a user", "markupType": "COMMON_MARK" },
+          "transport": "http"
+        },
+        {
+          "type": "Asynchronous/Messages",
+          "description": "a binary message",
+          "contents": {
+            "content": "AQID",
+            "contentType": "application/octet-stream",
+            "contentTypeHint": "BINARY",
+            "encoded": "base64"
+          },
+          "metadata": { "queue": "events" },
+          "pending": true
+        },
+        {
+          "type": "Synchronous/Messages",
+          "description": "a request for a quote",
+          "request": {
+            "contents": { "content": "quote?", "contentType": "text/plain", "encoded": false }
+          },
+          "response": [
+            {
+              "contents": { "content": { "price": 100 }, "contentType": "application/json", "encoded": false },
+              "matchingRules": {
+                "body": { "$.price": { "combine": "AND", "matchers": [ { "match": "number" } ] } }
+              }
+            }
+          ]
+        }
+      ],
+      "metadata": {
+        "pactSpecification": { "version": "4.0" },
+        "plugins": [ { "name": "test-plugin", "version": "1.0.0", "configuration": {} } ]
+      }
+    }), "test").unwrap();
+
+    let pact_json = pact.to_json(PactSpecification::V4).unwrap();
+    expect!(pact_json["interactions"].as_array().map(|i| i.len())).to(be_some().value(3));
+    expect!(validation_errors(&pact_json)).to(be_equal_to(Vec::<String>::new()));
+
+    let empty_pact = V4Pact::default().to_json(PactSpecification::V4).unwrap();
+    expect!(validation_errors(&empty_pact)).to(be_equal_to(Vec::<String>::new()));
+  }
+
+  #[test]
+  fn schema_rejects_invalid_pact_files() {
+    let pact_json = json!({
+      "consumer": { "name": "consumer" },
+      "provider": { "name": "provider" },
+      "interactions": [
+        {
+          "type": "Synchronous/HTTP",
+          "description": "missing the response",
+          "request": { "method": "GET", "path": "/" }
+        },
+        {
+          "type": "Synchronous/Carrier-Pigeon",
+          "description": "unknown interaction type"
+        }
+      ],
+      "metadata": {
+        "pactSpecification": { "version": "4.0" }
+      }
+    });
+    expect!(validation_errors(&pact_json)).to(be_equal_to(vec![
+      "/interactions/0: expected exactly one schema to match, but 0 did".to_string(),
+      "/interactions/1: expected exactly one schema to match, but 0 did".to_string()
+    ]));
+
+    let pact_json = json!({
+      "consumer": { "name": "consumer" },
+      "interactions": {}
+    });
+    expect!(validation_errors(&pact_json)).to(be_equal_to(vec![
+      ": missing required attribute 'provider'".to_string(),
+      ": missing required attribute 'metadata'".to_string(),
+      "/interactions: expected [\"array\"], got {}".to_string()
+    ]));
+  }
+}
@@ -18,11 +18,13 @@ use crate::interaction::Interaction;
 use crate::json_utils::{is_empty, json_to_string};
 use crate::matchingrules::MatchingRules;
 use crate::message::Message;
+use crate::PactSpecification;
 use crate::provider_states::ProviderState;
 use crate::sync_interaction::RequestResponseInteraction;
 use crate::v4::async_message::AsynchronousMessage;
-use crate::v4::interaction::{InteractionMarkup, parse_plugin_config, V4Interaction};
+use crate::v4::interaction::{hash_interaction_config, InteractionMarkup, parse_interaction_config, parse_plugin_config, V4Interaction};
 use crate::v4::message_parts::MessageContents;
+use crate::v4::schema::interaction_schema;
 use crate::v4::synch_http::SynchronousHttp;
 use crate::v4::V4InteractionType;
 
@@ -54,11 +56,25 @@ pub struct SynchronousMessage {
   /// Text markup to use to render the interaction in a UI
   pub interaction_markup: InteractionMarkup,
 
+  /// Configuration for how the interaction is matched
+  pub config: HashMap<String, Value>,
+
   /// Transport mechanism used with this message
   pub transport: Option<String>
 }
 
 impl SynchronousMessage {
+  /// Generate the JSON schema properties for the given Pact specification
+  pub fn schema(spec_version: PactSpecification) -> Value {
+    interaction_schema(V4InteractionType::Synchronous_Messages, json!({
+      "request": MessageContents::schema(spec_version),
+      "response": {
+        "type": "array",
+        "items": MessageContents::schema(spec_version)
+      }
+    }), &["request", "response"])
+  }
+
   fn calc_hash(&self) -> String {
     let mut s = DefaultHasher::new();
     self.hash(&mut s);
@@ -111,6 +127,7 @@ impl SynchronousMessage {
           .collect::<Vec<anyhow::Result<MessageContents>>>();
 
       let plugin_config = parse_plugin_config(json);
+      let config = parse_interaction_config(json);
       let interaction_markup = json.get("interactionMarkup")
         .map(|markup| InteractionMarkup::from_json(markup)).unwrap_or_default();
 
@@ -140,6 +157,7 @@ impl SynchronousMessage {
             .map(|value| value.as_bool().unwrap_or_default()).unwrap_or_default(),
           plugin_config,
           interaction_markup,
+          config,
           transport
         })
       }
@@ -188,6 +206,11 @@ impl V4Interaction for SynchronousMessage {
       map.insert("interactionMarkup".to_string(), self.interaction_markup.to_json());
     }
 
+    if !self.config.is_empty() {
+      map.insert("config".to_string(), Value::Object(self.config.iter()
+        .map(|(k, v)| (k.clone(), v.clone())).collect()));
+    }
+
     if let Some(transport) = &self.transport {
       map.insert("transport".to_string(), Value::String(transport.clone()));
     }
@@ -239,6 +262,14 @@ impl V4Interaction for SynchronousMessage {
     &mut self.interaction_markup
   }
 
+  fn config(&self) -> HashMap<String, Value> {
+    self.config.clone()
+  }
+
+  fn set_config(&mut self, config: HashMap<String, Value>) {
+    self.config = config;
+  }
+
   fn transport(&self) -> Option<String> {
     self.transport.clone()
   }
@@ -390,6 +421,7 @@ impl Default for SynchronousMessage {
       pending: false,
       plugin_config: Default::default(),
       interaction_markup: Default::default(),
+      config: Default::default(),
       transport: None
     }
   }
@@ -402,7 +434,8 @@ impl PartialEq for SynchronousMessage {
       self.provider_states == other.provider_states &&
       self.request == other.request &&
       self.response == other.response &&
-      self.pending == other.pending
+      self.pending == other.pending &&
+      self.config == other.config
   }
 }
 
@@ -413,6 +446,7 @@ impl Hash for SynchronousMessage {
     self.request.hash(state);
     self.response.hash(state);
     self.pending.hash(state);
+    hash_interaction_config(&self.config, state);
   }
 }
 
@@ -17,11 +17,13 @@ use crate::interaction::Interaction;
 use crate::json_utils::{is_empty, json_to_string};
 use crate::matchingrules::MatchingRules;
 use crate::message::Message;
+use crate::PactSpecification;
 use crate::provider_states::ProviderState;
 use crate::sync_interaction::RequestResponseInteraction;
 use crate::v4::async_message::AsynchronousMessage;
 use crate::v4::http_parts::{HttpRequest, HttpResponse};
-use crate::v4::interaction::{InteractionMarkup, parse_plugin_config, V4Interaction};
+use crate::v4::interaction::{hash_interaction_config, InteractionMarkup, parse_interaction_config, parse_plugin_config, V4Interaction};
+use crate::v4::schema::interaction_schema;
 use crate::v4::sync_message::SynchronousMessage;
 use crate::v4::V4InteractionType;
 
@@ -53,11 +55,22 @@ pub struct SynchronousHttp {
   /// Text markup to use to render the interaction in a UI
   pub interaction_markup: InteractionMarkup,
 
+  /// Configuration for how the interaction is matched
+  pub config: HashMap<String, Value>,
+
   /// Transport mechanism used with this request and response
   pub transport: Option<String>
 }
 
 impl SynchronousHttp {
+  /// Generate the JSON schema properties for the given Pact specification
+  pub fn schema(spec_version: PactSpecification) -> Value {
+    interaction_schema(V4InteractionType::Synchronous_HTTP, json!({
+      "request": HttpRequest::schema(spec_version),
+      "response": HttpResponse::schema(spec_version)
+    }), &["request", "response"])
+  }
+
   fn calc_hash(&self) -> String {
     let mut s = DefaultHasher::new();
     self.hash(&mut s);
@@ -102,6 +115,7 @@ impl SynchronousHttp {
       let response = json.get("response").cloned().unwrap_or_default();
 
       let plugin_config = parse_plugin_config(json);
+      let config = parse_interaction_config(json);
 
       let interaction_markup = json.get("interactionMarkup")
         .map(|markup| InteractionMarkup::from_json(markup)).unwrap_or_default();
@@ -125,6 +139,7 @@ impl SynchronousHttp {
           .map(|value| value.as_bool().unwrap_or_default()).unwrap_or_default(),
         plugin_config,
         interaction_markup,
+        config,
         transport
       })
     } else {
@@ -172,6 +187,11 @@ impl V4Interaction for SynchronousHttp {
       map.insert("interactionMarkup".to_string(), self.interaction_markup.to_json());
     }
 
+    if !self.config.is_empty() {
+      map.insert("config".to_string(), Value::Object(self.config.iter()
+        .map(|(k, v)| (k.clone(), v.clone())).collect()));
+    }
+
     if let Some(transport) = &self.transport {
       map.insert("transport".to_string(), Value::String(transport.clone()));
     }
@@ -223,6 +243,14 @@ impl V4Interaction for SynchronousHttp {
     &mut self.interaction_markup
   }
 
+  fn config(&self) -> HashMap<String, Value> {
+    self.config.clone()
+  }
+
+  fn set_config(&mut self, config: HashMap<String, Value>) {
+    self.config = config;
+  }
+
   fn transport(&self) -> Option<String> {
     self.transport.clone()
   }
@@ -380,6 +408,7 @@ impl Default for SynchronousHttp {
       pending: false,
       plugin_config: Default::default(),
       interaction_markup: Default::default(),
+      config: Default::default(),
       transport: None
     }
   }
@@ -392,7 +421,8 @@ impl PartialEq for SynchronousHttp {
     self.provider_states == other.provider_states &&
     self.request == other.request &&
     self.response == other.response &&
-    self.pending == other.pending
+    self.pending == other.pending &&
+    self.config == other.config
   }
 }
 
@@ -403,6 +433,7 @@ impl Hash for SynchronousHttp {
     self.request.hash(state);
     self.response.hash(state);
     self.pending.hash(state);
+    hash_interaction_config(&self.config, state);
   }
 }
 
@@ -731,4 +762,39 @@ mod tests {
     assert_ne!(i1, i2);
     assert_ne!(i2, i1);
   }
+
+  #[test]
+  fn interaction_config_is_loaded_from_and_written_to_json() {
+    let interaction = SynchronousHttp::from_json(&json!({
+      "description": "a retrieve Mallory request",
+      "config": {
+        "contentMatcher": "application/json"
+      },
+      "type": "Synchronous/HTTP"
+    }), 0).unwrap();
+    expect!(interaction.config.clone()).to(be_equal_to(hashmap!{
+      "contentMatcher".to_string() => json!("application/json")
+    }));
+    expect!(interaction.to_json().get("config").cloned()).to(be_some().value(json!({
+      "contentMatcher": "application/json"
+    })));
+    expect!(SynchronousHttp::default().to_json().get("config").cloned()).to(be_none());
+  }
+
+  #[test]
+  fn equality_and_hash_include_the_interaction_config() {
+    let i1 = SynchronousHttp {
+      description: "a retrieve Mallory request".to_string(),
+      .. SynchronousHttp::default()
+    };
+    let i2 = SynchronousHttp {
+      config: hashmap!{
+        "contentMatcher".to_string() => json!("application/json")
+      },
+      .. i1.clone()
+    };
+
+    assert_ne!(i1, i2);
+    assert_ne!(i1.unique_key(), i2.unique_key());
+  }
 }
@@ -0,0 +1,832 @@
+//! Structural validation of Pact JSON documents. Problems are reported with the JSON pointer to
+//! where they occurred, a severity and (where possible) a hint on how to fix them, instead of
+//! failing deep inside the parsing of the Pact.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{Consumer, PactSpecification, Provider};
+use crate::generators::{Generator, GeneratorCategory};
+use crate::matchingrules::{Category, MatchingRule};
+use crate::matchingrules::compatibility::normalise_matcher_json;
+use crate::pact::{determine_spec_version, parse_meta_data, verify_metadata};
+use crate::path_exp::DocPath;
+use crate::provider_states::ProviderState;
+use crate::sync_pact::RequestResponsePact;
+use crate::v4::V4InteractionType;
+use crate::verify_json::{json_type_of, PactFileVerificationResult, PactJsonVerifier, ResultLevel};
+
+/// Problem found while validating a Pact JSON document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidationIssue {
+  /// JSON pointer (RFC 6901) to the location of the problem. The root of the document is an empty
+  /// string.
+  pub pointer: String,
+  /// Severity of the problem
+  pub severity: ResultLevel,
+  /// Description of the problem
+  pub message: String,
+  /// Hint on how to fix the problem
+  pub hint: Option<String>
+}
+
+impl ValidationIssue {
+  fn error<P: Into<String>, M: Into<String>>(pointer: P, message: M, hint: Option<String>) -> Self {
+    ValidationIssue {
+      pointer: pointer.into(),
+      severity: ResultLevel::ERROR,
+      message: message.into(),
+      hint
+    }
+  }
+
+  fn warning<P: Into<String>, M: Into<String>>(pointer: P, message: M, hint: Option<String>) -> Self {
+    ValidationIssue {
+      pointer: pointer.into(),
+      severity: ResultLevel::WARNING,
+      message: message.into(),
+      hint
+    }
+  }
+}
+
+impl Display for ValidationIssue {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} at '{}': {}", self.severity, self.pointer, self.message)?;
+    if let Some(hint) = &self.hint {
+      write!(f, " ({})", hint)?;
+    }
+    Ok(())
+  }
+}
+
+impl From<PactFileVerificationResult> for ValidationIssue {
+  fn from(result: PactFileVerificationResult) -> Self {
+    let hint = hint_for_message(result.message.as_str());
+    ValidationIssue {
+      pointer: if result.path == "/" { String::default() } else { result.path },
+      severity: result.level,
+      message: result.message,
+      hint
+    }
+  }
+}
+
+fn hint_for_message(message: &str) -> Option<String> {
+  if message.starts_with("Unexpected attribute") {
+    Some("Remove the attribute, or check the spelling of the attribute name".to_string())
+  } else if let Some(missing) = message.strip_prefix("Missing ") {
+    Some(format!("Add the {} attribute", missing))
+  } else if let Some((_, replacement)) = message.split_once("is deprecated, use ") {
+    Some(format!("Replace it with {}", replacement.trim_end_matches(" instead")))
+  } else {
+    None
+  }
+}
+
+/// Validates the structure of the Pact JSON, returning any problems found. If the specification
+/// version is `Unknown`, it will be determined from the metadata in the Pact JSON. An empty result
+/// means the JSON is a structurally valid Pact.
+pub fn validate_pact_json(pact_json: &Value, spec_version: PactSpecification) -> Vec<ValidationIssue> {
+  let spec_version = match spec_version {
+    PactSpecification::Unknown => determine_spec_version("<json>", &parse_meta_data(pact_json)),
+    _ => spec_version
+  };
+
+  let mut validator = Validator { spec_version, issues: vec![] };
+  match pact_json {
+    Value::Object(values) => if spec_version >= PactSpecification::V4 {
+      validator.validate_v4_pact(values);
+    } else if values.contains_key("messages") {
+      validator.validate_message_pact(values);
+    } else {
+      validator.validate_request_response_pact(pact_json, values);
+    }
+    _ => validator.issues.push(ValidationIssue::error("", format!("Must be an Object, got {}", json_type_of(pact_json)),
+      Some("A Pact file must be a JSON Object with consumer, provider and interactions attributes".to_string())))
+  }
+  validator.issues
+}
+
+/// Returns the JSON pointer to the child of the given pointer
+fn child_pointer<S: Display>(pointer: &str, segment: S) -> String {
+  format!("{}/{}", pointer, segment.to_string().replace('~', "~0").replace('/', "~1"))
+}
+
+struct Validator {
+  spec_version: PactSpecification,
+  issues: Vec<ValidationIssue>
+}
+
+impl Validator {
+  fn extend_from_results(&mut self, results: Vec<PactFileVerificationResult>) {
+    self.issues.extend(results.into_iter().map(ValidationIssue::from));
+  }
+
+  fn type_error(&mut self, pointer: &str, expected: &str, value: &Value) {
+    self.issues.push(ValidationIssue::error(pointer, format!("Must be {}, got {}", expected, json_type_of(value)),
+      Some(format!("Change the value to {}", expected))));
+  }
+
+  fn unexpected_attributes(&mut self, pointer: &str, values: &Map<String, Value>, valid: &[&str]) {
+    for key in values.keys() {
+      if !valid.contains(&key.as_str()) {
+        self.issues.push(ValidationIssue::warning(child_pointer(pointer, key),
+          format!("Unexpected attribute '{}'", key),
+          Some(format!("Remove the attribute, or check the spelling (expected one of {})", valid.join(", ")))));
+      }
+    }
+  }
+
+  fn expect_string(&mut self, pointer: &str, values: &Map<String, Value>, key: &str, required: bool) {
+    match values.get(key) {
+      Some(Value::String(_)) => {}
+      Some(value) => self.type_error(child_pointer(pointer, key).as_str(), "a String", value),
+      None => if required {
+        self.issues.push(ValidationIssue::error(child_pointer(pointer, key), format!("Missing {}", key),
+          Some(format!("Add the {} attribute", key))));
+      }
+    }
+  }
+
+  fn expect_bool(&mut self, pointer: &str, values: &Map<String, Value>, key: &str) {
+    if let Some(value) = values.get(key) {
+      if !value.is_boolean() {
+        self.type_error(child_pointer(pointer, key).as_str(), "a Boolean", value);
+      }
+    }
+  }
+
+  fn expect_object(&mut self, pointer: &str, values: &Map<String, Value>, key: &str) {
+    if let Some(value) = values.get(key) {
+      if !value.is_object() {
+        self.type_error(child_pointer(pointer, key).as_str(), "an Object", value);
+      }
+    }
+  }
+
+  fn validate_participants(&mut self, values: &Map<String, Value>) {
+    match values.get("consumer") {
+      Some(consumer) => self.extend_from_results(Consumer::verify_json("/consumer", consumer, true, self.spec_version)),
+      None => self.issues.push(ValidationIssue::error("/consumer", "Missing consumer",
+        Some("Add a consumer attribute with the name of the consumer".to_string())))
+    }
+    match values.get("provider") {
+      Some(provider) => self.extend_from_results(Provider::verify_json("/provider", provider, true, self.spec_version)),
+      None => self.issues.push(ValidationIssue::error("/provider", "Missing provider",
+        Some("Add a provider attribute with the name of the provider".to_string())))
+    }
+    if let Some(metadata) = values.get("metadata") {
+      self.extend_from_results(verify_metadata(metadata, self.spec_version));
+    }
+  }
+
+  fn validate_provider_states(&mut self, pointer: &str, values: &Map<String, Value>) {
+    if let Some(states) = values.get("providerStates") {
+      match states {
+        Value::Array(states) => for (index, state) in states.iter().enumerate() {
+          let state_pointer = child_pointer(&child_pointer(pointer, "providerStates"), index);
+          self.extend_from_results(ProviderState::verify_json(&state_pointer, state, false, self.spec_version));
+        }
+        _ => self.type_error(child_pointer(pointer, "providerStates").as_str(), "an Array", states)
+      }
+    }
+  }
+
+  fn validate_request_response_pact(&mut self, pact_json: &Value, values: &Map<String, Value>) {
+    self.extend_from_results(RequestResponsePact::verify_json("/", pact_json, false, self.spec_version));
+    if let Some(Value::Array(interactions)) = values.get("interactions") {
+      for (index, interaction) in interactions.iter().enumerate() {
+        let pointer = child_pointer("/interactions", index);
+        if let Value::Object(interaction) = interaction {
+          self.validate_request(&pointer, interaction);
+          self.validate_response(&pointer, interaction);
+        }
+      }
+    }
+  }
+
+  fn validate_message_pact(&mut self, values: &Map<String, Value>) {
+    self.validate_participants(values);
+    match values.get("messages") {
+      Some(Value::Array(messages)) => for (index, message) in messages.iter().enumerate() {
+        let pointer = child_pointer("/messages", index);
+        match message {
+          Value::Object(message) => {
+            self.expect_string(&pointer, message, "description", true);
+            self.validate_provider_states(&pointer, message);
+            self.validate_message_contents(&pointer, message);
+            self.unexpected_attributes(&pointer, message, &["_id", "description", "providerState", "providerStates",
+              "contents", "metaData", "metadata", "matchingRules", "generators"]);
+          }
+          _ => self.type_error(&pointer, "an Object", message)
+        }
+      }
+      Some(messages) => self.type_error("/messages", "an Array", messages),
+      None => {}
+    }
+    self.unexpected_attributes("", values, &["consumer", "provider", "messages", "metadata"]);
+  }
+
+  fn validate_v4_pact(&mut self, values: &Map<String, Value>) {
+    self.validate_participants(values);
+    match values.get("interactions") {
+      Some(Value::Array(interactions)) => for (index, interaction) in interactions.iter().enumerate() {
+        let pointer = child_pointer("/interactions", index);
+        match interaction {
+          Value::Object(interaction) => self.validate_v4_interaction(&pointer, interaction),
+          _ => self.type_error(&pointer, "an Object", interaction)
+        }
+      }
+      Some(interactions) => self.type_error("/interactions", "an Array", interactions),
+      None => self.issues.push(ValidationIssue::warning("/interactions", "Missing interactions",
+        Some("Add an interactions attribute with the interactions of the Pact".to_string())))
+    }
+    self.unexpected_attributes("", values, &["consumer", "provider", "interactions", "metadata"]);
+  }
+
+  fn validate_v4_interaction(&mut self, pointer: &str, interaction: &Map<String, Value>) {
+    let common = ["_id", "type", "key", "description", "providerStates", "pending", "comments",
+      "pluginConfiguration", "interactionMarkup", "transport"];
+    self.expect_string(pointer, interaction, "description", true);
+    self.expect_string(pointer, interaction, "key", false);
+    self.expect_bool(pointer, interaction, "pending");
+    self.expect_object(pointer, interaction, "comments");
+    self.expect_object(pointer, interaction, "pluginConfiguration");
+    self.validate_provider_states(pointer, interaction);
+
+    let interaction_type = match interaction.get("type") {
+      Some(Value::String(interaction_type)) => match V4InteractionType::from_str(interaction_type) {
+        Ok(interaction_type) => Some(interaction_type),
+        Err(err) => {
+          self.issues.push(ValidationIssue::error(child_pointer(pointer, "type"), err.to_string(),
+            Some("The interaction type must be one of Synchronous/HTTP, Asynchronous/Messages or Synchronous/Messages".to_string())));
+          None
+        }
+      },
+      Some(value) => {
+        self.type_error(child_pointer(pointer, "type").as_str(), "a String", value);
+        None
+      }
+      None => {
+        self.issues.push(ValidationIssue::error(child_pointer(pointer, "type"), "Missing type",
+          Some("Add a type attribute of Synchronous/HTTP, Asynchronous/Messages or Synchronous/Messages".to_string())));
+        None
+      }
+    };
+
+    match interaction_type {
+      Some(V4InteractionType::Synchronous_HTTP) => {
+        self.validate_request(pointer, interaction);
+        self.validate_response(pointer, interaction);
+        self.unexpected_attributes(pointer, interaction, &[&common[..], &["request", "response"]].concat());
+      }
+      Some(V4InteractionType::Asynchronous_Messages) => {
+        self.validate_message_contents(pointer, interaction);
+        self.unexpected_attributes(pointer, interaction,
+          &[&common[..], &["contents", "metadata", "matchingRules", "generators"]].concat());
+      }
+      Some(V4InteractionType::Synchronous_Messages) => {
+        match interaction.get("request") {
+          Some(Value::Object(request)) => self.validate_message_contents(&child_pointer(pointer, "request"), request),
+          Some(request) => self.type_error(child_pointer(pointer, "request").as_str(), "an Object", request),
+          None => self.issues.push(ValidationIssue::error(child_pointer(pointer, "request"), "Missing request",
+            Some("Add a request attribute with the request message".to_string())))
+        }
+        match interaction.get("response") {
+          Some(Value::Array(responses)) => for (index, response) in responses.iter().enumerate() {
+            let response_pointer = child_pointer(&child_pointer(pointer, "response"), index);
+            match response {
+              Value::Object(response) => self.validate_message_contents(&response_pointer, response),
+              _ => self.type_error(&response_pointer, "an Object", response)
+            }
+          }
+          Some(response) => self.type_error(child_pointer(pointer, "response").as_str(), "an Array", response),
+          None => {}
+        }
+        self.unexpected_attributes(pointer, interaction, &[&common[..], &["request", "response"]].concat());
+      }
+      None => {}
+    }
+  }
+
+  fn validate_message_contents(&mut self, pointer: &str, message: &Map<String, Value>) {
+    self.expect_object(pointer, message, "metadata");
+    self.expect_object(pointer, message, "metaData");
+    if let Some(rules) = message.get("matchingRules") {
+      self.validate_matching_rules(&child_pointer(pointer, "matchingRules"), rules,
+        &[Category::BODY, Category::METADATA, Category::CONTENTS]);
+    }
+    if let Some(generators) = message.get("generators") {
+      self.validate_generators(&child_pointer(pointer, "generators"), generators,
+        &[GeneratorCategory::BODY, GeneratorCategory::METADATA]);
+    }
+  }
+
+  fn validate_headers(&mut self, pointer: &str, values: &Map<String, Value>) {
+    self.validate_header_values(pointer, values, "headers");
+  }
+
+  fn validate_header_values(&mut self, pointer: &str, values: &Map<String, Value>, attribute: &str) {
+    match values.get(attribute) {
+      Some(Value::Object(headers)) => for (key, value) in headers {
+        let valid = match value {
+          Value::String(_) => true,
+          Value::Array(values) => self.spec_version >= PactSpecification::V3 && values.iter().all(|v| v.is_string()),
+          _ => false
+        };
+        if !valid {
+          self.type_error(child_pointer(&child_pointer(pointer, attribute), key).as_str(),
+            if self.spec_version >= PactSpecification::V3 { "a String or an Array of Strings" } else { "a String" }, value);
+        }
+      }
+      Some(headers) => self.type_error(child_pointer(pointer, attribute).as_str(), "an Object", headers),
+      None => {}
+    }
+  }
+
+  fn validate_request(&mut self, pointer: &str, interaction: &Map<String, Value>) {
+    let pointer = child_pointer(pointer, "request");
+    match interaction.get("request") {
+      Some(Value::Object(request)) => {
+        self.expect_string(&pointer, request, "method", false);
+        self.expect_string(&pointer, request, "path", false);
+        match request.get("query") {
+          Some(Value::String(_)) | None => {}
+          Some(Value::Object(query)) if self.spec_version >= PactSpecification::V3 => for (key, value) in query {
+            let valid = match value {
+              Value::String(_) => true,
+              Value::Array(values) => values.iter().all(|v| v.is_string() || v.is_null()),
+              _ => false
+            };
+            if !valid {
+              self.type_error(child_pointer(&child_pointer(&pointer, "query"), key).as_str(), "an Array of Strings", value);
+            }
+          }
+          Some(query) => self.type_error(child_pointer(&pointer, "query").as_str(),
+            if self.spec_version >= PactSpecification::V3 { "an Object" } else { "a String" }, query)
+        }
+        self.validate_headers(&pointer, request);
+        if let Some(rules) = request.get("matchingRules") {
+          self.validate_matching_rules(&child_pointer(&pointer, "matchingRules"), rules,
+            &[Category::METHOD, Category::PATH, Category::QUERY, Category::HEADER, Category::BODY]);
+        }
+        if let Some(generators) = request.get("generators") {
+          self.validate_generators(&child_pointer(&pointer, "generators"), generators,
+            &[GeneratorCategory::METHOD, GeneratorCategory::PATH, GeneratorCategory::QUERY, GeneratorCategory::HEADER,
+              GeneratorCategory::BODY]);
+        }
+        self.unexpected_attributes(&pointer, request, &["method", "path", "query", "headers", "body",
+          "matchingRules", "generators"]);
+      }
+      Some(request) => self.type_error(&pointer, "an Object", request),
+      None => self.issues.push(ValidationIssue::error(pointer, "Missing request",
+        Some("Add a request attribute with the expected HTTP request".to_string())))
+    }
+  }
+
+  fn validate_response(&mut self, pointer: &str, interaction: &Map<String, Value>) {
+    let pointer = child_pointer(pointer, "response");
+    match interaction.get("response") {
+      Some(Value::Object(response)) => {
+        match response.get("status") {
+          Some(Value::Number(status)) => match status.as_u64() {
+            Some(status) if (100..600).contains(&status) => {}
+            _ => self.issues.push(ValidationIssue::error(child_pointer(&pointer, "status"),
+              format!("{} is not a valid HTTP status code", status),
+              Some("The status must be an integer between 100 and 599".to_string())))
+          }
+          Some(status) => self.type_error(child_pointer(&pointer, "status").as_str(), "a Number", status),
+          None => {}
+        }
+        self.validate_headers(&pointer, response);
+        let v4 = self.spec_version >= PactSpecification::V4;
+        if v4 {
+          self.validate_header_values(&pointer, response, "trailers");
+        }
+        if let Some(rules) = response.get("matchingRules") {
+          self.validate_matching_rules(&child_pointer(&pointer, "matchingRules"), rules,
+            if v4 {
+              &[Category::STATUS, Category::HEADER, Category::BODY, Category::TRAILER]
+            } else {
+              &[Category::STATUS, Category::HEADER, Category::BODY]
+            });
+        }
+        if let Some(generators) = response.get("generators") {
+          self.validate_generators(&child_pointer(&pointer, "generators"), generators,
+            &[GeneratorCategory::STATUS, GeneratorCategory::HEADER, GeneratorCategory::BODY]);
+        }
+        if v4 {
+          self.unexpected_attributes(&pointer, response, &["status", "headers", "trailers", "body", "matchingRules", "generators"]);
+        } else {
+          self.unexpected_attributes(&pointer, response, &["status", "headers", "body", "matchingRules", "generators"]);
+        }
+      }
+      Some(response) => self.type_error(&pointer, "an Object", response),
+      None => self.issues.push(ValidationIssue::error(pointer, "Missing response",
+        Some("Add a response attribute with the expected HTTP response".to_string())))
+    }
+  }
+
+  fn validate_matching_rules(&mut self, pointer: &str, rules: &Value, categories: &[Category]) {
+    match rules {
+      Value::Object(rules) => if rules.keys().next().map(|key| key.starts_with('$')).unwrap_or(false) {
+        self.validate_v2_matching_rules(pointer, rules);
+      } else {
+        for (key, value) in rules {
+          let category_pointer = child_pointer(pointer, key);
+          match Category::from_str(key) {
+            Ok(category) if categories.contains(&category) => self.validate_matching_rule_category(
+              &category_pointer, &category, value),
+            _ => self.issues.push(ValidationIssue::error(category_pointer,
+              format!("'{}' is not a valid matching rule category here", key),
+              Some(format!("The category must be one of {}",
+                categories.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(", ")))))
+          }
+        }
+      }
+      _ => self.type_error(pointer, "an Object", rules)
+    }
+  }
+
+  fn validate_v2_matching_rules(&mut self, pointer: &str, rules: &Map<String, Value>) {
+    if self.spec_version >= PactSpecification::V3 {
+      self.issues.push(ValidationIssue::warning(pointer, "Matching rules are in the V2 format",
+        Some("V3 and later Pacts group the matching rules by category (i.e. body, header, query)".to_string())));
+    }
+    for (key, value) in rules {
+      let rule_pointer = child_pointer(pointer, key);
+      match DocPath::new(key) {
+        Ok(_) if ["$.body", "$.headers", "$.header", "$.query", "$.path", "$.method", "$.status"].iter()
+          .any(|prefix| key == prefix || key.starts_with(&format!("{}.", prefix)) || key.starts_with(&format!("{}[", prefix))) => {
+          self.validate_matcher(&rule_pointer, value);
+        }
+        Ok(_) => self.issues.push(ValidationIssue::error(rule_pointer,
+          format!("'{}' is not a valid path for a V2 matching rule", key),
+          Some("V2 matching rule paths must start with $.body, $.headers, $.query or $.path".to_string()))),
+        Err(err) => self.issues.push(ValidationIssue::error(rule_pointer,
+          format!("'{}' is not a valid matching rule path: {}", key, err),
+          Some("Matching rule paths must be valid path expressions, e.g. $.body.items[*].id".to_string())))
+      }
+    }
+  }
+
+  fn validate_matching_rule_category(&mut self, pointer: &str, category: &Category, rules: &Value) {
+    match rules {
+      Value::Object(map) => if map.contains_key("matchers") {
+        self.validate_rule_list(pointer, rules);
+      } else if *category == Category::PATH || *category == Category::METHOD || *category == Category::STATUS {
+        self.issues.push(ValidationIssue::error(pointer, "Missing matchers",
+          Some(format!("The {} category must have a matchers attribute with the list of matchers", category))));
+      } else {
+        for (key, value) in map {
+          let rule_pointer = child_pointer(pointer, key);
+          if *category == Category::BODY || *category == Category::CONTENTS {
+            if let Err(err) = DocPath::new(key) {
+              self.issues.push(ValidationIssue::error(rule_pointer,
+                format!("'{}' is not a valid matching rule path: {}", key, err),
+                Some("Matching rule paths must be valid path expressions, e.g. $.items[*].id".to_string())));
+              continue;
+            } else if !key.starts_with('$') {
+              self.issues.push(ValidationIssue::warning(rule_pointer.as_str(),
+                format!("Matching rule path '{}' does not start with '$'", key),
+                Some(format!("Body matching rule paths should start from the root, e.g. $.{}", key))));
+            }
+          }
+          self.validate_rule_list(&rule_pointer, value);
+        }
+      }
+      _ => self.type_error(pointer, "an Object", rules)
+    }
+  }
+
+  fn validate_rule_list(&mut self, pointer: &str, rule_list: &Value) {
+    match rule_list {
+      Value::Object(map) => {
+        match map.get("matchers") {
+          Some(Value::Array(matchers)) => for (index, matcher) in matchers.iter().enumerate() {
+            self.validate_matcher(&child_pointer(&child_pointer(pointer, "matchers"), index), matcher);
+          }
+          Some(matchers) => self.type_error(child_pointer(pointer, "matchers").as_str(), "an Array", matchers),
+          None => self.issues.push(ValidationIssue::error(child_pointer(pointer, "matchers"), "Missing matchers",
+            Some("Add a matchers attribute with the list of matchers to apply".to_string())))
+        }
+        match map.get("combine") {
+          Some(Value::String(combine)) if combine.eq_ignore_ascii_case("AND") || combine.eq_ignore_ascii_case("OR") => {}
+          Some(combine) => self.issues.push(ValidationIssue::error(child_pointer(pointer, "combine"),
+            format!("{} is not a valid way to combine matchers", combine),
+            Some("combine must be either AND or OR".to_string()))),
+          None => {}
+        }
+      }
+      _ => self.type_error(pointer, "an Object", rule_list)
+    }
+  }
+
+  fn validate_matcher(&mut self, pointer: &str, matcher: &Value) {
+    if !matcher.is_object() {
+      self.type_error(pointer, "an Object", matcher);
+      return;
+    }
+
+    let (_, warnings) = normalise_matcher_json(matcher);
+    for warning in warnings {
+      self.issues.push(ValidationIssue::warning(pointer, warning,
+        Some("Update the matcher to the current format for the matching rule".to_string())));
+    }
+    if let Err(err) = MatchingRule::from_json(matcher) {
+      self.issues.push(ValidationIssue::error(pointer, err.to_string(),
+        Some("A matcher must have a 'match' attribute with a valid matcher type, along with the attributes it requires".to_string())));
+    }
+  }
+
+  fn validate_generators(&mut self, pointer: &str, generators: &Value, categories: &[GeneratorCategory]) {
+    match generators {
+      Value::Object(map) => for (key, value) in map {
+        let category_pointer = child_pointer(pointer, key);
+        match GeneratorCategory::from_str(key) {
+          Ok(category) if categories.contains(&category) => match category {
+            GeneratorCategory::PATH | GeneratorCategory::METHOD | GeneratorCategory::STATUS =>
+              self.validate_generator(&category_pointer, value),
+            _ => match value {
+              Value::Object(generators) => for (key, generator) in generators {
+                let generator_pointer = child_pointer(&category_pointer, key);
+                if category == GeneratorCategory::BODY {
+                  if let Err(err) = DocPath::new(key) {
+                    self.issues.push(ValidationIssue::error(generator_pointer,
+                      format!("'{}' is not a valid generator path: {}", key, err),
+                      Some("Generator paths must be valid path expressions, e.g. $.items[*].id".to_string())));
+                    continue;
+                  }
+                }
+                self.validate_generator(&generator_pointer, generator);
+              }
+              _ => self.type_error(&category_pointer, "an Object", value)
+            }
+          }
+          _ => self.issues.push(ValidationIssue::error(category_pointer,
+            format!("'{}' is not a valid generator category here", key),
+            Some(format!("The category must be one of {}",
+              categories.iter().map(|c| (*c).into()).collect::<Vec<&str>>().join(", ")))))
+        }
+      }
+      _ => self.type_error(pointer, "an Object", generators)
+    }
+  }
+
+  fn validate_generator(&mut self, pointer: &str, generator: &Value) {
+    match generator {
+      Value::Object(map) => match map.get("type") {
+        Some(Value::String(generator_type)) => if Generator::from_map(generator_type, map).is_none() {
+          self.issues.push(ValidationIssue::error(pointer, format!("'{}' is not a valid generator", generator_type),
+            Some("The generator type must be one of RandomInt, RandomDecimal, RandomHexadecimal, RandomString, \
+              Regex, Uuid, Date, Time, DateTime, RandomBoolean, ProviderState, MockServerURL or EpochTimestamp, \
+              along with the attributes it requires".to_string())));
+        }
+        Some(value) => self.type_error(child_pointer(pointer, "type").as_str(), "a String", value),
+        None => self.issues.push(ValidationIssue::error(child_pointer(pointer, "type"), "Missing type",
+          Some("Add a type attribute with the type of generator".to_string())))
+      }
+      _ => self.type_error(pointer, "an Object", generator)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+  use serde_json::json;
+
+  use crate::PactSpecification;
+  use crate::verify_json::ResultLevel;
+
+  use super::*;
+
+  fn pointers(issues: &[ValidationIssue]) -> Vec<(String, ResultLevel)> {
+    issues.iter().map(|issue| (issue.pointer.clone(), issue.severity.clone())).collect()
+  }
+
+  #[test]
+  fn validate_pact_json_with_a_valid_v3_pact() {
+    let pact = json!({
+      "consumer": { "name": "consumer" },
+      "provider": { "name": "provider" },
+      "interactions": [
+        {
+          "description": "a request",
+          "providerStates": [ { "name": "a state" } ],
+          "request": {
+            "method": "GET",
+            "path": "/items",
+            "query": { "id": [ "1" ] },
+            "headers": { "Accept": "application/json" },
+            "matchingRules": {
+              "query": { "id": { "matchers": [ { "match": "regex", "regex": "\\d+" } ] } }
+            }
+          },
+          "response": {
+            "status": 200,
+            "body": { "items": [ { "id": 1 } ] },
+            "matchingRules": {
+              "body": { "$.items": { "matchers": [ { "match": "type", "min": 1 } ], "combine": "AND" } }
+            },
+            "generators": {
+              "body": { "$.items[*].id": { "type": "RandomInt", "min": 1, "max": 10 } }
+            }
+          }
+        }
+      ],
+      "metadata": { "pactSpecification": { "version": "3.0.0" } }
+    });
+    expect!(validate_pact_json(&pact, PactSpecification::Unknown)).to(be_equal_to(vec![]));
+  }
+
+  #[test]
+  fn validate_pact_json_with_something_that_is_not_an_object() {
+    let issues = validate_pact_json(&json!([]), PactSpecification::V3);
+    expect!(pointers(&issues)).to(be_equal_to(vec![("".to_string(), ResultLevel::ERROR)]));
+    expect!(issues[0].message.as_str()).to(be_equal_to("Must be an Object, got Array"));
+  }
+
+  #[test]
+  fn validate_pact_json_reports_the_location_of_wrong_types_and_unknown_fields() {
+    let pact = json!({
+      "consumer": { "name": "consumer" },
+      "provider": { "name": "provider" },
+      "interactions": [
+        {
+          "description": "a request",
+          "request": { "method": 100, "path": "/", "headers": { "a/b": 1 }, "bdy": "" },
+          "response": { "status": "200" }
+        }
+      ],
+      "metadata": { "pactSpecification": { "version": "3.0.0" } }
+    });
+    let issues = validate_pact_json(&pact, PactSpecification::Unknown);
+    expect!(pointers(&issues)).to(be_equal_to(vec![
+      ("/interactions/0/request/method".to_string(), ResultLevel::ERROR),
+      ("/interactions/0/request/headers/a~1b".to_string(), ResultLevel::ERROR),
+      ("/interactions/0/request/bdy".to_string(), ResultLevel::WARNING),
+      ("/interactions/0/response/status".to_string(), ResultLevel::ERROR)
+    ]));
+    expect!(issues[0].to_string()).to(be_equal_to(
+      "ERROR at '/interactions/0/request/method': Must be a String, got Number (Change the value to a String)"));
+  }
+
+  #[test]
+  fn validate_pact_json_reports_invalid_matching_rules() {
+    let pact = json!({
+      "consumer": { "name": "consumer" },
+      "provider": { "name": "provider" },
+      "interactions": [
+        {
+          "description": "a request",
+          "request": { "method": "GET", "path": "/" },
+          "response": {
+            "status": 200,
+            "matchingRules": {
+              "body": {
+                "$.items[": { "matchers": [ { "match": "type" } ] },
+                "$.id": { "matchers": [ { "match": "not-a-matcher" } ] },
+                "$.name": { "matchers": [ { "match": "regex", "regex": "\\w+" } ], "combine": "XOR" }
+              },
+              "query": { "id": { "matchers": [] } }
+            }
+          }
+        }
+      ],
+      "metadata": { "pactSpecification": { "version": "3.0.0" } }
+    });
+    let mut issues = pointers(&validate_pact_json(&pact, PactSpecification::Unknown));
+    issues.sort_by(|a, b| a.0.cmp(&b.0));
+    expect!(issues).to(be_equal_to(vec![
+      ("/interactions/0/response/matchingRules/body/$.id/matchers/0".to_string(), ResultLevel::ERROR),
+      ("/interactions/0/response/matchingRules/body/$.items[".to_string(), ResultLevel::ERROR),
+      ("/interactions/0/response/matchingRules/body/$.name/combine".to_string(), ResultLevel::ERROR),
+      ("/interactions/0/response/matchingRules/query".to_string(), ResultLevel::ERROR)
+    ]));
+  }
+
+  #[test]
+  fn validate_pact_json_warns_about_v2_matching_rules_in_a_v3_pact() {
+    let pact = json!({
+      "consumer": { "name": "consumer" },
+      "provider": { "name": "provider" },
+      "interactions": [
+        {
+          "description": "a request",
+          "request": { "method": "GET", "path": "/" },
+          "response": {
+            "status": 200,
+            "matchingRules": {
+              "$.body.id": { "match": "type" },
+              "$.other": { "match": "type" }
+            }
+          }
+        }
+      ],
+      "metadata": { "pactSpecification": { "version": "3.0.0" } }
+    });
+    let issues = validate_pact_json(&pact, PactSpecification::Unknown);
+    expect!(pointers(&issues)).to(be_equal_to(vec![
+      ("/interactions/0/response/matchingRules".to_string(), ResultLevel::WARNING),
+      ("/interactions/0/response/matchingRules/$.other".to_string(), ResultLevel::ERROR)
+    ]));
+  }
+
+  #[test]
+  fn validate_pact_json_reports_invalid_generators() {
+    let pact = json!({
+      "consumer": { "name": "consumer" },
+      "provider": { "name": "provider" },
+      "interactions": [
+        {
+          "description": "a request",
+          "request": {
+            "method": "GET",
+            "path": "/",
+            "generators": {
+              "path": { "type": "ProviderState", "expression": "/items/${id}" },
+              "header": { "X-Id": { "min": 1 } },
+              "status": { "type": "RandomInt" }
+            }
+          },
+          "response": {
+            "status": 200,
+            "generators": { "body": { "$.id": { "type": "Regex" } } }
+          }
+        }
+      ],
+      "metadata": { "pactSpecification": { "version": "3.0.0" } }
+    });
+    let mut issues = validate_pact_json(&pact, PactSpecification::Unknown);
+    issues.sort_by(|a, b| a.pointer.cmp(&b.pointer));
+    expect!(pointers(&issues)).to(be_equal_to(vec![
+      ("/interactions/0/request/generators/header/X-Id/type".to_string(), ResultLevel::ERROR),
+      ("/interactions/0/request/generators/status".to_string(), ResultLevel::ERROR),
+      ("/interactions/0/response/generators/body/$.id".to_string(), ResultLevel::ERROR)
+    ]));
+    expect!(issues[2].message.as_str()).to(be_equal_to("'Regex' is not a valid generator"));
+  }
+
+  #[test]
+  fn validate_pact_json_with_v4_interactions() {
+    let pact = json!({
+      "consumer": { "name": "consumer" },
+      "provider": { "name": "provider" },
+      "interactions": [
+        {
+          "type": "Synchronous/HTTP",
+          "description": "a request",
+          "pending": "no",
+          "request": { "method": "GET", "path": "/" },
+          "response": { "status": 200, "headers": { "Content-Type": [ "application/json" ] } }
+        },
+        {
+          "type": "Asynchronous/Messages",
+          "description": "a message",
+          "contents": { "content": "{}", "contentType": "application/json" },
+          "metadata": "none",
+          "matchingRules": { "body": { "$.id": { "matchers": [ { "match": "integer" } ] } } }
+        },
+        {
+          "type": "Synchronous/Message",
+          "description": "a synchronous message"
+        }
+      ],
+      "metadata": { "pactSpecification": { "version": "4.0" } }
+    });
+    let issues = validate_pact_json(&pact, PactSpecification::Unknown);
+    expect!(pointers(&issues)).to(be_equal_to(vec![
+      ("/interactions/0/pending".to_string(), ResultLevel::ERROR),
+      ("/interactions/1/metadata".to_string(), ResultLevel::ERROR),
+      ("/interactions/2/type".to_string(), ResultLevel::ERROR)
+    ]));
+    expect!(issues[2].hint.clone()).to(be_some().value(
+      "The interaction type must be one of Synchronous/HTTP, Asynchronous/Messages or Synchronous/Messages"));
+  }
+
+  #[test]
+  fn validate_pact_json_adds_hints_to_the_existing_verification_results() {
+    let pact = json!({
+      "consumer": { "name": "consumer" },
+      "provider": { "name": "provider" },
+      "interactions": [
+        {
+          "providerState": "a state",
+          "request": { "method": "GET", "path": "/" },
+          "response": { "status": 200 }
+        }
+      ],
+      "metadata": { "pactSpecification": { "version": "3.0.0" } }
+    });
+    let issues = validate_pact_json(&pact, PactSpecification::Unknown);
+    expect!(issues.iter().map(|issue| (issue.message.clone(), issue.hint.clone())).collect::<Vec<_>>()).to(be_equal_to(vec![
+      ("Missing description".to_string(), Some("Add the description attribute".to_string())),
+      ("'providerState' is deprecated, use 'providerStates' instead".to_string(), Some("Replace it with 'providerStates'".to_string()))
+    ]));
+  }
+}
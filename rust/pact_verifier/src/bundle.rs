@@ -0,0 +1,252 @@
+//! Support for verifying pacts without access to a Pact Broker. The pacts (along with their
+//! verification context and links) are fetched ahead of time into a bundle file, which can then
+//! be verified offline with a `PactSource::Bundle` source. The verification results are written
+//! to a results file, to be published to the Pact Broker later.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::panic::RefUnwindSafe;
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use pact_models::http_utils::HttpAuth;
+use pact_models::pact::{load_pact_from_json, Pact};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, error, info};
+
+use crate::{fetch_pacts, PactSource, ProviderInfo};
+use crate::pact_broker::{Link, PactVerificationContext, publish_verification_payload};
+
+/// Pact fetched into a bundle, along with the details required to publish its verification result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundledPact {
+  /// Pact JSON
+  pub pact: Value,
+  /// Context on why the Pact was included for verification (only for Pacts from the newer pacts
+  /// for verification API)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub verification_context: Option<PactVerificationContext>,
+  /// Base URL of the Pact Broker the Pact was fetched from
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub broker_url: Option<String>,
+  /// Links to the Pact resources in the Pact Broker
+  #[serde(default)]
+  pub links: Vec<Link>
+}
+
+impl BundledPact {
+  /// Loads the Pact model from the bundled JSON
+  pub fn load_pact(&self, source: &str) -> anyhow::Result<Box<dyn Pact + Send + Sync + RefUnwindSafe>> {
+    load_pact_from_json(source, &self.pact)
+  }
+}
+
+/// Bundle of pacts that can be verified offline
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PactBundle {
+  /// Name of the provider the pacts were fetched for
+  pub provider: String,
+  /// Pacts in the bundle
+  pub pacts: Vec<BundledPact>
+}
+
+impl PactBundle {
+  /// Reads a bundle from the given file
+  pub fn read(path: &Path) -> anyhow::Result<PactBundle> {
+    let file = File::open(path)
+      .with_context(|| format!("Could not open pact bundle '{}'", path.display()))?;
+    serde_json::from_reader(BufReader::new(file))
+      .with_context(|| format!("Could not parse pact bundle '{}'", path.display()))
+  }
+
+  /// Writes the bundle to the given file, overwriting any existing file
+  pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+    let json = serde_json::to_string_pretty(self)?;
+    fs::write(path, json)
+      .with_context(|| format!("Could not write pact bundle '{}'", path.display()))
+  }
+}
+
+/// Fetches the pacts from the given sources into a bundle that can be verified offline. Will
+/// return an error if any of the pacts can not be fetched.
+pub async fn fetch_pact_bundle(
+  provider_info: &ProviderInfo,
+  source: Vec<PactSource>,
+  consumers: Vec<String>
+) -> anyhow::Result<PactBundle> {
+  let mut pacts = vec![];
+
+  for result in fetch_pacts(source, consumers, provider_info).await {
+    let (pact, context, pact_source, _) = result?;
+    debug!("Adding pact between {} and {} from {} to the bundle", pact.consumer().name,
+      pact.provider().name, pact_source);
+    let (broker_url, links) = match pact_source {
+      PactSource::BrokerUrl(_, broker_url, _, links) => (Some(broker_url), links),
+      _ => (None, vec![])
+    };
+    pacts.push(BundledPact {
+      pact: pact.to_json(pact.specification_version())?,
+      verification_context: context,
+      broker_url,
+      links
+    });
+  }
+
+  Ok(PactBundle {
+    provider: provider_info.name.clone(),
+    pacts
+  })
+}
+
+/// Verification result for a pact from a bundle that is waiting to be published to the Pact Broker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeferredVerificationResult {
+  /// Base URL of the Pact Broker the Pact was fetched from
+  pub broker_url: String,
+  /// Links to the Pact resources in the Pact Broker
+  pub links: Vec<Link>,
+  /// Provider version that was verified
+  pub provider_version: String,
+  /// Provider branch to publish
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub provider_branch: Option<String>,
+  /// Provider tags to publish
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub provider_tags: Vec<String>,
+  /// Verification result payload to publish
+  pub payload: Value
+}
+
+/// Appends the result to the results file. Each result is written as a single line of JSON.
+pub fn write_deferred_result(path: &Path, result: &DeferredVerificationResult) -> anyhow::Result<()> {
+  let mut file = OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(path)
+    .with_context(|| format!("Could not open results file '{}'", path.display()))?;
+  writeln!(file, "{}", serde_json::to_string(result)?)
+    .with_context(|| format!("Could not write to results file '{}'", path.display()))
+}
+
+/// Reads all the results from the results file
+pub fn read_deferred_results(path: &Path) -> anyhow::Result<Vec<DeferredVerificationResult>> {
+  let file = File::open(path)
+    .with_context(|| format!("Could not open results file '{}'", path.display()))?;
+  BufReader::new(file).lines()
+    .enumerate()
+    .filter(|(_, line)| line.as_ref().map(|line| !line.trim().is_empty()).unwrap_or(true))
+    .map(|(index, line)| {
+      let line = line?;
+      serde_json::from_str(line.as_str())
+        .with_context(|| format!("Could not parse line {} of results file '{}'", index + 1, path.display()))
+    })
+    .collect()
+}
+
+/// Publishes all the results from the results file to the Pact Broker. Returns the number of
+/// results published, or an error if any of them failed to be published.
+pub async fn publish_deferred_results(path: &Path, auth: Option<HttpAuth>) -> anyhow::Result<usize> {
+  let results = read_deferred_results(path)?;
+  let mut failures = 0;
+
+  for result in &results {
+    info!("Publishing verification result for provider version {} to {}", result.provider_version, result.broker_url);
+    if let Err(err) = publish_verification_payload(
+      result.links.clone(),
+      result.broker_url.as_str(),
+      auth.clone(),
+      &result.payload,
+      result.provider_version.as_str(),
+      result.provider_tags.clone(),
+      result.provider_branch.clone()
+    ).await {
+      error!("Publishing of verification results failed with an error: {}", err);
+      failures += 1;
+    }
+  }
+
+  if failures == 0 {
+    Ok(results.len())
+  } else {
+    Err(anyhow!("{} of {} verification results failed to be published", failures, results.len()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::env;
+
+  use expectest::prelude::*;
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn bundle_round_trips_through_a_file() {
+    let path = env::temp_dir().join(format!("pact-bundle-{}.json", std::process::id()));
+    let bundle = PactBundle {
+      provider: "provider".to_string(),
+      pacts: vec![
+        BundledPact {
+          pact: json!({
+            "consumer": { "name": "consumer" },
+            "provider": { "name": "provider" },
+            "interactions": [],
+            "metadata": { "pactSpecification": { "version": "3.0.0" } }
+          }),
+          verification_context: None,
+          broker_url: Some("http://localhost:9292".to_string()),
+          links: vec![Link {
+            name: "pb:publish-verification-results".to_string(),
+            href: Some("http://localhost:9292/results".to_string()),
+            .. Link::default()
+          }]
+        }
+      ]
+    };
+
+    bundle.write(&path).unwrap();
+    let result = PactBundle::read(&path);
+    fs::remove_file(&path).unwrap();
+
+    let result = result.unwrap();
+    expect!(result.provider).to(be_equal_to("provider"));
+    expect!(result.pacts.len()).to(be_equal_to(1));
+    let pact = result.pacts[0].load_pact("bundle").unwrap();
+    expect!(pact.consumer().name).to(be_equal_to("consumer"));
+    expect!(result.pacts[0].broker_url.clone()).to(be_some().value("http://localhost:9292"));
+    expect!(result.pacts[0].links[0].href.clone()).to(be_some().value("http://localhost:9292/results"));
+  }
+
+  #[test]
+  fn deferred_results_are_appended_to_the_results_file() {
+    let path = env::temp_dir().join(format!("pact-bundle-results-{}.jsonl", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let result = DeferredVerificationResult {
+      broker_url: "http://localhost:9292".to_string(),
+      links: vec![],
+      provider_version: "1.0.0".to_string(),
+      provider_branch: Some("main".to_string()),
+      provider_tags: vec![],
+      payload: json!({ "success": true })
+    };
+
+    write_deferred_result(&path, &result).unwrap();
+    write_deferred_result(&path, &DeferredVerificationResult {
+      provider_version: "1.0.1".to_string(),
+      .. result
+    }).unwrap();
+    let results = read_deferred_results(&path);
+    fs::remove_file(&path).unwrap();
+
+    let results = results.unwrap();
+    expect!(results.iter().map(|r| r.provider_version.clone()).collect::<Vec<_>>())
+      .to(be_equal_to(vec!["1.0.0".to_string(), "1.0.1".to_string()]));
+    expect!(results[0].provider_branch.clone()).to(be_some().value("main"));
+    expect!(results[1].payload.clone()).to(be_equal_to(json!({ "success": true })));
+  }
+}
@@ -19,10 +19,23 @@ use tracing::warn;
 use crate::provider_client::make_state_change_request;
 
 /// Trait for executors that call request filters
-pub trait RequestFilterExecutor: Debug {
+///
+/// Executors must be `Send + Sync`, as the same executor is shared by all the interactions being
+/// verified. When the interactions are verified in parallel, the filter can be called from
+/// multiple threads at the same time, so any state the executor uses must be synchronised.
+#[async_trait]
+pub trait RequestFilterExecutor: Debug + Send + Sync {
   /// Mutates HTTP requests based on some criteria.
   fn call(self: Arc<Self>, request: &HttpRequest) -> HttpRequest;
 
+  /// Mutates HTTP requests before they are sent to the provider. This is called with the request
+  /// after any generators have been applied, so it can be used to sign the request (for example,
+  /// with AWS SigV4) or to add credentials that need to be fetched first. Returning an error will
+  /// fail the interaction. The default implementation delegates to `call`.
+  async fn call_async(self: Arc<Self>, request: &HttpRequest) -> anyhow::Result<HttpRequest> {
+    Ok(self.call(request))
+  }
+
   /// Callback to mutate request data. This form is used by plugins.
   fn call_non_http(
     &self,
@@ -0,0 +1,148 @@
+//! Discovers the capabilities (i.e. enabled feature flags) of the provider before the verification
+//! starts, so that interactions that require capabilities the provider does not have can be skipped.
+//!
+//! Interactions declare the capabilities they require with the `requiredCapabilities` comment
+//! (V4 pacts only), which can be a single string or an array of strings.
+
+use std::collections::BTreeSet;
+
+use anyhow::anyhow;
+use pact_models::interaction::Interaction;
+use reqwest::Client;
+use serde_json::Value;
+use tracing::{debug, info};
+
+/// Key in the interaction comments that lists the capabilities the interaction requires
+pub const REQUIRED_CAPABILITIES_KEY: &str = "requiredCapabilities";
+
+/// Check that fetches the capabilities of the provider from a URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderCapabilityCheck {
+  /// URL that returns the capabilities of the provider as JSON
+  pub url: String
+}
+
+/// Capabilities that the provider has
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ProviderCapabilities {
+  /// Names of the enabled capabilities
+  pub capabilities: BTreeSet<String>
+}
+
+impl ProviderCapabilityCheck {
+  /// Creates a check for the URL
+  pub fn new<S: Into<String>>(url: S) -> Self {
+    ProviderCapabilityCheck {
+      url: url.into()
+    }
+  }
+
+  /// Fetches the capabilities from the provider. Returns an error if the request fails or the
+  /// response is not a valid capabilities document.
+  pub async fn fetch(&self, client: &Client) -> anyhow::Result<ProviderCapabilities> {
+    debug!("Fetching the provider capabilities from {}", self.url);
+    let response = client.get(&self.url).send().await
+      .map_err(|err| anyhow!("Failed to fetch the provider capabilities from {} - {}", self.url, err))?;
+    if !response.status().is_success() {
+      return Err(anyhow!("Failed to fetch the provider capabilities from {}, the request returned status {}",
+        self.url, response.status()));
+    }
+    let json: Value = response.json().await
+      .map_err(|err| anyhow!("Provider capabilities from {} are not valid JSON - {}", self.url, err))?;
+    let capabilities = ProviderCapabilities::from_json(&json)?;
+    info!("Provider has the following capabilities: {:?}", capabilities.capabilities);
+    Ok(capabilities)
+  }
+}
+
+impl ProviderCapabilities {
+  /// Parses the capabilities from JSON. This can either be an array of capability names, or an
+  /// object of capability names to boolean flags (only the ones set to true are enabled). Either
+  /// form can also be wrapped in an object with a `capabilities` attribute.
+  pub fn from_json(json: &Value) -> anyhow::Result<ProviderCapabilities> {
+    let json = match json {
+      Value::Object(map) => map.get("capabilities").unwrap_or(json),
+      _ => json
+    };
+    let capabilities = match json {
+      Value::Array(values) => values.iter()
+        .map(|value| value.as_str().map(|s| s.to_string())
+          .ok_or_else(|| anyhow!("Capability names must be strings, got {}", value)))
+        .collect::<anyhow::Result<_>>()?,
+      Value::Object(map) => map.iter()
+        .filter(|(_, enabled)| enabled.as_bool().unwrap_or(false))
+        .map(|(name, _)| name.clone())
+        .collect(),
+      _ => return Err(anyhow!("Expected the provider capabilities to be a JSON array or object, got {}", json))
+    };
+    Ok(ProviderCapabilities { capabilities })
+  }
+
+  /// Returns the capabilities required by the interaction that the provider does not have
+  pub fn missing_capabilities(&self, interaction: &dyn Interaction) -> Vec<String> {
+    required_capabilities(interaction).into_iter()
+      .filter(|capability| !self.capabilities.contains(capability))
+      .collect()
+  }
+}
+
+/// Returns the capabilities that the interaction requires, from the `requiredCapabilities` comment
+pub fn required_capabilities(interaction: &dyn Interaction) -> Vec<String> {
+  let comments = interaction.as_v4()
+    .map(|interaction| interaction.comments())
+    .unwrap_or_default();
+  match comments.get(REQUIRED_CAPABILITIES_KEY) {
+    Some(Value::String(capability)) => vec![capability.clone()],
+    Some(Value::Array(capabilities)) => capabilities.iter()
+      .filter_map(|capability| capability.as_str().map(|s| s.to_string()))
+      .collect(),
+    _ => vec![]
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+  use maplit::{btreeset, hashmap};
+  use serde_json::json;
+
+  use pact_models::sync_interaction::RequestResponseInteraction;
+  use pact_models::v4::synch_http::SynchronousHttp;
+
+  use super::{ProviderCapabilities, required_capabilities};
+
+  #[test]
+  fn capabilities_from_json() {
+    expect!(ProviderCapabilities::from_json(&json!(["a", "b"])).unwrap().capabilities)
+      .to(be_equal_to(btreeset!{ "a".to_string(), "b".to_string() }));
+    expect!(ProviderCapabilities::from_json(&json!({ "a": true, "b": false, "c": "yes" })).unwrap().capabilities)
+      .to(be_equal_to(btreeset!{ "a".to_string() }));
+    expect!(ProviderCapabilities::from_json(&json!({ "capabilities": ["a"] })).unwrap().capabilities)
+      .to(be_equal_to(btreeset!{ "a".to_string() }));
+    expect!(ProviderCapabilities::from_json(&json!({ "capabilities": { "a": true } })).unwrap().capabilities)
+      .to(be_equal_to(btreeset!{ "a".to_string() }));
+    expect!(ProviderCapabilities::from_json(&json!("a"))).to(be_err());
+    expect!(ProviderCapabilities::from_json(&json!([1]))).to(be_err());
+  }
+
+  #[test]
+  fn missing_capabilities_for_an_interaction() {
+    let capabilities = ProviderCapabilities::from_json(&json!(["new-checkout"])).unwrap();
+
+    let interaction = SynchronousHttp {
+      comments: hashmap!{ "requiredCapabilities".to_string() => json!(["new-checkout", "gift-cards"]) },
+      .. SynchronousHttp::default()
+    };
+    expect!(required_capabilities(&interaction)).to(be_equal_to(vec!["new-checkout".to_string(), "gift-cards".to_string()]));
+    expect!(capabilities.missing_capabilities(&interaction)).to(be_equal_to(vec!["gift-cards".to_string()]));
+
+    let interaction = SynchronousHttp {
+      comments: hashmap!{ "requiredCapabilities".to_string() => json!("new-checkout") },
+      .. SynchronousHttp::default()
+    };
+    expect!(capabilities.missing_capabilities(&interaction).is_empty()).to(be_true());
+
+    expect!(capabilities.missing_capabilities(&SynchronousHttp::default()).is_empty()).to(be_true());
+    expect!(capabilities.missing_capabilities(&RequestResponseInteraction::default()).is_empty()).to(be_true());
+  }
+}
@@ -0,0 +1,109 @@
+//! Check of the provider's clock, using the Date header returned in the responses from the provider
+
+use std::time::{Duration, SystemTime};
+
+use humantime::format_duration;
+
+use pact_matching::Mismatch;
+use pact_models::http_parts::HttpPart;
+use pact_models::v4::http_parts::HttpResponse;
+
+/// Options for checking the Date header returned by the provider against the verifier's clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSkewCheck {
+  /// Maximum allowed difference between the provider's Date header and the verifier's clock
+  pub max_skew: Duration,
+  /// If a missing, invalid or skewed Date header should fail the verification. Otherwise, it will
+  /// be reported as a warning.
+  pub fail_verification: bool
+}
+
+impl ClockSkewCheck {
+  /// Creates a check that reports any problems as warnings
+  pub fn new(max_skew: Duration) -> Self {
+    ClockSkewCheck { max_skew, fail_verification: false }
+  }
+
+  /// Checks the Date header of the response against the given time, returning a description of
+  /// the problem if the header is missing, invalid or outside the allowed skew
+  pub fn check(&self, response: &HttpResponse, now: SystemTime) -> Option<String> {
+    let header = match response.lookup_header_value("date") {
+      Some(header) => header,
+      None => return Some("Provider clock skew: The response from the provider has no Date header".to_string())
+    };
+    let date = match httpdate::parse_http_date(&header) {
+      Ok(date) => date,
+      Err(err) => return Some(format!("Provider clock skew: The Date header '{}' from the provider is not a valid HTTP date - {}",
+        header, err))
+    };
+    let (skew, direction) = match date.duration_since(now) {
+      Ok(skew) => (skew, "ahead of"),
+      Err(err) => (err.duration(), "behind")
+    };
+    // HTTP dates only have a precision of seconds
+    let skew = Duration::from_secs(skew.as_secs());
+    if skew > self.max_skew {
+      Some(format!("Provider clock skew: The Date header '{}' from the provider is {} {} the verifier's clock (the maximum allowed skew is {})",
+        header, format_duration(skew), direction, format_duration(self.max_skew)))
+    } else {
+      None
+    }
+  }
+}
+
+/// Returns the mismatch to report when the clock skew check fails the verification
+pub(crate) fn clock_skew_mismatch(response: &HttpResponse, message: &str) -> Mismatch {
+  Mismatch::HeaderMismatch {
+    key: "Date".to_string(),
+    expected: String::default(),
+    actual: response.lookup_header_value("date").unwrap_or_default(),
+    mismatch: message.to_string(),
+    rule: None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::{Duration, SystemTime};
+
+  use expectest::prelude::*;
+  use maplit::hashmap;
+
+  use pact_models::v4::http_parts::HttpResponse;
+
+  use super::ClockSkewCheck;
+
+  fn response(date: &str) -> HttpResponse {
+    HttpResponse {
+      headers: Some(hashmap!{ "Date".to_string() => vec![date.to_string()] }),
+      .. HttpResponse::default()
+    }
+  }
+
+  #[test]
+  fn check_returns_none_when_the_date_is_within_the_allowed_skew() {
+    let now = httpdate::parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+    let check = ClockSkewCheck::new(Duration::from_secs(30));
+    expect!(check.check(&response("Sun, 06 Nov 1994 08:49:37 GMT"), now)).to(be_none());
+    expect!(check.check(&response("Sun, 06 Nov 1994 08:50:07 GMT"), now)).to(be_none());
+    expect!(check.check(&response("Sun, 06 Nov 1994 08:49:07 GMT"), now)).to(be_none());
+  }
+
+  #[test]
+  fn check_returns_the_problem_when_the_date_is_skewed() {
+    let now = httpdate::parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+    let check = ClockSkewCheck::new(Duration::from_secs(30));
+    expect!(check.check(&response("Sun, 06 Nov 1994 10:49:37 GMT"), now)).to(be_some().value(
+      "Provider clock skew: The Date header 'Sun, 06 Nov 1994 10:49:37 GMT' from the provider is 2h ahead of the verifier's clock (the maximum allowed skew is 30s)".to_string()));
+    expect!(check.check(&response("Sun, 06 Nov 1994 08:48:37 GMT"), now)).to(be_some().value(
+      "Provider clock skew: The Date header 'Sun, 06 Nov 1994 08:48:37 GMT' from the provider is 1m behind the verifier's clock (the maximum allowed skew is 30s)".to_string()));
+  }
+
+  #[test]
+  fn check_returns_the_problem_when_the_date_is_missing_or_invalid() {
+    let check = ClockSkewCheck::new(Duration::from_secs(30));
+    expect!(check.check(&HttpResponse::default(), SystemTime::now())).to(be_some().value(
+      "Provider clock skew: The response from the provider has no Date header".to_string()));
+    expect!(check.check(&response("yesterday"), SystemTime::now())).to(be_some());
+  }
+}
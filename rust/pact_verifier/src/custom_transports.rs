@@ -0,0 +1,304 @@
+//! Support for custom transports implemented by the calling application (for instance, an
+//! in-process queue). When a V4 interaction is verified that has a transport that has been
+//! registered here, the verifier will call the registered executor instead of making a request
+//! to the provider.
+//!
+//! The executor is called with a JSON document describing the request, and must return the
+//! response as a JSON document. The format depends on the type of interaction:
+//!
+//! | Interaction | Request | Response |
+//! |-------------|---------|----------|
+//! | Synchronous HTTP | `{ "description", "providerStates", "request": <HTTP request> }` | HTTP response (i.e. `{ "status": 200, "headers": {}, "body": {} }`) |
+//! | Asynchronous message | `{ "description", "providerStates" }` | Message contents (i.e. `{ "contents": {}, "metadata": {} }`) |
+//! | Synchronous message | `{ "description", "providerStates", "request": <message contents> }` | Message contents of the response |
+
+use std::collections::HashMap;
+use std::panic::RefUnwindSafe;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+#[cfg(feature = "plugins")] use maplit::hashmap;
+use pact_models::generators::GeneratorTestMode;
+use pact_models::interaction::Interaction;
+use pact_models::pact::Pact;
+use pact_models::v4::async_message::AsynchronousMessage;
+use pact_models::v4::http_parts::HttpResponse;
+use pact_models::v4::message_parts::MessageContents;
+use pact_models::v4::synch_http::SynchronousHttp;
+use pact_models::v4::sync_message::SynchronousMessage;
+#[cfg(feature = "plugins")] use pact_plugin_driver::catalogue_manager::{
+  CatalogueEntry,
+  CatalogueEntryProviderType,
+  CatalogueEntryType,
+  register_core_entries
+};
+use serde_json::{json, Value};
+use tracing::debug;
+
+use pact_matching::{match_message, match_response, match_sync_message_response};
+
+use crate::MismatchResult;
+use crate::utils::as_safe_ref;
+
+lazy_static! {
+  static ref TRANSPORT_EXECUTORS: RwLock<HashMap<String, Arc<dyn TransportExecutor>>> = RwLock::new(HashMap::new());
+}
+
+/// Trait for executors that send the requests for a custom transport implemented by the
+/// calling application
+#[async_trait]
+pub trait TransportExecutor: Send + Sync {
+  /// Executes the request for the transport, returning the response. See the module
+  /// documentation for the format of the request and response.
+  async fn execute(&self, transport: &str, request: &Value) -> anyhow::Result<Value>;
+}
+
+/// Registers an executor for a custom transport. Any V4 interactions with the transport key will
+/// be verified by calling the executor. Registering an executor for an existing key will replace
+/// the previous one.
+pub fn register_transport(key: &str, executor: Arc<dyn TransportExecutor>) {
+  debug!("Registering custom transport '{}'", key);
+  let mut executors = TRANSPORT_EXECUTORS.write().unwrap();
+  executors.insert(key.to_string(), executor);
+
+  #[cfg(feature = "plugins")]
+  register_core_entries(&vec![CatalogueEntry {
+    entry_type: CatalogueEntryType::TRANSPORT,
+    provider_type: CatalogueEntryProviderType::CORE,
+    plugin: None,
+    key: key.to_string(),
+    values: hashmap!{}
+  }]);
+}
+
+/// Removes the executor for a custom transport, returning true if one was registered
+pub fn unregister_transport(key: &str) -> bool {
+  debug!("Removing custom transport '{}'", key);
+  let mut executors = TRANSPORT_EXECUTORS.write().unwrap();
+  executors.remove(key).is_some()
+}
+
+/// Looks up the executor for a custom transport
+pub fn lookup_transport(key: &str) -> Option<Arc<dyn TransportExecutor>> {
+  let executors = TRANSPORT_EXECUTORS.read().unwrap();
+  executors.get(key).cloned()
+}
+
+/// Verify an interaction by calling the executor for a custom transport
+pub(crate) async fn verify_interaction_using_custom_transport<'a>(
+  transport: &str,
+  executor: &Arc<dyn TransportExecutor>,
+  interaction: &(dyn Interaction + Send + Sync + RefUnwindSafe),
+  pact: &Box<dyn Pact + Send + Sync + RefUnwindSafe + 'a>,
+  provider_states_context: &HashMap<&str, Value>
+) -> Result<Option<String>, MismatchResult> {
+  let mut request = json!({
+    "description": interaction.description()
+  });
+  if !interaction.provider_states().is_empty() {
+    if let Some(map) = request.as_object_mut() {
+      map.insert("providerStates".into(), Value::Array(interaction.provider_states().iter()
+        .map(|ps| ps.to_json()).collect()));
+    }
+  }
+
+  if let Some(http) = interaction.as_v4_http() {
+    let expected_response = crate::apply_provider_state_values(&http.response, provider_states_context).await;
+    let (http_request, generator_report) = pact_matching::generate_request_with_report(&http.request,
+      &GeneratorTestMode::Provider, provider_states_context).await;
+    for generated_value in &generator_report.values {
+      debug!("Applied generator: {}", generated_value);
+    }
+    request["request"] = http_request.to_json();
+
+    let json = execute(transport, executor, &request, interaction).await?;
+    let response = HttpResponse::from_json(&json)
+      .map_err(|err| invalid_response(transport, err, interaction))?;
+    let mismatches = match_response(expected_response, response.clone(), pact, &http.boxed()).await;
+    if mismatches.is_empty() {
+      Ok(interaction.id())
+    } else {
+      Err(MismatchResult::Mismatches {
+        mismatches,
+        expected: Box::new(http.clone()),
+        actual: Box::new(SynchronousHttp { response, .. SynchronousHttp::default() }),
+        interaction_id: interaction.id()
+      })
+    }
+  } else if let Some(message) = interaction.as_v4_sync_message() {
+    request["request"] = message.request.to_json();
+
+    let json = execute(transport, executor, &request, interaction).await?;
+    let response = MessageContents::from_json(&json)
+      .map_err(|err| invalid_response(transport, err, interaction))?;
+    let actual = SynchronousMessage {
+      response: vec![response],
+      .. SynchronousMessage::default()
+    };
+    let mismatches = match_sync_message_response(&message, &message.response, &actual.response, pact).await;
+    if mismatches.is_empty() {
+      Ok(interaction.id())
+    } else {
+      Err(MismatchResult::Mismatches {
+        mismatches,
+        expected: as_safe_ref(&message),
+        actual: as_safe_ref(&actual),
+        interaction_id: interaction.id()
+      })
+    }
+  } else if interaction.is_message() {
+    let json = execute(transport, executor, &request, interaction).await?;
+    let contents = MessageContents::from_json(&json)
+      .map_err(|err| invalid_response(transport, err, interaction))?;
+    let actual = AsynchronousMessage {
+      contents,
+      .. AsynchronousMessage::default()
+    };
+    let mismatches = match_message(&interaction.boxed(), &actual.boxed(), pact).await;
+    if mismatches.is_empty() {
+      Ok(interaction.id())
+    } else {
+      Err(MismatchResult::Mismatches {
+        mismatches,
+        expected: as_safe_ref(interaction),
+        actual: as_safe_ref(&actual),
+        interaction_id: interaction.id()
+      })
+    }
+  } else {
+    Err(MismatchResult::Error(format!("Interactions of type '{}' can not be verified with a custom transport",
+      interaction.type_of()), interaction.id()))
+  }
+}
+
+async fn execute(
+  transport: &str,
+  executor: &Arc<dyn TransportExecutor>,
+  request: &Value,
+  interaction: &(dyn Interaction + Send + Sync + RefUnwindSafe)
+) -> Result<Value, MismatchResult> {
+  debug!("Sending request to custom transport '{}': {}", transport, request);
+  executor.execute(transport, request).await
+    .map_err(|err| MismatchResult::Error(
+      format!("Request to custom transport '{}' failed - {}", transport, err), interaction.id()))
+}
+
+fn invalid_response(
+  transport: &str,
+  err: anyhow::Error,
+  interaction: &(dyn Interaction + Send + Sync + RefUnwindSafe)
+) -> MismatchResult {
+  MismatchResult::Error(format!("Custom transport '{}' returned an invalid response - {}", transport, err),
+    interaction.id())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use async_trait::async_trait;
+  use expectest::prelude::*;
+  use maplit::hashmap;
+  use pact_models::bodies::OptionalBody;
+  use pact_models::pact::Pact;
+  use pact_models::v4::async_message::AsynchronousMessage;
+  use pact_models::v4::interaction::V4Interaction;
+  use pact_models::v4::message_parts::MessageContents;
+  use pact_models::v4::pact::V4Pact;
+  use serde_json::{json, Value};
+
+  use crate::MismatchResult;
+
+  use super::*;
+
+  #[derive(Debug)]
+  struct TestQueue {
+    message: Value
+  }
+
+  #[async_trait]
+  impl TransportExecutor for TestQueue {
+    async fn execute(&self, transport: &str, request: &Value) -> anyhow::Result<Value> {
+      expect!(transport).to(be_equal_to("test-queue"));
+      expect!(request).to(be_equal_to(&json!({ "description": "an order created event" })));
+      Ok(self.message.clone())
+    }
+  }
+
+  fn order_created_event() -> AsynchronousMessage {
+    AsynchronousMessage {
+      description: "an order created event".to_string(),
+      contents: MessageContents {
+        contents: OptionalBody::from(json!({ "id": 100 })),
+        .. MessageContents::default()
+      },
+      transport: Some("test-queue".to_string()),
+      .. AsynchronousMessage::default()
+    }
+  }
+
+  #[test]
+  fn register_and_unregister_transport() {
+    register_transport("register-test", Arc::new(TestQueue { message: json!({}) }));
+    expect!(lookup_transport("register-test").is_some()).to(be_true());
+    expect!(unregister_transport("register-test")).to(be_true());
+    expect!(lookup_transport("register-test").is_none()).to(be_true());
+    expect!(unregister_transport("register-test")).to(be_false());
+  }
+
+  #[tokio::test]
+  async fn verifies_an_async_message_using_the_executor() {
+    let interaction = order_created_event();
+    let pact = V4Pact {
+      interactions: vec![interaction.boxed_v4()],
+      .. V4Pact::default()
+    }.boxed();
+    let executor: Arc<dyn TransportExecutor> = Arc::new(TestQueue {
+      message: json!({
+        "contents": { "content": { "id": 100 }, "contentType": "application/json;charset=utf-8", "encoded": false }
+      })
+    });
+
+    let result = verify_interaction_using_custom_transport("test-queue", &executor,
+      &interaction, &pact, &hashmap!{}).await;
+    expect!(result).to(be_ok());
+  }
+
+  #[tokio::test]
+  async fn returns_mismatches_if_the_message_from_the_executor_does_not_match() {
+    let interaction = order_created_event();
+    let pact = V4Pact {
+      interactions: vec![interaction.boxed_v4()],
+      .. V4Pact::default()
+    }.boxed();
+    let executor: Arc<dyn TransportExecutor> = Arc::new(TestQueue {
+      message: json!({
+        "contents": { "content": { "id": 200 }, "contentType": "application/json;charset=utf-8", "encoded": false }
+      })
+    });
+
+    let result = verify_interaction_using_custom_transport("test-queue", &executor,
+      &interaction, &pact, &hashmap!{}).await;
+    match result {
+      Err(MismatchResult::Mismatches { mismatches, .. }) => {
+        expect!(mismatches.len()).to(be_equal_to(1));
+      }
+      _ => panic!("Expected mismatches, got {:?}", result)
+    }
+  }
+
+  #[tokio::test]
+  async fn returns_an_error_if_the_executor_returns_an_invalid_response() {
+    let interaction = order_created_event();
+    let pact = V4Pact {
+      interactions: vec![interaction.boxed_v4()],
+      .. V4Pact::default()
+    }.boxed();
+    let executor: Arc<dyn TransportExecutor> = Arc::new(TestQueue { message: json!("not a message") });
+
+    let result = verify_interaction_using_custom_transport("test-queue", &executor,
+      &interaction, &pact, &hashmap!{}).await;
+    expect!(result).to(be_err());
+  }
+}
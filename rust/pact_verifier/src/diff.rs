@@ -0,0 +1,62 @@
+//! Renders the differences between the expected and actual bodies as a unified diff
+
+use ansi_term::Colour::{Cyan, Green, Red};
+use ansi_term::Style;
+use similar::TextDiff;
+
+/// Generates a unified diff (like git) of the expected and actual text, with the given number of
+/// lines of context around each change. Each line of the diff is prefixed with the indent.
+pub(crate) fn unified_diff(
+  expected: &str,
+  actual: &str,
+  context: usize,
+  indent: &str,
+  coloured: bool
+) -> String {
+  let diff = TextDiff::from_lines(expected, actual);
+  let diff = diff.unified_diff()
+    .context_radius(context)
+    .header("expected", "actual")
+    .to_string();
+
+  diff.lines()
+    .map(|line| {
+      let line = if !coloured {
+        line.to_string()
+      } else if line.starts_with("---") || line.starts_with("+++") {
+        Style::new().bold().paint(line).to_string()
+      } else if line.starts_with("@@") {
+        Cyan.paint(line).to_string()
+      } else if line.starts_with('+') {
+        Green.paint(line).to_string()
+      } else if line.starts_with('-') {
+        Red.paint(line).to_string()
+      } else {
+        line.to_string()
+      };
+      format!("{}{}", indent, line)
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+
+  use super::unified_diff;
+
+  #[test]
+  fn unified_diff_returns_an_empty_string_when_there_are_no_differences() {
+    expect!(unified_diff("a\nb\n", "a\nb\n", 3, "", false)).to(be_equal_to(""));
+  }
+
+  #[test]
+  fn unified_diff_only_includes_the_requested_context() {
+    let expected = "1\n2\n3\n4\n5\n6\n7\n";
+    let actual = "1\n2\n3\nfour\n5\n6\n7\n";
+    expect!(unified_diff(expected, actual, 1, "  ", false)).to(be_equal_to(
+      "  --- expected\n  +++ actual\n  @@ -3,3 +3,3 @@\n   3\n  -4\n  +four\n   5"
+    ));
+  }
+}
@@ -0,0 +1,212 @@
+//! Support for checking the SHA-256 digests of pact sources before they are verified. This
+//! allows a pipeline to ensure that the pact files being verified are the same ones that were
+//! published, and have not been modified along the way.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use anyhow::anyhow;
+use pact_models::http_utils::HttpAuth;
+use sha2::{Digest, Sha256};
+use tracing::{debug, trace};
+
+use crate::PactSource;
+
+/// Digest calculated for a pact source
+#[derive(Debug, Clone, PartialEq)]
+pub struct PactDigest {
+  /// Pact source (file path or URL)
+  pub source: String,
+  /// SHA-256 digest of the pact contents (lowercase hex)
+  pub sha256: String,
+  /// If the digest was checked against an expected value
+  pub verified: bool
+}
+
+/// Error returned when the digest of a pact source does not match the expected digest
+#[derive(Debug, Clone, PartialEq)]
+pub struct PactDigestMismatch {
+  /// Pact source (file path or URL)
+  pub source: String,
+  /// Expected SHA-256 digest
+  pub expected: String,
+  /// SHA-256 digest of the pact contents
+  pub actual: String
+}
+
+impl Display for PactDigestMismatch {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "SHA-256 digest of pact '{}' does not match, expected sha256:{} but got sha256:{}",
+      self.source, self.expected, self.actual)
+  }
+}
+
+impl std::error::Error for PactDigestMismatch {}
+
+/// Calculates the SHA-256 digest of the bytes, returned as lowercase hex
+pub fn sha256_digest(bytes: &[u8]) -> String {
+  hex::encode(Sha256::digest(bytes))
+}
+
+/// Parses an expected digest value. This can either be the hex encoded SHA-256 digest, or the
+/// digest prefixed with `sha256:`.
+pub fn parse_digest(value: &str) -> anyhow::Result<String> {
+  let value = value.trim();
+  let digest = value.strip_prefix("sha256:").unwrap_or(value);
+  if digest.len() == 64 && digest.chars().all(|ch| ch.is_ascii_hexdigit()) {
+    Ok(digest.to_ascii_lowercase())
+  } else {
+    Err(anyhow!("'{}' is not a valid SHA-256 digest", value))
+  }
+}
+
+/// Checks the digests of the pact sources against the expected digests (keyed by the pact file
+/// path or URL). Digests are always calculated for pact files, but URLs are only fetched if
+/// there is an expected digest for them. Returns the sources that can be verified, the digests
+/// that were calculated and the errors for the sources that failed the check.
+pub(crate) async fn check_pact_digests(
+  sources: Vec<PactSource>,
+  expected_digests: &HashMap<String, String>
+) -> (Vec<PactSource>, Vec<PactDigest>, Vec<anyhow::Error>) {
+  let mut verified_sources = vec![];
+  let mut digests = vec![];
+  let mut errors = vec![];
+
+  for source in sources {
+    let key = match &source {
+      PactSource::File(file) => file.clone(),
+      PactSource::URL(url, _) if expected_digests.contains_key(url) => url.clone(),
+      _ => {
+        verified_sources.push(source);
+        continue;
+      }
+    };
+
+    let expected = match expected_digests.get(&key).map(|digest| parse_digest(digest)).transpose() {
+      Ok(expected) => expected,
+      Err(err) => {
+        errors.push(anyhow!("Invalid digest configured for pact '{}' - {}", key, err));
+        continue;
+      }
+    };
+
+    match source_digest(&source).await {
+      Ok(actual) => {
+        trace!(source = %key, sha256 = %actual, "Calculated pact digest");
+        digests.push(PactDigest {
+          source: key.clone(),
+          sha256: actual.clone(),
+          verified: expected.is_some()
+        });
+        match expected {
+          Some(expected) if expected != actual => {
+            errors.push(PactDigestMismatch { source: key, expected, actual }.into());
+          }
+          _ => verified_sources.push(source)
+        }
+      }
+      Err(err) => if expected.is_some() {
+        errors.push(anyhow!("Failed to calculate the digest of pact '{}' - {}", key, err));
+      } else {
+        // The error will be reported when the pact is loaded
+        debug!("Failed to calculate the digest of pact '{}' - {}", key, err);
+        verified_sources.push(source);
+      }
+    }
+  }
+
+  (verified_sources, digests, errors)
+}
+
+async fn source_digest(source: &PactSource) -> anyhow::Result<String> {
+  match source {
+    PactSource::File(file) => {
+      let contents = tokio::fs::read(file).await?;
+      Ok(sha256_digest(&contents))
+    }
+    PactSource::URL(url, auth) => {
+      let client = reqwest::Client::new();
+      let request = match auth {
+        Some(HttpAuth::User(username, password)) => client.get(url).basic_auth(username, password.clone()),
+        Some(HttpAuth::Token(token)) => client.get(url).bearer_auth(token),
+        _ => client.get(url)
+      };
+      let response = request.send().await?;
+      if response.status().is_success() {
+        let contents = response.bytes().await?;
+        Ok(sha256_digest(&contents))
+      } else {
+        Err(anyhow!("Request failed with status - {}", response.status()))
+      }
+    }
+    _ => Err(anyhow!("Digests can only be calculated for pact files and URLs"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use expectest::prelude::*;
+  use maplit::hashmap;
+
+  use crate::PactSource;
+
+  use super::*;
+
+  #[test]
+  fn sha256_digest_test() {
+    expect!(sha256_digest(b"")).to(be_equal_to("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"));
+    expect!(sha256_digest(b"abc")).to(be_equal_to("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"));
+  }
+
+  #[test]
+  fn parse_digest_test() {
+    let digest = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    expect!(parse_digest(digest).unwrap()).to(be_equal_to(digest));
+    expect!(parse_digest(format!("sha256:{}", digest).as_str()).unwrap()).to(be_equal_to(digest));
+    expect!(parse_digest(digest.to_uppercase().as_str()).unwrap()).to(be_equal_to(digest));
+    expect!(parse_digest("sha256:1234")).to(be_err());
+    expect!(parse_digest("md5:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")).to(be_err());
+  }
+
+  #[tokio::test]
+  async fn check_pact_digests_test() {
+    let dir = std::env::temp_dir().join(format!("pact-digests-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let ok_file = dir.join("ok.json").to_string_lossy().to_string();
+    let bad_file = dir.join("bad.json").to_string_lossy().to_string();
+    let other_file = dir.join("other.json").to_string_lossy().to_string();
+    fs::write(&ok_file, "abc").unwrap();
+    fs::write(&bad_file, "abd").unwrap();
+    fs::write(&other_file, "").unwrap();
+
+    let abc_digest = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    let (sources, digests, errors) = check_pact_digests(
+      vec![
+        PactSource::File(ok_file.clone()),
+        PactSource::File(bad_file.clone()),
+        PactSource::File(other_file.clone()),
+        PactSource::Dir(dir.to_string_lossy().to_string())
+      ],
+      &hashmap!{
+        ok_file.clone() => format!("sha256:{}", abc_digest),
+        bad_file.clone() => abc_digest.to_string()
+      }
+    ).await;
+    fs::remove_dir_all(&dir).unwrap();
+
+    expect!(sources.len()).to(be_equal_to(3));
+    expect!(digests.len()).to(be_equal_to(3));
+    expect!(digests[0].verified).to(be_true());
+    expect!(digests[2].clone()).to(be_equal_to(PactDigest {
+      source: other_file,
+      sha256: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855".to_string(),
+      verified: false
+    }));
+    expect!(errors.len()).to(be_equal_to(1));
+    let mismatch = errors[0].downcast_ref::<PactDigestMismatch>().unwrap();
+    expect!(&mismatch.source).to(be_equal_to(&bad_file));
+    expect!(mismatch.expected.as_str()).to(be_equal_to(abc_digest));
+  }
+}
@@ -0,0 +1,275 @@
+//! Categories for the failures that can occur during a verification, so that tools running the
+//! verifier (i.e. CI pipelines) can act on the type of failure without parsing the output.
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::MismatchResult;
+use crate::pact_broker::PactBrokerError;
+
+/// Text that is added to the start of the error for an interaction when the provider could not
+/// be reached
+pub const PROVIDER_UNREACHABLE: &str = "Provider could not be reached";
+
+/// Text that is added to the start of the error for an interaction that was not verified before
+/// the interaction timeout or the verification deadline
+pub const INTERACTION_TIMED_OUT: &str = "Interaction timed out";
+
+/// Text used in the errors for failed provider state change handlers
+pub(crate) const STATE_CHANGE_FAILED: &str = "state change handlers has failed";
+
+/// Error returned when the provider could not be reached
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{0}")]
+pub struct ProviderUnreachableError(pub String);
+
+/// Category of a verification failure. The categories are ordered by precedence, with the first
+/// one determining the overall result when there are failures of different categories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FailureCategory {
+  /// Pacts could not be fetched from the Pact Broker
+  PactBroker,
+  /// Pact files could not be loaded or parsed
+  PactLoading,
+  /// Requests could not be sent to the provider
+  ProviderUnreachable,
+  /// An interaction was not verified before the interaction timeout or verification deadline
+  Timeout,
+  /// A provider state change handler failed
+  StateChange,
+  /// Any other error
+  Other,
+  /// The provider returned a response or message that did not match the pact
+  Mismatch
+}
+
+impl FailureCategory {
+  /// All the categories, in order of precedence
+  pub const ALL: [FailureCategory; 7] = [
+    FailureCategory::PactBroker,
+    FailureCategory::PactLoading,
+    FailureCategory::ProviderUnreachable,
+    FailureCategory::Timeout,
+    FailureCategory::StateChange,
+    FailureCategory::Other,
+    FailureCategory::Mismatch
+  ];
+
+  /// Name of the category, as used in the summary JSON
+  pub fn name(&self) -> &'static str {
+    match self {
+      FailureCategory::PactBroker => "pactBroker",
+      FailureCategory::PactLoading => "pactLoading",
+      FailureCategory::ProviderUnreachable => "providerUnreachable",
+      FailureCategory::Timeout => "timeout",
+      FailureCategory::StateChange => "stateChange",
+      FailureCategory::Other => "other",
+      FailureCategory::Mismatch => "mismatch"
+    }
+  }
+
+  /// Process exit code for the category
+  pub fn exit_code(&self) -> i32 {
+    match self {
+      FailureCategory::Mismatch => 1,
+      FailureCategory::Other => 2,
+      FailureCategory::ProviderUnreachable => 4,
+      FailureCategory::PactBroker => 5,
+      FailureCategory::PactLoading => 6,
+      FailureCategory::StateChange => 7,
+      FailureCategory::Timeout => 8
+    }
+  }
+
+  /// Category for an error that stopped the verification
+  pub fn for_error(err: &anyhow::Error) -> FailureCategory {
+    if err.chain().any(|cause| cause.is::<PactBrokerError>()) {
+      FailureCategory::PactBroker
+    } else if is_unreachable(err) {
+      FailureCategory::ProviderUnreachable
+    } else {
+      FailureCategory::Other
+    }
+  }
+
+  /// Category for an error that occurred loading a pact
+  pub fn for_load_error(err: &anyhow::Error) -> FailureCategory {
+    if err.chain().any(|cause| cause.is::<PactBrokerError>()) {
+      FailureCategory::PactBroker
+    } else {
+      FailureCategory::PactLoading
+    }
+  }
+
+  /// Category for the failed verification of an interaction
+  pub fn for_mismatch_result(result: &MismatchResult) -> FailureCategory {
+    match result {
+      MismatchResult::Mismatches { .. } => FailureCategory::Mismatch,
+      MismatchResult::Error(error, _) => if error.starts_with(PROVIDER_UNREACHABLE) {
+        FailureCategory::ProviderUnreachable
+      } else if error.starts_with(INTERACTION_TIMED_OUT) {
+        FailureCategory::Timeout
+      } else if error.contains(STATE_CHANGE_FAILED) {
+        FailureCategory::StateChange
+      } else {
+        FailureCategory::Other
+      }
+    }
+  }
+}
+
+/// If the interaction should be retried (if retries are enabled). Only interactions that timed out
+/// or where the provider could not be reached are retried.
+pub(crate) fn is_retryable(result: &MismatchResult) -> bool {
+  matches!(FailureCategory::for_mismatch_result(result),
+    FailureCategory::ProviderUnreachable | FailureCategory::Timeout)
+}
+
+/// If the error was caused by not being able to connect to the provider (or the request timing out)
+fn is_unreachable(err: &anyhow::Error) -> bool {
+  err.chain().any(|cause| {
+    cause.is::<ProviderUnreachableError>() || cause.downcast_ref::<reqwest::Error>()
+      .map(|err| err.is_connect() || err.is_timeout())
+      .unwrap_or(false)
+  })
+}
+
+/// Formats the error from making a request to the provider, marking it if the provider could not
+/// be reached
+pub(crate) fn provider_request_error(err: &anyhow::Error) -> String {
+  if is_unreachable(err) {
+    format!("{} - {}", PROVIDER_UNREACHABLE, err)
+  } else {
+    err.to_string()
+  }
+}
+
+/// Summary of the failures that failed the verification (failures for pending and WIP pacts are
+/// not included)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FailureSummary {
+  /// Description of each failure, with its category
+  pub failures: Vec<(String, FailureCategory)>
+}
+
+impl FailureSummary {
+  /// Adds a failure to the summary
+  pub fn add<S: Into<String>>(&mut self, description: S, category: FailureCategory) {
+    self.failures.push((description.into(), category));
+  }
+
+  /// Number of failures in each category
+  pub fn counts(&self) -> BTreeMap<FailureCategory, usize> {
+    let mut counts = BTreeMap::new();
+    for (_, category) in &self.failures {
+      *counts.entry(*category).or_insert(0) += 1;
+    }
+    counts
+  }
+
+  /// The category with the highest precedence of all the failures
+  pub fn primary_category(&self) -> Option<FailureCategory> {
+    self.failures.iter().map(|(_, category)| *category).min()
+  }
+
+  /// Process exit code for the failures. This will be zero if there are no failures.
+  pub fn exit_code(&self) -> i32 {
+    self.primary_category().map(|category| category.exit_code()).unwrap_or(0)
+  }
+
+  /// Returns the summary as JSON
+  pub fn to_json(&self) -> Value {
+    let counts = self.counts();
+    json!({
+      "exitCode": self.exit_code(),
+      "category": self.primary_category().map(|category| category.name()),
+      "total": self.failures.len(),
+      "counts": FailureCategory::ALL.iter()
+        .map(|category| (category.name().to_string(), json!(counts.get(category).cloned().unwrap_or(0))))
+        .collect::<serde_json::Map<_, _>>(),
+      "failures": self.failures.iter().map(|(description, category)| json!({
+        "description": description,
+        "category": category.name()
+      })).collect::<Vec<_>>()
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anyhow::anyhow;
+  use expectest::prelude::*;
+  use serde_json::json;
+
+  use crate::MismatchResult;
+  use crate::pact_broker::PactBrokerError;
+
+  use super::*;
+
+  #[test]
+  fn failure_category_for_errors() {
+    expect!(FailureCategory::for_error(&anyhow!(PactBrokerError::NotFound("pacts".to_string()))))
+      .to(be_equal_to(FailureCategory::PactBroker));
+    expect!(FailureCategory::for_error(&anyhow!(ProviderUnreachableError("not ready".to_string()))))
+      .to(be_equal_to(FailureCategory::ProviderUnreachable));
+    expect!(FailureCategory::for_error(&anyhow!("boom"))).to(be_equal_to(FailureCategory::Other));
+
+    expect!(FailureCategory::for_load_error(&anyhow!(PactBrokerError::IoError("failed".to_string()))))
+      .to(be_equal_to(FailureCategory::PactBroker));
+    expect!(FailureCategory::for_load_error(&anyhow!("Failed to parse Pact JSON")))
+      .to(be_equal_to(FailureCategory::PactLoading));
+
+    expect!(FailureCategory::for_mismatch_result(&MismatchResult::Error(
+      provider_request_error(&anyhow!(ProviderUnreachableError("connection refused".to_string()))), None)))
+      .to(be_equal_to(FailureCategory::ProviderUnreachable));
+    expect!(FailureCategory::for_mismatch_result(&MismatchResult::Error(
+      "One or more of the setup state change handlers has failed".to_string(), None)))
+      .to(be_equal_to(FailureCategory::StateChange));
+    expect!(FailureCategory::for_mismatch_result(&MismatchResult::Error(
+      format!("{} after 1s", INTERACTION_TIMED_OUT), None)))
+      .to(be_equal_to(FailureCategory::Timeout));
+    expect!(FailureCategory::for_mismatch_result(&MismatchResult::Error("boom".to_string(), None)))
+      .to(be_equal_to(FailureCategory::Other));
+  }
+
+  #[test]
+  fn is_retryable_test() {
+    expect!(is_retryable(&MismatchResult::Error(format!("{} after 1s", INTERACTION_TIMED_OUT), None))).to(be_true());
+    expect!(is_retryable(&MismatchResult::Error(format!("{} - connection refused", PROVIDER_UNREACHABLE), None))).to(be_true());
+    expect!(is_retryable(&MismatchResult::Error("boom".to_string(), None))).to(be_false());
+  }
+
+  #[test]
+  fn failure_summary_test() {
+    let summary = FailureSummary::default();
+    expect!(summary.exit_code()).to(be_equal_to(0));
+    expect!(summary.primary_category()).to(be_none());
+
+    let mut summary = FailureSummary::default();
+    summary.add("get user", FailureCategory::Mismatch);
+    summary.add("get order", FailureCategory::StateChange);
+    summary.add("get orders", FailureCategory::Mismatch);
+    expect!(summary.exit_code()).to(be_equal_to(7));
+    expect!(summary.to_json()).to(be_equal_to(json!({
+      "exitCode": 7,
+      "category": "stateChange",
+      "total": 3,
+      "counts": {
+        "pactBroker": 0,
+        "pactLoading": 0,
+        "providerUnreachable": 0,
+        "timeout": 0,
+        "stateChange": 1,
+        "other": 0,
+        "mismatch": 2
+      },
+      "failures": [
+        { "description": "get user", "category": "mismatch" },
+        { "description": "get order", "category": "stateChange" },
+        { "description": "get orders", "category": "mismatch" }
+      ]
+    })));
+  }
+}
@@ -0,0 +1,428 @@
+//! Built-in transport for unary gRPC requests. The plugin for the interaction is still used to
+//! encode the request message and to compare the response message, but the verifier sends the
+//! request to the provider itself. This removes a call to the plugin for each request, and
+//! allows the request filter to be applied to gRPC requests in the same way as HTTP requests.
+//!
+//! Requests are sent over HTTP/2 without TLS (h2c). Only unary methods (a single request and
+//! response message) with uncompressed messages are supported.
+
+use std::collections::HashMap;
+#[cfg(feature = "plugins")] use std::panic::RefUnwindSafe;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use bytes::{BufMut, Bytes, BytesMut};
+use http::HeaderMap;
+use http_body_util::{BodyExt, Full};
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use itertools::Either;
+use maplit::hashmap;
+use pact_models::bodies::OptionalBody;
+use pact_models::content_types::ContentType;
+#[cfg(feature = "plugins")] use pact_models::pact::Pact;
+use pact_models::v4::http_parts::HttpRequest;
+use pact_models::v4::message_parts::MessageContents;
+use pact_models::v4::sync_message::SynchronousMessage;
+use serde_json::Value;
+use tracing::{debug, info};
+
+#[cfg(feature = "plugins")] use pact_matching::match_sync_message_response;
+
+#[cfg(feature = "plugins")] use crate::{MismatchResult, VerificationOptions};
+#[cfg(feature = "plugins")] use crate::callback_executors::RequestFilterExecutor;
+#[cfg(feature = "plugins")] use crate::failures::provider_request_error;
+use crate::failures::ProviderUnreachableError;
+#[cfg(feature = "plugins")] use crate::utils::as_safe_ref;
+
+/// Content type for gRPC requests
+pub const GRPC_CONTENT_TYPE: &str = "application/grpc";
+
+/// Names of the gRPC status codes, indexed by the code
+const STATUS_CODES: [&str; 17] = [
+  "OK",
+  "CANCELLED",
+  "UNKNOWN",
+  "INVALID_ARGUMENT",
+  "DEADLINE_EXCEEDED",
+  "NOT_FOUND",
+  "ALREADY_EXISTS",
+  "PERMISSION_DENIED",
+  "RESOURCE_EXHAUSTED",
+  "FAILED_PRECONDITION",
+  "ABORTED",
+  "OUT_OF_RANGE",
+  "UNIMPLEMENTED",
+  "INTERNAL",
+  "UNAVAILABLE",
+  "DATA_LOSS",
+  "UNAUTHENTICATED"
+];
+
+/// Response to a unary gRPC request
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GrpcResponse {
+  /// HTTP status of the response
+  pub http_status: u16,
+  /// Response headers
+  pub headers: HashMap<String, Vec<String>>,
+  /// Response trailers
+  pub trailers: HashMap<String, Vec<String>>,
+  /// Messages in the response body (with the gRPC framing removed)
+  pub messages: Vec<Bytes>
+}
+
+impl GrpcResponse {
+  /// gRPC status code of the response. This is taken from the trailers, or the headers for
+  /// responses that only have headers (i.e. errors).
+  pub fn grpc_status(&self) -> Option<u32> {
+    self.value("grpc-status").and_then(|status| status.parse().ok())
+  }
+
+  /// gRPC status message of the response
+  pub fn grpc_message(&self) -> Option<String> {
+    self.value("grpc-message")
+      .map(|message| urlencoding::decode(&message).map(|m| m.to_string()).unwrap_or(message))
+  }
+
+  fn value(&self, key: &str) -> Option<String> {
+    self.trailers.get(key)
+      .or_else(|| self.headers.get(key))
+      .and_then(|values| values.first().cloned())
+  }
+
+  /// Converts the response into the message contents for a synchronous message, so that it can
+  /// be compared to the expected response. The headers and trailers are added to the metadata,
+  /// with the `grpc-status` as the name of the status code.
+  pub fn to_message_contents(&self, content_type: Option<ContentType>) -> anyhow::Result<MessageContents> {
+    if self.http_status != 200 {
+      return Err(anyhow!("gRPC request failed with HTTP status {}", self.http_status));
+    }
+    if self.messages.len() > 1 {
+      return Err(anyhow!("Expected a single response message for a unary gRPC request, got {}", self.messages.len()));
+    }
+
+    let mut metadata = HashMap::new();
+    for (key, values) in self.headers.iter().chain(self.trailers.iter()) {
+      if let Some(value) = values.first() {
+        metadata.insert(key.clone(), Value::String(value.clone()));
+      }
+    }
+    let status = self.grpc_status().unwrap_or_default();
+    metadata.insert("grpc-status".to_string(), Value::String(status_name(status)));
+    if let Some(message) = self.grpc_message() {
+      metadata.insert("grpc-message".to_string(), Value::String(message));
+    }
+
+    Ok(MessageContents {
+      contents: match self.messages.first() {
+        Some(message) => OptionalBody::Present(message.clone(), content_type, None),
+        None => OptionalBody::Missing
+      },
+      metadata,
+      .. MessageContents::default()
+    })
+  }
+}
+
+/// Returns the name of the gRPC status code (i.e. `NOT_FOUND` for 5)
+pub fn status_name(code: u32) -> String {
+  STATUS_CODES.get(code as usize)
+    .map(|name| name.to_string())
+    .unwrap_or_else(|| code.to_string())
+}
+
+/// If the transport (or catalogue entry key for the transport) is for gRPC
+pub fn is_grpc_transport(transport: &str) -> bool {
+  transport == "grpc" || transport.ends_with("/grpc")
+}
+
+/// Adds the gRPC framing to a message (a compressed flag of zero followed by the length of the
+/// message as a 4 byte big-endian integer)
+pub fn frame_message(message: &[u8]) -> Bytes {
+  let mut buffer = BytesMut::with_capacity(message.len() + 5);
+  buffer.put_u8(0);
+  buffer.put_u32(message.len() as u32);
+  buffer.put_slice(message);
+  buffer.freeze()
+}
+
+/// Removes the gRPC framing from the messages in the body
+pub fn unframe_messages(body: &[u8]) -> anyhow::Result<Vec<Bytes>> {
+  let mut messages = vec![];
+  let mut remaining = body;
+  while !remaining.is_empty() {
+    if remaining.len() < 5 {
+      return Err(anyhow!("gRPC message frame is truncated, expected 5 header bytes but got {}", remaining.len()));
+    }
+    if remaining[0] != 0 {
+      return Err(anyhow!("Compressed gRPC messages are not supported"));
+    }
+    let length = u32::from_be_bytes([remaining[1], remaining[2], remaining[3], remaining[4]]) as usize;
+    if remaining.len() < length + 5 {
+      return Err(anyhow!("gRPC message frame is truncated, expected {} bytes but got {}", length, remaining.len() - 5));
+    }
+    messages.push(Bytes::copy_from_slice(&remaining[5..length + 5]));
+    remaining = &remaining[length + 5..];
+  }
+  Ok(messages)
+}
+
+/// Returns the path of the gRPC method to call (i.e. `/routeguide.RouteGuide/GetFeature`). This is
+/// taken from the `:path` metadata value supplied by the plugin, or the `service` value of the
+/// plugin configuration for the interaction.
+pub fn grpc_method_path(
+  message: &SynchronousMessage,
+  metadata: &HashMap<String, Either<Value, Bytes>>
+) -> Option<String> {
+  let path = match metadata.get(":path") {
+    Some(Either::Left(Value::String(path))) => Some(path.clone()),
+    _ => {
+      let mut configs = message.plugin_config.iter().collect::<Vec<_>>();
+      configs.sort_by(|a, b| a.0.cmp(b.0));
+      configs.iter()
+        .find_map(|(_, config)| config.get("service").and_then(Value::as_str).map(|s| s.to_string()))
+    }
+  };
+  path.filter(|path| path.contains('/'))
+    .map(|path| if path.starts_with('/') { path } else { format!("/{}", path) })
+}
+
+/// Creates the HTTP request for a unary gRPC request. The metadata is sent as headers, with
+/// binary values base64 encoded in `-bin` headers.
+pub fn grpc_request(
+  path: &str,
+  message: &OptionalBody,
+  metadata: &HashMap<String, Either<Value, Bytes>>
+) -> HttpRequest {
+  let mut headers = hashmap!{
+    "content-type".to_string() => vec![ GRPC_CONTENT_TYPE.to_string() ],
+    "te".to_string() => vec![ "trailers".to_string() ]
+  };
+  for (key, value) in metadata {
+    let key = key.to_lowercase();
+    if key.starts_with(':') || key == "content-type" || key == "contenttype" || key == "te" {
+      continue;
+    }
+    match value {
+      Either::Left(Value::String(value)) => {
+        headers.insert(key, vec![ value.clone() ]);
+      }
+      Either::Left(value) => {
+        headers.insert(key, vec![ value.to_string() ]);
+      }
+      Either::Right(bytes) => {
+        let key = if key.ends_with("-bin") { key } else { format!("{}-bin", key) };
+        headers.insert(key, vec![ STANDARD.encode(bytes) ]);
+      }
+    }
+  }
+
+  HttpRequest {
+    method: "POST".to_string(),
+    path: path.to_string(),
+    headers: Some(headers),
+    body: OptionalBody::Present(frame_message(&message.value().unwrap_or_default()),
+      ContentType::parse(GRPC_CONTENT_TYPE).ok(), None),
+    .. HttpRequest::default()
+  }
+}
+
+/// Sends a unary gRPC request to the provider at the base URL (i.e. `http://localhost:50051`)
+pub async fn send_unary_request(
+  base_url: &str,
+  request: &HttpRequest,
+  timeout: Duration
+) -> anyhow::Result<GrpcResponse> {
+  let uri = format!("{}{}", base_url.trim_end_matches('/'), request.path);
+  info!("Sending gRPC request to provider at {}", uri);
+
+  let mut builder = http::Request::builder()
+    .method(http::Method::POST)
+    .uri(uri.as_str())
+    .version(http::Version::HTTP_2);
+  for (name, values) in request.headers.clone().unwrap_or_default() {
+    for value in values {
+      builder = builder.header(name.as_str(), value.as_str());
+    }
+  }
+  let http_request = builder.body(Full::new(request.body.value().unwrap_or_default()))?;
+
+  let client = Client::builder(TokioExecutor::new())
+    .http2_only(true)
+    .build_http::<Full<Bytes>>();
+  let result = tokio::time::timeout(timeout, async {
+    let response = client.request(http_request).await
+      .map_err(|err| if err.is_connect() {
+        anyhow!(ProviderUnreachableError(format!("Failed to connect to {} - {}", uri, err)))
+      } else {
+        anyhow!("gRPC request to {} failed - {}", uri, err)
+      })?;
+    let (parts, body) = response.into_parts();
+    let collected = body.collect().await
+      .map_err(|err| anyhow!("Failed to read the gRPC response from {} - {}", uri, err))?;
+    let trailers = collected.trailers().map(header_map).unwrap_or_default();
+    Ok::<_, anyhow::Error>((parts, trailers, collected.to_bytes()))
+  }).await
+    .map_err(|_| anyhow!(ProviderUnreachableError(format!("gRPC request to {} timed out after {:?}", uri, timeout))))?;
+  let (parts, trailers, body) = result?;
+
+  let response = GrpcResponse {
+    http_status: parts.status.as_u16(),
+    headers: header_map(&parts.headers),
+    trailers,
+    messages: unframe_messages(&body)?
+  };
+  debug!("Received gRPC response: {:?}", response);
+  Ok(response)
+}
+
+fn header_map(headers: &HeaderMap) -> HashMap<String, Vec<String>> {
+  let mut map: HashMap<String, Vec<String>> = HashMap::new();
+  for (name, value) in headers {
+    if let Ok(value) = value.to_str() {
+      map.entry(name.as_str().to_string()).or_default().push(value.to_string());
+    }
+  }
+  map
+}
+
+/// Verifies a gRPC interaction by sending the request message (as encoded by the plugin) to the
+/// provider, and comparing the response to the expected response.
+#[cfg(feature = "plugins")]
+pub(crate) async fn verify_grpc_interaction<'a, F: RequestFilterExecutor>(
+  base_url: &str,
+  message: &SynchronousMessage,
+  request_data: &OptionalBody,
+  metadata: &HashMap<String, Either<Value, Bytes>>,
+  options: &VerificationOptions<F>,
+  pact: &Box<dyn Pact + Send + Sync + RefUnwindSafe + 'a>
+) -> Result<Option<String>, MismatchResult> {
+  let path = grpc_method_path(message, metadata)
+    .ok_or_else(|| MismatchResult::Error("Could not determine the gRPC method to call, the plugin did not supply a ':path' or 'service' value".to_string(),
+      message.id.clone()))?;
+
+  let request = grpc_request(&path, request_data, metadata);
+  let request = if let Some(filter) = &options.request_filter {
+    info!("Invoking request filter for the gRPC request");
+    filter.clone().call_async(&request).await
+      .map_err(|err| MismatchResult::Error(format!("Request filter failed - {}", err), message.id.clone()))?
+  } else {
+    request
+  };
+
+  let response = send_unary_request(base_url, &request, Duration::from_millis(options.request_timeout)).await
+    .map_err(|err| MismatchResult::Error(provider_request_error(&err), message.id.clone()))?;
+  let content_type = message.response.first().and_then(|response| response.message_content_type());
+  let actual_contents = response.to_message_contents(content_type)
+    .map_err(|err| MismatchResult::Error(err.to_string(), message.id.clone()))?;
+  let actual = SynchronousMessage {
+    response: vec![ actual_contents ],
+    .. SynchronousMessage::default()
+  };
+
+  let mismatches = match_sync_message_response(message, &message.response, &actual.response, pact).await;
+  if mismatches.is_empty() {
+    Ok(message.id.clone())
+  } else {
+    Err(MismatchResult::Mismatches {
+      mismatches,
+      expected: as_safe_ref(message),
+      actual: as_safe_ref(&actual),
+      interaction_id: message.id.clone()
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use bytes::Bytes;
+  use expectest::prelude::*;
+  use itertools::Either;
+  use maplit::hashmap;
+  use pact_models::bodies::OptionalBody;
+  use pact_models::v4::sync_message::SynchronousMessage;
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn framing_messages() {
+    let framed = frame_message(b"hello");
+    expect!(framed.as_ref()).to(be_equal_to(b"\x00\x00\x00\x00\x05hello".as_ref()));
+    expect!(unframe_messages(&framed).unwrap()).to(be_equal_to(vec![ Bytes::from("hello") ]));
+    expect!(unframe_messages(&[]).unwrap().is_empty()).to(be_true());
+
+    let two = [frame_message(b"a").as_ref(), frame_message(b"").as_ref()].concat();
+    expect!(unframe_messages(&two).unwrap()).to(be_equal_to(vec![ Bytes::from("a"), Bytes::new() ]));
+
+    expect!(unframe_messages(b"\x00\x00\x00")).to(be_err());
+    expect!(unframe_messages(b"\x00\x00\x00\x00\x05hell")).to(be_err());
+    expect!(unframe_messages(b"\x01\x00\x00\x00\x01a")).to(be_err());
+  }
+
+  #[test]
+  fn grpc_method_path_test() {
+    let message = SynchronousMessage {
+      plugin_config: hashmap!{
+        "protobuf".to_string() => hashmap!{ "service".to_string() => json!("routeguide.RouteGuide/GetFeature") }
+      },
+      .. SynchronousMessage::default()
+    };
+    expect!(grpc_method_path(&message, &hashmap!{})).to(be_some().value("/routeguide.RouteGuide/GetFeature".to_string()));
+    expect!(grpc_method_path(&message, &hashmap!{ ":path".to_string() => Either::Left(json!("/test.Test/Call")) }))
+      .to(be_some().value("/test.Test/Call".to_string()));
+    expect!(grpc_method_path(&SynchronousMessage::default(), &hashmap!{})).to(be_none());
+  }
+
+  #[test]
+  fn grpc_request_test() {
+    let metadata = hashmap!{
+      "contentType".to_string() => Either::Left(json!("application/protobuf")),
+      "Authorization".to_string() => Either::Left(json!("Bearer 1234")),
+      "trace".to_string() => Either::Right(Bytes::from_static(&[1, 2, 3]))
+    };
+    let request = grpc_request("/test.Test/Call", &OptionalBody::Present(Bytes::from("hi"), None, None), &metadata);
+    expect!(request.method).to(be_equal_to("POST".to_string()));
+    expect!(request.path).to(be_equal_to("/test.Test/Call".to_string()));
+    expect!(request.headers).to(be_some().value(hashmap!{
+      "content-type".to_string() => vec![ "application/grpc".to_string() ],
+      "te".to_string() => vec![ "trailers".to_string() ],
+      "authorization".to_string() => vec![ "Bearer 1234".to_string() ],
+      "trace-bin".to_string() => vec![ "AQID".to_string() ]
+    }));
+    expect!(request.body.value()).to(be_some().value(Bytes::from_static(b"\x00\x00\x00\x00\x02hi")));
+  }
+
+  #[test]
+  fn response_to_message_contents() {
+    let response = GrpcResponse {
+      http_status: 200,
+      headers: hashmap!{ "content-type".to_string() => vec![ "application/grpc".to_string() ] },
+      trailers: hashmap!{ "grpc-status".to_string() => vec![ "0".to_string() ] },
+      messages: vec![ Bytes::from("reply") ]
+    };
+    let contents = response.to_message_contents(None).unwrap();
+    expect!(contents.contents.value()).to(be_some().value(Bytes::from("reply")));
+    expect!(contents.metadata.get("grpc-status").cloned()).to(be_some().value(json!("OK")));
+
+    let response = GrpcResponse {
+      http_status: 200,
+      headers: hashmap!{
+        "grpc-status".to_string() => vec![ "5".to_string() ],
+        "grpc-message".to_string() => vec![ "no%20such%20feature".to_string() ]
+      },
+      .. GrpcResponse::default()
+    };
+    expect!(response.grpc_status()).to(be_some().value(5));
+    let contents = response.to_message_contents(None).unwrap();
+    expect!(contents.contents).to(be_equal_to(OptionalBody::Missing));
+    expect!(contents.metadata.get("grpc-status").cloned()).to(be_some().value(json!("NOT_FOUND")));
+    expect!(contents.metadata.get("grpc-message").cloned()).to(be_some().value(json!("no such feature")));
+
+    expect!(GrpcResponse { http_status: 404, .. GrpcResponse::default() }.to_message_contents(None)).to(be_err());
+    expect!(status_name(42)).to(be_equal_to("42".to_string()));
+  }
+}
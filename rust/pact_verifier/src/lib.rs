@@ -3,33 +3,37 @@
 //! and V4 Pact specification (`https://github.com/pact-foundation/pact-specification/tree/version-4`).
 #![warn(missing_docs)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display, Formatter};
 use std::fmt;
 use std::fs;
 use std::future::Future;
 use std::panic::RefUnwindSafe;
 use std::path::Path;
+use std::str::FromStr;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 use ansi_term::*;
 use ansi_term::Colour::*;
 use anyhow::anyhow;
 use futures::stream::StreamExt;
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use http::{header, HeaderMap};
 use http::header::HeaderName;
 use humantime::format_duration;
 use itertools::Itertools;
 #[cfg(feature = "plugins")] use itertools::Either;
 use maplit::*;
-use pact_models::generators::GeneratorTestMode;
+#[cfg(feature = "unified-diff")] use pact_models::bodies::OptionalBody;
+use pact_models::generators::{Generator, GeneratorCategory, GeneratorTestMode, RANDOM_SEED};
 use pact_models::http_utils::HttpAuth;
 use pact_models::interaction::Interaction;
 use pact_models::json_utils::json_to_string;
 use pact_models::pact::{load_pact_from_json, Pact, read_pact};
 use pact_models::prelude::v4::SynchronousHttp;
 use pact_models::provider_states::*;
+use pact_models::v4::http_parts::HttpResponse;
 use pact_models::v4::interaction::V4Interaction;
 #[cfg(feature = "plugins")] use pact_plugin_driver::{catalogue_manager, plugin_manager};
 #[cfg(feature = "plugins")] use pact_plugin_driver::catalogue_manager::{CatalogueEntry, CatalogueEntryProviderType};
@@ -48,18 +52,31 @@ use pact_matching::{match_response, Mismatch};
 use pact_matching::logging::LOG_ID;
 use pact_matching::metrics::{MetricEvent, send_metrics_async};
 
+use crate::bundle::{DeferredVerificationResult, PactBundle, write_deferred_result};
 use crate::callback_executors::{ProviderStateError, ProviderStateExecutor};
 use crate::messages::{process_message_result, process_sync_message_result, verify_message_from_provider, verify_sync_message_from_provider};
 use crate::metrics::VerificationMetrics;
+use crate::metrics_emitter::MetricsEmitter;
 use crate::pact_broker::{
+  build_payload,
   Link,
   PactBrokerError,
   PactVerificationContext,
   publish_verification_results,
-  TestResult
+  TestResult,
+  VerificationResultData
 };
 pub use crate::pact_broker::{ConsumerVersionSelector, PactsForVerificationRequest};
+use crate::capabilities::{ProviderCapabilities, ProviderCapabilityCheck};
+use crate::clock_skew::{ClockSkewCheck, clock_skew_mismatch};
+use crate::failures::{FailureCategory, INTERACTION_TIMED_OUT, is_retryable, provider_request_error, STATE_CHANGE_FAILED};
+use crate::message_routing::MessageRouting;
+use crate::pact_age::PactAgeCheck;
+use crate::pact_sources::{CustomPactSource, source_for_url};
+use crate::provider_readiness::ProviderReadinessCheck;
+use crate::provider_state_catalogue::ProviderStateCatalogue;
 use crate::provider_client::make_provider_request;
+use crate::response_validators::ResponseValidator;
 use crate::request_response::process_request_response_result;
 use crate::utils::as_safe_ref;
 use crate::verification_result::{
@@ -75,10 +92,27 @@ mod request_response;
 mod messages;
 pub mod selectors;
 pub mod metrics;
+pub mod metrics_emitter;
 pub mod verification_result;
+pub mod response_validators;
+pub mod clock_skew;
+pub mod message_routing;
+pub mod custom_transports;
+pub mod digests;
+pub mod pact_age;
+pub mod provider_readiness;
+pub mod provider_state_catalogue;
+pub mod capabilities;
+pub mod failures;
+pub mod grpc_transport;
+pub mod bundle;
+pub mod pact_sources;
+#[cfg(feature = "sql-states")] pub mod sql_state_executor;
 mod utils;
+#[cfg(feature = "unified-diff")] mod diff;
 
-const VERIFIER_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Version of the verifier library
+pub const VERIFIER_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Source for loading pacts
 #[derive(Debug, Clone)]
@@ -89,6 +123,18 @@ pub enum PactSource {
     File(String),
     /// Load all the pacts from a Directory
     Dir(String),
+    /// Load all the pacts from a Directory, only including the files whose paths (relative to the
+    /// directory) match the include globs and do not match any of the exclude globs
+    DirWithFilters {
+      /// Directory to load the pacts from
+      dir: String,
+      /// Globs the pact files must match. All files are included if this is empty
+      include: Vec<String>,
+      /// Globs of pact files to exclude
+      exclude: Vec<String>,
+      /// If the sub-directories should also be searched for pact files
+      recursive: bool
+    },
     /// Load the pact from a URL
     URL(String, Option<HttpAuth>),
     /// Load all pacts with the provider name from the pact broker url
@@ -124,7 +170,22 @@ pub enum PactSource {
       broker_url: String,
       /// HTTP authentication details for accessing the Pact Broker
       auth: Option<HttpAuth>
-    }
+    },
+    /// Load the pacts from a bundle file previously fetched from a Pact Broker (see the bundle
+    /// module). The verification results are written to the results file to be published later.
+    Bundle {
+      /// Bundle file to load the pacts from
+      file: String,
+      /// File to write the verification results to
+      results_file: Option<String>,
+      /// Base URL of the Pact Broker the Pact was fetched from. Internal field
+      broker_url: Option<String>,
+      /// Links to the specific Pact resources. Internal field
+      links: Vec<Link>
+    },
+    /// Load the pacts from a source implemented by the calling application (see the pact_sources
+    /// module)
+    Custom(Arc<dyn CustomPactSource>)
 }
 
 impl Display for PactSource {
@@ -132,6 +193,9 @@ impl Display for PactSource {
     match self {
       PactSource::File(file) => write!(f, "File({})", file),
       PactSource::Dir(dir) => write!(f, "Dir({})", dir),
+      PactSource::DirWithFilters { dir, include, exclude, recursive } => {
+        write!(f, "Dir({}, include={:?}, exclude={:?}, recursive={})", dir, include, exclude, recursive)
+      }
       PactSource::URL(url, _) => write!(f, "URL({})", url),
       PactSource::BrokerUrl(provider_name, broker_url, _, _) => {
           write!(f, "PactBroker({}, provider_name='{}')", broker_url, provider_name)
@@ -158,11 +222,56 @@ impl Display for PactSource {
           write!(f, "WebhookCallbackUrl({}, auth=None')", pact_url)
         }
       }
+      PactSource::Bundle { file, .. } => write!(f, "Bundle({})", file),
+      PactSource::Custom(source) => write!(f, "Custom({})", source.description()),
       _ => write!(f, "Unknown")
     }
   }
 }
 
+impl PactSource {
+  /// Creates a directory source that only includes the pact files that match the include globs
+  /// and do not match the exclude globs. Returns an error if any of the globs are not valid.
+  pub fn dir_with_filters(dir: &str, include: &[String], exclude: &[String]) -> anyhow::Result<PactSource> {
+    build_glob_set(include)?;
+    build_glob_set(exclude)?;
+    Ok(PactSource::DirWithFilters {
+      dir: dir.to_string(),
+      include: include.to_vec(),
+      exclude: exclude.to_vec(),
+      recursive: false
+    })
+  }
+
+  /// Creates a directory source that also searches all the sub-directories for pact files. The
+  /// include and exclude globs are matched against the paths relative to the directory, so
+  /// `**/*-web.json` will include any file ending in `-web.json` at any depth. Returns an error
+  /// if any of the globs are not valid.
+  pub fn recursive_dir(dir: &str, include: &[String], exclude: &[String]) -> anyhow::Result<PactSource> {
+    build_glob_set(include)?;
+    build_glob_set(exclude)?;
+    Ok(PactSource::DirWithFilters {
+      dir: dir.to_string(),
+      include: include.to_vec(),
+      exclude: exclude.to_vec(),
+      recursive: true
+    })
+  }
+
+  /// If the pacts from this source are loaded from the local file system
+  pub fn is_local(&self) -> bool {
+    matches!(self, PactSource::File(_) | PactSource::Dir(_) | PactSource::DirWithFilters { .. })
+  }
+}
+
+fn build_glob_set(globs: &[String]) -> anyhow::Result<GlobSet> {
+  let mut builder = GlobSetBuilder::new();
+  for glob in globs {
+    builder.add(Glob::new(glob).map_err(|err| anyhow!("'{}' is not a valid glob - {}", glob, err))?);
+  }
+  builder.build().map_err(|err| anyhow!(err))
+}
+
 /// Information about the Provider to verify
 #[derive(Debug, Clone)]
 pub struct ProviderTransport {
@@ -336,17 +445,49 @@ impl Clone for MismatchResult {
   }
 }
 
+/// Applies any provider state generators for the status and headers to the expected response, so
+/// the values returned from the provider state change requests can be used in the expectations.
+/// Other generators are not applied, as they only apply to responses returned by a mock server.
+async fn apply_provider_state_values(
+  response: &HttpResponse,
+  verification_context: &HashMap<&str, Value>
+) -> HttpResponse {
+  let mut expected = response.clone();
+  expected.generators.categories.retain(|category, generators| {
+    generators.retain(|_, generator| matches!(generator, Generator::ProviderStateGenerator(_, _)));
+    matches!(category, GeneratorCategory::STATUS | GeneratorCategory::HEADER) && !generators.is_empty()
+  });
+  if expected.generators.is_empty() {
+    response.clone()
+  } else {
+    let (generated, report) = pact_matching::generate_response_with_report(&expected,
+      &GeneratorTestMode::Provider, verification_context).await;
+    for generated_value in &report.values {
+      debug!("Applied generator to the expected response: {}", generated_value);
+    }
+    HttpResponse {
+      status: generated.status,
+      headers: generated.headers,
+      .. response.clone()
+    }
+  }
+}
+
 async fn verify_response_from_provider<F: RequestFilterExecutor>(
   provider: &ProviderInfo,
   interaction: &SynchronousHttp,
   pact: &Box<dyn Pact + Send + Sync + RefUnwindSafe>,
   options: &VerificationOptions<F>,
   client: &Client,
-  verification_context: &HashMap<&str, Value>
+  verification_context: &HashMap<&str, Value>,
+  warnings: &mut Vec<String>
 ) -> Result<Option<String>, MismatchResult> {
-  let expected_response = &interaction.response;
-  let request = pact_matching::generate_request(&interaction.request,
+  let expected_response = &apply_provider_state_values(&interaction.response, verification_context).await;
+  let (request, generator_report) = pact_matching::generate_request_with_report(&interaction.request,
     &GeneratorTestMode::Provider, &verification_context).await;
+  for generated_value in &generator_report.values {
+    debug!("Applied generator: {}", generated_value);
+  }
   let transport = if let Some(transport) = &interaction.transport {
     provider.transports
       .iter()
@@ -369,7 +510,20 @@ async fn verify_response_from_provider<F: RequestFilterExecutor>(
   });
   match make_provider_request(provider, &request, options, client, transport).await {
     Ok(ref actual_response) => {
-      let mismatches = match_response(expected_response.clone(), actual_response.clone(), pact, &interaction.boxed()).await;
+      let mut mismatches = match_response(expected_response.clone(), actual_response.clone(), pact, &interaction.boxed()).await;
+      for validator in &options.response_validators {
+        mismatches.extend(validator.validate(&request, actual_response));
+      }
+      if let Some(check) = &options.clock_skew_check {
+        if let Some(problem) = check.check(actual_response, SystemTime::now()) {
+          if check.fail_verification {
+            mismatches.push(clock_skew_mismatch(actual_response, &problem));
+          } else {
+            warn!("{}", problem);
+            warnings.push(problem);
+          }
+        }
+      }
       if mismatches.is_empty() {
         Ok(interaction.id.clone())
       } else {
@@ -382,7 +536,7 @@ async fn verify_response_from_provider<F: RequestFilterExecutor>(
       }
     },
     Err(err) => {
-      Err(MismatchResult::Error(err.to_string(), interaction.id.clone()))
+      Err(MismatchResult::Error(provider_request_error(&err), interaction.id.clone()))
     }
   }
 }
@@ -406,14 +560,16 @@ async fn execute_state_change<S: ProviderStateExecutor>(
 }
 
 /// Main implementation for verifying an interaction. Will return a tuple containing the
-/// result of the verification and any output collected plus the time taken to execute
+/// result of the verification and any output collected plus the time taken to execute. Any
+/// warnings raised during the verification are added to `warnings`.
 #[tracing::instrument(level = "trace", skip_all)]
 async fn verify_interaction<'a, F: RequestFilterExecutor, S: ProviderStateExecutor>(
   provider: &ProviderInfo,
   interaction: &(dyn Interaction + Send + Sync + RefUnwindSafe),
   pact: &Box<dyn Pact + Send + Sync + RefUnwindSafe + 'a>,
   options: &VerificationOptions<F>,
-  provider_state_executor: &Arc<S>
+  provider_state_executor: &Arc<S>,
+  warnings: &mut Vec<String>
 ) -> Result<(Option<String>, Vec<String>, Duration), (MismatchResult, Vec<String>, Duration)> {
   let start = Instant::now();
   trace!("Verifying interaction {} {} ({:?})", interaction.type_of(), interaction.description(), interaction.id());
@@ -428,14 +584,108 @@ async fn verify_interaction<'a, F: RequestFilterExecutor, S: ProviderStateExecut
   let context = execute_provider_states(interaction, provider_state_executor, &client, true)
     .await
     .map_err(|e| (e, vec![], start.elapsed()))?;
-  let provider_states_context = context
+  let mut provider_states_context: HashMap<&str, Value> = context
     .iter()
     .map(|(k, v)| (k.as_str(), v.clone()))
     .collect();
+  if let Some(seed) = options.generator_seed {
+    provider_states_context.insert(RANDOM_SEED, Value::from(seed));
+  }
 
   info!("Running provider verification for '{}'", interaction.description());
   trace!("Interaction to verify: {:?}", interaction);
 
+  let custom_transport = if interaction.is_v4() {
+    interaction.as_v4()
+      .and_then(|i| i.transport())
+      .and_then(|t| custom_transports::lookup_transport(&t).map(|executor| (t, executor)))
+  } else {
+    None
+  };
+
+  let result = if let Some((transport, executor)) = &custom_transport {
+    trace!("Verifying interaction via custom transport {}", transport);
+    custom_transports::verify_interaction_using_custom_transport(transport, executor, interaction,
+      pact, &provider_states_context)
+      .await
+      .map(|r| (r, vec![]))
+      .map_err(|e| (e, vec![]))
+  } else {
+    verify_interaction_with_provider(provider, interaction, pact, options, &client,
+      &provider_states_context, warnings).await
+  };
+
+  if provider_state_executor.teardown() {
+    execute_provider_states(interaction, provider_state_executor, &client, false)
+      .await
+      .map_err(|e| (e, vec![], start.elapsed()))?;
+  }
+
+  result
+    .map(|(id, output)| (id, output, start.elapsed()))
+    .map_err(|(result, output)| (result, output, start.elapsed()))
+}
+
+/// Verifies the interaction, applying the interaction timeout and verification deadline from the
+/// options, and retrying the interaction if it timed out or the provider could not be reached.
+/// If an interaction times out, the teardown state change for it will not be called.
+async fn verify_interaction_with_timeouts<'a, F: RequestFilterExecutor, S: ProviderStateExecutor>(
+  provider: &ProviderInfo,
+  interaction: &(dyn Interaction + Send + Sync + RefUnwindSafe),
+  pact: &Box<dyn Pact + Send + Sync + RefUnwindSafe + 'a>,
+  options: &VerificationOptions<F>,
+  provider_state_executor: &Arc<S>,
+  warnings: &mut Vec<String>
+) -> Result<(Option<String>, Vec<String>, Duration), (MismatchResult, Vec<String>, Duration)> {
+  let mut attempt = 0;
+  loop {
+    let remaining = options.deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+    if remaining == Some(Duration::ZERO) {
+      return Err((MismatchResult::Error(
+        format!("{} - the verification deadline was reached before it was verified", INTERACTION_TIMED_OUT),
+        interaction.id()), vec![], Duration::ZERO));
+    }
+
+    let timeout = match (options.interaction_timeout, remaining) {
+      (Some(timeout), Some(remaining)) => Some(timeout.min(remaining)),
+      (timeout, remaining) => timeout.or(remaining)
+    };
+    let result = match timeout {
+      Some(timeout) => {
+        let future = verify_interaction(provider, interaction, pact, options, provider_state_executor, warnings);
+        match tokio::time::timeout(timeout, future).await {
+          Ok(result) => result,
+          Err(_) => Err((MismatchResult::Error(
+            format!("{} after {}", INTERACTION_TIMED_OUT, format_duration(timeout)),
+            interaction.id()), vec![], timeout))
+        }
+      }
+      None => verify_interaction(provider, interaction, pact, options, provider_state_executor, warnings).await
+    };
+
+    match &result {
+      Err((err @ MismatchResult::Error(description, _), _, _)) if attempt < options.interaction_retries && is_retryable(err) => {
+        attempt += 1;
+        warn!("Verifying interaction '{}' failed, retrying it ({} of {}) - {}", interaction.description(),
+          attempt, options.interaction_retries, description);
+        warnings.push(format!("Interaction was retried after it failed - {}", description));
+      }
+      _ => return result
+    }
+  }
+}
+
+/// Verify an interaction by sending the request to the provider, using a plugin transport if
+/// one is registered for the interaction
+async fn verify_interaction_with_provider<'a, F: RequestFilterExecutor>(
+  provider: &ProviderInfo,
+  interaction: &(dyn Interaction + Send + Sync + RefUnwindSafe),
+  pact: &Box<dyn Pact + Send + Sync + RefUnwindSafe + 'a>,
+  options: &VerificationOptions<F>,
+  client: &Arc<Client>,
+  provider_states_context: &HashMap<&str, Value>,
+  warnings: &mut Vec<String>
+) -> Result<(Option<String>, Vec<String>), (MismatchResult, Vec<String>)> {
   #[allow(unused_assignments)] let mut result = Ok((None, vec![]));
   #[cfg(feature = "plugins")]
   {
@@ -449,9 +699,9 @@ async fn verify_interaction<'a, F: RequestFilterExecutor, S: ProviderStateExecut
 
     result = if let Some(transport) = &transport {
       trace!("Verifying interaction via {}", transport.key);
-      verify_interaction_using_transport(transport, provider, interaction, pact, options, &client, &provider_states_context).await
+      verify_interaction_using_transport(transport, provider, interaction, pact, options, client, provider_states_context, warnings).await
     } else {
-      verify_v3_interaction(provider, interaction, &pact, options, &client, &provider_states_context)
+      verify_v3_interaction(provider, interaction, pact, options, client, provider_states_context, warnings)
         .await
         .map(|r| (r, vec![]))
         .map_err(|e| (e, vec![]))
@@ -460,21 +710,13 @@ async fn verify_interaction<'a, F: RequestFilterExecutor, S: ProviderStateExecut
 
   #[cfg(not(feature = "plugins"))]
   {
-    result = verify_v3_interaction(provider, interaction, &pact, options, &client, &provider_states_context)
+    result = verify_v3_interaction(provider, interaction, pact, options, client, provider_states_context, warnings)
       .await
       .map(|r| (r, vec![]))
       .map_err(|e| (e, vec![]));
   }
 
-  if provider_state_executor.teardown() {
-    execute_provider_states(interaction, provider_state_executor, &client, false)
-      .await
-      .map_err(|e| (e, vec![], start.elapsed()))?;
-  }
-
   result
-    .map(|(id, output)| (id, output, start.elapsed()))
-    .map_err(|(result, output)| (result, output, start.elapsed()))
 }
 
 /// Verify an interaction using the provided transport
@@ -486,7 +728,8 @@ async fn verify_interaction_using_transport<'a, F: RequestFilterExecutor>(
   pact: &Box<dyn Pact + Send + Sync + RefUnwindSafe + 'a>,
   options: &VerificationOptions<F>,
   client: &Arc<Client>,
-  config: &HashMap<&str, Value>
+  config: &HashMap<&str, Value>,
+  warnings: &mut Vec<String>
 ) -> Result<(Option<String>, Vec<String>), (MismatchResult, Vec<String>)> {
   if transport_entry.provider_type == CatalogueEntryProviderType::PLUGIN {
     match pact.as_v4_pact() {
@@ -529,6 +772,20 @@ async fn verify_interaction_using_transport<'a, F: RequestFilterExecutor>(
           }
         }
 
+        // Send gRPC requests with the built-in transport if it is enabled
+        if options.builtin_grpc_transport && grpc_transport::is_grpc_transport(&transport_entry.key) {
+          if let Some(message) = interaction.as_v4_sync_message() {
+            let port = port.ok_or_else(|| (MismatchResult::Error(
+              "No port is configured for the gRPC transport".to_string(), interaction.id()), vec![]))?;
+            trace!("Verifying interaction via the built-in gRPC transport");
+            return grpc_transport::verify_grpc_interaction(&format!("http://{}:{}", provider.host, port),
+              &message, &request_data, &metadata, options, &pact.boxed())
+              .await
+              .map(|r| (r, vec![]))
+              .map_err(|e| (e, vec![]));
+          }
+        }
+
         // Invoke any callback to mutate the data
         let (request_body, request_metadata) = if let Some(filter) = &options.request_filter {
           info!("Invoking request filter for request data");
@@ -559,7 +816,8 @@ async fn verify_interaction_using_transport<'a, F: RequestFilterExecutor>(
                     path: path.clone(),
                     expected: Some(expected.clone()),
                     actual: Some(actual.clone()),
-                    mismatch: mismatch.clone()
+                    mismatch: mismatch.clone(),
+                    rule: None
                   })
                 }
               }).collect(),
@@ -578,7 +836,7 @@ async fn verify_interaction_using_transport<'a, F: RequestFilterExecutor>(
       }
     }
   } else {
-    verify_v3_interaction(provider, interaction, pact, options, client, config)
+    verify_v3_interaction(provider, interaction, pact, options, client, config, warnings)
       .await
       .map(|r| (r, vec![]))
       .map_err(|e| (e, vec![]))
@@ -592,7 +850,8 @@ async fn verify_v3_interaction<'a, F: RequestFilterExecutor>(
   pact: &Box<dyn Pact + Send + Sync + RefUnwindSafe + 'a>,
   options: &VerificationOptions<F>,
   client: &Arc<Client>,
-  provider_states_context: &HashMap<&str, Value>
+  provider_states_context: &HashMap<&str, Value>,
+  warnings: &mut Vec<String>
 ) -> Result<Option<String>, MismatchResult> {
   let mut result = Err(MismatchResult::Error("No interaction was verified".into(), interaction.id().clone()));
 
@@ -600,7 +859,7 @@ async fn verify_v3_interaction<'a, F: RequestFilterExecutor>(
   if let Some(interaction) = interaction.as_v4_http() {
     debug!("Verifying a HTTP interaction");
     result = verify_response_from_provider(provider, &interaction, &pact.boxed(), options,
-                                           &client, &provider_states_context).await;
+                                           &client, &provider_states_context, warnings).await;
   }
   // Verify an asynchronous message (single shot)
   if interaction.is_message() {
@@ -661,7 +920,7 @@ async fn execute_provider_states<S: ProviderStateExecutor>(
 
   if sc_results.iter().any(|result| result.is_err()) {
     return Err(MismatchResult::Error(
-      format!("One or more of the {} state change handlers has failed", sc_type), interaction.id()))
+      format!("One or more of the {} {}", sc_type, STATE_CHANGE_FAILED), interaction.id()))
   } else {
     for result in sc_results {
       if let Ok(data) = result {
@@ -712,7 +971,7 @@ fn setup_custom_headers(custom_headers: &HashMap<String, String>) -> anyhow::Res
 }
 
 fn generate_display_for_result(
-  status: u16,
+  status: String,
   status_result: ANSIGenericString<str>,
   header_results: Option<Vec<(String, String, ANSIGenericString<str>)>>,
   body_result: ANSIGenericString<str>,
@@ -721,7 +980,7 @@ fn generate_display_for_result(
 ) {
   output.push("    returns a response which".to_string());
   let style = if coloured { Style::new().bold() } else { Style::new() };
-  output.push(format!("      has status code {} ({})", style.paint(format!("{}", status)),
+  output.push(format!("      has status code {} ({})", style.paint(status),
       status_result));
   if let Some(header_results) = header_results {
     output.push("      includes headers".to_string());
@@ -735,15 +994,25 @@ fn generate_display_for_result(
 
 fn walkdir(
   dir: &Path,
-  provider: &ProviderInfo
+  provider: &ProviderInfo,
+  recursive: bool,
+  filter: &dyn Fn(&Path) -> bool
 ) -> anyhow::Result<Vec<anyhow::Result<(Box<dyn Pact + Send + Sync + RefUnwindSafe>, Duration)>>> {
     let mut pacts = vec![];
     debug!("Scanning {:?}", dir);
-    for entry in fs::read_dir(dir)? {
-        let entry = entry?;
-        let path = entry.path();
+    let mut entries = fs::read_dir(dir)?
+      .map(|entry| entry.map(|entry| entry.path()))
+      .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    for path in entries {
         if path.is_dir() {
-            walkdir(&path, provider)?;
+          if recursive {
+            pacts.extend(walkdir(&path, provider, recursive, filter)?);
+          } else {
+            trace!("Ignoring sub-directory {:?} as the directory is not being searched recursively", path);
+          }
+        } else if !filter(&path) {
+          trace!("Ignoring {:?} as it does not match the directory filters", path);
         } else {
           match timeit(|| read_pact(&path)) {
             Ok((pact, tm)) => {
@@ -762,13 +1031,67 @@ fn display_body_mismatch(
   expected: &dyn Interaction,
   actual: &dyn Interaction,
   path: &str,
-  output: &mut Vec<String>
+  output: &mut Vec<String>,
+  coloured_output: bool,
+  diff_context: usize
 ) {
-  if expected.contents_for_verification().content_type().unwrap_or_default().is_json() {
-    output.push(pact_matching::json::display_diff(
+  if let Some(diff) = body_mismatch_diff(expected, actual, path, "    ", coloured_output, diff_context) {
+    output.push(diff);
+  }
+}
+
+/// Generates a diff of the expected and actual bodies of the interactions, with each line
+/// prefixed with the indent. If the `unified-diff` feature is enabled, text bodies will be
+/// displayed as a unified diff of the whole body with the given number of lines of context (so
+/// the diff covers all the body mismatches, and the path is ignored). Otherwise only JSON bodies
+/// are supported, and the diff is of the fragment of the bodies at the mismatch path. Returns
+/// `None` if a diff can not be generated for the body.
+pub fn body_mismatch_diff(
+  expected: &dyn Interaction,
+  actual: &dyn Interaction,
+  path: &str,
+  indent: &str,
+  coloured_output: bool,
+  diff_context: usize
+) -> Option<String> {
+  let content_type = expected.contents_for_verification().content_type().unwrap_or_default();
+
+  #[cfg(feature = "unified-diff")]
+  {
+    if content_type.is_text() {
+      let expected_body = body_for_diff(&expected.contents_for_verification(), content_type.is_json());
+      let actual_body = body_for_diff(&actual.contents_for_verification(), content_type.is_json());
+      return Some(diff::unified_diff(&expected_body, &actual_body, diff_context, indent, coloured_output));
+    }
+  }
+
+  // The colour and context options only apply to unified diffs
+  #[cfg(not(feature = "unified-diff"))]
+  let _ = (coloured_output, diff_context);
+
+  if content_type.is_json() {
+    Some(pact_matching::json::display_diff(
       &expected.contents_for_verification().display_string().to_string(),
       &actual.contents_for_verification().display_string().to_string(),
-      path, "    "));
+      path, indent))
+  } else {
+    None
+  }
+}
+
+/// Returns the body as text for diffing. JSON bodies are pretty printed so that the differences
+/// can be displayed line by line.
+#[cfg(feature = "unified-diff")]
+fn body_for_diff(body: &OptionalBody, is_json: bool) -> String {
+  let text = body.display_string();
+  if is_json {
+    serde_json::from_str::<Value>(&text)
+      .ok()
+      .and_then(|json| serde_json::to_string_pretty(&json).ok())
+      .map(|json| json + "\n")
+      .unwrap_or(text)
+  } else {
+    text
   }
 }
 
@@ -782,16 +1105,30 @@ pub enum FilterInfo {
     /// Filter on the interaction provider state
     State(String),
     /// Filter on both the interaction description and provider state
-    DescriptionAndState(String, String)
+    DescriptionAndState(String, String),
+    /// Apply the filter, and also only verify interactions that have a provider state with
+    /// parameters matching all the given parameter filters
+    WithStateParameters(Box<FilterInfo>, Vec<StateParameterFilter>)
 }
 
 impl FilterInfo {
 
+    /// Adds the provider state parameter filters to this filter. Returns the filter unchanged if
+    /// there are no parameter filters.
+    pub fn with_state_parameters(self, parameters: Vec<StateParameterFilter>) -> FilterInfo {
+        if parameters.is_empty() {
+            self
+        } else {
+            FilterInfo::WithStateParameters(Box::new(self), parameters)
+        }
+    }
+
     /// If this filter is filtering on description
     pub fn has_description(&self) -> bool {
         match *self {
             FilterInfo::Description(_) => true,
             FilterInfo::DescriptionAndState(_, _) => true,
+            FilterInfo::WithStateParameters(ref filter, _) => filter.has_description(),
             _ => false
         }
     }
@@ -801,6 +1138,7 @@ impl FilterInfo {
         match *self {
             FilterInfo::State(_) => true,
             FilterInfo::DescriptionAndState(_, _) => true,
+            FilterInfo::WithStateParameters(_, _) => true,
             _ => false
         }
     }
@@ -810,6 +1148,7 @@ impl FilterInfo {
         match *self {
             FilterInfo::State(ref s) => s.clone(),
             FilterInfo::DescriptionAndState(_, ref s) => s.clone(),
+            FilterInfo::WithStateParameters(ref filter, _) => filter.state(),
             _ => String::default()
         }
     }
@@ -819,6 +1158,7 @@ impl FilterInfo {
         match *self {
             FilterInfo::Description(ref s) => s.clone(),
             FilterInfo::DescriptionAndState(ref s, _) => s.clone(),
+            FilterInfo::WithStateParameters(ref filter, _) => filter.description(),
             _ => String::default()
         }
     }
@@ -829,7 +1169,17 @@ impl FilterInfo {
     /// # Panics
     /// If the state filter value can't be parsed as a regular expression
     pub fn match_state(&self, interaction: &dyn Interaction) -> bool {
-      if !interaction.provider_states().is_empty() {
+      if let FilterInfo::WithStateParameters(filter, parameters) = self {
+        let name_filter = if filter.has_state() && !filter.state().is_empty() {
+          Some(Regex::new(&filter.state()).unwrap())
+        } else {
+          None
+        };
+        interaction.provider_states().iter().any(|state| {
+          name_filter.as_ref().map(|re| re.is_match(&state.name)).unwrap_or(true) &&
+            parameters.iter().all(|parameter| parameter.matches(state))
+        })
+      } else if !interaction.provider_states().is_empty() {
         if self.state().is_empty() {
           false
         } else {
@@ -851,6 +1201,84 @@ impl FilterInfo {
     }
 }
 
+/// Filter on a provider state parameter. The expression syntax is `NAME=VALUE`, where `VALUE` is
+/// a regular expression that must match the whole parameter value (non-string values are
+/// matched against their JSON form). Just `NAME` will match any provider state that has the
+/// parameter.
+#[derive(Debug, Clone)]
+pub struct StateParameterFilter {
+    /// Name of the provider state parameter
+    name: String,
+    /// Regular expression the parameter value must match
+    value: Option<String>,
+    /// Compiled form of the value expression, anchored to match the whole value
+    regex: Option<Regex>
+}
+
+impl StateParameterFilter {
+    /// Creates a filter for the named provider state parameter, with an optional regular
+    /// expression the parameter value must match. Returns an error if the name is empty or the
+    /// value is not a valid regular expression.
+    pub fn new(name: &str, value: Option<&str>) -> anyhow::Result<Self> {
+      let name = name.trim();
+      if name.is_empty() {
+        return Err(anyhow!("The provider state parameter filter name can not be empty"));
+      }
+      let regex = value.map(|value| Regex::new(&format!("^(?:{})$", value))
+          .map_err(|err| anyhow!("'{}' is not a valid regular expression - {}", value, err)))
+        .transpose()?;
+      Ok(StateParameterFilter {
+        name: name.to_string(),
+        value: value.map(|value| value.to_string()),
+        regex
+      })
+    }
+
+    /// Name of the provider state parameter
+    pub fn name(&self) -> &str {
+      self.name.as_str()
+    }
+
+    /// Regular expression the parameter value must match, if any
+    pub fn value(&self) -> Option<&str> {
+      self.value.as_deref()
+    }
+
+    /// If the provider state has a parameter matching this filter
+    pub fn matches(&self, state: &ProviderState) -> bool {
+      match state.params.get(&self.name) {
+        Some(value) => match &self.regex {
+          Some(re) => re.is_match(&json_to_string(value)),
+          None => true
+        }
+        None => false
+      }
+    }
+}
+
+impl PartialEq for StateParameterFilter {
+    fn eq(&self, other: &Self) -> bool {
+      self.name == other.name && self.value == other.value
+    }
+}
+
+impl Eq for StateParameterFilter {}
+
+impl FromStr for StateParameterFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+      let (name, value) = match s.split_once('=') {
+        Some((name, value)) => (name, Some(value)),
+        None => (s, None)
+      };
+      if name.trim().is_empty() {
+        return Err(anyhow!("'{}' is not a valid provider state parameter filter, expected NAME=VALUE", s));
+      }
+      StateParameterFilter::new(name, value)
+    }
+}
+
 fn filter_interaction(interaction: &dyn Interaction, filter: &FilterInfo) -> bool {
   if filter.has_description() && filter.has_state() {
     filter.match_description(interaction) && filter.match_state(interaction)
@@ -908,7 +1336,51 @@ pub struct VerificationOptions<F> where F: RequestFilterExecutor {
   /// If coloured output should be used (using ANSI escape codes)
   pub coloured_output: bool,
   /// If no pacts are found to verify, then this should be an error
-  pub no_pacts_is_error: bool
+  pub no_pacts_is_error: bool,
+  /// Number of lines of context to display around the changes in body diffs (requires the
+  /// unified-diff feature)
+  pub diff_context: usize,
+  /// Validators to run against each response returned by the provider
+  pub response_validators: Vec<Arc<dyn ResponseValidator>>,
+  /// Check of the Date header returned by the provider against the verifier's clock
+  pub clock_skew_check: Option<ClockSkewCheck>,
+  /// Maximum number of interactions to verify concurrently. Only interactions without provider
+  /// states are verified in parallel. Interactions with provider states are verified one at a time,
+  /// with no other interactions being verified while their states are set up.
+  pub parallelism: usize,
+  /// Routes the requests for message interactions to different provider handlers, based on the
+  /// message metadata
+  pub message_routing: Option<MessageRouting>,
+  /// Expected SHA-256 digests of the pact sources, keyed by the pact file path or URL. Pacts
+  /// whose contents do not match the digest will not be verified.
+  pub pact_digests: HashMap<String, String>,
+  /// Check of the age of the pacts, using the creation time recorded in the pact metadata
+  pub pact_age_check: Option<PactAgeCheck>,
+  /// Waits for the provider to become ready, by polling a health check URL, before starting the
+  /// verification
+  pub provider_readiness_check: Option<ProviderReadinessCheck>,
+  /// Publishes counts and durations from the verification to a metrics backend. This is opt-in,
+  /// and separate from the anonymous usage telemetry.
+  pub metrics_emitter: Option<MetricsEmitter>,
+  /// Fetches the capabilities of the provider before the verification. Interactions that require
+  /// capabilities the provider does not have will be skipped.
+  pub provider_capability_check: Option<ProviderCapabilityCheck>,
+  /// Send the requests for gRPC interactions with the built-in gRPC transport. The plugin is
+  /// then only used to encode the request message and compare the response message, and the
+  /// request filter is applied to the gRPC requests.
+  pub builtin_grpc_transport: bool,
+  /// Seed for the random generators. If set, the same values will be generated for the requests
+  /// each time the pacts are verified.
+  pub generator_seed: Option<u64>,
+  /// Timeout for verifying each interaction, including the provider state changes. Unlike the
+  /// request timeout, this also applies to interactions verified with plugin or custom transports.
+  pub interaction_timeout: Option<Duration>,
+  /// Number of times to retry an interaction that timed out or where the provider could not be
+  /// reached
+  pub interaction_retries: usize,
+  /// Time by which the whole verification must be complete. Interactions that have not been
+  /// verified by then will fail with a timeout error.
+  pub deadline: Option<Instant>
 }
 
 impl <F: RequestFilterExecutor> Default for VerificationOptions<F> {
@@ -919,7 +1391,22 @@ impl <F: RequestFilterExecutor> Default for VerificationOptions<F> {
       request_timeout: 5000,
       custom_headers: Default::default(),
       coloured_output: true,
-      no_pacts_is_error: true
+      no_pacts_is_error: true,
+      diff_context: 3,
+      response_validators: vec![],
+      clock_skew_check: None,
+      parallelism: 1,
+      message_routing: None,
+      pact_digests: Default::default(),
+      pact_age_check: None,
+      provider_readiness_check: None,
+      metrics_emitter: None,
+      provider_capability_check: None,
+      builtin_grpc_transport: false,
+      generator_seed: None,
+      interaction_timeout: None,
+      interaction_retries: 0,
+      deadline: None
     }
   }
 }
@@ -979,14 +1466,45 @@ pub async fn verify_provider_async<F: RequestFilterExecutor, S: ProviderStateExe
   pact_matching::matchers::configure_core_catalogue();
 
   LOG_ID.scope(format!("verify:{}", provider_info.name), async {
-    let pact_results = fetch_pacts(source, consumers, &provider_info).await;
-
+    let start = Instant::now();
     let mut total_results = 0;
     let mut pending_errors: Vec<(String, MismatchResult)> = vec![];
+    let mut wip_errors: Vec<(String, MismatchResult)> = vec![];
     let mut errors: Vec<(String, MismatchResult)> = vec![];
 
     let mut verification_result = VerificationExecutionResult::new();
 
+    if let Some(check) = &verification_options.provider_readiness_check {
+      let client = configure_http_client(verification_options)?;
+      check.wait(&client).await?;
+    }
+
+    let capabilities = match &verification_options.provider_capability_check {
+      Some(check) => {
+        let client = configure_http_client(verification_options)?;
+        Some(check.fetch(&client).await?)
+      }
+      None => None
+    };
+
+    let (source, pact_digests, digest_errors) = digests::check_pact_digests(source,
+      &verification_options.pact_digests).await;
+    verification_result.pact_digests = pact_digests;
+    for err in digest_errors {
+      let error = format!("{:#}", err);
+      if err.downcast_ref::<digests::PactDigestMismatch>().is_some() {
+        error!("Pact digest mismatch - {}", Red.paint(error.clone()));
+        errors.push(("Pact digest mismatch".to_string(), MismatchResult::Error(error, None)));
+        verification_result.failure_summary.add("Pact digest mismatch", FailureCategory::PactLoading);
+      } else {
+        error!("Failed to load pact - {}", Red.paint(error.clone()));
+        errors.push(("Failed to load pact".to_string(), MismatchResult::Error(error, None)));
+        verification_result.failure_summary.add("Failed to load pact", FailureCategory::for_load_error(&err));
+      }
+    }
+
+    let pact_results = fetch_pacts(source, consumers, &provider_info).await;
+
     for pact_result in pact_results {
       match pact_result {
         Ok((pact, context, pact_source, pact_source_duration)) => {
@@ -1009,13 +1527,44 @@ pub async fn verify_provider_async<F: RequestFilterExecutor, S: ProviderStateExe
 
           process_notices(&context, VERIFICATION_NOTICE_BEFORE, &mut verification_result);
 
+          let (pending, wip) = match &context {
+            Some(context) => (context.verification_properties.pending, context.verification_properties.wip),
+            None => (false, false)
+          };
+          let wip_label = if wip { " [WIP]" } else { "" };
           if verification_options.coloured_output {
-            verification_result.output.push(format!("\nVerifying a pact between {} and {}",
+            verification_result.output.push(format!("\nVerifying a pact between {} and {}{}",
               Style::new().bold().paint(pact.consumer().name.clone()),
-              Style::new().bold().paint(pact.provider().name.clone())));
+              Style::new().bold().paint(pact.provider().name.clone()),
+              Yellow.paint(wip_label)));
           } else {
-            verification_result.output.push(format!("\nVerifying a pact between {} and {}",
-              pact.consumer().name, pact.provider().name));
+            verification_result.output.push(format!("\nVerifying a pact between {} and {}{}",
+              pact.consumer().name, pact.provider().name, wip_label));
+          }
+
+          if let Some(check) = &verification_options.pact_age_check {
+            if let Some(problem) = check.check(pact.as_ref(), SystemTime::now()) {
+              if check.fail_verification {
+                error!("{}", problem);
+                let error = ("Stale pact".to_string(), MismatchResult::Error(problem, None));
+                if wip {
+                  wip_errors.push(error);
+                } else if pending {
+                  pending_errors.push(error);
+                } else {
+                  errors.push(error);
+                  verification_result.failure_summary.add("Stale pact", FailureCategory::Other);
+                }
+              } else {
+                warn!("{}", problem);
+                if verification_options.coloured_output {
+                  verification_result.output.push(Yellow.paint(format!("WARNING: {}", problem)).to_string());
+                } else {
+                  verification_result.output.push(format!("WARNING: {}", problem));
+                }
+                verification_result.warnings.push(("Stale pact".to_string(), problem));
+              }
+            }
           }
 
           if pact.interactions().is_empty() {
@@ -1027,18 +1576,16 @@ pub async fn verify_provider_async<F: RequestFilterExecutor, S: ProviderStateExe
               verification_result.output.push("WARNING: Pact file has no interactions".to_string());
             }
           } else {
-            let pending = match &context {
-              Some(context) => context.verification_properties.pending,
-              None => false
-            };
+            // Failures on WIP pacts are treated as pending, so they do not fail the verification
             let verify_result = verify_pact_internal(
               &provider_info,
               &filter,
               pact,
               &verification_options,
               &provider_state_executor.clone(),
-              pending,
-              pact_source_duration
+              pending || wip,
+              pact_source_duration,
+              capabilities.as_ref()
             ).await;
 
             let mut results = vec![];
@@ -1046,11 +1593,18 @@ pub async fn verify_provider_async<F: RequestFilterExecutor, S: ProviderStateExe
               Ok(result) => {
                 for interaction_result in &result.results {
                   results.push(interaction_result.clone());
+                  for warning in &interaction_result.warnings {
+                    verification_result.warnings.push((interaction_result.description.clone(), warning.clone()));
+                  }
                   if let Err(error) = &interaction_result.result {
-                    if interaction_result.pending {
+                    if wip {
+                      wip_errors.push((interaction_result.description.clone(), error.clone()));
+                    } else if interaction_result.pending {
                       pending_errors.push((interaction_result.description.clone(), error.clone()));
                     } else {
                       errors.push((interaction_result.description.clone(), error.clone()));
+                      verification_result.failure_summary.add(interaction_result.description.as_str(),
+                        FailureCategory::for_mismatch_result(error));
                     }
                   }
                 }
@@ -1058,14 +1612,20 @@ pub async fn verify_provider_async<F: RequestFilterExecutor, S: ProviderStateExe
                 for output in &result.output {
                   verification_result.output.push(output.clone());
                 }
+                verification_result.skipped.extend_from_slice(result.skipped.as_slice());
               }
               Err(err) => {
-                if pending {
+                if wip {
+                  wip_errors.push(("Could not verify the provided WIP pact".to_string(),
+                                   MismatchResult::Error(err.to_string(), None)));
+                } else if pending {
                   pending_errors.push(("Could not verify the provided pact".to_string(),
                                        MismatchResult::Error(err.to_string(), None)));
                 } else {
                   errors.push(("Could not verify the provided pact".to_string(),
                                MismatchResult::Error(err.to_string(), None)));
+                  verification_result.failure_summary.add("Could not verify the provided pact",
+                    FailureCategory::for_error(err));
                 }
               }
             }
@@ -1076,7 +1636,7 @@ pub async fn verify_provider_async<F: RequestFilterExecutor, S: ProviderStateExe
             if let Some(publish) = publish_options {
               publish_result(results.as_slice(), &pact_source, &publish, metrics_data.as_ref()).await;
 
-              if !errors.is_empty() || !pending_errors.is_empty() {
+              if !errors.is_empty() || !pending_errors.is_empty() || !wip_errors.is_empty() {
                 process_notices(&context, VERIFICATION_NOTICE_AFTER_ERROR_RESULT_AND_PUBLISH, &mut verification_result);
               } else {
                 process_notices(&context, VERIFICATION_NOTICE_AFTER_SUCCESSFUL_RESULT_AND_PUBLISH, &mut verification_result);
@@ -1095,6 +1655,7 @@ pub async fn verify_provider_async<F: RequestFilterExecutor, S: ProviderStateExe
             if verification_options.no_pacts_is_error {
               error!("Failed to load pact - {}", Red.paint(err.to_string()));
               errors.push(("Failed to load pact".to_string(), MismatchResult::Error(err.to_string(), None)));
+              verification_result.failure_summary.add("Failed to load pact", FailureCategory::PactBroker);
             } else {
               warn!("Ignoring no pacts error - {}", Yellow.paint(err.to_string()));
             }
@@ -1102,6 +1663,7 @@ pub async fn verify_provider_async<F: RequestFilterExecutor, S: ProviderStateExe
             let error = format!("{:#}", err);
             error!("Failed to load pact - {}", Red.paint(error.clone()));
             errors.push(("Failed to load pact".to_string(), MismatchResult::Error(error, None)));
+            verification_result.failure_summary.add("Failed to load pact", FailureCategory::for_load_error(&err));
           }
         }
       }
@@ -1125,16 +1687,48 @@ pub async fn verify_provider_async<F: RequestFilterExecutor, S: ProviderStateExe
     for (error, result) in &pending_errors {
       verification_result.pending_errors.push((error.clone(), result.into()));
     }
+    for (error, result) in &wip_errors {
+      verification_result.wip_errors.push((error.clone(), result.into()));
+    }
+
+    if !verification_result.warnings.is_empty() {
+      verification_result.output.push("\nWarnings:\n".to_string());
+      for (i, (description, warning)) in verification_result.warnings.iter().enumerate() {
+        verification_result.output.push(format!("{}) {}", i + 1, description));
+        verification_result.output.push(format!("    {}\n", warning));
+      }
+      verification_result.output.push(format!("There were {} warnings (these do not fail the verification)\n",
+        verification_result.warnings.len()));
+    }
+
+    if !verification_result.skipped.is_empty() {
+      verification_result.output.push("\nSkipped:\n".to_string());
+      for (i, (description, reason)) in verification_result.skipped.iter().enumerate() {
+        verification_result.output.push(format!("{}) {}", i + 1, description));
+        verification_result.output.push(format!("    {}\n", reason));
+      }
+      verification_result.output.push(format!("There were {} skipped interactions\n",
+        verification_result.skipped.len()));
+    }
 
     if !pending_errors.is_empty() {
       verification_result.output.push("\nPending Failures:\n".to_string());
-      process_errors(&pending_errors, &mut verification_result.output, verification_options.coloured_output);
+      process_errors(&pending_errors, &mut verification_result.output, verification_options.coloured_output,
+        verification_options.diff_context);
       verification_result.output.push(format!("\nThere were {} non-fatal pact failures on pending pacts or interactions (see docs.pact.io/pending for more information)\n", pending_errors.len()));
     }
 
+    if !wip_errors.is_empty() {
+      verification_result.output.push("\nWIP Pact Failures:\n".to_string());
+      process_errors(&wip_errors, &mut verification_result.output, verification_options.coloured_output,
+        verification_options.diff_context);
+      verification_result.output.push(format!("\nThere were {} non-fatal pact failures on work-in-progress (WIP) pacts (see docs.pact.io/wip for more information)\n", wip_errors.len()));
+    }
+
     if !errors.is_empty() {
       verification_result.output.push("\nFailures:\n".to_string());
-      process_errors(&errors, &mut verification_result.output, verification_options.coloured_output);
+      process_errors(&errors, &mut verification_result.output, verification_options.coloured_output,
+        verification_options.diff_context);
       verification_result.output.push(format!("\nThere were {} pact failures\n", errors.len()));
       verification_result.result = false;
     } else {
@@ -1146,18 +1740,119 @@ pub async fn verify_provider_async<F: RequestFilterExecutor, S: ProviderStateExe
       println!("{line}");
     }
 
+    if let Some(emitter) = &verification_options.metrics_emitter {
+      if let Err(err) = emitter.emit(&verification_result, start.elapsed()).await {
+        warn!("Failed to publish the verification metrics - {}", err);
+      }
+    }
+
     #[cfg(feature = "plugins")] shutdown_plugins();
 
     Ok(verification_result)
   }.instrument(tracing::trace_span!("verify_provider_async"))).await
 }
 
-fn process_errors(errors: &Vec<(String, MismatchResult)>, output: &mut Vec<String>, coloured_output: bool) {
+/// Interaction that would be verified, as returned by `list_interactions_async`
+#[derive(Debug, Clone)]
+pub struct InteractionToVerify {
+  /// Interaction description
+  pub description: String,
+  /// Type of interaction
+  pub interaction_type: String,
+  /// Names of the provider states for the interaction
+  pub provider_states: Vec<String>,
+  /// If the interaction is pending
+  pub pending: bool
+}
+
+/// Pact that would be verified, as returned by `list_interactions_async`
+#[derive(Debug, Clone)]
+pub struct PactToVerify {
+  /// Consumer name
+  pub consumer: String,
+  /// Provider name
+  pub provider: String,
+  /// Source that the Pact was loaded from
+  pub source: String,
+  /// If the Pact is pending
+  pub pending: bool,
+  /// If the Pact is a work in progress (WIP) pact
+  pub wip: bool,
+  /// Interactions from the Pact that would be verified after the filter has been applied
+  pub interactions: Vec<InteractionToVerify>
+}
+
+/// Fetches the Pacts from the given sources and returns the interactions that would be verified,
+/// without making any requests to the provider. This is useful for debugging the configuration
+/// of the pact sources (i.e. consumer version selectors) before running a verification.
+pub async fn list_interactions_async(
+  provider_info: &ProviderInfo,
+  source: Vec<PactSource>,
+  filter: &FilterInfo,
+  consumers: Vec<String>
+) -> Vec<anyhow::Result<PactToVerify>> {
+  fetch_pacts(source, consumers, provider_info).await
+    .into_iter()
+    .map(|result| result.map(|(pact, context, pact_source, _)| {
+      let (pending, wip) = context.as_ref()
+        .map(|c| (c.verification_properties.pending, c.verification_properties.wip))
+        .unwrap_or_default();
+      PactToVerify {
+        consumer: pact.consumer().name,
+        provider: pact.provider().name,
+        source: pact_source.to_string(),
+        pending,
+        wip,
+        interactions: pact.interactions().iter()
+          .filter(|interaction| filter_interaction(interaction.as_ref(), filter))
+          .map(|interaction| InteractionToVerify {
+            description: interaction.description(),
+            interaction_type: interaction.type_of(),
+            provider_states: interaction.provider_states().iter().map(|state| state.name.clone()).collect(),
+            pending: interaction.pending()
+          })
+          .collect()
+      }
+    }))
+    .collect()
+}
+
+/// Fetches the Pacts from the given sources and returns a catalogue of all the provider states
+/// (with the parameters they are used with) from the interactions that would be verified. This
+/// can be used to generate the scaffolding for the provider state handlers. Sources that return
+/// no pacts are ignored, but any other error loading the pacts will be returned.
+pub async fn provider_state_catalogue_async(
+  provider_info: &ProviderInfo,
+  source: Vec<PactSource>,
+  filter: &FilterInfo,
+  consumers: Vec<String>
+) -> anyhow::Result<ProviderStateCatalogue> {
+  let mut catalogue = ProviderStateCatalogue::new(provider_info.name.as_str());
+  for result in fetch_pacts(source, consumers, provider_info).await {
+    match result {
+      Ok((pact, _, _, _)) => catalogue.add_pact(pact.as_ref(), filter),
+      Err(err) => if let Some(PactBrokerError::NotFound(_)) = err.downcast_ref() {
+        warn!("Ignoring no pacts error - {}", err);
+      } else {
+        return Err(err);
+      }
+    }
+  }
+  Ok(catalogue)
+}
+
+fn process_errors(
+  errors: &Vec<(String, MismatchResult)>,
+  output: &mut Vec<String>,
+  coloured_output: bool,
+  diff_context: usize
+) {
   for (i, &(ref description, ref mismatch)) in errors.iter().enumerate() {
     match *mismatch {
         MismatchResult::Error(ref err, _) => output.push(format!("{}) {} - {}\n", i + 1, description, err)),
         MismatchResult::Mismatches { ref mismatches, ref expected, ref actual, .. } => {
-          interaction_mismatch_output(output, coloured_output, i, description, mismatches, expected.as_ref(), actual.as_ref())
+          interaction_mismatch_output_with_diff_context(output, coloured_output, i, description, mismatches,
+            expected.as_ref(), actual.as_ref(), diff_context)
         }
     }
   }
@@ -1172,6 +1867,23 @@ pub fn interaction_mismatch_output(
   mismatches: &Vec<Mismatch>,
   expected: &dyn Interaction,
   actual: &dyn Interaction
+) {
+  interaction_mismatch_output_with_diff_context(output, coloured_output, i, description, mismatches,
+    expected, actual, 3);
+}
+
+/// Generate the output for an interaction verification, displaying the given number of lines of
+/// context around the changes in any body diffs
+#[allow(clippy::too_many_arguments)]
+pub fn interaction_mismatch_output_with_diff_context(
+  output: &mut Vec<String>,
+  coloured_output: bool,
+  i: usize,
+  description: &String,
+  mismatches: &Vec<Mismatch>,
+  expected: &dyn Interaction,
+  actual: &dyn Interaction,
+  diff_context: usize
 ) {
   output.push(format!("{}) {}", i + 1, description));
 
@@ -1184,6 +1896,7 @@ pub fn interaction_mismatch_output(
       match (m1, m2) {
         (Mismatch::QueryMismatch { parameter: p1, .. }, Mismatch::QueryMismatch { parameter: p2, .. }) => Ord::cmp(&p1, &p2),
         (Mismatch::HeaderMismatch { key: p1, .. }, Mismatch::HeaderMismatch { key: p2, .. }) => Ord::cmp(&p1, &p2),
+        (Mismatch::TrailerMismatch { key: p1, .. }, Mismatch::TrailerMismatch { key: p2, .. }) => Ord::cmp(&p1, &p2),
         (Mismatch::BodyMismatch { path: p1, .. }, Mismatch::BodyMismatch { path: p2, .. }) => Ord::cmp(&p1, &p2),
         (Mismatch::MetadataMismatch { key: p1, .. }, Mismatch::MetadataMismatch { key: p2, .. }) => Ord::cmp(&p1, &p2),
         _ => Ord::cmp(m1, m2)
@@ -1192,12 +1905,19 @@ pub fn interaction_mismatch_output(
       output.push(format!("           {}", if coloured_output { mismatch.ansi_description() } else { mismatch.description() }));
     }
 
-    if let Mismatch::BodyMismatch { ref path, .. } = mismatch {
-      display_body_mismatch(expected, actual, path, output);
+    // Unified diffs cover the whole body, so are only displayed once after all the mismatches
+    if !cfg!(feature = "unified-diff") {
+      if let Mismatch::BodyMismatch { ref path, .. } = mismatch {
+        display_body_mismatch(expected, actual, path, output, coloured_output, diff_context);
+      }
     }
 
     j += 1;
   }
+
+  if cfg!(feature = "unified-diff") && mismatches.iter().any(|mismatch| matches!(mismatch, Mismatch::BodyMismatch { .. })) {
+    display_body_mismatch(expected, actual, "$", output, coloured_output, diff_context);
+  }
 }
 
 #[tracing::instrument(level = "trace")]
@@ -1216,7 +1936,7 @@ async fn fetch_pact(
           (pact, None, source.clone(), tm)
         })
     ],
-    PactSource::Dir(dir) => match walkdir(Path::new(dir), provider) {
+    PactSource::Dir(dir) => match walkdir(Path::new(dir), provider, false, &|_| true) {
       Ok(pact_results) => pact_results.into_iter().map(|pact_result| {
           match pact_result {
               Ok((pact, tm)) => {
@@ -1228,25 +1948,55 @@ async fn fetch_pact(
       }).collect(),
       Err(err) => vec![Err(anyhow!("Could not load pacts from directory '{}' - {}", dir, err))]
     },
-    PactSource::URL(url, auth) => vec![
-      timeit_async(pact_broker::fetch_pact_from_url(url, auth)).await
-        .map_err(|err| anyhow!("Failed to load pact '{}' - {}", url, err))
-        .map(|((pact, links), tm)| {
-          trace!(%url, duration = ?tm, "Loaded pact from url");
-          if is_pact_broker_source(&links) {
-            let provider = pact.provider();
-            let base_url = url.parse::<reqwest::Url>()
-              .map(|mut url| {
-                url.set_path("/");
-                url.to_string()
-              }).ok().unwrap_or_else(||url.to_string());
-            (pact, None, PactSource::BrokerUrl(provider.name.clone(), base_url,
-                                               auth.clone(), links.clone()), tm)
-          } else {
-            (pact, None, source.clone(), tm)
+    PactSource::DirWithFilters { dir, include, exclude, recursive } => {
+      let filter = build_glob_set(include)
+        .and_then(|include| build_glob_set(exclude).map(|exclude| (include, exclude)))
+        .and_then(|(include, exclude)| {
+          let base = Path::new(dir);
+          walkdir(base, provider, *recursive, &|path| {
+            let relative_path = path.strip_prefix(base).unwrap_or(path);
+            (include.is_empty() || include.is_match(relative_path)) && !exclude.is_match(relative_path)
+          })
+        });
+      match filter {
+        Ok(pact_results) => pact_results.into_iter().map(|pact_result| {
+          match pact_result {
+            Ok((pact, tm)) => {
+              trace!(%dir, duration = ?tm, "Loaded pact from directory");
+              Ok((pact, None, source.clone(), tm))
+            },
+            Err(err) => Err(anyhow!("Failed to load pact from '{}' - {}", dir, err))
           }
-        })
-    ],
+        }).collect(),
+        Err(err) => vec![Err(anyhow!("Could not load pacts from directory '{}' - {}", dir, err))]
+      }
+    },
+    PactSource::URL(url, auth) => if let Some(custom_source) = source_for_url(url, auth) {
+      match custom_source {
+        Ok(custom_source) => fetch_pacts_from_custom_source(&custom_source, &source, provider).await,
+        Err(err) => vec![Err(anyhow!("Failed to load pact '{}' - {}", url, err))]
+      }
+    } else {
+      vec![
+        timeit_async(pact_broker::fetch_pact_from_url(url, auth)).await
+          .map_err(|err| anyhow!("Failed to load pact '{}' - {}", url, err))
+          .map(|((pact, links), tm)| {
+            trace!(%url, duration = ?tm, "Loaded pact from url");
+            if is_pact_broker_source(&links) {
+              let provider = pact.provider();
+              let base_url = url.parse::<reqwest::Url>()
+                .map(|mut url| {
+                  url.set_path("/");
+                  url.to_string()
+                }).ok().unwrap_or_else(||url.to_string());
+              (pact, None, PactSource::BrokerUrl(provider.name.clone(), base_url,
+                                                 auth.clone(), links.clone()), tm)
+            } else {
+              (pact, None, source.clone(), tm)
+            }
+          })
+      ]
+    },
     PactSource::BrokerUrl(provider_name, broker_url, auth, _) => {
       let result = timeit_async(pact_broker::fetch_pacts_from_broker(
         broker_url.as_str(),
@@ -1343,10 +2093,49 @@ async fn fetch_pact(
             auth.clone(), links.clone()), tm)
         })
     ],
+    PactSource::Bundle { file, results_file, .. } => match timeit(|| PactBundle::read(Path::new(file))) {
+      Ok((bundle, tm)) => {
+        trace!(%file, duration = ?tm, "Loaded pact bundle");
+        bundle.pacts.iter().map(|bundled_pact| {
+          bundled_pact.load_pact(file)
+            .map_err(|err| anyhow!("Failed to load pact from bundle '{}' - {}", file, err))
+            .map(|pact| {
+              (pact, bundled_pact.verification_context.clone(), PactSource::Bundle {
+                file: file.clone(),
+                results_file: results_file.clone(),
+                broker_url: bundled_pact.broker_url.clone(),
+                links: bundled_pact.links.clone()
+              }, tm)
+            })
+        }).collect()
+      }
+      Err(err) => vec![Err(err)]
+    },
+    PactSource::Custom(custom_source) => fetch_pacts_from_custom_source(custom_source, &source, provider).await,
     _ => vec![Err(anyhow!("Could not load pacts, unknown pact source {}", source))]
   }
 }
 
+async fn fetch_pacts_from_custom_source(
+  custom_source: &Arc<dyn CustomPactSource>,
+  source: &PactSource,
+  provider: &ProviderInfo
+) -> Vec<anyhow::Result<(Box<dyn Pact + Send + Sync + RefUnwindSafe>, Option<PactVerificationContext>, PactSource, Duration)>> {
+  let description = custom_source.description();
+  match timeit_async(custom_source.load_pacts(provider)).await {
+    Ok((pact_results, tm)) => {
+      trace!(source = %description, duration = ?tm, "Loaded pacts from custom source");
+      pact_results.into_iter().map(|pact_result| {
+        pact_result
+          .and_then(|(name, json)| load_pact_from_json(&name, &json))
+          .map_err(|err| anyhow!("Failed to load pact from '{}' - {}", description, err))
+          .map(|pact| (pact, None, source.clone(), tm))
+      }).collect()
+    }
+    Err(err) => vec![Err(anyhow!("Could not load pacts from '{}' - {}", description, err))]
+  }
+}
+
 fn timeit<T, FN: FnOnce() -> anyhow::Result<T>>(callback: FN) -> anyhow::Result<(T, Duration)> {
   let start = Instant::now();
   let result = callback()?;
@@ -1375,14 +2164,44 @@ async fn fetch_pacts(
 ) -> Vec<anyhow::Result<(Box<dyn Pact + Send + Sync + RefUnwindSafe>, Option<PactVerificationContext>, PactSource, Duration)>> {
   trace!("fetch_pacts(source={}, consumers={:?})", source.iter().map(|s| s.to_string()).join(", "), consumers);
 
-  futures::stream::iter(source)
+  let results: Vec<_> = futures::stream::iter(source)
     .then(|pact_source| async {
       futures::stream::iter(fetch_pact(pact_source, provider).await)
     })
     .flatten()
     .filter(|res| futures::future::ready(filter_consumers(&consumers, res)))
     .collect()
-    .await
+    .await;
+  remove_duplicate_local_pacts(results)
+}
+
+/// Removes any pacts loaded from the local file system that are the same as a pact that has
+/// already been loaded. This happens when the same pact file is found in multiple directories,
+/// or is also passed in as a file.
+fn remove_duplicate_local_pacts(
+  results: Vec<anyhow::Result<(Box<dyn Pact + Send + Sync + RefUnwindSafe>, Option<PactVerificationContext>, PactSource, Duration)>>
+) -> Vec<anyhow::Result<(Box<dyn Pact + Send + Sync + RefUnwindSafe>, Option<PactVerificationContext>, PactSource, Duration)>> {
+  let mut seen = HashSet::new();
+  results.into_iter()
+    .filter(|result| match result {
+      Ok((pact, _, source, _)) if source.is_local() => {
+        match pact.to_json(pact.specification_version()) {
+          Ok(json) => {
+            let digest = digests::sha256_digest(json.to_string().as_bytes());
+            if seen.insert(digest) {
+              true
+            } else {
+              debug!("Ignoring pact between {} and {} from {} as it is a duplicate of a pact already loaded",
+                pact.consumer().name, pact.provider().name, source);
+              false
+            }
+          }
+          Err(_) => true
+        }
+      }
+      _ => true
+    })
+    .collect()
 }
 
 /// Internal function, public for testing purposes
@@ -1394,26 +2213,51 @@ pub async fn verify_pact_internal<'a, F: RequestFilterExecutor, S: ProviderState
   options: &VerificationOptions<F>,
   provider_state_executor: &Arc<S>,
   pending: bool,
-  pact_source_duration: Duration
+  pact_source_duration: Duration,
+  capabilities: Option<&ProviderCapabilities>
 ) -> anyhow::Result<VerificationResult> {
   let interactions = pact.interactions();
   let mut output = vec![];
+  let mut skipped = vec![];
+
+  if let Some(capabilities) = capabilities {
+    for interaction in interactions.iter().filter(|i| filter_interaction(i.as_ref(), filter)) {
+      let missing = capabilities.missing_capabilities(interaction.as_ref());
+      if !missing.is_empty() {
+        let reason = format!("Provider does not have the required capabilities: {}", missing.join(", "));
+        output.push(String::default());
+        output.push(format!("  {} {}", interaction.description(),
+          if options.coloured_output { Yellow.paint("[SKIPPED]") } else { Style::new().paint("[SKIPPED]") }));
+        output.push(format!("    {}", reason));
+        skipped.push((interaction_verification_description(pact.as_ref(), interaction.as_ref()), reason));
+      }
+    }
+  }
+  let should_verify = |interaction: &dyn Interaction| {
+    filter_interaction(interaction, filter) &&
+      capabilities.map(|c| c.missing_capabilities(interaction).is_empty()).unwrap_or(true)
+  };
 
-  let results: Vec<(Box<dyn Interaction + Send + Sync + RefUnwindSafe>, Result<(Option<String>, Vec<String>, Duration), (MismatchResult, Vec<String>, Duration)>)> =
-    futures::stream::iter(interactions.iter().map(|i| (&pact, i)))
-    .filter(|(_, interaction)| futures::future::ready(filter_interaction(interaction.as_ref(), filter)))
-    .then( |(pact, interaction)| async move {
-      let interaction_desc = interaction.description();
-      (interaction.boxed(), verify_interaction(provider_info, interaction.as_ref(), &pact.boxed(), options, provider_state_executor)
-        .instrument(debug_span!("verify_interaction", interaction = interaction_desc.as_str())).await)
-    })
-    .collect()
-    .await;
+  let results: Vec<(Box<dyn Interaction + Send + Sync + RefUnwindSafe>, Result<(Option<String>, Vec<String>, Duration), (MismatchResult, Vec<String>, Duration)>, Vec<String>)> =
+    if options.parallelism > 1 {
+      verify_interactions_in_parallel(provider_info, &should_verify, &pact, options, provider_state_executor).await
+    } else {
+      futures::stream::iter(interactions.iter().map(|i| (&pact, i)))
+      .filter(|(_, interaction)| futures::future::ready(should_verify(interaction.as_ref())))
+      .then( |(pact, interaction)| async move {
+        let interaction_desc = interaction.description();
+        let mut warnings = vec![];
+        let result = verify_interaction_with_timeouts(provider_info, interaction.as_ref(), &pact.boxed(), options, provider_state_executor, &mut warnings)
+          .instrument(debug_span!("verify_interaction", interaction = interaction_desc.as_str())).await;
+        (interaction.boxed(), result, warnings)
+      })
+      .collect()
+      .await
+    };
 
   let mut errors: Vec<VerificationInteractionResult> = vec![];
-  for (interaction, match_result) in results {
-    let mut description = format!("Verifying a pact between {} and {}",
-      pact.consumer().name.clone(), pact.provider().name.clone());
+  for (interaction, match_result, warnings) in results {
+    let description = interaction_verification_description(pact.as_ref(), interaction.as_ref());
 
     output.push(String::default());
     let duration = match match_result {
@@ -1433,17 +2277,11 @@ pub async fn verify_pact_internal<'a, F: RequestFilterExecutor, S: ProviderState
     };
 
     if let Some((first, elements)) = interaction.provider_states().split_first() {
-      let s = format!(" Given {}", first.name);
-      description.push_str(&s);
-      output.push(format!("    {}", s));
+      output.push(format!("     Given {}", first.name));
       for state in elements {
-        let s = format!(" And {}", state.name);
-        description.push_str(&s);
-        output.push(format!("    {}", s));
+        output.push(format!("     And {}", state.name));
       }
     }
-    description.push_str(" - ");
-    description.push_str(&interaction.description());
 
     let (interaction_key, verification_from_plugin) = if interaction.is_v4() {
       if let Some(interaction) = interaction.as_v4() {
@@ -1502,6 +2340,15 @@ pub async fn verify_pact_internal<'a, F: RequestFilterExecutor, S: ProviderState
       }
     }
 
+    for warning in &warnings {
+      let warning = format!("    WARNING: {}", warning);
+      if options.coloured_output {
+        output.push(Yellow.paint(warning).to_string());
+      } else {
+        output.push(warning);
+      }
+    }
+
     match match_result {
       Ok(_) => {
         errors.push(VerificationInteractionResult {
@@ -1509,9 +2356,11 @@ pub async fn verify_pact_internal<'a, F: RequestFilterExecutor, S: ProviderState
           interaction_key,
           description: description.clone(),
           interaction_description: interaction.description(),
+          consumer: pact.consumer().name.clone(),
           result: Ok(()),
           pending: pending || interaction.pending(),
-          duration
+          duration,
+          warnings: warnings.clone()
         });
       },
       Err(err) => {
@@ -1520,9 +2369,11 @@ pub async fn verify_pact_internal<'a, F: RequestFilterExecutor, S: ProviderState
           interaction_key,
           description: description.clone(),
           interaction_description: interaction.description(),
+          consumer: pact.consumer().name.clone(),
           result: Err(err.clone()),
           pending: pending || interaction.pending(),
-          duration
+          duration,
+          warnings: warnings.clone()
         });
       }
     }
@@ -1530,7 +2381,86 @@ pub async fn verify_pact_internal<'a, F: RequestFilterExecutor, S: ProviderState
 
   output.push(String::default());
 
-  Ok(VerificationResult { results: errors, output: output.clone() })
+  Ok(VerificationResult { results: errors, output: output.clone(), skipped })
+}
+
+/// Descriptive text for the verification of an interaction, including the provider states
+fn interaction_verification_description(pact: &dyn Pact, interaction: &dyn Interaction) -> String {
+  let mut description = format!("Verifying a pact between {} and {}",
+    pact.consumer().name, pact.provider().name);
+  if let Some((first, elements)) = interaction.provider_states().split_first() {
+    description.push_str(&format!(" Given {}", first.name));
+    for state in elements {
+      description.push_str(&format!(" And {}", state.name));
+    }
+  }
+  description.push_str(" - ");
+  description.push_str(&interaction.description());
+  description
+}
+
+/// Verifies the interactions concurrently, up to the parallelism set in the options. Only the
+/// interactions without provider states are verified in parallel. Interactions with provider
+/// states are grouped by their states, and each group holds an exclusive lock while its states
+/// are set up, its interactions verified and the states torn down, so no other interaction is
+/// verified against the provider while the states are in place. The results are returned in the
+/// same order as the interactions in the Pact.
+async fn verify_interactions_in_parallel<'a, F: RequestFilterExecutor, S: ProviderStateExecutor>(
+  provider_info: &ProviderInfo,
+  should_verify: &(dyn Fn(&dyn Interaction) -> bool + Sync),
+  pact: &Box<dyn Pact + Send + Sync + RefUnwindSafe + 'a>,
+  options: &VerificationOptions<F>,
+  provider_state_executor: &Arc<S>
+) -> Vec<(Box<dyn Interaction + Send + Sync + RefUnwindSafe>, Result<(Option<String>, Vec<String>, Duration), (MismatchResult, Vec<String>, Duration)>, Vec<String>)> {
+  let interactions = pact.interactions();
+  let groups = interactions.iter()
+    .enumerate()
+    .filter(|(_, interaction)| should_verify(interaction.as_ref()))
+    .into_group_map_by(|(index, interaction)| provider_state_group_key(*index, interaction.as_ref()));
+  debug!("Verifying {} groups of interactions with a parallelism of {}", groups.len(), options.parallelism);
+
+  let state_lock = tokio::sync::RwLock::new(());
+  let state_lock = &state_lock;
+  futures::stream::iter(groups.into_values())
+    .map(|group| async move {
+      let has_states = group.iter().any(|(_, interaction)| !interaction.provider_states().is_empty());
+      let (_read_guard, _write_guard) = if has_states {
+        (None, Some(state_lock.write().await))
+      } else {
+        (Some(state_lock.read().await), None)
+      };
+      let mut results = vec![];
+      for (index, interaction) in group {
+        let interaction_desc = interaction.description();
+        let mut warnings = vec![];
+        let result = verify_interaction_with_timeouts(provider_info, interaction.as_ref(), &pact.boxed(), options, provider_state_executor, &mut warnings)
+          .instrument(debug_span!("verify_interaction", interaction = interaction_desc.as_str())).await;
+        results.push((index, interaction.boxed(), result, warnings));
+      }
+      results
+    })
+    .buffer_unordered(options.parallelism)
+    .collect::<Vec<_>>()
+    .await
+    .into_iter()
+    .flatten()
+    .sorted_by_key(|(index, _, _, _)| *index)
+    .map(|(_, interaction, result, warnings)| (interaction, result, warnings))
+    .collect()
+}
+
+/// Key used to group interactions that can not be verified concurrently. Interactions without any
+/// provider states do not need to be grouped.
+fn provider_state_group_key(index: usize, interaction: &(dyn Interaction + Send + Sync + RefUnwindSafe)) -> String {
+  let states = interaction.provider_states();
+  if states.is_empty() {
+    format!("#{}", index)
+  } else {
+    states.iter()
+      .map(|state| state.to_json().to_string())
+      .sorted()
+      .join("|")
+  }
 }
 
 fn process_comments(interaction: &dyn V4Interaction, output: &mut Vec<String>) {
@@ -1580,6 +2510,23 @@ async fn publish_result(
         &options.provider_version, links.clone(), broker_url.clone(), auth.clone(), metrics_data
       ).await
     }
+    PactSource::Bundle { results_file: Some(results_file), broker_url: Some(broker_url), links, .. } => {
+      let (result, result_data) = test_result(results, source);
+      let deferred_result = DeferredVerificationResult {
+        broker_url: broker_url.clone(),
+        links: links.clone(),
+        provider_version: options.provider_version.clone().unwrap_or_default(),
+        provider_branch: options.provider_branch.clone(),
+        provider_tags: options.provider_tags.clone(),
+        payload: build_payload(result, options.provider_version.clone().unwrap_or_default(),
+          options.build_url.clone(), metrics_data, Some(&result_data))
+      };
+      match write_deferred_result(Path::new(results_file), &deferred_result) {
+        Ok(_) => info!("Results written to '{}' to be published later", results_file),
+        Err(err) => error!("Writing of verification results failed with an error: {}", err)
+      }
+      return;
+    }
     _ => {
       info!("Not publishing results as publishing for pact source {:?} is not possible or not yet implemented", source);
       return;
@@ -1604,6 +2551,25 @@ async fn publish_to_broker(
   metrics_data: Option<&VerificationMetrics>
 ) -> Result<Value, pact_broker::PactBrokerError> {
   info!("Publishing verification results back to the Pact Broker");
+  let (result, result_data) = test_result(results, source);
+  publish_verification_results(
+    links,
+    broker_url.as_str(),
+    auth.clone(),
+    result,
+    provider_version.clone().unwrap(),
+    build_url.clone(),
+    provider_tags.clone(),
+    provider_branch.clone(),
+    metrics_data,
+    Some(&result_data)
+  ).await
+}
+
+fn test_result(
+  results: &[VerificationInteractionResult],
+  source: &PactSource
+) -> (TestResult, VerificationResultData) {
   let result = if results.iter().all(|r| r.result.is_ok()) {
     debug!("Publishing a successful result to {}", source);
     TestResult::Ok(results.iter().map(|r| r.interaction_id.clone()).collect())
@@ -1615,17 +2581,10 @@ async fn publish_to_broker(
         .collect()
     )
   };
-  publish_verification_results(
-    links,
-    broker_url.as_str(),
-    auth.clone(),
-    result,
-    provider_version.clone().unwrap(),
-    build_url.clone(),
-    provider_tags.clone(),
-    provider_branch.clone(),
-    metrics_data
-  ).await
+  let durations = results.iter()
+    .filter_map(|r| r.interaction_id.clone().map(|id| (id, r.duration)))
+    .collect();
+  (result, VerificationResultData::new(durations))
 }
 
 #[cfg(test)]
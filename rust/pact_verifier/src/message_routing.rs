@@ -0,0 +1,249 @@
+//! Routing of message verification requests to different provider handler URLs, selected by the
+//! metadata of the message being verified.
+//!
+//! The routing configuration is a JSON document in the form:
+//!
+//! ```json
+//! {
+//!   "routes": [
+//!     {
+//!       "metadata": { "kind": "order-created" },
+//!       "url": "http://localhost:8081/orders",
+//!       "auth": { "token": "1234" }
+//!     }
+//!   ],
+//!   "default": { "url": "http://localhost:8081/messages" }
+//! }
+//! ```
+//!
+//! A route is selected if all of its metadata values match the message metadata. Routes are checked
+//! in order, and the default handler (if any) is used if no route matches. The `auth` attribute
+//! can either contain a bearer `token`, or a `username` and `password` for basic authentication.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use pact_models::http_utils::HttpAuth;
+use pact_models::v4::http_parts::HttpRequest;
+use reqwest::Url;
+use serde_json::Value;
+
+use crate::{ProviderInfo, ProviderTransport};
+
+/// Provider endpoint that message verification requests are sent to
+#[derive(Debug, Clone)]
+pub struct MessageHandler {
+  /// URL of the message handler
+  pub url: String,
+  /// Authentication to use with the handler
+  pub auth: HttpAuth
+}
+
+/// Route to a message handler for messages with matching metadata
+#[derive(Debug, Clone)]
+pub struct MessageRoute {
+  /// Metadata values that the message must have for this route to be used
+  pub metadata: HashMap<String, Value>,
+  /// Handler to send the requests for the matching messages to
+  pub handler: MessageHandler
+}
+
+/// Routing configuration for message verification requests
+#[derive(Debug, Clone, Default)]
+pub struct MessageRouting {
+  /// Routes, checked in order
+  pub routes: Vec<MessageRoute>,
+  /// Handler to use if no route matches. If not set, the message transport configured for the
+  /// provider is used.
+  pub default_handler: Option<MessageHandler>
+}
+
+impl MessageHandler {
+  /// Parses the handler from its JSON form
+  pub fn from_json(json: &Value) -> anyhow::Result<MessageHandler> {
+    let url = json.get("url")
+      .and_then(|url| url.as_str())
+      .ok_or_else(|| anyhow!("Message handler must have a 'url' attribute"))?;
+    Url::parse(url).map_err(|err| anyhow!("'{}' is not a valid message handler URL - {}", url, err))?;
+    let auth = match json.get("auth") {
+      Some(Value::Object(auth)) => if let Some(token) = auth.get("token").and_then(|t| t.as_str()) {
+        HttpAuth::Token(token.to_string())
+      } else if let Some(username) = auth.get("username").and_then(|u| u.as_str()) {
+        HttpAuth::User(username.to_string(), auth.get("password").and_then(|p| p.as_str()).map(|p| p.to_string()))
+      } else {
+        return Err(anyhow!("Message handler auth must have either a 'token' or 'username' attribute"));
+      },
+      Some(Value::Null) | None => HttpAuth::None,
+      Some(auth) => return Err(anyhow!("Message handler auth must be a JSON object, got '{}'", auth))
+    };
+    Ok(MessageHandler { url: url.to_string(), auth })
+  }
+
+  /// Returns the provider info and transport to use to send requests to this handler
+  pub fn provider_target(&self, provider: &ProviderInfo) -> anyhow::Result<(ProviderInfo, ProviderTransport)> {
+    let url = Url::parse(&self.url)
+      .map_err(|err| anyhow!("'{}' is not a valid message handler URL - {}", self.url, err))?;
+    let host = url.host_str()
+      .ok_or_else(|| anyhow!("Message handler URL '{}' does not have a host", self.url))?;
+    let provider = ProviderInfo {
+      host: host.to_string(),
+      .. provider.clone()
+    };
+    let transport = ProviderTransport {
+      transport: "message".to_string(),
+      port: url.port_or_known_default(),
+      path: Some(url.path().to_string()),
+      scheme: Some(url.scheme().to_string())
+    };
+    Ok((provider, transport))
+  }
+
+  /// Adds the Authorization header for the handler auth to the request
+  pub fn apply_auth(&self, request: &mut HttpRequest) {
+    let header = match &self.auth {
+      HttpAuth::Token(token) => Some(format!("Bearer {}", token)),
+      HttpAuth::User(username, password) => Some(format!("Basic {}",
+        STANDARD.encode(format!("{}:{}", username, password.clone().unwrap_or_default())))),
+      HttpAuth::None => None
+    };
+    if let Some(header) = header {
+      request.headers.get_or_insert_with(HashMap::new)
+        .insert("Authorization".to_string(), vec![header]);
+    }
+  }
+}
+
+impl MessageRoute {
+  /// If the route matches the message metadata
+  pub fn matches(&self, metadata: &HashMap<String, Value>) -> bool {
+    self.metadata.iter().all(|(key, value)| {
+      metadata.get(key).map(|actual| actual == value).unwrap_or(false)
+    })
+  }
+}
+
+impl MessageRouting {
+  /// Parses the routing configuration from its JSON form
+  pub fn from_json(json: &Value) -> anyhow::Result<MessageRouting> {
+    let routes = match json.get("routes") {
+      Some(Value::Array(routes)) => routes.iter().enumerate()
+        .map(|(index, route)| {
+          let metadata = match route.get("metadata") {
+            Some(Value::Object(metadata)) if !metadata.is_empty() => metadata.iter()
+              .map(|(k, v)| (k.clone(), v.clone()))
+              .collect(),
+            _ => return Err(anyhow!("Message route {} must have a non-empty 'metadata' object", index))
+          };
+          let handler = MessageHandler::from_json(route)
+            .with_context(|| format!("Message route {} is not valid", index))?;
+          Ok(MessageRoute { metadata, handler })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?,
+      Some(Value::Null) | None => vec![],
+      Some(_) => return Err(anyhow!("Message routing 'routes' attribute must be an array"))
+    };
+    let default_handler = match json.get("default") {
+      Some(Value::Null) | None => None,
+      Some(handler) => Some(MessageHandler::from_json(handler)
+        .context("Default message handler is not valid")?)
+    };
+    Ok(MessageRouting { routes, default_handler })
+  }
+
+  /// Loads the routing configuration from a JSON file
+  pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<MessageRouting> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)
+      .with_context(|| format!("Failed to read message routing file '{}'", path.display()))?;
+    let json: Value = serde_json::from_str(&contents)
+      .with_context(|| format!("Failed to parse message routing file '{}'", path.display()))?;
+    MessageRouting::from_json(&json)
+  }
+
+  /// Selects the handler to use for a message with the given metadata. Returns `None` if no route
+  /// matches and there is no default handler.
+  pub fn select_handler(&self, metadata: &HashMap<String, Value>) -> Option<&MessageHandler> {
+    self.routes.iter()
+      .find(|route| route.matches(metadata))
+      .map(|route| &route.handler)
+      .or(self.default_handler.as_ref())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+  use maplit::hashmap;
+  use serde_json::json;
+
+  use super::*;
+
+  fn routing() -> MessageRouting {
+    MessageRouting::from_json(&json!({
+      "routes": [
+        { "metadata": { "kind": "order-created" }, "url": "http://localhost:8081/orders", "auth": { "token": "1234" } },
+        { "metadata": { "kind": "invoice", "version": 2 }, "url": "https://invoices:9443/v2" }
+      ],
+      "default": { "url": "http://localhost:8081/messages", "auth": { "username": "user", "password": "pass" } }
+    })).unwrap()
+  }
+
+  #[test]
+  fn select_handler_uses_the_first_route_with_matching_metadata() {
+    let routing = routing();
+    expect!(routing.select_handler(&hashmap!{ "kind".to_string() => json!("order-created") }).map(|h| h.url.clone()))
+      .to(be_some().value("http://localhost:8081/orders"));
+    expect!(routing.select_handler(&hashmap!{ "kind".to_string() => json!("invoice"), "version".to_string() => json!(2) }).map(|h| h.url.clone()))
+      .to(be_some().value("https://invoices:9443/v2"));
+  }
+
+  #[test]
+  fn select_handler_falls_back_to_the_default_handler() {
+    let routing = routing();
+    expect!(routing.select_handler(&hashmap!{ "kind".to_string() => json!("invoice") }).map(|h| h.url.clone()))
+      .to(be_some().value("http://localhost:8081/messages"));
+    expect!(routing.select_handler(&hashmap!{}).map(|h| h.url.clone()))
+      .to(be_some().value("http://localhost:8081/messages"));
+
+    let routing = MessageRouting { default_handler: None, .. routing };
+    expect!(routing.select_handler(&hashmap!{})).to(be_none());
+  }
+
+  #[test]
+  fn from_json_returns_an_error_for_invalid_routes() {
+    expect!(MessageRouting::from_json(&json!({ "routes": {} }))).to(be_err());
+    expect!(MessageRouting::from_json(&json!({ "routes": [ { "url": "http://localhost" } ] }))).to(be_err());
+    expect!(MessageRouting::from_json(&json!({ "routes": [ { "metadata": { "kind": "a" } } ] }))).to(be_err());
+    expect!(MessageRouting::from_json(&json!({ "routes": [ { "metadata": { "kind": "a" }, "url": "not a url" } ] }))).to(be_err());
+    expect!(MessageRouting::from_json(&json!({ "default": { "url": "http://localhost", "auth": { "other": "a" } } }))).to(be_err());
+    expect!(MessageRouting::from_json(&json!({}))).to(be_ok());
+  }
+
+  #[test]
+  fn provider_target_uses_the_handler_url() {
+    let handler = MessageHandler { url: "https://invoices/v2/messages".to_string(), auth: HttpAuth::None };
+    let (provider, transport) = handler.provider_target(&ProviderInfo::default()).unwrap();
+    expect!(provider.host.as_str()).to(be_equal_to("invoices"));
+    expect!(transport.base_url(&provider.host)).to(be_equal_to("https://invoices:443/v2/messages"));
+  }
+
+  #[test]
+  fn apply_auth_adds_an_authorization_header() {
+    let mut request = HttpRequest::default();
+    MessageHandler { url: "http://localhost".to_string(), auth: HttpAuth::Token("1234".to_string()) }
+      .apply_auth(&mut request);
+    expect!(request.headers.clone().unwrap()["Authorization"].clone()).to(be_equal_to(vec!["Bearer 1234".to_string()]));
+
+    MessageHandler { url: "http://localhost".to_string(), auth: HttpAuth::User("user".to_string(), Some("pass".to_string())) }
+      .apply_auth(&mut request);
+    expect!(request.headers.clone().unwrap()["Authorization"].clone()).to(be_equal_to(vec!["Basic dXNlcjpwYXNz".to_string()]));
+
+    let mut request = HttpRequest::default();
+    MessageHandler { url: "http://localhost".to_string(), auth: HttpAuth::None }.apply_auth(&mut request);
+    expect!(request.headers).to(be_none());
+  }
+}
@@ -23,6 +23,7 @@ use pact_matching::{match_message, match_sync_message_response, Mismatch};
 
 use crate::{MismatchResult, ProviderInfo, ProviderTransport, VerificationOptions};
 use crate::callback_executors::RequestFilterExecutor;
+use crate::failures::provider_request_error;
 use crate::provider_client::make_provider_request;
 use crate::utils::as_safe_ref;
 
@@ -45,7 +46,7 @@ pub(crate) async fn verify_message_from_provider<'a, F: RequestFilterExecutor>(
     }
   }
 
-  let message_request = HttpRequest {
+  let mut message_request = HttpRequest {
     method: "POST".into(),
     body: OptionalBody::Present(Bytes::from(request_body.to_string()), Some("application/json".into()), None),
     headers: Some(hashmap! {
@@ -84,9 +85,15 @@ pub(crate) async fn verify_message_from_provider<'a, F: RequestFilterExecutor>(
     }
   });
 
-  match make_provider_request(provider, &message_request, options, client, transport).await {
+  let metadata = interaction.as_v4_async_message()
+    .map(|message| message.contents.metadata)
+    .unwrap_or_default();
+  let (provider, transport) = route_message_request(provider, &metadata, &mut message_request, options, transport)
+    .map_err(|err| MismatchResult::Error(err.to_string(), interaction.id().clone()))?;
+
+  match make_provider_request(&provider, &message_request, options, client, transport).await {
     Ok(ref actual_response) => {
-      let metadata = extract_metadata(actual_response);
+      let metadata = extract_metadata(actual_response, &metadata);
       let actual = AsynchronousMessage {
         contents: MessageContents {
           metadata,
@@ -111,7 +118,7 @@ pub(crate) async fn verify_message_from_provider<'a, F: RequestFilterExecutor>(
       }
     },
     Err(err) => {
-      Err(MismatchResult::Error(err.to_string(), interaction.id().clone()))
+      Err(MismatchResult::Error(provider_request_error(&err), interaction.id().clone()))
     }
   }
 }
@@ -242,7 +249,35 @@ fn generate_display_for_result(
   output.push(format!("      has a matching body ({})", body_result));
 }
 
-fn extract_metadata(actual_response: &HttpResponse) -> HashMap<String, Value> {
+/// Applies any message routing from the verification options, returning the provider and transport
+/// to send the message request to. If a handler is selected, the auth for the handler is added to
+/// the request.
+fn route_message_request<F: RequestFilterExecutor>(
+  provider: &ProviderInfo,
+  metadata: &HashMap<String, Value>,
+  request: &mut HttpRequest,
+  options: &VerificationOptions<F>,
+  transport: Option<ProviderTransport>
+) -> anyhow::Result<(ProviderInfo, Option<ProviderTransport>)> {
+  match options.message_routing.as_ref().and_then(|routing| routing.select_handler(metadata)) {
+    Some(handler) => {
+      debug!("Routing the message request to the handler at {}", handler.url);
+      handler.apply_auth(request);
+      let (provider, transport) = handler.provider_target(provider)?;
+      Ok((provider, Some(transport)))
+    }
+    None => Ok((provider.clone(), transport))
+  }
+}
+
+/// Extracts the message metadata from the response from the provider. This is the content type
+/// of the response, any values packed into the base64 encoded `Pact-Message-Metadata` header, and
+/// any other headers with the same name as one of the expected metadata keys (i.e. a message key
+/// or partition header).
+fn extract_metadata(
+  actual_response: &HttpResponse,
+  expected_metadata: &HashMap<String, Value>
+) -> HashMap<String, Value> {
   let content_type = "contentType".to_string();
 
   let mut default = hashmap!{
@@ -266,9 +301,38 @@ fn extract_metadata(actual_response: &HttpResponse) -> HashMap<String, Value> {
     }
   });
 
+  let headers = actual_response.headers.clone().unwrap_or_default();
+  for (key, expected_value) in expected_metadata {
+    if !default.contains_key(key) {
+      let header = headers.iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key.as_str()));
+      if let Some((_, values)) = header {
+        trace!("found metadata value for '{}' from headers: {:?}", key, values);
+        let values = values.iter()
+          .map(|value| metadata_header_value(value.as_str(), expected_value))
+          .collect::<Vec<_>>();
+        let value = if values.len() == 1 {
+          values[0].clone()
+        } else {
+          Value::Array(values)
+        };
+        default.insert(key.clone(), value);
+      }
+    }
+  }
+
   default
 }
 
+/// Converts a header value to a metadata value. Header values are strings, so if the expected
+/// value is not a string, the header value is parsed as JSON (i.e. a numeric partition).
+fn metadata_header_value(value: &str, expected: &Value) -> Value {
+  match expected {
+    Value::String(_) => Value::String(value.to_string()),
+    _ => serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()))
+  }
+}
+
 pub(crate) async fn verify_sync_message_from_provider<'a, F: RequestFilterExecutor>(
   provider: &ProviderInfo,
   pact: &Box<dyn Pact + Send + Sync + RefUnwindSafe + 'a>,
@@ -293,7 +357,7 @@ pub(crate) async fn verify_sync_message_from_provider<'a, F: RequestFilterExecut
     }
   }
 
-  let message_request = HttpRequest {
+  let mut message_request = HttpRequest {
     method: "POST".into(),
     body: OptionalBody::Present(Bytes::from(request_body.to_string()), Some("application/json".into()), None),
     headers: Some(hashmap! {
@@ -323,10 +387,16 @@ pub(crate) async fn verify_sync_message_from_provider<'a, F: RequestFilterExecut
     }
   });
 
-  match make_provider_request(provider, &message_request, options, client, transport).await {
+  let (provider, transport) = route_message_request(provider, &message.request.metadata, &mut message_request, options, transport)
+    .map_err(|err| MismatchResult::Error(err.to_string(), message.id().clone()))?;
+
+  match make_provider_request(&provider, &message_request, options, client, transport).await {
     Ok(ref actual_response) => {
       if actual_response.is_success() {
-        let metadata = extract_metadata(actual_response);
+        let expected_metadata = message.response.first()
+          .map(|response| response.metadata.clone())
+          .unwrap_or_default();
+        let metadata = extract_metadata(actual_response, &expected_metadata);
         let actual_contents = MessageContents {
           metadata,
           contents: actual_response.body.clone(),
@@ -355,7 +425,7 @@ pub(crate) async fn verify_sync_message_from_provider<'a, F: RequestFilterExecut
       }
     },
     Err(err) => {
-      Err(MismatchResult::Error(err.to_string(), message.id().clone()))
+      Err(MismatchResult::Error(provider_request_error(&err), message.id().clone()))
     }
   }
 }
@@ -366,6 +436,9 @@ mod tests {
   use pact_models::generators::Generators;
   use pact_models::matchingrules::MatchingRules;
 
+  use crate::callback_executors::NullRequestFilterExecutor;
+  use crate::message_routing::MessageRouting;
+
   use super::*;
 
   #[test]
@@ -381,13 +454,14 @@ mod tests {
         },
         matching_rules: MatchingRules {
           rules: hashmap!()
-        }
+        },
+        trailers: None
       };
       let expected = hashmap! {
         "contentType".to_string() => Value::String("application/json".to_string())
       };
 
-      expect(extract_metadata(&response)).to(be_eq(expected));
+      expect(extract_metadata(&response, &hashmap!{})).to(be_eq(expected));
     }
 
     #[test]
@@ -405,7 +479,8 @@ mod tests {
         },
         matching_rules: MatchingRules {
           rules: hashmap!()
-        }
+        },
+        trailers: None
       };
       let expected = hashmap! {
         "contentType".to_string() => Value::String("application/json".to_string()), // From actual HTTP response header
@@ -415,6 +490,72 @@ mod tests {
         "number".to_string() => json!(27),
       };
 
-      expect(extract_metadata(&response)).to(be_eq(expected));
+      expect(extract_metadata(&response, &hashmap!{})).to(be_eq(expected));
+    }
+
+    #[test]
+    fn extract_metadata_from_headers_for_expected_metadata_keys() {
+      let response = HttpResponse {
+        status: 200,
+        headers: Some(hashmap! {
+          "content-type".into() => vec!["application/json".into()],
+          "kafka_key".into() => vec!["order-1234".into()],
+          "kafka_partition".into() => vec!["3".into()],
+          "tags".into() => vec!["a".into(), "b".into()],
+          "topic".into() => vec!["orders".into()],
+          "x-other".into() => vec!["ignored".into()],
+          "pact-message-metadata".into() => vec![STANDARD.encode(json!({ "topic": "invoices" }).to_string())]
+        }),
+        .. HttpResponse::default()
+      };
+      let expected_metadata = hashmap! {
+        "Kafka_Key".to_string() => json!("order-1234"),
+        "kafka_partition".to_string() => json!(1),
+        "tags".to_string() => json!(["a", "b"]),
+        "topic".to_string() => json!("orders"),
+        "missing".to_string() => json!("value")
+      };
+      let expected = hashmap! {
+        "contentType".to_string() => json!("application/json"),
+        "Kafka_Key".to_string() => json!("order-1234"),
+        "kafka_partition".to_string() => json!(3),
+        "tags".to_string() => json!(["a", "b"]),
+        "topic".to_string() => json!("invoices")
+      };
+
+      expect(extract_metadata(&response, &expected_metadata)).to(be_eq(expected));
+    }
+
+    #[test]
+    fn route_message_request_uses_the_handler_selected_by_the_message_metadata() {
+      let provider = ProviderInfo::default();
+      let transport = ProviderTransport {
+        transport: "message".to_string(),
+        port: Some(8080),
+        path: Some("/messages".to_string()),
+        scheme: Some("http".to_string())
+      };
+      let options = VerificationOptions::<NullRequestFilterExecutor> {
+        message_routing: Some(MessageRouting::from_json(&json!({
+          "routes": [
+            { "metadata": { "kind": "order-created" }, "url": "http://orders:8081/orders", "auth": { "token": "1234" } }
+          ]
+        })).unwrap()),
+        .. VerificationOptions::default()
+      };
+
+      let mut request = HttpRequest::default();
+      let metadata = hashmap!{ "kind".to_string() => json!("order-created") };
+      let (routed_provider, routed_transport) = route_message_request(&provider, &metadata, &mut request,
+        &options, Some(transport.clone())).unwrap();
+      expect!(routed_transport.unwrap().base_url(&routed_provider.host)).to(be_equal_to("http://orders:8081/orders"));
+      expect!(request.headers.unwrap()["Authorization"].clone()).to(be_equal_to(vec!["Bearer 1234".to_string()]));
+
+      let mut request = HttpRequest::default();
+      let metadata = hashmap!{ "kind".to_string() => json!("invoice") };
+      let (routed_provider, routed_transport) = route_message_request(&provider, &metadata, &mut request,
+        &options, Some(transport.clone())).unwrap();
+      expect!(routed_transport.unwrap().base_url(&routed_provider.host)).to(be_equal_to("http://localhost:8080/messages"));
+      expect!(request.headers).to(be_none());
     }
 }
@@ -0,0 +1,345 @@
+//! Opt-in publishing of verification metrics (counts and durations) to a StatsD server or an
+//! OpenTelemetry collector.
+//!
+//! This is separate from the anonymous usage telemetry (see `pact_matching::metrics`), and is
+//! only enabled when an emitter is configured in the verification options. The metrics do not
+//! contain any details from the pacts (like consumer names or interaction descriptions), only
+//! the outcome and the tags that have been configured.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::anyhow;
+use reqwest::Client;
+use serde_json::{json, Value};
+use tokio::net::UdpSocket;
+use tracing::debug;
+
+use crate::verification_result::VerificationExecutionResult;
+
+/// Backend to publish the metrics to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetricsSink {
+  /// StatsD server address (`host:port`). Metrics are sent over UDP, with the tags written in
+  /// the DogStatsD format.
+  Statsd(String),
+  /// OpenTelemetry collector metrics URL (i.e. `http://localhost:4318/v1/metrics`). Metrics are
+  /// sent using OTLP over HTTP with a JSON payload.
+  Otlp(String)
+}
+
+/// Type of metric
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+  /// Count of events
+  Counter,
+  /// Duration in milliseconds
+  Timing
+}
+
+/// Metric value to publish
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metric {
+  /// Name of the metric, including the prefix
+  pub name: String,
+  /// Type of metric
+  pub kind: MetricKind,
+  /// Value (count or milliseconds)
+  pub value: f64,
+  /// Tags to attach to the metric
+  pub tags: BTreeMap<String, String>
+}
+
+/// Publishes metrics from verification runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricsEmitter {
+  /// Backend to publish the metrics to
+  pub sink: MetricsSink,
+  /// Prefix to add to the metric names (defaults to `pact`)
+  pub prefix: String,
+  /// Tags to add to all the metrics
+  pub tags: BTreeMap<String, String>
+}
+
+impl MetricsEmitter {
+  /// Creates an emitter for the sink, with the default prefix and no tags
+  pub fn new(sink: MetricsSink) -> Self {
+    MetricsEmitter {
+      sink,
+      prefix: "pact".to_string(),
+      tags: BTreeMap::default()
+    }
+  }
+
+  /// Sets the prefix for the metric names
+  pub fn with_prefix<S: Into<String>>(self, prefix: S) -> Self {
+    MetricsEmitter {
+      prefix: prefix.into(),
+      .. self
+    }
+  }
+
+  /// Adds a tag to add to all the metrics
+  pub fn with_tag<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+    self.tags.insert(key.into(), value.into());
+    self
+  }
+
+  /// Returns the metrics for the verification result, which took the given duration. These are:
+  /// * `<prefix>.verification.count` - one for the verification run, tagged with the outcome
+  /// * `<prefix>.verification.duration` - total duration of the verification run
+  /// * `<prefix>.interaction.count` - number of interactions verified for each outcome (passed,
+  ///   failed and pending)
+  /// * `<prefix>.interaction.duration` - duration of each interaction verified, tagged with the outcome
+  pub fn metrics(&self, result: &VerificationExecutionResult, duration: Duration) -> Vec<Metric> {
+    let run_outcome = if result.result { "passed" } else { "failed" };
+    let mut metrics = vec![
+      self.metric("verification.count", MetricKind::Counter, 1.0, run_outcome),
+      self.metric("verification.duration", MetricKind::Timing, duration.as_secs_f64() * 1000.0, run_outcome)
+    ];
+
+    let outcome = |pending: bool, ok: bool| match (ok, pending) {
+      (true, _) => "passed",
+      (false, true) => "pending",
+      (false, false) => "failed"
+    };
+    for interaction_outcome in ["passed", "failed", "pending"] {
+      let count = result.interaction_results.iter()
+        .filter(|interaction| outcome(interaction.pending, interaction.result.is_ok()) == interaction_outcome)
+        .count();
+      metrics.push(self.metric("interaction.count", MetricKind::Counter, count as f64, interaction_outcome));
+    }
+    for interaction in &result.interaction_results {
+      metrics.push(self.metric("interaction.duration", MetricKind::Timing,
+        interaction.duration.as_secs_f64() * 1000.0, outcome(interaction.pending, interaction.result.is_ok())));
+    }
+
+    metrics
+  }
+
+  fn metric(&self, name: &str, kind: MetricKind, value: f64, outcome: &str) -> Metric {
+    let mut tags = self.tags.clone();
+    tags.insert("outcome".to_string(), outcome.to_string());
+    Metric {
+      name: format!("{}.{}", self.prefix, name),
+      kind,
+      value,
+      tags
+    }
+  }
+
+  /// Publishes the metrics for the verification result to the sink
+  pub async fn emit(&self, result: &VerificationExecutionResult, duration: Duration) -> anyhow::Result<()> {
+    let metrics = self.metrics(result, duration);
+    debug!("Publishing {} verification metrics to {:?}", metrics.len(), self.sink);
+    match &self.sink {
+      MetricsSink::Statsd(address) => {
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        socket.connect(address).await?;
+        for metric in &metrics {
+          socket.send(statsd_line(metric).as_bytes()).await?;
+        }
+        Ok(())
+      }
+      MetricsSink::Otlp(url) => {
+        let response = Client::new()
+          .post(url)
+          .json(&otlp_json(&metrics, SystemTime::now()))
+          .send()
+          .await?;
+        if response.status().is_success() {
+          Ok(())
+        } else {
+          Err(anyhow!("OpenTelemetry collector returned status {}", response.status()))
+        }
+      }
+    }
+  }
+}
+
+/// Formats the metric as a StatsD line, with DogStatsD tags
+fn statsd_line(metric: &Metric) -> String {
+  let metric_type = match metric.kind {
+    MetricKind::Counter => "c",
+    MetricKind::Timing => "ms"
+  };
+  let tags = metric.tags.iter()
+    .map(|(key, value)| format!("{}:{}", key, value))
+    .collect::<Vec<_>>();
+  if tags.is_empty() {
+    format!("{}:{}|{}", metric.name, metric.value, metric_type)
+  } else {
+    format!("{}:{}|{}|#{}", metric.name, metric.value, metric_type, tags.join(","))
+  }
+}
+
+/// Builds the OTLP JSON payload for the metrics. Counters are written as delta sums, and timings
+/// as gauges. Metrics with the same name are combined into a single metric with multiple data points.
+fn otlp_json(metrics: &[Metric], time: SystemTime) -> Value {
+  let time = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos().to_string();
+  let mut otlp_metrics: Vec<(String, MetricKind, Vec<Value>)> = vec![];
+  for metric in metrics {
+    let data_point = json!({
+      "asDouble": metric.value,
+      "timeUnixNano": time,
+      "attributes": metric.tags.iter()
+        .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+        .collect::<Vec<_>>()
+    });
+    match otlp_metrics.iter_mut().find(|(name, kind, _)| *name == metric.name && *kind == metric.kind) {
+      Some((_, _, data_points)) => data_points.push(data_point),
+      None => otlp_metrics.push((metric.name.clone(), metric.kind, vec![data_point]))
+    }
+  }
+
+  json!({
+    "resourceMetrics": [{
+      "resource": {
+        "attributes": [ { "key": "service.name", "value": { "stringValue": "pact_verifier" } } ]
+      },
+      "scopeMetrics": [{
+        "scope": { "name": "pact_verifier", "version": crate::VERIFIER_VERSION },
+        "metrics": otlp_metrics.iter().map(|(name, kind, data_points)| match kind {
+          MetricKind::Counter => json!({
+            "name": name,
+            "unit": "1",
+            "sum": { "aggregationTemporality": 1, "isMonotonic": true, "dataPoints": data_points }
+          }),
+          MetricKind::Timing => json!({
+            "name": name,
+            "unit": "ms",
+            "gauge": { "dataPoints": data_points }
+          })
+        }).collect::<Vec<_>>()
+      }]
+    }]
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::{Duration, UNIX_EPOCH};
+
+  use expectest::prelude::*;
+  use maplit::btreemap;
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+  use tokio::net::UdpSocket;
+
+  use crate::MismatchResult;
+  use crate::verification_result::{VerificationExecutionResult, VerificationInteractionResult};
+
+  use super::*;
+
+  fn interaction_result(result: Result<(), MismatchResult>, pending: bool, millis: u64) -> VerificationInteractionResult {
+    VerificationInteractionResult {
+      interaction_id: None,
+      interaction_key: None,
+      description: "a request for an order".to_string(),
+      interaction_description: "a request for an order".to_string(),
+      consumer: "OrderWeb".to_string(),
+      result,
+      pending,
+      duration: Duration::from_millis(millis),
+      warnings: vec![]
+    }
+  }
+
+  fn verification_result() -> VerificationExecutionResult {
+    let mut result = VerificationExecutionResult::new();
+    result.result = false;
+    result.interaction_results = vec![
+      interaction_result(Ok(()), false, 10),
+      interaction_result(Err(MismatchResult::Error("failed".to_string(), None)), false, 20),
+      interaction_result(Err(MismatchResult::Error("failed".to_string(), None)), true, 5)
+    ];
+    result
+  }
+
+  #[test]
+  fn metrics_test() {
+    let emitter = MetricsEmitter::new(MetricsSink::Statsd("localhost:8125".to_string()))
+      .with_prefix("ci")
+      .with_tag("team", "orders");
+    let metrics = emitter.metrics(&verification_result(), Duration::from_millis(1500));
+    let summary = metrics.iter()
+      .map(|metric| (metric.name.as_str(), metric.value, metric.tags.get("outcome").unwrap().as_str()))
+      .collect::<Vec<_>>();
+    assert_eq!(summary, vec![
+      ("ci.verification.count", 1.0, "failed"),
+      ("ci.verification.duration", 1500.0, "failed"),
+      ("ci.interaction.count", 1.0, "passed"),
+      ("ci.interaction.count", 1.0, "failed"),
+      ("ci.interaction.count", 1.0, "pending"),
+      ("ci.interaction.duration", 10.0, "passed"),
+      ("ci.interaction.duration", 20.0, "failed"),
+      ("ci.interaction.duration", 5.0, "pending")
+    ]);
+    expect!(metrics.iter().all(|metric| metric.tags.get("team") == Some(&"orders".to_string()))).to(be_true());
+    expect!(metrics.iter().any(|metric| metric.tags.values().any(|value| value.contains("Order")))).to(be_false());
+  }
+
+  #[test]
+  fn statsd_line_test() {
+    let metric = Metric {
+      name: "pact.verification.duration".to_string(),
+      kind: MetricKind::Timing,
+      value: 1250.5,
+      tags: btreemap!{ "outcome".to_string() => "passed".to_string(), "env".to_string() => "ci".to_string() }
+    };
+    expect!(statsd_line(&metric)).to(be_equal_to("pact.verification.duration:1250.5|ms|#env:ci,outcome:passed"));
+
+    let metric = Metric {
+      name: "pact.verification.count".to_string(),
+      kind: MetricKind::Counter,
+      value: 1.0,
+      tags: btreemap!{}
+    };
+    expect!(statsd_line(&metric)).to(be_equal_to("pact.verification.count:1|c"));
+  }
+
+  #[test]
+  fn otlp_json_test() {
+    let emitter = MetricsEmitter::new(MetricsSink::Otlp("http://localhost:4318/v1/metrics".to_string()));
+    let mut result = VerificationExecutionResult::new();
+    result.interaction_results = vec![ interaction_result(Ok(()), false, 10) ];
+    let metrics = emitter.metrics(&result, Duration::from_millis(100));
+    let json = otlp_json(&metrics, UNIX_EPOCH + Duration::from_secs(1));
+
+    let otlp_metrics = json.pointer("/resourceMetrics/0/scopeMetrics/0/metrics").unwrap().as_array().unwrap();
+    let names = otlp_metrics.iter().map(|m| m["name"].as_str().unwrap()).collect::<Vec<_>>();
+    expect!(names).to(be_equal_to(vec![ "pact.verification.count", "pact.verification.duration",
+      "pact.interaction.count", "pact.interaction.duration" ]));
+    assert_eq!(otlp_metrics[0], json!({
+      "name": "pact.verification.count",
+      "unit": "1",
+      "sum": {
+        "aggregationTemporality": 1,
+        "isMonotonic": true,
+        "dataPoints": [{
+          "asDouble": 1.0,
+          "timeUnixNano": "1000000000",
+          "attributes": [ { "key": "outcome", "value": { "stringValue": "passed" } } ]
+        }]
+      }
+    }));
+    expect!(otlp_metrics[2]["sum"]["dataPoints"].as_array().unwrap().len()).to(be_equal_to(3));
+    expect!(otlp_metrics[3]["gauge"]["dataPoints"][0]["asDouble"].as_f64()).to(be_some().value(10.0));
+  }
+
+  #[test_log::test(tokio::test)]
+  async fn emit_sends_the_metrics_to_the_statsd_server() {
+    let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let address = server.local_addr().unwrap().to_string();
+    let emitter = MetricsEmitter::new(MetricsSink::Statsd(address));
+
+    let mut result = VerificationExecutionResult::new();
+    result.interaction_results = vec![ interaction_result(Ok(()), false, 10) ];
+    expect!(emitter.emit(&result, Duration::from_millis(100)).await).to(be_ok());
+
+    let mut buffer = [0_u8; 1024];
+    let len = server.recv(&mut buffer).await.unwrap();
+    expect!(String::from_utf8_lossy(&buffer[..len]).to_string()).to(
+      be_equal_to("pact.verification.count:1|c|#outcome:passed"));
+  }
+}
@@ -0,0 +1,89 @@
+//! Check of the age of the pacts being verified, using the `createdAt` value written to the pact
+//! metadata by the consumer. This can be used to force pacts to be re-published regularly.
+
+use std::time::{Duration, SystemTime};
+
+use humantime::{format_duration, format_rfc3339_seconds};
+
+use pact_models::pact::{created_at, Pact};
+
+/// Options for checking the age of the pacts being verified
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PactAgeCheck {
+  /// Maximum allowed age of a pact
+  pub max_age: Duration,
+  /// If a stale pact should fail the verification. Otherwise, it will be reported as a warning.
+  pub fail_verification: bool
+}
+
+impl PactAgeCheck {
+  /// Creates a check that reports stale pacts as warnings
+  pub fn new(max_age: Duration) -> Self {
+    PactAgeCheck { max_age, fail_verification: false }
+  }
+
+  /// Checks the creation time of the pact against the given time, returning a description of
+  /// the problem if the pact is older than the maximum allowed age. Pacts without a creation
+  /// time in the metadata are not checked.
+  pub fn check(&self, pact: &dyn Pact, now: SystemTime) -> Option<String> {
+    let created = created_at(pact)?;
+    let age = now.duration_since(created).unwrap_or_default();
+    // The creation time only has a precision of seconds
+    let age = Duration::from_secs(age.as_secs());
+    if age > self.max_age {
+      Some(format!("Stale pact: The pact between {} and {} was created at {}, which is {} ago (the maximum allowed age is {}). Re-publish the pact from the consumer to refresh it.",
+        pact.consumer().name, pact.provider().name, format_rfc3339_seconds(created), format_duration(age),
+        format_duration(self.max_age)))
+    } else {
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::{Duration, UNIX_EPOCH};
+
+  use expectest::prelude::*;
+
+  use pact_models::{Consumer, Provider};
+  use pact_models::pact::set_created_at;
+  use pact_models::v4::pact::V4Pact;
+
+  use super::PactAgeCheck;
+
+  fn pact() -> V4Pact {
+    V4Pact {
+      consumer: Consumer { name: "Consumer".to_string() },
+      provider: Provider { name: "Provider".to_string() },
+      .. V4Pact::default()
+    }
+  }
+
+  #[test]
+  fn check_returns_none_when_the_pact_is_within_the_allowed_age() {
+    let created = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut pact = pact();
+    set_created_at(&mut pact, created);
+    let check = PactAgeCheck::new(Duration::from_secs(7 * 86400));
+    expect!(check.check(&pact, created)).to(be_none());
+    expect!(check.check(&pact, created + Duration::from_secs(7 * 86400))).to(be_none());
+    expect!(check.check(&pact, created - Duration::from_secs(60))).to(be_none());
+  }
+
+  #[test]
+  fn check_returns_the_problem_when_the_pact_is_stale() {
+    let created = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut pact = pact();
+    set_created_at(&mut pact, created);
+    let check = PactAgeCheck::new(Duration::from_secs(7 * 86400));
+    expect!(check.check(&pact, created + Duration::from_secs(10 * 86400))).to(be_some().value(
+      "Stale pact: The pact between Consumer and Provider was created at 2023-11-14T22:13:20Z, which is 10days ago (the maximum allowed age is 7days). Re-publish the pact from the consumer to refresh it.".to_string()));
+  }
+
+  #[test]
+  fn check_ignores_pacts_without_a_creation_time() {
+    let check = PactAgeCheck::new(Duration::from_secs(86400));
+    expect!(check.check(&pact(), UNIX_EPOCH + Duration::from_secs(1_700_000_000))).to(be_none());
+  }
+}
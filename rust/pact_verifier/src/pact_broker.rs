@@ -4,11 +4,15 @@ use std::collections::HashMap;
 use std::ops::Not;
 use std::panic::RefUnwindSafe;
 use std::str::from_utf8;
+use std::time::Duration;
 
 use anyhow::anyhow;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
 use futures::stream::*;
 use itertools::Itertools;
 use maplit::hashmap;
+use pact_models::content_types::{ContentType, HAL_JSON, JSON};
 use pact_models::http_utils;
 use pact_models::http_utils::HttpAuth;
 use pact_models::json_utils::json_to_string;
@@ -51,14 +55,9 @@ fn content_type(response: &reqwest::Response) -> String {
 }
 
 fn json_content_type(response: &reqwest::Response) -> bool {
-    match content_type(response).parse::<mime::Mime>() {
-        Ok(mime) => {
-            match (mime.type_().as_str(), mime.subtype().as_str(), mime.suffix()) {
-                ("application", "json", None) => true,
-                ("application", "hal", Some(mime::JSON)) => true,
-                _ => false
-            }
-        }
+    match ContentType::parse(content_type(response).as_str()) {
+        Ok(content_type) => content_type.has_same_base_type(&JSON) ||
+          content_type.has_same_base_type(&HAL_JSON),
         Err(_) => false
     }
 }
@@ -764,6 +763,16 @@ pub async fn fetch_pact_from_url(url: &str, auth: &Option<HttpAuth>) -> anyhow::
   Ok((pact, links))
 }
 
+/// Fetches the index resource of the Pact Broker and returns the links it contains. The names of
+/// the links can be used to determine which capabilities (relations) the Pact Broker supports.
+pub async fn fetch_broker_index(broker_url: &str, auth: Option<HttpAuth>) -> Result<Vec<Link>, PactBrokerError> {
+  trace!("fetch_broker_index(broker_url='{}', auth={})", broker_url, auth.clone().unwrap_or_default());
+
+  let hal_client = HALClient::with_url(broker_url, auth);
+  let index = hal_client.fetch("/").await?;
+  Ok(links_from_json(&index))
+}
+
 /// Struct that wraps the result of a verification test
 pub enum TestResult {
   /// Test was OK
@@ -783,6 +792,28 @@ impl TestResult {
   }
 }
 
+/// Additional data about a verification run that is included in the published results
+#[derive(Debug, Clone, Default)]
+pub struct VerificationResultData {
+  /// Time taken to verify each interaction, keyed by the interaction ID
+  pub durations: HashMap<String, Duration>,
+  /// Details of the environment the verifier ran in (i.e. OS and architecture)
+  pub environment: HashMap<String, String>
+}
+
+impl VerificationResultData {
+  /// Creates the result data with the details of the current environment
+  pub fn new(durations: HashMap<String, Duration>) -> Self {
+    VerificationResultData {
+      durations,
+      environment: hashmap!{
+        "os".to_string() => std::env::consts::OS.to_string(),
+        "arch".to_string() => std::env::consts::ARCH.to_string()
+      }
+    }
+  }
+}
+
 /// Publishes the result to the "pb:publish-verification-results" link in the links associated with the pact
 pub async fn publish_verification_results(
   links: Vec<Link>,
@@ -793,7 +824,8 @@ pub async fn publish_verification_results(
   build_url: Option<String>,
   provider_tags: Vec<String>,
   branch: Option<String>,
-  metrics_data: Option<&VerificationMetrics>
+  metrics_data: Option<&VerificationMetrics>,
+  result_data: Option<&VerificationResultData>
 ) -> Result<serde_json::Value, PactBrokerError> {
   let hal_client = HALClient::with_url(broker_url, auth.clone());
 
@@ -805,23 +837,56 @@ pub async fn publish_verification_results(
     publish_provider_tags(&hal_client, &links, provider_tags, &version).await?;
   }
 
+  let json = build_payload(result, version, build_url, metrics_data, result_data);
+  post_verification_results(&hal_client, &links, &json).await
+}
+
+/// Publishes a verification result payload that was previously built (for instance, when verifying
+/// pacts offline from a bundle) to the "pb:publish-verification-results" link in the links
+/// associated with the pact
+pub async fn publish_verification_payload(
+  links: Vec<Link>,
+  broker_url: &str,
+  auth: Option<HttpAuth>,
+  payload: &serde_json::Value,
+  version: &str,
+  provider_tags: Vec<String>,
+  branch: Option<String>
+) -> Result<serde_json::Value, PactBrokerError> {
+  let hal_client = HALClient::with_url(broker_url, auth);
+
+  if let Some(branch) = &branch {
+    publish_provider_branch(&hal_client, &links, branch, version).await?;
+  }
+
+  if !provider_tags.is_empty() {
+    publish_provider_tags(&hal_client, &links, provider_tags, version).await?;
+  }
+
+  post_verification_results(&hal_client, &links, payload).await
+}
+
+async fn post_verification_results(
+  hal_client: &HALClient,
+  links: &[Link],
+  payload: &serde_json::Value
+) -> Result<serde_json::Value, PactBrokerError> {
   let publish_link = links
       .iter()
-      .cloned()
       .find(|item| item.name.to_ascii_lowercase() == "pb:publish-verification-results")
       .ok_or_else(|| PactBrokerError::LinkError(
           "Response from the pact broker has no 'pb:publish-verification-results' link".into()
       ))?;
 
-  let json = build_payload(result, version, build_url, metrics_data);
-  hal_client.post_json(publish_link.href.unwrap_or_default().as_str(), json.to_string().as_str()).await
+  hal_client.post_json(publish_link.href.clone().unwrap_or_default().as_str(), payload.to_string().as_str()).await
 }
 
-fn build_payload(
+pub(crate) fn build_payload(
   result: TestResult,
   version: String,
   build_url: Option<String>,
-  metrics_data: Option<&VerificationMetrics>
+  metrics_data: Option<&VerificationMetrics>,
+  result_data: Option<&VerificationResultData>
 ) -> serde_json::Value {
   let mut json = json!({
     "success": result.to_bool(),
@@ -845,6 +910,12 @@ fn build_payload(
     });
   }
 
+  if let Some(environment) = result_data.map(|data| &data.environment).filter(|env| !env.is_empty()) {
+    json_obj.get_mut("verifiedBy").unwrap()["environment"] = json!(environment);
+  }
+
+  let duration_for = |id: &str| result_data.and_then(|data| data.durations.get(id));
+
   match result {
     TestResult::Failed(mismatches) => {
       let values = mismatches.iter()
@@ -880,6 +951,11 @@ fn build_payload(
                         "identifier": key,
                         "description": mismatch
                       })),
+                      Mismatch::TrailerMismatch { key, mismatch, .. } => acc.0.push(json!({
+                        "attribute": "trailer",
+                        "identifier": key,
+                        "description": mismatch
+                      })),
                       Mismatch::BodyTypeMismatch { expected, actual, .. } => acc.0.push(json!({
                         "attribute": "body",
                         "identifier": "$",
@@ -915,6 +991,10 @@ fn build_payload(
           });
 
           if !mismatches.is_empty() {
+            let counts = mismatches.iter()
+              .filter_map(|mismatch| mismatch.get("attribute").and_then(|attr| attr.as_str()))
+              .counts();
+            json["mismatchCounts"] = json!(counts);
             json["mismatches"] = json!(mismatches);
           }
 
@@ -926,6 +1006,10 @@ fn build_payload(
             json["interactionDescription"] = json!(desc);
           }
 
+          if let Some(duration) = duration_for(key.as_str()) {
+            json["durationMs"] = json!(duration.as_millis() as u64);
+          }
+
           json
         }).collect::<Vec<serde_json::Value>>();
 
@@ -933,10 +1017,17 @@ fn build_payload(
     }
     TestResult::Ok(ids) => {
       let values = ids.iter().filter(|id| id.is_some())
-        .map(|id| json!({
-        "interactionId": id.clone().unwrap_or_default(),
-        "success": true
-      })).collect();
+        .map(|id| {
+          let id = id.clone().unwrap_or_default();
+          let mut json = json!({
+            "interactionId": id,
+            "success": true
+          });
+          if let Some(duration) = duration_for(id.as_str()) {
+            json["durationMs"] = json!(duration.as_millis() as u64);
+          }
+          json
+        }).collect();
       json_obj.insert("testResults".into(), serde_json::Value::Array(values));
     }
   }
@@ -999,6 +1090,128 @@ async fn publish_provider_branch(
   }
 }
 
+/// Options used when publishing consumer pacts to a Pact Broker
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PublishPactsOptions {
+  /// Version of the consumer application the pacts were created by
+  pub consumer_version: String,
+  /// Branch of the consumer application
+  pub branch: Option<String>,
+  /// Tags to apply to the consumer version
+  pub tags: Vec<String>,
+  /// URL to the build that created the pacts
+  pub build_url: Option<String>
+}
+
+/// Publishes the consumer pacts (as JSON documents) to the Pact Broker using the
+/// "pb:publish-contracts" link from the index resource. All the pacts must be for the same
+/// consumer. Returns the response from the Pact Broker, which includes any notices to display.
+pub async fn publish_pacts(
+  broker_url: &str,
+  auth: Option<HttpAuth>,
+  pacts: &[Value],
+  options: &PublishPactsOptions
+) -> Result<Value, PactBrokerError> {
+  let payload = build_publish_contracts_payload(pacts, options)?;
+
+  let hal_client = HALClient::with_url(broker_url, auth);
+  let index = hal_client.clone().fetch("/").await?;
+  let hal_client = hal_client.update_path_info(index);
+  match hal_client.find_link("pb:publish-contracts") {
+    Ok(link) => {
+      let url = link.href.clone().unwrap_or_default();
+      match hal_client.post_json(url.as_str(), payload.to_string().as_str()).await {
+        Ok(result) => {
+          debug!("Published {} pact(s) for consumer version {}", pacts.len(), options.consumer_version);
+          Ok(result)
+        }
+        Err(err) => {
+          error!("Failed to publish pacts for consumer version {}", options.consumer_version);
+          Err(err)
+        }
+      }
+    },
+    Err(_) => Err(PactBrokerError::LinkError("Can't publish pacts as there is no 'pb:publish-contracts' link. Please upgrade to Pact Broker version 2.86.0 or later to publish pacts".to_string()))
+  }
+}
+
+pub(crate) fn build_publish_contracts_payload(
+  pacts: &[Value],
+  options: &PublishPactsOptions
+) -> Result<Value, PactBrokerError> {
+  if pacts.is_empty() {
+    return Err(PactBrokerError::ValidationError(vec!["There are no pacts to publish".to_string()]));
+  }
+  if options.consumer_version.is_empty() {
+    return Err(PactBrokerError::ValidationError(vec!["A consumer version is required to publish pacts".to_string()]));
+  }
+
+  let mut consumer_name = None;
+  let mut contracts = vec![];
+  for pact in pacts {
+    let consumer = pact.pointer("/consumer/name").and_then(Value::as_str)
+      .ok_or_else(|| PactBrokerError::ValidationError(vec!["Pact does not have a consumer name".to_string()]))?;
+    let provider = pact.pointer("/provider/name").and_then(Value::as_str)
+      .ok_or_else(|| PactBrokerError::ValidationError(vec!["Pact does not have a provider name".to_string()]))?;
+    match consumer_name {
+      Some(name) if name != consumer => return Err(PactBrokerError::ValidationError(vec![
+        format!("All pacts must be for the same consumer, but got pacts for '{}' and '{}'", name, consumer)
+      ])),
+      _ => consumer_name = Some(consumer)
+    }
+    contracts.push(json!({
+      "consumerName": consumer,
+      "providerName": provider,
+      "specification": "pact",
+      "contentType": "application/json",
+      "content": STANDARD.encode(pact.to_string())
+    }));
+  }
+
+  let mut json = json!({
+    "pacticipantName": consumer_name.unwrap_or_default(),
+    "pacticipantVersionNumber": options.consumer_version,
+    "tags": options.tags,
+    "contracts": contracts
+  });
+  if let Some(branch) = &options.branch {
+    json["branch"] = json!(branch);
+  }
+  if let Some(build_url) = &options.build_url {
+    json["buildUrl"] = json!(build_url);
+  }
+  Ok(json)
+}
+
+/// Detects the version and branch of the application from the Git repository in the current
+/// directory. The version is the SHA of the current commit. The branch is taken from the common
+/// CI environment variables if they are set, otherwise from the checked out Git branch.
+pub fn detect_version_properties() -> (Option<String>, Option<String>) {
+  let version = git_output(&["rev-parse", "HEAD"]);
+  let branch = ["GITHUB_HEAD_REF", "GITHUB_REF_NAME", "CI_COMMIT_REF_NAME", "BITBUCKET_BRANCH",
+    "BUILDKITE_BRANCH", "BRANCH_NAME", "GIT_BRANCH"].iter()
+    .filter_map(|var| std::env::var(var).ok())
+    .find(|value| !value.is_empty())
+    .or_else(|| git_output(&["rev-parse", "--abbrev-ref", "HEAD"]).filter(|branch| branch != "HEAD"));
+  (version, branch)
+}
+
+fn git_output(args: &[&str]) -> Option<String> {
+  match std::process::Command::new("git").args(args).output() {
+    Ok(output) if output.status.success() => from_utf8(&output.stdout).ok()
+      .map(|value| value.trim().to_string())
+      .filter(|value| !value.is_empty()),
+    Ok(output) => {
+      debug!("git {} failed - {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+      None
+    }
+    Err(err) => {
+      debug!("Could not run git {} - {}", args.join(" "), err);
+      None
+    }
+  }
+}
+
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -1096,6 +1309,9 @@ pub struct PactVerificationProperties {
   #[serde(default)]
   /// If the Pact is pending
   pub pending: bool,
+  #[serde(default)]
+  /// If the Pact is a work in progress (WIP) pact
+  pub wip: bool,
   /// Notices provided by the Pact Broker
   pub notices: Vec<HashMap<String, String>>,
 }
@@ -1179,6 +1395,22 @@ mod tests {
         );
 
         expect!(json_content_type(&response)).to(be_true());
+
+        let response = reqwest::Response::from(
+            http::response::Builder::new()
+                .header("content-type", "Application/HAL+JSON; charset=UTF-8")
+                .body("null")
+                .unwrap()
+        );
+        expect!(json_content_type(&response)).to(be_true());
+
+        let response = reqwest::Response::from(
+            http::response::Builder::new()
+                .header("content-type", "text/html")
+                .body("null")
+                .unwrap()
+        );
+        expect!(json_content_type(&response)).to(be_false());
     }
 
     #[test_log::test(tokio::test)]
@@ -1563,6 +1795,35 @@ mod tests {
     expect!(result).to(be_ok().value(Value::String("Yay! You found your way here".to_string())));
   }
 
+  #[test_log::test(tokio::test)]
+  async fn fetch_broker_index_returns_the_links_from_the_root_resource() {
+    let pact_broker = PactBuilderAsync::new("RustPactVerifier", "PactBroker")
+      .interaction("a request to the pact broker root", "", |mut i| async move {
+        i.request
+          .path("/")
+          .header("Accept", "application/hal+json")
+          .header("Accept", "application/json");
+        i.response
+          .header("Content-Type", "application/hal+json")
+          .json_body(json_pattern!({
+            "_links": {
+              "pb:latest-provider-pacts": {
+                "href": "http://localhost/pacts/provider/{provider}/latest",
+                "templated": true,
+              }
+            }
+          }));
+        i
+      })
+      .await
+      .start_mock_server(None);
+
+    let result = fetch_broker_index(pact_broker.url().as_str(), None).await.unwrap();
+    expect!(result.iter().map(|link| link.name.clone()).collect::<Vec<_>>()).to(be_equal_to(vec![
+      "pb:latest-provider-pacts".to_string()
+    ]));
+  }
+
     #[test_log::test(tokio::test)]
     async fn fetch_pacts_from_broker_returns_empty_list_if_there_are_no_pacts() {
         let pact_broker = PactBuilderAsync::new("RustPactVerifier", "PactBroker")
@@ -2044,7 +2305,7 @@ mod tests {
   #[test]
   fn test_build_payload_with_success() {
     let result = TestResult::Ok(vec![]);
-    let payload = super::build_payload(result, "1".to_string(), None, None);
+    let payload = super::build_payload(result, "1".to_string(), None, None, None);
     assert_eq!(payload, json!({
       "providerApplicationVersion": "1",
       "success": true,
@@ -2059,7 +2320,7 @@ mod tests {
   #[test]
   fn test_build_payload_adds_the_build_url_if_provided() {
     let result = TestResult::Ok(vec![]);
-    let payload = super::build_payload(result, "1".to_string(), Some("http://build-url".to_string()), None);
+    let payload = super::build_payload(result, "1".to_string(), Some("http://build-url".to_string()), None, None);
     assert_eq!(payload, json!({
       "providerApplicationVersion": "1",
       "success": true,
@@ -2075,7 +2336,7 @@ mod tests {
   #[test]
   fn test_build_payload_adds_a_result_for_each_interaction() {
     let result = TestResult::Ok(vec![Some("1".to_string()), Some("2".to_string()), Some("3".to_string()), None]);
-    let payload = super::build_payload(result, "1".to_string(), Some("http://build-url".to_string()), None);
+    let payload = super::build_payload(result, "1".to_string(), Some("http://build-url".to_string()), None, None);
     assert_eq!(payload, json!({
       "providerApplicationVersion": "1",
       "success": true,
@@ -2095,7 +2356,7 @@ mod tests {
   #[test]
   fn test_build_payload_with_failure() {
     let result = TestResult::Failed(vec![]);
-    let payload = super::build_payload(result, "1".to_string(), None, None);
+    let payload = super::build_payload(result, "1".to_string(), None, None, None);
     assert_eq!(payload, json!({
       "providerApplicationVersion": "1",
       "success": false,
@@ -2119,13 +2380,14 @@ mod tests {
         interaction_id: Some("1234abc".to_string())
       }))
     ]);
-    let payload = super::build_payload(result, "1".to_string(), None, None);
+    let payload = super::build_payload(result, "1".to_string(), None, None, None);
     assert_eq!(payload, json!({
       "providerApplicationVersion": "1",
       "success": false,
       "testResults": [
         {
           "interactionId": "1234abc",
+          "mismatchCounts": { "method": 1 },
           "mismatches": [
             {
               "attribute": "method", "description": "Expected method of PUT but received POST"
@@ -2146,7 +2408,7 @@ mod tests {
     let result = TestResult::Failed(vec![
       (Some("1234abc".to_string()), None, Some(MismatchResult::Error("Bang".to_string(), Some("1234abc".to_string()))))
     ]);
-    let payload = super::build_payload(result, "1".to_string(), None, None);
+    let payload = super::build_payload(result, "1".to_string(), None, None, None);
     assert_eq!(payload, json!({
       "providerApplicationVersion": "1",
       "success": false,
@@ -2182,13 +2444,14 @@ mod tests {
       (Some("12345678".to_string()), None, Some(MismatchResult::Error("Bang".to_string(), Some("1234abc".to_string())))),
       (Some("abc123".to_string()), None, None)
     ]);
-    let payload = super::build_payload(result, "1".to_string(), None, None);
+    let payload = super::build_payload(result, "1".to_string(), None, None, None);
     assert_eq!(payload, json!({
       "providerApplicationVersion": "1",
       "success": false,
       "testResults": [
         {
           "interactionId": "1234abc",
+          "mismatchCounts": { "method": 1 },
           "mismatches": [
             {
               "attribute": "method", "description": "Expected method of PUT but received POST"
@@ -2225,7 +2488,7 @@ mod tests {
       app_name: "TESTER".to_string(),
       app_version: "1.2.3".to_string()
     };
-    let payload = super::build_payload(result, "1".to_string(), Some("http://build-url".to_string()), Some(&metrics));
+    let payload = super::build_payload(result, "1".to_string(), Some("http://build-url".to_string()), Some(&metrics), None);
     assert_eq!(payload, json!({
       "providerApplicationVersion": "1",
       "success": true,
@@ -2243,6 +2506,55 @@ mod tests {
     }));
   }
 
+  #[test]
+  fn test_build_payload_adds_the_interaction_durations_and_environment_if_provided() {
+    let result = TestResult::Failed(vec![
+      (Some("1234abc".to_string()), None, Some(MismatchResult::Error("Bang".to_string(), Some("1234abc".to_string())))),
+      (Some("abc123".to_string()), None, None),
+      (Some("xyz".to_string()), None, None)
+    ]);
+    let data = VerificationResultData {
+      durations: hashmap!{
+        "1234abc".to_string() => Duration::from_millis(120),
+        "abc123".to_string() => Duration::from_millis(45)
+      },
+      environment: hashmap!{ "os".to_string() => "linux".to_string() }
+    };
+    let payload = super::build_payload(result, "1".to_string(), None, None, Some(&data));
+    assert_eq!(payload, json!({
+      "providerApplicationVersion": "1",
+      "success": false,
+      "testResults": [
+        {
+          "durationMs": 120,
+          "exceptions": [
+            {
+              "message": "Bang"
+            }
+          ],
+          "interactionId": "1234abc",
+          "success": false
+        },
+        {
+          "durationMs": 45,
+          "interactionId": "abc123",
+          "success": true
+        },
+        {
+          "interactionId": "xyz",
+          "success": true
+        }
+      ],
+      "verifiedBy": {
+        "implementation": "Pact-Rust",
+        "version": VERIFIER_VERSION,
+        "environment": {
+          "os": "linux"
+        }
+      }
+    }));
+  }
+
   #[test]
   fn test_build_payload_with_failure_adds_the_interaction_desc_if_set() {
     let result = TestResult::Failed(vec![
@@ -2255,7 +2567,7 @@ mod tests {
         interaction_id: Some("1234abc".to_string())
       }))
     ]);
-    let payload = super::build_payload(result, "1".to_string(), None, None);
+    let payload = super::build_payload(result, "1".to_string(), None, None, None);
     assert_eq!(payload, json!({
       "providerApplicationVersion": "1",
       "success": false,
@@ -2263,6 +2575,7 @@ mod tests {
         {
           "interactionId": "1234abc",
           "interactionDescription": "int_desc",
+          "mismatchCounts": { "method": 1 },
           "mismatches": [
             {
               "attribute": "method", "description": "Expected method of PUT but received POST"
@@ -2416,6 +2729,123 @@ mod tests {
     expect!(result).to(be_ok());
   }
 
+  #[test]
+  fn build_publish_contracts_payload_test() {
+    let pact = json!({ "consumer": { "name": "Consumer" }, "provider": { "name": "Provider" } });
+    let pact2 = json!({ "consumer": { "name": "Consumer" }, "provider": { "name": "Provider 2" } });
+    let options = PublishPactsOptions {
+      consumer_version: "1.0.0".to_string(),
+      branch: Some("main".to_string()),
+      tags: vec!["dev".to_string()],
+      build_url: None
+    };
+
+    let result = build_publish_contracts_payload(&[pact.clone(), pact2.clone()], &options).unwrap();
+    assert_eq!(json!({
+      "pacticipantName": "Consumer",
+      "pacticipantVersionNumber": "1.0.0",
+      "branch": "main",
+      "tags": ["dev"],
+      "contracts": [
+        {
+          "consumerName": "Consumer",
+          "providerName": "Provider",
+          "specification": "pact",
+          "contentType": "application/json",
+          "content": STANDARD.encode(pact.to_string())
+        },
+        {
+          "consumerName": "Consumer",
+          "providerName": "Provider 2",
+          "specification": "pact",
+          "contentType": "application/json",
+          "content": STANDARD.encode(pact2.to_string())
+        }
+      ]
+    }), result);
+
+    expect!(build_publish_contracts_payload(&[], &options)).to(be_err());
+    expect!(build_publish_contracts_payload(&[pact.clone()], &PublishPactsOptions::default())).to(be_err());
+    expect!(build_publish_contracts_payload(&[json!({ "provider": { "name": "Provider" } })], &options)).to(be_err());
+    let other_consumer = json!({ "consumer": { "name": "Other" }, "provider": { "name": "Provider" } });
+    expect!(build_publish_contracts_payload(&[pact, other_consumer], &options)).to(be_err().value(
+      "All pacts must be for the same consumer, but got pacts for 'Consumer' and 'Other'"));
+  }
+
+  #[test_log::test(tokio::test)]
+  async fn publish_pacts_posts_the_pacts_to_the_publish_contracts_link() {
+    let pact = json!({ "consumer": { "name": "Consumer" }, "provider": { "name": "Provider" } });
+    let content = STANDARD.encode(pact.to_string());
+    let pact_broker = PactBuilderAsync::new("RustPactVerifier", "PactBroker")
+      .interaction("a request to the pact broker root", "", |mut i| async move {
+        i.request.path("/");
+        i.response
+          .header("Content-Type", "application/hal+json")
+          .json_body(json!({
+            "_links": {
+              "pb:publish-contracts": {
+                "href": "http://localhost/contracts/publish"
+              }
+            }
+          }));
+        i
+      })
+      .await
+      .interaction("a request to publish the pacts", "", |mut i| async move {
+        i.request
+          .method("POST")
+          .path("/contracts/publish")
+          .json_body(json!({
+            "pacticipantName": "Consumer",
+            "pacticipantVersionNumber": "1.0.0",
+            "tags": [],
+            "contracts": [
+              {
+                "consumerName": "Consumer",
+                "providerName": "Provider",
+                "specification": "pact",
+                "contentType": "application/json",
+                "content": content
+              }
+            ]
+          }));
+        i.response
+          .status(200)
+          .content_type("application/hal+json")
+          .json_body(json!({
+            "notices": [ { "type": "success", "text": "Created Consumer version 1.0.0" } ]
+          }));
+        i
+      })
+      .await
+      .start_mock_server(None);
+
+    let options = PublishPactsOptions { consumer_version: "1.0.0".to_string(), .. PublishPactsOptions::default() };
+    let result = publish_pacts(pact_broker.url().as_str(), None, &[pact], &options).await;
+    expect!(result).to(be_ok().value(json!({
+      "notices": [ { "type": "success", "text": "Created Consumer version 1.0.0" } ]
+    })));
+  }
+
+  #[test_log::test(tokio::test)]
+  async fn publish_pacts_returns_an_error_if_the_broker_does_not_support_publishing_contracts() {
+    let pact_broker = PactBuilderAsync::new("RustPactVerifier", "PactBroker")
+      .interaction("a request to an old pact broker root", "", |mut i| async move {
+        i.request.path("/");
+        i.response
+          .header("Content-Type", "application/hal+json")
+          .json_body(json!({ "_links": {} }));
+        i
+      })
+      .await
+      .start_mock_server(None);
+
+    let pact = json!({ "consumer": { "name": "Consumer" }, "provider": { "name": "Provider" } });
+    let options = PublishPactsOptions { consumer_version: "1.0.0".to_string(), .. PublishPactsOptions::default() };
+    let result = publish_pacts(pact_broker.url().as_str(), None, &[pact], &options).await;
+    expect!(result).to(be_err());
+  }
+
   #[test_log::test(tokio::test)]
   async fn send_document_supports_broker_urls_with_context_paths() {
     let pact_broker = PactBuilder::new("RustPactVerifier", "PactBrokerStub")
@@ -0,0 +1,385 @@
+//! Support for pact sources implemented by the calling application. A custom source can be used
+//! directly with `PactSource::Custom`, or a source can be registered for a URL scheme, in which
+//! case any `PactSource::URL` with that scheme will be loaded with it instead of making an HTTP
+//! request.
+//!
+//! There are built-in sources for loading pacts from object storage:
+//!
+//! | Scheme | Feature | Description |
+//! |--------|---------|-------------|
+//! | `s3://bucket/key` | `s3` | Loads pacts from an AWS S3 bucket. The credentials, region and endpoint are configured from the standard AWS environment variables and config files. |
+//! | `gs://bucket/object` | `gcs` | Loads pacts from a Google Cloud Storage bucket. The access token is taken from the URL token authentication, the `GOOGLE_OAUTH_ACCESS_TOKEN` environment variable or the GCE metadata server. The `STORAGE_EMULATOR_HOST` environment variable can be used to set the endpoint. |
+//!
+//! If the key (or object name) is empty or ends with a `/`, all the JSON files with that prefix
+//! will be loaded, and only the pacts for the provider being verified will be returned.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use pact_models::http_utils::HttpAuth;
+use serde_json::Value;
+use tracing::debug;
+use url::Url;
+
+use crate::ProviderInfo;
+
+/// Function that creates a pact source for a URL
+pub type UrlSourceFactory = dyn Fn(&Url, &Option<HttpAuth>) -> anyhow::Result<Arc<dyn CustomPactSource>> + Send + Sync;
+
+lazy_static! {
+  static ref URL_SOURCES: RwLock<HashMap<String, Arc<UrlSourceFactory>>> = RwLock::new(default_url_sources());
+}
+
+/// Trait for sources of pacts implemented by the calling application
+#[async_trait]
+pub trait CustomPactSource: Debug + Send + Sync {
+  /// Description of the source, used in log and error messages
+  fn description(&self) -> String;
+
+  /// Loads the pacts from the source. Each entry is the name of the resource the pact was loaded
+  /// from and the pact JSON. Sources that contain pacts for more than one provider should only
+  /// return the pacts for the given provider.
+  async fn load_pacts(&self, provider: &ProviderInfo) -> anyhow::Result<Vec<anyhow::Result<(String, Value)>>>;
+}
+
+/// Registers a factory for creating pact sources for URLs with the given scheme (i.e. `s3`).
+/// Registering a factory for an existing scheme will replace the previous one.
+pub fn register_url_source(scheme: &str, factory: Arc<UrlSourceFactory>) {
+  debug!("Registering pact source for URL scheme '{}'", scheme);
+  let mut sources = URL_SOURCES.write().unwrap();
+  sources.insert(scheme.to_lowercase(), factory);
+}
+
+/// Removes the pact source factory for a URL scheme, returning true if one was registered
+pub fn unregister_url_source(scheme: &str) -> bool {
+  debug!("Removing pact source for URL scheme '{}'", scheme);
+  let mut sources = URL_SOURCES.write().unwrap();
+  sources.remove(&scheme.to_lowercase()).is_some()
+}
+
+/// Returns the pact source for the URL, if a source has been registered for the URL scheme.
+pub fn source_for_url(url: &str, auth: &Option<HttpAuth>) -> Option<anyhow::Result<Arc<dyn CustomPactSource>>> {
+  let url = Url::parse(url).ok()?;
+  let factory = {
+    let sources = URL_SOURCES.read().unwrap();
+    sources.get(url.scheme()).cloned()
+  };
+  factory.map(|factory| factory(&url, auth))
+}
+
+#[allow(unused_mut)]
+fn default_url_sources() -> HashMap<String, Arc<UrlSourceFactory>> {
+  let mut sources: HashMap<String, Arc<UrlSourceFactory>> = HashMap::new();
+  #[cfg(feature = "s3")]
+  sources.insert("s3".to_string(), Arc::new(|url: &Url, _: &Option<HttpAuth>| {
+    let (bucket, key) = bucket_and_key(url)?;
+    Ok(Arc::new(s3::S3PactSource { bucket, key }) as Arc<dyn CustomPactSource>)
+  }));
+  #[cfg(feature = "gcs")]
+  sources.insert("gs".to_string(), Arc::new(|url: &Url, auth: &Option<HttpAuth>| {
+    let (bucket, object) = bucket_and_key(url)?;
+    let token = match auth {
+      Some(HttpAuth::Token(token)) => Some(token.clone()),
+      _ => None
+    };
+    Ok(Arc::new(gcs::GcsPactSource { bucket, object, token }) as Arc<dyn CustomPactSource>)
+  }));
+  sources
+}
+
+/// Splits an object storage URL into the bucket and the key
+#[cfg(any(feature = "s3", feature = "gcs", test))]
+fn bucket_and_key(url: &Url) -> anyhow::Result<(String, String)> {
+  let bucket = url.host_str()
+    .filter(|host| !host.is_empty())
+    .ok_or_else(|| anyhow::anyhow!("URL '{}' does not have a bucket", url))?;
+  let key = urlencoding::decode(url.path().trim_start_matches('/'))?;
+  Ok((bucket.to_string(), key.to_string()))
+}
+
+/// If the key refers to a prefix to list instead of a single object
+#[cfg(any(feature = "s3", feature = "gcs", test))]
+fn is_prefix(key: &str) -> bool {
+  key.is_empty() || key.ends_with('/')
+}
+
+/// If the pact JSON is for the provider
+#[cfg(any(feature = "s3", feature = "gcs", test))]
+fn is_for_provider(json: &Value, provider: &ProviderInfo) -> bool {
+  json.pointer("/provider/name").and_then(|name| name.as_str()) == Some(provider.name.as_str())
+}
+
+#[cfg(feature = "s3")]
+mod s3 {
+  use anyhow::anyhow;
+  use async_trait::async_trait;
+  use aws_sdk_s3::Client;
+  use aws_sdk_s3::error::DisplayErrorContext;
+  use serde_json::Value;
+  use tracing::trace;
+
+  use crate::ProviderInfo;
+
+  use super::{CustomPactSource, is_for_provider, is_prefix};
+
+  #[derive(Debug, Clone)]
+  pub(crate) struct S3PactSource {
+    pub(crate) bucket: String,
+    pub(crate) key: String
+  }
+
+  impl S3PactSource {
+    async fn fetch(&self, client: &Client, key: &str) -> anyhow::Result<Value> {
+      trace!(bucket = %self.bucket, %key, "Fetching pact from S3");
+      let output = client.get_object()
+        .bucket(&self.bucket)
+        .key(key)
+        .send()
+        .await
+        .map_err(|err| anyhow!("{}", DisplayErrorContext(&err)))?;
+      let contents = output.body.collect().await?.into_bytes();
+      Ok(serde_json::from_slice(&contents)?)
+    }
+  }
+
+  #[async_trait]
+  impl CustomPactSource for S3PactSource {
+    fn description(&self) -> String {
+      format!("s3://{}/{}", self.bucket, self.key)
+    }
+
+    async fn load_pacts(&self, provider: &ProviderInfo) -> anyhow::Result<Vec<anyhow::Result<(String, Value)>>> {
+      let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+      let client = Client::new(&config);
+
+      if is_prefix(&self.key) {
+        let mut keys = vec![];
+        let mut pages = client.list_objects_v2()
+          .bucket(&self.bucket)
+          .prefix(&self.key)
+          .into_paginator()
+          .send();
+        while let Some(page) = pages.next().await {
+          let page = page.map_err(|err| anyhow!("{}", DisplayErrorContext(&err)))?;
+          keys.extend(page.contents().iter()
+            .filter_map(|object| object.key())
+            .filter(|key| key.ends_with(".json"))
+            .map(|key| key.to_string()));
+        }
+        keys.sort();
+
+        let mut pacts = vec![];
+        for key in keys {
+          let name = format!("s3://{}/{}", self.bucket, key);
+          match self.fetch(&client, &key).await {
+            Ok(json) => if is_for_provider(&json, provider) {
+              pacts.push(Ok((name, json)));
+            }
+            Err(err) => pacts.push(Err(anyhow!("Failed to load pact '{}' - {}", name, err)))
+          }
+        }
+        Ok(pacts)
+      } else {
+        let json = self.fetch(&client, &self.key).await?;
+        Ok(vec![ Ok((self.description(), json)) ])
+      }
+    }
+  }
+}
+
+#[cfg(feature = "gcs")]
+mod gcs {
+  use std::env;
+
+  use anyhow::anyhow;
+  use async_trait::async_trait;
+  use reqwest::{Client, RequestBuilder};
+  use serde_json::Value;
+  use tracing::{debug, trace};
+
+  use crate::ProviderInfo;
+
+  use super::{CustomPactSource, is_for_provider, is_prefix};
+
+  const METADATA_TOKEN_URL: &str = "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+  #[derive(Debug, Clone)]
+  pub(crate) struct GcsPactSource {
+    pub(crate) bucket: String,
+    pub(crate) object: String,
+    pub(crate) token: Option<String>
+  }
+
+  impl GcsPactSource {
+    fn base_url(&self) -> String {
+      let host = env::var("STORAGE_EMULATOR_HOST")
+        .unwrap_or_else(|_| "https://storage.googleapis.com".to_string());
+      format!("{}/storage/v1/b/{}/o", host.trim_end_matches('/'), urlencoding::encode(&self.bucket))
+    }
+
+    async fn access_token(&self, client: &Client) -> Option<String> {
+      if let Some(token) = &self.token {
+        return Some(token.clone());
+      }
+      if let Ok(token) = env::var("GOOGLE_OAUTH_ACCESS_TOKEN") {
+        return Some(token);
+      }
+      if env::var("STORAGE_EMULATOR_HOST").is_ok() {
+        return None;
+      }
+
+      let response = client.get(METADATA_TOKEN_URL)
+        .header("Metadata-Flavor", "Google")
+        .send()
+        .await;
+      match response {
+        Ok(response) if response.status().is_success() => response.json::<Value>().await.ok()
+          .and_then(|json| json.get("access_token").and_then(|token| token.as_str()).map(|token| token.to_string())),
+        _ => {
+          debug!("Could not get an access token from the metadata server, requests will be anonymous");
+          None
+        }
+      }
+    }
+
+    async fn send(&self, request: RequestBuilder, token: &Option<String>) -> anyhow::Result<Value> {
+      let request = match token {
+        Some(token) => request.bearer_auth(token),
+        None => request
+      };
+      let response = request.send().await?;
+      if response.status().is_success() {
+        Ok(response.json().await?)
+      } else {
+        Err(anyhow!("Request failed with status - {}", response.status()))
+      }
+    }
+
+    async fn fetch(&self, client: &Client, object: &str, token: &Option<String>) -> anyhow::Result<Value> {
+      trace!(bucket = %self.bucket, %object, "Fetching pact from Google Cloud Storage");
+      let url = format!("{}/{}", self.base_url(), urlencoding::encode(object));
+      self.send(client.get(url).query(&[("alt", "media")]), token).await
+    }
+  }
+
+  #[async_trait]
+  impl CustomPactSource for GcsPactSource {
+    fn description(&self) -> String {
+      format!("gs://{}/{}", self.bucket, self.object)
+    }
+
+    async fn load_pacts(&self, provider: &ProviderInfo) -> anyhow::Result<Vec<anyhow::Result<(String, Value)>>> {
+      let client = Client::new();
+      let token = self.access_token(&client).await;
+
+      if is_prefix(&self.object) {
+        let mut objects = vec![];
+        let mut page_token: Option<String> = None;
+        loop {
+          let mut query = vec![("prefix", self.object.clone())];
+          if let Some(page_token) = &page_token {
+            query.push(("pageToken", page_token.clone()));
+          }
+          let page = self.send(client.get(self.base_url()).query(&query), &token).await?;
+          if let Some(items) = page.get("items").and_then(|items| items.as_array()) {
+            objects.extend(items.iter()
+              .filter_map(|item| item.get("name").and_then(|name| name.as_str()))
+              .filter(|name| name.ends_with(".json"))
+              .map(|name| name.to_string()));
+          }
+          page_token = page.get("nextPageToken").and_then(|token| token.as_str()).map(|token| token.to_string());
+          if page_token.is_none() {
+            break;
+          }
+        }
+        objects.sort();
+
+        let mut pacts = vec![];
+        for object in objects {
+          let name = format!("gs://{}/{}", self.bucket, object);
+          match self.fetch(&client, &object, &token).await {
+            Ok(json) => if is_for_provider(&json, provider) {
+              pacts.push(Ok((name, json)));
+            }
+            Err(err) => pacts.push(Err(anyhow!("Failed to load pact '{}' - {}", name, err)))
+          }
+        }
+        Ok(pacts)
+      } else {
+        let json = self.fetch(&client, &self.object, &token).await?;
+        Ok(vec![ Ok((self.description(), json)) ])
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use async_trait::async_trait;
+  use expectest::prelude::*;
+  use serde_json::{json, Value};
+  use url::Url;
+
+  use crate::ProviderInfo;
+
+  use super::*;
+
+  #[derive(Debug)]
+  struct TestSource;
+
+  #[async_trait]
+  impl CustomPactSource for TestSource {
+    fn description(&self) -> String {
+      "test".to_string()
+    }
+
+    async fn load_pacts(&self, _provider: &ProviderInfo) -> anyhow::Result<Vec<anyhow::Result<(String, Value)>>> {
+      Ok(vec![])
+    }
+  }
+
+  #[test]
+  fn bucket_and_key_test() {
+    expect!(bucket_and_key(&Url::parse("s3://pacts/consumer/provider.json").unwrap()).unwrap())
+      .to(be_equal_to(("pacts".to_string(), "consumer/provider.json".to_string())));
+    expect!(bucket_and_key(&Url::parse("gs://pacts/some%20dir/").unwrap()).unwrap())
+      .to(be_equal_to(("pacts".to_string(), "some dir/".to_string())));
+    expect!(bucket_and_key(&Url::parse("s3://pacts").unwrap()).unwrap())
+      .to(be_equal_to(("pacts".to_string(), "".to_string())));
+    expect!(bucket_and_key(&Url::parse("s3:///provider.json").unwrap())).to(be_err());
+  }
+
+  #[test]
+  fn is_prefix_test() {
+    expect!(is_prefix("")).to(be_true());
+    expect!(is_prefix("pacts/")).to(be_true());
+    expect!(is_prefix("pacts/provider.json")).to(be_false());
+  }
+
+  #[test]
+  fn is_for_provider_test() {
+    let provider = ProviderInfo { name: "provider".to_string(), .. ProviderInfo::default() };
+    expect!(is_for_provider(&json!({ "provider": { "name": "provider" } }), &provider)).to(be_true());
+    expect!(is_for_provider(&json!({ "provider": { "name": "other" } }), &provider)).to(be_false());
+    expect!(is_for_provider(&json!({}), &provider)).to(be_false());
+  }
+
+  #[test]
+  fn source_for_url_test() {
+    expect!(source_for_url("custom://pacts/provider.json", &None).is_none()).to(be_true());
+    expect!(source_for_url("http://localhost/pacts/provider.json", &None).is_none()).to(be_true());
+
+    register_url_source("custom", Arc::new(|_: &Url, _: &Option<HttpAuth>| {
+      Ok(Arc::new(TestSource) as Arc<dyn CustomPactSource>)
+    }));
+    let source = source_for_url("custom://pacts/provider.json", &None);
+    expect!(unregister_url_source("custom")).to(be_true());
+    expect!(source.unwrap().unwrap().description()).to(be_equal_to("test"));
+    expect!(source_for_url("custom://pacts/provider.json", &None).is_none()).to(be_true());
+  }
+}
@@ -13,6 +13,7 @@ use http::{HeaderMap, HeaderValue, Method};
 use http::header::{HeaderName, InvalidHeaderName, InvalidHeaderValue};
 use http::header::CONTENT_TYPE;
 use http::method::InvalidMethod;
+use http_body_util::BodyExt;
 use itertools::Itertools;
 use maplit::hashmap;
 use pact_models::bodies::OptionalBody;
@@ -161,12 +162,18 @@ fn extract_headers(headers: &HeaderMap) -> Option<HashMap<String, Vec<String>>>
   }
 }
 
-async fn extract_body(response: reqwest::Response, pact_response: &HttpResponse) -> anyhow::Result<OptionalBody> {
-  let body = response.bytes().await?;
+async fn extract_body(
+  response: reqwest::Response,
+  pact_response: &HttpResponse
+) -> anyhow::Result<(OptionalBody, Option<HashMap<String, Vec<String>>>)> {
+  // Collect the body as frames so that any trailers sent after the body (HTTP/2) are also captured
+  let collected = http::Response::from(response).into_body().collect().await?;
+  let trailers = collected.trailers().and_then(extract_headers);
+  let body = collected.to_bytes();
   if !body.is_empty() {
-    Ok(OptionalBody::Present(body, pact_response.content_type(), None))
+    Ok((OptionalBody::Present(body, pact_response.content_type(), None), trailers))
   } else {
-    Ok(OptionalBody::Empty)
+    Ok((OptionalBody::Empty, trailers))
   }
 }
 
@@ -181,10 +188,10 @@ async fn native_response_to_pact_response(native_response: reqwest::Response) ->
     .. HttpResponse::default()
   };
 
-  let body = extract_body(native_response, &response).await?;
+  let (body, trailers) = extract_body(native_response, &response).await?;
 
   Ok(HttpResponse {
-    body, .. response.clone()
+    body, trailers, .. response.clone()
   })
 }
 
@@ -201,7 +208,8 @@ pub async fn make_provider_request<F: RequestFilterExecutor>(
   let request = if request_filter_option.is_some() {
     let request_filter = request_filter_option.unwrap();
     info!("Invoking request filter for request");
-    request_filter.call(request)
+    request_filter.call_async(request).await
+      .map_err(|err| anyhow!("Request filter failed - {}", err))?
   } else {
     request.clone()
   };
@@ -283,13 +291,20 @@ pub async fn make_state_change_request(
 
 #[cfg(test)]
 mod tests {
+  use std::collections::HashMap;
+  use std::sync::Arc;
+
+  use async_trait::async_trait;
+  use bytes::Bytes;
   use expectest::expect;
   use expectest::prelude::*;
   use http::HeaderMap;
-  use itertools::Itertools;
+  use itertools::{Either, Itertools};
   use maplit::*;
   use pact_models::bodies::OptionalBody;
+  use pact_models::http_parts::HttpPart;
   use pact_models::v4::http_parts::HttpRequest;
+  use serde_json::Value;
 
   use pact_consumer::builders::{HttpPartBuilder, PactBuilderAsync};
   use pact_consumer::mock_server::StartMockServer;
@@ -298,6 +313,7 @@ mod tests {
     configure_http_client,
     NullRequestFilterExecutor,
     ProviderInfo,
+    RequestFilterExecutor,
     VerificationOptions
   };
 
@@ -490,6 +506,88 @@ mod tests {
     super::make_provider_request(&provider, &request, &options, &client, None).await.unwrap();
   }
 
+  #[derive(Debug)]
+  struct SigningRequestFilter {
+    fail: bool
+  }
+
+  #[async_trait]
+  impl RequestFilterExecutor for SigningRequestFilter {
+    fn call(self: Arc<Self>, request: &HttpRequest) -> HttpRequest {
+      request.clone()
+    }
+
+    async fn call_async(self: Arc<Self>, request: &HttpRequest) -> anyhow::Result<HttpRequest> {
+      if self.fail {
+        Err(anyhow::anyhow!("could not fetch signing key"))
+      } else {
+        let mut request = request.clone();
+        let signature = format!("Signature {} {}", request.method, request.path);
+        request.add_header("Authorization", vec![signature.as_str()]);
+        Ok(request)
+      }
+    }
+
+    fn call_non_http(
+      &self,
+      request_body: &OptionalBody,
+      metadata: &HashMap<String, Either<Value, Bytes>>
+    ) -> (OptionalBody, HashMap<String, Either<Value, Bytes>>) {
+      (request_body.clone(), metadata.clone())
+    }
+  }
+
+  #[tokio::test]
+  async fn make_provider_request_applies_the_async_request_filter() {
+    let request = HttpRequest {
+      path: "/signed".to_string(),
+      .. HttpRequest::default()
+    };
+    let options = VerificationOptions {
+      request_filter: Some(Arc::new(SigningRequestFilter { fail: false })),
+      .. VerificationOptions::default()
+    };
+    let client = configure_http_client(&options).unwrap();
+
+    let server = PactBuilderAsync::new("make_provider_request", "provider")
+      .interaction("request with a signature", "", |mut i| async move {
+        i.request
+          .method("GET")
+          .path("/signed")
+          .header("Authorization", "Signature GET /signed");
+        i.response.ok();
+        i
+      })
+      .await
+      .start_mock_server(None);
+
+    #[allow(deprecated)]
+    let provider = ProviderInfo {
+      port: server.url().port(),
+      .. ProviderInfo::default()
+    };
+    super::make_provider_request(&provider, &request, &options, &client, None).await.unwrap();
+  }
+
+  #[tokio::test]
+  async fn make_provider_request_returns_an_error_if_the_request_filter_fails() {
+    let request = HttpRequest::default();
+    let options = VerificationOptions {
+      request_filter: Some(Arc::new(SigningRequestFilter { fail: true })),
+      .. VerificationOptions::default()
+    };
+    let client = configure_http_client(&options).unwrap();
+
+    #[allow(deprecated)]
+    let provider = ProviderInfo {
+      port: Some(1),
+      .. ProviderInfo::default()
+    };
+    let result = super::make_provider_request(&provider, &request, &options, &client, None).await;
+    expect!(result.unwrap_err().to_string())
+      .to(be_equal_to("Request filter failed - could not fetch signing key"));
+  }
+
   #[test]
   fn convert_request_to_native_request_with_custom_headers() {
     let client = reqwest::Client::new();
@@ -0,0 +1,135 @@
+//! Waits for the provider to be ready before the verification starts, by polling a health check
+//! URL with an exponential backoff
+
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use humantime::format_duration;
+use reqwest::Client;
+use tokio::time::sleep;
+use tracing::{debug, info};
+
+use crate::failures::ProviderUnreachableError;
+
+/// Options for waiting for the provider to become ready before verifying it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderReadinessCheck {
+  /// Health check URL to poll. The provider is ready once this returns a successful (2xx) response.
+  pub url: String,
+  /// Maximum time to wait for the provider to become ready
+  pub timeout: Duration,
+  /// Delay before the first retry. The delay is doubled after each attempt.
+  pub initial_delay: Duration,
+  /// Maximum delay between attempts
+  pub max_delay: Duration
+}
+
+impl ProviderReadinessCheck {
+  /// Creates a check for the URL with the given timeout, starting with a delay of 100ms between
+  /// attempts up to a maximum of 5 seconds
+  pub fn new<S: Into<String>>(url: S, timeout: Duration) -> Self {
+    ProviderReadinessCheck {
+      url: url.into(),
+      timeout,
+      initial_delay: Duration::from_millis(100),
+      max_delay: Duration::from_secs(5)
+    }
+  }
+
+  /// Delay to wait after the given attempt (starting at zero) before trying again
+  fn delay(&self, attempt: u32) -> Duration {
+    self.initial_delay.saturating_mul(2_u32.saturating_pow(attempt)).min(self.max_delay)
+  }
+
+  /// Polls the health check URL until it returns a successful response. Returns an error if the
+  /// provider does not become ready before the timeout expires.
+  pub async fn wait(&self, client: &Client) -> anyhow::Result<()> {
+    info!("Waiting up to {} for the provider to be ready at {}", format_duration(self.timeout), self.url);
+    let start = Instant::now();
+    let mut attempt = 0;
+    loop {
+      let remaining = self.timeout.saturating_sub(start.elapsed());
+      let problem = match client.get(&self.url).timeout(remaining.max(Duration::from_millis(1))).send().await {
+        Ok(response) if response.status().is_success() => {
+          info!("Provider is ready after {}", format_duration(Duration::from_millis(start.elapsed().as_millis() as u64)));
+          return Ok(());
+        }
+        Ok(response) => format!("returned status {}", response.status()),
+        Err(err) => format!("failed - {}", err)
+      };
+
+      let elapsed = start.elapsed();
+      if elapsed >= self.timeout {
+        return Err(anyhow!(ProviderUnreachableError(format!("Provider did not become ready within {}, the last request to {} {}",
+          format_duration(self.timeout), self.url, problem))));
+      }
+
+      let delay = self.delay(attempt).min(self.timeout - elapsed);
+      debug!("Provider is not ready yet, the request to {} {}. Trying again in {}", self.url, problem,
+        format_duration(delay));
+      sleep(delay).await;
+      attempt += 1;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::net::TcpListener;
+  use std::time::Duration;
+
+  use expectest::prelude::*;
+  use reqwest::Client;
+  use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+  use super::ProviderReadinessCheck;
+
+  #[test]
+  fn delay_doubles_after_each_attempt_up_to_the_maximum() {
+    let check = ProviderReadinessCheck::new("http://localhost/health", Duration::from_secs(60));
+    expect!(check.delay(0)).to(be_equal_to(Duration::from_millis(100)));
+    expect!(check.delay(1)).to(be_equal_to(Duration::from_millis(200)));
+    expect!(check.delay(4)).to(be_equal_to(Duration::from_millis(1600)));
+    expect!(check.delay(6)).to(be_equal_to(Duration::from_secs(5)));
+    expect!(check.delay(100)).to(be_equal_to(Duration::from_secs(5)));
+  }
+
+  #[tokio::test]
+  async fn wait_fails_if_the_provider_never_becomes_ready() {
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let check = ProviderReadinessCheck {
+      initial_delay: Duration::from_millis(10),
+      .. ProviderReadinessCheck::new(format!("http://127.0.0.1:{}/health", port), Duration::from_millis(200))
+    };
+
+    let result = check.wait(&Client::new()).await;
+
+    expect!(result.as_ref()).to(be_err());
+    let message = result.unwrap_err().to_string();
+    expect!(message.starts_with(format!("Provider did not become ready within 200ms, the last request to http://127.0.0.1:{}/health failed - ", port).as_str()))
+      .to(be_true());
+  }
+
+  #[tokio::test]
+  async fn wait_returns_once_the_provider_returns_a_successful_response() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+      for status in ["503 Service Unavailable", "200 OK"] {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buffer = [0; 1024];
+        let _ = socket.read(&mut buffer).await.unwrap();
+        let response = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+        socket.write_all(response.as_bytes()).await.unwrap();
+      }
+    });
+    let check = ProviderReadinessCheck {
+      initial_delay: Duration::from_millis(10),
+      .. ProviderReadinessCheck::new(format!("http://127.0.0.1:{}/health", port), Duration::from_secs(5))
+    };
+
+    let result = check.wait(&Client::new()).await;
+
+    expect!(result).to(be_ok());
+  }
+}
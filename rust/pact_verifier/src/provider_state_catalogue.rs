@@ -0,0 +1,194 @@
+//! Catalogue of the provider states used by the interactions in a set of pacts. This can be used
+//! by provider teams to generate the scaffolding for their provider state handlers.
+
+use std::collections::BTreeMap;
+
+use pact_models::pact::Pact;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{filter_interaction, FilterInfo};
+
+/// Interaction that uses a provider state
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ProviderStateUsage {
+  /// Name of the consumer of the Pact the interaction is from
+  pub consumer: String,
+  /// Interaction description
+  pub description: String
+}
+
+/// Provider state with all the parameters it has been used with
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct ProviderStateEntry {
+  /// Name of the provider state
+  pub name: String,
+  /// Parameters for the provider state, with all the distinct example values that the parameter
+  /// has been given
+  pub parameters: BTreeMap<String, Vec<Value>>,
+  /// Interactions that use the provider state
+  pub interactions: Vec<ProviderStateUsage>
+}
+
+/// Catalogue of the provider states from a set of pacts, ordered by name
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct ProviderStateCatalogue {
+  /// Provider the states are for
+  pub provider: String,
+  /// Provider states
+  #[serde(rename = "providerStates")]
+  pub provider_states: Vec<ProviderStateEntry>
+}
+
+impl ProviderStateCatalogue {
+  /// Creates an empty catalogue for the provider
+  pub fn new(provider: &str) -> Self {
+    ProviderStateCatalogue {
+      provider: provider.to_string(),
+      provider_states: vec![]
+    }
+  }
+
+  /// Adds the provider states from the interactions in the Pact that match the filter
+  pub fn add_pact(&mut self, pact: &dyn Pact, filter: &FilterInfo) {
+    let consumer = pact.consumer().name;
+    for interaction in pact.interactions().iter()
+      .filter(|interaction| filter_interaction(interaction.as_ref(), filter)) {
+      for state in interaction.provider_states() {
+        let entry = match self.provider_states.iter().position(|entry| entry.name == state.name) {
+          Some(index) => &mut self.provider_states[index],
+          None => {
+            let index = self.provider_states.partition_point(|entry| entry.name < state.name);
+            self.provider_states.insert(index, ProviderStateEntry {
+              name: state.name.clone(),
+              .. ProviderStateEntry::default()
+            });
+            &mut self.provider_states[index]
+          }
+        };
+
+        for (key, value) in &state.params {
+          let values = entry.parameters.entry(key.clone()).or_default();
+          if !values.contains(value) {
+            values.push(value.clone());
+          }
+        }
+
+        let usage = ProviderStateUsage {
+          consumer: consumer.clone(),
+          description: interaction.description()
+        };
+        if let Err(index) = entry.interactions.binary_search(&usage) {
+          entry.interactions.insert(index, usage);
+        }
+      }
+    }
+  }
+
+  /// If there are no provider states in the catalogue
+  pub fn is_empty(&self) -> bool {
+    self.provider_states.is_empty()
+  }
+
+  /// Returns the catalogue as JSON
+  pub fn to_json(&self) -> Value {
+    serde_json::to_value(self).unwrap_or_default()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+  use maplit::hashmap;
+  use serde_json::json;
+
+  use pact_models::{Consumer, Provider};
+  use pact_models::pact::Pact;
+  use pact_models::provider_states::ProviderState;
+  use pact_models::v4::interaction::V4Interaction;
+  use pact_models::v4::pact::V4Pact;
+  use pact_models::v4::synch_http::SynchronousHttp;
+
+  use crate::FilterInfo;
+
+  use super::ProviderStateCatalogue;
+
+  fn interaction(description: &str, states: Vec<ProviderState>) -> Box<dyn V4Interaction + Send + Sync + std::panic::RefUnwindSafe> {
+    Box::new(SynchronousHttp {
+      description: description.to_string(),
+      provider_states: states,
+      .. SynchronousHttp::default()
+    })
+  }
+
+  fn pact(consumer: &str, interactions: Vec<Box<dyn V4Interaction + Send + Sync + std::panic::RefUnwindSafe>>) -> V4Pact {
+    V4Pact {
+      consumer: Consumer { name: consumer.to_string() },
+      provider: Provider { name: "Provider".to_string() },
+      interactions,
+      .. V4Pact::default()
+    }
+  }
+
+  #[test]
+  fn catalogue_aggregates_the_provider_states_from_all_the_pacts() {
+    let web = pact("web", vec![
+      interaction("get user", vec![
+        ProviderState { name: "user exists".to_string(), params: hashmap!{ "id".to_string() => json!(1) } }
+      ]),
+      interaction("get order", vec![
+        ProviderState::default("user exists"),
+        ProviderState::default("an order exists")
+      ])
+    ]);
+    let mobile = pact("mobile", vec![
+      interaction("get user", vec![
+        ProviderState { name: "user exists".to_string(), params: hashmap!{ "id".to_string() => json!(2) } }
+      ]),
+      interaction("health check", vec![])
+    ]);
+
+    let mut catalogue = ProviderStateCatalogue::new("Provider");
+    catalogue.add_pact(&web, &FilterInfo::None);
+    catalogue.add_pact(&mobile, &FilterInfo::None);
+    catalogue.add_pact(&web, &FilterInfo::None);
+
+    expect!(catalogue.to_json()).to(be_equal_to(json!({
+      "provider": "Provider",
+      "providerStates": [
+        {
+          "name": "an order exists",
+          "parameters": {},
+          "interactions": [ { "consumer": "web", "description": "get order" } ]
+        },
+        {
+          "name": "user exists",
+          "parameters": { "id": [1, 2] },
+          "interactions": [
+            { "consumer": "mobile", "description": "get user" },
+            { "consumer": "web", "description": "get order" },
+            { "consumer": "web", "description": "get user" }
+          ]
+        }
+      ]
+    })));
+  }
+
+  #[test]
+  fn catalogue_only_includes_the_interactions_that_match_the_filter() {
+    let web = pact("web", vec![
+      interaction("get user", vec![ProviderState::default("user exists")]),
+      interaction("get order", vec![ProviderState::default("an order exists")])
+    ]);
+
+    let mut catalogue = ProviderStateCatalogue::new("Provider");
+    catalogue.add_pact(&web, &FilterInfo::Description("order".to_string()));
+    expect!(catalogue.provider_states.iter().map(|s| s.name.as_str()).collect::<Vec<_>>())
+      .to(be_equal_to(vec!["an order exists"]));
+
+    let mut catalogue = ProviderStateCatalogue::new("Provider");
+    catalogue.add_pact(&web, &FilterInfo::Description("nothing".to_string()));
+    expect!(catalogue.is_empty()).to(be_true());
+    expect!(web.interactions().len()).to(be_equal_to(2));
+  }
+}
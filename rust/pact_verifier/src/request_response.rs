@@ -2,6 +2,7 @@ use ansi_term::Colour::*;
 use ansi_term::Style;
 
 use pact_matching::Mismatch;
+use pact_models::matchingrules::MatchingRule;
 use pact_models::sync_interaction::RequestResponseInteraction;
 
 use crate::{generate_display_for_result, MismatchResult};
@@ -15,7 +16,7 @@ pub fn process_request_response_result(
   match match_result {
     Ok(_) => {
       generate_display_for_result(
-        interaction.response.status,
+        expected_status(interaction),
         if coloured { Green.paint("OK") } else { plain.paint("OK") },
         interaction.response.headers.clone().map(|h| h.iter().map(|(k, v)| {
           (k.clone(), v.join(", "), if coloured { Green.paint("OK") } else { plain.paint("OK") })
@@ -60,9 +61,25 @@ pub fn process_request_response_result(
             if coloured { Green.paint("OK") } else { plain.paint("OK") }
         };
 
-        generate_display_for_result(interaction.response.status, status_result, header_results,
+        generate_display_for_result(expected_status(interaction), status_result, header_results,
                                     body_result, output, coloured);
       }
     }
   }
 }
+
+/// Returns the expected status for display, including the statuses allowed by any status code
+/// matching rule
+fn expected_status(interaction: &RequestResponseInteraction) -> String {
+  let status = interaction.response.matching_rules.rules_for_category("status")
+    .and_then(|category| category.rules.values()
+      .flat_map(|rules| rules.rules.iter())
+      .find_map(|rule| match rule {
+        MatchingRule::StatusCode(status) => Some(status.clone()),
+        _ => None
+      }));
+  match status {
+    Some(status) => format!("{} matching {}", interaction.response.status, status),
+    None => interaction.response.status.to_string()
+  }
+}
@@ -0,0 +1,408 @@
+//! Validators that are run against each response received from the provider during verification.
+//! Any violations are reported as additional mismatches for the interaction.
+
+#[cfg(feature = "openapi")] use std::collections::HashMap;
+use std::fmt::Debug;
+#[cfg(feature = "openapi")] use std::fs;
+#[cfg(feature = "openapi")] use std::path::Path;
+#[cfg(feature = "openapi")] use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "openapi")] use anyhow::anyhow;
+#[cfg(feature = "openapi")] use jsonschema::JSONSchema;
+#[cfg(feature = "openapi")] use serde_json::{json, Value};
+#[cfg(feature = "openapi")] use tracing::debug;
+
+use pact_matching::Mismatch;
+#[cfg(feature = "openapi")] use pact_models::http_parts::HttpPart;
+#[cfg(feature = "openapi")] use pact_models::path_exp::DocPath;
+use pact_models::v4::http_parts::{HttpRequest, HttpResponse};
+
+/// Trait for validators that check the responses returned by the provider during verification.
+pub trait ResponseValidator: Debug + Send + Sync {
+  /// Validates the response returned by the provider for the request, returning any violations
+  /// as mismatches
+  fn validate(&self, request: &HttpRequest, response: &HttpResponse) -> Vec<Mismatch>;
+}
+
+/// Response validator that validates the provider responses against an OpenAPI document.
+///
+/// OpenAPI 3.0 `nullable` schemas are converted to the JSON Schema equivalent (a `null` type)
+/// before the bodies are validated.
+#[cfg(feature = "openapi")]
+#[derive(Debug, Clone)]
+pub struct OpenApiResponseValidator {
+  document: Value,
+  /// Compiled schemas (or the compilation error), keyed by the JSON pointer to the schema
+  schemas: Arc<Mutex<HashMap<String, Result<Arc<JSONSchema>, String>>>>
+}
+
+#[cfg(feature = "openapi")]
+impl OpenApiResponseValidator {
+  /// Creates a validator from a parsed OpenAPI document
+  pub fn new(document: Value) -> Self {
+    OpenApiResponseValidator {
+      document: convert_nullable(document),
+      schemas: Default::default()
+    }
+  }
+
+  /// Loads the OpenAPI document from a JSON or YAML file
+  pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+    let contents = fs::read_to_string(path)?;
+    let is_yaml = path.extension()
+      .map(|ext| ext == "yaml" || ext == "yml")
+      .unwrap_or(false);
+    let document: Value = if is_yaml {
+      serde_yaml::from_str(&contents)?
+    } else {
+      serde_json::from_str(&contents)?
+    };
+    if document.get("paths").is_none() {
+      return Err(anyhow!("'{}' is not an OpenAPI document as it has no paths", path.display()));
+    }
+    Ok(Self::new(document))
+  }
+
+  /// Finds the OpenAPI path template and operation for the request
+  fn find_operation(&self, request: &HttpRequest) -> Option<(String, &Value)> {
+    let method = request.method.to_lowercase();
+    self.document.get("paths")
+      .and_then(|paths| paths.as_object())
+      .and_then(|paths| {
+        paths.iter()
+          .filter(|(template, _)| path_matches_template(&request.path, template))
+          .find_map(|(template, item)| item.get(&method).map(|operation| (template.clone(), operation)))
+      })
+  }
+
+  /// Returns the compiled schema at the given JSON pointer, compiling it the first time it is used
+  fn compiled_schema(&self, schema_pointer: &str) -> Result<Arc<JSONSchema>, String> {
+    let mut schemas = self.schemas.lock().unwrap();
+    schemas.entry(schema_pointer.to_string())
+      .or_insert_with(|| {
+        let mut schema = self.document.pointer(schema_pointer)
+          .cloned()
+          .ok_or_else(|| "the schema was not found in the document".to_string())?;
+        // The shared components are copied into the schema, so any references to them can be
+        // resolved
+        if let (Some(schema), Some(components)) = (schema.as_object_mut(), self.document.get("components")) {
+          schema.insert("components".to_string(), components.clone());
+        }
+        JSONSchema::compile(&schema)
+          .map(Arc::new)
+          .map_err(|err| err.to_string())
+      })
+      .clone()
+  }
+
+  /// Validates the response body against the schema at the given JSON pointer
+  fn validate_body(&self, schema_pointer: &str, body: &Value) -> Vec<Mismatch> {
+    match self.compiled_schema(schema_pointer) {
+      Ok(compiled) => match compiled.validate(body) {
+        Ok(_) => vec![],
+        Err(errors) => errors.map(|error| {
+          let path = error.instance_path.clone().into_vec().iter()
+            .fold(DocPath::root(), |path, part| path.join(part.as_str()));
+          Mismatch::BodyMismatch {
+            path: path.to_string(),
+            expected: None,
+            actual: Some(error.instance.to_string().into()),
+            mismatch: format!("OpenAPI: {}", error),
+            rule: None
+          }
+        }).collect()
+      },
+      Err(err) => vec![openapi_mismatch(format!("Failed to compile the schema '{}' - {}", schema_pointer, err))]
+    }
+  }
+}
+
+#[cfg(feature = "openapi")]
+impl ResponseValidator for OpenApiResponseValidator {
+  fn validate(&self, request: &HttpRequest, response: &HttpResponse) -> Vec<Mismatch> {
+    let (template, operation) = match self.find_operation(request) {
+      Some(operation) => operation,
+      None => return vec![openapi_mismatch(format!("No operation is defined for {} {} in the OpenAPI document",
+        request.method.to_uppercase(), request.path))]
+    };
+    debug!("Validating response against OpenAPI operation {} {}", request.method, template);
+
+    let status = response.status.to_string();
+    let status_range = format!("{}XX", response.status / 100);
+    let responses = operation.get("responses").and_then(|r| r.as_object());
+    let found = [status.as_str(), status_range.as_str(), "default"].iter()
+      .find_map(|key| responses.and_then(|r| r.get(*key)).map(|r| (*key, r)));
+    let (status_key, response_spec) = match found {
+      Some(found) => found,
+      None => return vec![openapi_mismatch(format!("Response status {} is not defined for {} {} in the OpenAPI document",
+        response.status, request.method.to_uppercase(), template))]
+    };
+
+    let mut mismatches = vec![];
+    if let Some(headers) = response_spec.get("headers").and_then(|h| h.as_object()) {
+      for (name, header) in headers {
+        let required = header.get("required").and_then(|r| r.as_bool()).unwrap_or(false);
+        if required && !response.has_header(name) {
+          mismatches.push(Mismatch::HeaderMismatch {
+            key: name.clone(),
+            expected: String::default(),
+            actual: String::default(),
+            mismatch: format!("OpenAPI: Expected required header '{}' but was missing", name),
+            rule: None
+          });
+        }
+      }
+    }
+
+    if let Some(content) = response_spec.get("content").and_then(|c| c.as_object()) {
+      if response.body.is_present() {
+        let content_type = response.content_type().unwrap_or_default();
+        let base_type = content_type.base_type().to_string();
+        let wildcard = format!("{}/*", content_type.main_type);
+        match [base_type.as_str(), wildcard.as_str(), "*/*"].iter().find(|key| content.contains_key(**key)) {
+          Some(media_type) => if content_type.is_json() && content[*media_type].get("schema").is_some() {
+            match serde_json::from_slice::<Value>(&response.body.value().unwrap_or_default()) {
+              Ok(body) => {
+                let pointer = format!("/paths/{}/{}/responses/{}/content/{}/schema", escape_pointer(&template),
+                  request.method.to_lowercase(), status_key, escape_pointer(media_type));
+                mismatches.extend(self.validate_body(&pointer, &body));
+              }
+              Err(err) => mismatches.push(openapi_mismatch(format!("Failed to parse the response body as JSON - {}", err)))
+            }
+          },
+          None => mismatches.push(openapi_mismatch(format!("Content type '{}' is not defined for response status {} of {} {}",
+            content_type, response.status, request.method.to_uppercase(), template)))
+        }
+      }
+    }
+
+    mismatches
+  }
+}
+
+/// If the request path matches the OpenAPI path template (i.e. `/users/{id}`)
+#[cfg(feature = "openapi")]
+fn path_matches_template(path: &str, template: &str) -> bool {
+  let path_segments = path.trim_end_matches('/').split('/').collect::<Vec<_>>();
+  let template_segments = template.trim_end_matches('/').split('/').collect::<Vec<_>>();
+  path_segments.len() == template_segments.len() &&
+    path_segments.iter().zip(template_segments.iter()).all(|(segment, template)| {
+      if template.starts_with('{') && template.ends_with('}') {
+        !segment.is_empty()
+      } else {
+        segment == template
+      }
+    })
+}
+
+/// Converts OpenAPI 3.0 `nullable: true` schemas into the JSON Schema form, by adding `null` to
+/// the schema type (or enum values). Schemas without a type are combined with a `null` schema.
+#[cfg(feature = "openapi")]
+fn convert_nullable(value: Value) -> Value {
+  match value {
+    Value::Object(map) => {
+      let mut map = map.into_iter()
+        .map(|(key, value)| (key, convert_nullable(value)))
+        .collect::<serde_json::Map<String, Value>>();
+      if map.get("nullable") == Some(&Value::Bool(true)) {
+        map.remove("nullable");
+        if let Some(values) = map.get_mut("enum").and_then(|values| values.as_array_mut()) {
+          if !values.contains(&Value::Null) {
+            values.push(Value::Null);
+          }
+        }
+        match map.get("type").cloned() {
+          Some(Value::String(schema_type)) => {
+            map.insert("type".to_string(), json!([schema_type, "null"]));
+            Value::Object(map)
+          }
+          Some(Value::Array(mut types)) => {
+            if !types.contains(&json!("null")) {
+              types.push(json!("null"));
+            }
+            map.insert("type".to_string(), Value::Array(types));
+            Value::Object(map)
+          }
+          _ => json!({ "anyOf": [ Value::Object(map), { "type": "null" } ] })
+        }
+      } else {
+        Value::Object(map)
+      }
+    }
+    Value::Array(values) => Value::Array(values.into_iter().map(convert_nullable).collect()),
+    _ => value
+  }
+}
+
+/// Escapes a JSON pointer reference token
+#[cfg(feature = "openapi")]
+fn escape_pointer(token: &str) -> String {
+  token.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(feature = "openapi")]
+fn openapi_mismatch(message: String) -> Mismatch {
+  Mismatch::BodyMismatch {
+    path: "$".to_string(),
+    expected: None,
+    actual: None,
+    mismatch: format!("OpenAPI: {}", message),
+    rule: None
+  }
+}
+
+#[cfg(all(test, feature = "openapi"))]
+mod tests {
+  use expectest::prelude::*;
+  use maplit::hashmap;
+  use serde_json::json;
+
+  use pact_matching::Mismatch;
+  use pact_models::bodies::OptionalBody;
+  use pact_models::content_types::JSON;
+  use pact_models::v4::http_parts::{HttpRequest, HttpResponse};
+
+  use super::{convert_nullable, OpenApiResponseValidator, path_matches_template, ResponseValidator};
+
+  fn validator() -> OpenApiResponseValidator {
+    OpenApiResponseValidator::new(json!({
+      "openapi": "3.0.3",
+      "info": { "title": "Users", "version": "1.0.0" },
+      "paths": {
+        "/users/{id}": {
+          "get": {
+            "responses": {
+              "200": {
+                "description": "A user",
+                "headers": {
+                  "X-Request-Id": { "required": true, "schema": { "type": "string" } }
+                },
+                "content": {
+                  "application/json": {
+                    "schema": { "$ref": "#/components/schemas/User" }
+                  }
+                }
+              }
+            }
+          }
+        }
+      },
+      "components": {
+        "schemas": {
+          "User": {
+            "type": "object",
+            "required": ["id", "name"],
+            "properties": {
+              "id": { "type": "integer" },
+              "name": { "type": "string" },
+              "email": { "type": "string", "nullable": true }
+            }
+          }
+        }
+      }
+    }))
+  }
+
+  fn messages(mismatches: &[Mismatch]) -> Vec<String> {
+    mismatches.iter().map(|m| m.description()).collect()
+  }
+
+  #[test]
+  fn path_matches_template_test() {
+    expect!(path_matches_template("/users/100", "/users/{id}")).to(be_true());
+    expect!(path_matches_template("/users/100/", "/users/{id}")).to(be_true());
+    expect!(path_matches_template("/users", "/users/{id}")).to(be_false());
+    expect!(path_matches_template("/users/100/roles", "/users/{id}")).to(be_false());
+    expect!(path_matches_template("/accounts/100", "/users/{id}")).to(be_false());
+  }
+
+  #[test]
+  fn validate_returns_no_mismatches_for_a_valid_response() {
+    let request = HttpRequest { path: "/users/100".to_string(), .. HttpRequest::default() };
+    let response = HttpResponse {
+      headers: Some(hashmap!{
+        "Content-Type".to_string() => vec!["application/json".to_string()],
+        "X-Request-Id".to_string() => vec!["1234".to_string()]
+      }),
+      body: OptionalBody::Present(json!({ "id": 100, "name": "Fred", "email": null }).to_string().into(), Some(JSON.clone()), None),
+      .. HttpResponse::default()
+    };
+    expect!(validator().validate(&request, &response)).to(be_empty());
+  }
+
+  #[test]
+  fn validate_returns_mismatches_for_schema_violations() {
+    let request = HttpRequest { path: "/users/100".to_string(), .. HttpRequest::default() };
+    let response = HttpResponse {
+      headers: Some(hashmap!{ "Content-Type".to_string() => vec!["application/json".to_string()] }),
+      body: OptionalBody::Present(json!({ "id": "100" }).to_string().into(), Some(JSON.clone()), None),
+      .. HttpResponse::default()
+    };
+    let mismatches = validator().validate(&request, &response);
+    expect!(messages(&mismatches)).to(be_equal_to(vec![
+      "OpenAPI: Expected required header 'X-Request-Id' but was missing".to_string(),
+      "$.id -> OpenAPI: \"100\" is not of type \"integer\"".to_string(),
+      "$ -> OpenAPI: \"name\" is a required property".to_string()
+    ]));
+  }
+
+  #[test]
+  fn validate_returns_a_mismatch_for_undocumented_operations_and_statuses() {
+    let request = HttpRequest { method: "DELETE".to_string(), path: "/users/100".to_string(), .. HttpRequest::default() };
+    expect!(messages(&validator().validate(&request, &HttpResponse::default()))).to(be_equal_to(vec![
+      "$ -> OpenAPI: No operation is defined for DELETE /users/100 in the OpenAPI document".to_string()
+    ]));
+
+    let request = HttpRequest { path: "/users/100".to_string(), .. HttpRequest::default() };
+    let response = HttpResponse { status: 404, .. HttpResponse::default() };
+    expect!(messages(&validator().validate(&request, &response))).to(be_equal_to(vec![
+      "$ -> OpenAPI: Response status 404 is not defined for GET /users/{id} in the OpenAPI document".to_string()
+    ]));
+  }
+
+  #[test]
+  fn convert_nullable_adds_null_to_the_schema_type() {
+    expect!(convert_nullable(json!({ "type": "string", "nullable": true })))
+      .to(be_equal_to(json!({ "type": ["string", "null"] })));
+    expect!(convert_nullable(json!({ "type": "string", "enum": ["a", "b"], "nullable": true })))
+      .to(be_equal_to(json!({ "type": ["string", "null"], "enum": ["a", "b", null] })));
+    expect!(convert_nullable(json!({ "allOf": [{ "$ref": "#/components/schemas/User" }], "nullable": true })))
+      .to(be_equal_to(json!({ "anyOf": [{ "allOf": [{ "$ref": "#/components/schemas/User" }] }, { "type": "null" }] })));
+    expect!(convert_nullable(json!({ "type": "string", "nullable": false })))
+      .to(be_equal_to(json!({ "type": "string", "nullable": false })));
+    expect!(convert_nullable(json!({ "properties": { "nullable": { "type": "boolean" } } })))
+      .to(be_equal_to(json!({ "properties": { "nullable": { "type": "boolean" } } })));
+  }
+
+  #[test]
+  fn validate_returns_a_mismatch_if_the_schema_can_not_be_compiled() {
+    let validator = OpenApiResponseValidator::new(json!({
+      "paths": {
+        "/users": {
+          "get": {
+            "responses": {
+              "200": {
+                "content": {
+                  "application/json": {
+                    "schema": { "type": 100 }
+                  }
+                }
+              }
+            }
+          }
+        }
+      }
+    }));
+    let request = HttpRequest { path: "/users".to_string(), .. HttpRequest::default() };
+    let response = HttpResponse {
+      headers: Some(hashmap!{ "Content-Type".to_string() => vec!["application/json".to_string()] }),
+      body: OptionalBody::Present("{}".into(), Some(JSON.clone()), None),
+      .. HttpResponse::default()
+    };
+    let mismatches = messages(&validator.validate(&request, &response));
+    expect!(mismatches.len()).to(be_equal_to(1));
+    expect!(mismatches[0].starts_with("$ -> OpenAPI: Failed to compile the schema '/paths/~1users/get/responses/200/content/application~1json/schema'"))
+      .to(be_true());
+    expect!(validator.schemas.lock().unwrap().len()).to(be_equal_to(1));
+  }
+}
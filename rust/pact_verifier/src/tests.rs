@@ -2,18 +2,26 @@ use std::collections::HashMap;
 use std::env;
 use std::panic::{catch_unwind, RefUnwindSafe};
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
 use anyhow::anyhow;
 use async_trait::async_trait;
 use expectest::prelude::*;
 use maplit::*;
+use pact_models::bodies::OptionalBody;
 use pact_models::Consumer;
+use pact_models::generators::{Generator, GeneratorCategory};
+use pact_models::http_utils::HttpAuth;
+use pact_models::interaction::Interaction;
 use pact_models::pact::Pact;
 use pact_models::provider_states::*;
 use pact_models::sync_interaction::RequestResponseInteraction;
+use pact_models::path_exp::DocPath;
 use pact_models::sync_pact::RequestResponsePact;
+use pact_models::v4::http_parts::HttpResponse;
 use reqwest::Client;
 use serde_json::{json, Value};
 
@@ -23,10 +31,37 @@ use pact_consumer::prelude::*;
 use crate::{NullRequestFilterExecutor, PactSource, ProviderInfo, ProviderStateExecutor, ProviderTransport, publish_result, PublishOptions, VerificationOptions};
 use crate::callback_executors::HttpRequestProviderStateExecutor;
 use crate::pact_broker::Link;
+use crate::pact_sources::{CustomPactSource, register_url_source, unregister_url_source};
 use crate::verification_result::VerificationInteractionResult;
 use crate::VERIFIER_VERSION;
 
-use super::{execute_state_change, filter_consumers, filter_interaction, FilterInfo};
+use super::{apply_provider_state_values, execute_state_change, filter_consumers, filter_interaction, FilterInfo, provider_state_group_key, StateParameterFilter};
+
+#[test]
+fn provider_state_group_key_groups_interactions_with_the_same_provider_states() {
+  let no_states = RequestResponseInteraction::default();
+  let state_a = RequestResponseInteraction {
+    provider_states: vec![ ProviderState::default("state A") ],
+    .. RequestResponseInteraction::default()
+  };
+  let state_a_with_params = RequestResponseInteraction {
+    provider_states: vec![ ProviderState { name: "state A".to_string(), params: hashmap!{ "id".to_string() => json!(1) } } ],
+    .. RequestResponseInteraction::default()
+  };
+  let states_a_and_b = RequestResponseInteraction {
+    provider_states: vec![ ProviderState::default("state A"), ProviderState::default("state B") ],
+    .. RequestResponseInteraction::default()
+  };
+  let states_b_and_a = RequestResponseInteraction {
+    provider_states: vec![ ProviderState::default("state B"), ProviderState::default("state A") ],
+    .. RequestResponseInteraction::default()
+  };
+
+  expect!(provider_state_group_key(0, &no_states)).to_not(be_equal_to(provider_state_group_key(1, &no_states)));
+  expect!(provider_state_group_key(0, &state_a)).to(be_equal_to(provider_state_group_key(1, &state_a)));
+  expect!(provider_state_group_key(0, &state_a)).to_not(be_equal_to(provider_state_group_key(1, &state_a_with_params)));
+  expect!(provider_state_group_key(0, &states_a_and_b)).to(be_equal_to(provider_state_group_key(1, &states_b_and_a)));
+}
 
 #[test]
 fn if_no_interaction_filter_is_defined_returns_true() {
@@ -106,6 +141,63 @@ fn if_the_state_filter_and_interaction_filter_is_defined_is_false_if_both_do_not
   expect!(filter_interaction(&interaction, &FilterInfo::DescriptionAndState(".*ddy".to_string(), "bob.*".to_string()))).to(be_false());
 }
 
+#[test]
+fn state_parameter_filter_from_str() {
+  expect!(StateParameterFilter::from_str("tenant=acme")).to(be_ok().value(
+    StateParameterFilter::new("tenant", Some("acme")).unwrap()));
+  expect!(StateParameterFilter::from_str("tenant")).to(be_ok().value(
+    StateParameterFilter::new("tenant", None).unwrap()));
+  expect!(StateParameterFilter::from_str("id=")).to(be_ok().value(
+    StateParameterFilter::new("id", Some("")).unwrap()));
+  let filter = StateParameterFilter::from_str(" tenant =acme|other").unwrap();
+  expect!(filter.name()).to(be_equal_to("tenant"));
+  expect!(filter.value()).to(be_some().value("acme|other"));
+  expect!(StateParameterFilter::from_str("=acme")).to(be_err());
+  expect!(StateParameterFilter::from_str("tenant=(acme")).to(be_err());
+}
+
+#[test]
+fn if_state_parameter_filters_are_defined_must_match_the_parameters_of_a_provider_state() {
+  let interaction = RequestResponseInteraction {
+    provider_states: vec![
+      ProviderState { name: "user exists".to_string(), params: hashmap!{ "tenant".to_string() => json!("acme"), "id".to_string() => json!(100) } },
+      ProviderState { name: "order exists".to_string(), params: hashmap!{ "tenant".to_string() => json!("acme-corp") } }
+    ],
+    .. RequestResponseInteraction::default()
+  };
+  let filter = |name: Option<&str>, parameters: &[&str]| {
+    let filter = name.map(|name| FilterInfo::State(name.to_string())).unwrap_or(FilterInfo::None);
+    filter.with_state_parameters(parameters.iter().map(|p| StateParameterFilter::from_str(p).unwrap()).collect())
+  };
+
+  expect!(filter_interaction(&interaction, &filter(None, &["tenant=acme"]))).to(be_true());
+  expect!(filter_interaction(&interaction, &filter(None, &["tenant=acme.*"]))).to(be_true());
+  expect!(filter_interaction(&interaction, &filter(None, &["tenant=other"]))).to(be_false());
+  expect!(filter_interaction(&interaction, &filter(None, &["id=100"]))).to(be_true());
+  expect!(filter_interaction(&interaction, &filter(None, &["id"]))).to(be_true());
+  expect!(filter_interaction(&interaction, &filter(None, &["name"]))).to(be_false());
+  expect!(filter_interaction(&interaction, &filter(None, &["tenant=acme-corp", "id=100"]))).to(be_false());
+  expect!(filter_interaction(&interaction, &filter(Some("order.*"), &["tenant=acme-corp"]))).to(be_true());
+  expect!(filter_interaction(&interaction, &filter(Some("user.*"), &["tenant=acme-corp"]))).to(be_false());
+
+  let interaction = RequestResponseInteraction { provider_states: vec![], .. RequestResponseInteraction::default() };
+  expect!(filter_interaction(&interaction, &filter(None, &["tenant=acme"]))).to(be_false());
+}
+
+#[test]
+fn state_parameter_filters_can_be_combined_with_a_description_filter() {
+  let interaction = RequestResponseInteraction {
+    description: "freddy".to_string(),
+    provider_states: vec![
+      ProviderState { name: "user exists".to_string(), params: hashmap!{ "tenant".to_string() => json!("acme") } }
+    ],
+    .. RequestResponseInteraction::default()
+  };
+  let parameters = vec![ StateParameterFilter::from_str("tenant=acme").unwrap() ];
+  expect!(filter_interaction(&interaction, &FilterInfo::Description(".*ddy".to_string()).with_state_parameters(parameters.clone()))).to(be_true());
+  expect!(filter_interaction(&interaction, &FilterInfo::Description("bob".to_string()).with_state_parameters(parameters))).to(be_false());
+}
+
 #[test]
 fn if_no_consumer_filter_is_defined_returns_true() {
   let consumers = vec![];
@@ -322,9 +414,11 @@ async fn publish_successful_result_to_broker() {
       interaction_key: None,
       description: "".to_string(),
       interaction_description: "".to_string(),
+      consumer: "".to_string(),
       result: Ok(()),
       pending: false,
       duration: Default::default(),
+      warnings: vec![]
     }], &source, &options, None
   ).await;
 
@@ -345,9 +439,11 @@ async fn publish_successful_result_to_broker() {
       interaction_key: None,
       description: "".to_string(),
       interaction_description: "".to_string(),
+      consumer: "".to_string(),
       result: Ok(()),
       pending: false,
       duration: Default::default(),
+      warnings: vec![]
     }], &source, &options, None
   ).await;
 }
@@ -603,6 +699,79 @@ impl ProviderStateExecutor for DummyProviderStateExecutor {
   }
 }
 
+#[derive(Debug, Default)]
+struct ConcurrentStatesExecutor {
+  active: AtomicUsize,
+  max_active: AtomicUsize
+}
+
+#[async_trait]
+impl ProviderStateExecutor for ConcurrentStatesExecutor {
+  async fn call(
+    self: Arc<Self>,
+    _interaction_id: Option<String>,
+    _provider_state: &ProviderState,
+    setup: bool,
+    _client: Option<&Client>
+  ) -> anyhow::Result<HashMap<String, Value>> {
+    if setup {
+      let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+      self.max_active.fetch_max(active, Ordering::SeqCst);
+      tokio::time::sleep(Duration::from_millis(20)).await;
+    } else {
+      self.active.fetch_sub(1, Ordering::SeqCst);
+    }
+    Ok(hashmap!{})
+  }
+
+  fn teardown(self: &Self) -> bool {
+    true
+  }
+}
+
+#[test_log::test(tokio::test)]
+async fn verify_interactions_in_parallel_verifies_interactions_with_provider_states_one_at_a_time() {
+  let interaction = |description: &str, state: Option<&str>| RequestResponseInteraction {
+    description: description.to_string(),
+    provider_states: state.map(|state| vec![ProviderState::default(state)]).unwrap_or_default(),
+    .. RequestResponseInteraction::default()
+  };
+  let pact: Box<dyn Pact + Send + Sync + RefUnwindSafe> = Box::new(RequestResponsePact {
+    interactions: vec![
+      interaction("a", Some("user 1 exists")),
+      interaction("b", Some("no users exist")),
+      interaction("c", None),
+      interaction("d", Some("user 2 exists")),
+      interaction("e", None),
+      interaction("f", Some("user 1 exists"))
+    ],
+    .. RequestResponsePact::default()
+  });
+  let provider = ProviderInfo {
+    name: "parallel_provider".to_string(),
+    host: "127.0.0.1".to_string(),
+    transports: vec![ ProviderTransport {
+      transport: "HTTP".to_string(),
+      port: Some(1),
+      path: None,
+      scheme: Some("http".to_string())
+    } ],
+    .. ProviderInfo::default()
+  };
+  let options = VerificationOptions::<NullRequestFilterExecutor> {
+    parallelism: 4,
+    .. VerificationOptions::default()
+  };
+  let executor = Arc::new(ConcurrentStatesExecutor::default());
+
+  let results = super::verify_interactions_in_parallel(&provider, &|_: &dyn Interaction| true, &pact, &options, &executor).await;
+
+  expect!(results.iter().map(|(i, _, _)| i.description()).collect::<Vec<_>>())
+    .to(be_equal_to(vec!["a", "b", "c", "d", "e", "f"]));
+  expect!(executor.max_active.load(Ordering::SeqCst)).to(be_equal_to(1));
+  expect!(executor.active.load(Ordering::SeqCst)).to(be_equal_to(0));
+}
+
 #[test_log::test(tokio::test)]
 async fn when_no_pacts_is_error_is_false_should_not_generate_error() {
   let server = PactBuilderAsync::new("RustPactVerifier", "PactBrokerNoPacts")
@@ -1042,3 +1211,269 @@ async fn fetch_pact_from_dir_filters_by_provider_name() {
   let (pact, _, _, _) = first_result.unwrap();
   expect!(pact.provider().name).to(be_equal_to(provider.name));
 }
+
+#[test_log::test(tokio::test)]
+async fn fetch_pact_from_dir_with_filters_only_includes_the_matching_files() {
+  let provider = ProviderInfo {
+    name: "test_provider".to_string(),
+    .. ProviderInfo::default()
+  };
+  let pacts_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("tests/pacts");
+  let dir = pacts_path.to_string_lossy().to_string();
+
+  let source = PactSource::dir_with_filters(&dir, &["*-1.json".to_string()], &[]).unwrap();
+  let result = super::fetch_pact(source, &provider).await;
+  expect!(result.len()).to(be_equal_to(1));
+
+  let source = PactSource::dir_with_filters(&dir, &["*.json".to_string()], &["pact-1.json".to_string()]).unwrap();
+  let result = super::fetch_pact(source, &provider).await;
+  expect!(result.len()).to(be_equal_to(0));
+
+  expect!(PactSource::dir_with_filters(&dir, &["pact-[1.json".to_string()], &[])).to(be_err());
+}
+
+#[test_log::test(tokio::test)]
+async fn fetch_pact_from_recursive_dir_includes_the_files_in_sub_directories() {
+  let provider = ProviderInfo {
+    name: "test_provider".to_string(),
+    .. ProviderInfo::default()
+  };
+  let pacts_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("tests/nested-pacts");
+  let dir = pacts_path.to_string_lossy().to_string();
+
+  let result = super::fetch_pact(PactSource::Dir(dir.clone()), &provider).await;
+  expect!(result.len()).to(be_equal_to(0));
+
+  let source = PactSource::recursive_dir(&dir, &[], &[]).unwrap();
+  let result = super::fetch_pact(source, &provider).await;
+  expect!(result.len()).to(be_equal_to(3));
+
+  let source = PactSource::recursive_dir(&dir, &["**/*-web.json".to_string()], &[]).unwrap();
+  let result = super::fetch_pact(source, &provider).await;
+  expect!(result.len()).to(be_equal_to(2));
+
+  let source = PactSource::recursive_dir(&dir, &["**/*.json".to_string()], &["team-b/**".to_string()]).unwrap();
+  let result = super::fetch_pact(source, &provider).await;
+  expect!(result.len()).to(be_equal_to(1));
+}
+
+#[derive(Debug)]
+struct TestPactSource;
+
+#[async_trait]
+impl CustomPactSource for TestPactSource {
+  fn description(&self) -> String {
+    "test-source".to_string()
+  }
+
+  async fn load_pacts(&self, _provider: &ProviderInfo) -> anyhow::Result<Vec<anyhow::Result<(String, Value)>>> {
+    Ok(vec![
+      Ok(("test-source/pact.json".to_string(), json!({
+        "consumer": { "name": "test_consumer" },
+        "provider": { "name": "test_provider" },
+        "interactions": [],
+        "metadata": { "pactSpecification": { "version": "4.0" } }
+      }))),
+      Err(anyhow!("Access denied"))
+    ])
+  }
+}
+
+#[test_log::test(tokio::test)]
+async fn fetch_pact_from_custom_source() {
+  let provider = ProviderInfo {
+    name: "test_provider".to_string(),
+    .. ProviderInfo::default()
+  };
+  let source = PactSource::Custom(Arc::new(TestPactSource));
+  let result = super::fetch_pact(source, &provider).await;
+
+  expect!(result.len()).to(be_equal_to(2));
+  let (pact, _, source, _) = result[0].as_ref().unwrap();
+  expect!(pact.consumer().name).to(be_equal_to("test_consumer".to_string()));
+  expect!(source.to_string()).to(be_equal_to("Custom(test-source)"));
+  expect!(result[1].as_ref().err().map(|err| err.to_string()))
+    .to(be_some().value("Failed to load pact from 'test-source' - Access denied".to_string()));
+}
+
+#[test_log::test(tokio::test)]
+async fn fetch_pact_from_url_uses_the_source_registered_for_the_url_scheme() {
+  let provider = ProviderInfo {
+    name: "test_provider".to_string(),
+    .. ProviderInfo::default()
+  };
+  register_url_source("test-scheme", Arc::new(|_: &url::Url, _: &Option<HttpAuth>| {
+    Ok(Arc::new(TestPactSource) as Arc<dyn CustomPactSource>)
+  }));
+  let result = super::fetch_pact(PactSource::URL("test-scheme://pacts/pact.json".to_string(), None), &provider).await;
+  unregister_url_source("test-scheme");
+
+  expect!(result.len()).to(be_equal_to(2));
+  let (pact, _, source, _) = result[0].as_ref().unwrap();
+  expect!(pact.consumer().name).to(be_equal_to("test_consumer".to_string()));
+  expect!(source.to_string()).to(be_equal_to("URL(test-scheme://pacts/pact.json)"));
+}
+
+#[test_log::test(tokio::test)]
+async fn fetch_pacts_removes_duplicate_pacts_from_local_sources() {
+  let provider = ProviderInfo {
+    name: "test_provider".to_string(),
+    .. ProviderInfo::default()
+  };
+  let pacts_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("tests/nested-pacts");
+  let dir = pacts_path.to_string_lossy().to_string();
+  let file = pacts_path.join("team-a/test_consumer-web.json").to_string_lossy().to_string();
+
+  let result = super::fetch_pacts(vec![
+    PactSource::recursive_dir(&dir, &[], &[]).unwrap(),
+    PactSource::File(file)
+  ], vec![], &provider).await;
+  let consumers = result.iter()
+    .map(|result| result.as_ref().unwrap().0.consumer().name)
+    .collect::<Vec<_>>();
+  expect!(consumers).to(be_equal_to(vec!["test_consumer".to_string(), "mobile_consumer".to_string()]));
+}
+
+#[test_log::test(tokio::test)]
+async fn provider_state_catalogue_returns_the_provider_states_from_the_pacts() {
+  let provider = ProviderInfo {
+    name: "test_provider".to_string(),
+    .. ProviderInfo::default()
+  };
+  let pact = json!({
+    "consumer": { "name": "test_consumer" },
+    "provider": { "name": "test_provider" },
+    "interactions": [
+      {
+        "type": "Synchronous/HTTP",
+        "description": "get user",
+        "providerStates": [ { "name": "user exists", "params": { "id": 100 } } ],
+        "request": { "method": "GET", "path": "/users/100" },
+        "response": { "status": 200 }
+      }
+    ],
+    "metadata": { "pactSpecification": { "version": "4.0" } }
+  });
+
+  let result = super::provider_state_catalogue_async(&provider,
+    vec![PactSource::String(pact.to_string())], &FilterInfo::None, vec![]).await;
+  expect!(result.unwrap().to_json()).to(be_equal_to(json!({
+    "provider": "test_provider",
+    "providerStates": [
+      {
+        "name": "user exists",
+        "parameters": { "id": [100] },
+        "interactions": [ { "consumer": "test_consumer", "description": "get user" } ]
+      }
+    ]
+  })));
+}
+
+#[test_log::test(tokio::test)]
+async fn list_interactions_returns_the_filtered_interactions_without_verifying_them() {
+  let provider = ProviderInfo {
+    name: "test_provider".to_string(),
+    .. ProviderInfo::default()
+  };
+  let pacts_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("tests/pacts");
+  let result = super::list_interactions_async(&provider,
+    vec![PactSource::Dir(pacts_path.to_string_lossy().to_string())], &FilterInfo::None, vec![]).await;
+  expect!(result.len()).to(be_equal_to(1));
+  let pact = result.first().unwrap().as_ref().unwrap();
+  expect!(pact.consumer.as_str()).to(be_equal_to("test_consumer"));
+  expect!(pact.pending).to(be_false());
+  expect!(pact.interactions.iter().map(|i| i.description.clone()).collect::<Vec<_>>()).to(be_equal_to(vec![
+    "first pact interaction".to_string()
+  ]));
+
+  let result = super::list_interactions_async(&provider,
+    vec![PactSource::Dir(pacts_path.to_string_lossy().to_string())],
+    &FilterInfo::Description("other".to_string()), vec![]).await;
+  expect!(result.first().unwrap().as_ref().unwrap().interactions.is_empty()).to(be_true());
+}
+
+#[tokio::test]
+async fn apply_provider_state_values_only_applies_provider_state_generators_for_the_status_and_headers() {
+  let mut response = HttpResponse {
+    status: 200,
+    headers: Some(hashmap!{ "Location".to_string() => vec!["/orders/1".to_string()] }),
+    body: OptionalBody::from(&json!({ "id": 1 })),
+    .. HttpResponse::default()
+  };
+  response.generators.add_generator(&GeneratorCategory::STATUS,
+    Generator::ProviderStateGenerator("status".to_string(), None));
+  response.generators.add_generator_with_subcategory(&GeneratorCategory::HEADER,
+    DocPath::new_unwrap("location"),
+    Generator::ProviderStateGenerator("/orders/${id}".to_string(), None));
+  response.generators.add_generator_with_subcategory(&GeneratorCategory::BODY,
+    DocPath::new_unwrap("$.id"),
+    Generator::ProviderStateGenerator("id".to_string(), None));
+  response.generators.add_generator_with_subcategory(&GeneratorCategory::HEADER,
+    DocPath::new_unwrap("X-Request-ID"),
+    Generator::Uuid(None));
+
+  let context = hashmap!{ "status" => json!(201), "id" => json!(1234) };
+  let expected = apply_provider_state_values(&response, &context).await;
+  expect!(expected.status).to(be_equal_to(201));
+  expect!(expected.headers).to(be_some().value(hashmap!{ "Location".to_string() => vec!["/orders/1234".to_string()] }));
+  expect!(expected.body).to(be_equal_to(response.body.clone()));
+  expect!(expected.generators).to(be_equal_to(response.generators));
+}
+
+fn body_mismatch_output() -> Vec<String> {
+  let interaction = |body: &str| RequestResponseInteraction {
+    response: pact_models::response::Response {
+      body: OptionalBody::Present(body.to_string().into(), Some(pact_models::content_types::JSON.clone()), None),
+      .. pact_models::response::Response::default()
+    },
+    .. RequestResponseInteraction::default()
+  };
+  let expected = interaction(r#"{"a": 1, "b": 2}"#);
+  let actual = interaction(r#"{"a": 2, "b": 3}"#);
+  let body_mismatch = |path: &str| pact_matching::Mismatch::BodyMismatch {
+    path: path.to_string(),
+    expected: None,
+    actual: None,
+    mismatch: "Expected a different value".to_string(),
+    rule: None
+  };
+  let mismatches = vec![
+    body_mismatch("$.a"),
+    pact_matching::Mismatch::HeaderMismatch {
+      key: "X-Test".to_string(),
+      expected: "1".to_string(),
+      actual: "2".to_string(),
+      mismatch: "Expected a different header value".to_string(),
+      rule: None
+    },
+    body_mismatch("$.b")
+  ];
+
+  let mut output = vec![];
+  crate::interaction_mismatch_output(&mut output, false, 0, &"test".to_string(), &mismatches,
+    &expected, &actual);
+  output
+}
+
+#[test]
+#[cfg(feature = "unified-diff")]
+fn interaction_mismatch_output_only_displays_the_unified_body_diff_once() {
+  let output = body_mismatch_output();
+  expect!(output.len()).to(be_equal_to(8));
+  expect!(output.iter().filter(|line| line.contains("\"b\": ")).count()).to(be_equal_to(1));
+  expect!(output.last().unwrap().contains("\"a\": ")).to(be_true());
+}
+
+#[test]
+#[cfg(not(feature = "unified-diff"))]
+fn interaction_mismatch_output_displays_a_body_diff_for_each_group_of_body_mismatches() {
+  let output = body_mismatch_output();
+  // Each of the three groups has a summary and description, and the two body mismatch groups
+  // are followed by a diff of the body at the mismatch path
+  expect!(output.len()).to(be_equal_to(9));
+  expect!(output.iter().any(|line| line.contains("\"a\": "))).to(be_false());
+}
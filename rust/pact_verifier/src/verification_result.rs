@@ -8,6 +8,9 @@ use serde_json::{json, Value};
 
 use pact_matching::Mismatch;
 
+use crate::digests::PactDigest;
+use crate::failures::FailureSummary;
+
 /// Result of verifying a Pact interaction
 #[derive(Clone, Debug)]
 pub struct VerificationInteractionResult {
@@ -19,12 +22,16 @@ pub struct VerificationInteractionResult {
   pub description: String,
   /// Interaction description from the Pact file
   pub interaction_description: String,
+  /// Name of the consumer of the Pact the interaction is from
+  pub consumer: String,
   /// Result of the verification
   pub result: Result<(), crate::MismatchResult>,
   /// If the Pact or interaction is pending
   pub pending: bool,
   /// Duration that the verification took
-  pub duration: Duration
+  pub duration: Duration,
+  /// Warnings raised while verifying the interaction that did not fail the verification
+  pub warnings: Vec<String>
 }
 
 /// Result of verifying a Pact
@@ -32,7 +39,9 @@ pub struct VerificationResult {
   /// Results that occurred
   pub results: Vec<VerificationInteractionResult>,
   /// Output from the verification
-  pub output: Vec<String>
+  pub output: Vec<String>,
+  /// Interactions that were skipped, with the reason they were skipped
+  pub skipped: Vec<(String, String)>
 }
 
 /// Main struct for returning the total verification execution result
@@ -46,10 +55,20 @@ pub struct VerificationExecutionResult {
   pub output: Vec<String>,
   /// Errors that occurred, but are marked as pending
   pub pending_errors: Vec<(String, VerificationMismatchResult)>,
+  /// Errors that occurred verifying work-in-progress (WIP) pacts. These do not fail the verification
+  pub wip_errors: Vec<(String, VerificationMismatchResult)>,
   /// Errors that occurred that are not considered pending
   pub errors: Vec<(String, VerificationMismatchResult)>,
+  /// Warnings that occurred, but did not fail the verification
+  pub warnings: Vec<(String, String)>,
+  /// Interactions that were not verified, with the reason they were skipped
+  pub skipped: Vec<(String, String)>,
   /// Result for each interaction that was verified
-  pub interaction_results: Vec<VerificationInteractionResult>
+  pub interaction_results: Vec<VerificationInteractionResult>,
+  /// SHA-256 digests calculated for the pact sources
+  pub pact_digests: Vec<PactDigest>,
+  /// Categories of the failures that failed the verification
+  pub failure_summary: FailureSummary
 }
 
 impl VerificationExecutionResult {
@@ -60,8 +79,13 @@ impl VerificationExecutionResult {
       notices: vec![],
       output: vec![],
       pending_errors: vec![],
+      wip_errors: vec![],
       errors: vec![],
+      warnings: vec![],
+      skipped: vec![],
       interaction_results: vec![],
+      pact_digests: vec![],
+      failure_summary: FailureSummary::default()
     }
   }
 }
@@ -81,12 +105,38 @@ impl Into<Value> for &VerificationExecutionResult {
           "mismatch": err
         })
       }).collect_vec(),
+      "wipErrors": self.wip_errors.iter().map(|(e, r)| {
+        let err: Value = r.into();
+        json!({
+          "interaction": e,
+          "mismatch": err
+        })
+      }).collect_vec(),
       "errors": self.errors.iter().map(|(e, r)| {
         let err: Value = r.into();
         json!({
           "interaction": e,
           "mismatch": err
         })
+      }).collect_vec(),
+      "warnings": self.warnings.iter().map(|(e, w)| {
+        json!({
+          "interaction": e,
+          "warning": w
+        })
+      }).collect_vec(),
+      "skipped": self.skipped.iter().map(|(i, r)| {
+        json!({
+          "interaction": i,
+          "reason": r
+        })
+      }).collect_vec(),
+      "pactDigests": self.pact_digests.iter().map(|d| {
+        json!({
+          "source": d.source,
+          "sha256": d.sha256,
+          "verified": d.verified
+        })
       }).collect_vec()
     })
   }
@@ -173,6 +223,8 @@ mod tests {
   use pact_matching::Mismatch;
 
   use crate::VerificationExecutionResult;
+  use crate::digests::PactDigest;
+  use crate::failures::FailureSummary;
   use crate::verification_result::VerificationMismatchResult;
 
   #[test]
@@ -183,7 +235,8 @@ mod tests {
           path: "1.2.3.4".to_string(),
           expected: Some("100".into()),
           actual: Some("200".into()),
-          mismatch: "Expected 100 but got 200".to_string()
+          mismatch: "Expected 100 but got 200".to_string(),
+          rule: None
         }
       ],
       interaction_id: None
@@ -239,6 +292,15 @@ mod tests {
           }
         )
       ],
+      wip_errors: vec![
+        (
+          "interaction 4".to_string(),
+          VerificationMismatchResult::Error {
+            error: "Boom!".to_string(),
+            interaction_id: None
+          }
+        )
+      ],
       errors: vec![
         (
           "interaction 2".to_string(),
@@ -248,7 +310,21 @@ mod tests {
           }
         )
       ],
+      warnings: vec![
+        ("interaction 3".to_string(), "Provider clock skew: The response from the provider has no Date header".to_string())
+      ],
+      skipped: vec![
+        ("interaction 5".to_string(), "Provider does not have the required capabilities: gift-cards".to_string())
+      ],
       interaction_results: vec![],
+      pact_digests: vec![
+        PactDigest {
+          source: "consumer-provider.json".to_string(),
+          sha256: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855".to_string(),
+          verified: true
+        }
+      ],
+      failure_summary: FailureSummary::default()
     };
     let json: Value = result.into();
     expect!(json).to(be_equal_to(json!({
@@ -273,6 +349,13 @@ mod tests {
         "line 3".to_string(),
         "line 4".to_string()
       ],
+      "pactDigests": [
+        {
+          "source": "consumer-provider.json",
+          "sha256": "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+          "verified": true
+        }
+      ],
       "pendingErrors": [
         {
           "interaction": "interaction 1".to_string(),
@@ -283,7 +366,71 @@ mod tests {
           }
         }
       ],
-      "result": false
+      "result": false,
+      "skipped": [
+        {
+          "interaction": "interaction 5",
+          "reason": "Provider does not have the required capabilities: gift-cards"
+        }
+      ],
+      "wipErrors": [
+        {
+          "interaction": "interaction 4".to_string(),
+          "mismatch": {
+            "interactionId": "".to_string(),
+            "message": "Boom!".to_string(),
+            "type": "error".to_string()
+          }
+        }
+      ],
+      "warnings": [
+        {
+          "interaction": "interaction 3".to_string(),
+          "warning": "Provider clock skew: The response from the provider has no Date header".to_string()
+        }
+      ]
     })));
   }
+
+  #[test]
+  fn verification_execution_result_json_conforms_to_the_schema() {
+    let schema: Value = serde_json::from_str(include_str!("verifier-results.schema.json")).unwrap();
+    let mismatch = || VerificationMismatchResult::Error {
+      error: "Boom!".to_string(),
+      interaction_id: Some("1234".to_string())
+    };
+    let result = VerificationExecutionResult {
+      result: false,
+      notices: vec![],
+      output: vec!["line 1".to_string()],
+      pending_errors: vec![("interaction 1".to_string(), mismatch())],
+      wip_errors: vec![("interaction 2".to_string(), mismatch())],
+      errors: vec![("interaction 3".to_string(), mismatch())],
+      warnings: vec![("interaction 4".to_string(), "Warning".to_string())],
+      skipped: vec![("interaction 5".to_string(), "Skipped".to_string())],
+      interaction_results: vec![],
+      pact_digests: vec![
+        PactDigest {
+          source: "consumer-provider.json".to_string(),
+          sha256: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855".to_string(),
+          verified: true
+        }
+      ],
+      failure_summary: FailureSummary::default()
+    };
+    let json: Value = result.into();
+
+    let properties = schema["properties"].as_object().unwrap();
+    for (key, value) in json.as_object().unwrap() {
+      let property = properties.get(key);
+      assert!(property.is_some(), "'{}' is not defined in the schema", key);
+      if let (Some(items), Some(item_properties)) = (value.as_array(), property.unwrap().pointer("/items/properties")) {
+        for item in items {
+          for item_key in item.as_object().unwrap().keys() {
+            assert!(item_properties.get(item_key).is_some(), "'{}.{}' is not defined in the schema", key, item_key);
+          }
+        }
+      }
+    }
+  }
 }
@@ -0,0 +1 @@
+INSERT INTO table_that_does_not_exist (id) VALUES (1);
@@ -0,0 +1 @@
+INSERT INTO users (id, name) VALUES (${id}, ${name});
@@ -5,7 +5,8 @@ use std::fs::File;
 use std::io::Read;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use base64::Engine;
@@ -13,8 +14,13 @@ use base64::engine::general_purpose::STANDARD;
 use chrono::Utc;
 use expectest::prelude::*;
 use maplit::*;
-use pact_models::pact::read_pact;
+use pact_models::{Consumer, Provider};
+use pact_models::pact::{Pact, read_pact};
 use pact_models::provider_states::ProviderState;
+use pact_models::v4::http_parts::HttpRequest;
+use pact_models::v4::interaction::V4Interaction;
+use pact_models::v4::pact::V4Pact;
+use pact_models::v4::synch_http::SynchronousHttp;
 use reqwest::Client;
 use serde_json::{json, Value};
 
@@ -22,6 +28,7 @@ use pact_consumer::*;
 use pact_consumer::prelude::*;
 use pact_verifier::{
   FilterInfo,
+  MismatchResult,
   NullRequestFilterExecutor,
   PactSource,
   ProviderInfo,
@@ -32,6 +39,7 @@ use pact_verifier::{
   verify_provider_async
 };
 use pact_verifier::callback_executors::ProviderStateExecutor;
+use pact_verifier::capabilities::ProviderCapabilities;
 
 /// Get the path to one of our sample *.json files.
 fn fixture_path(path: &str) -> PathBuf {
@@ -119,7 +127,8 @@ async fn verify_pact_with_match_values_matcher() {
     &options,
     &provider_states,
     false,
-    Duration::default()
+    Duration::default(),
+    None
   ).await;
 
   expect!(result.unwrap().results.get(0).unwrap().result.as_ref()).to(be_ok());
@@ -174,7 +183,8 @@ async fn verify_pact_with_attributes_with_special_values() {
     &options,
     &provider_states,
     false,
-    Duration::default()
+    Duration::default(),
+    None
   ).await;
 
   expect!(result.unwrap().results.get(0).unwrap().result.as_ref()).to(be_ok());
@@ -200,7 +210,8 @@ async fn verifying_a_pact_with_pending_interactions() {
     &options,
     &provider_states,
     false,
-    Duration::default()
+    Duration::default(),
+    None
   ).await;
 
   expect!(result.as_ref().unwrap().results.get(0).unwrap().result.as_ref()).to(be_err());
@@ -259,7 +270,8 @@ async fn verifying_a_pact_with_min_type_matcher_and_child_arrays() {
     &options,
     &provider_states,
     false,
-    Duration::default()
+    Duration::default(),
+    None
   ).await;
 
   expect!(result.unwrap().results.get(0).unwrap().result.as_ref()).to(be_ok());
@@ -831,7 +843,8 @@ async fn verify_pact_with_matcher_on_last_modified_header() {
       &options,
       &provider_states,
       false,
-      Duration::default()
+      Duration::default(),
+      None
     ).await
   };
 
@@ -993,8 +1006,169 @@ async fn verify_pact_with_body_but_no_content_type() {
     &options,
     &provider_states,
     false,
-    Duration::default()
+    Duration::default(),
+    None
   ).await;
 
   expect!(result.unwrap().results.get(0).unwrap().result.as_ref()).to(be_ok());
 }
+
+#[test_log::test(tokio::test)]
+async fn verify_pact_skips_interactions_that_require_missing_capabilities() {
+  let server = PactBuilder::new_v4("capabilities consumer", "capabilities provider")
+    .interaction("get an order", "", |mut i| {
+      i.test_name("verify_pact_skips_interactions_that_require_missing_capabilities");
+      i.request.path("/orders/1");
+      i.response.ok();
+      i
+    })
+    .start_mock_server(None);
+
+  #[allow(deprecated)]
+  let provider = ProviderInfo {
+    name: "capabilities provider".to_string(),
+    host: "127.0.0.1".to_string(),
+    port: server.url().port(),
+    transports: vec![ ProviderTransport {
+      transport: "HTTP".to_string(),
+      port: server.url().port(),
+      path: None,
+      scheme: Some("http".to_string())
+    } ],
+    .. ProviderInfo::default()
+  };
+
+  let pact = V4Pact {
+    consumer: Consumer { name: "capabilities consumer".to_string() },
+    provider: Provider { name: "capabilities provider".to_string() },
+    interactions: vec![
+      SynchronousHttp {
+        description: "get an order".to_string(),
+        request: HttpRequest { path: "/orders/1".to_string(), .. HttpRequest::default() },
+        comments: hashmap!{ "requiredCapabilities".to_string() => json!("orders") },
+        .. SynchronousHttp::default()
+      }.boxed_v4(),
+      SynchronousHttp {
+        description: "redeem a gift card".to_string(),
+        request: HttpRequest { method: "POST".to_string(), path: "/gift-cards/1".to_string(), .. HttpRequest::default() },
+        comments: hashmap!{ "requiredCapabilities".to_string() => json!(["orders", "gift-cards"]) },
+        .. SynchronousHttp::default()
+      }.boxed_v4()
+    ],
+    .. V4Pact::default()
+  };
+  let capabilities = ProviderCapabilities::from_json(&json!({ "orders": true, "gift-cards": false })).unwrap();
+  let options: VerificationOptions<NullRequestFilterExecutor> = VerificationOptions::default();
+  let provider_states = Arc::new(DummyProviderStateExecutor{});
+
+  let result = verify_pact_internal(
+    &provider,
+    &FilterInfo::None,
+    pact.boxed(),
+    &options,
+    &provider_states,
+    false,
+    Duration::default(),
+    Some(&capabilities)
+  ).await.unwrap();
+
+  expect!(result.results.len()).to(be_equal_to(1));
+  expect!(result.results[0].result.as_ref()).to(be_ok());
+  expect!(result.skipped).to(be_equal_to(vec![(
+    "Verifying a pact between capabilities consumer and capabilities provider - redeem a gift card".to_string(),
+    "Provider does not have the required capabilities: gift-cards".to_string()
+  )]));
+}
+
+#[derive(Debug, Default)]
+struct SlowProviderStateExecutor {
+  calls: AtomicUsize
+}
+
+#[async_trait]
+impl ProviderStateExecutor for SlowProviderStateExecutor {
+  async fn call(
+    self: Arc<Self>,
+    _interaction_id: Option<String>,
+    _provider_state: &ProviderState,
+    _setup: bool,
+    _client: Option<&Client>
+  ) -> anyhow::Result<HashMap<String, Value>> {
+    self.calls.fetch_add(1, Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    Ok(hashmap!{})
+  }
+
+  fn teardown(self: &Self) -> bool {
+    false
+  }
+}
+
+#[test_log::test(tokio::test)]
+async fn verify_pact_fails_interactions_that_time_out() {
+  let provider = ProviderInfo {
+    name: "slow provider".to_string(),
+    .. ProviderInfo::default()
+  };
+  let pact = V4Pact {
+    consumer: Consumer { name: "slow consumer".to_string() },
+    provider: Provider { name: "slow provider".to_string() },
+    interactions: vec![
+      SynchronousHttp {
+        description: "get an order".to_string(),
+        provider_states: vec![ ProviderState::default("an order exists") ],
+        request: HttpRequest { path: "/orders/1".to_string(), .. HttpRequest::default() },
+        .. SynchronousHttp::default()
+      }.boxed_v4()
+    ],
+    .. V4Pact::default()
+  };
+  let options: VerificationOptions<NullRequestFilterExecutor> = VerificationOptions {
+    interaction_timeout: Some(Duration::from_millis(50)),
+    interaction_retries: 2,
+    .. VerificationOptions::default()
+  };
+  let provider_states = Arc::new(SlowProviderStateExecutor::default());
+
+  let result = verify_pact_internal(
+    &provider,
+    &FilterInfo::None,
+    pact.boxed(),
+    &options,
+    &provider_states,
+    false,
+    Duration::default(),
+    None
+  ).await.unwrap();
+
+  expect!(provider_states.calls.load(Ordering::SeqCst)).to(be_equal_to(3));
+  let interaction_result = &result.results[0];
+  match interaction_result.result.as_ref() {
+    Err(MismatchResult::Error(error, _)) => expect!(error.as_str()).to(be_equal_to("Interaction timed out after 50ms")),
+    result => panic!("Expected a timeout error, got {:?}", result)
+  }
+  expect!(interaction_result.warnings.len()).to(be_equal_to(2));
+
+  let options: VerificationOptions<NullRequestFilterExecutor> = VerificationOptions {
+    deadline: Some(Instant::now()),
+    .. VerificationOptions::default()
+  };
+  let provider_states = Arc::new(SlowProviderStateExecutor::default());
+  let result = verify_pact_internal(
+    &provider,
+    &FilterInfo::None,
+    pact.boxed(),
+    &options,
+    &provider_states,
+    false,
+    Duration::default(),
+    None
+  ).await.unwrap();
+
+  expect!(provider_states.calls.load(Ordering::SeqCst)).to(be_equal_to(0));
+  match result.results[0].result.as_ref() {
+    Err(MismatchResult::Error(error, _)) => expect!(error.as_str()).to(
+      be_equal_to("Interaction timed out - the verification deadline was reached before it was verified")),
+    result => panic!("Expected a timeout error, got {:?}", result)
+  }
+}
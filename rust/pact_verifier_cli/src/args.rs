@@ -1,5 +1,8 @@
+use std::str::FromStr;
+
 use clap::{Arg, ArgAction, ArgGroup, Command, command};
 use clap::builder::{FalseyValueParser, NonEmptyStringValueParser, PossibleValuesParser};
+use pact_verifier::StateParameterFilter;
 use regex::Regex;
 
 fn port_value(v: &str) -> Result<u16, String> {
@@ -20,6 +23,11 @@ fn validate_regex(val: &str) -> Result<String, String> {
   }
 }
 
+fn state_param_filter(val: &str) -> Result<StateParameterFilter, String> {
+  StateParameterFilter::from_str(val)
+    .map_err(|err| format!("'{}' is an invalid filter value: {}", val, err))
+}
+
 fn transport_value(v: &str) -> Result<(String, u16), String> {
   let (transport, port) = v.split_once(':')
     .ok_or_else(|| format!("'{}' is not a valid transport, it must be in the form TRANSPORT:PORT", v))?;
@@ -43,6 +51,16 @@ pub(crate) fn setup_app() -> Command {
       .long("version")
       .action(ArgAction::Version)
       .help("Print version information and exit"))
+    .arg(Arg::new("version-check")
+      .long("version-check")
+      .action(ArgAction::SetTrue)
+      .help("Checks the versions of the verifier, Pact Broker and installed plugins for known incompatibilities and exits"))
+    .arg(Arg::new("compare")
+      .long("compare")
+      .num_args(2)
+      .value_names(["OLD", "NEW"])
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("Compares two pact files and displays the added, removed and changed interactions. Exits with a non-zero status if there are any differences"))
 
     .group(ArgGroup::new("logging").multiple(true))
     .next_help_heading("Logging options")
@@ -81,32 +99,67 @@ pub(crate) fn setup_app() -> Command {
       .action(ArgAction::Set)
       .value_parser(NonEmptyStringValueParser::new())
       .help("Generate a JUnit XML report of the verification (requires the junit feature)"))
+    .arg(Arg::new("report-file")
+      .long("report")
+      .env("PACT_VERIFIER_REPORT")
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("Generate a Markdown or HTML report of the verification, suitable for attaching to PR comments"))
+    .arg(Arg::new("report-format")
+      .long("format")
+      .action(ArgAction::Set)
+      .requires("report-file")
+      .value_parser(PossibleValuesParser::new(["markdown", "html"]))
+      .default_value("markdown")
+      .help("Format of the report generated with the --report option"))
+    .arg(Arg::new("summary-file")
+      .long("summary-file")
+      .env("PACT_VERIFIER_SUMMARY_FILE")
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("Write a JSON summary of the verification failures by category, along with the exit code"))
     .arg(Arg::new("no-colour")
       .long("no-colour")
       .action(ArgAction::SetTrue)
       .visible_alias("no-color")
       .help("Disables ANSI escape codes in the output"))
+    .arg(Arg::new("diff-context")
+      .long("diff-context")
+      .action(ArgAction::Set)
+      .value_parser(integer_value)
+      .help("Number of lines of context to display around the changes in body diffs (defaults to 3, requires the unified-diff feature)"))
 
     .group(ArgGroup::new("source").multiple(true))
     .next_help_heading("Loading pacts options")
     .arg(Arg::new("file")
       .short('f')
       .long("file")
-      .required_unless_present_any(&["dir", "url", "broker-url"])
+      .required_unless_present_any(&["dir", "url", "broker-url", "bundle", "version-check", "compare"])
       .action(ArgAction::Append)
       .value_parser(NonEmptyStringValueParser::new())
       .help("Pact file to verify (can be repeated)"))
     .arg(Arg::new("dir")
       .short('d')
       .long("dir")
-      .required_unless_present_any(&["file", "url", "broker-url"])
+      .required_unless_present_any(&["file", "url", "broker-url", "bundle", "version-check", "compare"])
       .action(ArgAction::Append)
       .value_parser(NonEmptyStringValueParser::new())
       .help("Directory of pact files to verify (can be repeated)"))
+    .arg(Arg::new("recursive")
+      .long("recursive")
+      .requires("dir")
+      .action(ArgAction::SetTrue)
+      .help("Also search the sub-directories of the pact directories for pact files"))
+    .arg(Arg::new("glob")
+      .long("glob")
+      .requires("dir")
+      .action(ArgAction::Append)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("Only verify the pact files in the pact directories whose relative paths match the glob (i.e. '**/*-web.json', can be repeated)"))
     .arg(Arg::new("url")
       .short('u')
       .long("url")
-      .required_unless_present_any(&["file", "dir", "broker-url"])
+      .required_unless_present_any(&["file", "dir", "broker-url", "bundle", "version-check", "compare"])
       .action(ArgAction::Append)
       .value_parser(NonEmptyStringValueParser::new())
       .help("URL of pact file to verify (can be repeated)"))
@@ -114,7 +167,7 @@ pub(crate) fn setup_app() -> Command {
       .short('b')
       .long("broker-url")
       .env("PACT_BROKER_BASE_URL")
-      .required_unless_present_any(&["file", "dir", "url"])
+      .required_unless_present_any(&["file", "dir", "url", "bundle", "version-check", "compare"])
       .requires("provider-name")
       .action(ArgAction::Set)
       .value_parser(NonEmptyStringValueParser::new())
@@ -123,39 +176,49 @@ pub(crate) fn setup_app() -> Command {
       .long("webhook-callback-url")
       .env("PACT_WEBHOOK_CALLBACK_URL")
       .requires("broker-url")
-      .conflicts_with_all(&["file", "dir", "url"])
+      .conflicts_with_all(&["file", "dir", "url", "bundle"])
       .action(ArgAction::Set)
       .value_parser(NonEmptyStringValueParser::new())
       .help("URL of a Pact to verify via a webhook callback. Requires the broker-url to be set."))
+    .arg(Arg::new("bundle")
+      .long("bundle")
+      .required_unless_present_any(&["file", "dir", "url", "broker-url", "version-check", "compare"])
+      .action(ArgAction::Append)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("Pact bundle file created with the fetch command to verify offline (can be repeated)"))
     .arg(Arg::new("ignore-no-pacts-error")
       .long("ignore-no-pacts-error")
       .action(ArgAction::SetTrue)
       .help("Do not fail if no pacts are found to verify"))
+    .arg(Arg::new("list-only")
+      .long("list-only")
+      .action(ArgAction::SetTrue)
+      .help("Only fetches the pacts and lists the interactions that would be verified, without making any requests to the provider"))
+    .arg(Arg::new("list-provider-states")
+      .long("list-provider-states")
+      .action(ArgAction::SetTrue)
+      .conflicts_with("list-only")
+      .help("Only fetches the pacts and outputs all the provider states (with their parameters) from the interactions that would be verified as JSON, without making any requests to the provider"))
+    .arg(Arg::new("pact-digest")
+      .long("pact-digest")
+      .action(ArgAction::Append)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("Expected SHA-256 digest of a pact file or URL, in the form SOURCE=DIGEST (the digest can be prefixed with sha256:). Pacts that do not match the digest will fail the verification. Can be repeated."))
+    .arg(Arg::new("max-pact-age")
+      .long("max-pact-age")
+      .env("PACT_MAX_PACT_AGE")
+      .action(ArgAction::Set)
+      .value_parser(integer_value)
+      .help("Checks the pacts were created within the given number of days, using the createdAt value in the pact metadata. Stale pacts are reported as warnings."))
+    .arg(Arg::new("fail-on-stale-pacts")
+      .long("fail-on-stale-pacts")
+      .action(ArgAction::SetTrue)
+      .requires("max-pact-age")
+      .help("Fails the verification if a pact is older than the maximum pact age"))
 
     .group(ArgGroup::new("auth").multiple(true))
     .next_help_heading("Authentication options")
-    .arg(Arg::new("user")
-      .long("user")
-      .env("PACT_BROKER_USERNAME")
-      .action(ArgAction::Set)
-      .value_parser(NonEmptyStringValueParser::new())
-      .conflicts_with("token")
-      .help("Username to use when fetching pacts from URLS"))
-    .arg(Arg::new("password")
-      .long("password")
-      .env("PACT_BROKER_PASSWORD")
-      .action(ArgAction::Set)
-      .value_parser(NonEmptyStringValueParser::new())
-      .conflicts_with("token")
-      .help("Password to use when fetching pacts from URLS"))
-    .arg(Arg::new("token")
-      .short('t')
-      .long("token")
-      .env("PACT_BROKER_TOKEN")
-      .action(ArgAction::Set)
-      .value_parser(NonEmptyStringValueParser::new())
-      .conflicts_with("user")
-      .help("Bearer token to use when fetching pacts from URLS"))
+    .args(auth_args())
 
     .group(ArgGroup::new("provider").multiple(true))
     .next_help_heading("Provider options")
@@ -188,6 +251,12 @@ pub(crate) fn setup_app() -> Command {
       .value_delimiter(' ')
       .help("Allows multiple protocol transports to be configured (http, https, grpc, etc.) with their associated port numbers separated by a colon. For example, use --transports http:8080 grpc:5555 to configure both.")
       .value_parser(transport_value))
+    .arg(Arg::new("builtin-grpc-transport")
+      .long("builtin-grpc-transport")
+      .env("PACT_PROVIDER_BUILTIN_GRPC_TRANSPORT")
+      .action(ArgAction::SetTrue)
+      .value_parser(FalseyValueParser::new())
+      .help("Send unary gRPC requests with the built-in gRPC transport (h2c only), only using the plugin to encode the request and compare the response"))
     .arg(Arg::new("provider-name")
       .short('n')
       .long("provider-name")
@@ -207,6 +276,30 @@ pub(crate) fn setup_app() -> Command {
       .action(ArgAction::Set)
       .value_parser(integer_value)
       .help("Sets the HTTP request timeout in milliseconds for requests to the target API and for state change requests."))
+    .arg(Arg::new("interaction-timeout")
+      .long("interaction-timeout")
+      .env("PACT_PROVIDER_INTERACTION_TIMEOUT")
+      .action(ArgAction::Set)
+      .value_parser(integer_value)
+      .help("Maximum number of seconds to verify each interaction, including the provider state changes. Interactions that take longer will fail with a timeout error"))
+    .arg(Arg::new("interaction-retries")
+      .long("interaction-retries")
+      .env("PACT_PROVIDER_INTERACTION_RETRIES")
+      .action(ArgAction::Set)
+      .value_parser(integer_value)
+      .help("Number of times to retry an interaction that timed out or where the provider could not be reached (defaults to 0)"))
+    .arg(Arg::new("verification-timeout")
+      .long("verification-timeout")
+      .env("PACT_PROVIDER_VERIFICATION_TIMEOUT")
+      .action(ArgAction::Set)
+      .value_parser(integer_value)
+      .help("Maximum number of seconds for the whole verification. Interactions that have not been verified by then will fail with a timeout error"))
+    .arg(Arg::new("generator-seed")
+      .long("generator-seed")
+      .env("PACT_PROVIDER_GENERATOR_SEED")
+      .action(ArgAction::Set)
+      .value_parser(integer_value)
+      .help("Seed for the random generators (i.e. RandomInt, Uuid, RandomString), so the generated request values are the same for each run"))
     .arg(Arg::new("custom-header")
       .long("header")
       .short('H')
@@ -218,6 +311,48 @@ pub(crate) fn setup_app() -> Command {
       .long("disable-ssl-verification")
       .action(ArgAction::SetTrue)
       .help("Disables validation of SSL certificates"))
+    .arg(Arg::new("max-clock-skew")
+      .long("max-clock-skew")
+      .env("PACT_PROVIDER_MAX_CLOCK_SKEW")
+      .action(ArgAction::Set)
+      .value_parser(integer_value)
+      .help("Checks the Date header returned by the provider is within the given number of seconds of the verifier's clock. Problems are reported as warnings."))
+    .arg(Arg::new("fail-on-clock-skew")
+      .long("fail-on-clock-skew")
+      .action(ArgAction::SetTrue)
+      .requires("max-clock-skew")
+      .help("Fails the verification if the Date header returned by the provider is missing, invalid or outside the maximum clock skew"))
+    .arg(Arg::new("parallel")
+      .long("parallel")
+      .action(ArgAction::Set)
+      .value_parser(integer_value)
+      .help("Number of interactions to verify concurrently (defaults to 1). Only interactions without provider states are verified in parallel, interactions with provider states are always verified one at a time"))
+    .arg(Arg::new("message-routing")
+      .long("message-routing")
+      .env("PACT_PROVIDER_MESSAGE_ROUTING")
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("JSON file that routes the requests for message interactions to different provider handler URLs, based on the message metadata"))
+    .arg(Arg::new("wait-for-provider")
+      .long("wait-for-provider")
+      .env("PACT_PROVIDER_WAIT_FOR")
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("Health check URL to poll before starting the verification. The verification will start once it returns a successful response."))
+    .arg(Arg::new("wait-timeout")
+      .long("wait-timeout")
+      .env("PACT_PROVIDER_WAIT_TIMEOUT")
+      .action(ArgAction::Set)
+      .value_parser(integer_value)
+      .requires("wait-for-provider")
+      .default_value("60")
+      .help("Maximum number of seconds to wait for the provider to become ready"))
+    .arg(Arg::new("provider-capabilities-url")
+      .long("provider-capabilities-url")
+      .env("PACT_PROVIDER_CAPABILITIES_URL")
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("URL that returns the capabilities (enabled features) of the provider as JSON. Interactions that require capabilities the provider does not have will be skipped."))
 
     .group(ArgGroup::new("states").multiple(true))
     .next_help_heading("Provider state options")
@@ -240,6 +375,13 @@ pub(crate) fn setup_app() -> Command {
       .action(ArgAction::SetTrue)
       .value_parser(FalseyValueParser::new())
       .help("State change teardown requests are to be made after each interaction"))
+    .arg(Arg::new("state-change-sql-config")
+      .long("state-change-sql-config")
+      .env("PACT_PROVIDER_STATE_CHANGE_SQL_CONFIG")
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .conflicts_with("state-change-url")
+      .help("JSON file that maps provider states to SQL scripts to run against a test database, instead of making state change requests (requires the sql-states feature)"))
 
     .group(ArgGroup::new("filtering").multiple(true))
     .next_help_heading("Filtering interactions")
@@ -262,6 +404,12 @@ pub(crate) fn setup_app() -> Command {
       .env("PACT_PROVIDER_NO_STATE")
       .conflicts_with("filter-state")
       .help("Only validate interactions that have no defined provider state"))
+    .arg(Arg::new("filter-state-param")
+      .long("filter-state-param")
+      .action(ArgAction::Append)
+      .conflicts_with("filter-no-state")
+      .value_parser(state_param_filter)
+      .help("Only validate interactions that have a provider state with a parameter matching this filter (NAME=VALUE, where VALUE is a regex that must match the whole value). Can be repeated, and all filters must match the same provider state"))
     .arg(Arg::new("filter-consumer")
       .short('c')
       .long("filter-consumer")
@@ -299,36 +447,218 @@ pub(crate) fn setup_app() -> Command {
       .action(ArgAction::Set)
       .value_parser(NonEmptyStringValueParser::new())
       .help("Provider branch to use when publishing results"))
+    .arg(Arg::new("bundle-results")
+      .long("bundle-results")
+      .action(ArgAction::Set)
+      .requires("bundle")
+      .requires("provider-version")
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("File to write the verification results of the pacts from a bundle to, to be published later with the publish command. Requires the bundle and provider-version parameters."))
+
+    .group(ArgGroup::new("metrics-sink").args(["metrics-statsd", "metrics-otlp"]))
+    .next_help_heading("Metrics options")
+    .arg(Arg::new("metrics-statsd")
+      .long("metrics-statsd")
+      .env("PACT_VERIFIER_METRICS_STATSD")
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("Publish counts and durations from the verification to a StatsD server (HOST:PORT). The metrics do not include any details from the pacts."))
+    .arg(Arg::new("metrics-otlp")
+      .long("metrics-otlp")
+      .env("PACT_VERIFIER_METRICS_OTLP")
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("Publish counts and durations from the verification to an OpenTelemetry collector metrics URL (i.e. http://localhost:4318/v1/metrics). The metrics do not include any details from the pacts."))
+    .arg(Arg::new("metrics-prefix")
+      .long("metrics-prefix")
+      .action(ArgAction::Set)
+      .requires("metrics-sink")
+      .value_parser(NonEmptyStringValueParser::new())
+      .default_value("pact")
+      .help("Prefix to add to the names of the published metrics"))
+    .arg(Arg::new("metrics-tag")
+      .long("metrics-tag")
+      .action(ArgAction::Append)
+      .requires("metrics-sink")
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("Tag to add to the published metrics, in the form KEY=VALUE. Can be repeated."))
 
     .group(ArgGroup::new("broker").multiple(true))
     .next_help_heading("Pact Broker options")
-    .arg(Arg::new("consumer-version-tags")
+    .args(broker_selection_args())
+
+    .subcommand_negates_reqs(true)
+    .disable_help_subcommand(true)
+    .subcommand(fetch_command())
+    .subcommand(publish_command())
+    .subcommand(stats_command())
+    .subcommand(schema_command())
+}
+
+fn auth_args() -> [Arg; 3] {
+  [
+    Arg::new("user")
+      .long("user")
+      .env("PACT_BROKER_USERNAME")
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .conflicts_with("token")
+      .help("Username to use when fetching pacts from URLS"),
+    Arg::new("password")
+      .long("password")
+      .env("PACT_BROKER_PASSWORD")
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .conflicts_with("token")
+      .help("Password to use when fetching pacts from URLS"),
+    Arg::new("token")
+      .short('t')
+      .long("token")
+      .env("PACT_BROKER_TOKEN")
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .conflicts_with("user")
+      .help("Bearer token to use when fetching pacts from URLS")
+  ]
+}
+
+fn broker_selection_args() -> [Arg; 4] {
+  [
+    Arg::new("consumer-version-tags")
       .long("consumer-version-tags")
       .action(ArgAction::Set)
       .use_value_delimiter(true)
       .value_parser(NonEmptyStringValueParser::new())
       .requires("broker-url")
       .conflicts_with("consumer-version-selectors")
-      .help("Consumer tags to use when fetching pacts from the Broker. Accepts comma-separated values."))
-    .arg(Arg::new("consumer-version-selectors")
+      .help("Consumer tags to use when fetching pacts from the Broker. Accepts comma-separated values."),
+    Arg::new("consumer-version-selectors")
       .long("consumer-version-selectors")
       .action(ArgAction::Set)
       .action(ArgAction::Append)
       .value_parser(NonEmptyStringValueParser::new())
       .requires("broker-url")
       .conflicts_with("consumer-version-tags")
-      .help("Consumer version selectors to use when fetching pacts from the Broker. Accepts a JSON string as per https://docs.pact.io/pact_broker/advanced_topics/consumer_version_selectors/. Can be repeated."))
-    .arg(Arg::new("enable-pending")
+      .help("Consumer version selectors to use when fetching pacts from the Broker. Accepts a JSON string as per https://docs.pact.io/pact_broker/advanced_topics/consumer_version_selectors/. Can be repeated."),
+    Arg::new("enable-pending")
       .long("enable-pending")
       .action(ArgAction::SetTrue)
       .requires("broker-url")
-      .help("Enables Pending Pacts"))
-    .arg(Arg::new("include-wip-pacts-since")
+      .help("Enables Pending Pacts"),
+    Arg::new("include-wip-pacts-since")
       .long("include-wip-pacts-since")
       .action(ArgAction::Set)
       .value_parser(NonEmptyStringValueParser::new())
       .requires("broker-url")
-      .help("Allow pacts that don't match given consumer selectors (or tags) to  be verified, without causing the overall task to fail. For more information, see https://pact.io/wip"))
+      .help("Allow pacts that don't match given consumer selectors (or tags) to  be verified, without causing the overall task to fail. For more information, see https://pact.io/wip")
+  ]
+}
+
+fn fetch_command() -> Command {
+  Command::new("fetch")
+    .about("Fetches the pacts to verify from the Pact Broker into a bundle file, which can then be verified offline with the --bundle option")
+    .arg(Arg::new("help")
+      .long("help")
+      .action(ArgAction::Help)
+      .help("Print help and exit"))
+    .arg(Arg::new("output")
+      .short('o')
+      .long("output")
+      .required(true)
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("File to write the pact bundle to"))
+    .arg(Arg::new("broker-url")
+      .short('b')
+      .long("broker-url")
+      .env("PACT_BROKER_BASE_URL")
+      .required(true)
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("URL of the pact broker to fetch pacts from"))
+    .arg(Arg::new("provider-name")
+      .short('n')
+      .long("provider-name")
+      .env("PACT_PROVIDER_NAME")
+      .required(true)
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("Provider name to fetch the pacts for"))
+    .arg(Arg::new("filter-consumer")
+      .short('c')
+      .long("filter-consumer")
+      .action(ArgAction::Append)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("Consumer name to filter the pacts to be fetched (can be repeated)"))
+    .arg(Arg::new("provider-tags")
+      .long("provider-tags")
+      .action(ArgAction::Set)
+      .use_value_delimiter(true)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("Provider tags to use when fetching pacts. Accepts comma-separated values."))
+    .arg(Arg::new("provider-branch")
+      .long("provider-branch")
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("Provider branch to use when fetching pacts"))
+    .args(auth_args())
+    .args(broker_selection_args())
+}
+
+fn publish_command() -> Command {
+  Command::new("publish")
+    .about("Publishes the verification results written by the --bundle-results option to the Pact Broker")
+    .arg(Arg::new("help")
+      .long("help")
+      .action(ArgAction::Help)
+      .help("Print help and exit"))
+    .arg(Arg::new("results")
+      .short('r')
+      .long("results")
+      .required(true)
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("File with the verification results to publish"))
+    .args(auth_args())
+}
+
+fn stats_command() -> Command {
+  Command::new("stats")
+    .about("Displays the serialised size of a pact file and its largest interactions, optionally failing if the pact exceeds a size budget")
+    .arg(Arg::new("help")
+      .long("help")
+      .action(ArgAction::Help)
+      .help("Print help and exit"))
+    .arg(Arg::new("file")
+      .required(true)
+      .action(ArgAction::Set)
+      .value_parser(NonEmptyStringValueParser::new())
+      .help("Pact file to calculate the sizes for"))
+    .arg(Arg::new("top")
+      .long("top")
+      .action(ArgAction::Set)
+      .value_parser(integer_value)
+      .default_value("5")
+      .help("Number of the largest interactions to display"))
+    .arg(Arg::new("max-size")
+      .long("max-size")
+      .action(ArgAction::Set)
+      .value_parser(integer_value)
+      .help("Maximum size of the pact file in bytes. The command will fail if the pact is larger than this."))
+    .arg(Arg::new("max-interaction-size")
+      .long("max-interaction-size")
+      .action(ArgAction::Set)
+      .value_parser(integer_value)
+      .help("Maximum size of each interaction in bytes. The command will fail if any interaction is larger than this."))
+}
+
+fn schema_command() -> Command {
+  Command::new("schema")
+    .about("Prints the JSON schema for V4 pact files")
+    .arg(Arg::new("help")
+      .long("help")
+      .action(ArgAction::Help)
+      .help("Print help and exit"))
 }
 
 #[cfg(test)]
@@ -337,7 +667,7 @@ mod test {
 
   use crate::args::setup_app;
 
-  use super::{integer_value, port_value, transport_value, validate_regex};
+  use super::{integer_value, port_value, state_param_filter, transport_value, validate_regex};
 
   #[test]
   fn validates_port_value() {
@@ -352,6 +682,15 @@ mod test {
     expect!(integer_value("1234x")).to(be_err());
   }
 
+  #[test]
+  fn validates_state_param_filter() {
+    expect!(state_param_filter("tenant=acme.*")).to(be_ok());
+    expect!(state_param_filter("tenant")).to(be_ok());
+    expect!(state_param_filter("=acme")).to(be_err());
+    expect!(state_param_filter("tenant=(acme")).to(be_err());
+    expect!(state_param_filter("")).to(be_err());
+  }
+
   #[test]
   fn validates_transport_value() {
     expect!(transport_value("http:1234")).to(be_ok());
@@ -373,4 +712,166 @@ mod test {
   fn verify_cli() {
     setup_app().debug_assert();
   }
+
+  #[test]
+  fn fetch_command_does_not_require_a_pact_source() {
+    let matches = setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "fetch", "-b", "http://localhost:9292", "-n", "provider", "-o", "bundle.json"
+    ]).unwrap();
+    let (name, fetch_matches) = matches.subcommand().unwrap();
+    expect!(name).to(be_equal_to("fetch"));
+    expect!(fetch_matches.get_one::<String>("output").cloned()).to(be_some().value("bundle.json"));
+  }
+
+  #[test]
+  fn stats_command_does_not_require_a_pact_source() {
+    let matches = setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "stats", "pact.json", "--max-size", "10000"
+    ]).unwrap();
+    let (name, stats_matches) = matches.subcommand().unwrap();
+    expect!(name).to(be_equal_to("stats"));
+    expect!(stats_matches.get_one::<String>("file").cloned()).to(be_some().value("pact.json"));
+    expect!(stats_matches.get_one::<u64>("top").cloned()).to(be_some().value(5));
+    expect!(stats_matches.get_one::<u64>("max-size").cloned()).to(be_some().value(10000));
+  }
+
+  #[test]
+  fn compare_option_does_not_require_a_pact_source() {
+    let matches = setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--compare", "old.json", "new.json"
+    ]).unwrap();
+    let files = matches.get_many::<String>("compare").unwrap().cloned().collect::<Vec<_>>();
+    expect!(files).to(be_equal_to(vec!["old.json".to_string(), "new.json".to_string()]));
+  }
+
+  #[test]
+  fn compare_option_requires_two_pact_files() {
+    let result = setup_app().try_get_matches_from(vec!["pact_verifier_cli", "--compare", "old.json"]);
+    expect!(result).to(be_err());
+  }
+
+  #[test]
+  fn schema_command_does_not_require_a_pact_source() {
+    let matches = setup_app().try_get_matches_from(vec!["pact_verifier_cli", "schema"]).unwrap();
+    expect!(matches.subcommand_name()).to(be_some().value("schema"));
+  }
+
+  #[test]
+  fn metrics_options() {
+    let matches = setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--file", "pact.json", "--metrics-statsd", "localhost:8125",
+      "--metrics-tag", "team=orders", "--metrics-tag", "env=ci"
+    ]).unwrap();
+    expect!(matches.get_one::<String>("metrics-statsd").cloned()).to(be_some().value("localhost:8125"));
+    expect!(matches.get_one::<String>("metrics-prefix").cloned()).to(be_some().value("pact"));
+    expect!(matches.get_many::<String>("metrics-tag").unwrap().cloned().collect::<Vec<_>>()).to(
+      be_equal_to(vec!["team=orders".to_string(), "env=ci".to_string()]));
+
+    expect!(setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--file", "pact.json", "--metrics-statsd", "localhost:8125",
+      "--metrics-otlp", "http://localhost:4318/v1/metrics"
+    ])).to(be_err());
+    expect!(setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--file", "pact.json", "--metrics-tag", "team=orders"
+    ])).to(be_err());
+  }
+
+  #[test]
+  fn recursive_dir_options() {
+    let matches = setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--dir", "pacts", "--recursive", "--glob", "**/*-web.json"
+    ]).unwrap();
+    expect!(matches.get_flag("recursive")).to(be_true());
+    expect!(matches.get_many::<String>("glob").unwrap().cloned().collect::<Vec<_>>()).to(
+      be_equal_to(vec!["**/*-web.json".to_string()]));
+
+    expect!(setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--file", "pact.json", "--recursive"
+    ])).to(be_err());
+    expect!(setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--file", "pact.json", "--glob", "*.json"
+    ])).to(be_err());
+  }
+
+  #[test]
+  fn list_provider_states_conflicts_with_list_only() {
+    let matches = setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--file", "pact.json", "--list-provider-states"
+    ]).unwrap();
+    expect!(matches.get_flag("list-provider-states")).to(be_true());
+
+    expect!(setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--file", "pact.json", "--list-provider-states", "--list-only"
+    ])).to(be_err());
+  }
+
+  #[test]
+  fn bundle_results_requires_the_provider_version() {
+    expect!(setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--bundle", "bundle.json", "--bundle-results", "results.jsonl"
+    ])).to(be_err());
+    expect!(setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--bundle", "bundle.json", "--bundle-results", "results.jsonl",
+      "--provider-version", "1.0.0"
+    ])).to(be_ok());
+  }
+
+  #[test]
+  fn summary_file_option() {
+    let matches = setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--file", "pact.json", "--summary-file", "summary.json"
+    ]).unwrap();
+    expect!(matches.get_one::<String>("summary-file").cloned()).to(be_some().value("summary.json".to_string()));
+  }
+
+  #[test]
+  fn provider_capabilities_url_requires_a_value() {
+    let matches = setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--file", "pact.json", "--provider-capabilities-url", "http://localhost:8080/capabilities"
+    ]).unwrap();
+    expect!(matches.get_one::<String>("provider-capabilities-url").cloned())
+      .to(be_some().value("http://localhost:8080/capabilities".to_string()));
+
+    expect!(setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--file", "pact.json", "--provider-capabilities-url", ""
+    ])).to(be_err());
+  }
+
+  #[test]
+  fn builtin_grpc_transport_flag() {
+    let matches = setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--file", "pact.json", "--transports", "grpc:50051", "--builtin-grpc-transport"
+    ]).unwrap();
+    expect!(matches.get_flag("builtin-grpc-transport")).to(be_true());
+
+    let matches = setup_app().try_get_matches_from(vec!["pact_verifier_cli", "--file", "pact.json"]).unwrap();
+    expect!(matches.get_flag("builtin-grpc-transport")).to(be_false());
+  }
+
+  #[test]
+  fn generator_seed_option() {
+    let matches = setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--file", "pact.json", "--generator-seed", "1234"
+    ]).unwrap();
+    expect!(matches.get_one::<u64>("generator-seed").cloned()).to(be_some().value(1234));
+
+    expect!(setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--file", "pact.json", "--generator-seed", "abc"
+    ])).to(be_err());
+  }
+
+  #[test]
+  fn timeout_options() {
+    let matches = setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--file", "pact.json", "--interaction-timeout", "30", "--interaction-retries", "2",
+      "--verification-timeout", "600"
+    ]).unwrap();
+    expect!(matches.get_one::<u64>("interaction-timeout").cloned()).to(be_some().value(30));
+    expect!(matches.get_one::<u64>("interaction-retries").cloned()).to(be_some().value(2));
+    expect!(matches.get_one::<u64>("verification-timeout").cloned()).to(be_some().value(600));
+
+    expect!(setup_app().try_get_matches_from(vec![
+      "pact_verifier_cli", "--file", "pact.json", "--interaction-timeout", "soon"
+    ])).to(be_err());
+  }
 }
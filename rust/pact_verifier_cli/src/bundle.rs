@@ -0,0 +1,55 @@
+//! Support for the `fetch` and `publish` commands. These allow pacts to be verified where the
+//! Pact Broker can not be reached: the pacts are fetched into a bundle file, verified offline
+//! with the `--bundle` option and the results then published with the `publish` command.
+
+use std::path::Path;
+
+use clap::ArgMatches;
+use tracing::error;
+
+use pact_verifier::bundle::{fetch_pact_bundle, publish_deferred_results};
+use pact_verifier::ProviderInfo;
+
+/// Fetches the pacts from the Pact Broker and writes them to the bundle file
+pub(crate) async fn fetch_bundle(matches: &ArgMatches) -> Result<(), i32> {
+  // It is ok to unwrap values here, as they have all been validated by the CLI
+  let broker_url = matches.get_one::<String>("broker-url").unwrap();
+  let output = matches.get_one::<String>("output").unwrap();
+  let provider = ProviderInfo {
+    name: matches.get_one::<String>("provider-name").cloned().unwrap_or_default(),
+    .. ProviderInfo::default()
+  };
+  let consumers = matches.get_many::<String>("filter-consumer").unwrap_or_default()
+    .map(|v| v.to_string())
+    .collect::<Vec<_>>();
+
+  let source = crate::broker_source(matches, broker_url);
+  let bundle = fetch_pact_bundle(&provider, vec![source], consumers).await
+    .map_err(|err| {
+      error!("Failed to fetch the pacts - {:#}", err);
+      1
+    })?;
+  bundle.write(Path::new(output))
+    .map_err(|err| {
+      error!("{:#}", err);
+      2
+    })?;
+
+  println!("Wrote {} pact(s) for provider '{}' to '{}'", bundle.pacts.len(), bundle.provider, output);
+  Ok(())
+}
+
+/// Publishes the verification results from the results file to the Pact Broker
+pub(crate) async fn publish_results(matches: &ArgMatches) -> Result<(), i32> {
+  let results = matches.get_one::<String>("results").unwrap();
+  match publish_deferred_results(Path::new(results), crate::broker_auth(matches)).await {
+    Ok(count) => {
+      println!("Published {} verification result(s) to the Pact Broker", count);
+      Ok(())
+    }
+    Err(err) => {
+      error!("Failed to publish the verification results - {:#}", err);
+      Err(1)
+    }
+  }
+}
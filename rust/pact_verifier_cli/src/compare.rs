@@ -0,0 +1,37 @@
+//! Support for the `--compare` option. This compares two pact files and displays the interactions
+//! that have been added, removed or changed, so it can be used in CI to gate changes to a contract
+//! without needing a Pact Broker.
+
+use std::panic::RefUnwindSafe;
+use std::path::Path;
+
+use clap::ArgMatches;
+use tracing::error;
+
+use pact_matching::diff_pacts;
+use pact_models::pact::{Pact, read_pact};
+
+/// Compares the two pact files, failing if there are any differences between them
+pub(crate) fn compare_pacts(matches: &ArgMatches) -> Result<(), i32> {
+  // It is ok to unwrap values here, as they have been validated by the CLI
+  let files = matches.get_many::<String>("compare").unwrap().collect::<Vec<_>>();
+  let old = load_pact(files[0])?;
+  let new = load_pact(files[1])?;
+
+  let diff = diff_pacts(old.as_ref(), new.as_ref());
+  print!("{}", diff);
+
+  if diff.is_empty() {
+    Ok(())
+  } else {
+    Err(1)
+  }
+}
+
+fn load_pact(file: &str) -> Result<Box<dyn Pact + Send + Sync + RefUnwindSafe>, i32> {
+  read_pact(Path::new(file))
+    .map_err(|err| {
+      error!("Failed to load pact file '{}' - {}", file, err);
+      2
+    })
+}
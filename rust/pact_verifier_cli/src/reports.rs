@@ -1,13 +1,48 @@
+use std::fmt::{Display, Formatter};
 use std::fs::File;
 use std::io::Write;
+use std::str::FromStr;
 
+use anyhow::anyhow;
 #[cfg(feature = "junit")] use junit_report::{ReportBuilder, TestCaseBuilder, TestSuiteBuilder};
 #[cfg(feature = "junit")] use strip_ansi_escapes;
 use serde_json::Value;
 use tracing::debug;
 
-#[cfg(feature = "junit")] use pact_verifier::{interaction_mismatch_output, MismatchResult};
-use pact_verifier::verification_result::VerificationExecutionResult;
+#[cfg(feature = "junit")] use pact_verifier::interaction_mismatch_output;
+use pact_verifier::{body_mismatch_diff, MismatchResult};
+use pact_verifier::failures::FailureSummary;
+use pact_verifier::verification_result::{VerificationExecutionResult, VerificationInteractionResult};
+
+/// Format of the report generated with the `--report` option
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ReportFormat {
+  /// Markdown (suitable for PR comments)
+  Markdown,
+  /// Self-contained HTML page
+  Html
+}
+
+impl FromStr for ReportFormat {
+  type Err = anyhow::Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.to_lowercase().as_str() {
+      "markdown" | "md" => Ok(ReportFormat::Markdown),
+      "html" => Ok(ReportFormat::Html),
+      _ => Err(anyhow!("'{}' is not a valid report format", s))
+    }
+  }
+}
+
+impl Display for ReportFormat {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ReportFormat::Markdown => write!(f, "Markdown"),
+      ReportFormat::Html => write!(f, "HTML")
+    }
+  }
+}
 
 pub(crate) fn write_json_report(result: &VerificationExecutionResult, file_name: &str) -> anyhow::Result<()> {
   debug!("Writing JSON result of the verification to '{file_name}'");
@@ -17,6 +52,13 @@ pub(crate) fn write_json_report(result: &VerificationExecutionResult, file_name:
   Ok(())
 }
 
+pub(crate) fn write_summary_file(summary: &FailureSummary, file_name: &str) -> anyhow::Result<()> {
+  debug!("Writing the failure summary of the verification to '{file_name}'");
+  let mut f = File::create(file_name)?;
+  f.write_all(summary.to_json().to_string().as_bytes())?;
+  Ok(())
+}
+
 #[cfg(feature = "junit")]
 pub(crate) fn write_junit_report(result: &VerificationExecutionResult, file_name: &str, provider: &String) -> anyhow::Result<()> {
   debug!("Writing JUnit result of the verification to '{file_name}'");
@@ -67,3 +109,379 @@ pub(crate) fn write_junit_report(result: &VerificationExecutionResult, file_name
   report.write_xml(&mut f)?;
   Ok(())
 }
+
+pub(crate) fn write_report(
+  result: &VerificationExecutionResult,
+  file_name: &str,
+  provider: &str,
+  format: ReportFormat,
+  diff_context: usize
+) -> anyhow::Result<()> {
+  debug!("Writing {format} report of the verification to '{file_name}'");
+  let report = match format {
+    ReportFormat::Markdown => markdown_report(result, provider, diff_context),
+    ReportFormat::Html => html_report(result, provider, diff_context)
+  };
+  let mut f = File::create(file_name)?;
+  f.write_all(report.as_bytes())?;
+  Ok(())
+}
+
+/// Details of why an interaction failed verification
+struct FailureDetails {
+  /// Full description of the interaction (including the provider states)
+  description: String,
+  /// Description of each mismatch or error
+  mismatches: Vec<String>,
+  /// Diff of the expected and actual bodies
+  body_diff: Option<String>
+}
+
+/// Groups the interaction results by consumer, in the order the consumers were verified
+fn results_by_consumer(result: &VerificationExecutionResult) -> Vec<(String, Vec<&VerificationInteractionResult>)> {
+  let mut consumers: Vec<(String, Vec<&VerificationInteractionResult>)> = vec![];
+  for interaction_result in &result.interaction_results {
+    match consumers.iter_mut().find(|(consumer, _)| *consumer == interaction_result.consumer) {
+      Some((_, results)) => results.push(interaction_result),
+      None => consumers.push((interaction_result.consumer.clone(), vec![interaction_result]))
+    }
+  }
+  consumers
+}
+
+fn interaction_status(result: &VerificationInteractionResult) -> &'static str {
+  match (&result.result, result.pending) {
+    (Ok(_), _) => "OK",
+    (Err(_), true) => "PENDING",
+    (Err(_), false) => "FAILED"
+  }
+}
+
+fn failure_details(result: &VerificationInteractionResult, diff_context: usize) -> Option<FailureDetails> {
+  match &result.result {
+    Ok(_) => None,
+    Err(MismatchResult::Mismatches { mismatches, expected, actual, .. }) => {
+      let body_diff = mismatches.iter()
+        .find(|mismatch| mismatch.mismatch_type() == "BodyMismatch")
+        .and_then(|mismatch| mismatch.to_json().get("path").and_then(|path| path.as_str()).map(|path| path.to_string()))
+        .and_then(|path| body_mismatch_diff(expected.as_ref(), actual.as_ref(), path.as_str(), "", false, diff_context));
+      Some(FailureDetails {
+        description: result.description.clone(),
+        mismatches: mismatches.iter().map(|mismatch| mismatch.description()).collect(),
+        body_diff
+      })
+    }
+    Err(MismatchResult::Error(error, _)) => Some(FailureDetails {
+      description: result.description.clone(),
+      mismatches: vec![error.clone()],
+      body_diff: None
+    })
+  }
+}
+
+/// Errors that are not associated with any verified interaction (i.e. a Pact that could not be loaded)
+fn other_errors(result: &VerificationExecutionResult) -> Vec<(String, String)> {
+  result.errors.iter()
+    .filter(|(description, _)| !result.interaction_results.iter().any(|r| r.description == *description))
+    .map(|(description, mismatch)| {
+      let json: Value = mismatch.into();
+      let message = json.get("message").and_then(|m| m.as_str()).map(|m| m.to_string())
+        .unwrap_or_else(|| json.to_string());
+      (description.clone(), message)
+    })
+    .collect()
+}
+
+fn counts(results: &[&VerificationInteractionResult]) -> (usize, usize, usize) {
+  results.iter().fold((0, 0, 0), |(passed, failed, pending), result| {
+    match interaction_status(result) {
+      "OK" => (passed + 1, failed, pending),
+      "PENDING" => (passed, failed, pending + 1),
+      _ => (passed, failed + 1, pending)
+    }
+  })
+}
+
+fn escape_markdown_cell(text: &str) -> String {
+  text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Generates a Markdown report of the verification, suitable for attaching to a PR comment
+pub(crate) fn markdown_report(result: &VerificationExecutionResult, provider: &str, diff_context: usize) -> String {
+  let mut report = vec![];
+  let consumers = results_by_consumer(result);
+
+  report.push(format!("# Pact verification report for {}", provider));
+  report.push(String::default());
+  report.push(format!("**Result:** {}", if result.result { "Passed" } else { "Failed" }));
+  report.push(String::default());
+
+  if !consumers.is_empty() {
+    report.push("| Consumer | Interactions | Passed | Failed | Pending |".to_string());
+    report.push("|----------|--------------|--------|--------|---------|".to_string());
+    for (consumer, results) in &consumers {
+      let (passed, failed, pending) = counts(results);
+      report.push(format!("| {} | {} | {} | {} | {} |", escape_markdown_cell(consumer), results.len(),
+        passed, failed, pending));
+    }
+    report.push(String::default());
+  }
+
+  for (consumer, results) in &consumers {
+    report.push(format!("## {}", consumer));
+    report.push(String::default());
+    report.push("| Interaction | Result | Duration |".to_string());
+    report.push("|-------------|--------|----------|".to_string());
+    for result in results {
+      report.push(format!("| {} | {} | {} ms |", escape_markdown_cell(&result.interaction_description),
+        interaction_status(result), result.duration.as_millis()));
+    }
+    report.push(String::default());
+
+    let failures = results.iter()
+      .filter_map(|result| failure_details(result, diff_context))
+      .collect::<Vec<_>>();
+    if !failures.is_empty() {
+      report.push("### Failures".to_string());
+      report.push(String::default());
+      for failure in failures {
+        report.push(format!("#### {}", failure.description));
+        report.push(String::default());
+        for mismatch in &failure.mismatches {
+          report.push(format!("- {}", mismatch.replace('\n', "\n  ")));
+        }
+        if let Some(diff) = &failure.body_diff {
+          report.push(String::default());
+          report.push("```diff".to_string());
+          report.push(diff.clone());
+          report.push("```".to_string());
+        }
+        report.push(String::default());
+      }
+    }
+
+    let warnings = results.iter()
+      .flat_map(|result| result.warnings.iter().map(|warning| (result.interaction_description.clone(), warning.clone())))
+      .collect::<Vec<_>>();
+    if !warnings.is_empty() {
+      report.push("### Warnings".to_string());
+      report.push(String::default());
+      for (interaction, warning) in warnings {
+        report.push(format!("- {}: {}", interaction, warning));
+      }
+      report.push(String::default());
+    }
+  }
+
+  let errors = other_errors(result);
+  if !errors.is_empty() {
+    report.push("## Errors".to_string());
+    report.push(String::default());
+    for (description, error) in errors {
+      report.push(format!("- {}: {}", description, error));
+    }
+    report.push(String::default());
+  }
+
+  report.join("\n")
+}
+
+fn escape_html(text: &str) -> String {
+  text.replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+fn html_diff(diff: &str) -> String {
+  diff.lines()
+    .map(|line| {
+      let class = if line.starts_with("---") || line.starts_with("+++") {
+        "header"
+      } else if line.starts_with("@@") {
+        "hunk"
+      } else if line.starts_with('+') {
+        "added"
+      } else if line.starts_with('-') {
+        "removed"
+      } else {
+        "context"
+      };
+      format!("<span class=\"{}\">{}</span>", class, escape_html(line))
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+const HTML_STYLE: &str = r#"body { font-family: sans-serif; margin: 2em; color: #24292f; }
+table { border-collapse: collapse; margin-bottom: 1em; }
+th, td { border: 1px solid #d0d7de; padding: 4px 12px; text-align: left; }
+th { background: #f6f8fa; }
+.OK { color: #1a7f37; font-weight: bold; }
+.FAILED { color: #cf222e; font-weight: bold; }
+.PENDING { color: #9a6700; font-weight: bold; }
+pre { background: #f6f8fa; padding: 8px; overflow-x: auto; }
+pre .added { color: #1a7f37; }
+pre .removed { color: #cf222e; }
+pre .hunk { color: #0969da; }
+pre .header { font-weight: bold; }"#;
+
+/// Generates a self-contained HTML report of the verification
+pub(crate) fn html_report(result: &VerificationExecutionResult, provider: &str, diff_context: usize) -> String {
+  let mut report = vec![];
+  let consumers = results_by_consumer(result);
+  let overall_status = if result.result { "OK" } else { "FAILED" };
+
+  report.push("<!DOCTYPE html>".to_string());
+  report.push("<html>".to_string());
+  report.push("<head>".to_string());
+  report.push("<meta charset=\"utf-8\">".to_string());
+  report.push(format!("<title>Pact verification report for {}</title>", escape_html(provider)));
+  report.push(format!("<style>\n{}\n</style>", HTML_STYLE));
+  report.push("</head>".to_string());
+  report.push("<body>".to_string());
+  report.push(format!("<h1>Pact verification report for {}</h1>", escape_html(provider)));
+  report.push(format!("<p><strong>Result:</strong> <span class=\"{}\">{}</span></p>", overall_status,
+    if result.result { "Passed" } else { "Failed" }));
+
+  if !consumers.is_empty() {
+    report.push("<table>".to_string());
+    report.push("<tr><th>Consumer</th><th>Interactions</th><th>Passed</th><th>Failed</th><th>Pending</th></tr>".to_string());
+    for (consumer, results) in &consumers {
+      let (passed, failed, pending) = counts(results);
+      report.push(format!("<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>", escape_html(consumer),
+        results.len(), passed, failed, pending));
+    }
+    report.push("</table>".to_string());
+  }
+
+  for (consumer, results) in &consumers {
+    report.push(format!("<h2>{}</h2>", escape_html(consumer)));
+    report.push("<table>".to_string());
+    report.push("<tr><th>Interaction</th><th>Result</th><th>Duration</th></tr>".to_string());
+    for result in results {
+      let status = interaction_status(result);
+      report.push(format!("<tr><td>{}</td><td class=\"{}\">{}</td><td>{} ms</td></tr>",
+        escape_html(&result.interaction_description), status, status, result.duration.as_millis()));
+    }
+    report.push("</table>".to_string());
+
+    let failures = results.iter()
+      .filter_map(|result| failure_details(result, diff_context))
+      .collect::<Vec<_>>();
+    if !failures.is_empty() {
+      report.push("<h3>Failures</h3>".to_string());
+      for failure in failures {
+        report.push(format!("<h4>{}</h4>", escape_html(&failure.description)));
+        report.push("<ul>".to_string());
+        for mismatch in &failure.mismatches {
+          report.push(format!("<li><pre>{}</pre></li>", escape_html(mismatch)));
+        }
+        report.push("</ul>".to_string());
+        if let Some(diff) = &failure.body_diff {
+          report.push(format!("<pre>{}</pre>", html_diff(diff)));
+        }
+      }
+    }
+
+    let warnings = results.iter()
+      .flat_map(|result| result.warnings.iter().map(|warning| (result.interaction_description.clone(), warning.clone())))
+      .collect::<Vec<_>>();
+    if !warnings.is_empty() {
+      report.push("<h3>Warnings</h3>".to_string());
+      report.push("<ul>".to_string());
+      for (interaction, warning) in warnings {
+        report.push(format!("<li>{}: {}</li>", escape_html(&interaction), escape_html(&warning)));
+      }
+      report.push("</ul>".to_string());
+    }
+  }
+
+  let errors = other_errors(result);
+  if !errors.is_empty() {
+    report.push("<h2>Errors</h2>".to_string());
+    report.push("<ul>".to_string());
+    for (description, error) in errors {
+      report.push(format!("<li>{}: <pre>{}</pre></li>", escape_html(&description), escape_html(&error)));
+    }
+    report.push("</ul>".to_string());
+  }
+
+  report.push("</body>".to_string());
+  report.push("</html>".to_string());
+  report.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use expectest::prelude::*;
+  use pact_verifier::MismatchResult;
+  use pact_verifier::verification_result::{VerificationExecutionResult, VerificationInteractionResult};
+
+  use super::*;
+
+  fn interaction_result(consumer: &str, description: &str, result: Result<(), MismatchResult>) -> VerificationInteractionResult {
+    VerificationInteractionResult {
+      interaction_id: None,
+      interaction_key: None,
+      description: format!("Verifying a pact between {} and provider - {}", consumer, description),
+      interaction_description: description.to_string(),
+      consumer: consumer.to_string(),
+      result,
+      pending: false,
+      duration: Duration::from_millis(12),
+      warnings: vec![]
+    }
+  }
+
+  fn verification_result() -> VerificationExecutionResult {
+    VerificationExecutionResult {
+      result: false,
+      interaction_results: vec![
+        interaction_result("consumer-a", "a request for <things>", Ok(())),
+        interaction_result("consumer-b", "a request | with a pipe", Ok(())),
+        interaction_result("consumer-a", "a failing request",
+          Err(MismatchResult::Error("Provider state failed".to_string(), None)))
+      ],
+      .. VerificationExecutionResult::new()
+    }
+  }
+
+  #[test]
+  fn report_format_from_str() {
+    expect!("markdown".parse::<ReportFormat>().unwrap()).to(be_equal_to(ReportFormat::Markdown));
+    expect!("HTML".parse::<ReportFormat>().unwrap()).to(be_equal_to(ReportFormat::Html));
+    expect!("pdf".parse::<ReportFormat>()).to(be_err());
+  }
+
+  #[test]
+  fn markdown_report_groups_interactions_by_consumer() {
+    let report = markdown_report(&verification_result(), "provider", 3);
+
+    expect!(report.starts_with("# Pact verification report for provider\n\n**Result:** Failed")).to(be_true());
+    expect!(report.contains("| consumer-a | 2 | 1 | 1 | 0 |")).to(be_true());
+    expect!(report.contains("| consumer-b | 1 | 1 | 0 | 0 |")).to(be_true());
+    expect!(report.find("## consumer-a").unwrap() < report.find("## consumer-b").unwrap()).to(be_true());
+    expect!(report.contains("| a failing request | FAILED | 12 ms |")).to(be_true());
+    expect!(report.contains("| a request \\| with a pipe | OK | 12 ms |")).to(be_true());
+    expect!(report.contains("#### Verifying a pact between consumer-a and provider - a failing request\n\n- Provider state failed")).to(be_true());
+  }
+
+  #[test]
+  fn html_report_escapes_the_interaction_descriptions() {
+    let report = html_report(&verification_result(), "provider", 3);
+
+    expect!(report.starts_with("<!DOCTYPE html>")).to(be_true());
+    expect!(report.contains("<td>a request for &lt;things&gt;</td><td class=\"OK\">OK</td>")).to(be_true());
+    expect!(report.contains("<td>a failing request</td><td class=\"FAILED\">FAILED</td>")).to(be_true());
+    expect!(report.contains("<li><pre>Provider state failed</pre></li>")).to(be_true());
+  }
+
+  #[test]
+  fn html_diff_marks_the_changed_lines() {
+    expect!(html_diff("@@ -1 +1 @@\n-a\n+<b>\n c")).to(be_equal_to(
+      "<span class=\"hunk\">@@ -1 +1 @@</span>\n<span class=\"removed\">-a</span>\n<span class=\"added\">+&lt;b&gt;</span>\n<span class=\"context\"> c</span>"));
+  }
+}
@@ -0,0 +1,64 @@
+//! Support for the `stats` command. This displays the serialised size of a pact file and its
+//! largest interactions, and can be used in CI to enforce a size budget for pact files.
+
+use std::fs;
+
+use clap::ArgMatches;
+use serde_json::Value;
+use tracing::error;
+
+use pact_models::pact_size::PactSize;
+
+/// Calculates and displays the sizes of the pact file, failing if any of the size budgets are exceeded
+pub(crate) fn pact_stats(matches: &ArgMatches) -> Result<(), i32> {
+  // It is ok to unwrap values here, as they have all been validated by the CLI
+  let file = matches.get_one::<String>("file").unwrap();
+  let top = matches.get_one::<u64>("top").map(|v| *v as usize).unwrap_or(5);
+  let json = fs::read_to_string(file)
+    .map_err(|err| err.to_string())
+    .and_then(|contents| serde_json::from_str::<Value>(&contents).map_err(|err| err.to_string()))
+    .map_err(|err| {
+      error!("Failed to load pact file '{}' - {}", file, err);
+      2
+    })?;
+
+  let size = PactSize::from_json(&json);
+  let body = size.body();
+  println!("Pact between {} and {}: {} bytes", size.consumer, size.provider, size.size);
+  println!("  Interactions:       {}", size.interactions.len());
+  println!("  Metadata:           {} bytes", size.metadata_size);
+  println!("  Bodies:             {} bytes ({} bytes encoding overhead)", body.serialised_size,
+    body.encoding_overhead());
+
+  let largest = size.largest_interactions(top);
+  if !largest.is_empty() {
+    println!();
+    println!("Largest interactions:");
+    for (index, interaction) in largest.iter().enumerate() {
+      println!("  {}. {} ({}): {} bytes, bodies {} bytes ({} bytes encoding overhead)", index + 1,
+        interaction.description, interaction.interaction_type, interaction.size,
+        interaction.body.serialised_size, interaction.body.encoding_overhead());
+    }
+  }
+
+  let mut budget_exceeded = false;
+  if let Some(max_size) = matches.get_one::<u64>("max-size") {
+    if size.size as u64 > *max_size {
+      error!("Pact file '{}' is {} bytes, which is larger than the maximum of {} bytes", file, size.size, max_size);
+      budget_exceeded = true;
+    }
+  }
+  if let Some(max_size) = matches.get_one::<u64>("max-interaction-size") {
+    for interaction in size.interactions.iter().filter(|interaction| interaction.size as u64 > *max_size) {
+      error!("Interaction '{}' is {} bytes, which is larger than the maximum of {} bytes",
+        interaction.description, interaction.size, max_size);
+      budget_exceeded = true;
+    }
+  }
+
+  if budget_exceeded {
+    Err(1)
+  } else {
+    Ok(())
+  }
+}
@@ -0,0 +1,192 @@
+//! Support for the `--version-check` option. This reports the versions of the verifier, the
+//! capabilities of the Pact Broker and any installed plugins, and flags known incompatible
+//! combinations.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::ArgMatches;
+use serde_json::Value;
+use tracing::debug;
+
+use pact_verifier::pact_broker::fetch_broker_index;
+use pact_verifier::VERIFIER_VERSION;
+
+/// Version of the plugin interface that is supported by the plugin driver
+const SUPPORTED_PLUGIN_INTERFACE_VERSION: u64 = 1;
+
+/// Broker relation required to fetch pacts using selectors, pending and WIP pacts
+const PACTS_FOR_VERIFICATION: &str = "pb:provider-pacts-for-verification";
+
+/// Broker relation required to fetch the latest pacts for a provider
+const LATEST_PROVIDER_PACTS: &str = "pb:latest-provider-pacts";
+
+/// Details of a plugin installed in the plugin directory
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct InstalledPlugin {
+  /// Plugin name
+  pub name: String,
+  /// Plugin version
+  pub version: String,
+  /// Version of the plugin interface the plugin implements
+  pub interface_version: u64
+}
+
+/// Runs the version check, printing the results to standard out. Returns an error exit code if
+/// any incompatibilities were found.
+pub(crate) async fn version_check(matches: &ArgMatches, version: &str) -> Result<(), i32> {
+  let mut problems = vec![];
+
+  crate::print_version(version);
+  println!("verifier library version: v{}", VERIFIER_VERSION);
+
+  if let Some(broker_url) = matches.get_one::<String>("broker-url") {
+    println!();
+    println!("Pact Broker ({}):", broker_url);
+    match fetch_broker_index(broker_url, crate::broker_auth(matches)).await {
+      Ok(links) => {
+        let relations = links.iter().map(|link| link.name.clone()).collect::<Vec<_>>();
+        for relation in [LATEST_PROVIDER_PACTS, PACTS_FOR_VERIFICATION] {
+          let supported = relations.iter().any(|r| r == relation);
+          println!("  {:<36}: {}", relation, if supported { "supported" } else { "not supported" });
+        }
+        problems.extend(check_broker_relations(&relations, uses_dynamic_configuration(matches)));
+      }
+      Err(err) => problems.push(format!("Failed to access the Pact Broker - {}", err))
+    }
+  }
+
+  println!();
+  match plugin_dir() {
+    Some(dir) => {
+      println!("Plugins ({}):", dir.display());
+      match load_installed_plugins(&dir) {
+        Ok(plugins) => {
+          if plugins.is_empty() {
+            println!("  No plugins are installed");
+          }
+          for plugin in &plugins {
+            println!("  {} v{} (plugin interface v{})", plugin.name, plugin.version, plugin.interface_version);
+          }
+          problems.extend(check_plugins(&plugins, cfg!(feature = "plugins")));
+        }
+        Err(err) => println!("  Could not read the plugin directory - {}", err)
+      }
+    }
+    None => println!("Plugins: could not determine the plugin directory")
+  }
+
+  println!();
+  if problems.is_empty() {
+    println!("No known incompatibilities were found");
+    Ok(())
+  } else {
+    println!("The following incompatibilities were found:");
+    for problem in &problems {
+      println!("  - {}", problem);
+    }
+    Err(1)
+  }
+}
+
+/// If the options require the "pacts for verification" endpoint of the Pact Broker
+fn uses_dynamic_configuration(matches: &ArgMatches) -> bool {
+  matches.contains_id("consumer-version-selectors") ||
+    matches.contains_id("consumer-version-tags") ||
+    matches.get_flag("enable-pending") ||
+    matches.contains_id("include-wip-pacts-since")
+}
+
+/// Checks the relations supported by the Pact Broker against the ones required by the verifier,
+/// returning a description of any problems.
+pub(crate) fn check_broker_relations(relations: &[String], dynamic_configuration: bool) -> Vec<String> {
+  let required = if dynamic_configuration { PACTS_FOR_VERIFICATION } else { LATEST_PROVIDER_PACTS };
+  if relations.iter().any(|relation| relation == required) {
+    vec![]
+  } else {
+    vec![format!("The Pact Broker does not support the '{}' relation required by the given options", required)]
+  }
+}
+
+/// Checks the installed plugins for known incompatibilities, returning a description of any problems.
+pub(crate) fn check_plugins(plugins: &[InstalledPlugin], plugins_enabled: bool) -> Vec<String> {
+  let mut problems = vec![];
+
+  if !plugins_enabled && !plugins.is_empty() {
+    problems.push("Plugins are installed, but the verifier was built without the plugins feature".to_string());
+  }
+
+  for plugin in plugins {
+    if plugin.interface_version != SUPPORTED_PLUGIN_INTERFACE_VERSION {
+      problems.push(format!("Plugin {} v{} implements plugin interface v{}, but only v{} is supported",
+        plugin.name, plugin.version, plugin.interface_version, SUPPORTED_PLUGIN_INTERFACE_VERSION));
+    }
+  }
+
+  problems
+}
+
+/// Directory that plugins are loaded from. This is either the value of the `PACT_PLUGIN_DIR`
+/// environment variable, or `~/.pact/plugins`.
+fn plugin_dir() -> Option<PathBuf> {
+  env::var("PACT_PLUGIN_DIR").ok()
+    .filter(|dir| !dir.is_empty())
+    .map(PathBuf::from)
+    .or_else(|| {
+      env::var("HOME").or_else(|_| env::var("USERPROFILE")).ok()
+        .map(|home| PathBuf::from(home).join(".pact").join("plugins"))
+    })
+}
+
+/// Loads the manifests of all the plugins installed in the given directory
+fn load_installed_plugins(dir: &Path) -> anyhow::Result<Vec<InstalledPlugin>> {
+  let mut plugins = vec![];
+
+  if dir.exists() {
+    for entry in fs::read_dir(dir)? {
+      let manifest_file = entry?.path().join("pact-plugin.json");
+      if manifest_file.is_file() {
+        debug!("Loading plugin manifest {}", manifest_file.display());
+        let json: Value = serde_json::from_str(&fs::read_to_string(&manifest_file)?)?;
+        plugins.push(InstalledPlugin {
+          name: json["name"].as_str().unwrap_or_default().to_string(),
+          version: json["version"].as_str().unwrap_or_default().to_string(),
+          interface_version: json["pluginInterfaceVersion"].as_u64().unwrap_or_default()
+        });
+      }
+    }
+  }
+
+  plugins.sort_by(|a, b| Ord::cmp(&(&a.name, &a.version), &(&b.name, &b.version)));
+  Ok(plugins)
+}
+
+#[cfg(test)]
+mod tests {
+  use expectest::prelude::*;
+
+  use super::{check_broker_relations, check_plugins, InstalledPlugin};
+
+  #[test]
+  fn check_broker_relations_requires_pacts_for_verification_with_selectors() {
+    let relations = vec!["pb:latest-provider-pacts".to_string()];
+    expect!(check_broker_relations(&relations, false).iter()).to(be_empty());
+    expect!(check_broker_relations(&relations, true)).to(be_equal_to(vec![
+      "The Pact Broker does not support the 'pb:provider-pacts-for-verification' relation required by the given options".to_string()
+    ]));
+  }
+
+  #[test]
+  fn check_plugins_flags_unsupported_interface_versions() {
+    let plugins = vec![
+      InstalledPlugin { name: "protobuf".to_string(), version: "0.3.0".to_string(), interface_version: 1 },
+      InstalledPlugin { name: "csv".to_string(), version: "1.0.0".to_string(), interface_version: 2 }
+    ];
+    expect!(check_plugins(&plugins, true)).to(be_equal_to(vec![
+      "Plugin csv v1.0.0 implements plugin interface v2, but only v1 is supported".to_string()
+    ]));
+    expect!(check_plugins(&plugins, false).len()).to(be_equal_to(2));
+    expect!(check_plugins(&[], false).iter()).to(be_empty());
+  }
+}